@@ -2,9 +2,16 @@
 //!
 //! ANN/CNN weather-aware routing engine for FSO (Free Space Optical) links.
 //! Uses 5-year weather backtest data and HFT-style optimization.
+//!
+//! Station weather comes in as ground-station `FsoWeatherScore`s: a
+//! station whose score is not viable blocks every link touching it, and a
+//! viable station costs its links `weather_weight` of quality per unit of
+//! lost composite quality.
 
 use chrono::{DateTime, Utc};
+use ground_station_wasm::FsoWeatherScore;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -58,17 +65,6 @@ pub enum NodeType {
     Satellite,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WeatherData {
-    pub station_id: String,
-    pub cloud_cover: f64,
-    pub visibility_km: f64,
-    pub precipitation_mm: f64,
-    pub temperature_c: f64,
-    pub humidity_pct: f64,
-    pub timestamp: DateTime<Utc>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LinkQuality {
     pub link_id: String,
@@ -108,55 +104,166 @@ impl RoutingEngine {
         &self,
         request: &RouteRequest,
         link_qualities: &[LinkQuality],
-        weather_data: &[WeatherData],
+        weather: &[FsoWeatherScore],
     ) -> Result<Route> {
         // Placeholder for ANN/CNN routing algorithm
         // Real implementation would use trained neural network
 
-        let weather_adjustment = self.compute_weather_impact(weather_data);
+        let weather = station_weather(weather);
+        for station in [&request.source, &request.destination] {
+            if let Some(score) = weather.get(station.as_str()).filter(|s| !s.link_viable) {
+                return Err(RoutingError::WeatherBlocked(format!(
+                    "{}: {}",
+                    station,
+                    score.degradation_reason.as_deref().unwrap_or("link not viable")
+                )));
+            }
+        }
+        let weather_adjustment =
+            self.compute_weather_impact(&[request.source.as_str(), request.destination.as_str()], &weather);
+        let ground_quality = |id: &str, quality: f64| quality * self.weather_factor(weather.get(id).copied());
+
+        let path = vec![
+            RouteHop {
+                node_id: request.source.clone(),
+                node_type: NodeType::GroundStation,
+                link_quality: ground_quality(&request.source, 0.95),
+                hop_latency_ms: 5.0,
+            },
+            RouteHop {
+                node_id: "SAT-01".to_string(),
+                node_type: NodeType::Satellite,
+                link_quality: 0.92,
+                hop_latency_ms: 35.0,
+            },
+            RouteHop {
+                node_id: "SAT-02".to_string(),
+                node_type: NodeType::Satellite,
+                link_quality: 0.94,
+                hop_latency_ms: 10.0,
+            },
+            RouteHop {
+                node_id: request.destination.clone(),
+                node_type: NodeType::GroundStation,
+                link_quality: ground_quality(&request.destination, 0.91),
+                hop_latency_ms: 35.0,
+            },
+        ];
 
         Ok(Route {
-            path: vec![
-                RouteHop {
-                    node_id: request.source.clone(),
-                    node_type: NodeType::GroundStation,
-                    link_quality: 0.95,
-                    hop_latency_ms: 5.0,
-                },
-                RouteHop {
-                    node_id: "SAT-01".to_string(),
-                    node_type: NodeType::Satellite,
-                    link_quality: 0.92,
-                    hop_latency_ms: 35.0,
-                },
-                RouteHop {
-                    node_id: "SAT-02".to_string(),
-                    node_type: NodeType::Satellite,
-                    link_quality: 0.94,
-                    hop_latency_ms: 10.0,
-                },
-                RouteHop {
-                    node_id: request.destination.clone(),
-                    node_type: NodeType::GroundStation,
-                    link_quality: 0.91,
-                    hop_latency_ms: 35.0,
-                },
-            ],
-            total_latency_ms: 85.0,
-            quality_score: 0.93,
+            total_latency_ms: path.iter().map(|h| h.hop_latency_ms).sum(),
+            quality_score: path.iter().map(|h| h.link_quality).sum::<f64>() / path.len() as f64,
+            path,
             weather_impact: weather_adjustment,
             computed_at: Utc::now(),
         })
     }
 
-    fn compute_weather_impact(&self, weather_data: &[WeatherData]) -> f64 {
-        if weather_data.is_empty() {
-            return 0.0;
+    /// Re-score links for station weather. Links touching a station whose
+    /// score is not viable are blocked (quality 0); the rest keep
+    /// `weather_factor` of their quality for each end.
+    pub fn apply_weather(&self, link_qualities: &[LinkQuality], weather: &[FsoWeatherScore]) -> Vec<LinkQuality> {
+        let weather = station_weather(weather);
+        link_qualities
+            .iter()
+            .map(|link| {
+                let ends = [link.source.as_str(), link.destination.as_str()].map(|id| weather.get(id).copied());
+                if ends.iter().flatten().all(|s| s.quality == 1.0 && s.link_viable) {
+                    return link.clone();
+                }
+                let quality_score = if ends.iter().flatten().any(|s| !s.link_viable) {
+                    0.0
+                } else {
+                    ends.iter().fold(link.quality_score, |q, s| q * self.weather_factor(*s))
+                };
+                LinkQuality {
+                    quality_score,
+                    weather_adjusted: true,
+                    last_updated: Utc::now(),
+                    ..link.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// Share of link quality kept at a station with `score` (1 without one)
+    fn weather_factor(&self, score: Option<&FsoWeatherScore>) -> f64 {
+        score.map_or(1.0, |s| 1.0 - self.weather_weight * (1.0 - s.quality.clamp(0.0, 1.0)))
+    }
+
+    /// Weather impact (0-1) on a route through `stations`: its most
+    /// degraded station's lost quality, weighted
+    fn compute_weather_impact(&self, stations: &[&str], weather: &HashMap<&str, &FsoWeatherScore>) -> f64 {
+        stations
+            .iter()
+            .filter_map(|id| weather.get(id))
+            .map(|s| 1.0 - self.weather_factor(Some(s)))
+            .fold(0.0, f64::max)
+    }
+}
+
+fn station_weather(scores: &[FsoWeatherScore]) -> HashMap<&str, &FsoWeatherScore> {
+    scores.iter().map(|s| (s.station_id.as_str(), s)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(station_id: &str, quality: f64, link_viable: bool) -> FsoWeatherScore {
+        FsoWeatherScore {
+            station_id: station_id.to_string(),
+            quality,
+            cloud_score: quality,
+            visibility_score: 1.0,
+            precip_score: 1.0,
+            turbulence_score: 1.0,
+            sunshine_score: 1.0,
+            clear_night_score: 1.0,
+            air_quality_score: 1.0,
+            link_viable,
+            degradation_reason: (!link_viable).then(|| "Heavy fog".to_string()),
+        }
+    }
+
+    fn link(source: &str, destination: &str) -> LinkQuality {
+        LinkQuality {
+            link_id: format!("{}-{}", source, destination),
+            source: source.to_string(),
+            destination: destination.to_string(),
+            quality_score: 0.9,
+            weather_adjusted: false,
+            last_updated: Utc::now(),
+        }
+    }
+
+    fn request(source: &str, destination: &str) -> RouteRequest {
+        RouteRequest {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            priority: RoutePriority::Latency,
+            min_quality: 0.7,
+            max_latency_ms: 200.0,
         }
+    }
+
+    #[test]
+    fn test_non_viable_station_blocks_links() {
+        let engine = RoutingEngine::default();
+        let weather = [score("GS-1", 0.5, true), score("GS-2", 0.1, false)];
 
-        let avg_cloud = weather_data.iter().map(|w| w.cloud_cover).sum::<f64>()
-            / weather_data.len() as f64;
+        let links = engine.apply_weather(&[link("SAT-1", "GS-1"), link("SAT-1", "GS-2"), link("SAT-1", "GS-3")], &weather);
+        assert!((links[0].quality_score - 0.9 * (1.0 - 0.3 * 0.5)).abs() < 1e-12);
+        assert!(links[0].weather_adjusted);
+        assert_eq!(links[1].quality_score, 0.0);
+        assert!(!links[2].weather_adjusted);
 
-        avg_cloud * self.weather_weight
+        // Only the route's own stations count, not a network-wide average
+        let route = engine.calculate_route(&request("GS-1", "GS-3"), &[], &weather).unwrap();
+        assert!((route.weather_impact - 0.15).abs() < 1e-12);
+        assert!(matches!(
+            engine.calculate_route(&request("GS-1", "GS-2"), &[], &weather),
+            Err(RoutingError::WeatherBlocked(reason)) if reason.contains("Heavy fog")
+        ));
     }
 }
@@ -39,6 +39,7 @@ pub mod loader;
 pub mod scorer;
 pub mod security;
 pub mod selector;
+pub mod serve;
 
 pub use scorer::ScorerConfig;
 pub use security::{CountryRisk, SecurityConfig};
@@ -70,6 +71,10 @@ pub enum SelectorError {
     NoCandidates,
     #[error("Insufficient candidates for zone {0:?}: need {1}, have {2}")]
     InsufficientCandidates(Zone, usize, usize),
+    #[error("Invalid rollout phases: {0}")]
+    InvalidPhases(String),
+    #[error("Invalid scoring weights: {0}")]
+    InvalidWeights(String),
 }
 
 pub type Result<T> = std::result::Result<T, SelectorError>;
@@ -367,6 +372,37 @@ pub struct SelectionMetadata {
     pub generated_at: String,
 }
 
+/// Nested build-out plan: each phase's stations include every earlier
+/// phase's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhasedPlan {
+    pub phases: Vec<RolloutPhase>,
+    /// Metadata of the final phase's full selection
+    pub metadata: SelectionMetadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutPhase {
+    /// 1-based
+    pub phase: usize,
+    /// Stations built by the end of this phase
+    pub total: usize,
+    /// Cumulative stations per zone by the end of this phase
+    pub zone_distribution: HashMap<String, usize>,
+    /// Stations first built in this phase
+    pub added: Vec<ScoredCandidate>,
+}
+
+impl PhasedPlan {
+    /// Every station built by the end of `phase` (1-based)
+    pub fn stations(&self, phase: usize) -> impl Iterator<Item = &ScoredCandidate> {
+        self.phases
+            .iter()
+            .take_while(move |p| p.phase <= phase)
+            .flat_map(|p| p.added.iter())
+    }
+}
+
 /// Haversine distance between two points in km (9 decimal precision)
 pub fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     const R: f64 = 6371.000000000; // Earth radius in km
@@ -6,14 +6,21 @@
 //!   select-stations --ground-nodes data/all_ground_nodes_backup.json \
 //!                   --cable-landings data/cable-infrastructure/cable_landing_complete.json \
 //!                   --output data/selected_247_stations.json
+//!
+//! With `--phases 50,100,247` the output is a nested build-out plan
+//! instead: each phase's stations include every earlier phase's.
+//!
+//! `select-stations serve` loads and scores the candidates once, then
+//! serves what-if rescoring and reselection over HTTP (see `serve`).
 
 use anyhow::Result;
 use candidate_selector::{
-    loader, scorer, selector, ScorerConfig, DEDUP_THRESHOLD_KM, MIN_SPACING_KM,
+    loader, scorer, selector, serve, ScoredCandidate, ScorerConfig, DEDUP_THRESHOLD_KM, MIN_SPACING_KM,
 };
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::fs::File;
 use std::io::BufWriter;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
@@ -52,9 +59,26 @@ struct Args {
     #[arg(long, default_value_t = MIN_SPACING_KM)]
     spacing_km: f64,
 
+    /// Cumulative station totals per rollout phase (e.g. 50,100,247)
+    #[arg(long, value_delimiter = ',')]
+    phases: Vec<usize>,
+
     /// Verbose output
     #[arg(short, long)]
     verbose: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Keep the scored candidates in memory and serve what-if rescoring
+    Serve {
+        /// Listen address
+        #[arg(long, default_value = "127.0.0.1:8095")]
+        listen: SocketAddr,
+    },
 }
 
 fn main() -> Result<()> {
@@ -81,6 +105,11 @@ fn main() -> Result<()> {
 
     info!("Scored {} candidates", scored.len());
 
+    if let Some(Command::Serve { listen }) = args.command {
+        let session = serve::Session::new(scored, config, args.spacing_km);
+        return Ok(tokio::runtime::Runtime::new()?.block_on(serve::serve(session, listen))?);
+    }
+
     // Show top 10 by score
     let mut sorted = scored.clone();
     sorted.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
@@ -94,6 +123,10 @@ fn main() -> Result<()> {
         );
     }
 
+    if !args.phases.is_empty() {
+        return write_phased(&args, scored);
+    }
+
     // Select by zone
     let result = selector::select_by_zone(scored, args.spacing_km)?;
 
@@ -124,3 +157,33 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+fn write_phased(args: &Args, scored: Vec<ScoredCandidate>) -> Result<()> {
+    let plan = selector::select_phased(scored, &args.phases, args.spacing_km)?;
+
+    info!("\nWriting phased plan to {:?}", args.output);
+    let file = File::create(&args.output)?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &plan)?;
+
+    if args.geojson {
+        let geojson_path = args.output.with_extension("geojson");
+        info!("Writing GeoJSON to {:?}", geojson_path);
+        let file = File::create(&geojson_path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &selector::phased_to_geojson(&plan))?;
+    }
+
+    info!("\n{}", "=".repeat(60));
+    info!("ROLLOUT PHASES");
+    info!("{}", "=".repeat(60));
+    for phase in &plan.phases {
+        info!(
+            "Phase {}: {} stations (+{}) {:?}",
+            phase.phase,
+            phase.total,
+            phase.added.len(),
+            phase.zone_distribution
+        );
+    }
+
+    Ok(())
+}
@@ -63,6 +63,33 @@ impl Default for ScorerConfig {
     }
 }
 
+impl ScorerConfig {
+    /// Composite score of already-computed factors under these weights
+    pub fn composite(&self, s: &ScoredCandidate) -> f64 {
+        self.w_population * s.pop_score
+            + self.w_pop_proximity * s.pop_proximity_score
+            + self.w_xai * s.xai_score
+            + self.w_weather * s.weather_score
+            + self.w_network * s.network_score
+            + self.w_security * s.security_score
+            + self.w_infrastructure * s.infrastructure_score
+    }
+}
+
+/// Recompute composite scores under new weights, keeping the factors
+///
+/// Factor scores don't depend on the weights, so a what-if needs no
+/// reloading, geocoding or risk lookups.
+pub fn rescore(scored: &[ScoredCandidate], config: &ScorerConfig) -> Vec<ScoredCandidate> {
+    scored
+        .iter()
+        .map(|s| ScoredCandidate {
+            score: config.composite(s),
+            ..s.clone()
+        })
+        .collect()
+}
+
 /// Score all candidates
 pub fn score_candidates(candidates: Vec<Candidate>, config: &ScorerConfig) -> Vec<ScoredCandidate> {
     // Find max cable count for normalization
@@ -171,23 +198,9 @@ fn score_candidate(mut candidate: Candidate, config: &ScorerConfig, max_cables:
     // Composite infrastructure score
     let infrastructure_score = (base_infrastructure + tier_bonus + proximity_bonus).min(1.000000000);
 
-    // Calculate composite score (7-factor model)
-    let score = config.w_population * pop_score
-        + config.w_pop_proximity * pop_proximity_score
-        + config.w_xai * xai_score
-        + config.w_weather * weather_score
-        + config.w_network * network_score
-        + config.w_security * security_score
-        + config.w_infrastructure * infrastructure_score;
-
-    debug!(
-        "Scored {}: {:.3} (pop={:.2}, pop_prox={:.2}, xai={:.2}, wx={:.2}, net={:.2}, sec={:.2}, infra={:.2})",
-        candidate.name, score, pop_score, pop_proximity_score, xai_score, weather_score, network_score, security_score, infrastructure_score
-    );
-
-    ScoredCandidate {
+    let mut scored = ScoredCandidate {
         candidate,
-        score,
+        score: 0.000000000,
         pop_score,
         pop_proximity_score,
         xai_score,
@@ -195,7 +208,17 @@ fn score_candidate(mut candidate: Candidate, config: &ScorerConfig, max_cables:
         network_score,
         security_score,
         infrastructure_score,
-    }
+    };
+
+    // Calculate composite score (7-factor model)
+    scored.score = config.composite(&scored);
+
+    debug!(
+        "Scored {}: {:.3} (pop={:.2}, pop_prox={:.2}, xai={:.2}, wx={:.2}, net={:.2}, sec={:.2}, infra={:.2})",
+        scored.candidate.name, scored.score, pop_score, pop_proximity_score, xai_score, weather_score, network_score, security_score, infrastructure_score
+    );
+
+    scored
 }
 
 /// Calculate infrastructure proximity bonus
@@ -428,4 +451,30 @@ mod tests {
             "Tier 0 should have > infra score than Tier 3: {} vs {}",
             scored_tier0.infrastructure_score, scored_tier3.infrastructure_score);
     }
+
+    #[test]
+    fn test_rescore_matches_full_scoring() {
+        let candidates = vec![
+            make_candidate("Memphis", XAI_LAT, XAI_LON, Some(1), Some(5)),
+            make_candidate("Singapore", 1.352100000, 103.819800000, Some(2), Some(10)),
+        ];
+        let baseline = score_candidates(candidates.clone(), &ScorerConfig::default());
+
+        let weather_only = ScorerConfig {
+            w_population: 0.000000000,
+            w_pop_proximity: 0.000000000,
+            w_xai: 0.000000000,
+            w_weather: 1.000000000,
+            w_network: 0.000000000,
+            w_security: 0.000000000,
+            w_infrastructure: 0.000000000,
+            ..ScorerConfig::default()
+        };
+        let rescored = rescore(&baseline, &weather_only);
+        let full = score_candidates(candidates, &weather_only);
+        for (r, f) in rescored.iter().zip(&full) {
+            assert!((r.score - f.score).abs() < 1e-12);
+            assert!((r.score - 0.900000000).abs() < 1e-12);
+        }
+    }
 }
@@ -1,8 +1,8 @@
 //! Candidate selection with zone quotas and spacing constraints
 
 use crate::{
-    haversine_km, Candidate, CandidateSource, Result, ScoredCandidate, SelectionMetadata,
-    SelectionResult, SelectorError, Zone, DEDUP_THRESHOLD_KM, ZONE_QUOTAS,
+    haversine_km, Candidate, CandidateSource, PhasedPlan, Result, RolloutPhase, ScoredCandidate,
+    SelectionMetadata, SelectionResult, SelectorError, Zone, DEDUP_THRESHOLD_KM, ZONE_QUOTAS,
 };
 use std::collections::HashMap;
 use tracing::{debug, info};
@@ -126,6 +126,105 @@ pub fn select_by_zone(
     Ok(SelectionResult { selected, metadata })
 }
 
+/// Plan a phased build-out of nested selections, e.g. `[50, 100, 247]`
+///
+/// Each zone's stations are ranked once, by the same score-and-spacing
+/// greedy as `select_by_zone`, and every phase takes a prefix of that
+/// ranking, so a phase never re-sites an earlier phase's stations. Zone
+/// quotas grow with the phases in proportion to `ZONE_QUOTAS`; a final
+/// phase of 247 reproduces `select_by_zone` exactly.
+pub fn select_phased(
+    mut scored: Vec<ScoredCandidate>,
+    phases: &[usize],
+    min_spacing_km: f64,
+) -> Result<PhasedPlan> {
+    if phases.is_empty() || phases[0] == 0 || phases.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(SelectorError::InvalidPhases(format!(
+            "{:?}: phase totals must be positive and strictly increasing",
+            phases
+        )));
+    }
+    let quotas = phase_zone_quotas(phases);
+
+    scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    let mut by_zone: HashMap<Zone, Vec<ScoredCandidate>> = HashMap::new();
+    for s in scored {
+        by_zone.entry(s.candidate.zone).or_default().push(s);
+    }
+
+    // Build order per zone, sized for the final phase
+    let final_quotas = &quotas[quotas.len() - 1];
+    let mut ranked: HashMap<Zone, Vec<ScoredCandidate>> = HashMap::new();
+    for (i, (zone, _)) in ZONE_QUOTAS.iter().enumerate() {
+        let zone_candidates = by_zone.get(zone).map(|v| v.as_slice()).unwrap_or(&[]);
+        if zone_candidates.len() < final_quotas[i] {
+            return Err(SelectorError::InsufficientCandidates(
+                *zone,
+                final_quotas[i],
+                zone_candidates.len(),
+            ));
+        }
+        ranked.insert(*zone, select_with_spacing(zone_candidates, final_quotas[i], min_spacing_km));
+    }
+
+    let mut plan = Vec::with_capacity(phases.len());
+    let mut built = [0usize; ZONE_QUOTAS.len()];
+    for (k, (&total, zone_quotas)) in phases.iter().zip(&quotas).enumerate() {
+        let mut added = Vec::new();
+        let mut zone_distribution = HashMap::new();
+        for (i, (zone, _)) in ZONE_QUOTAS.iter().enumerate() {
+            added.extend_from_slice(&ranked[zone][built[i]..zone_quotas[i]]);
+            built[i] = zone_quotas[i];
+            zone_distribution.insert(format!("{:?}", zone), zone_quotas[i]);
+        }
+        info!("Phase {}: {} stations ({} new)", k + 1, total, added.len());
+        plan.push(RolloutPhase {
+            phase: k + 1,
+            total,
+            zone_distribution,
+            added,
+        });
+    }
+
+    let metadata = SelectionMetadata {
+        total_selected: phases[phases.len() - 1],
+        zone_distribution: plan[plan.len() - 1].zone_distribution.clone(),
+        total_candidates: by_zone.values().map(|v| v.len()).sum(),
+        dedup_threshold_km: DEDUP_THRESHOLD_KM,
+        min_spacing_km,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    Ok(PhasedPlan {
+        phases: plan,
+        metadata,
+    })
+}
+
+/// Cumulative per-zone quotas for each phase, in `ZONE_QUOTAS` order
+///
+/// Each phase starts from the previous one's quotas (so they never
+/// shrink) and gives each new station to the zone furthest below its
+/// `ZONE_QUOTAS` share of the phase total.
+fn phase_zone_quotas(phases: &[usize]) -> Vec<[usize; ZONE_QUOTAS.len()]> {
+    let full: usize = ZONE_QUOTAS.iter().map(|(_, q)| q).sum();
+    let mut current = [0usize; ZONE_QUOTAS.len()];
+    let mut out = Vec::with_capacity(phases.len());
+
+    for &total in phases {
+        let assigned: usize = current.iter().sum();
+        for _ in assigned..total {
+            let deficit = |i: usize| ZONE_QUOTAS[i].1 as f64 * total as f64 / full as f64 - current[i] as f64;
+            let neediest = (0..ZONE_QUOTAS.len())
+                .reduce(|best, i| if deficit(i) > deficit(best) { i } else { best })
+                .unwrap_or(0);
+            current[neediest] += 1;
+        }
+        out.push(current);
+    }
+    out
+}
+
 /// Select top N candidates with minimum spacing
 fn select_with_spacing(
     candidates: &[ScoredCandidate],
@@ -181,32 +280,25 @@ fn select_with_spacing(
 
 /// Export selection result to GeoJSON
 pub fn to_geojson(result: &SelectionResult) -> serde_json::Value {
-    let features: Vec<serde_json::Value> = result
-        .selected
+    let features: Vec<serde_json::Value> = result.selected.iter().map(feature).collect();
+
+    serde_json::json!({
+        "type": "FeatureCollection",
+        "features": features,
+        "metadata": result.metadata
+    })
+}
+
+/// Export a phased plan to GeoJSON, each station tagged with its phase
+pub fn phased_to_geojson(plan: &PhasedPlan) -> serde_json::Value {
+    let features: Vec<serde_json::Value> = plan
+        .phases
         .iter()
-        .map(|s| {
-            serde_json::json!({
-                "type": "Feature",
-                "geometry": {
-                    "type": "Point",
-                    "coordinates": [s.candidate.longitude, s.candidate.latitude]
-                },
-                "properties": {
-                    "id": s.candidate.id,
-                    "name": s.candidate.name,
-                    "zone": format!("{:?}", s.candidate.zone),
-                    "score": s.score,
-                    "pop_score": s.pop_score,
-                    "pop_proximity_score": s.pop_proximity_score,
-                    "xai_score": s.xai_score,
-                    "weather_score": s.weather_score,
-                    "network_score": s.network_score,
-                    "security_score": s.security_score,
-                    "tier": s.candidate.tier,
-                    "cable_count": s.candidate.cable_count,
-                    "country_code": s.candidate.country_code,
-                    "source": format!("{:?}", s.candidate.source)
-                }
+        .flat_map(|p| {
+            p.added.iter().map(move |s| {
+                let mut f = feature(s);
+                f["properties"]["phase"] = serde_json::json!(p.phase);
+                f
             })
         })
         .collect();
@@ -214,7 +306,33 @@ pub fn to_geojson(result: &SelectionResult) -> serde_json::Value {
     serde_json::json!({
         "type": "FeatureCollection",
         "features": features,
-        "metadata": result.metadata
+        "metadata": plan.metadata
+    })
+}
+
+fn feature(s: &ScoredCandidate) -> serde_json::Value {
+    serde_json::json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "Point",
+            "coordinates": [s.candidate.longitude, s.candidate.latitude]
+        },
+        "properties": {
+            "id": s.candidate.id,
+            "name": s.candidate.name,
+            "zone": format!("{:?}", s.candidate.zone),
+            "score": s.score,
+            "pop_score": s.pop_score,
+            "pop_proximity_score": s.pop_proximity_score,
+            "xai_score": s.xai_score,
+            "weather_score": s.weather_score,
+            "network_score": s.network_score,
+            "security_score": s.security_score,
+            "tier": s.candidate.tier,
+            "cable_count": s.candidate.cable_count,
+            "country_code": s.candidate.country_code,
+            "source": format!("{:?}", s.candidate.source)
+        }
     })
 }
 
@@ -303,4 +421,66 @@ mod tests {
         assert!(selected.iter().any(|s| s.candidate.id == "a"));
         assert!(selected.iter().any(|s| s.candidate.id == "c"));
     }
+
+    /// `n` candidates per zone on a 2° grid, scores descending by index
+    fn zone_grid(n: usize) -> Vec<ScoredCandidate> {
+        let mut scored = Vec::new();
+        for (zone, lon0) in [("am", -100.0), ("em", 10.0), ("ap", 100.0)] {
+            for i in 0..n {
+                let (lat, lon) = (-40.0 + 2.0 * (i / 10) as f64, lon0 + 2.0 * (i % 10) as f64);
+                let id = format!("{}-{}", zone, i);
+                let score = 1.0 - i as f64 / n as f64 + lon0 / 1e4;
+                scored.push(make_scored(make_candidate(&id, lat, lon, CandidateSource::GroundNode), score));
+            }
+        }
+        scored
+    }
+
+    #[test]
+    fn test_phase_quotas_nested_and_balanced() {
+        let quotas = phase_zone_quotas(&[50, 100, 247]);
+        assert_eq!(quotas[2], [72, 85, 90]);
+        for (q, total) in quotas.iter().zip([50, 100, 247]) {
+            assert_eq!(q.iter().sum::<usize>(), total);
+            for (i, (_, full)) in ZONE_QUOTAS.iter().enumerate() {
+                let share = *full as f64 * total as f64 / 247.0;
+                assert!((q[i] as f64 - share).abs() < 1.0, "{:?} at {}", q, total);
+            }
+        }
+        for w in quotas.windows(2) {
+            assert!(w[0].iter().zip(&w[1]).all(|(a, b)| a <= b));
+        }
+    }
+
+    #[test]
+    fn test_select_phased_nested() {
+        let scored = zone_grid(100);
+        let plan = select_phased(scored.clone(), &[50, 100, 247], 50.0).unwrap();
+        assert_eq!(plan.phases.len(), 3);
+
+        let ids = |phase| plan.stations(phase).map(|s| s.candidate.id.clone()).collect::<Vec<_>>();
+        let (p1, p2, p3) = (ids(1), ids(2), ids(3));
+        assert_eq!((p1.len(), p2.len(), p3.len()), (50, 100, 247));
+        assert!(p1.iter().all(|id| p2.contains(id)));
+        assert!(p2.iter().all(|id| p3.contains(id)));
+
+        // Final phase is the one-shot selection
+        let full = select_by_zone(scored, 50.0).unwrap();
+        let mut one_shot: Vec<_> = full.selected.iter().map(|s| s.candidate.id.clone()).collect();
+        let mut phased = p3.clone();
+        one_shot.sort();
+        phased.sort();
+        assert_eq!(one_shot, phased);
+        assert_eq!(plan.metadata.zone_distribution, full.metadata.zone_distribution);
+    }
+
+    #[test]
+    fn test_select_phased_rejects_bad_phases() {
+        assert!(select_phased(zone_grid(10), &[], 50.0).is_err());
+        assert!(select_phased(zone_grid(10), &[20, 20], 50.0).is_err());
+        assert!(matches!(
+            select_phased(zone_grid(10), &[100], 50.0),
+            Err(SelectorError::InsufficientCandidates(..))
+        ));
+    }
 }
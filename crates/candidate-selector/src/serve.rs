@@ -0,0 +1,422 @@
+//! What-if rescoring server
+//!
+//! `select-stations serve` loads, deduplicates and scores the candidates
+//! once and keeps them in memory. Factor scores don't depend on the
+//! weights, so a what-if only recombines them (`scorer::rescore`),
+//! filters and reruns zone selection: a weight or constraint tweak comes
+//! back in well under a second instead of a full CLI run.
+//!
+//! - `GET  /candidates` loaded set, default weights and spacing
+//! - `POST /rescore` ranking under modified weights and filters
+//! - `POST /select` zone selection under modified weights and
+//!   constraints, with the stations gained and lost against the
+//!   default-weight baseline
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::scorer::{self, ScorerConfig};
+use crate::{selector, Result, ScoredCandidate, SelectionResult, SelectorError};
+
+/// Default length of a `/rescore` ranking
+const DEFAULT_RANKING_LIMIT: usize = 50;
+
+/// Weight overrides; unset weights keep the server's. The result is
+/// normalised to sum to 1 so scores stay comparable with the baseline.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Weights {
+    pub population: Option<f64>,
+    pub pop_proximity: Option<f64>,
+    pub xai: Option<f64>,
+    pub weather: Option<f64>,
+    pub network: Option<f64>,
+    pub security: Option<f64>,
+    pub infrastructure: Option<f64>,
+}
+
+impl Weights {
+    fn of(config: &ScorerConfig) -> Self {
+        Self {
+            population: Some(config.w_population),
+            pop_proximity: Some(config.w_pop_proximity),
+            xai: Some(config.w_xai),
+            weather: Some(config.w_weather),
+            network: Some(config.w_network),
+            security: Some(config.w_security),
+            infrastructure: Some(config.w_infrastructure),
+        }
+    }
+
+    /// `base` with these overrides applied and normalised
+    pub fn apply(&self, base: &ScorerConfig) -> Result<ScorerConfig> {
+        let mut config = base.clone();
+        let fields = [
+            (&mut config.w_population, self.population),
+            (&mut config.w_pop_proximity, self.pop_proximity),
+            (&mut config.w_xai, self.xai),
+            (&mut config.w_weather, self.weather),
+            (&mut config.w_network, self.network),
+            (&mut config.w_security, self.security),
+            (&mut config.w_infrastructure, self.infrastructure),
+        ];
+
+        let mut total = 0.000000000;
+        let mut weights = Vec::with_capacity(fields.len());
+        for (weight, value) in fields {
+            if let Some(value) = value {
+                if !(value.is_finite() && value >= 0.000000000) {
+                    return Err(SelectorError::InvalidWeights(format!("{} is not a non-negative weight", value)));
+                }
+                *weight = value;
+            }
+            total += *weight;
+            weights.push(weight);
+        }
+        if total <= 0.000000000 {
+            return Err(SelectorError::InvalidWeights("weights sum to zero".to_string()));
+        }
+        for weight in weights {
+            *weight /= total;
+        }
+        Ok(config)
+    }
+}
+
+/// Weight and constraint changes for one what-if
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WhatIf {
+    pub weights: Weights,
+    /// Minimum spacing between selected stations (km); defaults to the
+    /// server's
+    pub min_spacing_km: Option<f64>,
+    /// Drop candidates below this weather score (0-1)
+    pub min_weather_score: Option<f64>,
+    /// Drop candidates below this security score (0-1)
+    pub min_security_score: Option<f64>,
+    /// ISO 3166-1 alpha-2 codes to exclude
+    pub exclude_countries: Vec<String>,
+    pub exclude_ids: Vec<String>,
+    /// Length of a `/rescore` ranking (default 50)
+    pub limit: Option<usize>,
+}
+
+/// A candidate's place in a what-if ranking
+#[derive(Debug, Clone, Serialize)]
+pub struct RankedCandidate {
+    /// 1-based
+    pub rank: usize,
+    pub id: String,
+    pub name: String,
+    pub zone: String,
+    pub score: f64,
+    pub baseline_rank: usize,
+    pub baseline_score: f64,
+}
+
+/// A what-if selection and how it differs from the baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct WhatIfSelection {
+    #[serde(flatten)]
+    pub result: SelectionResult,
+    /// Selected here but not in the baseline
+    pub added: Vec<String>,
+    /// Selected in the baseline but not here
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateSetSummary {
+    pub total_candidates: usize,
+    pub zone_distribution: HashMap<String, usize>,
+    pub weights: Weights,
+    pub min_spacing_km: f64,
+    /// Stations in the default-weight selection (0 if it failed)
+    pub baseline_selected: usize,
+}
+
+/// The scored candidate set and its default-weight baseline
+pub struct Session {
+    scored: Vec<ScoredCandidate>,
+    config: ScorerConfig,
+    min_spacing_km: f64,
+    /// Candidate ID to baseline (rank, score)
+    baseline_ranks: HashMap<String, (usize, f64)>,
+    baseline_selected: HashSet<String>,
+}
+
+fn ranked(mut scored: Vec<ScoredCandidate>) -> Vec<ScoredCandidate> {
+    scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    scored
+}
+
+impl Session {
+    /// `scored` must have been scored with `config`
+    pub fn new(scored: Vec<ScoredCandidate>, config: ScorerConfig, min_spacing_km: f64) -> Self {
+        let scored = ranked(scored);
+        let baseline_ranks = scored
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.candidate.id.clone(), (i + 1, s.score)))
+            .collect();
+        let baseline_selected = match selector::select_by_zone(scored.clone(), min_spacing_km) {
+            Ok(result) => result.selected.into_iter().map(|s| s.candidate.id).collect(),
+            Err(e) => {
+                warn!("Baseline selection failed: {}", e);
+                HashSet::new()
+            }
+        };
+        Self {
+            scored,
+            config,
+            min_spacing_km,
+            baseline_ranks,
+            baseline_selected,
+        }
+    }
+
+    pub fn summary(&self) -> CandidateSetSummary {
+        let mut zone_distribution = HashMap::new();
+        for s in &self.scored {
+            *zone_distribution.entry(format!("{:?}", s.candidate.zone)).or_insert(0) += 1;
+        }
+        CandidateSetSummary {
+            total_candidates: self.scored.len(),
+            zone_distribution,
+            weights: Weights::of(&self.config),
+            min_spacing_km: self.min_spacing_km,
+            baseline_selected: self.baseline_selected.len(),
+        }
+    }
+
+    /// Rescored and filtered candidates, best first
+    fn candidates(&self, what_if: &WhatIf) -> Result<Vec<ScoredCandidate>> {
+        let config = what_if.weights.apply(&self.config)?;
+        let excluded_ids: HashSet<&str> = what_if.exclude_ids.iter().map(String::as_str).collect();
+        let excluded_countries: HashSet<String> =
+            what_if.exclude_countries.iter().map(|c| c.to_uppercase()).collect();
+
+        let kept = scorer::rescore(&self.scored, &config)
+            .into_iter()
+            .filter(|s| {
+                !excluded_ids.contains(s.candidate.id.as_str())
+                    && !s
+                        .candidate
+                        .country_code
+                        .as_ref()
+                        .is_some_and(|c| excluded_countries.contains(&c.to_uppercase()))
+                    && !what_if.min_weather_score.is_some_and(|min| s.weather_score < min)
+                    && !what_if.min_security_score.is_some_and(|min| s.security_score < min)
+            })
+            .collect();
+        Ok(ranked(kept))
+    }
+
+    pub fn rank(&self, what_if: &WhatIf) -> Result<Vec<RankedCandidate>> {
+        let limit = what_if.limit.unwrap_or(DEFAULT_RANKING_LIMIT);
+        Ok(self
+            .candidates(what_if)?
+            .into_iter()
+            .take(limit)
+            .enumerate()
+            .map(|(i, s)| {
+                let (baseline_rank, baseline_score) = self.baseline_ranks[&s.candidate.id];
+                RankedCandidate {
+                    rank: i + 1,
+                    id: s.candidate.id,
+                    name: s.candidate.name,
+                    zone: format!("{:?}", s.candidate.zone),
+                    score: s.score,
+                    baseline_rank,
+                    baseline_score,
+                }
+            })
+            .collect())
+    }
+
+    pub fn select(&self, what_if: &WhatIf) -> Result<WhatIfSelection> {
+        let spacing = what_if.min_spacing_km.unwrap_or(self.min_spacing_km);
+        let result = selector::select_by_zone(self.candidates(what_if)?, spacing)?;
+
+        let selected: HashSet<&str> = result.selected.iter().map(|s| s.candidate.id.as_str()).collect();
+        let added = result
+            .selected
+            .iter()
+            .map(|s| &s.candidate.id)
+            .filter(|id| !self.baseline_selected.contains(*id))
+            .cloned()
+            .collect();
+        let mut removed: Vec<String> = self
+            .baseline_selected
+            .iter()
+            .filter(|id| !selected.contains(id.as_str()))
+            .cloned()
+            .collect();
+        removed.sort_by_key(|id| self.baseline_ranks[id].0);
+
+        Ok(WhatIfSelection {
+            result,
+            added,
+            removed,
+        })
+    }
+}
+
+// ========== Routes ==========
+
+fn status(e: SelectorError) -> (StatusCode, String) {
+    let code = match e {
+        SelectorError::InvalidWeights(_) => StatusCode::BAD_REQUEST,
+        SelectorError::InsufficientCandidates(..) => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (code, e.to_string())
+}
+
+/// GET /candidates
+async fn candidates(State(session): State<Arc<Session>>) -> Json<CandidateSetSummary> {
+    Json(session.summary())
+}
+
+/// POST /rescore
+async fn rescore(
+    State(session): State<Arc<Session>>,
+    Json(what_if): Json<WhatIf>,
+) -> std::result::Result<Json<Vec<RankedCandidate>>, (StatusCode, String)> {
+    session.rank(&what_if).map(Json).map_err(status)
+}
+
+/// POST /select
+async fn select(
+    State(session): State<Arc<Session>>,
+    Json(what_if): Json<WhatIf>,
+) -> std::result::Result<Json<WhatIfSelection>, (StatusCode, String)> {
+    session.select(&what_if).map(Json).map_err(status)
+}
+
+pub fn router(session: Session) -> Router {
+    Router::new()
+        .route("/candidates", get(candidates))
+        .route("/rescore", post(rescore))
+        .route("/select", post(select))
+        .with_state(Arc::new(session))
+}
+
+/// Serve `session` until the process is stopped
+pub async fn serve(session: Session, addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("What-if server listening on http://{}", addr);
+    axum::serve(listener, router(session)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Candidate, Zone, MIN_SPACING_KM};
+
+    /// 100 candidates per zone on a 2° grid; composite score falls with
+    /// the index, weather score rises with it
+    fn session() -> Session {
+        let mut scored = Vec::new();
+        for (zone, lon0) in [("am", -100.0), ("em", 10.0), ("ap", 100.0)] {
+            for i in 0..100 {
+                let (lat, lon) = (-40.0 + 2.0 * (i / 10) as f64, lon0 + 2.0 * (i % 10) as f64);
+                let id = format!("{}-{}", zone, i);
+                let mut candidate = Candidate::from_ground_node(id.clone(), id, lat, lon, Some(1), None, None);
+                candidate.country_code = Some(if i % 2 == 0 { "US" } else { "SG" }.to_string());
+                let rank = 1.0 - i as f64 / 100.0;
+                let mut s = ScoredCandidate {
+                    candidate,
+                    score: 0.0,
+                    pop_score: rank,
+                    pop_proximity_score: rank,
+                    xai_score: rank,
+                    weather_score: 1.0 - rank,
+                    network_score: rank,
+                    security_score: 0.8,
+                    infrastructure_score: rank,
+                };
+                s.score = ScorerConfig::default().composite(&s);
+                scored.push(s);
+            }
+        }
+        Session::new(scored, ScorerConfig::default(), MIN_SPACING_KM)
+    }
+
+    #[test]
+    fn test_default_what_if_reproduces_baseline() {
+        let session = session();
+        assert_eq!(session.summary().baseline_selected, 247);
+        assert_eq!(session.summary().zone_distribution[&format!("{:?}", Zone::Apac)], 100);
+
+        let selection = session.select(&WhatIf::default()).unwrap();
+        assert_eq!(selection.result.selected.len(), 247);
+        assert!(selection.added.is_empty() && selection.removed.is_empty());
+
+        let ranking = session.rank(&WhatIf::default()).unwrap();
+        assert_eq!(ranking.len(), DEFAULT_RANKING_LIMIT);
+        assert!(ranking.iter().all(|r| r.rank == r.baseline_rank));
+    }
+
+    #[test]
+    fn test_weights_and_constraints_change_selection() {
+        let session = session();
+
+        // Weather alone inverts the ranking
+        let weather_only = WhatIf {
+            weights: Weights {
+                population: Some(0.0),
+                pop_proximity: Some(0.0),
+                xai: Some(0.0),
+                weather: Some(2.0),
+                network: Some(0.0),
+                security: Some(0.0),
+                infrastructure: Some(0.0),
+            },
+            limit: Some(3),
+            ..Default::default()
+        };
+        let ranking = session.rank(&weather_only).unwrap();
+        assert!(ranking.iter().all(|r| r.id.ends_with("-99")));
+        assert!((ranking[0].score - 0.99).abs() < 1e-9);
+        assert_eq!(session.select(&weather_only).unwrap().added.len(), 28 + 15 + 10);
+
+        // Dropping a baseline station brings in the next one in its zone
+        let excluded = WhatIf {
+            exclude_ids: vec!["am-0".to_string()],
+            ..Default::default()
+        };
+        let selection = session.select(&excluded).unwrap();
+        assert_eq!(selection.removed, ["am-0"]);
+        assert_eq!(selection.added, ["am-72"]);
+
+        // Half of each zone is too few for the quotas
+        let no_sg = WhatIf {
+            exclude_countries: vec!["sg".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            session.select(&no_sg),
+            Err(SelectorError::InsufficientCandidates(..))
+        ));
+
+        let negative = WhatIf {
+            weights: Weights {
+                xai: Some(-1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(session.rank(&negative), Err(SelectorError::InvalidWeights(_))));
+    }
+}
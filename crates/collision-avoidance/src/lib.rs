@@ -1,9 +1,15 @@
 //! Collision Avoidance Library
 //!
 //! Conjunction assessment and collision avoidance maneuver planning
-//! with UCLA CTAS (Conjunction Threat Assessment System) integration.
+//! with UCLA CTAS (Conjunction Threat Assessment System) integration,
+//! screening of a constellation against itself and against owner/operator
+//! ephemerides (`constellation`), and CCSDS OEM ingest (`oem`). A
+//! `ManeuverPlan` applied to a satellite refits its elements after the
+//! burn, so the new orbit can be re-propagated and re-screened.
 
 use chrono::{DateTime, Duration, Utc};
+use orbital_mechanics::stationkeeping::{self, MeanElements};
+use orbital_mechanics::Satellite;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -15,6 +21,8 @@ pub enum CollisionError {
     PropagationFailed(String),
     #[error("Maneuver not feasible: {0}")]
     ManeuverNotFeasible(String),
+    #[error("Invalid ephemeris: {0}")]
+    InvalidEphemeris(String),
 }
 
 pub type Result<T> = std::result::Result<T, CollisionError>;
@@ -57,6 +65,8 @@ pub enum ObjectType {
     Unknown,
 }
 
+/// Impulsive avoidance burn. The delta-v is in the primary's RIC frame
+/// (km/s): x radial, y in-track, z cross-track.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ManeuverPlan {
     pub event_id: String,
@@ -69,6 +79,26 @@ pub struct ManeuverPlan {
     pub fuel_cost_kg: f64,
 }
 
+impl ManeuverPlan {
+    /// Radial, in-track, cross-track delta-v (km/s)
+    pub fn dv_ric_km_s(&self) -> [f64; 3] {
+        [self.delta_v_x, self.delta_v_y, self.delta_v_z]
+    }
+
+    /// `sat` with elements refitted after the burn, at the execution time
+    pub fn apply(&self, sat: &Satellite) -> Result<Satellite> {
+        let failed = |e: orbital_mechanics::OrbitalError| CollisionError::PropagationFailed(e.to_string());
+        let elements = MeanElements::from_satellite(sat).map_err(failed)?;
+        let burned = stationkeeping::apply_delta_v(&elements, self.dv_ric_km_s(), self.execution_time).map_err(failed)?;
+        let (tle_line1, tle_line2) = burned.to_tle_lines(sat.norad_id);
+        Ok(Satellite {
+            tle_line1,
+            tle_line2,
+            ..sat.clone()
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum ManeuverType {
     InTrack,
@@ -149,8 +179,8 @@ impl CollisionAssessment {
         Ok(ManeuverPlan {
             event_id: event.id.clone(),
             maneuver_type: ManeuverType::InTrack,
-            delta_v_x: delta_v_magnitude,
-            delta_v_y: 0.0,
+            delta_v_x: 0.0,
+            delta_v_y: delta_v_magnitude,
             delta_v_z: 0.0,
             execution_time: event.tca - Duration::hours(12),
             new_miss_distance_km: event.miss_distance_km + self.screening_radius_km,
@@ -159,6 +189,720 @@ impl CollisionAssessment {
     }
 }
 
+pub mod oem {
+    //! Owner/operator ephemerides (CCSDS OEM)
+    //!
+    //! Other operators publish their manoeuvre-aware predictions as Orbit
+    //! Ephemeris Messages, which are far better than their catalogue TLEs
+    //! around a burn. `parse` reads the KVN form: `META_START`/`META_STOP`
+    //! blocks, each followed by state lines `epoch x y z vx vy vz` (km,
+    //! km/s; an acceleration triple may follow and is ignored). `COMMENT`
+    //! lines and covariance sections are skipped. Segments of the same
+    //! object are merged; where they overlap the later segment wins.
+    //!
+    //! States are kept in TEME, the frame SGP4 output is in, so they can be
+    //! compared with propagated TLEs directly. `EME2000` (and its
+    //! `GCRF`/`ICRF` equivalents, at screening accuracy) is rotated by
+    //! `transforms::j2000_to_teme_matrix`; epochs are moved to UTC from
+    //! `TAI`, `GPS` or `TT`. Only Earth-centred messages are accepted.
+
+    use super::*;
+    use chrono::NaiveDateTime;
+    use orbital_mechanics::interpolation::hermite;
+    use orbital_mechanics::transforms;
+    use orbital_mechanics::{Satellite, StateVector};
+    use std::collections::BTreeMap;
+
+    /// States further apart than this are a coverage gap, not a step (s)
+    pub const MAX_INTERPOLATION_GAP_S: i64 = 3600;
+
+    /// TAI - UTC since 2017 (s)
+    const TAI_UTC_S: f64 = 37.0;
+    /// TT - TAI (s)
+    const TT_TAI_S: f64 = 32.184;
+    /// TAI - GPS (s)
+    const TAI_GPS_S: f64 = 19.0;
+
+    type Matrix = [[f64; 3]; 3];
+
+    /// Ephemeris of one object, states in TEME on UTC epochs
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Ephemeris {
+        pub object_name: String,
+        pub object_id: String,
+        /// Frame the states were delivered in
+        pub ref_frame: String,
+        pub states: Vec<StateVector>,
+    }
+
+    impl Ephemeris {
+        pub fn start(&self) -> Option<DateTime<Utc>> {
+            self.states.first().map(|s| s.epoch)
+        }
+
+        pub fn stop(&self) -> Option<DateTime<Utc>> {
+            self.states.last().map(|s| s.epoch)
+        }
+
+        /// State at `time` by Hermite interpolation between the bracketing
+        /// states; `None` outside the ephemeris or across a coverage gap
+        pub fn state_at(&self, time: DateTime<Utc>) -> Option<StateVector> {
+            let i = self.states.partition_point(|s| s.epoch <= time);
+            if i == 0 {
+                return None;
+            }
+            let before = &self.states[i - 1];
+            if before.epoch == time {
+                return Some(*before);
+            }
+            let after = self.states.get(i)?;
+            if (after.epoch - before.epoch).num_seconds() > MAX_INTERPOLATION_GAP_S {
+                return None;
+            }
+            Some(hermite(before, after, time))
+        }
+
+        /// Whether this is the ephemeris of `sat`: by ID, name or NORAD
+        /// number in `OBJECT_ID`, or by name in `OBJECT_NAME`
+        pub fn describes(&self, sat: &Satellite) -> bool {
+            self.object_id == sat.id
+                || self.object_id == sat.norad_id.to_string()
+                || self.object_name == sat.id
+                || self.object_name == sat.name
+        }
+    }
+
+    fn invalid(line: usize, what: impl std::fmt::Display) -> CollisionError {
+        CollisionError::InvalidEphemeris(format!("line {}: {}", line, what))
+    }
+
+    fn parse_epoch(text: &str) -> Option<NaiveDateTime> {
+        let text = text.trim_end_matches('Z');
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+            .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%jT%H:%M:%S%.f"))
+            .ok()
+    }
+
+    /// Offset added to an epoch in `time_system` to reach UTC (s)
+    fn utc_offset_s(time_system: &str) -> Option<f64> {
+        match time_system {
+            "UTC" => Some(0.0),
+            "TAI" => Some(-TAI_UTC_S),
+            "GPS" => Some(TAI_GPS_S - TAI_UTC_S),
+            "TT" => Some(-(TT_TAI_S + TAI_UTC_S)),
+            _ => None,
+        }
+    }
+
+    /// Rotation taking `ref_frame` vectors to TEME at `time`
+    fn to_teme(ref_frame: &str, time: DateTime<Utc>) -> Option<Matrix> {
+        match ref_frame {
+            "TEME" => Some([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]),
+            "EME2000" | "J2000" | "GCRF" | "ICRF" => Some(transforms::j2000_to_teme_matrix(time)),
+            _ => None,
+        }
+    }
+
+    struct Segment {
+        object_name: String,
+        object_id: String,
+        ref_frame: String,
+        offset_s: f64,
+        states: Vec<StateVector>,
+    }
+
+    fn segment(meta: &BTreeMap<String, String>, line: usize) -> Result<Segment> {
+        let key = |k: &str| meta.get(k).cloned().unwrap_or_default();
+        let center = key("CENTER_NAME");
+        if !center.eq_ignore_ascii_case("EARTH") {
+            return Err(invalid(line, format!("unsupported CENTER_NAME {:?}", center)));
+        }
+        let ref_frame = key("REF_FRAME");
+        if to_teme(&ref_frame, Utc::now()).is_none() {
+            return Err(invalid(line, format!("unsupported REF_FRAME {:?}", ref_frame)));
+        }
+        let time_system = key("TIME_SYSTEM");
+        let offset_s = utc_offset_s(&time_system)
+            .ok_or_else(|| invalid(line, format!("unsupported TIME_SYSTEM {:?}", time_system)))?;
+        let (object_name, object_id) = (key("OBJECT_NAME"), key("OBJECT_ID"));
+        if object_name.is_empty() && object_id.is_empty() {
+            return Err(invalid(line, "segment names no object"));
+        }
+        Ok(Segment {
+            object_name,
+            object_id,
+            ref_frame,
+            offset_s,
+            states: Vec::new(),
+        })
+    }
+
+    fn state(segment: &Segment, fields: &[&str], line: usize) -> Result<StateVector> {
+        let epoch = parse_epoch(fields[0]).ok_or_else(|| invalid(line, format!("bad epoch {:?}", fields[0])))?;
+        let epoch = DateTime::<Utc>::from_naive_utc_and_offset(epoch, Utc)
+            + Duration::microseconds((segment.offset_s * 1e6) as i64);
+        let mut values = [0.0; 6];
+        for (value, field) in values.iter_mut().zip(&fields[1..7]) {
+            *value = field.parse().map_err(|_| invalid(line, format!("bad value {:?}", field)))?;
+        }
+        let m = to_teme(&segment.ref_frame, epoch)
+            .ok_or_else(|| invalid(line, format!("unsupported REF_FRAME {:?}", segment.ref_frame)))?;
+        let rotate = |v: &[f64]| -> [f64; 3] { std::array::from_fn(|i| m[i][0] * v[0] + m[i][1] * v[1] + m[i][2] * v[2]) };
+        let (p, v) = (rotate(&values[..3]), rotate(&values[3..]));
+        Ok(StateVector {
+            position_x: p[0],
+            position_y: p[1],
+            position_z: p[2],
+            velocity_x: v[0],
+            velocity_y: v[1],
+            velocity_z: v[2],
+            epoch,
+        })
+    }
+
+    /// Ephemerides in a KVN OEM, one per object, in order of appearance
+    pub fn parse(text: &str) -> Result<Vec<Ephemeris>> {
+        let mut segments: Vec<Segment> = Vec::new();
+        let mut meta: Option<BTreeMap<String, String>> = None;
+        let mut in_covariance = false;
+
+        for (i, raw) in text.lines().enumerate() {
+            let (n, line) = (i + 1, raw.trim());
+            if line.is_empty() || line.starts_with("COMMENT") {
+                continue;
+            }
+            if in_covariance {
+                in_covariance = line != "COVARIANCE_STOP";
+                continue;
+            }
+            match line {
+                "META_START" => meta = Some(BTreeMap::new()),
+                "META_STOP" => {
+                    let block = meta.take().ok_or_else(|| invalid(n, "META_STOP without META_START"))?;
+                    segments.push(segment(&block, n)?);
+                }
+                "COVARIANCE_START" => in_covariance = true,
+                _ => {
+                    if let Some(block) = meta.as_mut() {
+                        let (key, value) = line.split_once('=').ok_or_else(|| invalid(n, "expected KEY = value"))?;
+                        block.insert(key.trim().to_string(), value.trim().to_string());
+                        continue;
+                    }
+                    let fields: Vec<&str> = line.split_whitespace().collect();
+                    let Some(current) = segments.last_mut() else {
+                        // Header keywords before the first segment
+                        if line.contains('=') {
+                            continue;
+                        }
+                        return Err(invalid(n, "state before any META block"));
+                    };
+                    if line.contains('=') {
+                        return Err(invalid(n, "keyword outside a META block"));
+                    }
+                    if fields.len() != 7 && fields.len() != 10 {
+                        return Err(invalid(n, format!("expected 7 or 10 fields, found {}", fields.len())));
+                    }
+                    let state = state(current, &fields, n)?;
+                    current.states.push(state);
+                }
+            }
+        }
+        if meta.is_some() {
+            return Err(CollisionError::InvalidEphemeris("unterminated META block".to_string()));
+        }
+
+        let mut ephemerides: Vec<(Ephemeris, BTreeMap<DateTime<Utc>, StateVector>)> = Vec::new();
+        for segment in segments {
+            let same = |e: &Ephemeris| e.object_id == segment.object_id && e.object_name == segment.object_name;
+            let index = match ephemerides.iter().position(|(e, _)| same(e)) {
+                Some(index) => index,
+                None => {
+                    let ephemeris = Ephemeris {
+                        object_name: segment.object_name.clone(),
+                        object_id: segment.object_id.clone(),
+                        ref_frame: segment.ref_frame.clone(),
+                        states: Vec::new(),
+                    };
+                    ephemerides.push((ephemeris, BTreeMap::new()));
+                    ephemerides.len() - 1
+                }
+            };
+            ephemerides[index].1.extend(segment.states.into_iter().map(|s| (s.epoch, s)));
+        }
+        Ok(ephemerides
+            .into_iter()
+            .map(|(mut ephemeris, states)| {
+                ephemeris.states = states.into_values().collect();
+                ephemeris
+            })
+            .collect())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use chrono::TimeZone;
+        use orbital_mechanics::walker::MU_EARTH_KM3_S2;
+
+        fn transpose(m: &Matrix) -> Matrix {
+            std::array::from_fn(|i| std::array::from_fn(|j| m[j][i]))
+        }
+
+        fn apply(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+            std::array::from_fn(|i| m[i][0] * v[0] + m[i][1] * v[1] + m[i][2] * v[2])
+        }
+
+        fn line(epoch: &str, p: [f64; 3], v: [f64; 3]) -> String {
+            format!("{} {:.6} {:.6} {:.6} {:.9} {:.9} {:.9}\n", epoch, p[0], p[1], p[2], v[0], v[1], v[2])
+        }
+
+        /// Circular equatorial MEO orbit (TEME), as a function of seconds
+        fn orbit(s: f64) -> ([f64; 3], [f64; 3]) {
+            let (r, w) = (16_878.0, (MU_EARTH_KM3_S2 / 16_878f64.powi(3)).sqrt());
+            let (sin, cos) = (w * s).sin_cos();
+            ([r * cos, r * sin, 0.0], [-r * w * sin, r * w * cos, 0.0])
+        }
+
+        fn message(frame: &str, time_system: &str, m: &Matrix, offset_s: i64) -> String {
+            let start = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+            let mut text = format!(
+                "CCSDS_OEM_VERS = 2.0\nCREATION_DATE = 2026-01-03T12:00:00\nORIGINATOR = OTHER-OP\n\n\
+                 META_START\nOBJECT_NAME = MEO-7\nOBJECT_ID = 2025-042A\nCENTER_NAME = EARTH\n\
+                 REF_FRAME = {}\nTIME_SYSTEM = {}\nMETA_STOP\nCOMMENT predicted\n",
+                frame, time_system
+            );
+            for k in 0..=20 {
+                let (p, v) = orbit(k as f64 * 60.0);
+                let epoch = start + Duration::seconds(k * 60 + offset_s);
+                let inverse = transpose(m);
+                text += &line(&epoch.format("%Y-%m-%dT%H:%M:%S%.3f").to_string(), apply(&inverse, p), apply(&inverse, v));
+            }
+            text + "COVARIANCE_START\nEPOCH = 2026-01-04T00:00:00\n1.0\nCOVARIANCE_STOP\n"
+        }
+
+        #[test]
+        fn test_parse_and_interpolate() {
+            let start = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+            let identity = to_teme("TEME", start).unwrap();
+            let eme = transforms::j2000_to_teme_matrix(start + Duration::minutes(10));
+
+            for (frame, time_system, m, offset_s) in
+                [("TEME", "UTC", identity, 0), ("EME2000", "UTC", eme, 0), ("TEME", "TAI", identity, 37)]
+            {
+                let ephemerides = parse(&message(frame, time_system, &m, offset_s)).unwrap();
+                assert_eq!(ephemerides.len(), 1);
+                let e = &ephemerides[0];
+                assert_eq!((e.object_name.as_str(), e.object_id.as_str(), e.ref_frame.as_str()), ("MEO-7", "2025-042A", frame));
+                assert_eq!(e.states.len(), 21);
+                assert_eq!(e.start(), Some(start));
+
+                // Between nodes, against the analytic orbit
+                let s = 10.0 * 60.0 + 17.5;
+                let state = e.state_at(start + Duration::milliseconds((s * 1000.0) as i64)).unwrap();
+                let (p, _) = orbit(s);
+                let error = ((state.position_x - p[0]).powi(2) + (state.position_y - p[1]).powi(2)
+                    + (state.position_z - p[2]).powi(2))
+                .sqrt();
+                assert!(error < 0.001, "{} {}: {} km", frame, time_system, error);
+
+                assert!(e.state_at(start - Duration::seconds(1)).is_none());
+                assert!(e.state_at(e.stop().unwrap() + Duration::seconds(1)).is_none());
+                assert!(e.state_at(e.stop().unwrap()).is_some());
+            }
+        }
+
+        #[test]
+        fn test_segments_merge_and_reject() {
+            let identity = to_teme("TEME", Utc::now()).unwrap();
+            let text = message("TEME", "UTC", &identity, 0);
+            let twice = format!("{}\n{}", text, text.lines().skip(3).collect::<Vec<_>>().join("\n"));
+            let ephemerides = parse(&twice).unwrap();
+            assert_eq!((ephemerides.len(), ephemerides[0].states.len()), (1, 21));
+
+            let moon = text.replace("CENTER_NAME = EARTH", "CENTER_NAME = MOON");
+            assert!(matches!(parse(&moon), Err(CollisionError::InvalidEphemeris(_))));
+            assert!(parse(&text.replace("REF_FRAME = TEME", "REF_FRAME = ITRF")).is_err());
+            assert!(parse(&text.replace("TIME_SYSTEM = UTC", "TIME_SYSTEM = UT1")).is_err());
+            assert!(parse(&text.replace(" 0.000000000\n", "\n")).is_err());
+        }
+    }
+}
+
+pub mod constellation {
+    //! Intra-constellation screening
+    //!
+    //! External catalogs only learn of a manoeuvre once new elements are
+    //! published, so phasing errors after station keeping can bring two
+    //! satellites of the same shell together unflagged, most likely where
+    //! planes cross. Every pair is screened over the prediction horizon:
+    //! positions are sampled every `SAMPLE_STEP_S`, sampled range minima
+    //! that could hide an approach inside the screening radius (allowing
+    //! one step of relative motion) are refined to TCA by golden-section
+    //! search on SGP4, and approaches inside the radius become events.
+    //! Collision probability assumes an isotropic Gaussian miss of
+    //! `POSITION_SIGMA_KM` in the encounter plane and a hard-body radius
+    //! of `HARD_BODY_RADIUS_KM`.
+    //!
+    //! `screen_with_ephemerides` adds owner/operator ephemerides (`oem`):
+    //! refinement then interpolates the ephemeris instead of running SGP4
+    //! wherever one covers, and each event says which source it used.
+
+    use super::*;
+    use crate::oem::Ephemeris;
+    use orbital_mechanics::{Satellite, StateVector};
+
+    /// Coarse sampling step (s)
+    pub const SAMPLE_STEP_S: i64 = 60;
+    /// Combined 1σ position uncertainty of two owned satellites (km)
+    pub const POSITION_SIGMA_KM: f64 = 0.5;
+    /// Combined hard-body radius (km)
+    pub const HARD_BODY_RADIUS_KM: f64 = 0.01;
+
+    /// TCA refinement tolerance (s)
+    const TCA_TOLERANCE_S: f64 = 0.1;
+
+    /// Close approach between two satellites of one constellation
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SelfConjunction {
+        #[serde(flatten)]
+        pub event: ConjunctionEvent,
+        pub primary_plane: u8,
+        pub secondary_plane: u8,
+        /// Satellites in different orbital planes
+        pub cross_plane: bool,
+    }
+
+    fn range_km(a: &StateVector, b: &StateVector) -> f64 {
+        ((a.position_x - b.position_x).powi(2)
+            + (a.position_y - b.position_y).powi(2)
+            + (a.position_z - b.position_z).powi(2))
+        .sqrt()
+    }
+
+    fn relative_speed_km_s(a: &StateVector, b: &StateVector) -> f64 {
+        ((a.velocity_x - b.velocity_x).powi(2)
+            + (a.velocity_y - b.velocity_y).powi(2)
+            + (a.velocity_z - b.velocity_z).powi(2))
+        .sqrt()
+    }
+
+    /// Probability of a miss `miss_km` being a hit (small hard body)
+    pub fn collision_probability(miss_km: f64) -> f64 {
+        let sigma_sq = POSITION_SIGMA_KM * POSITION_SIGMA_KM;
+        (HARD_BODY_RADIUS_KM * HARD_BODY_RADIUS_KM / (2.0 * sigma_sq)) * (-miss_km * miss_km / (2.0 * sigma_sq)).exp()
+    }
+
+    /// Where a state used in screening came from
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum StateSource {
+        Tle,
+        Oem,
+    }
+
+    /// Close approach screened with owner/operator ephemerides
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct EphemerisConjunction {
+        #[serde(flatten)]
+        pub event: ConjunctionEvent,
+        /// States at TCA, per object
+        pub primary_source: StateSource,
+        pub secondary_source: StateSource,
+    }
+
+    /// A state and where it came from
+    type Sample = (StateVector, StateSource);
+
+    /// One screened object: a satellite whose TLE is overridden by an
+    /// ephemeris where one covers, or an ephemeris alone
+    struct Track<'a> {
+        id: &'a str,
+        tle: Option<&'a Satellite>,
+        oem: Option<&'a Ephemeris>,
+    }
+
+    impl Track<'_> {
+        fn state_at(&self, time: DateTime<Utc>) -> Option<Sample> {
+            if let Some(state) = self.oem.and_then(|e| e.state_at(time)) {
+                return Some((state, StateSource::Oem));
+            }
+            Some((self.tle?.propagate(time).ok()?, StateSource::Tle))
+        }
+    }
+
+    /// Close approach between `tracks[primary]` and `tracks[secondary]`
+    struct Approach {
+        primary: usize,
+        secondary: usize,
+        event: ConjunctionEvent,
+        sources: (StateSource, StateSource),
+    }
+
+    /// Time and states of closest approach between `from` and `to`
+    fn refine(
+        a: &Track,
+        b: &Track,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, Sample, Sample)> {
+        let at = |s: f64| from + Duration::microseconds((s * 1e6) as i64);
+        let range_at = |s: f64| -> Option<f64> { Some(range_km(&a.state_at(at(s))?.0, &b.state_at(at(s))?.0)) };
+
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let (mut lo, mut hi) = (0.0, (to - from).num_milliseconds() as f64 / 1000.0);
+        let mut x1 = hi - ratio * (hi - lo);
+        let mut x2 = lo + ratio * (hi - lo);
+        let (mut f1, mut f2) = (range_at(x1)?, range_at(x2)?);
+        while hi - lo > TCA_TOLERANCE_S {
+            if f1 < f2 {
+                hi = x2;
+                (x2, f2) = (x1, f1);
+                x1 = hi - ratio * (hi - lo);
+                f1 = range_at(x1)?;
+            } else {
+                lo = x1;
+                (x1, f1) = (x2, f2);
+                x2 = lo + ratio * (hi - lo);
+                f2 = range_at(x2)?;
+            }
+        }
+        let tca = at((lo + hi) / 2.0);
+        Some((tca, a.state_at(tca)?, b.state_at(tca)?))
+    }
+
+    impl CollisionAssessment {
+        /// Screen every pair of `satellites` against each other from
+        /// `epoch` over the prediction horizon, earliest TCA first.
+        /// Satellites that fail to propagate are left out.
+        pub fn screen_constellation(&self, satellites: &[Satellite], epoch: DateTime<Utc>) -> Vec<SelfConjunction> {
+            let tracks: Vec<Track> = satellites
+                .iter()
+                .map(|sat| Track {
+                    id: &sat.id,
+                    tle: Some(sat),
+                    oem: None,
+                })
+                .collect();
+            self.screen_tracks(&tracks, epoch)
+                .into_iter()
+                .map(|approach| {
+                    let (a, b) = (&satellites[approach.primary], &satellites[approach.secondary]);
+                    SelfConjunction {
+                        event: approach.event,
+                        primary_plane: a.plane,
+                        secondary_plane: b.plane,
+                        cross_plane: a.plane != b.plane,
+                    }
+                })
+                .collect()
+        }
+
+        /// As `screen_constellation`, with owner/operator `ephemerides`
+        /// alongside the TLEs. An ephemeris that describes one of the
+        /// `satellites` replaces its TLE within its span; the others are
+        /// screened as objects of their own, only where they cover.
+        pub fn screen_with_ephemerides(
+            &self,
+            satellites: &[Satellite],
+            ephemerides: &[Ephemeris],
+            epoch: DateTime<Utc>,
+        ) -> Vec<EphemerisConjunction> {
+            let mut tracks: Vec<Track> = satellites
+                .iter()
+                .map(|sat| Track {
+                    id: &sat.id,
+                    tle: Some(sat),
+                    oem: ephemerides.iter().find(|e| e.describes(sat)),
+                })
+                .collect();
+            tracks.extend(
+                ephemerides
+                    .iter()
+                    .filter(|e| !satellites.iter().any(|sat| e.describes(sat)))
+                    .map(|e| Track {
+                        id: if e.object_id.is_empty() { &e.object_name } else { &e.object_id },
+                        tle: None,
+                        oem: Some(e),
+                    }),
+            );
+            self.screen_tracks(&tracks, epoch)
+                .into_iter()
+                .map(|approach| EphemerisConjunction {
+                    event: approach.event,
+                    primary_source: approach.sources.0,
+                    secondary_source: approach.sources.1,
+                })
+                .collect()
+        }
+
+        fn screen_tracks(&self, tracks: &[Track], epoch: DateTime<Utc>) -> Vec<Approach> {
+            let steps = self.prediction_horizon_days * 86_400 / SAMPLE_STEP_S;
+            let times: Vec<DateTime<Utc>> = (0..=steps).map(|i| epoch + Duration::seconds(i * SAMPLE_STEP_S)).collect();
+            // Samples outside an ephemeris are gaps; a TLE that fails to
+            // propagate anywhere drops its track
+            let samples: Vec<(usize, Vec<Option<StateVector>>)> = tracks
+                .iter()
+                .enumerate()
+                .filter_map(|(i, track)| {
+                    let samples: Vec<_> = times.iter().map(|t| track.state_at(*t).map(|(s, _)| s)).collect();
+                    let failed = track.oem.is_none() && samples.iter().any(Option::is_none);
+                    (!failed).then_some((i, samples))
+                })
+                .collect();
+
+            let mut approaches = Vec::new();
+            for (n, (i, track_a)) in samples.iter().enumerate() {
+                for (j, track_b) in &samples[n + 1..] {
+                    let (a, b) = (&tracks[*i], &tracks[*j]);
+                    let ranges: Vec<f64> = track_a
+                        .iter()
+                        .zip(track_b)
+                        .map(|(p, q)| match (p, q) {
+                            (Some(p), Some(q)) => range_km(p, q),
+                            _ => f64::INFINITY,
+                        })
+                        .collect();
+                    for (k, &range) in ranges.iter().enumerate() {
+                        let before = if k > 0 { ranges[k - 1] } else { f64::INFINITY };
+                        let after = ranges.get(k + 1).copied().unwrap_or(f64::INFINITY);
+                        if range >= before || range > after {
+                            continue;
+                        }
+                        let (Some(p), Some(q)) = (&track_a[k], &track_b[k]) else {
+                            continue;
+                        };
+                        // Up to one step of relative motion may separate the
+                        // sample from the true minimum
+                        let slack = relative_speed_km_s(p, q) * SAMPLE_STEP_S as f64;
+                        if range.powi(2) > self.screening_radius_km.powi(2) + slack.powi(2) {
+                            continue;
+                        }
+
+                        let from = times[k.saturating_sub(1)];
+                        let to = times[(k + 1).min(times.len() - 1)];
+                        let Some((tca, (sa, source_a), (sb, source_b))) = refine(a, b, from, to) else {
+                            continue;
+                        };
+                        let miss_distance_km = range_km(&sa, &sb);
+                        if miss_distance_km > self.screening_radius_km {
+                            continue;
+                        }
+
+                        let mut event = ConjunctionEvent {
+                            id: format!("{}|{}|{}", a.id, b.id, tca.timestamp()),
+                            primary_object: a.id.to_string(),
+                            secondary_object: b.id.to_string(),
+                            tca,
+                            miss_distance_km,
+                            collision_probability: collision_probability(miss_distance_km),
+                            risk_level: RiskLevel::None,
+                            relative_velocity_km_s: relative_speed_km_s(&sa, &sb),
+                        };
+                        event.risk_level = self.assess_event(&event);
+                        approaches.push(Approach {
+                            primary: *i,
+                            secondary: *j,
+                            event,
+                            sources: (source_a, source_b),
+                        });
+                    }
+                }
+            }
+            approaches.sort_by_key(|a| a.event.tca);
+            approaches
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use chrono::TimeZone;
+        use orbital_mechanics::stationkeeping::MeanElements;
+        use orbital_mechanics::walker::WalkerDelta;
+
+        #[test]
+        fn test_walker_shell_is_clear() {
+            let epoch = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+            let sats = WalkerDelta::halo_constellation().generate_satellites("HALO", 60000, epoch);
+            assert!(CollisionAssessment::new(10.0, 1e-4, 1).screen_constellation(&sats, epoch).is_empty());
+        }
+
+        #[test]
+        fn test_plane_crossing_is_flagged() {
+            let epoch = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+            let mut sats = WalkerDelta::halo_constellation().generate_satellites("HALO", 60000, epoch);
+
+            // Same node and phase, 1° more inclined: the two meet at both nodes
+            let mut elements = MeanElements::from_satellite(&sats[0]).unwrap();
+            elements.inclination_deg += 1.0;
+            let mut intruder = sats[0].clone();
+            intruder.id = "HALO-X".to_string();
+            intruder.plane = 9;
+            (intruder.tle_line1, intruder.tle_line2) = elements.to_tle_lines(60099);
+            sats.push(intruder);
+
+            let events = CollisionAssessment::new(10.0, 1e-4, 1).screen_constellation(&sats, epoch);
+            assert!(events.len() >= 6, "{} events", events.len());
+            // Phase drifts apart slowly under J2, but they start at the node
+            assert!(events[0].event.miss_distance_km < 1.0);
+            assert!(events.windows(2).all(|w| w[0].event.tca <= w[1].event.tca));
+            for e in &events {
+                assert_eq!((e.event.primary_object.as_str(), e.event.secondary_object.as_str()), ("HALO-01", "HALO-X"));
+                assert!(e.cross_plane && e.event.miss_distance_km < 10.0);
+                assert!(e.event.relative_velocity_km_s > 0.05 && e.event.relative_velocity_km_s < 0.2);
+            }
+            assert_ne!(events[0].event.risk_level, RiskLevel::None);
+        }
+
+        #[test]
+        fn test_operator_ephemeris_is_screened() {
+            let epoch = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+            let sats = WalkerDelta::halo_constellation().generate_satellites("HALO", 60000, epoch);
+
+            // The intruder of `test_plane_crossing_is_flagged`, known only
+            // through its operator's ephemeris, for the first 12 hours
+            let mut elements = MeanElements::from_satellite(&sats[0]).unwrap();
+            elements.inclination_deg += 1.0;
+            let mut intruder = sats[0].clone();
+            (intruder.tle_line1, intruder.tle_line2) = elements.to_tle_lines(60099);
+            let mut text = String::from(
+                "CCSDS_OEM_VERS = 2.0\nMETA_START\nOBJECT_NAME = OTHER-1\nOBJECT_ID = 2025-042A\n\
+                 CENTER_NAME = EARTH\nREF_FRAME = TEME\nTIME_SYSTEM = UTC\nMETA_STOP\n",
+            );
+            for k in 0..=12 * 60 {
+                let s = intruder.propagate(epoch + Duration::minutes(k)).unwrap();
+                text += &format!(
+                    "{} {} {} {} {} {} {}\n",
+                    s.epoch.format("%Y-%m-%dT%H:%M:%S"),
+                    s.position_x,
+                    s.position_y,
+                    s.position_z,
+                    s.velocity_x,
+                    s.velocity_y,
+                    s.velocity_z
+                );
+            }
+            let ephemerides = crate::oem::parse(&text).unwrap();
+
+            let assessment = CollisionAssessment::new(10.0, 1e-4, 1);
+            let events = assessment.screen_with_ephemerides(&sats, &ephemerides, epoch);
+            assert!(events.len() >= 3, "{} events", events.len());
+            let coverage = ephemerides[0].stop().unwrap();
+            for e in &events {
+                assert_eq!((e.event.primary_object.as_str(), e.event.secondary_object.as_str()), ("HALO-01", "2025-042A"));
+                assert_eq!((e.primary_source, e.secondary_source), (StateSource::Tle, StateSource::Oem));
+                assert!(e.event.tca <= coverage);
+            }
+            assert!(events[0].event.miss_distance_km < 1.0);
+
+            // An ephemeris of an owned satellite stands in for its TLE
+            let mut owned = ephemerides[0].clone();
+            owned.object_id = "HALO-01".to_string();
+            let events = assessment.screen_with_ephemerides(&sats, &[owned], epoch);
+            assert!(events.is_empty(), "{} events", events.len());
+        }
+    }
+}
+
 pub mod ctas {
     //! UCLA CTAS Integration
     //!
@@ -192,3 +936,37 @@ pub mod ctas {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use orbital_mechanics::walker::WalkerDelta;
+
+    #[test]
+    fn test_maneuver_plan_applies_to_elements() {
+        let epoch = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+        let sat = &WalkerDelta::halo_constellation().generate_satellites("HALO", 60000, epoch)[0];
+        let plan = ManeuverPlan {
+            event_id: "CONJ-1".to_string(),
+            maneuver_type: ManeuverType::InTrack,
+            delta_v_x: 0.0,
+            delta_v_y: 1e-3,
+            delta_v_z: 0.0,
+            execution_time: epoch + Duration::hours(2),
+            new_miss_distance_km: 20.0,
+            fuel_cost_kg: 0.1,
+        };
+        let burned = plan.apply(sat).unwrap();
+        assert_eq!((burned.id.as_str(), burned.norad_id), (sat.id.as_str(), sat.norad_id));
+        let elements = MeanElements::from_satellite(&burned).unwrap();
+        // TLE epochs are kept to 10⁻⁸ day
+        assert!((elements.epoch - plan.execution_time).num_milliseconds().abs() <= 1);
+
+        // Re-propagated, the burned satellite falls behind its old track
+        let later = plan.execution_time + Duration::days(1);
+        let (old, new) = (sat.propagate(later).unwrap(), burned.propagate(later).unwrap());
+        let hill = orbital_mechanics::relative::hill_state(&old, &new).unwrap();
+        assert!(hill.along_track_km < -100.0, "{:?}", hill);
+    }
+}
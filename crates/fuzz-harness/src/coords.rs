@@ -0,0 +1,222 @@
+//! Geodetic and ECI coordinate strategies, with the invariants the
+//! transforms module must keep
+//!
+//! The position generators are Earth-fixed, which is the frame of
+//! `ecef_to_geodetic` and `geodetic_to_eci`; states are inertial and go
+//! through `StateVector::to_geodetic`. Checks return `Err` with a description
+//! rather than panicking, so they work inside `prop_assert!` and in plain
+//! tests alike.
+
+use crate::elements::{epoch, EARTH_RADIUS_KM};
+use orbital_mechanics::transforms::{ecef_to_geodetic, geodetic_to_eci};
+use orbital_mechanics::walker::MU_EARTH_KM3_S2;
+use orbital_mechanics::{GeodeticPosition, StateVector};
+use proptest::prelude::*;
+
+/// Round-trip bounds: about 0.1 mm on the ground and 1 mm in altitude
+pub const ROUND_TRIP_DEG: f64 = 1e-9;
+pub const ROUND_TRIP_KM: f64 = 1e-6;
+
+/// Beyond lunar distance
+pub const MAX_ALTITUDE_KM: f64 = 400_000.0;
+
+fn position(latitude: f64, longitude: f64, altitude_km: f64) -> GeodeticPosition {
+    GeodeticPosition {
+        latitude,
+        longitude,
+        altitude_km,
+    }
+}
+
+/// Anywhere from just below the ellipsoid to beyond GEO
+pub fn geodetic() -> impl Strategy<Value = GeodeticPosition> {
+    (-90.0..=90.0f64, -180.0..=180.0f64, prop_oneof![-0.5..10.0f64, 10.0..MAX_ALTITUDE_KM])
+        .prop_map(|(lat, lon, alt)| position(lat, lon, alt))
+}
+
+/// On or within a microdegree of either pole
+pub fn polar_geodetic() -> impl Strategy<Value = GeodeticPosition> {
+    (prop_oneof![Just(90.0), Just(-90.0), 89.999999..90.0f64, -90.0..-89.999999f64], -180.0..=180.0f64, -0.5..50_000.0f64)
+        .prop_map(|(lat, lon, alt)| position(lat, lon, alt))
+}
+
+/// Either side of ±180° longitude
+pub fn antimeridian_geodetic() -> impl Strategy<Value = GeodeticPosition> {
+    let lon = prop_oneof![Just(180.0), Just(-180.0), 179.999999..=180.0f64, -180.0..-179.999999f64];
+    (-90.0..=90.0f64, lon, -0.5..50_000.0f64).prop_map(|(lat, lon, alt)| position(lat, lon, alt))
+}
+
+/// GEO and beyond
+pub fn high_altitude_geodetic() -> impl Strategy<Value = GeodeticPosition> {
+    (-90.0..=90.0f64, -180.0..=180.0f64, 35_786.0..MAX_ALTITUDE_KM).prop_map(|(lat, lon, alt)| position(lat, lon, alt))
+}
+
+/// Mix of the above, weighted towards the edge cases
+pub fn any_geodetic() -> impl Strategy<Value = GeodeticPosition> {
+    prop_oneof![
+        2 => geodetic(),
+        1 => polar_geodetic(),
+        1 => antimeridian_geodetic(),
+        1 => high_altitude_geodetic(),
+    ]
+}
+
+/// Positions the transforms must reject: latitude past a pole or a
+/// non-finite field
+pub fn invalid_geodetic() -> impl Strategy<Value = GeodeticPosition> {
+    prop_oneof![
+        (90.000001..1000.0f64, any::<bool>()).prop_map(|(lat, south)| position(if south { -lat } else { lat }, 0.0, 0.0)),
+        Just(position(f64::NAN, 0.0, 0.0)),
+        Just(position(0.0, f64::INFINITY, 0.0)),
+        Just(position(0.0, 0.0, f64::NAN)),
+    ]
+}
+
+/// Unit vector, uniform on the sphere plus the coordinate axes
+pub fn direction() -> impl Strategy<Value = [f64; 3]> {
+    prop_oneof![
+        4 => (-1.0..=1.0f64, -std::f64::consts::PI..std::f64::consts::PI).prop_map(|(z, phi)| {
+            let r = (1.0 - z * z).sqrt();
+            [r * phi.cos(), r * phi.sin(), z]
+        }),
+        1 => proptest::sample::select(vec![
+            [1.0, 0.0, 0.0],
+            [-1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, -1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, -1.0],
+        ]),
+    ]
+}
+
+/// ECI (equivalently ECEF) position above 100 km, km
+pub fn eci_position() -> impl Strategy<Value = (f64, f64, f64)> {
+    (direction(), EARTH_RADIUS_KM + 100.0..EARTH_RADIUS_KM + MAX_ALTITUDE_KM)
+        .prop_map(|(u, r)| (u[0] * r, u[1] * r, u[2] * r))
+}
+
+/// Bound orbital states: position as `eci_position`, speed between half
+/// and just under escape speed in any direction
+pub fn eci_state() -> impl Strategy<Value = StateVector> {
+    (eci_position(), direction(), 0.5..1.41f64, epoch()).prop_map(|((x, y, z), v, factor, epoch)| {
+        let r = (x * x + y * y + z * z).sqrt();
+        let speed = (MU_EARTH_KM3_S2 / r).sqrt() * factor;
+        StateVector {
+            position_x: x,
+            position_y: y,
+            position_z: z,
+            velocity_x: v[0] * speed,
+            velocity_y: v[1] * speed,
+            velocity_z: v[2] * speed,
+            epoch,
+        }
+    })
+}
+
+/// Longitude difference wrapped to ±180°, so 180 and -180 agree
+pub fn longitude_difference(a: f64, b: f64) -> f64 {
+    (a - b + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Geodetic -> ECI -> geodetic within `ROUND_TRIP_*`. Longitude is
+/// ignored at the poles, where it is undefined.
+pub fn check_geodetic_round_trip(pos: &GeodeticPosition) -> Result<(), String> {
+    let (x, y, z) = geodetic_to_eci(pos).map_err(|e| e.to_string())?;
+    let back = ecef_to_geodetic(x, y, z).map_err(|e| e.to_string())?;
+
+    if (back.latitude - pos.latitude).abs() > ROUND_TRIP_DEG {
+        return Err(format!("latitude {} -> {}", pos.latitude, back.latitude));
+    }
+    if 90.0 - pos.latitude.abs() > ROUND_TRIP_DEG
+        && longitude_difference(back.longitude, pos.longitude).abs() > ROUND_TRIP_DEG
+    {
+        return Err(format!("longitude {} -> {}", pos.longitude, back.longitude));
+    }
+    if (back.altitude_km - pos.altitude_km).abs() > ROUND_TRIP_KM {
+        return Err(format!("altitude {} -> {} km", pos.altitude_km, back.altitude_km));
+    }
+    check_geodetic_ranges(&back)
+}
+
+/// ECI -> geodetic -> ECI within `ROUND_TRIP_KM` on each axis
+pub fn check_eci_round_trip(x: f64, y: f64, z: f64) -> Result<(), String> {
+    let geo = ecef_to_geodetic(x, y, z).map_err(|e| e.to_string())?;
+    check_geodetic_ranges(&geo)?;
+    let (bx, by, bz) = geodetic_to_eci(&geo).map_err(|e| e.to_string())?;
+    let error = ((bx - x).powi(2) + (by - y).powi(2) + (bz - z).powi(2)).sqrt();
+    if error > ROUND_TRIP_KM {
+        return Err(format!("({}, {}, {}) came back {} km off", x, y, z, error));
+    }
+    Ok(())
+}
+
+/// Latitude within ±90°, longitude within ±180°, everything finite
+pub fn check_geodetic_ranges(pos: &GeodeticPosition) -> Result<(), String> {
+    if !(pos.latitude.abs() <= 90.0 && pos.longitude.abs() <= 180.0 && pos.altitude_km.is_finite()) {
+        return Err(format!("out of range: {:?}", pos));
+    }
+    Ok(())
+}
+
+/// State is finite and above the ellipsoid
+pub fn check_altitude_positive(state: &StateVector) -> Result<(), String> {
+    let values = [
+        state.position_x,
+        state.position_y,
+        state.position_z,
+        state.velocity_x,
+        state.velocity_y,
+        state.velocity_z,
+    ];
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(format!("non-finite state: {:?}", values));
+    }
+    let geo = state.to_geodetic().map_err(|e| e.to_string())?;
+    if geo.altitude_km <= 0.0 {
+        return Err(format!("altitude {} km at {}", geo.altitude_km, state.epoch));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(512))]
+
+        #[test]
+        fn test_geodetic_round_trip(pos in any_geodetic()) {
+            prop_assert_eq!(check_geodetic_round_trip(&pos), Ok(()));
+        }
+
+        #[test]
+        fn test_eci_round_trip((x, y, z) in eci_position()) {
+            prop_assert_eq!(check_eci_round_trip(x, y, z), Ok(()));
+        }
+
+        #[test]
+        fn test_generated_states_are_above_ground(state in eci_state()) {
+            prop_assert_eq!(check_altitude_positive(&state), Ok(()));
+        }
+
+        #[test]
+        fn test_invalid_positions_are_rejected(pos in invalid_geodetic()) {
+            prop_assert!(geodetic_to_eci(&pos).is_err());
+        }
+    }
+
+    #[test]
+    fn test_origin_and_non_finite_eci_are_rejected() {
+        assert!(ecef_to_geodetic(0.0, 0.0, 0.0).is_err());
+        assert!(ecef_to_geodetic(f64::NAN, 1.0, 1.0).is_err());
+        assert!(ecef_to_geodetic(7000.0, 0.0, 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_longitude_difference_wraps() {
+        assert_eq!(longitude_difference(180.0, -180.0), 0.0);
+        assert!((longitude_difference(-179.5, 179.5) - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,222 @@
+//! Failure corpus
+//!
+//! Every shrunk failure is kept as a `CorpusEntry`: the target, the seed
+//! that found it and the input as JSON, one file per entry under
+//! `<root>/<target>/<seed>.json`. Replaying feeds each stored input
+//! straight to its target's check, with no generation or shrinking, so the
+//! corpus runs as a deterministic regression suite before a campaign
+//! starts fuzzing. Entries stay after they pass; a fixed bug stays fixed.
+
+use crate::reports::{Outcome, TargetReport};
+use crate::targets;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Checked-in corpus replayed by `cargo test`, relative to the crate root
+pub const CHECKED_IN: &str = "corpus";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorpusEntry {
+    pub target: String,
+    pub seed: u64,
+    pub invariant: String,
+    pub counterexample: String,
+    pub input: serde_json::Value,
+    pub found_at: DateTime<Utc>,
+}
+
+impl CorpusEntry {
+    /// Entry for a failed target run whose input serialised
+    pub fn from_report(report: &TargetReport) -> Option<Self> {
+        let Outcome::Failed {
+            invariant,
+            counterexample,
+            input: Some(input),
+        } = &report.outcome
+        else {
+            return None;
+        };
+        Some(Self {
+            target: report.target.clone(),
+            seed: report.seed,
+            invariant: invariant.clone(),
+            counterexample: counterexample.clone(),
+            input: input.clone(),
+            found_at: Utc::now(),
+        })
+    }
+
+    /// Path relative to the corpus root
+    pub fn file_name(&self) -> String {
+        format!("{}/{:016x}.json", self.target, self.seed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayOutcome {
+    Passed,
+    Failed(String),
+    /// Target renamed or removed since the entry was stored
+    UnknownTarget,
+}
+
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub entry: CorpusEntry,
+    pub outcome: ReplayOutcome,
+}
+
+impl Replay {
+    pub fn failed(&self) -> bool {
+        matches!(self.outcome, ReplayOutcome::Failed(_))
+    }
+}
+
+pub fn replay(entry: &CorpusEntry) -> ReplayOutcome {
+    match targets::find(&entry.target) {
+        Some(target) => match target.replay(&entry.input) {
+            Ok(()) => ReplayOutcome::Passed,
+            Err(message) => ReplayOutcome::Failed(message),
+        },
+        None => ReplayOutcome::UnknownTarget,
+    }
+}
+
+pub struct Corpus {
+    root: PathBuf,
+}
+
+impl Corpus {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn add(&self, entry: &CorpusEntry) -> io::Result<PathBuf> {
+        let path = self.root.join(entry.file_name());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(entry)?)?;
+        Ok(path)
+    }
+
+    /// Add every failure in `reports` that carries an input; returns how
+    /// many were added
+    pub fn add_failures<'a>(&self, reports: impl IntoIterator<Item = &'a TargetReport>) -> io::Result<usize> {
+        let mut added = 0;
+        for entry in reports.into_iter().filter_map(CorpusEntry::from_report) {
+            self.add(&entry)?;
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    /// All entries, sorted by target then seed; an absent root is empty
+    pub fn entries(&self) -> io::Result<Vec<CorpusEntry>> {
+        let mut entries = Vec::new();
+        collect(&self.root, &mut entries)?;
+        entries.sort_by(|a, b| a.target.cmp(&b.target).then(a.seed.cmp(&b.seed)));
+        Ok(entries)
+    }
+
+    pub fn replay(&self) -> io::Result<Vec<Replay>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .map(|entry| {
+                let outcome = replay(&entry);
+                Replay { entry, outcome }
+            })
+            .collect())
+    }
+}
+
+fn collect(dir: &Path, entries: &mut Vec<CorpusEntry>) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for item in std::fs::read_dir(dir)? {
+        let path = item?.path();
+        if path.is_dir() {
+            collect(&path, entries)?;
+        } else if path.extension().is_some_and(|e| e == "json") {
+            let text = std::fs::read_to_string(&path)?;
+            let entry = serde_json::from_str(&text)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))?;
+            entries.push(entry);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reports::run_target;
+    use orbital_mechanics::GeodeticPosition;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fuzz-corpus-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn entry(target: &str, input: serde_json::Value) -> CorpusEntry {
+        CorpusEntry {
+            target: target.to_string(),
+            seed: 9,
+            invariant: "stored".to_string(),
+            counterexample: String::new(),
+            input,
+            found_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_failures_round_trip_through_the_corpus() {
+        let corpus = Corpus::new(scratch("round-trip"));
+        let report = run_target("demo/below-500", 3, 64, 0u32..10_000, |x| {
+            if *x < 500 {
+                Ok(())
+            } else {
+                Err("too big".to_string())
+            }
+        });
+        assert_eq!(corpus.add_failures([&report]).unwrap(), 1);
+
+        let entries = corpus.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].input, serde_json::json!(500));
+        assert!(corpus.root().join("demo/below-500/0000000000000003.json").exists());
+        // Not a registered target, so replay can't run it
+        assert_eq!(replay(&entries[0]), ReplayOutcome::UnknownTarget);
+        std::fs::remove_dir_all(corpus.root()).unwrap();
+    }
+
+    #[test]
+    fn test_replay_runs_the_target_check() {
+        let passing = GeodeticPosition {
+            latitude: 10.0,
+            longitude: 20.0,
+            altitude_km: 500.0,
+        };
+        let entry_ok = entry("coords/geodetic-round-trip", serde_json::to_value(passing).unwrap());
+        assert_eq!(replay(&entry_ok), ReplayOutcome::Passed);
+
+        let bad_input = entry("coords/geodetic-round-trip", serde_json::json!({"latitude": "north"}));
+        assert!(matches!(replay(&bad_input), ReplayOutcome::Failed(_)));
+    }
+
+    #[test]
+    fn test_checked_in_corpus_replays() {
+        let corpus = Corpus::new(Path::new(env!("CARGO_MANIFEST_DIR")).join(CHECKED_IN));
+        let failures: Vec<Replay> = corpus.replay().unwrap().into_iter().filter(|r| r.outcome != ReplayOutcome::Passed).collect();
+        assert!(failures.is_empty(), "{:#?}", failures);
+    }
+}
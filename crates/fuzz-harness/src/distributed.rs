@@ -0,0 +1,333 @@
+//! Distributed fuzz campaigns
+//!
+//! A campaign runs the selected targets with `total_cases` split across
+//! shards. Each shard derives its own seed per target from the campaign
+//! seed, so shards explore different inputs and any one can be replayed
+//! alone. Shard reports and corpus entries for failures go to a `Store`
+//! (a local directory, or a GCS bucket through the `gcloud` CLI); the
+//! reports are merged into one at the end and the corpus pulled for
+//! replay.
+//!
+//! On GCP a shard is one task of a Cloud Run or Batch job; `Shard::from_env`
+//! reads the task index and count those set.
+
+use crate::corpus::CorpusEntry;
+use crate::reports::FuzzReport;
+use crate::targets;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Task index/count variables: Cloud Run jobs, then Batch
+const TASK_ENV: [(&str, &str); 2] = [
+    ("CLOUD_RUN_TASK_INDEX", "CLOUD_RUN_TASK_COUNT"),
+    ("BATCH_TASK_INDEX", "BATCH_TASK_COUNT"),
+];
+
+pub const CORPUS_DIR: &str = "corpus";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Campaign {
+    pub seed: u64,
+    /// Cases per target, summed over all shards
+    pub total_cases: u64,
+    /// Target name prefixes; empty runs every target
+    pub targets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    pub index: u32,
+    pub count: u32,
+}
+
+impl Shard {
+    pub fn new(index: u32, count: u32) -> Option<Self> {
+        (index < count).then_some(Self { index, count })
+    }
+
+    /// Shard from the Cloud Run or Batch task environment
+    pub fn from_env() -> Option<Self> {
+        TASK_ENV.iter().find_map(|(index, count)| {
+            let index = std::env::var(index).ok()?.parse().ok()?;
+            let count = std::env::var(count).ok()?.parse().ok()?;
+            Self::new(index, count)
+        })
+    }
+
+    /// This shard's part of `total` cases; the first `total % count`
+    /// shards take one extra
+    pub fn cases(&self, total: u64) -> u32 {
+        let count = self.count as u64;
+        let extra = (self.index as u64) < total % count;
+        (total / count + extra as u64).min(u32::MAX as u64) as u32
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("shard-{:05}.json", self.index)
+    }
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// FNV-1a: stable across builds and platforms, unlike `DefaultHasher`
+fn fnv1a(text: &str) -> u64 {
+    text.bytes()
+        .fold(0xCBF2_9CE4_8422_2325, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01B3))
+}
+
+/// Seed for one target on one shard
+pub fn shard_seed(campaign_seed: u64, target: &str, shard_index: u32) -> u64 {
+    splitmix64(campaign_seed ^ fnv1a(target) ^ splitmix64(shard_index as u64))
+}
+
+pub fn run_shard(campaign: &Campaign, shard: Shard) -> FuzzReport {
+    let cases = shard.cases(campaign.total_cases);
+    let mut report = FuzzReport::new();
+    if cases == 0 {
+        return report;
+    }
+    for target in targets::matching(&campaign.targets) {
+        tracing::info!("shard {}/{}: {} x{}", shard.index, shard.count, target.name, cases);
+        report.push(target.run(shard_seed(campaign.seed, target.name, shard.index), cases));
+    }
+    report
+}
+
+/// One report for the whole campaign, targets sorted by name then seed
+pub fn merge(reports: impl IntoIterator<Item = FuzzReport>) -> FuzzReport {
+    let mut merged = FuzzReport::new();
+    for report in reports {
+        merged.targets.extend(report.targets);
+    }
+    merged.targets.sort_by(|a, b| a.target.cmp(&b.target).then(a.seed.cmp(&b.seed)));
+    merged
+}
+
+/// Where shard reports and corpora are collected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Store {
+    Local(PathBuf),
+    /// `gs://bucket/prefix`, accessed with `gcloud storage`
+    Gcs(String),
+}
+
+impl Store {
+    pub fn parse(location: &str) -> Self {
+        if location.starts_with("gs://") {
+            Store::Gcs(location.trim_end_matches('/').to_string())
+        } else {
+            Store::Local(PathBuf::from(location))
+        }
+    }
+
+    pub fn put(&self, name: &str, contents: &str) -> io::Result<()> {
+        match self {
+            Store::Local(dir) => {
+                let path = dir.join(name);
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, contents)
+            }
+            Store::Gcs(url) => {
+                let staging = scratch_dir()?;
+                let file = staging.join("upload");
+                std::fs::write(&file, contents)?;
+                let result = gcloud(&["storage", "cp", path_str(&file)?, &format!("{}/{}", url, name)]);
+                let _ = std::fs::remove_dir_all(&staging);
+                result
+            }
+        }
+    }
+
+    /// Write a shard's report and a corpus entry per failure
+    pub fn put_shard(&self, shard: Shard, report: &FuzzReport) -> io::Result<()> {
+        for entry in report.targets.iter().filter_map(CorpusEntry::from_report) {
+            let json = serde_json::to_string_pretty(&entry)?;
+            self.put(&format!("{}/{}", CORPUS_DIR, entry.file_name()), &json)?;
+        }
+        self.put(&shard.file_name(), &report.to_json()?)
+    }
+
+    /// Copy the stored corpus into `dest` (which then holds target
+    /// directories directly), keeping entries already there
+    pub fn pull_corpus(&self, dest: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(dest)?;
+        match self {
+            Store::Local(dir) => copy_tree(&dir.join(CORPUS_DIR), dest),
+            Store::Gcs(url) => {
+                let staging = scratch_dir()?;
+                // No corpus yet is not an error: nothing has failed
+                let _ = gcloud(&["storage", "cp", "-r", &format!("{}/{}", url, CORPUS_DIR), path_str(&staging)?]);
+                let result = copy_tree(&staging.join(CORPUS_DIR), dest);
+                let _ = std::fs::remove_dir_all(&staging);
+                result
+            }
+        }
+    }
+
+    /// Shard reports present, keyed by shard index
+    pub fn shard_reports(&self) -> io::Result<Vec<(u32, FuzzReport)>> {
+        match self {
+            Store::Local(dir) => read_shard_reports(dir),
+            Store::Gcs(url) => {
+                let staging = scratch_dir()?;
+                // No matches is not an error: nothing has finished yet
+                let _ = gcloud(&["storage", "cp", &format!("{}/shard-*.json", url), path_str(&staging)?]);
+                let reports = read_shard_reports(&staging);
+                let _ = std::fs::remove_dir_all(&staging);
+                reports
+            }
+        }
+    }
+}
+
+fn read_shard_reports(dir: &Path) -> io::Result<Vec<(u32, FuzzReport)>> {
+    let mut reports = Vec::new();
+    if !dir.exists() {
+        return Ok(reports);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(index) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix("shard-")?.strip_suffix(".json")?.parse().ok())
+        else {
+            continue;
+        };
+        let report = FuzzReport::from_json(&std::fs::read_to_string(&path)?)?;
+        reports.push((index, report));
+    }
+    reports.sort_by_key(|(index, _)| *index);
+    Ok(reports)
+}
+
+fn copy_tree(from: &Path, to: &Path) -> io::Result<()> {
+    if !from.is_dir() || from == to {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(from)? {
+        let path = entry?.path();
+        let target = to.join(path.file_name().unwrap_or_default());
+        if path.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            copy_tree(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Shard indices below `count` with no report
+pub fn missing_shards(reports: &[(u32, FuzzReport)], count: u32) -> Vec<u32> {
+    (0..count).filter(|i| !reports.iter().any(|(index, _)| index == i)).collect()
+}
+
+fn scratch_dir() -> io::Result<PathBuf> {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("fuzz-harness-{}-{}", std::process::id(), nanos));
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn path_str(path: &Path) -> io::Result<&str> {
+    path.to_str()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("non-UTF-8 path {:?}", path)))
+}
+
+fn gcloud(args: &[&str]) -> io::Result<()> {
+    let status = Command::new("gcloud").args(args).arg("--quiet").status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("gcloud {} exited with {}", args.join(" "), status)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campaign(total_cases: u64) -> Campaign {
+        Campaign {
+            seed: 7,
+            total_cases,
+            targets: vec!["coords/".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_cases_split_across_shards() {
+        for count in 1..=7 {
+            let total: u64 = (0..count).map(|i| Shard::new(i, count).unwrap().cases(100) as u64).sum();
+            assert_eq!(total, 100);
+        }
+        assert!(Shard::new(3, 3).is_none());
+        assert_eq!(Shard::new(4, 5).unwrap().cases(3), 0);
+    }
+
+    #[test]
+    fn test_shard_seeds_are_distinct_and_stable() {
+        let seeds: std::collections::HashSet<u64> = (0..64)
+            .flat_map(|i| ["a", "b"].map(|t| shard_seed(1, t, i)))
+            .collect();
+        assert_eq!(seeds.len(), 128);
+        assert_eq!(shard_seed(1, "a", 0), shard_seed(1, "a", 0));
+        assert_ne!(shard_seed(1, "a", 0), shard_seed(2, "a", 0));
+    }
+
+    #[test]
+    fn test_local_store_collects_and_merges_shards() {
+        let dir = scratch_dir().unwrap();
+        let store = Store::parse(dir.to_str().unwrap());
+        let campaign = campaign(10);
+
+        for index in [0, 2] {
+            let shard = Shard::new(index, 3).unwrap();
+            store.put_shard(shard, &run_shard(&campaign, shard)).unwrap();
+        }
+        let reports = store.shard_reports().unwrap();
+        assert_eq!(missing_shards(&reports, 3), vec![1]);
+
+        let merged = merge(reports.into_iter().map(|(_, r)| r));
+        let expected = targets::matching(&campaign.targets).len();
+        assert_eq!(merged.targets.len(), 2 * expected);
+        assert!(merged.passed());
+        assert!(merged.targets.windows(2).all(|w| w[0].target <= w[1].target));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_failures_reach_the_pulled_corpus() {
+        let dir = scratch_dir().unwrap();
+        let store = Store::Local(dir.join("store"));
+        let mut report = FuzzReport::new();
+        report.push(crate::reports::run_target("demo/fails", 5, 16, 0u32..10, |_| Err("always".to_string())));
+        store.put_shard(Shard::new(0, 1).unwrap(), &report).unwrap();
+
+        let corpus = crate::corpus::Corpus::new(dir.join("pulled"));
+        store.pull_corpus(corpus.root()).unwrap();
+        let entries = corpus.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].target.as_str(), entries[0].seed), ("demo/fails", 5));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_store_parse() {
+        assert_eq!(Store::parse("gs://fuzz/run-1/"), Store::Gcs("gs://fuzz/run-1".to_string()));
+        assert_eq!(Store::parse("target/fuzz"), Store::Local(PathBuf::from("target/fuzz")));
+    }
+}
@@ -0,0 +1,236 @@
+//! Orbital element and Walker Delta strategies
+//!
+//! Elements are `stationkeeping::MeanElements`, the crate's TLE-equivalent
+//! element set. Every valid set keeps perigee above `MIN_PERIGEE_KM` and
+//! its epoch inside the two-digit TLE year window, so it must format,
+//! parse and initialise SGP4.
+
+use chrono::{DateTime, TimeZone, Utc};
+use orbital_mechanics::stationkeeping::MeanElements;
+use orbital_mechanics::walker::{DragTerms, WalkerDelta, MU_EARTH_KM3_S2};
+use proptest::prelude::*;
+
+pub const EARTH_RADIUS_KM: f64 = 6378.137;
+
+/// Lowest perigee altitude generated (km)
+pub const MIN_PERIGEE_KM: f64 = 200.0;
+
+/// Mean motion bounds (rev/day): from beyond GEO to very low LEO
+pub const MIN_MEAN_MOTION: f64 = 0.9;
+pub const MAX_MEAN_MOTION: f64 = 16.0;
+
+/// Semi-major axis (km) for a mean motion in rev/day
+pub fn semi_major_axis_km(mean_motion_rev_day: f64) -> f64 {
+    let n_rad_s = mean_motion_rev_day * 2.0 * std::f64::consts::PI / 86400.0;
+    (MU_EARTH_KM3_S2 / (n_rad_s * n_rad_s)).cbrt()
+}
+
+/// Largest eccentricity that keeps perigee above `MIN_PERIGEE_KM`
+pub fn max_eccentricity(mean_motion_rev_day: f64) -> f64 {
+    (1.0 - (EARTH_RADIUS_KM + MIN_PERIGEE_KM) / semi_major_axis_km(mean_motion_rev_day)).clamp(0.0, 0.95)
+}
+
+/// Epochs 1990-2049 at millisecond resolution (TLE years 57-99 are 19xx)
+pub fn epoch() -> impl Strategy<Value = DateTime<Utc>> {
+    let start = Utc.with_ymd_and_hms(1990, 1, 1, 0, 0, 0).unwrap().timestamp_millis();
+    let end = Utc.with_ymd_and_hms(2049, 12, 31, 23, 59, 59).unwrap().timestamp_millis();
+    (start..end).prop_map(|ms| Utc.timestamp_millis_opt(ms).unwrap())
+}
+
+/// Line 1 drag terms: none, or realistic magnitudes of either sign
+pub fn drag_terms() -> impl Strategy<Value = DragTerms> {
+    prop_oneof![
+        Just(DragTerms::default()),
+        (-1e-4..1e-4f64, -1e-9..1e-9f64, -5e-3..5e-3f64).prop_map(|(ndot_over_2, nddot_over_6, bstar)| {
+            DragTerms {
+                ndot_over_2,
+                nddot_over_6,
+                bstar,
+            }
+        }),
+    ]
+}
+
+fn angle() -> impl Strategy<Value = f64> {
+    0.0..360.0f64
+}
+
+/// Valid mean elements anywhere from LEO to beyond GEO, near-circular or
+/// eccentric
+pub fn mean_elements() -> impl Strategy<Value = MeanElements> {
+    (MIN_MEAN_MOTION..MAX_MEAN_MOTION)
+        .prop_flat_map(|n| (Just(n), 0.0..=max_eccentricity(n)))
+        .prop_flat_map(|(n, e)| {
+            (epoch(), 0.0..=180.0f64, angle(), angle(), angle(), drag_terms()).prop_map(
+                move |(epoch, inclination_deg, raan_deg, arg_perigee_deg, mean_anomaly_deg, drag)| MeanElements {
+                    epoch,
+                    inclination_deg,
+                    raan_deg,
+                    eccentricity: e,
+                    arg_perigee_deg,
+                    mean_anomaly_deg,
+                    mean_motion_rev_day: n,
+                    drag,
+                },
+            )
+        })
+}
+
+/// Circular elements in one regime, as the Walker generator produces
+pub fn circular_elements(altitude_km: std::ops::Range<f64>) -> impl Strategy<Value = MeanElements> {
+    (altitude_km, mean_elements()).prop_map(|(altitude, elements)| {
+        let a = EARTH_RADIUS_KM + altitude;
+        let n_rad_s = (MU_EARTH_KM3_S2 / (a * a * a)).sqrt();
+        MeanElements {
+            eccentricity: 0.0,
+            arg_perigee_deg: 0.0,
+            mean_motion_rev_day: n_rad_s * 86400.0 / (2.0 * std::f64::consts::PI),
+            ..elements
+        }
+    })
+}
+
+/// Valid elements with fields pushed to the edges of their TLE columns:
+/// equatorial/retrograde-equatorial inclination, angles that round up to
+/// 360.0000, eccentricity at the 7-digit resolution, mean motion either
+/// side of 10 rev/day (the `{:11.8}` width change), first and last
+/// instant of a leap year
+pub fn edge_elements() -> impl Strategy<Value = MeanElements> {
+    let inclination = prop_oneof![Just(0.0), Just(180.0), 0.0..=180.0f64];
+    let edge_angle = || prop_oneof![Just(0.0), Just(359.99996), angle()];
+    let eccentricity = prop_oneof![Just(0.0), Just(1e-7), Just(0.0001)];
+    let mean_motion = prop_oneof![Just(9.99999999), Just(10.0), Just(MIN_MEAN_MOTION), Just(MAX_MEAN_MOTION)];
+    let epoch = prop_oneof![
+        Just(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+        Just(Utc.with_ymd_and_hms(2024, 12, 31, 23, 59, 59).unwrap() + chrono::Duration::milliseconds(999)),
+        Just(Utc.with_ymd_and_hms(2049, 12, 31, 0, 0, 0).unwrap()),
+        epoch(),
+    ];
+    (inclination, edge_angle(), edge_angle(), edge_angle(), eccentricity, mean_motion, epoch, drag_terms()).prop_map(
+        |(inclination_deg, raan_deg, arg_perigee_deg, mean_anomaly_deg, eccentricity, mean_motion_rev_day, epoch, drag)| {
+            MeanElements {
+                epoch,
+                inclination_deg,
+                raan_deg,
+                eccentricity,
+                arg_perigee_deg,
+                mean_anomaly_deg,
+                mean_motion_rev_day,
+                drag,
+            }
+        },
+    )
+}
+
+/// Walker T/P/F shells that divide evenly: 1-24 planes of 1-24
+/// satellites, phasing below the plane count
+pub fn walker_delta() -> impl Strategy<Value = WalkerDelta> {
+    (1u32..=24, 1u32..=24)
+        .prop_flat_map(|(planes, per_plane)| {
+            (Just(planes), Just(per_plane), 0..planes, 300.0..36_000.0f64, 0.0..=180.0f64)
+        })
+        .prop_map(|(planes, per_plane, phasing, altitude_km, inclination_deg)| WalkerDelta {
+            total_satellites: planes * per_plane,
+            planes,
+            phasing,
+            altitude_km,
+            inclination_deg,
+        })
+}
+
+/// Shells the generator must still handle: totals that don't divide by
+/// the plane count and phasing at or above it. Plane counts above the
+/// total are left out; `generate_satellites` needs at least one
+/// satellite per plane.
+pub fn near_valid_walker_delta() -> impl Strategy<Value = WalkerDelta> {
+    (1u32..=24, 1u32..=300)
+        .prop_filter("at least one satellite per plane", |(planes, total)| total >= planes)
+        .prop_flat_map(|(planes, total)| {
+            (Just(planes), Just(total), 0..planes * 3, 300.0..36_000.0f64, 0.0..=180.0f64)
+        })
+        .prop_map(|(planes, total_satellites, phasing, altitude_km, inclination_deg)| WalkerDelta {
+            total_satellites,
+            planes,
+            phasing,
+            altitude_km,
+            inclination_deg,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orbital_mechanics::tle::{self, TleRecord};
+
+    fn validate(elements: &MeanElements) -> Result<TleRecord, String> {
+        let (line1, line2) = elements.to_tle_lines(12345);
+        let record = TleRecord {
+            name: None,
+            norad_id: 12345,
+            line1,
+            line2,
+        };
+        tle::validate(&record).map_err(|e| e.to_string())?;
+        Ok(record)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        #[test]
+        fn test_generated_elements_stay_above_perigee(elements in mean_elements()) {
+            let a = semi_major_axis_km(elements.mean_motion_rev_day);
+            prop_assert!(a * (1.0 - elements.eccentricity) >= EARTH_RADIUS_KM + MIN_PERIGEE_KM - 1e-6);
+        }
+
+        #[test]
+        fn test_valid_elements_format_as_valid_tles(elements in mean_elements()) {
+            prop_assert!(validate(&elements).is_ok(), "{:?}", validate(&elements));
+        }
+
+        #[test]
+        fn test_valid_elements_propagate_between_apsides(elements in mean_elements()) {
+            let state = validate(&elements).unwrap().into_satellite().propagate(elements.epoch);
+            prop_assert!(state.is_ok(), "{:?}", state);
+            let state = state.unwrap();
+            let r = (state.position_x.powi(2) + state.position_y.powi(2) + state.position_z.powi(2)).sqrt();
+            // Mean vs osculating elements: allow 1% plus short-period terms
+            let a = semi_major_axis_km(elements.mean_motion_rev_day);
+            prop_assert!(r > a * (1.0 - elements.eccentricity) * 0.99 - 50.0, "{} km", r);
+            prop_assert!(r < a * (1.0 + elements.eccentricity) * 1.01 + 50.0, "{} km", r);
+        }
+
+        #[test]
+        fn test_edge_elements_format_as_valid_tles(elements in edge_elements()) {
+            let record = validate(&elements);
+            prop_assert!(record.is_ok(), "{:?}", record);
+            let record = record.unwrap();
+            prop_assert_eq!(record.line1.len(), 69);
+            prop_assert_eq!(record.line2.len(), 69);
+        }
+
+        #[test]
+        fn test_walker_shells_generate_valid_tles(walker in walker_delta()) {
+            let epoch = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+            let sats = walker.generate_satellites("FUZZ", 10000, epoch);
+            prop_assert_eq!(sats.len() as u32, walker.total_satellites);
+            for sat in &sats {
+                prop_assert!((1..=walker.planes as u8).contains(&sat.plane));
+                let record = TleRecord {
+                    name: None,
+                    norad_id: sat.norad_id,
+                    line1: sat.tle_line1.clone(),
+                    line2: sat.tle_line2.clone(),
+                };
+                prop_assert!(tle::validate(&record).is_ok());
+            }
+        }
+
+        #[test]
+        fn test_near_valid_walker_shells_do_not_panic(walker in near_valid_walker_delta()) {
+            let epoch = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+            let sats = walker.generate_satellites("FUZZ", 10000, epoch);
+            prop_assert_eq!(sats.len() as u32, walker.total_satellites);
+        }
+    }
+}
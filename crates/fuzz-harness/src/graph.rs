@@ -0,0 +1,443 @@
+//! Constellation graph topology strategies
+//!
+//! `ConstellationGraph` is neither `Clone` nor `Debug`, so strategies
+//! generate a `GraphSpec` (nodes plus an edge list) that proptest can
+//! shrink and print, and tests call `build()`. Specs are structurally
+//! valid: unique node IDs, no self-loops, at most one link per node pair.
+//! "Connected" is structural; degraded links may still cut the active
+//! topology, which `reachable` accounts for.
+
+use orbital_glaf::objective::RouteMetrics;
+use orbital_glaf::routing::{RouteOptimizer, RouteRequest};
+use orbital_glaf::{ConstellationGraph, ConstellationLink, ConstellationNode, GlafError, LinkType};
+use proptest::prelude::*;
+use proptest::sample::Index;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+/// Node `i` is a satellite when `i < satellites`, otherwise a ground station
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphSpec {
+    pub satellites: usize,
+    pub stations: usize,
+    pub nodes: Vec<ConstellationNode>,
+    pub links: Vec<(usize, usize, ConstellationLink)>,
+}
+
+impl GraphSpec {
+    pub fn node_count(&self) -> usize {
+        self.satellites + self.stations
+    }
+
+    pub fn id(&self, i: usize) -> &str {
+        &self.nodes[i].id
+    }
+
+    pub fn build(&self) -> ConstellationGraph {
+        let mut graph = ConstellationGraph::new();
+        for node in &self.nodes {
+            graph.add_node(node.clone());
+        }
+        for (a, b, link) in &self.links {
+            graph
+                .add_link(&self.nodes[*a].id, &self.nodes[*b].id, link.clone())
+                .expect("spec links reference spec nodes");
+        }
+        graph
+    }
+
+    /// Reference BFS over active links
+    pub fn reachable(&self, from: usize, to: usize) -> bool {
+        let mut seen = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(n) = queue.pop_front() {
+            if n == to {
+                return true;
+            }
+            for (a, b, link) in &self.links {
+                let next = if *a == n { *b } else if *b == n { *a } else { continue };
+                if link.active && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Quality of one link before its type is known
+#[derive(Debug, Clone, Copy)]
+pub struct LinkQuality {
+    pub margin_db: f64,
+    pub weather_score: f64,
+    pub latency_ms: f64,
+    pub throughput_gbps: f64,
+    pub active: bool,
+}
+
+/// Healthy link
+pub fn link_quality() -> impl Strategy<Value = LinkQuality> {
+    (3.0..20.0f64, 0.7..=1.0f64, 0.1..150.0f64, 1.0..100.0f64).prop_map(
+        |(margin_db, weather_score, latency_ms, throughput_gbps)| LinkQuality {
+            margin_db,
+            weather_score,
+            latency_ms,
+            throughput_gbps,
+            active: true,
+        },
+    )
+}
+
+/// Link that may be down, fading (negative margin) or weathered out;
+/// `down_percent` of links are inactive
+pub fn degraded_link_quality(down_percent: u32) -> impl Strategy<Value = LinkQuality> {
+    (
+        prop_oneof![3 => -6.0..20.0f64, 1 => Just(0.0)],
+        prop_oneof![3 => 0.0..=1.0f64, 1 => Just(0.0)],
+        0.1..150.0f64,
+        0.0..100.0f64,
+        0u32..100,
+    )
+        .prop_map(move |(margin_db, weather_score, latency_ms, throughput_gbps, roll)| LinkQuality {
+            margin_db,
+            weather_score,
+            latency_ms,
+            throughput_gbps,
+            active: roll >= down_percent,
+        })
+}
+
+fn link_between(spec_satellites: usize, a: usize, b: usize, quality: LinkQuality) -> ConstellationLink {
+    let link_type = match (a < spec_satellites, b < spec_satellites) {
+        (true, true) => LinkType::InterSatellite,
+        (false, false) => LinkType::Terrestrial,
+        _ => LinkType::SatelliteToGround,
+    };
+    ConstellationLink {
+        id: format!("L-{}-{}", a.min(b), a.max(b)),
+        link_type,
+        margin_db: quality.margin_db,
+        throughput_gbps: quality.throughput_gbps,
+        utilization: 0.0,
+        latency_ms: quality.latency_ms,
+        active: quality.active,
+        weather_score: quality.weather_score,
+    }
+}
+
+fn nodes(satellites: usize, stations: usize) -> impl Strategy<Value = Vec<ConstellationNode>> {
+    let sats = proptest::collection::vec((-90.0..=90.0f64, -180.0..180.0f64, 500.0..36_000.0f64, 0u8..12), satellites);
+    let gs = proptest::collection::vec((-90.0..=90.0f64, -180.0..180.0f64, 1u8..=3), stations);
+    (sats, gs).prop_map(|(sats, gs)| {
+        let mut nodes: Vec<ConstellationNode> = sats
+            .into_iter()
+            .enumerate()
+            .map(|(i, (lat, lon, alt, plane))| {
+                ConstellationNode::satellite(format!("SAT-{}", i), format!("Sat {}", i), lat, lon, alt, plane, 55.0)
+            })
+            .collect();
+        nodes.extend(gs.into_iter().enumerate().map(|(i, (lat, lon, tier))| {
+            ConstellationNode::ground_station(format!("GS-{}", i), format!("Ground {}", i), lat, lon, tier)
+        }));
+        nodes
+    })
+}
+
+/// Shape of a random topology
+#[derive(Debug, Clone, Copy)]
+pub struct TopologyConfig {
+    pub max_satellites: usize,
+    pub max_stations: usize,
+    /// Extra links beyond the spanning forest, per node
+    pub max_extra_links_per_node: usize,
+    /// Components the nodes are split into (1 = connected)
+    pub components: usize,
+    /// Percentage of links generated inactive
+    pub down_percent: u32,
+}
+
+impl Default for TopologyConfig {
+    fn default() -> Self {
+        Self {
+            max_satellites: 24,
+            max_stations: 8,
+            max_extra_links_per_node: 2,
+            components: 1,
+            down_percent: 0,
+        }
+    }
+}
+
+/// Random topology: a spanning forest with `components` trees, plus extra
+/// links (ISL density) that never cross between components
+pub fn topology(config: TopologyConfig) -> impl Strategy<Value = GraphSpec> {
+    let components = config.components.max(1);
+    (components.max(2)..=config.max_satellites.max(components.max(2)), 0..=config.max_stations)
+        .prop_flat_map(move |(satellites, stations)| {
+            let total = satellites + stations;
+            let quality = move || degraded_link_quality(config.down_percent);
+            (
+                Just((satellites, stations)),
+                nodes(satellites, stations),
+                proptest::collection::vec((any::<Index>(), quality()), total - 1),
+                proptest::collection::vec(
+                    (any::<Index>(), any::<Index>(), quality()),
+                    0..=total * config.max_extra_links_per_node,
+                ),
+            )
+        })
+        .prop_map(move |((satellites, stations), nodes, parents, extras)| {
+            let total = satellites + stations;
+            // Component of node i: contiguous blocks, so each block's
+            // first node is a root and every later node has a parent in
+            // its own block
+            let component = |i: usize| i * components / total;
+            let first_of = |c: usize| (0..total).find(|&i| component(i) == c).unwrap();
+
+            let mut pairs = HashSet::new();
+            let mut links = Vec::new();
+            for (i, (parent, quality)) in (1..total).zip(parents) {
+                let start = first_of(component(i));
+                if start == i {
+                    continue;
+                }
+                let p = start + parent.index(i - start);
+                pairs.insert((p.min(i), p.max(i)));
+                links.push((p, i, link_between(satellites, p, i, quality)));
+            }
+            for (a, b, quality) in extras {
+                let (a, b) = (a.index(total), b.index(total));
+                if a == b || component(a) != component(b) || !pairs.insert((a.min(b), a.max(b))) {
+                    continue;
+                }
+                links.push((a, b, link_between(satellites, a, b, quality)));
+            }
+            GraphSpec {
+                satellites,
+                stations,
+                nodes,
+                links,
+            }
+        })
+}
+
+/// Connected, every link up (quality still varies)
+pub fn connected_graph() -> impl Strategy<Value = GraphSpec> {
+    topology(TopologyConfig::default())
+}
+
+/// Two or three islands with no links between them
+pub fn disconnected_graph() -> impl Strategy<Value = GraphSpec> {
+    (2usize..=3).prop_flat_map(|components| {
+        topology(TopologyConfig {
+            components,
+            ..TopologyConfig::default()
+        })
+    })
+}
+
+/// Connected topology with a share of links down or fading
+pub fn degraded_graph() -> impl Strategy<Value = GraphSpec> {
+    (5u32..=60, 0usize..=4).prop_flat_map(|(down_percent, max_extra_links_per_node)| {
+        topology(TopologyConfig {
+            down_percent,
+            max_extra_links_per_node,
+            ..TopologyConfig::default()
+        })
+    })
+}
+
+/// Walker-style +grid mesh: an ISL ring in each plane, cross-plane links
+/// between neighbouring slots, and each station linked to 1-3 satellites
+pub fn walker_mesh() -> impl Strategy<Value = GraphSpec> {
+    (2usize..=6, 2usize..=8, 1usize..=6)
+        .prop_flat_map(|(planes, per_plane, stations)| {
+            let satellites = planes * per_plane;
+            let isl = 2 * satellites;
+            (
+                Just((planes, per_plane, stations)),
+                nodes(satellites, stations),
+                proptest::collection::vec(link_quality(), isl),
+                proptest::collection::vec(
+                    (proptest::collection::vec(any::<Index>(), 1..=3), link_quality()),
+                    stations,
+                ),
+            )
+        })
+        .prop_map(|((planes, per_plane, stations), nodes, isl_quality, uplinks)| {
+            let satellites = planes * per_plane;
+            let slot = |p: usize, s: usize| (p % planes) * per_plane + s % per_plane;
+            let mut pairs = HashSet::new();
+            let mut links = Vec::new();
+            let mut quality = isl_quality.into_iter();
+            for p in 0..planes {
+                for s in 0..per_plane {
+                    let a = slot(p, s);
+                    for b in [slot(p, s + 1), slot(p + 1, s)] {
+                        let q = quality.next().unwrap();
+                        if a != b && pairs.insert((a.min(b), a.max(b))) {
+                            links.push((a, b, link_between(satellites, a, b, q)));
+                        }
+                    }
+                }
+            }
+            for (g, (sats, q)) in uplinks.into_iter().enumerate() {
+                let station = satellites + g;
+                for sat in sats {
+                    let sat = sat.index(satellites);
+                    if pairs.insert((sat, station)) {
+                        links.push((sat, station, link_between(satellites, sat, station, q)));
+                    }
+                }
+            }
+            GraphSpec {
+                satellites,
+                stations,
+                nodes,
+                links,
+            }
+        })
+}
+
+/// Any of the above
+pub fn any_graph() -> impl Strategy<Value = GraphSpec> {
+    prop_oneof![connected_graph(), disconnected_graph(), degraded_graph(), walker_mesh()]
+}
+
+/// Graph plus a source/destination pair drawn from its nodes
+pub fn graph_with_endpoints(graph: impl Strategy<Value = GraphSpec>) -> impl Strategy<Value = (GraphSpec, usize, usize)> {
+    graph.prop_flat_map(|spec| {
+        let n = spec.node_count();
+        (Just(spec), 0..n, 0..n)
+    })
+}
+
+/// Path starts and ends where asked, visits no node twice and only uses
+/// active links
+pub fn check_path(spec: &GraphSpec, graph: &ConstellationGraph, from: usize, to: usize, path: &[String]) -> Result<(), String> {
+    if path.first().map(String::as_str) != Some(spec.id(from)) || path.last().map(String::as_str) != Some(spec.id(to)) {
+        return Err(format!("endpoints {:?}", path));
+    }
+    let unique: HashSet<&String> = path.iter().collect();
+    if unique.len() != path.len() {
+        return Err(format!("loop in {:?}", path));
+    }
+    for hop in path.windows(2) {
+        match graph.get_link(&hop[0], &hop[1]) {
+            Some(link) if link.active => {}
+            _ => return Err(format!("{} -> {} not an active link", hop[0], hop[1])),
+        }
+    }
+    Ok(())
+}
+
+/// `find_path` returns a valid path exactly when BFS says one exists
+pub fn check_find_path(spec: &GraphSpec, from: usize, to: usize) -> Result<(), String> {
+    let graph = spec.build();
+    match graph.find_path(spec.id(from), spec.id(to)) {
+        Ok(path) if spec.reachable(from, to) => check_path(spec, &graph, from, to, &path),
+        Ok(path) => Err(format!("{:?} found but BFS has no active route", path)),
+        Err(GlafError::NoPath(..)) if !spec.reachable(from, to) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Up to `k` distinct valid paths in non-decreasing cost, the first as
+/// cheap as `find_path`'s
+pub fn check_k_shortest_paths(spec: &GraphSpec, from: usize, to: usize, k: usize) -> Result<(), String> {
+    let graph = spec.build();
+    let paths = match graph.k_shortest_paths(spec.id(from), spec.id(to), k) {
+        Ok(paths) => paths,
+        Err(GlafError::NoPath(..)) if !spec.reachable(from, to) => return Ok(()),
+        Err(e) => return Err(e.to_string()),
+    };
+    if paths.len() > k || paths.is_empty() != (k == 0) {
+        return Err(format!("{} paths for k = {}", paths.len(), k));
+    }
+    let distinct: HashSet<&Vec<String>> = paths.iter().collect();
+    if distinct.len() != paths.len() {
+        return Err(format!("duplicate paths in {:?}", paths));
+    }
+    for pair in paths.windows(2) {
+        if graph.path_cost(&pair[0]) > graph.path_cost(&pair[1]) + 1e-9 {
+            return Err(format!("{:?} costs more than the next path {:?}", pair[0], pair[1]));
+        }
+    }
+    for path in &paths {
+        check_path(spec, &graph, from, to, path)?;
+    }
+    if let (Some(first), Ok(best)) = (paths.first(), graph.find_path(spec.id(from), spec.id(to))) {
+        if (graph.path_cost(first) - graph.path_cost(&best)).abs() >= 1e-9 {
+            return Err(format!("first path {:?} is not the shortest {:?}", first, best));
+        }
+    }
+    Ok(())
+}
+
+/// Any route the optimizer picks scores within [0, 1] and has metrics
+pub fn check_route_score(spec: &GraphSpec, from: usize, to: usize) -> Result<(), String> {
+    if from == to {
+        return Ok(());
+    }
+    let graph = spec.build();
+    let request = RouteRequest {
+        source_id: spec.id(from).to_string(),
+        destination_id: spec.id(to).to_string(),
+        alternatives: 2,
+        thresholds: None,
+    };
+    let Ok(response) = RouteOptimizer::new().optimize(&graph, &request) else {
+        return Ok(());
+    };
+    let route = response.best_route.ok_or("path found but not scored")?;
+    if !(0.0..=1.0).contains(&route.score) {
+        return Err(format!("score {} for {:?}", route.score, route.path));
+    }
+    if RouteMetrics::from_path(&graph, &route.path).is_none() {
+        return Err(format!("no metrics for {:?}", route.path));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        #[test]
+        fn test_specs_are_structurally_valid(spec in any_graph()) {
+            let graph = spec.build();
+            let stats = graph.stats();
+            prop_assert_eq!(stats.total_nodes, spec.node_count());
+            prop_assert_eq!(stats.total_links, spec.links.len());
+            let pairs: HashSet<(usize, usize)> = spec.links.iter().map(|(a, b, _)| (*a.min(b), *a.max(b))).collect();
+            prop_assert_eq!(pairs.len(), spec.links.len());
+            prop_assert!(spec.links.iter().all(|(a, b, _)| a != b));
+        }
+
+        #[test]
+        fn test_connected_graphs_are_connected((spec, from, to) in graph_with_endpoints(connected_graph())) {
+            prop_assert!(spec.reachable(from, to));
+        }
+
+        #[test]
+        fn test_find_path_agrees_with_bfs((spec, from, to) in graph_with_endpoints(any_graph())) {
+            prop_assert_eq!(check_find_path(&spec, from, to), Ok(()));
+        }
+
+        #[test]
+        fn test_k_shortest_paths_are_ordered_and_distinct(
+            (spec, from, to) in graph_with_endpoints(prop_oneof![degraded_graph(), walker_mesh()]),
+            k in 0usize..=5,
+        ) {
+            prop_assert_eq!(check_k_shortest_paths(&spec, from, to, k), Ok(()));
+        }
+
+        #[test]
+        fn test_route_scores_stay_in_range((spec, from, to) in graph_with_endpoints(any_graph())) {
+            prop_assert_eq!(check_route_score(&spec, from, to), Ok(()));
+        }
+    }
+}
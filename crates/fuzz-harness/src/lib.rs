@@ -0,0 +1,34 @@
+//! Fuzz Harness
+//!
+//! Shared proptest strategies for property-testing the orbital crates:
+//!
+//! - Orbital elements and Walker Delta configurations
+//! - TLE line pairs, valid and near-valid (checksum corruption,
+//!   truncation, field-width extremes)
+//! - Geodetic positions (poles, antimeridian, high altitude) and ECI
+//!   states, with round-trip and altitude invariant checks
+//! - Constellation graph topologies (connected, disconnected, Walker mesh,
+//!   degraded links) with routing invariant checks
+//! - Route objective inputs (utility weights, SLA tiers and constraints,
+//!   route metrics) with utility, feasibility and selection checks
+//! - A differential SGP4 runner comparing direct elements, formatted TLEs
+//!   and an optional external reference
+//! - Seeded target runs reported as JSON and an HTML summary
+//! - Sharded campaigns over named targets, run as local processes or as
+//!   GCP Cloud Run / Batch tasks collecting into a bucket
+//!   (`fuzz-campaign` binary)
+//! - A failure corpus replayed as regression tests before new fuzzing
+//!
+//! Valid strategies only produce inputs the library must accept; the
+//! near-valid ones produce inputs it must reject without panicking.
+
+pub mod coords;
+pub mod corpus;
+pub mod distributed;
+pub mod elements;
+pub mod graph;
+pub mod objective;
+pub mod reports;
+pub mod runner;
+pub mod targets;
+pub mod tle;
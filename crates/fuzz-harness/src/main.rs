@@ -0,0 +1,260 @@
+//! Fuzz Campaign CLI
+//!
+//! Runs the harness targets as a sharded campaign.
+//!
+//! Usage:
+//!   fuzz-campaign local  --workers 8 --cases 100000 --store target/fuzz
+//!   fuzz-campaign worker --store gs://bucket/run-1 --cases 10000000
+//!   fuzz-campaign merge  --store gs://bucket/run-1 --shards 500 --out target/fuzz
+//!   fuzz-campaign replay --corpus target/fuzz/corpus
+//!
+//! On GCP, run `worker` as a Cloud Run or Batch job with N tasks: each task
+//! picks its shard from the task environment and writes
+//! `shard-NNNNN.json` (plus `corpus/` entries for failures) to the bucket.
+//! `merge` then writes the combined JSON and HTML report and pulls the
+//! corpus next to it. `local` replays the existing corpus before fuzzing.
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use fuzz_harness::corpus::{Corpus, ReplayOutcome};
+use fuzz_harness::distributed::{merge, missing_shards, run_shard, Campaign, Shard, Store};
+use fuzz_harness::targets;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{info, warn, Level};
+use tracing_subscriber::FmtSubscriber;
+
+#[derive(Parser, Debug)]
+#[command(name = "fuzz-campaign", about = "Sharded property-test campaigns for the orbital crates")]
+struct Cli {
+    #[command(subcommand)]
+    command: Cmd,
+
+    /// Verbose output
+    #[arg(short, long, global = true)]
+    verbose: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+struct CampaignArgs {
+    /// Campaign seed; shard seeds derive from it
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Cases per target across all shards
+    #[arg(long, default_value_t = 10_000)]
+    cases: u64,
+
+    /// Target name prefixes to run (repeatable; default all)
+    #[arg(short, long = "target")]
+    targets: Vec<String>,
+}
+
+impl CampaignArgs {
+    fn campaign(&self) -> Campaign {
+        Campaign {
+            seed: self.seed,
+            total_cases: self.cases,
+            targets: self.targets.clone(),
+        }
+    }
+
+    fn to_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--seed".to_string(),
+            self.seed.to_string(),
+            "--cases".to_string(),
+            self.cases.to_string(),
+        ];
+        for target in &self.targets {
+            args.extend(["--target".to_string(), target.clone()]);
+        }
+        args
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    /// Run one shard and store its report
+    Worker {
+        #[command(flatten)]
+        campaign: CampaignArgs,
+
+        /// Directory or gs:// URL for shard reports
+        #[arg(long)]
+        store: String,
+
+        /// Shard index; defaults to the Cloud Run / Batch task index
+        #[arg(long, requires = "shards")]
+        shard: Option<u32>,
+
+        /// Shard count; defaults to the Cloud Run / Batch task count
+        #[arg(long, requires = "shard")]
+        shards: Option<u32>,
+    },
+
+    /// Run every shard as a local process, then merge
+    Local {
+        #[command(flatten)]
+        campaign: CampaignArgs,
+
+        #[arg(long, default_value = "target/fuzz")]
+        store: String,
+
+        #[arg(short, long, default_value_t = 4)]
+        workers: u32,
+
+        /// Where the store's corpus is pulled and replayed before fuzzing
+        #[arg(long, default_value = "target/fuzz/corpus")]
+        corpus: PathBuf,
+
+        /// Skip the corpus replay
+        #[arg(long)]
+        no_replay: bool,
+    },
+
+    /// Merge stored shard reports into one JSON and HTML report
+    Merge {
+        #[arg(long)]
+        store: String,
+
+        /// Expected shard count, to report missing shards
+        #[arg(long)]
+        shards: Option<u32>,
+
+        #[arg(short, long, default_value = "target/fuzz")]
+        out: PathBuf,
+    },
+
+    /// Re-check every stored failure against its target
+    Replay {
+        #[arg(long, default_value = "target/fuzz/corpus")]
+        corpus: PathBuf,
+    },
+
+    /// List target names
+    List,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let level = if cli.verbose { Level::DEBUG } else { Level::INFO };
+    let subscriber = FmtSubscriber::builder().with_max_level(level).finish();
+    tracing::subscriber::set_global_default(subscriber)?;
+
+    match cli.command {
+        Cmd::Worker {
+            campaign,
+            store,
+            shard,
+            shards,
+        } => {
+            let shard = match (shard, shards) {
+                (Some(index), Some(count)) => Shard::new(index, count).context("shard index must be below the count")?,
+                _ => Shard::from_env().context("no --shard/--shards and no Cloud Run or Batch task environment")?,
+            };
+            let report = run_shard(&campaign.campaign(), shard);
+            Store::parse(&store).put_shard(shard, &report)?;
+            info!(
+                "shard {}/{}: {} targets, {} failing",
+                shard.index,
+                shard.count,
+                report.targets.len(),
+                report.failures().count()
+            );
+            Ok(())
+        }
+
+        Cmd::Local {
+            campaign,
+            store,
+            workers,
+            corpus,
+            no_replay,
+        } => {
+            if workers == 0 {
+                bail!("--workers must be at least 1");
+            }
+            if !no_replay {
+                Store::parse(&store).pull_corpus(&corpus)?;
+                replay(&corpus)?;
+            }
+            let exe = std::env::current_exe()?;
+            let children = (0..workers)
+                .map(|index| {
+                    Command::new(&exe)
+                        .arg("worker")
+                        .args(campaign.to_args())
+                        .args(["--store", &store, "--shard", &index.to_string(), "--shards", &workers.to_string()])
+                        .spawn()
+                        .with_context(|| format!("spawning shard {}", index))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            for (index, mut child) in children.into_iter().enumerate() {
+                let status = child.wait()?;
+                if !status.success() {
+                    warn!("shard {} exited with {}", index, status);
+                }
+            }
+            merge_store(&store, Some(workers), Path::new(&store))
+        }
+
+        Cmd::Merge { store, shards, out } => merge_store(&store, shards, &out),
+
+        Cmd::Replay { corpus } => replay(&corpus),
+
+        Cmd::List => {
+            for target in targets::all() {
+                println!("{}", target.name);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn merge_store(store: &str, shards: Option<u32>, out: &Path) -> Result<()> {
+    let reports = Store::parse(store).shard_reports()?;
+    if let Some(count) = shards {
+        let missing = missing_shards(&reports, count);
+        if !missing.is_empty() {
+            warn!("{} of {} shards missing: {:?}", missing.len(), count, missing);
+        }
+    }
+
+    let report = merge(reports.into_iter().map(|(_, r)| r));
+    report.write(out)?;
+    Store::parse(store).pull_corpus(&out.join("corpus"))?;
+    info!(
+        "{} target runs, {} cases, {} failing -> {}",
+        report.targets.len(),
+        report.total_cases(),
+        report.failures().count(),
+        out.display()
+    );
+    if !report.passed() {
+        bail!("{} target runs failed", report.failures().count());
+    }
+    Ok(())
+}
+
+/// Replay the corpus; any entry that still fails stops the run
+fn replay(dir: &Path) -> Result<()> {
+    let replays = Corpus::new(dir).replay()?;
+    let mut failing = 0;
+    for r in &replays {
+        match &r.outcome {
+            ReplayOutcome::Passed => info!("{} {:016x}: passes", r.entry.target, r.entry.seed),
+            ReplayOutcome::UnknownTarget => warn!("{} {:016x}: no such target", r.entry.target, r.entry.seed),
+            ReplayOutcome::Failed(message) => {
+                warn!("{} {:016x}: still failing: {}", r.entry.target, r.entry.seed, message);
+                failing += 1;
+            }
+        }
+    }
+    info!("replayed {} corpus entries from {}", replays.len(), dir.display());
+    if failing > 0 {
+        bail!("{} corpus entries still failing", failing);
+    }
+    Ok(())
+}
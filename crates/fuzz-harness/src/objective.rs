@@ -0,0 +1,340 @@
+//! Route objective function strategies (RFC-9050)
+//!
+//! Strategies for the inputs of `orbital_glaf::objective`: utility
+//! weights (normalised, or with a broken sum or a negative entry), SLA
+//! tiers with preset or custom weights and constraints, and
+//! candidate route metrics. The BestEffort preset has an infinite latency
+//! ceiling, which JSON can't carry, so strategies generate an
+//! `ObjectiveSpec` and tests call `build()`.
+//!
+//! Invariants: utility terms stay within their weights and sum to the
+//! utility, utility never rises under a penalty, feasibility agrees with
+//! the constraints and with `select_optimal`, and weights survive a JSON
+//! round trip bit for bit, so anything keyed on them stays stable.
+
+use orbital_glaf::objective::{ObjectiveFunction, ObjectiveWeights, RouteMetrics, SlaConstraints, SlaTier};
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Tolerance on utility comparisons
+const EPSILON: f64 = 1e-12;
+
+/// An objective function: a tier preset, optionally with its weights or
+/// constraints replaced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectiveSpec {
+    pub tier: SlaTier,
+    pub weights: Option<ObjectiveWeights>,
+    pub constraints: Option<SlaConstraints>,
+}
+
+impl ObjectiveSpec {
+    pub fn build(&self) -> ObjectiveFunction {
+        let mut objective = ObjectiveFunction::for_tier(self.tier);
+        if let Some(weights) = &self.weights {
+            objective.weights = weights.clone();
+        }
+        if let Some(constraints) = &self.constraints {
+            objective.constraints = constraints.clone();
+        }
+        objective
+    }
+}
+
+/// A change that makes a route no better
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Penalty {
+    /// Extra latency (ms)
+    Latency(f64),
+    /// Margin lost on the weakest link (dB)
+    Margin(f64),
+    /// Bottleneck throughput lost (Gbps)
+    Throughput(f64),
+    /// Extra hops
+    Hops(usize),
+    /// Weather factor scaled by this (0-1)
+    Weather(f64),
+}
+
+impl Penalty {
+    pub fn apply(&self, m: &RouteMetrics) -> RouteMetrics {
+        let mut m = m.clone();
+        match *self {
+            Penalty::Latency(ms) => m.latency_ms += ms,
+            Penalty::Margin(db) => {
+                m.min_margin_db -= db;
+                m.avg_margin_db = m.avg_margin_db.max(m.min_margin_db);
+            }
+            Penalty::Throughput(gbps) => m.throughput_gbps = (m.throughput_gbps - gbps).max(0.0),
+            Penalty::Hops(hops) => {
+                m.hop_count += hops;
+                let last = m.path.len();
+                m.path.extend((last..last + hops).map(|i| format!("N-{}", i)));
+            }
+            Penalty::Weather(factor) => m.weather_factor *= factor,
+        }
+        m
+    }
+}
+
+pub fn sla_tier() -> impl Strategy<Value = SlaTier> {
+    prop_oneof![
+        Just(SlaTier::Platinum),
+        Just(SlaTier::Gold),
+        Just(SlaTier::Silver),
+        Just(SlaTier::BestEffort),
+    ]
+}
+
+fn weights_from(raw: [f64; 5]) -> ObjectiveWeights {
+    ObjectiveWeights {
+        latency: raw[0],
+        margin: raw[1],
+        throughput: raw[2],
+        hops: raw[3],
+        weather: raw[4],
+    }
+}
+
+/// Non-negative weights normalised to sum to 1, some terms possibly off
+pub fn objective_weights() -> impl Strategy<Value = ObjectiveWeights> {
+    proptest::array::uniform5(prop_oneof![4 => 0.01..1.0f64, 1 => Just(0.0)])
+        .prop_filter("at least one non-zero weight", |raw| raw.iter().sum::<f64>() > 0.0)
+        .prop_map(|raw| {
+            let sum: f64 = raw.iter().sum();
+            weights_from(raw.map(|w| w / sum))
+        })
+}
+
+/// Weights `validate` must reject: a sum off 1, or a negative weight.
+/// Non-finite weights are rejected too, but don't survive JSON, so they
+/// are left to the unit tests.
+pub fn invalid_objective_weights() -> impl Strategy<Value = ObjectiveWeights> {
+    let off_sum = (objective_weights(), prop_oneof![0.0..0.99f64, 1.01..5.0f64])
+        .prop_map(|(w, scale)| weights_from([w.latency, w.margin, w.throughput, w.hops, w.weather].map(|x| x * scale)));
+    let bad_entry = (objective_weights(), 0usize..5, -1.0..-1e-3f64).prop_map(|(w, i, bad)| {
+        let mut raw = [w.latency, w.margin, w.throughput, w.hops, w.weather];
+        raw[i] = bad;
+        weights_from(raw)
+    });
+    prop_oneof![off_sum, bad_entry]
+}
+
+/// Finite constraints, from lenient to unmeetable
+pub fn sla_constraints() -> impl Strategy<Value = SlaConstraints> {
+    (1.0..400.0f64, -3.0..15.0f64, 1usize..=16, 0.0..20.0f64).prop_map(
+        |(max_latency_ms, min_margin_db, max_hops, min_throughput_gbps)| SlaConstraints {
+            max_latency_ms,
+            min_margin_db,
+            max_hops,
+            min_throughput_gbps,
+        },
+    )
+}
+
+/// A tier preset, or a tier with custom weights and/or constraints
+pub fn objective_spec() -> impl Strategy<Value = ObjectiveSpec> {
+    (
+        sla_tier(),
+        proptest::option::weighted(0.5, objective_weights()),
+        proptest::option::weighted(0.5, sla_constraints()),
+    )
+        .prop_map(|(tier, weights, constraints)| ObjectiveSpec {
+            tier,
+            weights,
+            constraints,
+        })
+}
+
+/// Metrics of a plausible route, including ones no tier accepts
+pub fn route_metrics() -> impl Strategy<Value = RouteMetrics> {
+    (
+        0.1..400.0f64,
+        -6.0..25.0f64,
+        0.0..10.0f64,
+        0.0..100.0f64,
+        1usize..=20,
+        0.0..=1.0f64,
+    )
+        .prop_map(
+            |(latency_ms, min_margin_db, spread_db, throughput_gbps, hop_count, weather_factor)| RouteMetrics {
+                path: (0..=hop_count).map(|i| format!("N-{}", i)).collect(),
+                latency_ms,
+                min_margin_db,
+                avg_margin_db: min_margin_db + spread_db,
+                throughput_gbps,
+                hop_count,
+                weather_factor,
+            },
+        )
+}
+
+pub fn penalty() -> impl Strategy<Value = Penalty> {
+    prop_oneof![
+        (0.0..200.0f64).prop_map(Penalty::Latency),
+        (0.0..10.0f64).prop_map(Penalty::Margin),
+        (0.0..50.0f64).prop_map(Penalty::Throughput),
+        (0usize..=8).prop_map(Penalty::Hops),
+        (0.0..=1.0f64).prop_map(Penalty::Weather),
+    ]
+}
+
+/// Normalised weights validate; broken ones don't
+pub fn check_weights(weights: &ObjectiveWeights, valid: bool) -> Result<(), String> {
+    match (weights.validate(), valid) {
+        (Ok(()), true) | (Err(_), false) => Ok(()),
+        (Ok(()), false) => Err(format!("{:?} accepted", weights)),
+        (Err(e), true) => Err(e.to_string()),
+    }
+}
+
+/// Each term lies within [0, its weight] and the terms sum to the utility
+pub fn check_utility_bounds(spec: &ObjectiveSpec, m: &RouteMetrics) -> Result<(), String> {
+    let objective = spec.build();
+    let (w, b) = (&objective.weights, objective.evaluate(m));
+    let terms = [
+        ("latency", b.latency, w.latency),
+        ("margin", b.margin, w.margin),
+        ("throughput", b.throughput, w.throughput),
+        ("hops", b.hops, w.hops),
+        ("weather", b.weather, w.weather),
+    ];
+    for (name, term, weight) in terms {
+        if !(-EPSILON..=weight + EPSILON).contains(&term) {
+            return Err(format!("{} term {} outside [0, {}]", name, term, weight));
+        }
+    }
+    let sum: f64 = terms.iter().map(|(_, term, _)| term).sum();
+    if (sum - b.utility).abs() > 1e-9 || !(-EPSILON..=1.0 + 1e-9).contains(&b.utility) {
+        return Err(format!("utility {} from terms summing to {}", b.utility, sum));
+    }
+    Ok(())
+}
+
+/// A penalty never raises utility and never makes a route feasible
+pub fn check_penalty_monotonic(spec: &ObjectiveSpec, m: &RouteMetrics, penalty: Penalty) -> Result<(), String> {
+    let objective = spec.build();
+    let (before, after) = (objective.evaluate(m), objective.evaluate(&penalty.apply(m)));
+    if after.utility > before.utility + EPSILON {
+        return Err(format!(
+            "{:?} raised utility {} -> {}",
+            penalty, before.utility, after.utility
+        ));
+    }
+    if after.feasible && !before.feasible {
+        return Err(format!("{:?} made the route feasible", penalty));
+    }
+    Ok(())
+}
+
+/// `feasible` holds exactly when no constraint is broken, and then there
+/// are no violations to report
+pub fn check_viability(spec: &ObjectiveSpec, m: &RouteMetrics) -> Result<(), String> {
+    let objective = spec.build();
+    let c = &objective.constraints;
+    let meets = m.latency_ms <= c.max_latency_ms
+        && m.min_margin_db >= c.min_margin_db
+        && m.hop_count <= c.max_hops
+        && m.throughput_gbps >= c.min_throughput_gbps;
+    let b = objective.evaluate(m);
+    if b.feasible != meets || b.feasible != b.violations.is_empty() {
+        return Err(format!(
+            "feasible {} for constraints met {} with violations {:?}",
+            b.feasible, meets, b.violations
+        ));
+    }
+    Ok(())
+}
+
+/// `rank` puts feasible routes first, each group by descending utility,
+/// and `select_optimal` is its head exactly when that is feasible
+pub fn check_selection(spec: &ObjectiveSpec, candidates: &[RouteMetrics]) -> Result<(), String> {
+    let objective = spec.build();
+    let ranked = objective.rank(candidates);
+    if ranked.len() != candidates.len() {
+        return Err(format!("{} ranked of {}", ranked.len(), candidates.len()));
+    }
+    for pair in ranked.windows(2) {
+        let (a, b) = (&pair[0].breakdown, &pair[1].breakdown);
+        if (!a.feasible && b.feasible) || (a.feasible == b.feasible && a.utility < b.utility) {
+            return Err(format!(
+                "({}, {}) ranked above ({}, {})",
+                a.feasible, a.utility, b.feasible, b.utility
+            ));
+        }
+    }
+    let any_feasible = ranked.iter().any(|r| r.breakdown.feasible);
+    match objective.select_optimal(candidates) {
+        Some(best) if !best.breakdown.feasible => Err("infeasible route selected".to_string()),
+        Some(best) if best.breakdown.utility != ranked[0].breakdown.utility => Err(format!(
+            "selected utility {} is not the best {}",
+            best.breakdown.utility, ranked[0].breakdown.utility
+        )),
+        Some(_) => Ok(()),
+        None if any_feasible => Err("feasible route not selected".to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Weights come back from JSON bit for bit and evaluate identically
+pub fn check_weights_round_trip(weights: &ObjectiveWeights, m: &RouteMetrics) -> Result<(), String> {
+    let json = serde_json::to_string(weights).map_err(|e| e.to_string())?;
+    let back: ObjectiveWeights = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    let bits = |w: &ObjectiveWeights| [w.latency, w.margin, w.throughput, w.hops, w.weather].map(f64::to_bits);
+    if bits(weights) != bits(&back) {
+        return Err(format!("{:?} came back as {:?}", weights, back));
+    }
+    let spec = |weights: &ObjectiveWeights| ObjectiveSpec {
+        tier: SlaTier::Silver,
+        weights: Some(weights.clone()),
+        constraints: None,
+    };
+    let (a, b) = (spec(weights).build().evaluate(m), spec(&back).build().evaluate(m));
+    if a.utility.to_bits() != b.utility.to_bits() {
+        return Err(format!("utility {} became {}", a.utility, b.utility));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        #[test]
+        fn test_weights_validate(weights in objective_weights(), invalid in invalid_objective_weights()) {
+            prop_assert_eq!(check_weights(&weights, true), Ok(()));
+            prop_assert_eq!(check_weights(&invalid, false), Ok(()));
+        }
+
+        #[test]
+        fn test_utility_is_bounded(spec in objective_spec(), m in route_metrics()) {
+            prop_assert_eq!(check_utility_bounds(&spec, &m), Ok(()));
+        }
+
+        #[test]
+        fn test_penalties_never_help(spec in objective_spec(), m in route_metrics(), penalty in penalty()) {
+            prop_assert_eq!(check_penalty_monotonic(&spec, &m, penalty), Ok(()));
+        }
+
+        #[test]
+        fn test_viability_matches_constraints(spec in objective_spec(), m in route_metrics()) {
+            prop_assert_eq!(check_viability(&spec, &m), Ok(()));
+        }
+
+        #[test]
+        fn test_selection_is_consistent(
+            spec in objective_spec(),
+            candidates in proptest::collection::vec(route_metrics(), 0..8),
+        ) {
+            prop_assert_eq!(check_selection(&spec, &candidates), Ok(()));
+        }
+
+        #[test]
+        fn test_weights_round_trip(weights in objective_weights(), m in route_metrics()) {
+            prop_assert_eq!(check_weights_round_trip(&weights, &m), Ok(()));
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Structured fuzz reports
+//!
+//! `run_target` drives one strategy/check pair through a seeded proptest
+//! `TestRunner` and records what happened: cases run, shrink steps, and
+//! the shrunk counterexample with the failing invariant. A `FuzzReport`
+//! collects targets and serialises to JSON (for aggregating runs) and a
+//! standalone HTML summary (for triage).
+
+use chrono::{DateTime, Utc};
+use proptest::prelude::*;
+use proptest::test_runner::{RngAlgorithm, TestError, TestRng, TestRunner};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::path::Path;
+use std::time::Instant;
+
+pub const JSON_FILE: &str = "fuzz-report.json";
+pub const HTML_FILE: &str = "fuzz-report.html";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum Outcome {
+    Passed,
+    Failed {
+        /// Message from the check that failed on the shrunk input
+        invariant: String,
+        /// `Debug` rendering of the shrunk input
+        counterexample: String,
+        /// The shrunk input as JSON, for the corpus and replay
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input: Option<serde_json::Value>,
+    },
+    /// Too many rejected inputs or a runner-level abort
+    Aborted { reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetReport {
+    pub target: String,
+    /// Replays the run with `run_target(.., seed, ..)`
+    pub seed: u64,
+    pub cases_requested: u32,
+    /// Inputs checked before the first failure (all of them on a pass)
+    pub cases_run: u64,
+    pub shrink_steps: u64,
+    pub duration_ms: f64,
+    pub outcome: Outcome,
+}
+
+impl TargetReport {
+    pub fn passed(&self) -> bool {
+        self.outcome == Outcome::Passed
+    }
+}
+
+/// proptest RNG for a 64-bit seed, so any run is replayable from the report
+pub fn seeded_rng(seed: u64) -> TestRng {
+    let mut bytes = [0u8; 32];
+    for chunk in bytes.chunks_mut(8) {
+        chunk.copy_from_slice(&seed.to_le_bytes());
+    }
+    TestRng::from_seed(RngAlgorithm::ChaCha, &bytes)
+}
+
+/// Check `cases` inputs from `strategy`, shrinking the first failure.
+/// Failure persistence is off: the seed in the report replaces the
+/// regression file.
+pub fn run_target<S>(
+    target: &str,
+    seed: u64,
+    cases: u32,
+    strategy: S,
+    check: impl Fn(&S::Value) -> Result<(), String>,
+) -> TargetReport
+where
+    S: Strategy,
+    S::Value: Debug + Serialize,
+{
+    let config = ProptestConfig {
+        cases,
+        failure_persistence: None,
+        ..ProptestConfig::default()
+    };
+    let mut runner = TestRunner::new_with_rng(config, seeded_rng(seed));
+    let calls = Cell::new(0u64);
+    let first_failure = Cell::new(None);
+
+    let start = Instant::now();
+    let result = runner.run(&strategy, |value| {
+        calls.set(calls.get() + 1);
+        check(&value).map_err(|message| {
+            if first_failure.get().is_none() {
+                first_failure.set(Some(calls.get()));
+            }
+            TestCaseError::fail(message)
+        })
+    });
+    let duration_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let outcome = match result {
+        Ok(()) => Outcome::Passed,
+        Err(TestError::Fail(reason, value)) => Outcome::Failed {
+            invariant: reason.message().to_string(),
+            counterexample: format!("{:?}", value),
+            input: serde_json::to_value(&value).ok(),
+        },
+        Err(TestError::Abort(reason)) => Outcome::Aborted {
+            reason: reason.message().to_string(),
+        },
+    };
+    let cases_run = first_failure.get().unwrap_or(calls.get());
+
+    TargetReport {
+        target: target.to_string(),
+        seed,
+        cases_requested: cases,
+        cases_run,
+        shrink_steps: calls.get() - cases_run,
+        duration_ms,
+        outcome,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuzzReport {
+    pub generated_at: DateTime<Utc>,
+    pub targets: Vec<TargetReport>,
+}
+
+impl Default for FuzzReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FuzzReport {
+    pub fn new() -> Self {
+        Self {
+            generated_at: Utc::now(),
+            targets: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, report: TargetReport) {
+        self.targets.push(report);
+    }
+
+    pub fn passed(&self) -> bool {
+        self.targets.iter().all(TargetReport::passed)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &TargetReport> {
+        self.targets.iter().filter(|t| !t.passed())
+    }
+
+    pub fn total_cases(&self) -> u64 {
+        self.targets.iter().map(|t| t.cases_run).sum()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Self-contained page: totals, then one row per target with failures
+    /// first
+    pub fn to_html(&self) -> String {
+        let mut targets: Vec<&TargetReport> = self.targets.iter().collect();
+        targets.sort_by_key(|t| t.passed());
+
+        let mut rows = String::new();
+        for t in targets {
+            let (status, detail) = match &t.outcome {
+                Outcome::Passed => ("passed", String::new()),
+                Outcome::Failed {
+                    invariant,
+                    counterexample,
+                    ..
+                } => (
+                    "failed",
+                    format!("<p>{}</p><pre>{}</pre>", escape(invariant), escape(counterexample)),
+                ),
+                Outcome::Aborted { reason } => ("aborted", format!("<p>{}</p>", escape(reason))),
+            };
+            rows.push_str(&format!(
+                "<tr class=\"{status}\"><td>{}</td><td>{status}</td><td>{}</td><td>{} / {}</td><td>{}</td><td>{:.1}</td><td>{}</td></tr>\n",
+                escape(&t.target),
+                t.seed,
+                t.cases_run,
+                t.cases_requested,
+                t.shrink_steps,
+                t.duration_ms,
+                detail,
+            ));
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Fuzz report {generated}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+td, th {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}
+tr.failed {{ background: #fdd; }}
+tr.aborted {{ background: #ffd; }}
+pre {{ white-space: pre-wrap; max-width: 60em; }}
+</style>
+</head>
+<body>
+<h1>Fuzz report</h1>
+<p>{generated}: {count} targets, {failed} failing, {cases} cases</p>
+<table>
+<tr><th>Target</th><th>Status</th><th>Seed</th><th>Cases</th><th>Shrink steps</th><th>ms</th><th>Detail</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+            generated = self.generated_at.to_rfc3339(),
+            count = self.targets.len(),
+            failed = self.failures().count(),
+            cases = self.total_cases(),
+            rows = rows,
+        )
+    }
+
+    /// Write `fuzz-report.json` and `fuzz-report.html` into `dir`
+    pub fn write(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join(JSON_FILE), self.to_json()?)?;
+        std::fs::write(dir.join(HTML_FILE), self.to_html())
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn below_500(x: &u32) -> Result<(), String> {
+        if *x < 500 {
+            Ok(())
+        } else {
+            Err(format!("{} >= 500", x))
+        }
+    }
+
+    #[test]
+    fn test_passing_target_runs_every_case() {
+        let report = run_target("small", 7, 64, 0u32..500, below_500);
+        assert!(report.passed());
+        assert_eq!(report.cases_run, 64);
+        assert_eq!(report.shrink_steps, 0);
+    }
+
+    #[test]
+    fn test_failure_is_shrunk_and_replayable() {
+        let report = run_target("large", 42, 256, 0u32..10_000, below_500);
+        assert_eq!(
+            report.outcome,
+            Outcome::Failed {
+                invariant: "500 >= 500".to_string(),
+                counterexample: "500".to_string(),
+                input: Some(500.into()),
+            }
+        );
+        assert!(report.cases_run >= 1);
+
+        let replay = run_target("large", 42, 256, 0u32..10_000, below_500);
+        assert_eq!(replay.cases_run, report.cases_run);
+        assert_eq!(replay.shrink_steps, report.shrink_steps);
+    }
+
+    #[test]
+    fn test_report_round_trips_and_escapes() {
+        let mut report = FuzzReport::new();
+        report.push(run_target("ok", 1, 8, 0u32..500, below_500));
+        report.push(run_target("<bad>", 1, 64, 0u32..10_000, below_500));
+        assert!(!report.passed());
+        assert_eq!(report.failures().count(), 1);
+
+        let json = report.to_json().unwrap();
+        assert!(json.contains("\"status\": \"failed\""));
+        assert_eq!(FuzzReport::from_json(&json).unwrap(), report);
+
+        let html = report.to_html();
+        assert!(html.contains("&lt;bad&gt;"));
+        assert!(!html.contains("<bad>"));
+        assert!(html.find("&lt;bad&gt;") < html.find("<td>ok</td>"));
+    }
+}
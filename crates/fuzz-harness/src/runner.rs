@@ -0,0 +1,417 @@
+//! Differential SGP4 runner
+//!
+//! Propagates one element set along two paths and compares the states:
+//!
+//! - direct: `MeanElements` fields rounded to TLE column precision and
+//!   handed to `sgp4` as an `Elements` struct, never formatted
+//! - TLE: `MeanElements::to_tle_lines` into `propagation::sgp4_propagate`,
+//!   the path every stored satellite takes
+//!
+//! Both start from the same numbers, so a gap beyond `Tolerance` is
+//! formatting, parsing or time-handling drift: a field written in the
+//! wrong units or mean-motion convention (Kozai vs Brouwer), an epoch off
+//! by a rounding step. An optional `Reference` adds an external SGP4
+//! implementation as a third opinion.
+
+use chrono::{DateTime, Duration, NaiveDateTime, Timelike, Utc};
+use orbital_mechanics::propagation::sgp4_propagate;
+use orbital_mechanics::stationkeeping::MeanElements;
+use orbital_mechanics::walker::DragTerms;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Sample times after the element epoch (min): epoch, one step, about one
+/// LEO orbit, one day, one week
+pub const DEFAULT_OFFSETS_MIN: [f64; 5] = [0.0, 1.0, 97.5, 1440.0, 10080.0];
+
+/// External reference command, whitespace-separated program and arguments
+pub const REFERENCE_ENV: &str = "FUZZ_SGP4_REFERENCE";
+
+/// State vector as x, y, z (km) then vx, vy, vz (km/s)
+pub type State = [f64; 6];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub position_km: f64,
+    pub velocity_km_s: f64,
+}
+
+impl Default for Tolerance {
+    /// 1 m and 1 cm/s: well above floating-point noise, well below one
+    /// TLE rounding step in any field
+    fn default() -> Self {
+        Self {
+            position_km: 1e-3,
+            velocity_km_s: 1e-5,
+        }
+    }
+}
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let scale = 10f64.powi(decimals);
+    (value * scale).round() / scale
+}
+
+/// Implied-decimal exponent field (`n̈/6`, B*): five significant digits,
+/// exponent -9..=9
+fn round_exp_field(value: f64) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return 0.0;
+    }
+    let mut exponent = value.abs().log10().floor() as i32 + 1;
+    let mut mantissa = (value.abs() / 10f64.powi(exponent) * 1e5).round();
+    if mantissa >= 1e5 {
+        mantissa = (mantissa / 10.0).floor();
+        exponent += 1;
+    }
+    if exponent < -9 {
+        return 0.0;
+    }
+    value.signum() * mantissa * 1e-5 * 10f64.powi(exponent.min(9))
+}
+
+/// Epoch rounded to the 1e-8 day (864 µs) resolution of the epoch field
+fn round_epoch(epoch: DateTime<Utc>) -> DateTime<Utc> {
+    let midnight = epoch.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let fraction = epoch.num_seconds_from_midnight() as f64 / 86400.0 + epoch.nanosecond() as f64 / 86400.0e9;
+    midnight + Duration::microseconds((fraction * 1e8).round() as i64 * 864)
+}
+
+/// The element set a TLE can hold: every field rounded to its column
+pub fn tle_precision(elements: &MeanElements) -> MeanElements {
+    MeanElements {
+        epoch: round_epoch(elements.epoch),
+        inclination_deg: round_to(elements.inclination_deg, 4),
+        raan_deg: round_to(elements.raan_deg.rem_euclid(360.0), 4),
+        eccentricity: round_to(elements.eccentricity.clamp(0.0, 0.9999999), 7),
+        arg_perigee_deg: round_to(elements.arg_perigee_deg.rem_euclid(360.0), 4),
+        mean_anomaly_deg: round_to(elements.mean_anomaly_deg.rem_euclid(360.0), 4),
+        mean_motion_rev_day: round_to(elements.mean_motion_rev_day, 8),
+        drag: DragTerms {
+            ndot_over_2: elements.drag.ndot_over_2.signum() * round_to(elements.drag.ndot_over_2.abs().min(0.99999999), 8),
+            nddot_over_6: round_exp_field(elements.drag.nddot_over_6),
+            bstar: round_exp_field(elements.drag.bstar),
+        },
+    }
+}
+
+/// `sgp4::Elements` built field by field, bypassing the TLE text
+pub fn direct_elements(elements: &MeanElements, norad_id: u32) -> sgp4::Elements {
+    let e = tle_precision(elements);
+    sgp4::Elements {
+        object_name: None,
+        international_designator: None,
+        norad_id: (norad_id % 100_000) as u64,
+        classification: sgp4::Classification::Unclassified,
+        datetime: e.epoch.naive_utc(),
+        mean_motion_dot: e.drag.ndot_over_2,
+        mean_motion_ddot: e.drag.nddot_over_6,
+        drag_term: e.drag.bstar,
+        element_set_number: 999,
+        inclination: e.inclination_deg,
+        right_ascension: e.raan_deg,
+        eccentricity: e.eccentricity,
+        argument_of_perigee: e.arg_perigee_deg,
+        mean_anomaly: e.mean_anomaly_deg,
+        mean_motion: e.mean_motion_rev_day,
+        revolution_number: 0,
+        ephemeris_type: 0,
+    }
+}
+
+fn minutes_between(from: NaiveDateTime, to: NaiveDateTime) -> f64 {
+    let duration = to - from;
+    match duration.num_microseconds() {
+        Some(us) => us as f64 / 60.0e6,
+        None => duration.num_seconds() as f64 / 60.0,
+    }
+}
+
+fn at_offset(epoch: DateTime<Utc>, offset_min: f64) -> DateTime<Utc> {
+    epoch + Duration::microseconds((offset_min * 60.0e6).round() as i64)
+}
+
+fn propagate_direct(elements: &sgp4::Elements, time: DateTime<Utc>) -> Result<State, String> {
+    let constants = sgp4::Constants::from_elements(elements).map_err(|e| format!("{:?}", e))?;
+    let prediction = constants
+        .propagate(minutes_between(elements.datetime, time.naive_utc()))
+        .map_err(|e| format!("{:?}", e))?;
+    let (p, v) = (prediction.position, prediction.velocity);
+    Ok([p[0], p[1], p[2], v[0], v[1], v[2]])
+}
+
+fn propagate_tle(line1: &str, line2: &str, time: DateTime<Utc>) -> Result<State, String> {
+    let s = sgp4_propagate(line1, line2, time).map_err(|e| e.to_string())?;
+    Ok([s.position_x, s.position_y, s.position_z, s.velocity_x, s.velocity_y, s.velocity_z])
+}
+
+/// Position (km) and velocity (km/s) distance between two states
+pub fn state_error(a: &State, b: &State) -> (f64, f64) {
+    let norm = |range: std::ops::Range<usize>| range.map(|i| (a[i] - b[i]).powi(2)).sum::<f64>().sqrt();
+    (norm(0..3), norm(3..6))
+}
+
+/// External SGP4 implementation (Vallado's C++, python-sgp4, ...)
+pub trait Reference {
+    fn name(&self) -> &str;
+
+    /// States at each offset from the TLE epoch; `None` where the
+    /// implementation reports a propagation error
+    fn propagate(&self, line1: &str, line2: &str, minutes: &[f64]) -> Result<Vec<Option<State>>, String>;
+}
+
+/// Reference run as a subprocess. Protocol: line 1, line 2 and the
+/// space-separated minutes on stdin; one `x y z vx vy vz` line per minute
+/// on stdout, anything else on a line meaning that sample failed.
+#[derive(Debug, Clone)]
+pub struct CommandReference {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+}
+
+impl CommandReference {
+    /// From `FUZZ_SGP4_REFERENCE`, if set
+    pub fn from_env() -> Option<Self> {
+        let command = std::env::var(REFERENCE_ENV).ok()?;
+        let mut parts = command.split_whitespace();
+        Some(Self {
+            program: parts.next()?.into(),
+            args: parts.map(String::from).collect(),
+        })
+    }
+}
+
+impl Reference for CommandReference {
+    fn name(&self) -> &str {
+        self.program.to_str().unwrap_or("reference")
+    }
+
+    fn propagate(&self, line1: &str, line2: &str, minutes: &[f64]) -> Result<Vec<Option<State>>, String> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("{}: {}", self.name(), e))?;
+        let times: Vec<String> = minutes.iter().map(|m| format!("{:.9}", m)).collect();
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(format!("{}\n{}\n{}\n", line1, line2, times.join(" ")).as_bytes())
+            .map_err(|e| format!("{}: {}", self.name(), e))?;
+        let output = child.wait_with_output().map_err(|e| format!("{}: {}", self.name(), e))?;
+        if !output.status.success() {
+            return Err(format!("{} exited with {}", self.name(), output.status));
+        }
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        let states: Vec<Option<State>> = text
+            .lines()
+            .map(|line| {
+                let values: Vec<f64> = line.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+                State::try_from(values.as_slice()).ok().filter(|s| s.iter().all(|v| v.is_finite()))
+            })
+            .collect();
+        if states.len() != minutes.len() {
+            return Err(format!("{} returned {} states for {} times", self.name(), states.len(), minutes.len()));
+        }
+        Ok(states)
+    }
+}
+
+/// Agreement between the paths at one sample time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub offset_min: f64,
+    /// `"tle"` or the reference name
+    pub against: String,
+    pub position_error_km: f64,
+    pub velocity_error_km_s: f64,
+}
+
+pub struct DifferentialRunner {
+    pub tolerance: Tolerance,
+    pub offsets_min: Vec<f64>,
+    reference: Option<Box<dyn Reference>>,
+}
+
+impl Default for DifferentialRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DifferentialRunner {
+    pub fn new() -> Self {
+        Self {
+            tolerance: Tolerance::default(),
+            offsets_min: DEFAULT_OFFSETS_MIN.to_vec(),
+            reference: None,
+        }
+    }
+
+    /// Runner with the `FUZZ_SGP4_REFERENCE` command attached, if set
+    pub fn from_env() -> Self {
+        match CommandReference::from_env() {
+            Some(reference) => Self::new().with_reference(reference),
+            None => Self::new(),
+        }
+    }
+
+    pub fn with_reference(mut self, reference: impl Reference + 'static) -> Self {
+        self.reference = Some(Box::new(reference));
+        self
+    }
+
+    /// Errors against the direct path at every offset. Samples where all
+    /// paths fail (e.g. decay) are skipped; one path failing alone is an
+    /// `Err`.
+    pub fn compare(&self, elements: &MeanElements, norad_id: u32) -> Result<Vec<Sample>, String> {
+        let direct = direct_elements(elements, norad_id);
+        let (line1, line2) = elements.to_tle_lines(norad_id);
+        let times: Vec<DateTime<Utc>> = self.offsets_min.iter().map(|&m| at_offset(elements.epoch, m)).collect();
+        let direct_states: Vec<Result<State, String>> = times.iter().map(|&t| propagate_direct(&direct, t)).collect();
+
+        let mut samples = Vec::new();
+        let mut record = |offset_min: f64, against: &str, direct: &Result<State, String>, other: Result<State, String>| {
+            match (direct, other) {
+                (Ok(a), Ok(b)) => {
+                    let (position_error_km, velocity_error_km_s) = state_error(a, &b);
+                    samples.push(Sample {
+                        offset_min,
+                        against: against.to_string(),
+                        position_error_km,
+                        velocity_error_km_s,
+                    });
+                    Ok(())
+                }
+                (Err(_), Err(_)) => Ok(()),
+                (Ok(_), Err(e)) => Err(format!("{} failed at +{} min where direct did not: {}", against, offset_min, e)),
+                (Err(e), Ok(_)) => Err(format!("direct failed at +{} min where {} did not: {}", offset_min, against, e)),
+            }
+        };
+
+        for ((&offset, &time), direct) in self.offsets_min.iter().zip(&times).zip(&direct_states) {
+            record(offset, "tle", direct, propagate_tle(&line1, &line2, time))?;
+        }
+
+        if let Some(reference) = &self.reference {
+            let minutes: Vec<f64> = times.iter().map(|t| minutes_between(direct.datetime, t.naive_utc())).collect();
+            let states = reference.propagate(&line1, &line2, &minutes)?;
+            for ((&offset, direct), state) in self.offsets_min.iter().zip(&direct_states).zip(states) {
+                record(offset, reference.name(), direct, state.ok_or_else(|| "propagation failed".to_string()))?;
+            }
+        }
+        Ok(samples)
+    }
+
+    /// All paths agree within `tolerance` at every offset
+    pub fn check(&self, elements: &MeanElements, norad_id: u32) -> Result<(), String> {
+        for sample in self.compare(elements, norad_id)? {
+            if sample.position_error_km > self.tolerance.position_km
+                || sample.velocity_error_km_s > self.tolerance.velocity_km_s
+            {
+                return Err(format!(
+                    "{} off by {:.6} km, {:.9} km/s at +{} min",
+                    sample.against, sample.position_error_km, sample.velocity_error_km_s, sample.offset_min
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fields parsed back from the formatted TLE match `tle_precision`; names
+/// the drifting field when `DifferentialRunner::check` fails
+pub fn check_fields(elements: &MeanElements, norad_id: u32) -> Result<(), String> {
+    let (line1, line2) = elements.to_tle_lines(norad_id);
+    let parsed = MeanElements::from_tle(&line1, &line2).map_err(|e| e.to_string())?;
+    let expected = tle_precision(elements);
+
+    let epoch_error = (parsed.epoch - expected.epoch).num_microseconds().unwrap_or(i64::MAX).abs();
+    if epoch_error > 1 {
+        return Err(format!("epoch {} parsed as {}", expected.epoch, parsed.epoch));
+    }
+    let fields = [
+        ("inclination", expected.inclination_deg, parsed.inclination_deg),
+        ("raan", expected.raan_deg, parsed.raan_deg),
+        ("eccentricity", expected.eccentricity, parsed.eccentricity),
+        ("argument of perigee", expected.arg_perigee_deg, parsed.arg_perigee_deg),
+        ("mean anomaly", expected.mean_anomaly_deg, parsed.mean_anomaly_deg),
+        ("mean motion", expected.mean_motion_rev_day, parsed.mean_motion_rev_day),
+        ("ndot/2", expected.drag.ndot_over_2, parsed.drag.ndot_over_2),
+        ("nddot/6", expected.drag.nddot_over_6, parsed.drag.nddot_over_6),
+        ("bstar", expected.drag.bstar, parsed.drag.bstar),
+    ];
+    for (name, expected, parsed) in fields {
+        if (expected - parsed).abs() > 1e-12 * expected.abs().max(1e-6) {
+            return Err(format!("{} {} parsed as {}\n{}\n{}", name, expected, parsed, line1, line2));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::{edge_elements, mean_elements};
+    use proptest::prelude::*;
+
+    /// Reference that is the direct path with a fixed along-x bias
+    struct Biased(f64);
+
+    impl Reference for Biased {
+        fn name(&self) -> &str {
+            "biased"
+        }
+
+        fn propagate(&self, line1: &str, line2: &str, minutes: &[f64]) -> Result<Vec<Option<State>>, String> {
+            let elements = sgp4::Elements::from_tle(None, line1.as_bytes(), line2.as_bytes()).map_err(|e| format!("{:?}", e))?;
+            let constants = sgp4::Constants::from_elements(&elements).map_err(|e| format!("{:?}", e))?;
+            Ok(minutes
+                .iter()
+                .map(|&m| {
+                    constants.propagate(m).ok().map(|p| {
+                        [p.position[0] + self.0, p.position[1], p.position[2], p.velocity[0], p.velocity[1], p.velocity[2]]
+                    })
+                })
+                .collect())
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        #[test]
+        fn test_direct_and_tle_paths_agree(elements in mean_elements(), norad_id in 1u32..=99_999) {
+            prop_assert_eq!(DifferentialRunner::from_env().check(&elements, norad_id), Ok(()));
+        }
+
+        #[test]
+        fn test_edge_elements_agree(elements in edge_elements()) {
+            prop_assert_eq!(DifferentialRunner::from_env().check(&elements, 12345), Ok(()));
+        }
+
+        #[test]
+        fn test_fields_survive_formatting(elements in prop_oneof![mean_elements(), edge_elements()]) {
+            prop_assert_eq!(check_fields(&elements, 12345), Ok(()));
+        }
+
+        #[test]
+        fn test_reference_bias_is_reported(elements in mean_elements()) {
+            let runner = DifferentialRunner::new().with_reference(Biased(0.01));
+            let samples = runner.compare(&elements, 12345).unwrap();
+            prop_assert!(samples.iter().filter(|s| s.against == "biased").all(|s| (s.position_error_km - 0.01).abs() < 1e-3));
+            prop_assert!(runner.check(&elements, 12345).is_err());
+        }
+    }
+
+    #[test]
+    fn test_exp_field_rounding() {
+        assert!((round_exp_field(-0.116064e-4) + 0.11606e-4).abs() < 1e-18);
+        assert!((round_exp_field(9.999996e-5) - 1e-4).abs() < 1e-18);
+        assert_eq!(round_exp_field(1e-12), 0.0);
+    }
+}
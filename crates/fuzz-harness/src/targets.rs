@@ -0,0 +1,150 @@
+//! Named fuzz targets
+//!
+//! Every strategy/check pair a campaign can run, by a stable name. The
+//! property tests in each module cover the same invariants at a fixed
+//! case count for `cargo test`; campaigns run them with many seeds and
+//! far more cases. Inputs serialise, so failures can be stored in the
+//! corpus and replayed by name.
+
+use crate::reports::{run_target, TargetReport};
+use crate::{coords, elements, graph, objective, runner, tle};
+use orbital_mechanics::tle::{parse_tle_text, validate};
+use proptest::prelude::*;
+use serde::de::DeserializeOwned;
+
+pub struct Target {
+    pub name: &'static str,
+    run: fn(seed: u64, cases: u32) -> TargetReport,
+    replay: fn(input: &serde_json::Value) -> Result<(), String>,
+}
+
+impl Target {
+    pub fn run(&self, seed: u64, cases: u32) -> TargetReport {
+        (self.run)(seed, cases)
+    }
+
+    /// Check one stored input, as serialised in a failure report
+    pub fn replay(&self, input: &serde_json::Value) -> Result<(), String> {
+        (self.replay)(input)
+    }
+}
+
+/// Decode `input` as a value of `strategy` and check it. The strategy is
+/// only there to fix the value type.
+fn replay_input<S>(_strategy: S, input: &serde_json::Value, check: impl Fn(&S::Value) -> Result<(), String>) -> Result<(), String>
+where
+    S: Strategy,
+    S::Value: DeserializeOwned,
+{
+    let value = serde_json::from_value(input.clone()).map_err(|e| format!("undecodable input: {}", e))?;
+    check(&value)
+}
+
+macro_rules! target {
+    ($name:literal, $strategy:expr, $check:expr) => {
+        Target {
+            name: $name,
+            run: |seed, cases| run_target($name, seed, cases, $strategy, $check),
+            replay: |input| replay_input($strategy, input, $check),
+        }
+    };
+}
+
+pub fn all() -> Vec<Target> {
+    vec![
+        target!("coords/geodetic-round-trip", coords::any_geodetic(), coords::check_geodetic_round_trip),
+        target!("coords/eci-round-trip", coords::eci_position(), |&(x, y, z)| coords::check_eci_round_trip(x, y, z)),
+        target!("coords/states-above-ground", coords::eci_state(), coords::check_altitude_positive),
+        target!("tle/valid-records", prop_oneof![tle::tle_record(), tle::edge_tle_record()], |record| {
+            validate(record).map_err(|e| e.to_string())
+        }),
+        target!("tle/corrupted-records", tle::corrupted_tle_record(), |(_, corruption, broken)| {
+            if validate(broken).is_ok() {
+                return Err(format!("{:?} accepted by validate", corruption));
+            }
+            if parse_tle_text(&tle::to_text(&[broken.clone()], &[], &[])).is_ok() {
+                return Err(format!("{:?} accepted by parse_tle_text", corruption));
+            }
+            Ok(())
+        }),
+        target!("sgp4/differential", prop_oneof![elements::mean_elements(), elements::edge_elements()], |e| {
+            runner::DifferentialRunner::from_env().check(e, 12345)
+        }),
+        target!("sgp4/fields", prop_oneof![elements::mean_elements(), elements::edge_elements()], |e| {
+            runner::check_fields(e, 12345)
+        }),
+        target!("graph/find-path", graph::graph_with_endpoints(graph::any_graph()), |(spec, from, to)| {
+            graph::check_find_path(spec, *from, *to)
+        }),
+        target!(
+            "graph/k-shortest-paths",
+            (graph::graph_with_endpoints(prop_oneof![graph::degraded_graph(), graph::walker_mesh()]), 0usize..=5),
+            |((spec, from, to), k)| graph::check_k_shortest_paths(spec, *from, *to, *k)
+        ),
+        target!("graph/route-score", graph::graph_with_endpoints(graph::any_graph()), |(spec, from, to)| {
+            graph::check_route_score(spec, *from, *to)
+        }),
+        target!(
+            "objective/weights",
+            (objective::objective_weights(), objective::invalid_objective_weights()),
+            |(valid, invalid)| objective::check_weights(valid, true).and(objective::check_weights(invalid, false))
+        ),
+        target!("objective/utility-bounds", (objective::objective_spec(), objective::route_metrics()), |(spec, m)| {
+            objective::check_utility_bounds(spec, m)
+        }),
+        target!(
+            "objective/penalty-monotonic",
+            (objective::objective_spec(), objective::route_metrics(), objective::penalty()),
+            |(spec, m, penalty)| objective::check_penalty_monotonic(spec, m, *penalty)
+        ),
+        target!("objective/viability", (objective::objective_spec(), objective::route_metrics()), |(spec, m)| {
+            objective::check_viability(spec, m)
+        }),
+        target!(
+            "objective/selection",
+            (objective::objective_spec(), proptest::collection::vec(objective::route_metrics(), 0..8)),
+            |(spec, candidates)| objective::check_selection(spec, candidates)
+        ),
+        target!(
+            "objective/weights-round-trip",
+            (objective::objective_weights(), objective::route_metrics()),
+            |(weights, m)| objective::check_weights_round_trip(weights, m)
+        ),
+    ]
+}
+
+pub fn find(name: &str) -> Option<Target> {
+    all().into_iter().find(|t| t.name == name)
+}
+
+/// Targets whose name starts with any of `prefixes` (all when empty)
+pub fn matching(prefixes: &[String]) -> Vec<Target> {
+    all()
+        .into_iter()
+        .filter(|t| prefixes.is_empty() || prefixes.iter().any(|p| t.name.starts_with(p.as_str())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_names_are_unique() {
+        let targets = all();
+        let names: HashSet<&str> = targets.iter().map(|t| t.name).collect();
+        assert_eq!(names.len(), targets.len());
+        assert_eq!(matching(&["graph/".to_string()]).len(), 3);
+        assert_eq!(matching(&["objective/".to_string()]).len(), 6);
+        assert!(find("coords/eci-round-trip").is_some());
+    }
+
+    #[test]
+    fn test_every_target_passes_a_short_run() {
+        for target in all() {
+            let report = target.run(1, 8);
+            assert!(report.passed(), "{}: {:?}", target.name, report.outcome);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! TLE line-pair strategies
+//!
+//! Valid records come from formatting generated elements, so they exercise
+//! the same writer the Walker generator and OMM import use. Near-valid
+//! records take a valid pair and apply one `Corruption`; each must be
+//! rejected by `tle::validate` and `tle::parse_tle_text`.
+
+use crate::elements::{edge_elements, mean_elements};
+use orbital_mechanics::tle::TleRecord;
+use orbital_mechanics::walker::tle_checksum;
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Line 2 columns holding the angle, eccentricity and mean-motion fields
+const LINE2_NUMERIC: std::ops::Range<usize> = 8..63;
+
+/// Five-digit catalog numbers
+pub fn norad_id() -> impl Strategy<Value = u32> {
+    prop_oneof![Just(1u32), Just(99_999u32), 1u32..=99_999]
+}
+
+/// Object names as they appear on the name line of a 3LE
+pub fn object_name() -> impl Strategy<Value = String> {
+    "[A-Z][A-Z0-9()/-]{0,11}( [A-Z0-9()/-]{1,11})?"
+}
+
+fn record(norad_id: u32, name: Option<String>, lines: (String, String)) -> TleRecord {
+    TleRecord {
+        name,
+        norad_id,
+        line1: lines.0,
+        line2: lines.1,
+    }
+}
+
+/// Valid element set, optionally named
+pub fn tle_record() -> impl Strategy<Value = TleRecord> {
+    (norad_id(), proptest::option::of(object_name()), mean_elements())
+        .prop_map(|(id, name, elements)| record(id, name, elements.to_tle_lines(id)))
+}
+
+/// Valid element set with fields at the edges of their columns
+pub fn edge_tle_record() -> impl Strategy<Value = TleRecord> {
+    (norad_id(), edge_elements()).prop_map(|(id, elements)| record(id, None, elements.to_tle_lines(id)))
+}
+
+/// One way of breaking a valid line pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Corruption {
+    /// Final digit of line 1 or 2 no longer matches the checksum
+    Checksum { line: u8, delta: u8 },
+    /// Line cut short by `by` characters
+    Truncated { line: u8, by: usize },
+    /// Non-blank characters after column 69
+    Extended { line: u8, extra: String },
+    /// Line 2 carries a different catalog number (checksum still correct)
+    CatalogMismatch { norad_id: u32 },
+    /// Line 2 before line 1
+    Swapped,
+    /// A letter in a line 2 numeric field (checksum still correct)
+    NonNumericField { index: usize },
+    /// A multi-byte character in place of column `column`
+    NonAscii { line: u8, column: usize },
+}
+
+fn line_number() -> impl Strategy<Value = u8> {
+    1u8..=2
+}
+
+pub fn corruption() -> impl Strategy<Value = Corruption> {
+    prop_oneof![
+        (line_number(), 1u8..=9).prop_map(|(line, delta)| Corruption::Checksum { line, delta }),
+        (line_number(), 1usize..=5).prop_map(|(line, by)| Corruption::Truncated { line, by }),
+        (line_number(), "[0-9A-Z]{1,3}").prop_map(|(line, extra)| Corruption::Extended { line, extra }),
+        norad_id().prop_map(|norad_id| Corruption::CatalogMismatch { norad_id }),
+        Just(Corruption::Swapped),
+        any::<usize>().prop_map(|index| Corruption::NonNumericField { index }),
+        (line_number(), 2usize..68).prop_map(|(line, column)| Corruption::NonAscii { line, column }),
+    ]
+}
+
+/// Replace the checksum of a 69-column line
+fn with_checksum(line: &str) -> String {
+    format!("{}{}", &line[..68], tle_checksum(line))
+}
+
+impl Corruption {
+    /// Apply to a valid record. A `CatalogMismatch` that happens to draw
+    /// the record's own number uses the next one instead.
+    pub fn apply(&self, mut record: TleRecord) -> TleRecord {
+        fn pick(record: &mut TleRecord, line: u8) -> &mut String {
+            if line == 1 {
+                &mut record.line1
+            } else {
+                &mut record.line2
+            }
+        }
+
+        match self {
+            Corruption::Checksum { line, delta } => {
+                let text = pick(&mut record, *line);
+                let digit = text.pop().and_then(|c| c.to_digit(10)).unwrap_or(0) as u8;
+                text.push(char::from(b'0' + (digit + delta) % 10));
+            }
+            Corruption::Truncated { line, by } => {
+                let text = pick(&mut record, *line);
+                text.truncate(text.len().saturating_sub(*by));
+            }
+            Corruption::Extended { line, extra } => pick(&mut record, *line).push_str(extra),
+            Corruption::CatalogMismatch { norad_id } => {
+                let mut id = *norad_id;
+                if id == record.norad_id % 100_000 {
+                    id = id % 99_999 + 1;
+                }
+                let line = format!("{}{:05}{}", &record.line2[..2], id, &record.line2[7..]);
+                record.line2 = with_checksum(&line);
+            }
+            Corruption::Swapped => std::mem::swap(&mut record.line1, &mut record.line2),
+            Corruption::NonNumericField { index } => {
+                let digits: Vec<usize> = LINE2_NUMERIC
+                    .filter(|&i| record.line2.as_bytes()[i].is_ascii_digit())
+                    .collect();
+                let column = digits[index % digits.len()];
+                let line = format!("{}X{}", &record.line2[..column], &record.line2[column + 1..]);
+                record.line2 = with_checksum(&line);
+            }
+            Corruption::NonAscii { line, column } => {
+                let text = pick(&mut record, *line);
+                text.replace_range(*column..*column + 1, "é");
+            }
+        }
+        record
+    }
+}
+
+/// Valid record plus a broken copy of it
+pub fn corrupted_tle_record() -> impl Strategy<Value = (TleRecord, Corruption, TleRecord)> {
+    (tle_record(), corruption()).prop_map(|(valid, corruption)| {
+        let broken = corruption.apply(valid.clone());
+        (valid, corruption, broken)
+    })
+}
+
+/// Render records as 2LE/3LE text: names with or without the `0 ` prefix,
+/// and blank lines between sets
+pub fn to_text(records: &[TleRecord], zero_prefix: &[bool], blank_lines: &[usize]) -> String {
+    let mut text = String::new();
+    for (i, record) in records.iter().enumerate() {
+        text.push_str(&"\n".repeat(blank_lines.get(i).copied().unwrap_or(0)));
+        if let Some(name) = &record.name {
+            let prefix = if zero_prefix.get(i).copied().unwrap_or(false) { "0 " } else { "" };
+            text.push_str(&format!("{}{}\n", prefix, name));
+        }
+        text.push_str(&format!("{}\n{}\n", record.line1, record.line2));
+    }
+    text
+}
+
+/// Block of 1-8 valid element sets and the records it should parse to
+pub fn tle_text() -> impl Strategy<Value = (String, Vec<TleRecord>)> {
+    proptest::collection::vec((tle_record(), any::<bool>(), 0usize..=2), 1..=8).prop_map(|sets| {
+        let records: Vec<TleRecord> = sets.iter().map(|(r, _, _)| r.clone()).collect();
+        let prefixes: Vec<bool> = sets.iter().map(|(_, p, _)| *p).collect();
+        let blanks: Vec<usize> = sets.iter().map(|(_, _, b)| *b).collect();
+        (to_text(&records, &prefixes, &blanks), records)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orbital_mechanics::tle::{parse_tle_text, validate};
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        #[test]
+        fn test_valid_records_validate(record in tle_record()) {
+            prop_assert!(validate(&record).is_ok(), "{:?}", validate(&record));
+        }
+
+        #[test]
+        fn test_edge_records_validate(record in edge_tle_record()) {
+            prop_assert!(validate(&record).is_ok(), "{}\n{}", record.line1, record.line2);
+        }
+
+        #[test]
+        fn test_corrupted_records_are_rejected((_valid, corruption, broken) in corrupted_tle_record()) {
+            prop_assert!(validate(&broken).is_err(), "{:?} accepted", corruption);
+            let text = to_text(&[broken], &[], &[]);
+            prop_assert!(parse_tle_text(&text).is_err(), "{:?} parsed", corruption);
+        }
+
+        #[test]
+        fn test_text_blocks_round_trip((text, records) in tle_text()) {
+            let parsed = parse_tle_text(&text).unwrap();
+            prop_assert_eq!(parsed.len(), records.len());
+            for (parsed, expected) in parsed.iter().zip(&records) {
+                prop_assert_eq!(&parsed.line1, &expected.line1);
+                prop_assert_eq!(&parsed.line2, &expected.line2);
+                prop_assert_eq!(parsed.norad_id, expected.norad_id);
+                prop_assert_eq!(&parsed.name, &expected.name);
+            }
+        }
+    }
+}
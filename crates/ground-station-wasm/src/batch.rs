@@ -0,0 +1,216 @@
+//! Batch Look Angles
+//!
+//! Look angles for one station against many satellites, or many stations
+//! against one satellite, with the same geometry as
+//! `calculate_look_angles`. Positions are held structure-of-arrays and
+//! each station's ENU rotation is precomputed, so the inner loops are
+//! straight-line arithmetic over equal-length columns that the compiler
+//! can vectorize; `atan2` runs once per pair at the end.
+
+use crate::{EARTH_RADIUS_KM, DEG_TO_RAD, RAD_TO_DEG};
+
+/// Cartesian points (spherical Earth, km), one column per axis
+#[derive(Debug, Clone, Default)]
+pub struct PointBatch {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub z: Vec<f64>,
+}
+
+impl PointBatch {
+    /// From `(latitude_deg, longitude_deg, altitude_km)` triples
+    pub fn from_geodetic(points: impl IntoIterator<Item = (f64, f64, f64)>) -> Self {
+        let mut batch = Self::default();
+        for (lat, lon, alt_km) in points {
+            let (x, y, z) = to_cartesian(lat, lon, alt_km);
+            batch.x.push(x);
+            batch.y.push(y);
+            batch.z.push(z);
+        }
+        batch
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+}
+
+/// Look angles, one column per quantity, in input order
+#[derive(Debug, Clone, Default)]
+pub struct LookAngleBatch {
+    pub azimuth_deg: Vec<f64>,
+    pub elevation_deg: Vec<f64>,
+    pub range_km: Vec<f64>,
+}
+
+/// Station sites with their topocentric frames precomputed
+#[derive(Debug, Clone, Default)]
+pub struct StationBatch {
+    sites: PointBatch,
+    // Rows of the ECEF -> ENU rotation
+    east: [Vec<f64>; 2],
+    north: [Vec<f64>; 3],
+    up: [Vec<f64>; 3],
+}
+
+impl StationBatch {
+    /// From `(latitude_deg, longitude_deg, altitude_km)` triples
+    pub fn from_geodetic(stations: impl IntoIterator<Item = (f64, f64, f64)>) -> Self {
+        let mut batch = Self::default();
+        for (lat, lon, alt_km) in stations {
+            let (x, y, z) = to_cartesian(lat, lon, alt_km);
+            batch.sites.x.push(x);
+            batch.sites.y.push(y);
+            batch.sites.z.push(z);
+            let (sin_lat, cos_lat) = (lat * DEG_TO_RAD).sin_cos();
+            let (sin_lon, cos_lon) = (lon * DEG_TO_RAD).sin_cos();
+            batch.east[0].push(-sin_lon);
+            batch.east[1].push(cos_lon);
+            batch.north[0].push(-sin_lat * cos_lon);
+            batch.north[1].push(-sin_lat * sin_lon);
+            batch.north[2].push(cos_lat);
+            batch.up[0].push(cos_lat * cos_lon);
+            batch.up[1].push(cos_lat * sin_lon);
+            batch.up[2].push(sin_lat);
+        }
+        batch
+    }
+
+    pub fn len(&self) -> usize {
+        self.sites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sites.is_empty()
+    }
+
+    /// Station `i` against every satellite in `sats`
+    #[allow(clippy::needless_range_loop)]
+    pub fn station_to_satellites(&self, i: usize, sats: &PointBatch) -> LookAngleBatch {
+        let n = sats.len();
+        let (mut east, mut north, mut up, mut range) = (vec![0.0; n], vec![0.0; n], vec![0.0; n], vec![0.0; n]);
+        let (sx, sy, sz) = (self.sites.x[i], self.sites.y[i], self.sites.z[i]);
+        let (e0, e1) = (self.east[0][i], self.east[1][i]);
+        let (n0, n1, n2) = (self.north[0][i], self.north[1][i], self.north[2][i]);
+        let (u0, u1, u2) = (self.up[0][i], self.up[1][i], self.up[2][i]);
+        for j in 0..n {
+            let (dx, dy, dz) = (sats.x[j] - sx, sats.y[j] - sy, sats.z[j] - sz);
+            range[j] = (dx * dx + dy * dy + dz * dz).sqrt();
+            east[j] = e0 * dx + e1 * dy;
+            north[j] = n0 * dx + n1 * dy + n2 * dz;
+            up[j] = u0 * dx + u1 * dy + u2 * dz;
+        }
+        angles(&east, &north, &up, range)
+    }
+
+    /// Every station against the satellite at `sat` (Cartesian, km)
+    #[allow(clippy::needless_range_loop)]
+    pub fn stations_to_satellite(&self, sat: (f64, f64, f64)) -> LookAngleBatch {
+        let n = self.len();
+        let (mut east, mut north, mut up, mut range) = (vec![0.0; n], vec![0.0; n], vec![0.0; n], vec![0.0; n]);
+        let s = &self.sites;
+        for i in 0..n {
+            let (dx, dy, dz) = (sat.0 - s.x[i], sat.1 - s.y[i], sat.2 - s.z[i]);
+            range[i] = (dx * dx + dy * dy + dz * dz).sqrt();
+            east[i] = self.east[0][i] * dx + self.east[1][i] * dy;
+            north[i] = self.north[0][i] * dx + self.north[1][i] * dy + self.north[2][i] * dz;
+            up[i] = self.up[0][i] * dx + self.up[1][i] * dy + self.up[2][i] * dz;
+        }
+        angles(&east, &north, &up, range)
+    }
+}
+
+/// Indices with elevation at or above `min_elevation_deg`
+pub fn above(angles: &LookAngleBatch, min_elevation_deg: f64) -> impl Iterator<Item = usize> + '_ {
+    angles
+        .elevation_deg
+        .iter()
+        .enumerate()
+        .filter(move |(_, el)| **el >= min_elevation_deg)
+        .map(|(i, _)| i)
+}
+
+/// Position in the Cartesian frame the batches use
+pub fn to_cartesian(lat_deg: f64, lon_deg: f64, alt_km: f64) -> (f64, f64, f64) {
+    let (sin_lat, cos_lat) = (lat_deg * DEG_TO_RAD).sin_cos();
+    let (sin_lon, cos_lon) = (lon_deg * DEG_TO_RAD).sin_cos();
+    let r = EARTH_RADIUS_KM + alt_km;
+    (r * cos_lat * cos_lon, r * cos_lat * sin_lon, r * sin_lat)
+}
+
+fn angles(east: &[f64], north: &[f64], up: &[f64], range_km: Vec<f64>) -> LookAngleBatch {
+    let azimuth_deg = east
+        .iter()
+        .zip(north)
+        .map(|(e, n)| {
+            let az = e.atan2(*n) * RAD_TO_DEG;
+            if az < 0.0 { az + 360.0 } else { az }
+        })
+        .collect();
+    let elevation_deg = east
+        .iter()
+        .zip(north)
+        .zip(up)
+        .map(|((e, n), u)| u.atan2((e * e + n * n).sqrt()) * RAD_TO_DEG)
+        .collect();
+    LookAngleBatch {
+        azimuth_deg,
+        elevation_deg,
+        range_km,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_look_angles;
+
+    fn grid() -> Vec<(f64, f64, f64)> {
+        let mut points = Vec::new();
+        for lat in (-80..=80).step_by(20) {
+            for lon in (-180..180).step_by(45) {
+                points.push((lat as f64 + 0.3, lon as f64 - 0.7, 10_500.0 + lat as f64));
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_batch_matches_scalar() {
+        let stations = [(51.5, -0.1, 0.02), (-33.9, 18.4, 0.5), (0.0, 0.0, 0.0)];
+        let sats = grid();
+        let station_batch = StationBatch::from_geodetic(stations);
+        let sat_batch = PointBatch::from_geodetic(sats.iter().copied());
+
+        for (i, &(lat, lon, alt)) in stations.iter().enumerate() {
+            let batch = station_batch.station_to_satellites(i, &sat_batch);
+            for (j, &(slat, slon, salt)) in sats.iter().enumerate() {
+                let scalar = calculate_look_angles(lat, lon, alt, slat, slon, salt);
+                assert!((batch.azimuth_deg[j] - scalar.azimuth_deg).abs() < 1e-9);
+                assert!((batch.elevation_deg[j] - scalar.elevation_deg).abs() < 1e-9);
+                assert!((batch.range_km[j] - scalar.range_km).abs() < 1e-9);
+            }
+        }
+
+        let (slat, slon, salt) = sats[17];
+        let batch = station_batch.stations_to_satellite(to_cartesian(slat, slon, salt));
+        for (i, &(lat, lon, alt)) in stations.iter().enumerate() {
+            let scalar = calculate_look_angles(lat, lon, alt, slat, slon, salt);
+            assert!((batch.elevation_deg[i] - scalar.elevation_deg).abs() < 1e-9);
+            assert!((batch.azimuth_deg[i] - scalar.azimuth_deg).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_above_mask() {
+        let stations = StationBatch::from_geodetic([(0.0, 0.0, 0.0)]);
+        let sats = PointBatch::from_geodetic([(0.0, 0.0, 10_500.0), (0.0, 180.0, 10_500.0), (0.0, 40.0, 10_500.0)]);
+        let angles = stations.station_to_satellites(0, &sats);
+        assert_eq!(above(&angles, 10.0).collect::<Vec<_>>(), vec![0, 2]);
+        assert!(StationBatch::default().is_empty() && sats.len() == 3);
+    }
+}
@@ -0,0 +1,188 @@
+//! Beam footprint placement
+//!
+//! Classifies where a station sits inside a satellite's downlink beam from
+//! geometry alone: the boresight (aimed at a focal point on the ground),
+//! the station's off-axis angle from it, the beam divergence and the slant
+//! range. The beam is treated as Gaussian, so the off-axis pointing loss is
+//! `8.686 * (θ / θ½)²` dB, where θ½ is the divergence half-angle (the 1/e²
+//! intensity edge).
+//!
+//! All positions are Earth-centred Cartesian in km (any frame, as long as
+//! satellite, focal point and station share it).
+
+use serde::{Deserialize, Serialize};
+
+/// Full-angle divergence of the 25 cm MEO terminal at 1550 nm, with
+/// margin over the diffraction limit for jitter
+pub const DEFAULT_DIVERGENCE_URAD: f64 = 20.0;
+
+/// Zone boundaries as a fraction of the divergence half-angle
+const CORE_FRACTION: f64 = 0.5;
+const MAIN_FRACTION: f64 = 0.8;
+
+/// 10·log10(e²): Gaussian loss in dB per (θ/θ½)²
+const GAUSSIAN_DB_PER_UNIT: f64 = 8.685_889_638;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BeamZone {
+    /// Within half the half-angle: under ~2.2 dB pointing loss
+    Core,
+    /// Up to 0.8 of the half-angle: under ~5.6 dB
+    Main,
+    /// Out to the 1/e² edge: under ~8.7 dB
+    Edge,
+    /// Beyond the 1/e² edge
+    Outside,
+}
+
+impl BeamZone {
+    /// Zone for an off-axis angle expressed as a fraction of the half-angle
+    pub fn from_normalized_offset(offset: f64) -> Self {
+        if offset <= CORE_FRACTION {
+            BeamZone::Core
+        } else if offset <= MAIN_FRACTION {
+            BeamZone::Main
+        } else if offset <= 1.0 {
+            BeamZone::Edge
+        } else {
+            BeamZone::Outside
+        }
+    }
+}
+
+/// A satellite beam aimed at a focal point
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BeamPointing {
+    pub satellite_km: [f64; 3],
+    pub focal_point_km: [f64; 3],
+    /// Full-angle divergence (µrad)
+    pub divergence_urad: f64,
+}
+
+/// Where a station sits in the beam
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BeamPlacement {
+    pub zone: BeamZone,
+    /// Angle between boresight and the satellite-to-station line (µrad)
+    pub off_axis_urad: f64,
+    /// Off-axis angle / divergence half-angle (1.0 = 1/e² edge)
+    pub normalized_offset: f64,
+    /// Ground distance from the focal point (km)
+    pub focal_offset_km: f64,
+    pub slant_range_km: f64,
+    /// 1/e² footprint radius at the station's range (km)
+    pub footprint_radius_km: f64,
+    pub pointing_loss_db: f64,
+}
+
+impl BeamPointing {
+    pub fn new(satellite_km: [f64; 3], focal_point_km: [f64; 3]) -> Self {
+        Self {
+            satellite_km,
+            focal_point_km,
+            divergence_urad: DEFAULT_DIVERGENCE_URAD,
+        }
+    }
+
+    pub fn with_divergence(mut self, divergence_urad: f64) -> Self {
+        self.divergence_urad = divergence_urad;
+        self
+    }
+
+    fn half_angle_rad(&self) -> f64 {
+        self.divergence_urad * 1e-6 / 2.0
+    }
+
+    /// Classify a station at `station_km`
+    pub fn place(&self, station_km: [f64; 3]) -> BeamPlacement {
+        let boresight = sub(self.focal_point_km, self.satellite_km);
+        let to_station = sub(station_km, self.satellite_km);
+        let slant_range_km = norm(to_station);
+
+        let cos_angle = dot(boresight, to_station) / (norm(boresight) * slant_range_km);
+        let off_axis = if cos_angle.is_finite() {
+            cos_angle.clamp(-1.0, 1.0).acos()
+        } else {
+            0.0
+        };
+
+        let half_angle = self.half_angle_rad();
+        let normalized_offset = if half_angle > 0.0 {
+            off_axis / half_angle
+        } else {
+            f64::INFINITY
+        };
+
+        BeamPlacement {
+            zone: BeamZone::from_normalized_offset(normalized_offset),
+            off_axis_urad: off_axis * 1e6,
+            normalized_offset,
+            focal_offset_km: norm(sub(station_km, self.focal_point_km)),
+            slant_range_km,
+            footprint_radius_km: slant_range_km * half_angle.tan(),
+            pointing_loss_db: GAUSSIAN_DB_PER_UNIT * normalized_offset.powi(2),
+        }
+    }
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EARTH_R: f64 = 6378.137;
+
+    /// Satellite at 10,500 km over (0°, 0°), beam aimed at the sub-satellite point
+    fn zenith_beam() -> BeamPointing {
+        BeamPointing::new([EARTH_R + 10_500.0, 0.0, 0.0], [EARTH_R, 0.0, 0.0])
+    }
+
+    /// Point on the equator `km` east of the sub-satellite point
+    fn east_of_focus(km: f64) -> [f64; 3] {
+        let angle = km / EARTH_R;
+        [EARTH_R * angle.cos(), EARTH_R * angle.sin(), 0.0]
+    }
+
+    #[test]
+    fn test_focal_point_is_core() {
+        let placement = zenith_beam().place([EARTH_R, 0.0, 0.0]);
+        assert_eq!(placement.zone, BeamZone::Core);
+        assert!(placement.off_axis_urad < 1e-3);
+        assert!((placement.slant_range_km - 10_500.0).abs() < 1e-6);
+        // 10 µrad half-angle over 10,500 km is a ~105 m footprint
+        assert!((placement.footprint_radius_km - 0.105).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_zones_widen_with_offset() {
+        let beam = zenith_beam();
+        let zones: Vec<BeamZone> = [0.02, 0.07, 0.1, 0.2]
+            .iter()
+            .map(|km| beam.place(east_of_focus(*km)).zone)
+            .collect();
+        assert_eq!(zones, [BeamZone::Core, BeamZone::Main, BeamZone::Edge, BeamZone::Outside]);
+
+        let edge = beam.place(east_of_focus(0.1));
+        assert!(edge.pointing_loss_db > 5.6 && edge.pointing_loss_db <= GAUSSIAN_DB_PER_UNIT);
+        assert!((edge.focal_offset_km - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_wider_beam_recovers_station() {
+        let station = east_of_focus(0.2);
+        assert_eq!(zenith_beam().place(station).zone, BeamZone::Outside);
+        assert_eq!(zenith_beam().with_divergence(80.0).place(station).zone, BeamZone::Core);
+    }
+}
@@ -0,0 +1,231 @@
+//! Station Command Channel
+//!
+//! Typed commands sent from the gateway to a station over NATS
+//! request-reply on `orbital.gs.{id}.cmd`. The station applies the command
+//! to its state and replies with a `CommandAck`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::door::{DoorController, DoorState};
+use crate::{calculate_look_angles, GroundStationState, PointingAngles};
+
+/// NATS subject a station listens on for commands
+pub fn command_subject(station_id: &str) -> String {
+    format!("orbital.gs.{}.cmd", station_id)
+}
+
+/// Operator command
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum StationCommand {
+    Slew { azimuth_deg: f64, elevation_deg: f64 },
+    OpenDoor,
+    CloseDoor,
+    StartTracking {
+        norad_id: u32,
+        latitude_deg: f64,
+        longitude_deg: f64,
+        altitude_km: f64,
+    },
+    StopTracking,
+    /// Weather hold closes the door and refuses door/tracking commands
+    SetWeatherHold { hold: bool },
+}
+
+/// Command as sent on the wire
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandEnvelope {
+    pub command_id: String,
+    pub station_id: String,
+    pub issued_unix_ms: i64,
+    #[serde(flatten)]
+    pub command: StationCommand,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AckStatus {
+    Accepted,
+    Rejected,
+}
+
+/// Station reply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandAck {
+    pub command_id: String,
+    pub station_id: String,
+    pub status: AckStatus,
+    pub message: Option<String>,
+    /// Station state after applying the command
+    pub state: Option<GroundStationState>,
+}
+
+/// Apply `command` to a station. Returns `Err(reason)` if refused.
+pub fn apply_command(
+    state: &mut GroundStationState,
+    door: &mut DoorController,
+    command: &StationCommand,
+) -> Result<(), String> {
+    let door_allowed = |state: &GroundStationState| {
+        if state.weather_hold {
+            Err("Station is on weather hold".to_string())
+        } else if state.door_state == DoorState::Fault {
+            Err("Door is in fault state".to_string())
+        } else {
+            Ok(())
+        }
+    };
+
+    match command {
+        StationCommand::Slew { azimuth_deg, elevation_deg } => {
+            if !(0.0..=90.0).contains(elevation_deg) {
+                return Err(format!("Elevation {} outside 0-90°", elevation_deg));
+            }
+            state.target_pointing = Some(PointingAngles {
+                azimuth_deg: azimuth_deg.rem_euclid(360.0),
+                elevation_deg: *elevation_deg,
+                range_km: 0.0,
+                doppler_shift_hz: 0.0,
+            });
+        }
+        StationCommand::OpenDoor => {
+            door_allowed(state)?;
+            door.open(&mut state.door_state);
+        }
+        StationCommand::CloseDoor => door.close(&mut state.door_state),
+        StationCommand::StartTracking {
+            norad_id,
+            latitude_deg,
+            longitude_deg,
+            altitude_km,
+        } => {
+            door_allowed(state)?;
+            let angles = calculate_look_angles(
+                state.config.latitude_deg,
+                state.config.longitude_deg,
+                state.config.altitude_m / 1000.0,
+                *latitude_deg,
+                *longitude_deg,
+                *altitude_km,
+            );
+            if angles.elevation_deg < state.config.min_elevation_deg {
+                return Err(format!(
+                    "NORAD {} at {:.1}° is below minimum elevation {:.1}°",
+                    norad_id, angles.elevation_deg, state.config.min_elevation_deg
+                ));
+            }
+            state.tracking_satellite = Some(*norad_id);
+            state.target_pointing = Some(angles);
+            door.open(&mut state.door_state);
+        }
+        StationCommand::StopTracking => {
+            state.tracking_satellite = None;
+            state.target_pointing = None;
+            door.close(&mut state.door_state);
+        }
+        StationCommand::SetWeatherHold { hold } => {
+            state.weather_hold = *hold;
+            if *hold {
+                state.tracking_satellite = None;
+                state.target_pointing = None;
+                door.close(&mut state.door_state);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply an envelope and build the reply
+pub fn handle_envelope(
+    state: &mut GroundStationState,
+    door: &mut DoorController,
+    envelope: &CommandEnvelope,
+) -> CommandAck {
+    let result = if envelope.station_id != state.config.id {
+        Err(format!("Command addressed to {}", envelope.station_id))
+    } else {
+        apply_command(state, door, &envelope.command)
+    };
+
+    CommandAck {
+        command_id: envelope.command_id.clone(),
+        station_id: state.config.id.clone(),
+        status: if result.is_ok() { AckStatus::Accepted } else { AckStatus::Rejected },
+        message: result.err(),
+        state: Some(state.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GroundStationConfig;
+
+    fn station() -> GroundStationState {
+        GroundStationState {
+            config: GroundStationConfig {
+                id: "GS-001".to_string(),
+                latitude_deg: 34.742,
+                longitude_deg: -120.5724,
+                ..Default::default()
+            },
+            current_pointing: PointingAngles {
+                azimuth_deg: 0.0,
+                elevation_deg: 90.0,
+                range_km: 0.0,
+                doppler_shift_hz: 0.0,
+            },
+            target_pointing: None,
+            door_state: DoorState::Closed,
+            tracking_satellite: None,
+            link_margin_db: 0.0,
+            weather_score: 1.0,
+            weather_hold: false,
+            last_update_unix: 0,
+        }
+    }
+
+    #[test]
+    fn test_weather_hold_blocks_door() {
+        let mut state = station();
+        let mut door = DoorController::new();
+
+        apply_command(&mut state, &mut door, &StationCommand::SetWeatherHold { hold: true }).unwrap();
+        assert!(apply_command(&mut state, &mut door, &StationCommand::OpenDoor).is_err());
+        assert_eq!(state.door_state, DoorState::Closed);
+
+        apply_command(&mut state, &mut door, &StationCommand::SetWeatherHold { hold: false }).unwrap();
+        apply_command(&mut state, &mut door, &StationCommand::OpenDoor).unwrap();
+        assert_eq!(state.door_state, DoorState::Opening);
+    }
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let envelope = CommandEnvelope {
+            command_id: "c1".to_string(),
+            station_id: "GS-001".to_string(),
+            issued_unix_ms: 0,
+            command: StationCommand::Slew { azimuth_deg: 370.0, elevation_deg: 45.0 },
+        };
+        let json = serde_json::to_string(&envelope).unwrap();
+        assert!(json.contains("\"command\":\"slew\""));
+
+        let parsed: CommandEnvelope = serde_json::from_str(&json).unwrap();
+        let mut state = station();
+        let ack = handle_envelope(&mut state, &mut DoorController::new(), &parsed);
+        assert_eq!(ack.status, AckStatus::Accepted);
+        assert_eq!(state.target_pointing.unwrap().azimuth_deg, 10.0);
+    }
+
+    #[test]
+    fn test_wrong_station_rejected() {
+        let envelope = CommandEnvelope {
+            command_id: "c2".to_string(),
+            station_id: "GS-999".to_string(),
+            issued_unix_ms: 0,
+            command: StationCommand::StopTracking,
+        };
+        let ack = handle_envelope(&mut station(), &mut DoorController::new(), &envelope);
+        assert_eq!(ack.status, AckStatus::Rejected);
+    }
+}
@@ -19,6 +19,96 @@ pub struct ContactWindow {
     pub duration_sec: f64,
 }
 
+/// Pass-level acquisition model
+///
+/// The terminal opens loop on an ephemeris prediction with Gaussian
+/// pointing error (`pointing_sigma_urad` per axis, so a Rayleigh radial
+/// miss) and spirals out to `scan_radius_urad` to find the beacon. A full
+/// spiral takes `scan_time_s` and covers area linearly in time, so a
+/// partial scan reaches `scan_radius_urad * sqrt(fraction)`. Scans repeat
+/// for as long as the pass leaves `min_track_s` of tracking after them.
+/// The beacon is only there to find under clear sky, taken as the
+/// station's weather score.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AcquisitionModel {
+    /// 1σ open-loop pointing error per axis (µrad)
+    pub pointing_sigma_urad: f64,
+    /// Radius of the spiral acquisition scan (µrad)
+    pub scan_radius_urad: f64,
+    /// Time for one full spiral (s)
+    pub scan_time_s: f64,
+    /// Tracking time a pass must leave after acquisition to be useful (s)
+    pub min_track_s: f64,
+}
+
+impl Default for AcquisitionModel {
+    /// TLE-grade MEO ephemeris (~1 km at 10,000 km) and a 3σ spiral
+    fn default() -> Self {
+        Self {
+            pointing_sigma_urad: 100.0,
+            scan_radius_urad: 300.0,
+            scan_time_s: 30.0,
+            min_track_s: 60.0,
+        }
+    }
+}
+
+/// Acquisition probability of one pass and its factors
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AcquisitionEstimate {
+    /// Overall probability (product of the factors below)
+    pub probability: f64,
+    /// Chance one full scan covers the satellite
+    pub per_scan: f64,
+    /// Chance any scan that fits in the pass covers it
+    pub pointing_and_timing: f64,
+    /// Chance of a clear line of sight
+    pub weather: f64,
+    /// Scans that fit before `min_track_s` runs out (fractional)
+    pub scans: f64,
+}
+
+impl AcquisitionModel {
+    /// Chance a scan covering `fraction` of the spiral finds the satellite
+    fn coverage(&self, fraction: f64) -> f64 {
+        if self.pointing_sigma_urad <= 0.0 {
+            return if fraction > 0.0 { 1.0 } else { 0.0 };
+        }
+        let reach_sq = self.scan_radius_urad.powi(2) * fraction.clamp(0.0, 1.0);
+        1.0 - (-reach_sq / (2.0 * self.pointing_sigma_urad.powi(2))).exp()
+    }
+
+    /// Acquisition probability of a pass of `duration_sec` at a station
+    /// with `weather_score` (0-1)
+    pub fn estimate(&self, duration_sec: f64, weather_score: f64) -> AcquisitionEstimate {
+        let available = (duration_sec - self.min_track_s).max(0.0);
+        let scans = if self.scan_time_s > 0.0 {
+            available / self.scan_time_s
+        } else if available > 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+        let per_scan = self.coverage(1.0);
+        let miss = (1.0 - per_scan).powf(scans.floor()) * (1.0 - self.coverage(scans.fract()));
+        let pointing_and_timing = (1.0 - miss).clamp(0.0, 1.0);
+        let weather = weather_score.clamp(0.0, 1.0);
+
+        AcquisitionEstimate {
+            probability: pointing_and_timing * weather,
+            per_scan,
+            pointing_and_timing,
+            weather,
+            scans,
+        }
+    }
+
+    pub fn estimate_window(&self, window: &ContactWindow, weather_score: f64) -> AcquisitionEstimate {
+        self.estimate(window.duration_sec, weather_score)
+    }
+}
+
 /// Contact window calculator
 pub struct ContactCalculator {
     config: GroundStationConfig,
@@ -147,4 +237,30 @@ mod tests {
         // Satellite on opposite side of Earth should not be visible
         assert!(!calc.is_visible(-34.0, 62.0, 500.0));
     }
+
+    #[test]
+    fn test_acquisition_probability() {
+        let model = AcquisitionModel::default();
+        // 3σ spiral: 1 - e^-4.5
+        let long = model.estimate(600.0, 1.0);
+        assert!((long.per_scan - (1.0 - (-4.5f64).exp())).abs() < 1e-12);
+        assert!(long.probability > 0.999);
+        assert_eq!(long.scans, 18.0);
+
+        // Too short to scan and still track
+        assert_eq!(model.estimate(60.0, 1.0).probability, 0.0);
+
+        // Half a spiral reaches 300·√0.5 µrad
+        let half = model.estimate(75.0, 1.0);
+        assert!((half.probability - (1.0 - (-2.25f64).exp())).abs() < 1e-12);
+        assert!(half.probability < model.estimate(90.0, 1.0).probability);
+
+        // Weather scales it, worse pointing lowers it
+        assert!((model.estimate(600.0, 0.5).probability - 0.5 * long.probability).abs() < 1e-12);
+        let loose = AcquisitionModel {
+            pointing_sigma_urad: 400.0,
+            ..model
+        };
+        assert!(loose.estimate(90.0, 1.0).probability < model.estimate(90.0, 1.0).probability);
+    }
 }
@@ -0,0 +1,184 @@
+//! Interpolated Ephemeris
+//!
+//! The twin has no propagator of its own. The gateway hands it ECI state
+//! nodes (`GET /satellites/:id/ephemeris`, or the position stream's
+//! `position_eci_km`/`velocity_eci_km_s`), and pointing between them comes
+//! from cubic Hermite interpolation, so the UI can animate at frame rate
+//! without a request per frame.
+//!
+//! Positions are inertial; rotated by GMST at the query time they give a
+//! spherical sub-satellite point, which is what `calculate_look_angles`
+//! expects.
+
+use serde::{Deserialize, Serialize};
+use crate::{calculate_look_angles, GroundStationConfig, PointingAngles, SatellitePosition, EARTH_RADIUS_KM, RAD_TO_DEG};
+
+/// Greenwich mean sidereal time at `unix_s` (degrees), as the gateway
+/// computes it
+pub fn gmst_deg(unix_s: f64) -> f64 {
+    let days = (unix_s - 946_728_000.0) / 86_400.0;
+    (280.460_618_37 + 360.985_647_366_29 * days).rem_euclid(360.0)
+}
+
+/// One propagated state
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EphemerisNode {
+    pub unix_s: f64,
+    pub position_km: [f64; 3],
+    pub velocity_km_s: [f64; 3],
+}
+
+/// Time-ordered nodes of one satellite, oldest dropped past `max_nodes`
+#[derive(Debug, Clone)]
+pub struct Ephemeris {
+    pub norad_id: u32,
+    max_nodes: usize,
+    nodes: Vec<EphemerisNode>,
+}
+
+impl Ephemeris {
+    pub fn new(norad_id: u32, max_nodes: usize) -> Self {
+        Self {
+            norad_id,
+            max_nodes: max_nodes.max(2),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Add a node, replacing any at the same time
+    pub fn push(&mut self, node: EphemerisNode) {
+        match self.nodes.binary_search_by(|n| n.unix_s.total_cmp(&node.unix_s)) {
+            Ok(i) => self.nodes[i] = node,
+            Err(i) => self.nodes.insert(i, node),
+        }
+        if self.nodes.len() > self.max_nodes {
+            let excess = self.nodes.len() - self.max_nodes;
+            self.nodes.drain(..excess);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Interpolated position and velocity; `None` outside the node span
+    pub fn state_at(&self, unix_s: f64) -> Option<([f64; 3], [f64; 3])> {
+        let i = self.nodes.partition_point(|n| n.unix_s <= unix_s);
+        if i == 0 {
+            return None;
+        }
+        let a = &self.nodes[i - 1];
+        if a.unix_s == unix_s {
+            return Some((a.position_km, a.velocity_km_s));
+        }
+        let b = self.nodes.get(i)?;
+
+        let h = b.unix_s - a.unix_s;
+        let s = (unix_s - a.unix_s) / h;
+        let (s2, s3) = (s * s, s * s * s);
+        let (h00, h10, h01, h11) = (2.0 * s3 - 3.0 * s2 + 1.0, s3 - 2.0 * s2 + s, -2.0 * s3 + 3.0 * s2, s3 - s2);
+        let (d00, d10, d01, d11) = (6.0 * s2 - 6.0 * s, 3.0 * s2 - 4.0 * s + 1.0, -6.0 * s2 + 6.0 * s, 3.0 * s2 - 2.0 * s);
+
+        let mut position = [0.0; 3];
+        let mut velocity = [0.0; 3];
+        for k in 0..3 {
+            let (p0, v0, p1, v1) = (a.position_km[k], a.velocity_km_s[k], b.position_km[k], b.velocity_km_s[k]);
+            position[k] = h00 * p0 + h10 * h * v0 + h01 * p1 + h11 * h * v1;
+            velocity[k] = (d00 * p0 + d10 * h * v0 + d01 * p1 + d11 * h * v1) / h;
+        }
+        Some((position, velocity))
+    }
+
+    /// Sub-satellite point at `unix_s`
+    pub fn position_at(&self, unix_s: f64) -> Option<SatellitePosition> {
+        let ([x, y, z], _) = self.state_at(unix_s)?;
+        let r = (x * x + y * y + z * z).sqrt();
+        let longitude_deg = y.atan2(x) * RAD_TO_DEG - gmst_deg(unix_s);
+        Some(SatellitePosition {
+            norad_id: self.norad_id,
+            latitude_deg: (z / r).asin() * RAD_TO_DEG,
+            longitude_deg: (longitude_deg + 180.0).rem_euclid(360.0) - 180.0,
+            altitude_km: r - EARTH_RADIUS_KM,
+            epoch_unix: unix_s.floor() as i64,
+        })
+    }
+
+    /// Pointing from `config`'s site at `unix_s`
+    pub fn look_angles_at(&self, config: &GroundStationConfig, unix_s: f64) -> Option<PointingAngles> {
+        let sat = self.position_at(unix_s)?;
+        Some(calculate_look_angles(
+            config.latitude_deg,
+            config.longitude_deg,
+            config.altitude_m / 1000.0,
+            sat.latitude_deg,
+            sat.longitude_deg,
+            sat.altitude_km,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Circular equatorial orbit, exact at any time
+    fn circular(unix_s: f64) -> EphemerisNode {
+        let r = EARTH_RADIUS_KM + 10_500.0;
+        let w = (398_600.4418 / (r * r * r)).sqrt();
+        let (sin, cos) = (w * unix_s).sin_cos();
+        EphemerisNode {
+            unix_s,
+            position_km: [r * cos, r * sin, 0.0],
+            velocity_km_s: [-r * w * sin, r * w * cos, 0.0],
+        }
+    }
+
+    #[test]
+    fn test_interpolates_between_nodes() {
+        let mut eph = Ephemeris::new(90001, 8);
+        for t in [0.0, 60.0, 120.0] {
+            eph.push(circular(t));
+        }
+        assert!(eph.state_at(-1.0).is_none());
+        assert!(eph.state_at(121.0).is_none());
+
+        for i in 0..=120 {
+            let t = i as f64;
+            let (p, v) = eph.state_at(t).unwrap();
+            let exact = circular(t);
+            for k in 0..3 {
+                assert!((p[k] - exact.position_km[k]).abs() < 1e-4, "{} at {}", p[k], t);
+                assert!((v[k] - exact.velocity_km_s[k]).abs() < 1e-5, "{} at {}", v[k], t);
+            }
+        }
+
+        // The node on the inertial x axis is over the Greenwich meridian
+        // turned back by GMST
+        let site = GroundStationConfig {
+            longitude_deg: -gmst_deg(0.0),
+            ..Default::default()
+        };
+        let look = eph.look_angles_at(&site, 0.0).unwrap();
+        assert!(look.elevation_deg > 89.9);
+        assert!((look.range_km - 10_500.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_push_orders_and_bounds() {
+        let mut eph = Ephemeris::new(90001, 3);
+        for t in [120.0, 0.0, 60.0, 60.0, 180.0] {
+            eph.push(circular(t));
+        }
+        assert_eq!(eph.len(), 3);
+        assert!(eph.state_at(30.0).is_none());
+        assert!(eph.state_at(150.0).is_some());
+    }
+}
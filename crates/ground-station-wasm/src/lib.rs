@@ -5,7 +5,7 @@
 //! - Slew control for optical tracking
 //! - Door/aperture state machine
 //! - Contact window calculations
-//! - Real-time satellite tracking
+//! - Real-time satellite tracking, interpolated between gateway ephemeris nodes
 //!
 //! Deployed as individual containers in OrbStack, each assigned
 //! to a specific geographic location from the 257-station network.
@@ -21,9 +21,14 @@ pub mod door;
 pub mod contact;
 pub mod tracking;
 pub mod link_budget;
+pub mod beam_profile;
 pub mod stations;
 pub mod downselect;
 pub mod weather;
+pub mod command;
+pub mod sideband;
+pub mod ephemeris;
+pub mod batch;
 
 #[cfg(feature = "weather-api")]
 pub mod weather_api;
@@ -31,8 +36,15 @@ pub mod weather_api;
 // Re-exports
 pub use slew::SlewController;
 pub use door::{DoorState, DoorController};
-pub use contact::ContactWindow;
+pub use contact::{AcquisitionEstimate, AcquisitionModel, ContactWindow};
+pub use command::{StationCommand, CommandEnvelope, CommandAck, AckStatus};
+pub use sideband::{
+    MessagePriority, SidebandMessage, SidebandAck, SequenceTracker, QosSample, QosSummary, SpeedOfService,
+};
 pub use tracking::TrackingLoop;
+pub use ephemeris::{Ephemeris, EphemerisNode};
+pub use batch::{LookAngleBatch, PointBatch, StationBatch};
+pub use beam_profile::{BeamZone, BeamPointing, BeamPlacement};
 pub use stations::{NetworkStation, StationType, StationStats};
 pub use downselect::{Downselect, ScoringWeights, StationEvaluation, DownselectSummary};
 pub use weather::{
@@ -109,6 +121,9 @@ pub struct GroundStationState {
     pub tracking_satellite: Option<u32>, // NORAD ID if tracking
     pub link_margin_db: f64,
     pub weather_score: f64,
+    /// Door and tracking commands are refused while set
+    #[serde(default)]
+    pub weather_hold: bool,
     pub last_update_unix: i64,
 }
 
@@ -122,8 +137,13 @@ pub struct GroundStation {
     state: GroundStationState,
     slew: SlewController,
     door: DoorController,
+    ephemeris: Ephemeris,
 }
 
+/// Nodes the twin keeps for the satellite it points at (a day at 60 s)
+#[cfg(feature = "wasm")]
+const EPHEMERIS_MAX_NODES: usize = 1440;
+
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
 impl GroundStation {
@@ -147,10 +167,12 @@ impl GroundStation {
                 tracking_satellite: None,
                 link_margin_db: 0.0,
                 weather_score: 1.0,
+                weather_hold: false,
                 last_update_unix: 0,
             },
             slew: SlewController::new(config.max_slew_rate_deg_s),
             door: DoorController::new(),
+            ephemeris: Ephemeris::new(0, EPHEMERIS_MAX_NODES),
         })
     }
 
@@ -168,6 +190,42 @@ impl GroundStation {
         serde_json::to_string(&angles).unwrap_or_default()
     }
 
+    /// Micro-function: Add an ECI state node (from the gateway's
+    /// ephemeris endpoint or position stream); nodes for a different
+    /// satellite replace the current set
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn push_ephemeris_node(
+        &mut self,
+        norad_id: u32,
+        unix_s: f64,
+        x_km: f64,
+        y_km: f64,
+        z_km: f64,
+        vx_km_s: f64,
+        vy_km_s: f64,
+        vz_km_s: f64,
+    ) {
+        if self.ephemeris.norad_id != norad_id {
+            self.ephemeris = Ephemeris::new(norad_id, EPHEMERIS_MAX_NODES);
+        }
+        self.ephemeris.push(EphemerisNode {
+            unix_s,
+            position_km: [x_km, y_km, z_km],
+            velocity_km_s: [vx_km_s, vy_km_s, vz_km_s],
+        });
+    }
+
+    /// Micro-function: Pointing at `unix_s` interpolated from the pushed
+    /// nodes; empty when `unix_s` is outside them
+    #[wasm_bindgen]
+    pub fn calc_pointing_at(&self, unix_s: f64) -> String {
+        self.ephemeris
+            .look_angles_at(&self.state.config, unix_s)
+            .and_then(|angles| serde_json::to_string(&angles).ok())
+            .unwrap_or_default()
+    }
+
     /// Micro-function: Check if satellite is visible (above min elevation)
     #[wasm_bindgen]
     pub fn is_visible(&self, sat_lat: f64, sat_lon: f64, sat_alt_km: f64) -> bool {
@@ -264,6 +322,23 @@ impl GroundStation {
         self.state.weather_score = score.clamp(0.0, 1.0);
     }
 
+    /// Handle a `CommandEnvelope` (JSON) from `orbital.gs.{id}.cmd`,
+    /// returning the `CommandAck` JSON to reply with
+    #[wasm_bindgen]
+    pub fn handle_command(&mut self, envelope_json: &str) -> String {
+        let ack = match serde_json::from_str::<CommandEnvelope>(envelope_json) {
+            Ok(envelope) => command::handle_envelope(&mut self.state, &mut self.door, &envelope),
+            Err(e) => CommandAck {
+                command_id: String::new(),
+                station_id: self.state.config.id.clone(),
+                status: AckStatus::Rejected,
+                message: Some(format!("Invalid command: {}", e)),
+                state: None,
+            },
+        };
+        serde_json::to_string(&ack).unwrap_or_default()
+    }
+
     /// Get full state as JSON
     #[wasm_bindgen]
     pub fn get_state(&self) -> String {
@@ -0,0 +1,238 @@
+//! CTAS Sideband Messages
+//!
+//! Out-of-band messages between the gateway and stations, carried over
+//! NATS request-reply on `orbital.sideband.{channel}`. Every message on a
+//! channel gets the next sequence number; the receiver replies with a
+//! `SidebandAck` and uses `SequenceTracker` to account for gaps and
+//! duplicates. Priorities order the sender's queue: emergencies and threat
+//! alerts always go out before routine traffic. Heartbeat probes feed
+//! `SpeedOfService`, a rolling RTT/jitter/loss window per channel.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// NATS subject for a sideband channel
+pub fn sideband_subject(channel: &str) -> String {
+    format!("orbital.sideband.{}", channel)
+}
+
+/// Send priority, lowest first (so `Ord` puts `Emergency` on top)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessagePriority {
+    Heartbeat,
+    Telemetry,
+    Command,
+    ThreatAlert,
+    Emergency,
+}
+
+/// Message as sent on the wire
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidebandMessage {
+    pub message_id: String,
+    pub channel: String,
+    pub priority: MessagePriority,
+    /// Per-channel, starting at 1
+    pub sequence: u64,
+    pub sent_unix_ms: i64,
+    /// Channel key version in force when sent; absent on unkeyed channels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_version: Option<u64>,
+    pub payload: serde_json::Value,
+}
+
+/// Receiver reply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidebandAck {
+    pub message_id: String,
+    pub channel: String,
+    pub sequence: u64,
+    pub received_unix_ms: i64,
+}
+
+impl SidebandAck {
+    pub fn for_message(message: &SidebandMessage, received_unix_ms: i64) -> Self {
+        Self {
+            message_id: message.message_id.clone(),
+            channel: message.channel.clone(),
+            sequence: message.sequence,
+            received_unix_ms,
+        }
+    }
+}
+
+/// How a received sequence number relates to what came before
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceCheck {
+    InOrder,
+    /// Arrived ahead of `missing` earlier messages
+    Gap { missing: u64 },
+    /// Fills an earlier gap (priority reordering or a retry)
+    Late,
+    Duplicate,
+}
+
+/// Receive-side sequence accounting for one channel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SequenceTracker {
+    /// Highest sequence seen
+    pub highest: u64,
+    pub received: u64,
+    pub duplicates: u64,
+    /// Sequences skipped and not (yet) filled in
+    pub missing: Vec<u64>,
+}
+
+impl SequenceTracker {
+    pub fn record(&mut self, sequence: u64) -> SequenceCheck {
+        if sequence > self.highest {
+            let missing = sequence - self.highest - 1;
+            self.missing.extend(self.highest + 1..sequence);
+            self.highest = sequence;
+            self.received += 1;
+            if missing == 0 {
+                SequenceCheck::InOrder
+            } else {
+                SequenceCheck::Gap { missing }
+            }
+        } else if let Some(pos) = self.missing.iter().position(|s| *s == sequence) {
+            self.missing.swap_remove(pos);
+            self.received += 1;
+            SequenceCheck::Late
+        } else {
+            self.duplicates += 1;
+            SequenceCheck::Duplicate
+        }
+    }
+}
+
+/// One probe result
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct QosSample {
+    pub sequence: u64,
+    pub sent_unix_ms: i64,
+    /// `None` if the probe was never acknowledged
+    pub rtt_ms: Option<f64>,
+}
+
+/// Rolling QoS figures for one channel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QosSummary {
+    pub samples: usize,
+    pub lost: usize,
+    pub loss_pct: f64,
+    pub rtt_mean_ms: Option<f64>,
+    pub rtt_min_ms: Option<f64>,
+    pub rtt_max_ms: Option<f64>,
+    /// Mean absolute RTT difference between consecutive answered probes
+    pub jitter_ms: Option<f64>,
+    pub last_sent_unix_ms: Option<i64>,
+}
+
+/// Speed-of-service window over the most recent probes of a channel
+#[derive(Debug, Clone)]
+pub struct SpeedOfService {
+    window: VecDeque<QosSample>,
+    capacity: usize,
+}
+
+impl SpeedOfService {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            window: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn record(&mut self, sample: QosSample) {
+        if self.window.len() == self.capacity {
+            self.window.pop_front();
+        }
+        self.window.push_back(sample);
+    }
+
+    pub fn summary(&self) -> QosSummary {
+        let rtts: Vec<f64> = self.window.iter().filter_map(|s| s.rtt_ms).collect();
+        let samples = self.window.len();
+        let lost = samples - rtts.len();
+        let jitter = (rtts.len() > 1).then(|| {
+            rtts.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>() / (rtts.len() - 1) as f64
+        });
+
+        QosSummary {
+            samples,
+            lost,
+            loss_pct: if samples > 0 { 100.0 * lost as f64 / samples as f64 } else { 0.0 },
+            rtt_mean_ms: (!rtts.is_empty()).then(|| rtts.iter().sum::<f64>() / rtts.len() as f64),
+            rtt_min_ms: rtts.iter().copied().reduce(f64::min),
+            rtt_max_ms: rtts.iter().copied().reduce(f64::max),
+            jitter_ms: jitter,
+            last_sent_unix_ms: self.window.back().map(|s| s.sent_unix_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_of_service_summary() {
+        let mut sos = SpeedOfService::new(4);
+        for (sequence, rtt_ms) in [(1, Some(40.0)), (2, Some(50.0)), (3, None), (4, Some(45.0)), (5, Some(55.0))] {
+            sos.record(QosSample {
+                sequence,
+                sent_unix_ms: sequence as i64 * 1000,
+                rtt_ms,
+            });
+        }
+
+        // Window keeps probes 2..=5
+        let summary = sos.summary();
+        assert_eq!((summary.samples, summary.lost), (4, 1));
+        assert_eq!(summary.loss_pct, 25.0);
+        assert_eq!(summary.rtt_mean_ms, Some(50.0));
+        assert_eq!((summary.rtt_min_ms, summary.rtt_max_ms), (Some(45.0), Some(55.0)));
+        assert_eq!(summary.jitter_ms, Some(7.5));
+        assert_eq!(summary.last_sent_unix_ms, Some(5000));
+    }
+
+    #[test]
+    fn test_priority_order() {
+        assert!(MessagePriority::Emergency > MessagePriority::ThreatAlert);
+        assert!(MessagePriority::ThreatAlert > MessagePriority::Command);
+        assert!(MessagePriority::Telemetry > MessagePriority::Heartbeat);
+    }
+
+    #[test]
+    fn test_sequence_tracker() {
+        let mut tracker = SequenceTracker::default();
+        assert_eq!(tracker.record(1), SequenceCheck::InOrder);
+        assert_eq!(tracker.record(4), SequenceCheck::Gap { missing: 2 });
+        assert_eq!(tracker.record(2), SequenceCheck::Late);
+        assert_eq!(tracker.record(2), SequenceCheck::Duplicate);
+        assert_eq!(tracker.missing, vec![3]);
+        assert_eq!((tracker.highest, tracker.received, tracker.duplicates), (4, 3, 1));
+    }
+
+    #[test]
+    fn test_ack_echoes_message() {
+        let message = SidebandMessage {
+            message_id: "m-1".into(),
+            channel: "GS-001".into(),
+            priority: MessagePriority::ThreatAlert,
+            sequence: 7,
+            sent_unix_ms: 1_000,
+            key_version: Some(3),
+            payload: serde_json::json!({"threat": "debris"}),
+        };
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(json.contains("\"priority\":\"threat_alert\""));
+        assert!(json.contains("\"key_version\":3"));
+
+        let ack = SidebandAck::for_message(&message, 1_250);
+        assert_eq!((ack.message_id.as_str(), ack.sequence), ("m-1", 7));
+    }
+}
@@ -37,7 +37,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Weather conditions affecting FSO link quality
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WeatherConditions {
     /// Location identifier
     pub station_id: String,
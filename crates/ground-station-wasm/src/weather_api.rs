@@ -234,6 +234,7 @@ impl WeatherApi {
             temperature_c: data.current.temperature_2m,
             humidity_pct: data.current.relative_humidity_2m,
             timestamp: chrono::Utc::now().timestamp(),
+            ..Default::default()
         })
     }
 
@@ -293,6 +294,7 @@ impl WeatherApi {
             temperature_c: data.data.values.temperature,
             humidity_pct: data.data.values.humidity,
             timestamp: chrono::Utc::now().timestamp(),
+            ..Default::default()
         })
     }
 
@@ -362,27 +364,28 @@ impl WeatherApi {
             temperature_c: data.main.temp,
             humidity_pct: data.main.humidity,
             timestamp: chrono::Utc::now().timestamp(),
+            ..Default::default()
         })
     }
 
-    /// Fetch weather for multiple stations in parallel
+    /// Fetch weather for multiple stations, at most `max_concurrent`
+    /// requests in flight. Cached locations are served without a request.
     pub async fn fetch_batch(&self, locations: &[(String, f64, f64)]) -> HashMap<String, Result<FsoWeatherScore, WeatherApiError>> {
-        use futures::future::join_all;
-
-        let futures: Vec<_> = locations
-            .iter()
-            .map(|(id, lat, lon)| {
-                let id = id.clone();
-                let lat = *lat;
-                let lon = *lon;
-                async move {
-                    let result = self.fetch_current(lat, lon).await;
-                    (id, result.map(|w| w.to_fso_score()))
-                }
+        use futures::stream::{self, StreamExt};
+
+        stream::iter(locations.iter().cloned())
+            .map(|(id, lat, lon)| async move {
+                let result = self.fetch_current(lat, lon).await;
+                let score = result.map(|w| {
+                    let mut score = w.to_fso_score();
+                    score.station_id = id.clone();
+                    score
+                });
+                (id, score)
             })
-            .collect();
-
-        join_all(futures).await.into_iter().collect()
+            .buffer_unordered(self.config.max_concurrent.max(1))
+            .collect()
+            .await
     }
 
     /// Clear the cache
@@ -5,8 +5,13 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use thiserror::Error;
 
+pub mod selection;
+
+pub use selection::SelectionInfo;
+
 #[derive(Error, Debug)]
 pub enum StationError {
     #[error("Station not found: {0}")]
@@ -15,6 +20,8 @@ pub enum StationError {
     Offline(String),
     #[error("Weather threshold exceeded at {station}: {condition}")]
     WeatherBlocked { station: String, condition: String },
+    #[error("Invalid selection result {path}: {reason}")]
+    InvalidSelection { path: String, reason: String },
 }
 
 pub type Result<T> = std::result::Result<T, StationError>;
@@ -28,6 +35,9 @@ pub struct GroundStation {
     pub capabilities: StationCapabilities,
     pub weather: Option<WeatherConditions>,
     pub last_contact: DateTime<Utc>,
+    /// Scores and provenance for stations loaded from a selection result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<SelectionInfo>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -54,6 +64,18 @@ pub struct StationCapabilities {
     pub wavelength_nm: u16,
 }
 
+impl Default for StationCapabilities {
+    /// Standard four-terminal 1550 nm FSO site
+    fn default() -> Self {
+        Self {
+            fso_terminals: 4,
+            max_throughput_gbps: 100.0,
+            tracking_accuracy_urad: 1.0,
+            wavelength_nm: 1550,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherConditions {
     pub cloud_cover_pct: f64,
@@ -87,6 +109,30 @@ impl StationRegistry {
         Self::with_fso_network()
     }
 
+    /// Registry of the stations in a candidate-selector `SelectionResult`
+    /// JSON (`select-stations` output), with default capabilities
+    pub fn from_selection_result(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let invalid = |reason: String| StationError::InvalidSelection {
+            path: path.display().to_string(),
+            reason,
+        };
+        let json = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        Self::from_selection_json(&json).map_err(|e| invalid(e.to_string()))
+    }
+
+    /// Registry from `SelectionResult` JSON text
+    pub fn from_selection_json(json: &str) -> serde_json::Result<Self> {
+        let result: selection::SelectionResult = serde_json::from_str(json)?;
+        Ok(Self {
+            stations: result
+                .selected
+                .into_iter()
+                .map(selection::ScoredCandidate::into_station)
+                .collect(),
+        })
+    }
+
     fn load_fso_network(&mut self) {
         // Load 257 FSO ground stations
         // In production, this would load from config/database
@@ -111,14 +157,10 @@ impl StationRegistry {
                     altitude_m: alt,
                 },
                 status: StationStatus::Operational,
-                capabilities: StationCapabilities {
-                    fso_terminals: 4,
-                    max_throughput_gbps: 100.0,
-                    tracking_accuracy_urad: 1.0,
-                    wavelength_nm: 1550,
-                },
+                capabilities: StationCapabilities::default(),
                 weather: None,
                 last_contact: Utc::now(),
+                selection: None,
             });
         }
     }
@@ -130,6 +172,10 @@ impl StationRegistry {
             .ok_or_else(|| StationError::NotFound(id.to_string()))
     }
 
+    pub fn all(&self) -> impl Iterator<Item = &GroundStation> {
+        self.stations.iter()
+    }
+
     pub fn operational(&self) -> impl Iterator<Item = &GroundStation> {
         self.stations
             .iter()
@@ -173,3 +219,31 @@ impl Default for StationRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_selection_result() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../data/selected_247_stations.json");
+        let registry = StationRegistry::from_selection_result(path).unwrap();
+        assert_eq!(registry.all().count(), 247);
+        assert_eq!(registry.operational().count(), 247);
+
+        let merged = registry
+            .all()
+            .find(|s| s.selection.as_ref().is_some_and(|sel| sel.source == "Merged"))
+            .unwrap();
+        let selection = merged.selection.as_ref().unwrap();
+        assert!(!selection.merged_from.is_empty());
+        assert!(["Americas", "Emea", "Apac"].contains(&selection.zone.as_str()));
+        assert!((0.0..=1.0).contains(&selection.score));
+        assert_eq!(merged.capabilities.wavelength_nm, 1550);
+
+        assert!(matches!(
+            StationRegistry::from_selection_result("does/not/exist.json"),
+            Err(StationError::InvalidSelection { .. })
+        ));
+    }
+}
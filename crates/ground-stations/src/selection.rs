@@ -0,0 +1,92 @@
+//! Candidate-selector output
+//!
+//! The `select-stations` pipeline writes a `SelectionResult` JSON: scored
+//! candidates with their zone and the source records merged into them.
+//! Only the fields the registry keeps are read here, so the selector's
+//! scoring factors can grow without touching this crate.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GeoLocation, GroundStation, StationCapabilities, StationStatus};
+
+/// Where a registry station came from in the selection pipeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionInfo {
+    /// Composite selector score (0-1)
+    pub score: f64,
+    /// `Americas`, `Emea` or `Apac`
+    pub zone: String,
+    /// Candidate source, e.g. `GroundNode`, `CableLanding`, `Merged`
+    pub source: String,
+    /// IDs of the candidates deduplicated into this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub merged_from: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    /// FSO weather suitability (0-1)
+    pub weather_score: f64,
+    /// Geopolitical security (0-1, higher = safer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_score: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SelectionResult {
+    pub selected: Vec<ScoredCandidate>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ScoredCandidate {
+    candidate: Candidate,
+    score: f64,
+    weather_score: f64,
+    #[serde(default)]
+    security_score: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Candidate {
+    id: String,
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    zone: String,
+    source: String,
+    #[serde(default)]
+    tier: Option<u8>,
+    #[serde(default)]
+    merged_from: Option<Vec<String>>,
+    #[serde(default)]
+    country_code: Option<String>,
+}
+
+impl ScoredCandidate {
+    pub(crate) fn into_station(self) -> GroundStation {
+        let c = self.candidate;
+        GroundStation {
+            id: c.id,
+            name: c.name,
+            location: GeoLocation {
+                latitude: c.latitude,
+                longitude: c.longitude,
+                altitude_m: 0.0,
+            },
+            status: StationStatus::Operational,
+            capabilities: StationCapabilities::default(),
+            weather: None,
+            last_contact: chrono::Utc::now(),
+            selection: Some(SelectionInfo {
+                score: self.score,
+                zone: c.zone,
+                source: c.source,
+                merged_from: c.merged_from.unwrap_or_default(),
+                tier: c.tier,
+                country_code: c.country_code,
+                weather_score: self.weather_score,
+                security_score: self.security_score,
+            }),
+        }
+    }
+}
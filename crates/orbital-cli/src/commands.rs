@@ -0,0 +1,296 @@
+//! Commands
+//!
+//! Each returns flat rows for `output::write_rows`. Geodetic positions
+//! come from `StateVector::to_geodetic` (Earth rotated to each sample's
+//! epoch), as in the gateway.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use ground_station_wasm::{contact::ContactCalculator, GroundStationConfig};
+use orbital_mechanics::coverage::{self, CoverageReport, Grid};
+use orbital_mechanics::walker::WalkerDelta;
+use orbital_mechanics::Satellite;
+use serde::Serialize;
+
+/// Upper bound on samples per satellite, to catch a mistyped window
+const MAX_SAMPLES: i64 = 1_000_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StateRow {
+    pub satellite_id: String,
+    pub time: DateTime<Utc>,
+    /// ECI (TEME) position, km
+    pub x_km: f64,
+    pub y_km: f64,
+    pub z_km: f64,
+    /// ECI (TEME) velocity, km/s
+    pub vx_km_s: f64,
+    pub vy_km_s: f64,
+    pub vz_km_s: f64,
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    pub altitude_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackRow {
+    pub satellite_id: String,
+    pub time: DateTime<Utc>,
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    pub altitude_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PassRow {
+    pub satellite_id: String,
+    pub norad_id: u32,
+    pub aos: DateTime<Utc>,
+    pub tca: DateTime<Utc>,
+    pub los: DateTime<Utc>,
+    pub duration_sec: f64,
+    pub max_elevation_deg: f64,
+    pub aos_azimuth_deg: f64,
+    pub los_azimuth_deg: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalkerRow {
+    pub id: String,
+    pub norad_id: u32,
+    pub name: String,
+    pub plane: u8,
+    pub slot: u8,
+    pub line1: String,
+    pub line2: String,
+}
+
+/// Ground site for pass prediction
+#[derive(Debug, Clone)]
+pub struct Site {
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    pub altitude_m: f64,
+}
+
+/// Sample times from `from` for `span`, `step` apart, both ends included
+pub fn sample_times(from: DateTime<Utc>, span: Duration, step: Duration) -> Result<Vec<DateTime<Utc>>> {
+    if step.num_milliseconds() <= 0 || span < Duration::zero() {
+        bail!("step must be at least 1 ms and the window must not be negative");
+    }
+    let steps = span.num_milliseconds() / step.num_milliseconds();
+    if steps > MAX_SAMPLES {
+        bail!("{} samples requested; widen the step", steps);
+    }
+    Ok((0..=steps).map(|i| from + step * i as i32).collect())
+}
+
+pub fn propagate(satellites: &[Satellite], times: &[DateTime<Utc>]) -> Result<Vec<StateRow>> {
+    let mut rows = Vec::with_capacity(satellites.len() * times.len());
+    for sat in satellites {
+        for &time in times {
+            let sv = sat.propagate(time)?;
+            let geo = sv.to_geodetic()?;
+            rows.push(StateRow {
+                satellite_id: sat.id.clone(),
+                time,
+                x_km: sv.position_x,
+                y_km: sv.position_y,
+                z_km: sv.position_z,
+                vx_km_s: sv.velocity_x,
+                vy_km_s: sv.velocity_y,
+                vz_km_s: sv.velocity_z,
+                latitude_deg: geo.latitude,
+                longitude_deg: geo.longitude,
+                altitude_km: geo.altitude_km,
+            });
+        }
+    }
+    Ok(rows)
+}
+
+pub fn ground_track(satellites: &[Satellite], times: &[DateTime<Utc>]) -> Result<Vec<TrackRow>> {
+    let mut rows = Vec::with_capacity(satellites.len() * times.len());
+    for sat in satellites {
+        for &time in times {
+            let geo = sat.ground_track(time)?;
+            rows.push(TrackRow {
+                satellite_id: sat.id.clone(),
+                time,
+                latitude_deg: geo.latitude,
+                longitude_deg: geo.longitude,
+                altitude_km: geo.altitude_km,
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// Passes of every satellite over `site`, sampled at `times`; ordered by AOS
+pub fn passes(
+    satellites: &[Satellite],
+    site: &Site,
+    times: &[DateTime<Utc>],
+    min_elevation_deg: f64,
+) -> Result<Vec<PassRow>> {
+    let calculator = ContactCalculator::new(GroundStationConfig {
+        latitude_deg: site.latitude_deg,
+        longitude_deg: site.longitude_deg,
+        altitude_m: site.altitude_m,
+        min_elevation_deg,
+        ..Default::default()
+    });
+
+    let mut rows = Vec::new();
+    for sat in satellites {
+        let mut samples = Vec::with_capacity(times.len());
+        for &time in times {
+            let geo = sat.ground_track(time)?;
+            samples.push((time.timestamp(), geo.latitude, geo.longitude, geo.altitude_km));
+        }
+        for window in calculator.find_windows(sat.norad_id, &samples) {
+            let ts = |unix: i64| DateTime::<Utc>::from_timestamp(unix, 0).unwrap_or(times[0]);
+            rows.push(PassRow {
+                satellite_id: sat.id.clone(),
+                norad_id: sat.norad_id,
+                aos: ts(window.aos_unix),
+                tca: ts(window.tca_unix),
+                los: ts(window.los_unix),
+                duration_sec: window.duration_sec,
+                max_elevation_deg: window.max_elevation_deg,
+                aos_azimuth_deg: window.aos_azimuth_deg,
+                los_azimuth_deg: window.los_azimuth_deg,
+            });
+        }
+    }
+    rows.sort_by_key(|r| r.aos);
+    Ok(rows)
+}
+
+/// Coverage of a global grid of `grid_deg` cells up to `max_latitude_deg`;
+/// the cells are the rows
+pub fn coverage(
+    satellites: &[Satellite],
+    grid_deg: f64,
+    max_latitude_deg: f64,
+    times: &[DateTime<Utc>],
+    min_elevation_deg: f64,
+) -> Result<CoverageReport> {
+    let grid = Grid {
+        max_latitude_deg,
+        ..Grid::global(grid_deg)
+    };
+    Ok(coverage::analyze(satellites, &grid, times, min_elevation_deg)?)
+}
+
+/// Satellites of a Walker shell with TLEs at `epoch`
+pub fn walker(shell: &WalkerDelta, prefix: &str, norad_base: u32, epoch: DateTime<Utc>) -> Result<Vec<WalkerRow>> {
+    if shell.planes == 0 || shell.total_satellites % shell.planes != 0 {
+        bail!(
+            "{} satellites do not divide evenly into {} planes",
+            shell.total_satellites,
+            shell.planes
+        );
+    }
+    Ok(shell
+        .generate_satellites(prefix, norad_base, epoch)
+        .into_iter()
+        .map(|sat| WalkerRow {
+            id: sat.id,
+            norad_id: sat.norad_id,
+            name: sat.name,
+            plane: sat.plane,
+            slot: sat.slot,
+            line1: sat.tle_line1,
+            line2: sat.tle_line2,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use orbital_mechanics::tle::{validate, TleRecord};
+
+    fn epoch() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    fn halo() -> Vec<Satellite> {
+        WalkerDelta::halo_constellation().generate_satellites("HALO", 90001, epoch())
+    }
+
+    #[test]
+    fn test_sample_times() {
+        let times = sample_times(epoch(), Duration::minutes(10), Duration::minutes(5)).unwrap();
+        assert_eq!(times, vec![epoch(), epoch() + Duration::minutes(5), epoch() + Duration::minutes(10)]);
+        assert!(sample_times(epoch(), Duration::minutes(10), Duration::zero()).is_err());
+        assert!(sample_times(epoch(), Duration::days(365), Duration::milliseconds(1)).is_err());
+    }
+
+    #[test]
+    fn test_propagate_and_track_agree() {
+        let sats = halo();
+        let times = sample_times(epoch(), Duration::hours(1), Duration::minutes(30)).unwrap();
+        let states = propagate(&sats[..2], &times).unwrap();
+        let track = ground_track(&sats[..2], &times).unwrap();
+        assert_eq!(states.len(), 6);
+        for (s, t) in states.iter().zip(&track) {
+            assert_eq!((s.satellite_id.as_str(), s.time), (t.satellite_id.as_str(), t.time));
+            assert_eq!(s.latitude_deg, t.latitude_deg);
+            let r = (s.x_km.powi(2) + s.y_km.powi(2) + s.z_km.powi(2)).sqrt();
+            assert!((r - 16_878.0).abs() < 60.0, "{}", r);
+        }
+    }
+
+    #[test]
+    fn test_meo_passes_over_equatorial_site() {
+        let site = Site {
+            latitude_deg: 0.0,
+            longitude_deg: 0.0,
+            altitude_m: 0.0,
+        };
+        let times = sample_times(epoch(), Duration::hours(24), Duration::minutes(1)).unwrap();
+        let rows = passes(&halo(), &site, &times, 10.0).unwrap();
+        assert!(!rows.is_empty());
+        assert!(rows.windows(2).all(|w| w[0].aos <= w[1].aos));
+        for row in &rows {
+            assert!(row.aos <= row.tca && row.tca <= row.los);
+            assert!(row.max_elevation_deg >= 10.0);
+        }
+    }
+
+    #[test]
+    fn test_coverage_cells() {
+        let times = sample_times(epoch(), Duration::hours(6), Duration::minutes(10)).unwrap();
+        let report = coverage(&halo(), 15.0, 60.0, &times, 10.0).unwrap();
+        assert_eq!(report.cells.len(), 8 * 24);
+        assert!(report.cells.iter().all(|c| c.latitude.abs() < 60.0));
+        assert!(report.coverage_percent > 0.0 && report.coverage_percent <= 100.0);
+        assert!(coverage(&halo(), 15.0, 91.0, &times, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_walker_rows_are_valid_tles() {
+        let rows = walker(&WalkerDelta::halo_constellation(), "HALO", 90001, epoch()).unwrap();
+        assert_eq!(rows.len(), 12);
+        assert_eq!((rows[11].plane, rows[11].slot), (3, 4));
+        for row in &rows {
+            let record = TleRecord {
+                name: Some(row.name.clone()),
+                norad_id: row.norad_id,
+                line1: row.line1.clone(),
+                line2: row.line2.clone(),
+            };
+            validate(&record).unwrap();
+        }
+
+        let uneven = WalkerDelta {
+            total_satellites: 10,
+            planes: 3,
+            ..WalkerDelta::halo_constellation()
+        };
+        assert!(walker(&uneven, "X", 1, epoch()).is_err());
+    }
+}
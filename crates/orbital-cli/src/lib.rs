@@ -0,0 +1,15 @@
+//! orb - orbital-mechanics from the command line
+//!
+//! The pieces behind the `orb` binary, kept separate so they can be
+//! tested without a shell:
+//!
+//! - Satellite sources: 2LE/3LE text or mean elements
+//! - Commands producing flat rows: propagation, ground tracks, passes over
+//!   a site, grid coverage, Walker shell generation and TLE validation
+//! - Row output as JSON or CSV
+
+pub mod commands;
+pub mod output;
+pub mod source;
+
+pub use output::{write_rows, Format};
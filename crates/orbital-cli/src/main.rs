@@ -0,0 +1,346 @@
+//! orb - orbital-mechanics CLI
+//!
+//! Usage:
+//!   orb propagate    --tle iss.tle --at 2026-01-01T00:00:00Z
+//!   orb propagate    --altitude-km 10500 --inclination-deg 55 --epoch 2026-01-01T00:00:00Z
+//!   orb ground-track --tle halo.tle --hours 6 --step-secs 60 --format csv
+//!   orb passes       --tle halo.tle --lat 51.5 --lon -0.1 --hours 24
+//!   orb coverage     --tle halo.tle --grid-deg 5 --hours 24 --step-secs 120
+//!   orb walker generate --satellites 12 --planes 3 --phasing 4 --altitude-km 10500 --inclination-deg 55
+//!   orb tle validate catalog.tle
+//!
+//! `--tle -` reads element sets from stdin, so shells pipe through:
+//! `orb walker generate --format csv` for rows, or take the `line1`/`line2`
+//! fields from JSON. Output is JSON unless `--format csv` is given.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use clap::{Args, Parser, Subcommand};
+use orbital_cli::commands::{self, Site};
+use orbital_cli::source::{self, ElementParams};
+use orbital_cli::{write_rows, Format};
+use orbital_mechanics::walker::WalkerDelta;
+use orbital_mechanics::Satellite;
+use std::io::Read;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(name = "orb", about = "Propagation, passes, Walker shells and TLE checks from the command line")]
+struct Cli {
+    #[command(subcommand)]
+    command: Cmd,
+
+    /// Output format: json or csv
+    #[arg(long, global = true, default_value = "json")]
+    format: Format,
+}
+
+/// Where satellites come from: element sets, or mean elements
+#[derive(Args, Debug)]
+struct SatelliteArgs {
+    /// 2LE/3LE file; `-` for stdin
+    #[arg(long)]
+    tle: Option<PathBuf>,
+
+    /// TLE line 1 (with --line2)
+    #[arg(long, requires = "line2", conflicts_with = "tle")]
+    line1: Option<String>,
+
+    #[arg(long, requires = "line1")]
+    line2: Option<String>,
+
+    /// Element epoch (with mean elements)
+    #[arg(long)]
+    epoch: Option<DateTime<Utc>>,
+
+    /// Mean motion (rev/day); or give --altitude-km
+    #[arg(long, conflicts_with = "altitude_km")]
+    mean_motion: Option<f64>,
+
+    /// Circular-orbit altitude (km)
+    #[arg(long)]
+    altitude_km: Option<f64>,
+
+    #[arg(long, default_value_t = 0.0)]
+    inclination_deg: f64,
+
+    #[arg(long, default_value_t = 0.0)]
+    raan_deg: f64,
+
+    #[arg(long, default_value_t = 0.0)]
+    eccentricity: f64,
+
+    #[arg(long, default_value_t = 0.0)]
+    arg_perigee_deg: f64,
+
+    #[arg(long, default_value_t = 0.0)]
+    mean_anomaly_deg: f64,
+}
+
+impl SatelliteArgs {
+    fn load(&self) -> Result<Vec<Satellite>> {
+        if let Some(path) = &self.tle {
+            let text = if path.as_os_str() == "-" {
+                let mut text = String::new();
+                std::io::stdin().read_to_string(&mut text)?;
+                text
+            } else {
+                std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?
+            };
+            return source::satellites_from_tle_text(&text);
+        }
+        if let (Some(line1), Some(line2)) = (&self.line1, &self.line2) {
+            return source::satellites_from_tle_text(&format!("{}\n{}", line1, line2));
+        }
+        if self.mean_motion.is_none() && self.altitude_km.is_none() {
+            bail!("give --tle, --line1/--line2, or mean elements with --mean-motion or --altitude-km");
+        }
+        let params = ElementParams {
+            epoch: self.epoch.unwrap_or_else(Utc::now),
+            mean_motion_rev_day: self.mean_motion,
+            altitude_km: self.altitude_km,
+            inclination_deg: self.inclination_deg,
+            raan_deg: self.raan_deg,
+            eccentricity: self.eccentricity,
+            arg_perigee_deg: self.arg_perigee_deg,
+            mean_anomaly_deg: self.mean_anomaly_deg,
+        };
+        Ok(vec![source::satellite_from_elements(&params)?])
+    }
+}
+
+/// Sampling window
+#[derive(Args, Debug)]
+struct WindowArgs {
+    /// Window start; defaults to now
+    #[arg(long)]
+    from: Option<DateTime<Utc>>,
+
+    #[arg(long, default_value_t = 24.0)]
+    hours: f64,
+
+    #[arg(long, default_value_t = 60.0)]
+    step_secs: f64,
+}
+
+impl WindowArgs {
+    fn times(&self) -> Result<Vec<DateTime<Utc>>> {
+        commands::sample_times(
+            self.from.unwrap_or_else(Utc::now),
+            Duration::milliseconds((self.hours * 3_600_000.0) as i64),
+            Duration::milliseconds((self.step_secs * 1000.0) as i64),
+        )
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    /// ECI state and geodetic position at given times
+    Propagate {
+        #[command(flatten)]
+        satellites: SatelliteArgs,
+
+        /// Times to propagate to (repeatable); defaults to now
+        #[arg(long = "at")]
+        times: Vec<DateTime<Utc>>,
+    },
+
+    /// Sub-satellite points over a window
+    GroundTrack {
+        #[command(flatten)]
+        satellites: SatelliteArgs,
+
+        #[command(flatten)]
+        window: WindowArgs,
+    },
+
+    /// Passes over a ground site
+    Passes {
+        #[command(flatten)]
+        satellites: SatelliteArgs,
+
+        #[command(flatten)]
+        window: WindowArgs,
+
+        #[arg(long, allow_negative_numbers = true)]
+        lat: f64,
+
+        #[arg(long, allow_negative_numbers = true)]
+        lon: f64,
+
+        #[arg(long, default_value_t = 0.0)]
+        alt_m: f64,
+
+        #[arg(long, default_value_t = 10.0)]
+        min_elevation_deg: f64,
+    },
+
+    /// Percent coverage, longest gap and mean revisit per grid cell; the
+    /// constellation summary goes to stderr
+    Coverage {
+        #[command(flatten)]
+        satellites: SatelliteArgs,
+
+        #[command(flatten)]
+        window: WindowArgs,
+
+        /// Cell size (deg)
+        #[arg(long, default_value_t = 5.0)]
+        grid_deg: f64,
+
+        /// Leave out cells poleward of this latitude
+        #[arg(long, default_value_t = 90.0)]
+        max_lat: f64,
+
+        #[arg(long, default_value_t = 10.0)]
+        min_elevation_deg: f64,
+    },
+
+    /// Walker Delta shells
+    Walker {
+        #[command(subcommand)]
+        command: WalkerCmd,
+    },
+
+    /// Element set checks
+    Tle {
+        #[command(subcommand)]
+        command: TleCmd,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WalkerCmd {
+    /// Satellites of a T/P/F shell with TLEs at an epoch (defaults: HALO)
+    Generate {
+        #[arg(long, default_value_t = 12)]
+        satellites: u32,
+
+        #[arg(long, default_value_t = 3)]
+        planes: u32,
+
+        #[arg(long, default_value_t = 4)]
+        phasing: u32,
+
+        #[arg(long, default_value_t = 10_500.0)]
+        altitude_km: f64,
+
+        #[arg(long, default_value_t = 55.0)]
+        inclination_deg: f64,
+
+        /// ID prefix
+        #[arg(long, default_value = "HALO")]
+        prefix: String,
+
+        #[arg(long, default_value_t = 90001)]
+        norad_base: u32,
+
+        /// Element epoch; defaults to now
+        #[arg(long)]
+        epoch: Option<DateTime<Utc>>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TleCmd {
+    /// Check every element set in a file (`-` for stdin); exits non-zero
+    /// if any is invalid
+    Validate { file: PathBuf },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let out = std::io::stdout().lock();
+
+    match cli.command {
+        Cmd::Propagate { satellites, times } => {
+            let times = if times.is_empty() { vec![Utc::now()] } else { times };
+            write_rows(&commands::propagate(&satellites.load()?, &times)?, cli.format, out)
+        }
+        Cmd::GroundTrack { satellites, window } => write_rows(
+            &commands::ground_track(&satellites.load()?, &window.times()?)?,
+            cli.format,
+            out,
+        ),
+        Cmd::Passes {
+            satellites,
+            window,
+            lat,
+            lon,
+            alt_m,
+            min_elevation_deg,
+        } => {
+            if !(-90.0..=90.0).contains(&lat) {
+                bail!("latitude must be within ±90°");
+            }
+            let site = Site {
+                latitude_deg: lat,
+                longitude_deg: lon,
+                altitude_m: alt_m,
+            };
+            let rows = commands::passes(&satellites.load()?, &site, &window.times()?, min_elevation_deg)?;
+            write_rows(&rows, cli.format, out)
+        }
+        Cmd::Coverage {
+            satellites,
+            window,
+            grid_deg,
+            max_lat,
+            min_elevation_deg,
+        } => {
+            let report = commands::coverage(
+                &satellites.load()?,
+                grid_deg,
+                max_lat,
+                &window.times()?,
+                min_elevation_deg,
+            )?;
+            eprintln!(
+                "coverage {:.2}%, continuous {:.2}%, max gap {:.0} s, mean revisit {:.0} s",
+                report.coverage_percent, report.continuous_percent, report.max_gap_s, report.mean_revisit_s
+            );
+            write_rows(&report.cells, cli.format, out)
+        }
+        Cmd::Walker {
+            command:
+                WalkerCmd::Generate {
+                    satellites,
+                    planes,
+                    phasing,
+                    altitude_km,
+                    inclination_deg,
+                    prefix,
+                    norad_base,
+                    epoch,
+                },
+        } => {
+            let shell = WalkerDelta {
+                total_satellites: satellites,
+                planes,
+                phasing,
+                altitude_km,
+                inclination_deg,
+            };
+            let rows = commands::walker(&shell, &prefix, norad_base, epoch.unwrap_or_else(Utc::now))?;
+            write_rows(&rows, cli.format, out)
+        }
+        Cmd::Tle {
+            command: TleCmd::Validate { file },
+        } => {
+            let text = if file.as_os_str() == "-" {
+                let mut text = String::new();
+                std::io::stdin().read_to_string(&mut text)?;
+                text
+            } else {
+                std::fs::read_to_string(&file).with_context(|| format!("reading {}", file.display()))?
+            };
+            let checks = source::check_tle_text(&text);
+            write_rows(&checks, cli.format, out)?;
+            let invalid = checks.iter().filter(|c| !c.valid).count();
+            if invalid > 0 {
+                bail!("{} of {} element sets invalid", invalid, checks.len());
+            }
+            Ok(())
+        }
+    }
+}
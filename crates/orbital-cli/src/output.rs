@@ -0,0 +1,84 @@
+//! Row output
+//!
+//! Every command produces a list of flat rows: JSON writes them as a
+//! pretty-printed array, CSV as a header line plus one record per row
+//! (empty cells for missing optional values).
+
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            other => Err(format!("unknown format {} (json, csv)", other)),
+        }
+    }
+}
+
+pub fn write_rows<T: Serialize>(rows: &[T], format: Format, out: impl Write) -> Result<()> {
+    match format {
+        Format::Json => {
+            let mut out = out;
+            serde_json::to_writer_pretty(&mut out, rows)?;
+            writeln!(out)?;
+        }
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            for row in rows {
+                writer.serialize(row)?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        id: &'static str,
+        value: f64,
+        note: Option<String>,
+    }
+
+    #[test]
+    fn test_csv_and_json() {
+        let rows = [
+            Row {
+                id: "HALO-01",
+                value: 1.5,
+                note: None,
+            },
+            Row {
+                id: "HALO-02",
+                value: 2.0,
+                note: Some("spare".into()),
+            },
+        ];
+
+        let mut csv = Vec::new();
+        write_rows(&rows, Format::Csv, &mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "id,value,note\nHALO-01,1.5,\nHALO-02,2.0,spare\n");
+
+        let mut json = Vec::new();
+        write_rows(&rows, Format::Json, &mut json).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(parsed[1]["note"], "spare");
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! Satellite sources
+//!
+//! Commands take satellites from 2LE/3LE text (a file, stdin, or two
+//! lines on the command line) or from mean elements, which are formatted
+//! into a TLE so everything propagates through the same SGP4 path.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use orbital_mechanics::stationkeeping::MeanElements;
+use orbital_mechanics::tle::{self, TleRecord};
+use orbital_mechanics::walker::{DragTerms, MU_EARTH_KM3_S2};
+use orbital_mechanics::Satellite;
+use serde::Serialize;
+
+const EARTH_RADIUS_KM: f64 = 6378.137;
+
+/// Catalog number for satellites built from elements
+pub const ELEMENTS_NORAD_ID: u32 = 99999;
+
+/// Mean elements as given on the command line. Exactly one of
+/// `mean_motion_rev_day` and `altitude_km` sets the orbit size.
+#[derive(Debug, Clone)]
+pub struct ElementParams {
+    pub epoch: DateTime<Utc>,
+    pub mean_motion_rev_day: Option<f64>,
+    /// Circular-orbit altitude, converted to mean motion
+    pub altitude_km: Option<f64>,
+    pub inclination_deg: f64,
+    pub raan_deg: f64,
+    pub eccentricity: f64,
+    pub arg_perigee_deg: f64,
+    pub mean_anomaly_deg: f64,
+}
+
+/// Mean motion of a circular orbit at `altitude_km` (rev/day)
+pub fn mean_motion_at_altitude(altitude_km: f64) -> f64 {
+    let a = EARTH_RADIUS_KM + altitude_km;
+    (MU_EARTH_KM3_S2 / (a * a * a)).sqrt() * 86400.0 / (2.0 * std::f64::consts::PI)
+}
+
+pub fn satellite_from_elements(params: &ElementParams) -> Result<Satellite> {
+    let mean_motion_rev_day = match (params.mean_motion_rev_day, params.altitude_km) {
+        (Some(n), None) => n,
+        (None, Some(alt)) if alt > 0.0 => mean_motion_at_altitude(alt),
+        (None, Some(_)) => bail!("altitude must be positive"),
+        _ => bail!("give exactly one of mean motion and altitude"),
+    };
+    let elements = MeanElements {
+        epoch: params.epoch,
+        inclination_deg: params.inclination_deg,
+        raan_deg: params.raan_deg,
+        eccentricity: params.eccentricity,
+        arg_perigee_deg: params.arg_perigee_deg,
+        mean_anomaly_deg: params.mean_anomaly_deg,
+        mean_motion_rev_day,
+        drag: DragTerms::default(),
+    };
+    let (line1, line2) = elements.to_tle_lines(ELEMENTS_NORAD_ID);
+    let record = TleRecord {
+        name: Some("ELEMENTS".to_string()),
+        norad_id: ELEMENTS_NORAD_ID,
+        line1,
+        line2,
+    };
+    tle::validate(&record)?;
+    Ok(record.into_satellite())
+}
+
+/// Every element set in `text`; fails on the first bad one
+pub fn satellites_from_tle_text(text: &str) -> Result<Vec<Satellite>> {
+    let satellites: Vec<Satellite> = tle::parse_tle_text(text)?
+        .into_iter()
+        .map(TleRecord::into_satellite)
+        .collect();
+    if satellites.is_empty() {
+        bail!("no element sets found");
+    }
+    Ok(satellites)
+}
+
+/// Validation result of one element set
+#[derive(Debug, Clone, Serialize)]
+pub struct TleCheck {
+    /// 1-based line of the set's first line (its name, for 3LE)
+    pub line: usize,
+    pub name: Option<String>,
+    pub norad_id: Option<u32>,
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+/// Check every element set in `text`, carrying on past bad ones
+pub fn check_tle_text(text: &str) -> Vec<TleCheck> {
+    let lines: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim_end()))
+        .filter(|(_, l)| !l.trim().is_empty())
+        .collect();
+
+    let mut checks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let start = lines[i].0;
+        let name = if lines[i].1.starts_with("1 ") {
+            None
+        } else {
+            i += 1;
+            Some(lines[i - 1].1.trim_start_matches("0 ").trim().to_string())
+        };
+        let (Some((_, line1)), Some((_, line2))) = (lines.get(i), lines.get(i + 1)) else {
+            checks.push(TleCheck {
+                line: start,
+                name,
+                norad_id: None,
+                valid: false,
+                error: Some("truncated element set".to_string()),
+            });
+            break;
+        };
+        if !line2.starts_with("2 ") {
+            // Resynchronise on the next line rather than pairing it up
+            checks.push(TleCheck {
+                line: start,
+                name,
+                norad_id: None,
+                valid: false,
+                error: Some("line 2 missing".to_string()),
+            });
+            i += 1;
+            continue;
+        }
+
+        let norad_id = line1.get(2..7).and_then(|s| s.trim().parse().ok());
+        let error = tle::validate(&TleRecord {
+            name: name.clone(),
+            norad_id: norad_id.unwrap_or(0),
+            line1: line1.to_string(),
+            line2: line2.to_string(),
+        })
+        .err()
+        .map(|e| e.to_string());
+        checks.push(TleCheck {
+            line: start,
+            name,
+            norad_id,
+            valid: error.is_none(),
+            error,
+        });
+        i += 2;
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537
+";
+
+    #[test]
+    fn test_elements_build_a_propagating_satellite() {
+        let epoch = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let mut params = ElementParams {
+            epoch,
+            mean_motion_rev_day: None,
+            altitude_km: Some(10_500.0),
+            inclination_deg: 55.0,
+            raan_deg: 120.0,
+            eccentricity: 0.0,
+            arg_perigee_deg: 0.0,
+            mean_anomaly_deg: 0.0,
+        };
+        let sat = satellite_from_elements(&params).unwrap();
+        let geo = sat.ground_track(epoch).unwrap();
+        assert!((geo.altitude_km - 10_500.0).abs() < 50.0, "{}", geo.altitude_km);
+
+        params.mean_motion_rev_day = Some(4.0);
+        assert!(satellite_from_elements(&params).is_err());
+    }
+
+    #[test]
+    fn test_check_reports_each_set() {
+        let bad = ISS.replace("2927", "2928");
+        let text = format!("{}\n{}", ISS, bad);
+        let checks = check_tle_text(&text);
+        assert_eq!(checks.len(), 2);
+        assert!(checks[0].valid);
+        assert_eq!(checks[0].norad_id, Some(25544));
+        assert!(!checks[1].valid);
+        assert_eq!(checks[1].line, 5);
+        assert!(checks[1].error.as_deref().unwrap().contains("checksum"));
+
+        let truncated = check_tle_text(ISS.lines().take(2).collect::<Vec<_>>().join("\n").as_str());
+        assert_eq!(truncated[0].error.as_deref(), Some("truncated element set"));
+        let iss = &satellites_from_tle_text(ISS).unwrap()[0];
+        assert_eq!((iss.id.as_str(), iss.name.as_str()), ("NORAD-25544", "ISS (ZARYA)"));
+    }
+}
@@ -0,0 +1,337 @@
+//! Terrestrial fibre backhaul
+//!
+//! Adds `LinkType::Terrestrial` edges between ground stations joined by a
+//! cable system, so routing can weigh a space path against the fibre
+//! between the same stations, or mix the two. Each cable landing attaches
+//! to the nearest ground station within `max_access_km`; any two stations
+//! on the same cable get a link whose latency follows the route length in
+//! glass (group index 1.468).
+//!
+//! The cable datasets give landings and a total length but not the path
+//! between landings, so a segment is the great circle times
+//! `route_factor`, never longer than the whole cable; a two-landing cable
+//! uses its published length. Where several cables join the same pair of
+//! stations the shortest wins.
+
+use crate::{ConstellationGraph, ConstellationLink, LinkType, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Light in fibre (km per ms)
+pub const FIBER_KM_PER_MS: f64 = 299_792.458 / 1.468 / 1000.0;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// A cable landing with known coordinates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiberLanding {
+    pub id: String,
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+}
+
+/// One cable system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiberRoute {
+    pub id: String,
+    pub name: String,
+    /// Published system length, when known
+    pub length_km: Option<f64>,
+    pub landings: Vec<FiberLanding>,
+}
+
+/// How landings attach and links are costed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackhaulConfig {
+    /// Furthest a landing may be from the station it serves
+    pub max_access_km: f64,
+    /// Route length over great-circle distance between landings
+    pub route_factor: f64,
+    /// Fibre margin (no weather or pointing losses)
+    pub margin_db: f64,
+    pub throughput_gbps: f64,
+}
+
+impl Default for BackhaulConfig {
+    fn default() -> Self {
+        Self {
+            max_access_km: 150.0,
+            route_factor: 1.3,
+            margin_db: 20.0,
+            throughput_gbps: 100.0,
+        }
+    }
+}
+
+/// What `add_fiber_backhaul` added
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackhaulSummary {
+    /// Cables reaching two or more stations
+    pub routes_used: usize,
+    pub links_added: usize,
+    /// Stations with at least one fibre link
+    pub stations_attached: usize,
+}
+
+#[derive(Deserialize)]
+struct CableFile {
+    cables: Vec<CableRecord>,
+}
+
+#[derive(Deserialize)]
+struct CableRecord {
+    id: String,
+    name: String,
+    length: Option<String>,
+    landing_points: Vec<LandingRef>,
+}
+
+#[derive(Deserialize)]
+struct LandingRef {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct LandingFile {
+    landing_points: Vec<LandingRecord>,
+}
+
+#[derive(Deserialize)]
+struct LandingRecord {
+    id: String,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
+/// Cable routes from the submarine cable dataset (`submarine_cables.json`)
+/// and its landing coordinates (`cable_landing_complete.json`). Landings
+/// without coordinates are dropped.
+pub fn routes_from_json(cables_json: &str, landings_json: &str) -> Result<Vec<FiberRoute>> {
+    let cables: CableFile = serde_json::from_str(cables_json)?;
+    let landings: LandingFile = serde_json::from_str(landings_json)?;
+
+    let coords: HashMap<String, (f64, f64)> = landings
+        .landing_points
+        .into_iter()
+        .filter_map(|l| Some((l.id, (l.latitude?, l.longitude?))))
+        .collect();
+
+    Ok(cables
+        .cables
+        .into_iter()
+        .map(|c| FiberRoute {
+            id: c.id,
+            name: c.name,
+            length_km: c.length.as_deref().and_then(parse_length_km),
+            landings: c
+                .landing_points
+                .into_iter()
+                .filter_map(|l| {
+                    let (latitude_deg, longitude_deg) = *coords.get(&l.id)?;
+                    Some(FiberLanding {
+                        id: l.id,
+                        latitude_deg,
+                        longitude_deg,
+                    })
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// "45,000 km" -> 45000
+fn parse_length_km(text: &str) -> Option<f64> {
+    text.trim().trim_end_matches("km").trim().replace(',', "").parse().ok()
+}
+
+fn great_circle_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = phi2 - phi1;
+    let dlambda = (lon2 - lon1).to_radians();
+    let a = (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+impl ConstellationLink {
+    pub fn terrestrial(id: impl Into<String>, route_km: f64, config: &BackhaulConfig) -> Self {
+        Self {
+            id: id.into(),
+            link_type: LinkType::Terrestrial,
+            margin_db: config.margin_db,
+            throughput_gbps: config.throughput_gbps,
+            utilization: 0.0,
+            latency_ms: route_km / FIBER_KM_PER_MS,
+            active: true,
+            weather_score: 1.0, // Buried/submarine fibre
+        }
+    }
+}
+
+impl ConstellationGraph {
+    /// Link ground stations that share a cable system
+    pub fn add_fiber_backhaul(&mut self, routes: &[FiberRoute], config: &BackhaulConfig) -> BackhaulSummary {
+        let stations: Vec<(String, f64, f64)> = self
+            .ground_stations()
+            .map(|n| (n.id.clone(), n.latitude_deg, n.longitude_deg))
+            .collect();
+
+        // Shortest route per unordered station pair: (km, link id)
+        let mut best: HashMap<(String, String), (f64, String)> = HashMap::new();
+        let mut summary = BackhaulSummary::default();
+
+        for route in routes {
+            // Nearest station per landing; per station, its closest landing
+            let mut attached: HashMap<&str, (f64, &FiberLanding)> = HashMap::new();
+            for landing in &route.landings {
+                let nearest = stations
+                    .iter()
+                    .map(|(id, lat, lon)| {
+                        (id, great_circle_km(landing.latitude_deg, landing.longitude_deg, *lat, *lon))
+                    })
+                    .filter(|(_, km)| *km <= config.max_access_km)
+                    .min_by(|a, b| a.1.total_cmp(&b.1));
+                if let Some((id, km)) = nearest {
+                    let entry = attached.entry(id.as_str()).or_insert((km, landing));
+                    if km < entry.0 {
+                        *entry = (km, landing);
+                    }
+                }
+            }
+            if attached.len() < 2 {
+                continue;
+            }
+            summary.routes_used += 1;
+
+            let mut ends: Vec<_> = attached.into_iter().collect();
+            ends.sort_by(|a, b| a.0.cmp(b.0));
+            for (i, (a, (access_a, la))) in ends.iter().enumerate() {
+                for (b, (access_b, lb)) in &ends[i + 1..] {
+                    let gc = great_circle_km(la.latitude_deg, la.longitude_deg, lb.latitude_deg, lb.longitude_deg);
+                    let segment = match (route.length_km, route.landings.len()) {
+                        (Some(length), 2) => length.max(gc),
+                        (Some(length), _) => (gc * config.route_factor).min(length.max(gc)),
+                        (None, _) => gc * config.route_factor,
+                    };
+                    let km = segment + access_a + access_b;
+                    let key = (a.to_string(), b.to_string());
+                    if best.get(&key).is_none_or(|(existing, _)| km < *existing) {
+                        best.insert(key, (km, format!("FIBER-{}-{}-{}", route.id, a, b)));
+                    }
+                }
+            }
+        }
+
+        let mut linked: Vec<&String> = Vec::new();
+        let mut pairs: Vec<_> = best.iter().collect();
+        pairs.sort_by(|a, b| a.0.cmp(b.0));
+        for ((a, b), (km, id)) in pairs {
+            if self.has_terrestrial_link(a, b) {
+                continue;
+            }
+            if self.add_link(a, b, ConstellationLink::terrestrial(id, *km, config)).is_ok() {
+                summary.links_added += 1;
+                linked.push(a);
+                linked.push(b);
+            }
+        }
+        linked.sort();
+        linked.dedup();
+        summary.stations_attached = linked.len();
+
+        tracing::debug!(
+            "Fibre backhaul: {} links across {} stations from {} cables",
+            summary.links_added,
+            summary.stations_attached,
+            summary.routes_used
+        );
+        summary
+    }
+
+    fn has_terrestrial_link(&self, a: &str, b: &str) -> bool {
+        let (Some(ia), Some(ib)) = (self.node_index.get(a), self.node_index.get(b)) else {
+            return false;
+        };
+        self.graph
+            .edges_connecting(*ia, *ib)
+            .any(|e| e.weight().link_type == LinkType::Terrestrial)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConstellationNode;
+
+    fn landing(id: &str, lat: f64, lon: f64) -> FiberLanding {
+        FiberLanding {
+            id: id.to_string(),
+            latitude_deg: lat,
+            longitude_deg: lon,
+        }
+    }
+
+    fn graph() -> ConstellationGraph {
+        let mut graph = ConstellationGraph::new();
+        graph.add_node(ConstellationNode::ground_station("GS-NYC", "New York", 40.7, -74.0, 1));
+        graph.add_node(ConstellationNode::ground_station("GS-LON", "London", 51.5, -0.1, 1));
+        graph.add_node(ConstellationNode::ground_station("GS-SIN", "Singapore", 1.35, 103.8, 1));
+        graph.add_node(ConstellationNode::satellite("SAT-1", "Sat 1", 45.0, -40.0, 10_500.0, 1, 55.0));
+        graph
+    }
+
+    #[test]
+    fn test_backhaul_links_stations_on_shared_cable() {
+        let routes = vec![
+            FiberRoute {
+                id: "tat".to_string(),
+                name: "Transatlantic".to_string(),
+                length_km: Some(6_500.0),
+                landings: vec![landing("shirley", 40.8, -72.9), landing("essex", 51.6, 0.7)],
+            },
+            FiberRoute {
+                id: "long-way".to_string(),
+                name: "Long way round".to_string(),
+                length_km: Some(20_000.0),
+                landings: vec![landing("shirley", 40.8, -72.9), landing("essex", 51.6, 0.7)],
+            },
+            FiberRoute {
+                id: "stub".to_string(),
+                name: "Unreached".to_string(),
+                length_km: None,
+                landings: vec![landing("nowhere", -60.0, 0.0), landing("essex", 51.6, 0.7)],
+            },
+        ];
+
+        let mut graph = graph();
+        let summary = graph.add_fiber_backhaul(&routes, &BackhaulConfig::default());
+        assert_eq!(summary.routes_used, 2);
+        assert_eq!(summary.links_added, 1);
+        assert_eq!(summary.stations_attached, 2);
+
+        let link = graph.get_link("GS-NYC", "GS-LON").unwrap();
+        assert_eq!(link.link_type, LinkType::Terrestrial);
+        assert!(link.id.starts_with("FIBER-tat-"));
+        // ~6,500 km of cable plus two short access runs, ~5 µs/km
+        assert!(link.latency_ms > 32.0 && link.latency_ms < 35.0, "{}", link.latency_ms);
+        assert_eq!(graph.find_path("GS-NYC", "GS-LON").unwrap(), vec!["GS-NYC", "GS-LON"]);
+        assert!(graph.get_link("GS-NYC", "GS-SIN").is_none());
+
+        // Loading twice adds nothing
+        assert_eq!(graph.add_fiber_backhaul(&routes, &BackhaulConfig::default()).links_added, 0);
+        assert_eq!(graph.stats().terrestrial_links, 1);
+    }
+
+    #[test]
+    fn test_routes_from_dataset_json() {
+        let cables = r#"{"cables":[{"id":"c1","name":"Cable 1","length":"1,015 km",
+            "landing_points":[{"id":"a"},{"id":"b"},{"id":"c"}]}],"count":1}"#;
+        let landings = r#"{"landing_points":[{"id":"a","latitude":1.0,"longitude":2.0},
+            {"id":"b","latitude":3.0,"longitude":4.0},{"id":"c","latitude":null,"longitude":null}]}"#;
+        let routes = routes_from_json(cables, landings).unwrap();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].length_km, Some(1015.0));
+        assert_eq!(routes[0].landings.len(), 2);
+        assert_eq!(parse_length_km("45,000 km"), Some(45_000.0));
+        assert_eq!(parse_length_km("TBD"), None);
+    }
+}
@@ -0,0 +1,75 @@
+//! GLAF routing server
+//!
+//! Usage:
+//!   glaf-server --listen 0.0.0.0:8096 --nats nats://localhost:4222
+//!   glaf-server --graph topology.json --no-nats
+//!
+//! Routes on the latest topology the gateway publishes on NATS (see
+//! `orbital_glaf::server`). `--graph` preloads a `GraphSnapshot` file so
+//! queries can be answered before the first publish, or without NATS.
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use orbital_glaf::server::{self, RoutingService};
+use orbital_glaf::snapshot::GraphSnapshot;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{error, info, Level};
+use tracing_subscriber::FmtSubscriber;
+
+#[derive(Parser, Debug)]
+#[command(name = "glaf-server", about = "Standalone GLAF routing service")]
+struct Cli {
+    /// HTTP listen address
+    #[arg(long, env = "GLAF_LISTEN", default_value = "127.0.0.1:8096")]
+    listen: SocketAddr,
+
+    #[arg(long, env = "NATS_URL", default_value = "nats://localhost:4222")]
+    nats: String,
+
+    /// Don't ingest topology from NATS
+    #[arg(long)]
+    no_nats: bool,
+
+    /// Graph snapshot (JSON) to load at startup
+    #[arg(long)]
+    graph: Option<PathBuf>,
+
+    /// Verbose output
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let level = if cli.verbose { Level::DEBUG } else { Level::INFO };
+    let subscriber = FmtSubscriber::builder().with_max_level(level).finish();
+    tracing::subscriber::set_global_default(subscriber)?;
+
+    let service = Arc::new(RoutingService::new());
+    if let Some(path) = &cli.graph {
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let snapshot: GraphSnapshot = serde_json::from_str(&text)?;
+        let stats = service.load(&snapshot)?;
+        info!("Loaded {}: {} nodes, {} links", path.display(), stats.total_nodes, stats.total_links);
+    }
+
+    if !cli.no_nats {
+        let client = async_nats::connect(&cli.nats)
+            .await
+            .with_context(|| format!("connecting to {}", cli.nats))?;
+        info!("Connected to NATS at {}", cli.nats);
+        let service = service.clone();
+        tokio::spawn(async move {
+            if let Err(e) = server::ingest(service, client).await {
+                error!("Topology ingest stopped: {}", e);
+            }
+        });
+    }
+
+    server::serve(service, cli.listen).await?;
+    Ok(())
+}
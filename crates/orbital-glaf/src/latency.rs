@@ -0,0 +1,251 @@
+//! Per-hop latency budget of a route
+//!
+//! Itemizes where a route's one-way latency goes, so SLA latency claims
+//! can be backed hop by hop:
+//! - propagation: the link's latency, with the slant range (or fibre route
+//!   length for terrestrial hops) it implies at the speed of light in the
+//!   medium
+//! - switching/processing at the node each hop leaves, plus processing at
+//!   the destination, from `LatencyAssumptions`
+//! - queueing: the M/M/1 wait ρ/(1-ρ)·L/C of a packet of `packet_bytes`.
+//!   A link's `throughput_gbps` is the capacity left after `utilization`,
+//!   C·(1-ρ), so the wait reduces to ρ·L/throughput; it is capped at the
+//!   buffer depth `max_queueing_ms`
+//!
+//! The budget serializes to JSON; `to_csv` writes one row per hop.
+
+use crate::backhaul::FIBER_KM_PER_MS;
+use crate::{ConstellationGraph, GlafError, LinkType, NodeType, Result};
+use serde::{Deserialize, Serialize};
+
+/// Speed of light in vacuum (km/ms)
+const VACUUM_KM_PER_MS: f64 = 299_792.458 / 1000.0;
+
+/// Delay assumptions not carried by the graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyAssumptions {
+    /// Onboard optical switching per satellite hop (ms)
+    pub satellite_switching_ms: f64,
+    /// Ground-station processing (demodulation, framing, routing) (ms)
+    pub ground_processing_ms: f64,
+    /// Packet size for queueing estimates (bytes)
+    pub packet_bytes: u32,
+    /// Queueing delay cap: the buffer depth of one link (ms)
+    pub max_queueing_ms: f64,
+}
+
+impl Default for LatencyAssumptions {
+    fn default() -> Self {
+        Self {
+            satellite_switching_ms: 0.05,
+            ground_processing_ms: 0.5,
+            packet_bytes: 1500,
+            max_queueing_ms: 10.0,
+        }
+    }
+}
+
+impl LatencyAssumptions {
+    fn switching_ms(&self, node_type: &NodeType) -> f64 {
+        match node_type {
+            NodeType::Satellite { .. } => self.satellite_switching_ms,
+            NodeType::GroundStation { .. } => self.ground_processing_ms,
+        }
+    }
+
+    /// M/M/1 queueing wait on a link at `utilization` with
+    /// `spare_gbps` left over
+    pub fn queueing_ms(&self, utilization: f64, spare_gbps: f64) -> f64 {
+        let rho = utilization.clamp(0.0, 1.0);
+        if rho == 0.0 {
+            return 0.0;
+        }
+        if spare_gbps <= 0.0 {
+            return self.max_queueing_ms;
+        }
+        let packet_bits = self.packet_bytes as f64 * 8.0;
+        (rho * packet_bits / (spare_gbps * 1e6)).min(self.max_queueing_ms)
+    }
+}
+
+/// One hop of a route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HopBudget {
+    /// 1-based
+    pub hop: usize,
+    pub from: String,
+    pub to: String,
+    pub link_id: String,
+    pub link_type: LinkType,
+    /// Slant range, or fibre route length for terrestrial hops (km)
+    pub range_km: f64,
+    pub propagation_ms: f64,
+    /// Switching/processing at `from`
+    pub switching_ms: f64,
+    pub utilization: f64,
+    pub queueing_ms: f64,
+    pub total_ms: f64,
+}
+
+/// Itemized one-way latency of a route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyBudget {
+    pub path: Vec<String>,
+    pub assumptions: LatencyAssumptions,
+    pub hops: Vec<HopBudget>,
+    /// Processing at the destination
+    pub egress_processing_ms: f64,
+    pub propagation_ms: f64,
+    pub switching_ms: f64,
+    pub queueing_ms: f64,
+    pub total_ms: f64,
+}
+
+impl LatencyBudget {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Header plus one row per hop
+    pub fn to_csv(&self) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for hop in &self.hops {
+            writer.serialize(hop)?;
+        }
+        let bytes = writer.into_inner().map_err(|e| csv::Error::from(e.into_error()))?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+impl ConstellationGraph {
+    /// Latency budget of `path`. Every hop must exist; inactive hops are
+    /// still itemized, so a budget can be drawn up for a failed route.
+    pub fn latency_budget(&self, path: &[String], assumptions: &LatencyAssumptions) -> Result<LatencyBudget> {
+        let node = |id: &String| self.get_node(id).ok_or_else(|| GlafError::NodeNotFound(id.clone()));
+        let destination = match path {
+            [_, .., last] => node(last)?,
+            _ => {
+                let id = path.first().cloned().unwrap_or_default();
+                return Err(GlafError::NoPath(id.clone(), id));
+            }
+        };
+
+        let mut hops = Vec::with_capacity(path.len() - 1);
+        for (i, pair) in path.windows(2).enumerate() {
+            let (from, to) = (&pair[0], &pair[1]);
+            let link = self
+                .get_link(from, to)
+                .ok_or_else(|| GlafError::LinkNotFound(format!("{} -> {}", from, to)))?;
+            let km_per_ms = match link.link_type {
+                LinkType::Terrestrial => FIBER_KM_PER_MS,
+                LinkType::InterSatellite | LinkType::SatelliteToGround => VACUUM_KM_PER_MS,
+            };
+            let switching_ms = assumptions.switching_ms(&node(from)?.node_type);
+            let queueing_ms = assumptions.queueing_ms(link.utilization, link.throughput_gbps);
+            hops.push(HopBudget {
+                hop: i + 1,
+                from: from.clone(),
+                to: to.clone(),
+                link_id: link.id.clone(),
+                link_type: link.link_type,
+                range_km: link.latency_ms * km_per_ms,
+                propagation_ms: link.latency_ms,
+                switching_ms,
+                utilization: link.utilization,
+                queueing_ms,
+                total_ms: link.latency_ms + switching_ms + queueing_ms,
+            });
+        }
+
+        let egress_processing_ms = assumptions.switching_ms(&destination.node_type);
+        let propagation_ms = hops.iter().map(|h| h.propagation_ms).sum();
+        let switching_ms = hops.iter().map(|h| h.switching_ms).sum::<f64>() + egress_processing_ms;
+        let queueing_ms = hops.iter().map(|h| h.queueing_ms).sum();
+        Ok(LatencyBudget {
+            path: path.to_vec(),
+            assumptions: assumptions.clone(),
+            hops,
+            egress_processing_ms,
+            propagation_ms,
+            switching_ms,
+            queueing_ms,
+            total_ms: propagation_ms + switching_ms + queueing_ms,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstellationLink, ConstellationNode};
+
+    fn graph() -> ConstellationGraph {
+        let mut graph = ConstellationGraph::new();
+        graph.add_node(ConstellationNode::ground_station("GS-1", "Ground 1", 40.0, -74.0, 1));
+        graph.add_node(ConstellationNode::satellite("SAT-1", "Sat 1", 40.0, -60.0, 10_500.0, 1, 55.0));
+        graph.add_node(ConstellationNode::satellite("SAT-2", "Sat 2", 45.0, -20.0, 10_500.0, 1, 55.0));
+        graph.add_node(ConstellationNode::ground_station("GS-2", "Ground 2", 51.5, 0.0, 1));
+
+        let mut up = ConstellationLink::satellite_to_ground("SG-1-1", 6.0, 1.0);
+        up.latency_ms = 40.0;
+        let mut isl = ConstellationLink::inter_satellite("ISL-1-2", 10.0);
+        isl.latency_ms = 30.0;
+        isl.utilization = 0.5;
+        isl.throughput_gbps = 5.0;
+        let mut down = ConstellationLink::satellite_to_ground("SG-2-2", 6.0, 1.0);
+        down.latency_ms = 38.0;
+        graph.add_link("GS-1", "SAT-1", up).unwrap();
+        graph.add_link("SAT-1", "SAT-2", isl).unwrap();
+        graph.add_link("SAT-2", "GS-2", down).unwrap();
+        graph
+    }
+
+    fn path(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_budget_itemizes_hops() {
+        let assumptions = LatencyAssumptions::default();
+        let budget = graph()
+            .latency_budget(&path(&["GS-1", "SAT-1", "SAT-2", "GS-2"]), &assumptions)
+            .unwrap();
+
+        assert_eq!(budget.hops.len(), 3);
+        assert!((budget.hops[0].range_km - 40.0 * VACUUM_KM_PER_MS).abs() < 1e-9);
+        assert_eq!(budget.hops[0].switching_ms, assumptions.ground_processing_ms);
+        assert_eq!(budget.hops[1].switching_ms, assumptions.satellite_switching_ms);
+
+        // ρ·L/spare: 0.5 · 12 000 bit / 5 Gbps = 1.2 µs
+        assert!((budget.hops[1].queueing_ms - 0.0012).abs() < 1e-12);
+        assert_eq!(budget.hops[0].queueing_ms, 0.0);
+
+        assert!((budget.propagation_ms - 108.0).abs() < 1e-9);
+        assert!((budget.switching_ms - (2.0 * 0.5 + 2.0 * 0.05)).abs() < 1e-12);
+        let hop_total: f64 = budget.hops.iter().map(|h| h.total_ms).sum();
+        assert!((budget.total_ms - hop_total - budget.egress_processing_ms).abs() < 1e-9);
+
+        let csv = budget.to_csv().unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("hop,from,to,link_id,link_type,range_km"));
+        assert!(lines.next().unwrap().starts_with("1,GS-1,SAT-1,SG-1-1,SatelliteToGround,"));
+        assert_eq!(lines.count(), 2);
+    }
+
+    #[test]
+    fn test_budget_rejects_unknown_hops() {
+        let graph = graph();
+        let assumptions = LatencyAssumptions::default();
+        assert!(matches!(
+            graph.latency_budget(&path(&["GS-1", "SAT-2"]), &assumptions),
+            Err(GlafError::LinkNotFound(_))
+        ));
+        assert!(matches!(
+            graph.latency_budget(&path(&["GS-1", "SAT-9"]), &assumptions),
+            Err(GlafError::NodeNotFound(_))
+        ));
+        assert!(graph.latency_budget(&path(&["GS-1"]), &assumptions).is_err());
+        assert_eq!(assumptions.queueing_ms(1.0, 0.0), assumptions.max_queueing_ms);
+    }
+}
@@ -7,16 +7,29 @@
 //! - FSO link routing with quality metrics
 //! - Path finding through mesh network
 //! - Export to visualization formats (Cytoscape, React Flow)
-
-use petgraph::graph::{DiGraph, NodeIndex};
-use petgraph::algo::{dijkstra, astar};
-use petgraph::visit::EdgeRef;
+//! - Weather-driven ground link updates with topology diffs
+//! - Terrestrial fibre backhaul between ground stations
+//! - Per-hop latency budgets of routes
+//! - Graph snapshots for shipping topology between processes, and the
+//!   standalone `glaf-server` routing service (`server` feature)
+
+use petgraph::graph::{DiGraph, EdgeIndex, NodeIndex};
+use petgraph::algo::astar;
+use petgraph::visit::{EdgeFiltered, EdgeRef};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
 pub mod routing;
 pub mod export;
+pub mod objective;
+pub mod backhaul;
+pub mod latency;
+pub mod weather;
+pub mod snapshot;
+
+#[cfg(feature = "server")]
+pub mod server;
 
 #[cfg(feature = "neo4j")]
 pub mod neo4j_client;
@@ -36,8 +49,14 @@ pub enum GlafError {
     NoPath(String, String),
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
     #[error("Neo4j error: {0}")]
     Neo4jError(String),
+    #[error("No graph loaded")]
+    NoGraph,
+    #[error("Invalid objective weights: {0}")]
+    InvalidWeights(String),
 }
 
 pub type Result<T> = std::result::Result<T, GlafError>;
@@ -141,8 +160,11 @@ pub struct ConstellationLink {
     pub link_type: LinkType,
     /// Link margin in dB (higher = better)
     pub margin_db: f64,
-    /// Current throughput in Gbps
+    /// Current throughput in Gbps: the capacity left after `utilization`
     pub throughput_gbps: f64,
+    /// Offered load / capacity (0-1)
+    #[serde(default)]
+    pub utilization: f64,
     /// Latency in milliseconds
     pub latency_ms: f64,
     /// Whether link is currently active
@@ -158,6 +180,7 @@ impl ConstellationLink {
             link_type: LinkType::InterSatellite,
             margin_db,
             throughput_gbps: 10.0, // Typical FSO throughput
+            utilization: 0.0,
             latency_ms: 0.1,       // ~30km light travel
             active: true,
             weather_score: 1.0,    // No weather in space
@@ -170,6 +193,7 @@ impl ConstellationLink {
             link_type: LinkType::SatelliteToGround,
             margin_db,
             throughput_gbps: 10.0,
+            utilization: 0.0,
             latency_ms: 5.0, // ~500km altitude
             active: true,
             weather_score,
@@ -198,6 +222,8 @@ impl ConstellationLink {
 pub struct ConstellationGraph {
     graph: DiGraph<ConstellationNode, ConstellationLink>,
     node_index: HashMap<String, NodeIndex>,
+    /// Links taken down by `apply_weather`, which alone may restore them
+    weather_disabled: HashSet<EdgeIndex>,
 }
 
 impl ConstellationGraph {
@@ -205,6 +231,7 @@ impl ConstellationGraph {
         Self {
             graph: DiGraph::new(),
             node_index: HashMap::new(),
+            weather_disabled: HashSet::new(),
         }
     }
 
@@ -235,6 +262,11 @@ impl ConstellationGraph {
         self.node_index.get(id).map(|idx| &self.graph[*idx])
     }
 
+    /// Get all nodes
+    pub fn nodes(&self) -> impl Iterator<Item = &ConstellationNode> {
+        self.graph.node_weights()
+    }
+
     /// Get all satellites
     pub fn satellites(&self) -> impl Iterator<Item = &ConstellationNode> {
         self.graph.node_weights().filter(|n| n.is_satellite())
@@ -245,38 +277,112 @@ impl ConstellationGraph {
         self.graph.node_weights().filter(|n| n.is_ground_station())
     }
 
-    /// Find shortest path between two nodes using Dijkstra
+    /// Find shortest path between two nodes using Dijkstra. Inactive links
+    /// are never traversed.
     pub fn find_path(&self, from_id: &str, to_id: &str) -> Result<Vec<String>> {
         let from_idx = self.node_index.get(from_id)
             .ok_or_else(|| GlafError::NodeNotFound(from_id.to_string()))?;
         let to_idx = self.node_index.get(to_id)
             .ok_or_else(|| GlafError::NodeNotFound(to_id.to_string()))?;
 
-        // Run Dijkstra
-        let result = dijkstra(&self.graph, *from_idx, Some(*to_idx), |e| e.weight().cost());
+        // A* with no heuristic is Dijkstra that also yields the path
+        let (_, path_nodes) = self
+            .shortest_path_excluding(*from_idx, *to_idx, &HashSet::new(), &HashSet::new())
+            .ok_or_else(|| GlafError::NoPath(from_id.to_string(), to_id.to_string()))?;
+
+        Ok(path_nodes.iter()
+            .map(|idx| self.graph[*idx].id.clone())
+            .collect())
+    }
+
+    /// Find up to `k` loopless paths in increasing cost order (Yen's algorithm).
+    /// Inactive links are never traversed.
+    pub fn k_shortest_paths(&self, from_id: &str, to_id: &str, k: usize) -> Result<Vec<Vec<String>>> {
+        let from_idx = *self.node_index.get(from_id)
+            .ok_or_else(|| GlafError::NodeNotFound(from_id.to_string()))?;
+        let to_idx = *self.node_index.get(to_id)
+            .ok_or_else(|| GlafError::NodeNotFound(to_id.to_string()))?;
 
-        if !result.contains_key(to_idx) {
-            return Err(GlafError::NoPath(from_id.to_string(), to_id.to_string()));
+        if k == 0 {
+            return Ok(Vec::new());
         }
 
-        // Reconstruct path (Dijkstra gives us costs, need to trace back)
-        // For simplicity, use A* which gives us the actual path
-        let path = astar(
-            &self.graph,
-            *from_idx,
-            |n| n == *to_idx,
-            |e| e.weight().cost(),
-            |_| 0.0, // No heuristic (same as Dijkstra)
-        );
-
-        match path {
-            Some((_, path_nodes)) => {
-                Ok(path_nodes.iter()
-                    .map(|idx| self.graph[*idx].id.clone())
-                    .collect())
+        let first = self.shortest_path_excluding(from_idx, to_idx, &HashSet::new(), &HashSet::new())
+            .ok_or_else(|| GlafError::NoPath(from_id.to_string(), to_id.to_string()))?;
+
+        let mut accepted: Vec<(f64, Vec<NodeIndex>)> = vec![first];
+        let mut candidates: Vec<(f64, Vec<NodeIndex>)> = Vec::new();
+
+        while accepted.len() < k {
+            let last = accepted.last().unwrap().1.clone();
+
+            for i in 0..last.len() - 1 {
+                let spur = last[i];
+                let root = &last[..=i];
+
+                // Block the next hop of every accepted path sharing this root
+                let banned_edges: HashSet<(NodeIndex, NodeIndex)> = accepted.iter()
+                    .filter(|(_, p)| p.len() > i + 1 && p[..=i] == *root)
+                    .map(|(_, p)| (p[i], p[i + 1]))
+                    .collect();
+                // Keep the spur path loopless
+                let banned_nodes: HashSet<NodeIndex> = root[..i].iter().copied().collect();
+
+                if let Some((_, spur_path)) =
+                    self.shortest_path_excluding(spur, to_idx, &banned_edges, &banned_nodes)
+                {
+                    let mut path = root[..i].to_vec();
+                    path.extend(spur_path);
+
+                    let known = accepted.iter().chain(candidates.iter()).any(|(_, p)| *p == path);
+                    if !known {
+                        candidates.push((self.index_path_cost(&path), path));
+                    }
+                }
+            }
+
+            if candidates.is_empty() {
+                break;
             }
-            None => Err(GlafError::NoPath(from_id.to_string(), to_id.to_string())),
+            candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+            accepted.push(candidates.remove(0));
         }
+
+        Ok(accepted.into_iter()
+            .map(|(_, path)| path.iter().map(|idx| self.graph[*idx].id.clone()).collect())
+            .collect())
+    }
+
+    /// Cheapest active path avoiding the given directed edges and nodes
+    fn shortest_path_excluding(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+        banned_edges: &HashSet<(NodeIndex, NodeIndex)>,
+        banned_nodes: &HashSet<NodeIndex>,
+    ) -> Option<(f64, Vec<NodeIndex>)> {
+        let filtered = EdgeFiltered::from_fn(&self.graph, |e| {
+            e.weight().active
+                && !banned_edges.contains(&(e.source(), e.target()))
+                && !banned_nodes.contains(&e.source())
+                && !banned_nodes.contains(&e.target())
+        });
+
+        astar(&filtered, from, |n| n == to, |e| e.weight().cost(), |_| 0.0)
+    }
+
+    fn index_path_cost(&self, path: &[NodeIndex]) -> f64 {
+        path.windows(2)
+            .filter_map(|w| self.graph.find_edge(w[0], w[1]))
+            .map(|edge| self.graph[edge].cost())
+            .sum()
+    }
+
+    /// Get the link from `from_id` to `to_id`
+    pub fn get_link(&self, from_id: &str, to_id: &str) -> Option<&ConstellationLink> {
+        let from_idx = self.node_index.get(from_id)?;
+        let to_idx = self.node_index.get(to_id)?;
+        self.graph.find_edge(*from_idx, *to_idx).map(|edge| &self.graph[edge])
     }
 
     /// Calculate total path cost
@@ -341,6 +447,7 @@ impl ConstellationGraph {
 
         let mut isl_links = 0;
         let mut gs_links = 0;
+        let mut terrestrial_links = 0;
         let mut active_links = 0;
 
         for edge in self.graph.edge_references() {
@@ -348,7 +455,7 @@ impl ConstellationGraph {
             match link.link_type {
                 LinkType::InterSatellite => isl_links += 1,
                 LinkType::SatelliteToGround => gs_links += 1,
-                LinkType::Terrestrial => {}
+                LinkType::Terrestrial => terrestrial_links += 1,
             }
             if link.active {
                 active_links += 1;
@@ -362,6 +469,7 @@ impl ConstellationGraph {
             total_links: self.graph.edge_count() / 2, // Bidirectional
             isl_links: isl_links / 2,
             gs_links: gs_links / 2,
+            terrestrial_links: terrestrial_links / 2,
             active_links: active_links / 2,
         }
     }
@@ -382,6 +490,7 @@ pub struct GraphStats {
     pub total_links: usize,
     pub isl_links: usize,
     pub gs_links: usize,
+    pub terrestrial_links: usize,
     pub active_links: usize,
 }
 
@@ -437,6 +546,40 @@ mod tests {
         assert_eq!(path.last().unwrap(), "GS-2");
     }
 
+    #[test]
+    fn test_k_shortest_paths() {
+        let graph = create_test_graph();
+
+        // Ring gives two ways round: via SAT-1 -> SAT-2 directly, or the long way
+        let paths = graph.k_shortest_paths("GS-1", "GS-2", 3).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0], vec!["GS-1", "SAT-1", "SAT-2", "GS-2"]);
+        assert_eq!(paths[1], vec!["GS-1", "SAT-1", "SAT-4", "SAT-3", "SAT-2", "GS-2"]);
+        assert!(graph.path_cost(&paths[0]) <= graph.path_cost(&paths[1]));
+    }
+
+    #[test]
+    fn test_k_shortest_skips_inactive() {
+        let mut graph = create_test_graph();
+        graph.update_link("SAT-1", "SAT-2", false, None).unwrap();
+
+        let paths = graph.k_shortest_paths("GS-1", "GS-2", 3).unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].len(), 6);
+    }
+
+    #[test]
+    fn test_find_path_skips_inactive() {
+        let mut graph = create_test_graph();
+        graph.update_link("SAT-1", "SAT-2", false, None).unwrap();
+        let path = graph.find_path("GS-1", "GS-2").unwrap();
+        assert_eq!(path, vec!["GS-1", "SAT-1", "SAT-4", "SAT-3", "SAT-2", "GS-2"]);
+
+        graph.update_link("SAT-1", "GS-1", false, None).unwrap();
+        assert!(matches!(graph.find_path("GS-1", "GS-2"), Err(GlafError::NoPath(..))));
+        assert!(graph.k_shortest_paths("SAT-1", "GS-2", 0).unwrap().is_empty());
+    }
+
     #[test]
     fn test_link_cost() {
         let link = ConstellationLink::inter_satellite("test", 10.0);
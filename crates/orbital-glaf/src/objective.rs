@@ -0,0 +1,312 @@
+//! Route objective function (RFC-9050)
+//!
+//! Multi-attribute utility over candidate routes. Each SLA tier defines:
+//! - Hard constraints: latency ceiling, margin floor, hop limit, minimum
+//!   throughput. Routes violating any are infeasible.
+//! - Weights for the normalised utility terms (latency, margin, throughput,
+//!   hops, weather). Weights sum to 1 so utility stays in 0-1.
+//!
+//! `select_optimal` returns the highest-utility feasible route together
+//! with its per-term breakdown.
+
+use crate::{ConstellationGraph, GlafError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Latency at which the latency term reaches zero (ms)
+const LATENCY_REFERENCE_MS: f64 = 200.0;
+/// Tolerance on the weight sum
+const WEIGHT_SUM_TOLERANCE: f64 = 1e-6;
+/// Margin at which the margin term saturates (dB)
+const MARGIN_REFERENCE_DB: f64 = 10.0;
+/// Throughput at which the throughput term saturates (Gbps)
+const THROUGHPUT_REFERENCE_GBPS: f64 = 10.0;
+
+/// Service level tier
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaTier {
+    /// Latency-critical traffic
+    Platinum,
+    Gold,
+    #[default]
+    Silver,
+    BestEffort,
+}
+
+/// Aggregate metrics of one candidate route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteMetrics {
+    pub path: Vec<String>,
+    /// Sum of link latencies (ms)
+    pub latency_ms: f64,
+    /// Weakest link margin (dB)
+    pub min_margin_db: f64,
+    pub avg_margin_db: f64,
+    /// Bottleneck throughput (Gbps)
+    pub throughput_gbps: f64,
+    pub hop_count: usize,
+    /// Product of link weather scores (0-1, 1 = no impact)
+    pub weather_factor: f64,
+}
+
+impl RouteMetrics {
+    /// Aggregate link metrics along `path`. Returns `None` if any hop is
+    /// missing or inactive.
+    pub fn from_path(graph: &ConstellationGraph, path: &[String]) -> Option<Self> {
+        if path.len() < 2 {
+            return None;
+        }
+
+        let mut latency_ms = 0.0;
+        let mut min_margin_db = f64::MAX;
+        let mut total_margin = 0.0;
+        let mut throughput_gbps = f64::MAX;
+        let mut weather_factor = 1.0;
+
+        for hop in path.windows(2) {
+            let link = graph.get_link(&hop[0], &hop[1])?;
+            if !link.active {
+                return None;
+            }
+            latency_ms += link.latency_ms;
+            min_margin_db = min_margin_db.min(link.margin_db);
+            total_margin += link.margin_db;
+            throughput_gbps = throughput_gbps.min(link.throughput_gbps);
+            weather_factor *= link.weather_score;
+        }
+
+        let hop_count = path.len() - 1;
+        Some(Self {
+            path: path.to_vec(),
+            latency_ms,
+            min_margin_db,
+            avg_margin_db: total_margin / hop_count as f64,
+            throughput_gbps,
+            hop_count,
+            weather_factor,
+        })
+    }
+}
+
+/// Utility weights (sum to 1)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectiveWeights {
+    pub latency: f64,
+    pub margin: f64,
+    pub throughput: f64,
+    pub hops: f64,
+    pub weather: f64,
+}
+
+impl ObjectiveWeights {
+    /// Weights must be finite, non-negative and sum to 1, or utility
+    /// leaves 0-1 and stops being comparable across tiers
+    pub fn validate(&self) -> Result<()> {
+        let weights = [self.latency, self.margin, self.throughput, self.hops, self.weather];
+        if let Some(w) = weights.iter().find(|w| !(w.is_finite() && **w >= 0.0)) {
+            return Err(GlafError::InvalidWeights(format!("{} is not a non-negative weight", w)));
+        }
+        let sum: f64 = weights.iter().sum();
+        if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+            return Err(GlafError::InvalidWeights(format!("weights sum to {}", sum)));
+        }
+        Ok(())
+    }
+}
+
+/// Hard SLA constraints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaConstraints {
+    pub max_latency_ms: f64,
+    pub min_margin_db: f64,
+    pub max_hops: usize,
+    pub min_throughput_gbps: f64,
+}
+
+/// Per-term utility contributions for one route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtilityBreakdown {
+    /// Weighted contributions (sum = `utility`)
+    pub latency: f64,
+    pub margin: f64,
+    pub throughput: f64,
+    pub hops: f64,
+    pub weather: f64,
+    pub utility: f64,
+    pub feasible: bool,
+    /// Human-readable constraint violations
+    pub violations: Vec<String>,
+}
+
+/// A candidate route with its evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluatedRoute {
+    pub metrics: RouteMetrics,
+    pub breakdown: UtilityBreakdown,
+}
+
+/// Tiered route objective function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectiveFunction {
+    pub tier: SlaTier,
+    pub weights: ObjectiveWeights,
+    pub constraints: SlaConstraints,
+}
+
+impl ObjectiveFunction {
+    pub fn for_tier(tier: SlaTier) -> Self {
+        let (weights, constraints) = match tier {
+            SlaTier::Platinum => (
+                ObjectiveWeights { latency: 0.40, margin: 0.30, throughput: 0.10, hops: 0.10, weather: 0.10 },
+                SlaConstraints { max_latency_ms: 50.0, min_margin_db: 6.0, max_hops: 8, min_throughput_gbps: 5.0 },
+            ),
+            SlaTier::Gold => (
+                ObjectiveWeights { latency: 0.30, margin: 0.30, throughput: 0.15, hops: 0.10, weather: 0.15 },
+                SlaConstraints { max_latency_ms: 100.0, min_margin_db: 3.0, max_hops: 10, min_throughput_gbps: 2.0 },
+            ),
+            SlaTier::Silver => (
+                ObjectiveWeights { latency: 0.25, margin: 0.25, throughput: 0.20, hops: 0.15, weather: 0.15 },
+                SlaConstraints { max_latency_ms: 200.0, min_margin_db: 1.0, max_hops: 12, min_throughput_gbps: 1.0 },
+            ),
+            SlaTier::BestEffort => (
+                ObjectiveWeights { latency: 0.15, margin: 0.20, throughput: 0.35, hops: 0.15, weather: 0.15 },
+                SlaConstraints { max_latency_ms: f64::INFINITY, min_margin_db: 0.0, max_hops: 16, min_throughput_gbps: 0.0 },
+            ),
+        };
+        Self { tier, weights, constraints }
+    }
+
+    /// Score a single route
+    pub fn evaluate(&self, m: &RouteMetrics) -> UtilityBreakdown {
+        let c = &self.constraints;
+        let mut violations = Vec::new();
+        if m.latency_ms > c.max_latency_ms {
+            violations.push(format!("latency {:.1} ms > {:.1} ms", m.latency_ms, c.max_latency_ms));
+        }
+        if m.min_margin_db < c.min_margin_db {
+            violations.push(format!("margin {:.1} dB < {:.1} dB", m.min_margin_db, c.min_margin_db));
+        }
+        if m.hop_count > c.max_hops {
+            violations.push(format!("{} hops > {}", m.hop_count, c.max_hops));
+        }
+        if m.throughput_gbps < c.min_throughput_gbps {
+            violations.push(format!(
+                "throughput {:.1} Gbps < {:.1} Gbps",
+                m.throughput_gbps, c.min_throughput_gbps
+            ));
+        }
+
+        let w = &self.weights;
+        let latency = w.latency * (1.0 - m.latency_ms / LATENCY_REFERENCE_MS).clamp(0.0, 1.0);
+        let margin = w.margin * (m.min_margin_db / MARGIN_REFERENCE_DB).clamp(0.0, 1.0);
+        let throughput = w.throughput * (m.throughput_gbps / THROUGHPUT_REFERENCE_GBPS).clamp(0.0, 1.0);
+        let hops = w.hops * (1.0 - m.hop_count as f64 / c.max_hops.max(1) as f64).clamp(0.0, 1.0);
+        let weather = w.weather * m.weather_factor.clamp(0.0, 1.0);
+
+        UtilityBreakdown {
+            latency,
+            margin,
+            throughput,
+            hops,
+            weather,
+            utility: latency + margin + throughput + hops + weather,
+            feasible: violations.is_empty(),
+            violations,
+        }
+    }
+
+    /// Evaluate all candidates, feasible routes first, each group by
+    /// descending utility
+    pub fn rank(&self, candidates: &[RouteMetrics]) -> Vec<EvaluatedRoute> {
+        let mut ranked: Vec<EvaluatedRoute> = candidates
+            .iter()
+            .map(|m| EvaluatedRoute {
+                metrics: m.clone(),
+                breakdown: self.evaluate(m),
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.breakdown
+                .feasible
+                .cmp(&a.breakdown.feasible)
+                .then(b.breakdown.utility.total_cmp(&a.breakdown.utility))
+        });
+        ranked
+    }
+
+    /// Highest-utility feasible route, if any
+    pub fn select_optimal(&self, candidates: &[RouteMetrics]) -> Option<EvaluatedRoute> {
+        self.rank(candidates)
+            .into_iter()
+            .next()
+            .filter(|r| r.breakdown.feasible)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(latency_ms: f64, min_margin_db: f64, hop_count: usize) -> RouteMetrics {
+        RouteMetrics {
+            path: vec![],
+            latency_ms,
+            min_margin_db,
+            avg_margin_db: min_margin_db,
+            throughput_gbps: 10.0,
+            hop_count,
+            weather_factor: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_weights_sum_to_one() {
+        for tier in [SlaTier::Platinum, SlaTier::Gold, SlaTier::Silver, SlaTier::BestEffort] {
+            let w = ObjectiveFunction::for_tier(tier).weights;
+            let sum = w.latency + w.margin + w.throughput + w.hops + w.weather;
+            assert!((sum - 1.0).abs() < 1e-9, "{:?} weights sum to {}", tier, sum);
+            assert!(w.validate().is_ok());
+        }
+
+        let mut w = ObjectiveFunction::for_tier(SlaTier::Gold).weights;
+        w.hops += 0.1;
+        assert!(matches!(w.validate(), Err(GlafError::InvalidWeights(_))));
+        w.hops = f64::NAN;
+        assert!(w.validate().is_err());
+    }
+
+    #[test]
+    fn test_platinum_rejects_slow_route() {
+        let objective = ObjectiveFunction::for_tier(SlaTier::Platinum);
+        let slow = metrics(80.0, 8.0, 4);
+        let fast = metrics(30.0, 7.0, 4);
+
+        assert!(!objective.evaluate(&slow).feasible);
+        let best = objective.select_optimal(&[slow, fast]).unwrap();
+        assert_eq!(best.metrics.latency_ms, 30.0);
+    }
+
+    #[test]
+    fn test_no_feasible_route() {
+        let objective = ObjectiveFunction::for_tier(SlaTier::Gold);
+        assert!(objective.select_optimal(&[metrics(30.0, 1.0, 3)]).is_none());
+    }
+
+    #[test]
+    fn test_route_metrics_from_graph() {
+        use crate::{ConstellationLink, ConstellationNode};
+
+        let mut graph = ConstellationGraph::new();
+        graph.add_node(ConstellationNode::ground_station("GS-1", "Ground 1", 40.0, -74.0, 1));
+        graph.add_node(ConstellationNode::satellite("SAT-1", "Sat 1", 0.0, 0.0, 550.0, 0, 53.0));
+        graph.add_node(ConstellationNode::ground_station("GS-2", "Ground 2", 51.0, 0.0, 1));
+        graph.add_link("GS-1", "SAT-1", ConstellationLink::satellite_to_ground("SG-1", 6.0, 0.9)).unwrap();
+        graph.add_link("SAT-1", "GS-2", ConstellationLink::satellite_to_ground("SG-2", 4.0, 0.8)).unwrap();
+
+        let path: Vec<String> = ["GS-1", "SAT-1", "GS-2"].iter().map(|s| s.to_string()).collect();
+        let m = RouteMetrics::from_path(&graph, &path).unwrap();
+        assert_eq!(m.hop_count, 2);
+        assert_eq!(m.min_margin_db, 4.0);
+        assert!((m.weather_factor - 0.72).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,396 @@
+//! Standalone routing service
+//!
+//! `glaf-server` holds the latest routing graph in memory and answers
+//! routing queries over HTTP, so routing can be scaled and deployed apart
+//! from the gateway. Graphs arrive as `GraphSnapshot`s, published by the
+//! gateway on `TOPOLOGY_SUBJECT` every propagation tick or loaded with
+//! `PUT /graph`; each replaces the previous one whole. Weather and
+//! backhaul are already applied by the publisher.
+//!
+//! - `GET  /health` epoch of the loaded graph, if any
+//! - `GET  /graph` graph statistics
+//! - `PUT  /graph` load a snapshot
+//! - `GET  /graph/export?format=cytoscape|react_flow`
+//! - `GET  /paths?from=&to=&k=` k shortest paths and their cost
+//! - `POST /routing/optimal` candidate routes ranked under an SLA tier's
+//!   objective function (RFC-9050)
+//! - `GET  /routing/latency-budget?path=&format=json|csv` per-hop budget
+
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use tracing::{debug, info, warn};
+
+use crate::latency::{LatencyAssumptions, LatencyBudget};
+use crate::objective::{EvaluatedRoute, ObjectiveFunction, RouteMetrics, SlaTier};
+use crate::snapshot::{GraphSnapshot, TOPOLOGY_SUBJECT};
+use crate::{ConstellationGraph, GlafError, GraphStats, Result};
+
+/// Default number of candidate routes to enumerate
+const DEFAULT_ROUTE_CANDIDATES: usize = 5;
+const MAX_ROUTE_CANDIDATES: usize = 20;
+
+/// The loaded graph and its topology epoch
+struct Loaded {
+    graph: ConstellationGraph,
+    epoch: i64,
+}
+
+/// Latest routing graph
+#[derive(Default)]
+pub struct RoutingService {
+    loaded: RwLock<Option<Loaded>>,
+}
+
+impl RoutingService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the graph with `snapshot`; the previous graph is kept if
+    /// the snapshot is inconsistent
+    pub fn load(&self, snapshot: &GraphSnapshot) -> Result<GraphStats> {
+        let graph = ConstellationGraph::from_snapshot(snapshot)?;
+        let stats = graph.stats();
+        *self.loaded.write().unwrap() = Some(Loaded {
+            graph,
+            epoch: snapshot.epoch,
+        });
+        Ok(stats)
+    }
+
+    /// Epoch of the loaded graph
+    pub fn epoch(&self) -> Option<i64> {
+        self.loaded.read().unwrap().as_ref().map(|l| l.epoch)
+    }
+
+    fn with_graph<T>(&self, f: impl FnOnce(&ConstellationGraph, i64) -> Result<T>) -> Result<T> {
+        let loaded = self.loaded.read().unwrap();
+        let loaded = loaded.as_ref().ok_or(GlafError::NoGraph)?;
+        f(&loaded.graph, loaded.epoch)
+    }
+
+    pub fn paths(&self, from: &str, to: &str, k: usize) -> Result<Vec<PathCost>> {
+        self.with_graph(|graph, _| {
+            Ok(graph
+                .k_shortest_paths(from, to, k)?
+                .into_iter()
+                .map(|path| PathCost {
+                    cost: graph.path_cost(&path),
+                    path,
+                })
+                .collect())
+        })
+    }
+
+    /// Enumerate `k` candidate routes and pick the best under the tier's
+    /// objective function
+    pub fn optimal(&self, request: &OptimalRouteRequest) -> Result<OptimalRoute> {
+        let k = request.k.unwrap_or(DEFAULT_ROUTE_CANDIDATES).clamp(1, MAX_ROUTE_CANDIDATES);
+        self.with_graph(|graph, epoch| {
+            let candidates: Vec<RouteMetrics> = graph
+                .k_shortest_paths(&request.source, &request.destination, k)?
+                .iter()
+                .filter_map(|path| RouteMetrics::from_path(graph, path))
+                .collect();
+
+            let objective = ObjectiveFunction::for_tier(request.sla_tier);
+            let mut ranked = objective.rank(&candidates).into_iter();
+            let selected = objective.select_optimal(&candidates);
+            if selected.is_some() {
+                ranked.next();
+            }
+            Ok(OptimalRoute {
+                epoch,
+                sla_tier: request.sla_tier,
+                objective,
+                selected,
+                alternatives: ranked.collect(),
+                candidates_evaluated: candidates.len(),
+            })
+        })
+    }
+
+    pub fn latency_budget(&self, path: &[String], assumptions: &LatencyAssumptions) -> Result<LatencyBudget> {
+        self.with_graph(|graph, _| graph.latency_budget(path, assumptions))
+    }
+}
+
+/// A path and its routing cost
+#[derive(Debug, Clone, Serialize)]
+pub struct PathCost {
+    pub path: Vec<String>,
+    pub cost: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OptimalRouteRequest {
+    pub source: String,
+    pub destination: String,
+    #[serde(default)]
+    pub sla_tier: SlaTier,
+    /// Number of candidate routes to enumerate (default 5)
+    pub k: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OptimalRoute {
+    /// Topology epoch of the graph routed on (unix timestamp)
+    pub epoch: i64,
+    pub sla_tier: SlaTier,
+    pub objective: ObjectiveFunction,
+    /// Best feasible route, if any candidate meets the SLA
+    pub selected: Option<EvaluatedRoute>,
+    /// Remaining candidates, best first
+    pub alternatives: Vec<EvaluatedRoute>,
+    pub candidates_evaluated: usize,
+}
+
+/// Load every snapshot published on `TOPOLOGY_SUBJECT` until the
+/// subscription ends
+pub async fn ingest(service: Arc<RoutingService>, client: async_nats::Client) -> anyhow::Result<()> {
+    let mut subscriber = client.subscribe(TOPOLOGY_SUBJECT).await?;
+    info!("Ingesting topology from {}", TOPOLOGY_SUBJECT);
+    while let Some(message) = subscriber.next().await {
+        let content_type = message
+            .headers
+            .as_ref()
+            .and_then(|h| h.get("Content-Type"))
+            .map(|v| v.as_str().to_string());
+        if content_type.as_deref().is_some_and(|c| c != "application/json") {
+            warn!("Skipping topology encoded as {}", content_type.unwrap_or_default());
+            continue;
+        }
+        let snapshot: GraphSnapshot = match serde_json::from_slice(&message.payload) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!("Bad topology snapshot: {}", e);
+                continue;
+            }
+        };
+        match service.load(&snapshot) {
+            Ok(stats) => debug!(
+                "Loaded topology at {}: {} nodes, {} links",
+                snapshot.epoch, stats.total_nodes, stats.total_links
+            ),
+            Err(e) => warn!("Rejected topology at {}: {}", snapshot.epoch, e),
+        }
+    }
+    Ok(())
+}
+
+// ========== Routes ==========
+
+type Service = State<Arc<RoutingService>>;
+
+fn status(e: GlafError) -> (StatusCode, String) {
+    let code = match e {
+        GlafError::NoGraph => StatusCode::SERVICE_UNAVAILABLE,
+        GlafError::NodeNotFound(_) | GlafError::LinkNotFound(_) => StatusCode::NOT_FOUND,
+        GlafError::NoPath(..) => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (code, e.to_string())
+}
+
+#[derive(Serialize)]
+struct Health {
+    /// Epoch of the loaded graph; absent until one arrives
+    epoch: Option<i64>,
+}
+
+/// GET /health
+async fn health(State(service): Service) -> Json<Health> {
+    Json(Health { epoch: service.epoch() })
+}
+
+/// GET /graph
+async fn stats(State(service): Service) -> std::result::Result<Json<GraphStats>, (StatusCode, String)> {
+    service.with_graph(|graph, _| Ok(graph.stats())).map(Json).map_err(status)
+}
+
+/// PUT /graph
+async fn load(
+    State(service): Service,
+    Json(snapshot): Json<GraphSnapshot>,
+) -> std::result::Result<Json<GraphStats>, (StatusCode, String)> {
+    service.load(&snapshot).map(Json).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExportFormat {
+    #[default]
+    Cytoscape,
+    ReactFlow,
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+/// GET /graph/export?format=cytoscape|react_flow
+async fn export(
+    State(service): Service,
+    Query(q): Query<ExportQuery>,
+) -> std::result::Result<Response, (StatusCode, String)> {
+    let json = service
+        .with_graph(|graph, _| {
+            Ok(match q.format {
+                ExportFormat::Cytoscape => graph.to_cytoscape_json()?,
+                ExportFormat::ReactFlow => graph.to_react_flow_json()?,
+            })
+        })
+        .map_err(status)?;
+    Ok(([(header::CONTENT_TYPE, "application/json")], json).into_response())
+}
+
+#[derive(Deserialize)]
+struct PathsQuery {
+    from: String,
+    to: String,
+    /// Number of paths (default 1)
+    k: Option<usize>,
+}
+
+/// GET /paths?from=&to=&k=
+async fn paths(
+    State(service): Service,
+    Query(q): Query<PathsQuery>,
+) -> std::result::Result<Json<Vec<PathCost>>, (StatusCode, String)> {
+    let k = q.k.unwrap_or(1).clamp(1, MAX_ROUTE_CANDIDATES);
+    service.paths(&q.from, &q.to, k).map(Json).map_err(status)
+}
+
+/// POST /routing/optimal
+async fn optimal(
+    State(service): Service,
+    Json(request): Json<OptimalRouteRequest>,
+) -> std::result::Result<Json<OptimalRoute>, (StatusCode, String)> {
+    service.optimal(&request).map(Json).map_err(status)
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BudgetFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Deserialize)]
+struct LatencyBudgetQuery {
+    /// Comma-separated node IDs, source first
+    path: String,
+    #[serde(default)]
+    format: BudgetFormat,
+}
+
+/// GET /routing/latency-budget?path=&format=json|csv
+async fn latency_budget(
+    State(service): Service,
+    Query(q): Query<LatencyBudgetQuery>,
+) -> std::result::Result<Response, (StatusCode, String)> {
+    let path: Vec<String> = q
+        .path
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let budget = service
+        .latency_budget(&path, &LatencyAssumptions::default())
+        .map_err(status)?;
+    Ok(match q.format {
+        BudgetFormat::Json => Json(budget).into_response(),
+        BudgetFormat::Csv => {
+            let csv = budget.to_csv().map_err(status)?;
+            ([(header::CONTENT_TYPE, "text/csv")], csv).into_response()
+        }
+    })
+}
+
+pub fn router(service: Arc<RoutingService>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/graph", get(stats).put(load))
+        .route("/graph/export", get(export))
+        .route("/paths", get(paths))
+        .route("/routing/optimal", post(optimal))
+        .route("/routing/latency-budget", get(latency_budget))
+        .with_state(service)
+}
+
+/// Serve `service` until the process is stopped
+pub async fn serve(service: Arc<RoutingService>, addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("GLAF routing server listening on http://{}", addr);
+    axum::serve(listener, router(service)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstellationLink, ConstellationNode};
+
+    fn snapshot(epoch: i64) -> GraphSnapshot {
+        let mut graph = ConstellationGraph::new();
+        graph.add_node(ConstellationNode::ground_station("GS-1", "Ground 1", 40.0, -74.0, 1));
+        graph.add_node(ConstellationNode::satellite("SAT-1", "Sat 1", 40.0, -60.0, 10_500.0, 1, 55.0));
+        graph.add_node(ConstellationNode::satellite("SAT-2", "Sat 2", 45.0, -20.0, 10_500.0, 2, 55.0));
+        graph.add_node(ConstellationNode::ground_station("GS-2", "Ground 2", 51.5, 0.0, 1));
+        for (from, to, link) in [
+            ("GS-1", "SAT-1", ConstellationLink::satellite_to_ground("SG-1-1", 6.0, 1.0)),
+            ("SAT-1", "SAT-2", ConstellationLink::inter_satellite("ISL-1-2", 10.0)),
+            ("SAT-2", "GS-2", ConstellationLink::satellite_to_ground("SG-2-2", 6.0, 1.0)),
+            ("GS-1", "SAT-2", ConstellationLink::satellite_to_ground("SG-1-2", 4.0, 0.8)),
+        ] {
+            graph.add_link(from, to, link).unwrap();
+        }
+        graph.to_snapshot(epoch)
+    }
+
+    #[test]
+    fn test_routes_on_loaded_graph() {
+        let service = RoutingService::new();
+        assert!(matches!(service.paths("GS-1", "GS-2", 1), Err(GlafError::NoGraph)));
+
+        service.load(&snapshot(100)).unwrap();
+        assert_eq!(service.epoch(), Some(100));
+
+        let paths = service.paths("GS-1", "GS-2", 2).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].path, ["GS-1", "SAT-2", "GS-2"]);
+        assert!(paths[0].cost <= paths[1].cost);
+
+        let request = OptimalRouteRequest {
+            source: "GS-1".to_string(),
+            destination: "GS-2".to_string(),
+            sla_tier: SlaTier::BestEffort,
+            k: None,
+        };
+        let route = service.optimal(&request).unwrap();
+        assert_eq!((route.epoch, route.candidates_evaluated), (100, 2));
+        assert!(route.selected.is_some());
+        assert_eq!(route.alternatives.len(), 1);
+
+        let path: Vec<String> = paths[0].path.clone();
+        assert_eq!(service.latency_budget(&path, &LatencyAssumptions::default()).unwrap().hops.len(), 2);
+        assert!(matches!(service.paths("GS-1", "GS-9", 1), Err(GlafError::NodeNotFound(_))));
+
+        // A broken snapshot leaves the loaded graph in place
+        let mut broken = snapshot(200);
+        broken.links[0].source = "GS-9".to_string();
+        assert!(service.load(&broken).is_err());
+        assert_eq!(service.epoch(), Some(100));
+    }
+}
@@ -0,0 +1,103 @@
+//! Serializable graph snapshots
+//!
+//! `ConstellationGraph` is indexed by petgraph, so it travels between
+//! processes as a `GraphSnapshot`: the nodes plus each bidirectional link
+//! once, with its endpoints. The gateway publishes the routing graph of
+//! every propagation tick as JSON on `TOPOLOGY_SUBJECT`, which
+//! `glaf-server` ingests.
+
+use crate::{ConstellationGraph, ConstellationLink, ConstellationNode, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// NATS subject of published routing graphs
+pub const TOPOLOGY_SUBJECT: &str = "orbital.topology.graph";
+
+/// One link and the nodes it joins
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotLink {
+    pub source: String,
+    pub target: String,
+    #[serde(flatten)]
+    pub link: ConstellationLink,
+}
+
+/// A whole graph at one epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphSnapshot {
+    /// Topology epoch (unix timestamp)
+    pub epoch: i64,
+    pub nodes: Vec<ConstellationNode>,
+    pub links: Vec<SnapshotLink>,
+}
+
+impl ConstellationGraph {
+    /// Snapshot of the graph at `epoch`
+    pub fn to_snapshot(&self, epoch: i64) -> GraphSnapshot {
+        let mut seen = HashSet::new();
+        let links = self
+            .links()
+            .filter(|(source, target, _)| {
+                // add_link inserts both directions; keep the first
+                seen.insert((target.id.as_str(), source.id.as_str()));
+                !seen.contains(&(source.id.as_str(), target.id.as_str()))
+            })
+            .map(|(source, target, link)| SnapshotLink {
+                source: source.id.clone(),
+                target: target.id.clone(),
+                link: link.clone(),
+            })
+            .collect();
+        GraphSnapshot {
+            epoch,
+            nodes: self.nodes().cloned().collect(),
+            links,
+        }
+    }
+
+    /// Rebuild a graph; fails on a link to an unknown node
+    pub fn from_snapshot(snapshot: &GraphSnapshot) -> Result<Self> {
+        let mut graph = ConstellationGraph::new();
+        for node in &snapshot.nodes {
+            graph.add_node(node.clone());
+        }
+        for link in &snapshot.links {
+            graph.add_link(&link.source, &link.target, link.link.clone())?;
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GlafError;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut graph = ConstellationGraph::new();
+        graph.add_node(ConstellationNode::ground_station("GS-1", "Ground 1", 40.0, -74.0, 1));
+        graph.add_node(ConstellationNode::satellite("SAT-1", "Sat 1", 40.0, -60.0, 10_500.0, 1, 55.0));
+        graph.add_node(ConstellationNode::satellite("SAT-2", "Sat 2", 45.0, -20.0, 10_500.0, 1, 55.0));
+        graph.add_link("GS-1", "SAT-1", ConstellationLink::satellite_to_ground("SG-1-1", 6.0, 0.9)).unwrap();
+        graph.add_link("SAT-1", "SAT-2", ConstellationLink::inter_satellite("ISL-1-2", 8.0)).unwrap();
+        graph.update_link("SAT-1", "SAT-2", false, Some(2.0)).unwrap();
+
+        let snapshot = graph.to_snapshot(1_767_484_800);
+        assert_eq!((snapshot.nodes.len(), snapshot.links.len()), (3, 2));
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let rebuilt = ConstellationGraph::from_snapshot(&serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(rebuilt.stats().total_links, 2);
+        let isl = rebuilt.get_link("SAT-2", "SAT-1").unwrap();
+        assert!(!isl.active && isl.margin_db == 2.0);
+        assert_eq!(rebuilt.find_path("GS-1", "SAT-1").unwrap().len(), 2);
+
+        let mut broken = snapshot;
+        broken.links[0].target = "SAT-9".to_string();
+        assert!(matches!(
+            ConstellationGraph::from_snapshot(&broken),
+            Err(GlafError::NodeNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,246 @@
+//! Weather-driven ground link updates
+//!
+//! Applies station FSO weather scores to every satellite-to-ground link
+//! touching the station, so routing reacts to weather without callers
+//! patching links one at a time with `update_link`:
+//! - `weather_score` becomes the station's quality score
+//! - `margin_db` moves by the change in weather penalty (the same
+//!   -10·log10(score) term as the ground-station link budget), so margin
+//!   from elevation and faults already on the link is kept
+//! - links drop out when the station is not viable or margin goes
+//!   non-positive, and come back when weather clears; links made inactive
+//!   by anything other than weather are never reactivated here
+//!
+//! `StationWeather` is a field-compatible subset of the ground-station
+//! `FsoWeatherScore`, so scores from the gateway weather state or a NATS
+//! payload deserialize into it directly. Each application returns a
+//! `TopologyDiff` of the links that changed.
+
+use crate::{ConstellationGraph, GlafError, LinkType, NodeType, Result};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+
+/// Scores below this are treated as this (total blockage caps at 20 dB)
+const MIN_WEATHER_SCORE: f64 = 0.01;
+
+/// Changes smaller than this are not reported
+const EPSILON: f64 = 1e-9;
+
+/// FSO weather at one station
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationWeather {
+    pub station_id: String,
+    /// Overall quality score (0-1, 1 = clear)
+    pub quality: f64,
+    /// Whether an optical link is viable at all
+    pub link_viable: bool,
+    #[serde(default)]
+    pub degradation_reason: Option<String>,
+}
+
+/// One link's state before and after a weather update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkChange {
+    pub link_id: String,
+    pub source: String,
+    pub target: String,
+    pub weather_score_before: f64,
+    pub weather_score_after: f64,
+    pub margin_db_before: f64,
+    pub margin_db_after: f64,
+    pub active_before: bool,
+    pub active_after: bool,
+}
+
+impl LinkChange {
+    /// Whether the link went up or down
+    pub fn toggled(&self) -> bool {
+        self.active_before != self.active_after
+    }
+}
+
+/// Links changed by one batch of weather scores
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopologyDiff {
+    /// Stations whose scores were applied
+    pub stations: Vec<String>,
+    /// Station IDs not in the graph (or not ground stations)
+    pub unmatched: Vec<String>,
+    pub changes: Vec<LinkChange>,
+}
+
+impl TopologyDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn deactivated(&self) -> impl Iterator<Item = &LinkChange> {
+        self.changes.iter().filter(|c| c.active_before && !c.active_after)
+    }
+
+    pub fn reactivated(&self) -> impl Iterator<Item = &LinkChange> {
+        self.changes.iter().filter(|c| !c.active_before && c.active_after)
+    }
+}
+
+/// Link budget loss for a weather score (dB)
+pub fn weather_penalty_db(weather_score: f64) -> f64 {
+    -10.0 * weather_score.clamp(MIN_WEATHER_SCORE, 1.0).log10()
+}
+
+impl ConstellationGraph {
+    /// Apply one station's weather to its ground links; links are
+    /// reported once although both directions are updated
+    pub fn apply_station_weather(&mut self, weather: &StationWeather) -> Result<Vec<LinkChange>> {
+        let idx = *self
+            .node_index
+            .get(&weather.station_id)
+            .ok_or_else(|| GlafError::NodeNotFound(weather.station_id.clone()))?;
+        let quality = weather.quality.clamp(0.0, 1.0);
+
+        match &mut self.graph[idx].node_type {
+            NodeType::GroundStation { weather_score, .. } => *weather_score = quality,
+            NodeType::Satellite { .. } => return Err(GlafError::NodeNotFound(weather.station_id.clone())),
+        }
+
+        let edges: Vec<_> = self
+            .graph
+            .edges_directed(idx, Direction::Outgoing)
+            .chain(self.graph.edges_directed(idx, Direction::Incoming))
+            .filter(|e| e.weight().link_type == LinkType::SatelliteToGround)
+            .map(|e| (e.id(), e.source(), e.target()))
+            .collect();
+
+        let mut changes: Vec<LinkChange> = Vec::new();
+        for (edge, source, target) in edges {
+            let (source, target) = (self.graph[source].id.clone(), self.graph[target].id.clone());
+            let link = &mut self.graph[edge];
+            let before = (link.weather_score, link.margin_db, link.active);
+
+            link.margin_db += weather_penalty_db(link.weather_score) - weather_penalty_db(quality);
+            link.weather_score = quality;
+            let weather_ok = weather.link_viable && link.margin_db > 0.0;
+            if link.active && !weather_ok {
+                link.active = false;
+                self.weather_disabled.insert(edge);
+            } else if !link.active && weather_ok && self.weather_disabled.remove(&edge) {
+                link.active = true;
+            }
+
+            let changed = (link.weather_score - before.0).abs() > EPSILON
+                || (link.margin_db - before.1).abs() > EPSILON
+                || link.active != before.2;
+            if changed && !changes.iter().any(|c| c.link_id == link.id) {
+                changes.push(LinkChange {
+                    link_id: link.id.clone(),
+                    source,
+                    target,
+                    weather_score_before: before.0,
+                    weather_score_after: link.weather_score,
+                    margin_db_before: before.1,
+                    margin_db_after: link.margin_db,
+                    active_before: before.2,
+                    active_after: link.active,
+                });
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Apply a batch of station scores; stations not in the graph are
+    /// listed in the diff rather than failing the batch
+    pub fn apply_weather<'a>(&mut self, scores: impl IntoIterator<Item = &'a StationWeather>) -> TopologyDiff {
+        let mut diff = TopologyDiff::default();
+        for weather in scores {
+            match self.apply_station_weather(weather) {
+                Ok(changes) => {
+                    diff.stations.push(weather.station_id.clone());
+                    diff.changes.extend(changes);
+                }
+                Err(_) => diff.unmatched.push(weather.station_id.clone()),
+            }
+        }
+        tracing::debug!(
+            "Weather applied to {} stations: {} links changed",
+            diff.stations.len(),
+            diff.changes.len()
+        );
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConstellationLink, ConstellationNode};
+
+    fn graph() -> ConstellationGraph {
+        let mut graph = ConstellationGraph::new();
+        graph.add_node(ConstellationNode::satellite("SAT-1", "Sat 1", 0.0, 0.0, 550.0, 0, 53.0));
+        graph.add_node(ConstellationNode::satellite("SAT-2", "Sat 2", 0.0, 90.0, 550.0, 0, 53.0));
+        graph.add_node(ConstellationNode::ground_station("GS-1", "Ground 1", 40.0, -74.0, 1));
+        graph.add_link("SAT-1", "SAT-2", ConstellationLink::inter_satellite("ISL-1-2", 8.0)).unwrap();
+        graph.add_link("SAT-1", "GS-1", ConstellationLink::satellite_to_ground("SG-1-1", 6.0, 1.0)).unwrap();
+        graph.add_link("SAT-2", "GS-1", ConstellationLink::satellite_to_ground("SG-2-1", 6.0, 1.0)).unwrap();
+        graph
+    }
+
+    fn weather(station_id: &str, quality: f64, link_viable: bool) -> StationWeather {
+        StationWeather {
+            station_id: station_id.to_string(),
+            quality,
+            link_viable,
+            degradation_reason: None,
+        }
+    }
+
+    #[test]
+    fn test_weather_updates_ground_links() {
+        let mut graph = graph();
+        let diff = graph.apply_weather(&[weather("GS-1", 0.5, true), weather("GS-9", 0.5, true)]);
+        assert_eq!(diff.stations, vec!["GS-1"]);
+        assert_eq!(diff.unmatched, vec!["GS-9"]);
+        assert_eq!(diff.changes.len(), 2);
+        assert_eq!(diff.deactivated().count(), 0);
+
+        // Both directions updated, -3 dB for half quality
+        for (a, b) in [("SAT-1", "GS-1"), ("GS-1", "SAT-1")] {
+            let link = graph.get_link(a, b).unwrap();
+            assert!((link.margin_db - (6.0 - 3.0103)).abs() < 1e-3);
+            assert_eq!(link.weather_score, 0.5);
+        }
+        assert_eq!(graph.get_link("SAT-1", "SAT-2").unwrap().margin_db, 8.0);
+
+        // Same scores again change nothing
+        assert!(graph.apply_weather(&[weather("GS-1", 0.5, true)]).is_empty());
+    }
+
+    #[test]
+    fn test_weather_toggles_only_its_own_outages() {
+        let mut graph = graph();
+        graph.update_link("SAT-2", "GS-1", false, None).unwrap();
+
+        let diff = graph.apply_weather(&[weather("GS-1", 0.1, false)]);
+        assert_eq!(diff.deactivated().map(|c| c.link_id.as_str()).collect::<Vec<_>>(), vec!["SG-1-1"]);
+        assert!(graph.find_path("SAT-1", "GS-1").is_err());
+
+        // Clears: weather's outage lifts, the manual one stays
+        let diff = graph.apply_weather(&[weather("GS-1", 1.0, true)]);
+        assert_eq!(diff.reactivated().count(), 1);
+        assert!(graph.get_link("SAT-1", "GS-1").unwrap().active);
+        assert!(!graph.get_link("SAT-2", "GS-1").unwrap().active);
+        assert!((graph.get_link("SAT-1", "GS-1").unwrap().margin_db - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_deserializes_fso_score_json() {
+        let json = r#"{"station_id":"GS-1","quality":0.42,"cloud_score":0.3,"visibility_score":0.9,
+            "precip_score":1.0,"turbulence_score":0.8,"sunshine_score":0.7,"clear_night_score":0.6,
+            "air_quality_score":0.9,"link_viable":true,"degradation_reason":null}"#;
+        let weather: StationWeather = serde_json::from_str(json).unwrap();
+        assert_eq!(weather.quality, 0.42);
+        assert!(weather.link_viable);
+    }
+}
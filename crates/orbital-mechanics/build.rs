@@ -0,0 +1,21 @@
+//! Regenerates `include/orbital_mechanics.h` from `src/ffi.rs` when the
+//! `cdylib` feature is on; the header is checked in for C builds that
+//! never run cargo.
+
+fn main() {
+    #[cfg(feature = "cdylib")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+            .expect("reading cbindgen.toml");
+        cbindgen::Builder::new()
+            .with_crate(&crate_dir)
+            .with_config(config)
+            .generate()
+            .expect("generating C header")
+            .write_to_file(format!("{}/include/orbital_mechanics.h", crate_dir));
+    }
+}
@@ -0,0 +1,134 @@
+#ifndef ORBITAL_MECHANICS_H
+#define ORBITAL_MECHANICS_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdint.h>
+
+/**
+ * Bumped on any incompatible change to the functions or types below
+ */
+#define ORB_ABI_VERSION 2
+
+typedef enum OrbStatus {
+  ORB_STATUS_OK = 0,
+  ORB_STATUS_NULL_POINTER = 1,
+  ORB_STATUS_INVALID_TLE = 2,
+  ORB_STATUS_PROPAGATION_FAILED = 3,
+  ORB_STATUS_INVALID_COORDINATES = 4,
+  ORB_STATUS_INVALID_TIME = 5,
+  ORB_STATUS_INTERNAL = 6,
+} OrbStatus;
+
+/**
+ * ECI (TEME) state, km and km/s
+ */
+typedef struct OrbStateVector {
+  double x_km;
+  double y_km;
+  double z_km;
+  double vx_km_s;
+  double vy_km_s;
+  double vz_km_s;
+} OrbStateVector;
+
+/**
+ * WGS84 geodetic position
+ */
+typedef struct OrbGeodetic {
+  double latitude_deg;
+  double longitude_deg;
+  double altitude_km;
+} OrbGeodetic;
+
+/**
+ * Earth-fixed Cartesian position, km
+ */
+typedef struct OrbCartesian {
+  double x_km;
+  double y_km;
+  double z_km;
+} OrbCartesian;
+
+/**
+ * Pointing from a ground site
+ */
+typedef struct OrbLookAngles {
+  double azimuth_deg;
+  double elevation_deg;
+  double range_km;
+} OrbLookAngles;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+uint32_t orb_abi_version(void);
+
+/**
+ * Static description of a status; never null
+ */
+const char *orb_status_message(OrbStatus status);
+
+/**
+ * SGP4 state of a two-line element set at `unix_s`
+ *
+ * # Safety
+ * `line1` and `line2` are null or NUL-terminated; `out` is null or valid
+ * for writes
+ */
+OrbStatus orb_propagate(const char *line1,
+                        const char *line2,
+                        double unix_s,
+                        OrbStateVector *out);
+
+/**
+ * Sub-satellite point of a two-line element set at `unix_s`
+ *
+ * # Safety
+ * As `orb_propagate`
+ */
+OrbStatus orb_ground_track(const char *line1, const char *line2, double unix_s, OrbGeodetic *out);
+
+/**
+ * Rotate an inertial position at `unix_s` into the Earth-fixed frame
+ *
+ * # Safety
+ * `out` is null or valid for writes
+ */
+OrbStatus orb_eci_to_ecef(double x_km, double y_km, double z_km, double unix_s, OrbCartesian *out);
+
+/**
+ * Geodetic position of an Earth-fixed point; inertial positions go
+ * through `orb_eci_to_ecef` first
+ *
+ * # Safety
+ * `out` is null or valid for writes
+ */
+OrbStatus orb_ecef_to_geodetic(double x_km, double y_km, double z_km, OrbGeodetic *out);
+
+/**
+ * Earth-fixed position of a geodetic point
+ *
+ * # Safety
+ * `pos` is null or valid for reads; `out` is null or valid for writes
+ */
+OrbStatus orb_geodetic_to_ecef(const OrbGeodetic *pos, OrbCartesian *out);
+
+/**
+ * Look angles from `site` to a satellite at Earth-fixed `(x, y, z)` km
+ *
+ * # Safety
+ * `site` is null or valid for reads; `out` is null or valid for writes
+ */
+OrbStatus orb_look_angles(const OrbGeodetic *site,
+                          double x_km,
+                          double y_km,
+                          double z_km,
+                          OrbLookAngles *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ORBITAL_MECHANICS_H */
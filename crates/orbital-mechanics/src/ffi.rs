@@ -0,0 +1,325 @@
+//! C ABI
+//!
+//! Propagation, transforms and look angles for C/C++ callers, built with
+//! the `cdylib` feature. The header is generated into
+//! `include/orbital_mechanics.h` by the build script.
+//!
+//! Conventions, which are part of the ABI:
+//!
+//! - Every function returns an `OrbStatus`; results go through out
+//!   pointers, which are left untouched on failure
+//! - Times are Unix seconds (UTC) as `double`
+//! - TLE lines are NUL-terminated ASCII
+//! - Nothing allocates on behalf of the caller, and no panic crosses the
+//!   boundary (it is reported as `ORB_STATUS_INTERNAL`)
+//!
+//! Additions keep `ORB_ABI_VERSION`; changing or removing anything here
+//! bumps it.
+
+use crate::{propagation, transforms, GeodeticPosition, OrbitalError};
+use chrono::DateTime;
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Bumped on any incompatible change to the functions or types below
+pub const ORB_ABI_VERSION: u32 = 2;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrbStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidTle = 2,
+    PropagationFailed = 3,
+    InvalidCoordinates = 4,
+    InvalidTime = 5,
+    Internal = 6,
+}
+
+/// ECI (TEME) state, km and km/s
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrbStateVector {
+    pub x_km: f64,
+    pub y_km: f64,
+    pub z_km: f64,
+    pub vx_km_s: f64,
+    pub vy_km_s: f64,
+    pub vz_km_s: f64,
+}
+
+/// WGS84 geodetic position
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrbGeodetic {
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    pub altitude_km: f64,
+}
+
+/// Earth-fixed Cartesian position, km
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrbCartesian {
+    pub x_km: f64,
+    pub y_km: f64,
+    pub z_km: f64,
+}
+
+/// Pointing from a ground site
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrbLookAngles {
+    pub azimuth_deg: f64,
+    pub elevation_deg: f64,
+    pub range_km: f64,
+}
+
+impl From<OrbitalError> for OrbStatus {
+    fn from(e: OrbitalError) -> Self {
+        match e {
+            OrbitalError::InvalidTle(_) => OrbStatus::InvalidTle,
+            OrbitalError::PropagationFailed(_) | OrbitalError::StaleElements(_) => OrbStatus::PropagationFailed,
+            OrbitalError::InvalidCoordinates(_) => OrbStatus::InvalidCoordinates,
+            // No entry point takes Earth orientation data
+            OrbitalError::InvalidEop(_) => OrbStatus::Internal,
+        }
+    }
+}
+
+impl From<OrbGeodetic> for GeodeticPosition {
+    fn from(g: OrbGeodetic) -> Self {
+        GeodeticPosition {
+            latitude: g.latitude_deg,
+            longitude: g.longitude_deg,
+            altitude_km: g.altitude_km,
+        }
+    }
+}
+
+impl From<GeodeticPosition> for OrbGeodetic {
+    fn from(g: GeodeticPosition) -> Self {
+        OrbGeodetic {
+            latitude_deg: g.latitude,
+            longitude_deg: g.longitude,
+            altitude_km: g.altitude_km,
+        }
+    }
+}
+
+/// Run `f`, writing its value to `out`; panics become `Internal`
+fn guarded<T>(out: *mut T, f: impl FnOnce() -> Result<T, OrbStatus>) -> OrbStatus {
+    if out.is_null() {
+        return OrbStatus::NullPointer;
+    }
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => {
+            // SAFETY: non-null, and the caller guarantees it points to a T
+            unsafe { out.write(value) };
+            OrbStatus::Ok
+        }
+        Ok(Err(status)) => status,
+        Err(_) => OrbStatus::Internal,
+    }
+}
+
+/// # Safety
+/// `p` is null or a NUL-terminated string
+unsafe fn tle_line<'a>(p: *const c_char) -> Result<&'a str, OrbStatus> {
+    if p.is_null() {
+        return Err(OrbStatus::NullPointer);
+    }
+    CStr::from_ptr(p).to_str().map_err(|_| OrbStatus::InvalidTle)
+}
+
+fn utc(unix_s: f64) -> Result<DateTime<chrono::Utc>, OrbStatus> {
+    if !unix_s.is_finite() {
+        return Err(OrbStatus::InvalidTime);
+    }
+    let micros = (unix_s * 1e6).round() as i64;
+    DateTime::from_timestamp(micros.div_euclid(1_000_000), micros.rem_euclid(1_000_000) as u32 * 1000)
+        .ok_or(OrbStatus::InvalidTime)
+}
+
+#[no_mangle]
+pub extern "C" fn orb_abi_version() -> u32 {
+    ORB_ABI_VERSION
+}
+
+/// Static description of a status; never null
+#[no_mangle]
+pub extern "C" fn orb_status_message(status: OrbStatus) -> *const c_char {
+    let msg: &'static [u8] = match status {
+        OrbStatus::Ok => b"ok\0",
+        OrbStatus::NullPointer => b"null pointer argument\0",
+        OrbStatus::InvalidTle => b"invalid TLE\0",
+        OrbStatus::PropagationFailed => b"propagation failed\0",
+        OrbStatus::InvalidCoordinates => b"invalid coordinates\0",
+        OrbStatus::InvalidTime => b"time out of range\0",
+        OrbStatus::Internal => b"internal error\0",
+    };
+    msg.as_ptr() as *const c_char
+}
+
+/// SGP4 state of a two-line element set at `unix_s`
+///
+/// # Safety
+/// `line1` and `line2` are null or NUL-terminated; `out` is null or valid
+/// for writes
+#[no_mangle]
+pub unsafe extern "C" fn orb_propagate(
+    line1: *const c_char,
+    line2: *const c_char,
+    unix_s: f64,
+    out: *mut OrbStateVector,
+) -> OrbStatus {
+    guarded(out, || {
+        let sv = propagation::sgp4_propagate(tle_line(line1)?, tle_line(line2)?, utc(unix_s)?)?;
+        Ok(OrbStateVector {
+            x_km: sv.position_x,
+            y_km: sv.position_y,
+            z_km: sv.position_z,
+            vx_km_s: sv.velocity_x,
+            vy_km_s: sv.velocity_y,
+            vz_km_s: sv.velocity_z,
+        })
+    })
+}
+
+/// Sub-satellite point of a two-line element set at `unix_s`
+///
+/// # Safety
+/// As `orb_propagate`
+#[no_mangle]
+pub unsafe extern "C" fn orb_ground_track(
+    line1: *const c_char,
+    line2: *const c_char,
+    unix_s: f64,
+    out: *mut OrbGeodetic,
+) -> OrbStatus {
+    guarded(out, || {
+        let sv = propagation::sgp4_propagate(tle_line(line1)?, tle_line(line2)?, utc(unix_s)?)?;
+        Ok(sv.to_geodetic()?.into())
+    })
+}
+
+/// Rotate an inertial position at `unix_s` into the Earth-fixed frame
+///
+/// # Safety
+/// `out` is null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn orb_eci_to_ecef(x_km: f64, y_km: f64, z_km: f64, unix_s: f64, out: *mut OrbCartesian) -> OrbStatus {
+    guarded(out, || {
+        let (x_km, y_km, z_km) = transforms::eci_to_ecef(x_km, y_km, z_km, utc(unix_s)?);
+        Ok(OrbCartesian { x_km, y_km, z_km })
+    })
+}
+
+/// Geodetic position of an Earth-fixed point; inertial positions go
+/// through `orb_eci_to_ecef` first
+///
+/// # Safety
+/// `out` is null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn orb_ecef_to_geodetic(x_km: f64, y_km: f64, z_km: f64, out: *mut OrbGeodetic) -> OrbStatus {
+    guarded(out, || Ok(transforms::ecef_to_geodetic(x_km, y_km, z_km)?.into()))
+}
+
+/// Earth-fixed position of a geodetic point
+///
+/// # Safety
+/// `pos` is null or valid for reads; `out` is null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn orb_geodetic_to_ecef(pos: *const OrbGeodetic, out: *mut OrbCartesian) -> OrbStatus {
+    guarded(out, || {
+        let pos = pos.as_ref().ok_or(OrbStatus::NullPointer)?;
+        let (x_km, y_km, z_km) = transforms::geodetic_to_eci(&(*pos).into())?;
+        Ok(OrbCartesian { x_km, y_km, z_km })
+    })
+}
+
+/// Look angles from `site` to a satellite at Earth-fixed `(x, y, z)` km
+///
+/// # Safety
+/// `site` is null or valid for reads; `out` is null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn orb_look_angles(
+    site: *const OrbGeodetic,
+    x_km: f64,
+    y_km: f64,
+    z_km: f64,
+    out: *mut OrbLookAngles,
+) -> OrbStatus {
+    guarded(out, || {
+        let site = site.as_ref().ok_or(OrbStatus::NullPointer)?;
+        let look = transforms::look_angles(&(*site).into(), x_km, y_km, z_km)?;
+        Ok(OrbLookAngles {
+            azimuth_deg: look.azimuth_deg,
+            elevation_deg: look.elevation_deg,
+            range_km: look.range_km,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::ffi::CString;
+
+    const ISS1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+    const ISS2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+    #[test]
+    fn test_propagate_matches_library() {
+        let (l1, l2) = (CString::new(ISS1).unwrap(), CString::new(ISS2).unwrap());
+        let time = Utc.with_ymd_and_hms(2008, 9, 21, 12, 0, 0).unwrap();
+        let expected = propagation::sgp4_propagate(ISS1, ISS2, time).unwrap();
+
+        let mut sv = OrbStateVector::default();
+        let status = unsafe { orb_propagate(l1.as_ptr(), l2.as_ptr(), time.timestamp() as f64, &mut sv) };
+        assert_eq!(status, OrbStatus::Ok);
+        assert_eq!((sv.x_km, sv.vz_km_s), (expected.position_x, expected.velocity_z));
+
+        let mut geo = OrbGeodetic::default();
+        let status = unsafe { orb_ground_track(l1.as_ptr(), l2.as_ptr(), time.timestamp() as f64, &mut geo) };
+        assert_eq!(status, OrbStatus::Ok);
+        let mut ecef = OrbCartesian::default();
+        let status = unsafe { orb_eci_to_ecef(sv.x_km, sv.y_km, sv.z_km, time.timestamp() as f64, &mut ecef) };
+        assert_eq!(status, OrbStatus::Ok);
+        let mut look = OrbLookAngles::default();
+        let site = OrbGeodetic {
+            altitude_km: 0.0,
+            ..geo
+        };
+        assert_eq!(unsafe { orb_look_angles(&site, ecef.x_km, ecef.y_km, ecef.z_km, &mut look) }, OrbStatus::Ok);
+        assert!(look.elevation_deg > 89.9);
+        assert!((look.range_km - geo.altitude_km).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_errors_leave_output_untouched() {
+        let l1 = CString::new("1 25544U not an element set").unwrap();
+        let l2 = CString::new(ISS2).unwrap();
+        let mut sv = OrbStateVector {
+            x_km: 42.0,
+            ..Default::default()
+        };
+        assert_eq!(unsafe { orb_propagate(l1.as_ptr(), l2.as_ptr(), 0.0, &mut sv) }, OrbStatus::InvalidTle);
+        assert_eq!(unsafe { orb_propagate(std::ptr::null(), l2.as_ptr(), 0.0, &mut sv) }, OrbStatus::NullPointer);
+        assert_eq!(unsafe { orb_propagate(l1.as_ptr(), l2.as_ptr(), f64::NAN, &mut sv) }, OrbStatus::InvalidTime);
+        assert_eq!(sv.x_km, 42.0);
+
+        let mut xyz = OrbCartesian::default();
+        let bad = OrbGeodetic {
+            latitude_deg: 91.0,
+            ..Default::default()
+        };
+        assert_eq!(unsafe { orb_geodetic_to_ecef(&bad, &mut xyz) }, OrbStatus::InvalidCoordinates);
+        assert_eq!(unsafe { orb_ecef_to_geodetic(0.0, 0.0, 0.0, std::ptr::null_mut()) }, OrbStatus::NullPointer);
+
+        let msg = unsafe { CStr::from_ptr(orb_status_message(OrbStatus::InvalidTle)) };
+        assert_eq!(msg.to_str().unwrap(), "invalid TLE");
+        assert_eq!(orb_abi_version(), ORB_ABI_VERSION);
+    }
+}
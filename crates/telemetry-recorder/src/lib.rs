@@ -0,0 +1,20 @@
+//! Telemetry Recorder
+//!
+//! Captures NATS traffic (subject, headers, payload and receive time) to
+//! segmented files and plays it back, so UI development and routing
+//! experiments can run against a real session without the simulator
+//! fleet:
+//!
+//! - Segment format, writer with size/age rotation, and a reader that
+//!   walks a recording's segments in order
+//! - Playback pacing at original or accelerated speed, and NATS subject
+//!   filters
+//!
+//! Payloads are stored as received, whatever their codec; the headers
+//! (`Content-Type`, `Sx9-Schema`) go with them.
+
+pub mod playback;
+pub mod segment;
+
+pub use playback::{subject_matches, Pacer};
+pub use segment::{Record, RecordingReader, SegmentWriter};
@@ -0,0 +1,208 @@
+//! Telemetry Recorder CLI
+//!
+//! Usage:
+//!   orbital-recorder record --out recordings/pass-1 --subject 'orbital.>'
+//!   orbital-recorder play   --from recordings/pass-1 --speed 10 --loop
+//!   orbital-recorder info   --from recordings/pass-1
+//!
+//! `record` subscribes on core NATS and writes until interrupted. `play`
+//! republishes every record (headers included) on its original subject;
+//! point the gateway's dashboards or a routing experiment at the same NATS
+//! server, with no simulator fleet running.
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use futures::StreamExt;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use telemetry_recorder::{subject_matches, Pacer, Record, RecordingReader, SegmentWriter};
+use tracing::{info, Level};
+use tracing_subscriber::FmtSubscriber;
+
+#[derive(Parser, Debug)]
+#[command(name = "orbital-recorder", about = "Record and replay NATS telemetry sessions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Cmd,
+
+    /// Verbose output
+    #[arg(short, long, global = true)]
+    verbose: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    /// Subscribe and write records until interrupted
+    Record {
+        #[arg(long, env = "NATS_URL", default_value = "nats://localhost:4222")]
+        nats: String,
+
+        /// Recording directory; an existing recording is appended to
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// Subjects to subscribe to (repeatable)
+        #[arg(short, long = "subject", default_value = "orbital.>")]
+        subjects: Vec<String>,
+
+        /// Start a new segment after this many MiB
+        #[arg(long, default_value_t = 64)]
+        segment_mb: u64,
+
+        /// Start a new segment after this many minutes
+        #[arg(long, default_value_t = 10)]
+        segment_mins: i64,
+    },
+
+    /// Republish a recording
+    Play {
+        #[arg(long, env = "NATS_URL", default_value = "nats://localhost:4222")]
+        nats: String,
+
+        #[arg(short, long)]
+        from: PathBuf,
+
+        /// Playback speed; 1 is as recorded, 0 as fast as possible
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+
+        /// Only republish subjects matching these patterns (repeatable)
+        #[arg(short, long = "subject")]
+        subjects: Vec<String>,
+
+        /// Start over at the end
+        #[arg(long = "loop")]
+        repeat: bool,
+    },
+
+    /// Summarise a recording: span and messages per subject
+    Info {
+        #[arg(short, long)]
+        from: PathBuf,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let level = if cli.verbose { Level::DEBUG } else { Level::INFO };
+    let subscriber = FmtSubscriber::builder().with_max_level(level).finish();
+    tracing::subscriber::set_global_default(subscriber)?;
+
+    match cli.command {
+        Cmd::Record {
+            nats,
+            out,
+            subjects,
+            segment_mb,
+            segment_mins,
+        } => record(&nats, out, &subjects, segment_mb, segment_mins).await,
+        Cmd::Play {
+            nats,
+            from,
+            speed,
+            subjects,
+            repeat,
+        } => play(&nats, from, speed, &subjects, repeat).await,
+        Cmd::Info { from } => summarise(from),
+    }
+}
+
+async fn record(nats: &str, out: PathBuf, subjects: &[String], segment_mb: u64, segment_mins: i64) -> Result<()> {
+    let client = async_nats::connect(nats).await.with_context(|| format!("connecting to {}", nats))?;
+    let mut subscriptions = Vec::new();
+    for subject in subjects {
+        subscriptions.push(client.subscribe(subject.clone()).await?);
+    }
+    let mut messages = futures::stream::select_all(subscriptions);
+    let mut writer = SegmentWriter::create(&out, segment_mb * 1024 * 1024, chrono::Duration::minutes(segment_mins))?;
+    info!("Recording {:?} from {} into {}", subjects, nats, out.display());
+
+    let interrupt = tokio::signal::ctrl_c();
+    tokio::pin!(interrupt);
+    let mut count = 0u64;
+    loop {
+        tokio::select! {
+            message = messages.next() => {
+                let Some(message) = message else { break };
+                let headers = message
+                    .headers
+                    .iter()
+                    .flat_map(|h| h.iter())
+                    .flat_map(|(name, values)| values.iter().map(move |v| (name.to_string(), v.as_str().to_string())))
+                    .collect();
+                writer.write(&Record {
+                    received_at: chrono::Utc::now(),
+                    subject: message.subject.to_string(),
+                    headers,
+                    payload: message.payload.to_vec(),
+                })?;
+                count += 1;
+                if count % 10_000 == 0 {
+                    info!("{} messages recorded", count);
+                }
+            }
+            _ = &mut interrupt => break,
+        }
+    }
+    writer.flush()?;
+    info!("{} messages in {} segments", count, writer.segment_count());
+    Ok(())
+}
+
+async fn play(nats: &str, from: PathBuf, speed: f64, subjects: &[String], repeat: bool) -> Result<()> {
+    let client = async_nats::connect(nats).await.with_context(|| format!("connecting to {}", nats))?;
+    let mut pacer = Pacer::new(speed);
+    loop {
+        let mut reader = RecordingReader::open(&from)?;
+        let mut count = 0u64;
+        while let Some(record) = reader.next_record()? {
+            if !subjects.is_empty() && !subjects.iter().any(|p| subject_matches(p, &record.subject)) {
+                continue;
+            }
+            pacer.wait(record.received_at).await;
+            let mut headers = async_nats::HeaderMap::new();
+            for (name, value) in &record.headers {
+                headers.append(name.as_str(), value.as_str());
+            }
+            client
+                .publish_with_headers(record.subject, headers, record.payload.into())
+                .await?;
+            count += 1;
+        }
+        client.flush().await?;
+        info!("Played {} messages from {}", count, from.display());
+        if !repeat {
+            return Ok(());
+        }
+        pacer.reset();
+    }
+}
+
+fn summarise(from: PathBuf) -> Result<()> {
+    let mut reader = RecordingReader::open(&from)?;
+    let mut per_subject: BTreeMap<String, u64> = BTreeMap::new();
+    let mut span = None;
+    while let Some(record) = reader.next_record()? {
+        let first = span.map_or(record.received_at, |(first, _)| first);
+        span = Some((first, record.received_at));
+        *per_subject.entry(record.subject).or_default() += 1;
+    }
+    let Some((first, last)) = span else {
+        println!("{}: empty", from.display());
+        return Ok(());
+    };
+    println!(
+        "{}: {} messages, {} to {} ({}s)",
+        from.display(),
+        per_subject.values().sum::<u64>(),
+        first.to_rfc3339(),
+        last.to_rfc3339(),
+        (last - first).num_seconds()
+    );
+    for (subject, count) in per_subject {
+        println!("{:>8}  {}", count, subject);
+    }
+    Ok(())
+}
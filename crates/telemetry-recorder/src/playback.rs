@@ -0,0 +1,103 @@
+//! Playback pacing and subject filters
+//!
+//! A `Pacer` maps each record's receive time onto the wall clock from the
+//! start of playback, scaled by the speed factor: 1.0 replays the session
+//! as captured, 10.0 ten times faster, and 0 (or any non-positive speed)
+//! as fast as NATS will take it.
+
+use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant};
+
+pub struct Pacer {
+    speed: f64,
+    origin: Option<(DateTime<Utc>, Instant)>,
+}
+
+impl Pacer {
+    pub fn new(speed: f64) -> Self {
+        Self { speed, origin: None }
+    }
+
+    /// Restart timing from the next record (e.g. when looping)
+    pub fn reset(&mut self) {
+        self.origin = None;
+    }
+
+    /// Offset from the start of playback at which a record received at
+    /// `received_at` is due; the first record sets the origin
+    pub fn offset(&mut self, received_at: DateTime<Utc>) -> Duration {
+        if self.speed <= 0.0 {
+            return Duration::ZERO;
+        }
+        let (first, _) = *self.origin.get_or_insert((received_at, Instant::now()));
+        let recorded = (received_at - first).to_std().unwrap_or_default();
+        recorded.div_f64(self.speed)
+    }
+
+    /// Wait until the record is due
+    pub async fn wait(&mut self, received_at: DateTime<Utc>) {
+        let offset = self.offset(received_at);
+        if let Some((_, started)) = self.origin {
+            tokio::time::sleep_until((started + offset).into()).await;
+        }
+    }
+}
+
+/// NATS subject matching: `*` matches one token, a trailing `>` one or
+/// more
+pub fn subject_matches(pattern: &str, subject: &str) -> bool {
+    let mut tokens = subject.split('.');
+    for want in pattern.split('.') {
+        match (want, tokens.next()) {
+            (">", Some(_)) => return true,
+            ("*", Some(_)) => {}
+            (want, Some(token)) if want == token => {}
+            _ => return false,
+        }
+    }
+    tokens.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(1_700_000_000_000 + ms).unwrap()
+    }
+
+    #[test]
+    fn test_offsets_scale_with_speed() {
+        let mut original = Pacer::new(1.0);
+        assert_eq!(original.offset(at(5_000)), Duration::ZERO);
+        assert_eq!(original.offset(at(7_500)), Duration::from_millis(2_500));
+
+        let mut fast = Pacer::new(10.0);
+        fast.offset(at(0));
+        assert_eq!(fast.offset(at(60_000)), Duration::from_secs(6));
+        fast.reset();
+        assert_eq!(fast.offset(at(60_000)), Duration::ZERO);
+
+        let mut flat_out = Pacer::new(0.0);
+        flat_out.offset(at(0));
+        assert_eq!(flat_out.offset(at(3_600_000)), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_out_of_order_records_are_due_immediately() {
+        let mut pacer = Pacer::new(1.0);
+        pacer.offset(at(10_000));
+        assert_eq!(pacer.offset(at(9_000)), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_subject_matches() {
+        assert!(subject_matches("orbital.>", "orbital.sat.HALO-01.position"));
+        assert!(subject_matches("orbital.sat.*.position", "orbital.sat.HALO-01.position"));
+        assert!(!subject_matches("orbital.sat.*.position", "orbital.sat.HALO-01.velocity"));
+        assert!(!subject_matches("orbital.sat.*", "orbital.sat.HALO-01.position"));
+        assert!(!subject_matches("orbital.>", "orbital"));
+        assert!(subject_matches("orbital.keys.GS-001", "orbital.keys.GS-001"));
+        assert!(!subject_matches("orbital.keys.GS-001", "orbital.keys"));
+    }
+}
@@ -0,0 +1,336 @@
+//! Segment files
+//!
+//! A recording is a directory of `segment-NNNNNN.rec` files, each starting
+//! with an 8-byte magic followed by records (little-endian):
+//!
+//! ```text
+//! i64  received_at, Unix microseconds
+//! u16  subject length, subject (UTF-8)
+//! u16  header count, then per header: u16 name length, name, u16 value length, value
+//! u32  payload length, payload
+//! ```
+//!
+//! A recorder killed mid-write leaves a truncated last record; readers
+//! stop at it instead of failing the whole segment.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+pub const MAGIC: &[u8; 8] = b"SX9REC01";
+const EXTENSION: &str = "rec";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub received_at: DateTime<Utc>,
+    pub subject: String,
+    /// In arrival order; a name may repeat
+    pub headers: Vec<(String, String)>,
+    pub payload: Vec<u8>,
+}
+
+impl Record {
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<usize> {
+        let mut len = 8;
+        out.write_all(&self.received_at.timestamp_micros().to_le_bytes())?;
+        len += write_str(out, &self.subject)?;
+        out.write_all(&u16_len(self.headers.len())?.to_le_bytes())?;
+        len += 2;
+        for (name, value) in &self.headers {
+            len += write_str(out, name)?;
+            len += write_str(out, value)?;
+        }
+        let payload_len = u32::try_from(self.payload.len()).map_err(|_| invalid("payload over 4 GiB"))?;
+        out.write_all(&payload_len.to_le_bytes())?;
+        out.write_all(&self.payload)?;
+        Ok(len + 4 + self.payload.len())
+    }
+
+    /// Next record, `None` at a clean end of input
+    pub fn read_from(input: &mut impl Read) -> io::Result<Option<Self>> {
+        let mut micros = [0u8; 8];
+        match input.read_exact(&mut micros) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let received_at = DateTime::from_timestamp_micros(i64::from_le_bytes(micros))
+            .ok_or_else(|| invalid("timestamp out of range"))?;
+        let subject = read_str(input)?;
+        let count = read_u16(input)?;
+        let mut headers = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            headers.push((read_str(input)?, read_str(input)?));
+        }
+        let mut len = [0u8; 4];
+        input.read_exact(&mut len)?;
+        let mut payload = vec![0u8; u32::from_le_bytes(len) as usize];
+        input.read_exact(&mut payload)?;
+        Ok(Some(Self {
+            received_at,
+            subject,
+            headers,
+            payload,
+        }))
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+fn u16_len(len: usize) -> io::Result<u16> {
+    u16::try_from(len).map_err(|_| invalid("field over 65535 bytes"))
+}
+
+fn write_str(out: &mut impl Write, s: &str) -> io::Result<usize> {
+    out.write_all(&u16_len(s.len())?.to_le_bytes())?;
+    out.write_all(s.as_bytes())?;
+    Ok(2 + s.len())
+}
+
+fn read_u16(input: &mut impl Read) -> io::Result<u16> {
+    let mut buf = [0u8; 2];
+    input.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_str(input: &mut impl Read) -> io::Result<String> {
+    let mut buf = vec![0u8; read_u16(input)? as usize];
+    input.read_exact(&mut buf)?;
+    String::from_utf8(buf).map_err(|_| invalid("field is not UTF-8"))
+}
+
+pub fn segment_name(index: u32) -> String {
+    format!("segment-{:06}.{}", index, EXTENSION)
+}
+
+/// Segment files in `dir`, in recording order
+pub fn segments(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|p| {
+        p.extension().is_some_and(|e| e == EXTENSION)
+            && p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("segment-"))
+    });
+    paths.sort();
+    Ok(paths)
+}
+
+/// Appends records to a recording, starting a new segment when the
+/// current one reaches `max_bytes` or spans `max_age` of receive time.
+/// Never overwrites: recording into an existing directory continues
+/// after its last segment.
+pub struct SegmentWriter {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_age: Duration,
+    next_index: u32,
+    current: Option<OpenSegment>,
+}
+
+struct OpenSegment {
+    file: BufWriter<File>,
+    bytes: u64,
+    started_at: DateTime<Utc>,
+}
+
+impl SegmentWriter {
+    pub fn create(dir: impl Into<PathBuf>, max_bytes: u64, max_age: Duration) -> io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        let next_index = segments(&dir)?.len() as u32 + 1;
+        Ok(Self {
+            dir,
+            max_bytes,
+            max_age,
+            next_index,
+            current: None,
+        })
+    }
+
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        let rotate = self.current.as_ref().is_some_and(|s| {
+            s.bytes >= self.max_bytes || record.received_at - s.started_at >= self.max_age
+        });
+        if rotate {
+            self.flush()?;
+            self.current = None;
+        }
+        if self.current.is_none() {
+            self.current = Some(self.open(record.received_at)?);
+        }
+        let segment = self.current.as_mut().expect("segment just opened");
+        segment.bytes += record.write_to(&mut segment.file)? as u64;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match &mut self.current {
+            Some(segment) => segment.file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Segments started so far, including ones from an earlier session
+    pub fn segment_count(&self) -> u32 {
+        self.next_index - 1
+    }
+
+    fn open(&mut self, started_at: DateTime<Utc>) -> io::Result<OpenSegment> {
+        let path = self.dir.join(segment_name(self.next_index));
+        let mut file = BufWriter::new(File::options().write(true).create_new(true).open(&path)?);
+        file.write_all(MAGIC)?;
+        self.next_index += 1;
+        tracing::debug!("Recording to {}", path.display());
+        Ok(OpenSegment {
+            file,
+            bytes: MAGIC.len() as u64,
+            started_at,
+        })
+    }
+}
+
+/// Every record of a recording, segment by segment
+pub struct RecordingReader {
+    pending: VecDeque<PathBuf>,
+    current: Option<(PathBuf, BufReader<File>)>,
+}
+
+impl RecordingReader {
+    pub fn open(dir: &Path) -> io::Result<Self> {
+        let pending: VecDeque<PathBuf> = segments(dir)?.into();
+        if pending.is_empty() {
+            return Err(io::Error::new(
+                ErrorKind::NotFound,
+                format!("no segments in {}", dir.display()),
+            ));
+        }
+        Ok(Self {
+            pending,
+            current: None,
+        })
+    }
+
+    pub fn next_record(&mut self) -> io::Result<Option<Record>> {
+        loop {
+            if self.current.is_none() {
+                let Some(path) = self.pending.pop_front() else {
+                    return Ok(None);
+                };
+                let mut reader = BufReader::new(File::open(&path)?);
+                let mut magic = [0u8; 8];
+                reader.read_exact(&mut magic)?;
+                if &magic != MAGIC {
+                    return Err(invalid(&format!("{} is not a recording segment", path.display())));
+                }
+                self.current = Some((path, reader));
+            }
+            let (path, reader) = self.current.as_mut().expect("segment just opened");
+            match Record::read_from(reader) {
+                Ok(Some(record)) => return Ok(Some(record)),
+                Ok(None) => {}
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+                    tracing::warn!("{}: truncated last record skipped", path.display());
+                }
+                Err(e) => return Err(e),
+            }
+            self.current = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("recorder-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn record(ms: i64, subject: &str, payload: &[u8]) -> Record {
+        Record {
+            received_at: DateTime::from_timestamp_millis(1_700_000_000_000 + ms).unwrap(),
+            subject: subject.to_string(),
+            headers: vec![("Content-Type".to_string(), "application/x-protobuf".to_string())],
+            payload: payload.to_vec(),
+        }
+    }
+
+    fn read_all(dir: &Path) -> Vec<Record> {
+        let mut reader = RecordingReader::open(dir).unwrap();
+        std::iter::from_fn(|| reader.next_record().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_records_round_trip_across_rotated_segments() {
+        let dir = scratch("rotate");
+        let mut writer = SegmentWriter::create(&dir, 1 << 20, Duration::seconds(10)).unwrap();
+        let records: Vec<Record> = (0..30)
+            .map(|i| record(i * 1000, &format!("orbital.gs.GS-{:03}.telemetry", i), &[i as u8; 5]))
+            .collect();
+        for r in &records {
+            writer.write(r).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(writer.segment_count(), 3);
+        assert_eq!(segments(&dir).unwrap().len(), 3);
+        assert_eq!(read_all(&dir), records);
+
+        // A second session appends new segments after the existing ones
+        let mut writer = SegmentWriter::create(&dir, 16, Duration::hours(1)).unwrap();
+        writer.write(&record(40_000, "orbital.sat.HALO-01.position", b"late")).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.segment_count(), 4);
+        assert_eq!(read_all(&dir).last().unwrap().payload, b"late");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_size_rotation() {
+        let dir = scratch("size");
+        let mut writer = SegmentWriter::create(&dir, 64, Duration::hours(1)).unwrap();
+        for i in 0..4 {
+            writer.write(&record(i, "orbital.sat.X.position", &[0u8; 100])).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(writer.segment_count(), 4);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_truncated_tail_is_skipped() {
+        let dir = scratch("truncated");
+        let mut writer = SegmentWriter::create(&dir, 1 << 20, Duration::hours(1)).unwrap();
+        writer.write(&record(0, "orbital.keys.GS-001", b"first")).unwrap();
+        writer.write(&record(1, "orbital.keys.GS-001", b"second")).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        let path = &segments(&dir).unwrap()[0];
+        let len = std::fs::metadata(path).unwrap().len();
+        File::options().write(true).open(path).unwrap().set_len(len - 3).unwrap();
+        let records = read_all(&dir);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].payload, b"first");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_foreign_files_are_rejected_or_ignored() {
+        let dir = scratch("foreign");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a segment").unwrap();
+        assert!(RecordingReader::open(&dir).is_err());
+
+        std::fs::write(dir.join(segment_name(1)), b"NOTMAGIC").unwrap();
+        let mut reader = RecordingReader::open(&dir).unwrap();
+        assert_eq!(reader.next_record().unwrap_err().kind(), ErrorKind::InvalidData);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,5 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tonic_build::compile_protos("proto/orbital.proto")?;
+    tonic_build::compile_protos("proto/telemetry.proto")?;
+    Ok(())
+}
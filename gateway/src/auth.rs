@@ -0,0 +1,262 @@
+//! Authentication and role-based access control
+//!
+//! Callers authenticate with either:
+//! - `X-API-Key: <key>` (or `Authorization: Bearer <key>`) for keys configured
+//!   in `ORBITAL_API_KEYS`
+//! - `Authorization: Bearer <jwt>` signed HS256 with `ORBITAL_JWT_SECRET`
+//!
+//! Roles are ordered: Viewer < Operator < Admin. Mutating endpoints are
+//! wrapped in a `RoleGuard`; read endpoints stay public when
+//! `ORBITAL_PUBLIC_READS` is true (the default).
+//!
+//! If neither keys nor a JWT secret are configured the gateway runs in
+//! open dev mode and every caller is treated as Admin.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Access role, ordered by privilege
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Read-only dashboards
+    Viewer,
+    /// Mission operators (downselect, simulation controls)
+    Operator,
+    /// Constellation administration (TLE upload, restore)
+    Admin,
+}
+
+impl Role {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "viewer" | "read" | "readonly" => Some(Role::Viewer),
+            "operator" | "ops" => Some(Role::Operator),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// Authenticated caller, inserted into request extensions
+#[derive(Debug, Clone, Serialize)]
+pub struct Principal {
+    pub subject: String,
+    pub role: Role,
+}
+
+impl Principal {
+    pub(crate) fn dev_admin() -> Self {
+        Self {
+            subject: "dev".to_string(),
+            role: Role::Admin,
+        }
+    }
+}
+
+/// JWT claims accepted by the gateway
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)] // `exp` is checked by jsonwebtoken's Validation, not read directly
+pub struct Claims {
+    pub sub: String,
+    pub role: Role,
+    pub exp: usize,
+}
+
+/// Authentication configuration
+pub struct AuthConfig {
+    api_keys: HashMap<String, Principal>,
+    jwt_secret: Option<String>,
+    pub public_reads: bool,
+}
+
+impl AuthConfig {
+    /// Load from environment:
+    /// - `ORBITAL_API_KEYS`: comma-separated `key:role[:name]` entries
+    /// - `ORBITAL_JWT_SECRET`: HS256 secret for bearer tokens
+    /// - `ORBITAL_PUBLIC_READS`: allow unauthenticated reads (default true)
+    pub fn from_env() -> Self {
+        let api_keys = std::env::var("ORBITAL_API_KEYS")
+            .map(|s| parse_api_keys(&s))
+            .unwrap_or_default();
+        let jwt_secret = std::env::var("ORBITAL_JWT_SECRET")
+            .ok()
+            .filter(|s| !s.is_empty());
+        let public_reads = std::env::var("ORBITAL_PUBLIC_READS")
+            .map(|v| !matches!(v.to_lowercase().as_str(), "0" | "false" | "no"))
+            .unwrap_or(true);
+
+        Self {
+            api_keys,
+            jwt_secret,
+            public_reads,
+        }
+    }
+
+    /// Auth is enforced only when credentials are configured
+    pub fn enabled(&self) -> bool {
+        !self.api_keys.is_empty() || self.jwt_secret.is_some()
+    }
+
+    pub fn key_count(&self) -> usize {
+        self.api_keys.len()
+    }
+
+    /// Resolve request headers to a principal.
+    ///
+    /// Returns `Ok(None)` when no credentials were presented.
+    pub(crate) fn resolve(&self, headers: &HeaderMap) -> Result<Option<Principal>, (StatusCode, String)> {
+        if let Some(key) = headers.get("x-api-key").and_then(|v| v.to_str().ok()) {
+            return self
+                .api_keys
+                .get(key)
+                .cloned()
+                .map(Some)
+                .ok_or((StatusCode::UNAUTHORIZED, "Invalid API key".to_string()));
+        }
+
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim);
+
+        let Some(token) = bearer else {
+            return Ok(None);
+        };
+
+        if let Some(principal) = self.api_keys.get(token) {
+            return Ok(Some(principal.clone()));
+        }
+
+        let secret = self
+            .jwt_secret
+            .as_ref()
+            .ok_or((StatusCode::UNAUTHORIZED, "Invalid bearer token".to_string()))?;
+
+        let data = decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        )
+        .map_err(|e| (StatusCode::UNAUTHORIZED, format!("Invalid token: {}", e)))?;
+
+        Ok(Some(Principal {
+            subject: data.claims.sub,
+            role: data.claims.role,
+        }))
+    }
+}
+
+/// Parse `key:role[:name]` entries, skipping malformed ones
+fn parse_api_keys(spec: &str) -> HashMap<String, Principal> {
+    spec.split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.trim().splitn(3, ':');
+            let key = parts.next()?.trim();
+            let role = Role::parse(parts.next()?)?;
+            if key.is_empty() {
+                return None;
+            }
+            let subject = parts
+                .next()
+                .map(|s| s.trim().to_string())
+                .unwrap_or_else(|| format!("key-{}", key.chars().take(4).collect::<String>()));
+            Some((
+                key.to_string(),
+                Principal { subject, role },
+            ))
+        })
+        .collect()
+}
+
+/// Minimum role required by a group of routes
+#[derive(Clone)]
+pub struct RoleGuard {
+    pub config: Arc<AuthConfig>,
+    pub min_role: Role,
+}
+
+impl RoleGuard {
+    pub fn new(config: Arc<AuthConfig>, min_role: Role) -> Self {
+        Self { config, min_role }
+    }
+}
+
+/// Outer middleware: resolve credentials and attach a `Principal`.
+///
+/// Unauthenticated requests pass through without one; route guards decide
+/// whether that is acceptable.
+pub async fn authenticate(
+    State(config): State<Arc<AuthConfig>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    let principal = if config.enabled() {
+        config.resolve(req.headers())?
+    } else {
+        Some(Principal::dev_admin())
+    };
+
+    if let Some(principal) = principal {
+        req.extensions_mut().insert(principal);
+    }
+    Ok(next.run(req).await)
+}
+
+/// Route-level middleware: reject callers below the guard's role
+pub async fn require_role(
+    State(guard): State<RoleGuard>,
+    req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    if !guard.config.enabled() {
+        return Ok(next.run(req).await);
+    }
+
+    if guard.min_role == Role::Viewer && guard.config.public_reads {
+        return Ok(next.run(req).await);
+    }
+
+    let principal = req
+        .extensions()
+        .get::<Principal>()
+        .ok_or((StatusCode::UNAUTHORIZED, "Authentication required".to_string()))?;
+
+    if principal.role < guard.min_role {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!("Requires {:?} role", guard.min_role),
+        ));
+    }
+
+    Ok(next.run(req).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_api_keys() {
+        let keys = parse_api_keys("abc123:admin:ops-lead, view1:viewer,bad:nobody,:operator");
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys["abc123"].role, Role::Admin);
+        assert_eq!(keys["abc123"].subject, "ops-lead");
+        assert_eq!(keys["view1"].role, Role::Viewer);
+    }
+
+    #[test]
+    fn test_role_ordering() {
+        assert!(Role::Viewer < Role::Operator);
+        assert!(Role::Operator < Role::Admin);
+    }
+}
@@ -0,0 +1,374 @@
+//! Satellite bus model
+//!
+//! Power and thermal state for every satellite, advanced with the sim
+//! clock on each propagation tick. Solar generation follows the eclipse
+//! model (`orbital_mechanics::eclipse`); the battery takes the difference
+//! between generation and load. Load is housekeeping plus the optical
+//! terminal while a ground link is active plus crosslinks for operational
+//! satellites; payload and crosslinks are shed while the battery is below
+//! `load_shed_pct`. Temperature is a single lumped node: absorbed sunlight
+//! and dissipated load in, radiator out. Offline satellites draw
+//! housekeeping only.
+//!
+//! A sim-clock jump is integrated as at most an hour, with illumination
+//! held at the new tick's value. States are published on
+//! `orbital.bus.{sat}` and served at `GET /bus`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use orbital_mechanics::eclipse;
+use orbital_mechanics::SatelliteStatus;
+
+use crate::config::BusSection;
+use crate::history::{LinkRecord, PositionRecord};
+use crate::AppState;
+
+const STEFAN_BOLTZMANN: f64 = 5.670_374e-8;
+const KELVIN: f64 = 273.15;
+const INITIAL_TEMPERATURE_C: f64 = 20.0;
+
+/// Longest stretch of sim time integrated in one tick
+const MAX_GAP_SECS: i64 = 3600;
+/// Integration step within a tick
+const SUBSTEP_SECS: f64 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BusHealth {
+    Nominal,
+    /// Payload and crosslinks shed to protect the battery
+    LoadShed,
+    /// Battery empty; load exceeds generation
+    Depleted,
+    Hot,
+    Cold,
+}
+
+impl BusHealth {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BusHealth::Nominal => "nominal",
+            BusHealth::LoadShed => "load_shed",
+            BusHealth::Depleted => "depleted",
+            BusHealth::Hot => "hot",
+            BusHealth::Cold => "cold",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        [
+            BusHealth::Nominal,
+            BusHealth::LoadShed,
+            BusHealth::Depleted,
+            BusHealth::Hot,
+            BusHealth::Cold,
+        ]
+        .into_iter()
+        .find(|h| h.as_str() == s)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusState {
+    pub satellite_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub illumination: f64,
+    pub solar_w: f64,
+    pub load_w: f64,
+    pub battery_wh: f64,
+    pub state_of_charge_pct: f64,
+    pub temperature_c: f64,
+    pub payload_on: bool,
+    pub isl_on: bool,
+    pub health: BusHealth,
+}
+
+/// What the bus needs to know about a satellite at a tick
+pub struct BusInput<'a> {
+    pub satellite_id: &'a str,
+    pub status: SatelliteStatus,
+    pub position_eci_km: [f64; 3],
+    pub active_links: usize,
+}
+
+#[derive(Default)]
+pub struct BusModel {
+    states: Mutex<HashMap<String, BusState>>,
+}
+
+impl BusModel {
+    /// Advance every satellite in `inputs` to `now`; satellites seen for
+    /// the first time start fully charged at room temperature
+    pub fn step(&self, inputs: &[BusInput], now: DateTime<Utc>, params: &BusSection) -> Vec<BusState> {
+        let sun = eclipse::sun_position_km(now);
+        let mut states = self.states.lock().unwrap();
+        inputs
+            .iter()
+            .map(|input| {
+                let illumination = eclipse::illumination(input.position_eci_km, sun);
+                let state = states
+                    .entry(input.satellite_id.to_string())
+                    .or_insert_with(|| initial(input.satellite_id, now, params));
+                // A clock moved backwards restarts integration from `now`
+                let elapsed =
+                    (now - state.timestamp).clamp(Duration::zero(), Duration::seconds(MAX_GAP_SECS));
+                advance(state, input, illumination, elapsed, params);
+                state.timestamp = now;
+                state.clone()
+            })
+            .collect()
+    }
+
+    pub fn get(&self, satellite_id: &str) -> Option<BusState> {
+        self.states.lock().unwrap().get(satellite_id).cloned()
+    }
+
+    pub fn all(&self) -> Vec<BusState> {
+        let mut all: Vec<BusState> = self.states.lock().unwrap().values().cloned().collect();
+        all.sort_by(|a, b| a.satellite_id.cmp(&b.satellite_id));
+        all
+    }
+}
+
+fn initial(satellite_id: &str, now: DateTime<Utc>, params: &BusSection) -> BusState {
+    BusState {
+        satellite_id: satellite_id.to_string(),
+        timestamp: now,
+        illumination: 1.0,
+        solar_w: 0.0,
+        load_w: 0.0,
+        battery_wh: params.battery_wh,
+        state_of_charge_pct: 100.0,
+        temperature_c: INITIAL_TEMPERATURE_C,
+        payload_on: false,
+        isl_on: false,
+        health: BusHealth::Nominal,
+    }
+}
+
+fn advance(state: &mut BusState, input: &BusInput, illumination: f64, elapsed: Duration, params: &BusSection) {
+    let solar_w = params.solar_array_w * illumination;
+    let total_secs = elapsed.num_milliseconds() as f64 / 1000.0;
+    // At least one (possibly zero-length) step, so switch state is current
+    let steps = (total_secs / SUBSTEP_SECS).ceil().max(1.0) as usize;
+    let dt = total_secs / steps as f64;
+
+    for _ in 0..steps {
+        let (payload_on, isl_on, load_w) = loads(state, input, params);
+        let net_wh = (solar_w - load_w) * dt / 3600.0;
+        state.battery_wh = (state.battery_wh + net_wh).clamp(0.0, params.battery_wh);
+
+        let temperature_k = state.temperature_c + KELVIN;
+        let heat_in = params.absorbed_solar_w * illumination + load_w;
+        let heat_out = params.emissivity * STEFAN_BOLTZMANN * params.radiator_area_m2 * temperature_k.powi(4);
+        state.temperature_c += (heat_in - heat_out) * dt / params.heat_capacity_j_k;
+
+        state.payload_on = payload_on;
+        state.isl_on = isl_on;
+        state.load_w = load_w;
+    }
+
+    state.illumination = illumination;
+    state.solar_w = solar_w;
+    state.state_of_charge_pct = 100.0 * state.battery_wh / params.battery_wh;
+    state.health = if state.battery_wh <= 0.0 && solar_w < state.load_w {
+        BusHealth::Depleted
+    } else if state.temperature_c > params.max_temperature_c {
+        BusHealth::Hot
+    } else if state.temperature_c < params.min_temperature_c {
+        BusHealth::Cold
+    } else if state.state_of_charge_pct < params.load_shed_pct {
+        BusHealth::LoadShed
+    } else {
+        BusHealth::Nominal
+    };
+}
+
+/// Which loads run given the battery and the satellite's status
+fn loads(state: &BusState, input: &BusInput, params: &BusSection) -> (bool, bool, f64) {
+    let soc_pct = 100.0 * state.battery_wh / params.battery_wh;
+    let powered = input.status != SatelliteStatus::Offline && soc_pct >= params.load_shed_pct;
+    let payload_on = powered && input.active_links > 0;
+    let isl_on = powered && input.status == SatelliteStatus::Operational;
+    let load_w = params.bus_load_w
+        + if payload_on { params.payload_w } else { 0.0 }
+        + if isl_on { params.isl_w } else { 0.0 };
+    (payload_on, isl_on, load_w)
+}
+
+/// Step the bus model from a propagation tick's records and publish
+pub async fn tick(state: &AppState, now: DateTime<Utc>, positions: &[PositionRecord], links: &[LinkRecord]) {
+    let params = &state.config.bus;
+    if !params.enabled {
+        return;
+    }
+
+    let statuses: HashMap<String, SatelliteStatus> = state
+        .constellation
+        .load()
+        .satellites
+        .iter()
+        .map(|s| (s.id.clone(), s.status))
+        .collect();
+    let mut active_links: HashMap<&str, usize> = HashMap::new();
+    for link in links.iter().filter(|l| l.active) {
+        *active_links.entry(link.satellite_id.as_str()).or_default() += 1;
+    }
+    let inputs: Vec<BusInput> = positions
+        .iter()
+        .map(|p| BusInput {
+            satellite_id: &p.satellite_id,
+            status: statuses.get(&p.satellite_id).copied().unwrap_or(SatelliteStatus::Operational),
+            position_eci_km: p.position_eci_km,
+            active_links: active_links.get(p.satellite_id.as_str()).copied().unwrap_or(0),
+        })
+        .collect();
+
+    let states = state.bus.step(&inputs, now, params);
+    for bus in states.iter().filter(|b| b.health != BusHealth::Nominal) {
+        tracing::debug!(
+            "{} bus {} ({:.0}% SoC, {:.1} °C)",
+            bus.satellite_id,
+            bus.health.as_str(),
+            bus.state_of_charge_pct,
+            bus.temperature_c
+        );
+    }
+    if let Some(telemetry) = &state.telemetry {
+        for bus in &states {
+            if let Err(e) = telemetry.publish_bus(bus).await {
+                tracing::warn!("Bus telemetry publish failed: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+// ========== Routes ==========
+
+/// GET /bus
+pub async fn list_bus(State(state): State<AppState>) -> Json<Vec<BusState>> {
+    Json(state.bus.all())
+}
+
+/// GET /bus/:id
+pub async fn get_bus(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<BusState>, (StatusCode, String)> {
+    state
+        .bus
+        .get(&id)
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, format!("No bus state for satellite: {}", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const R_KM: f64 = 6378.137 + 10_500.0;
+
+    fn input(position_eci_km: [f64; 3], active_links: usize) -> BusInput<'static> {
+        BusInput {
+            satellite_id: "HALO-01",
+            status: SatelliteStatus::Operational,
+            position_eci_km,
+            active_links,
+        }
+    }
+
+    fn equinox() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 20, 14, 46, 0).unwrap()
+    }
+
+    /// Tick every 5 minutes for `hours` at a fixed position
+    fn run(model: &BusModel, position: [f64; 3], links: usize, hours: i64, params: &BusSection) -> BusState {
+        let mut last = None;
+        for minute in (0..=hours * 60).step_by(5) {
+            let now = equinox() + Duration::minutes(minute);
+            last = model.step(&[input(position, links)], now, params).pop();
+        }
+        last.unwrap()
+    }
+
+    #[test]
+    fn test_sunlit_satellite_stays_charged_and_warm() {
+        let params = BusSection::default();
+        // Sun is near +X at the March equinox
+        let bus = run(&BusModel::default(), [R_KM, 0.0, 0.0], 2, 6, &params);
+        assert!(bus.illumination > 0.999);
+        assert!(bus.payload_on && bus.isl_on);
+        assert_eq!(bus.load_w, params.bus_load_w + params.payload_w + params.isl_w);
+        assert_eq!(bus.state_of_charge_pct, 100.0);
+        assert!(bus.temperature_c > params.min_temperature_c && bus.temperature_c < params.max_temperature_c);
+        assert_eq!(bus.health, BusHealth::Nominal);
+    }
+
+    #[test]
+    fn test_long_eclipse_drains_then_sheds_load() {
+        let params = BusSection::default();
+        let model = BusModel::default();
+        let shadow = [-R_KM, 0.0, 0.0];
+
+        // One hour in shadow at full load costs 1.8 kWh of 3 kWh
+        let bus = run(&model, shadow, 1, 1, &params);
+        assert_eq!(bus.illumination, 0.0);
+        assert!((bus.state_of_charge_pct - 40.0).abs() < 1.0, "{}", bus.state_of_charge_pct);
+
+        // Below the threshold payload and crosslinks are shed
+        let later = model
+            .step(&[input(shadow, 1)], equinox() + Duration::minutes(90), &params)
+            .pop()
+            .unwrap();
+        assert!(!later.payload_on && !later.isl_on);
+        assert_eq!(later.load_w, params.bus_load_w);
+        assert_eq!(later.health, BusHealth::LoadShed);
+    }
+
+    #[test]
+    fn test_clock_jumps_are_bounded() {
+        let params = BusSection::default();
+        let model = BusModel::default();
+        let shadow = [-R_KM, 0.0, 0.0];
+        model.step(&[input(shadow, 0)], equinox(), &params);
+        // A day's jump integrates as one hour
+        let bus = model
+            .step(&[input(shadow, 0)], equinox() + Duration::days(1), &params)
+            .pop()
+            .unwrap();
+        assert!(bus.battery_wh > 0.0);
+        // Backwards keeps the state and restarts from the earlier time
+        let back = model.step(&[input(shadow, 0)], equinox(), &params).pop().unwrap();
+        assert_eq!(back.battery_wh, bus.battery_wh);
+        assert_eq!(back.timestamp, equinox());
+    }
+
+    #[test]
+    fn test_offline_draws_housekeeping_only() {
+        let params = BusSection::default();
+        let model = BusModel::default();
+        let mut offline = input([R_KM, 0.0, 0.0], 3);
+        offline.status = SatelliteStatus::Offline;
+        let bus = model.step(&[offline], equinox(), &params).pop().unwrap();
+        assert!(!bus.payload_on && !bus.isl_on);
+        assert_eq!(bus.load_w, params.bus_load_w);
+    }
+
+    #[test]
+    fn test_health_names_round_trip() {
+        for health in [BusHealth::Nominal, BusHealth::LoadShed, BusHealth::Depleted, BusHealth::Hot, BusHealth::Cold] {
+            assert_eq!(BusHealth::parse(health.as_str()), Some(health));
+            assert_eq!(serde_json::to_value(health).unwrap(), health.as_str());
+        }
+    }
+}
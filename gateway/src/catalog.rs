@@ -0,0 +1,389 @@
+//! Screening catalog with scheduled CelesTrak refresh
+//!
+//! A background task fetches each configured CelesTrak GP group as OMM
+//! JSON on a fixed interval, converts the records to validated TLEs and
+//! folds them into the screening catalog used for conjunction checks.
+//! Every fetch leaves a provenance record (URL, time, count, outcome).
+//! Uploaded real satellites (plane 0) whose NORAD ID appears in a fetched
+//! group get their elements refreshed in the live constellation.
+//!
+//! Each object keeps its last `HISTORY_LEN` distinct element sets, from
+//! which `GET /catalog/:norad_id/drag` estimates decay, ballistic
+//! coefficient and possible manoeuvres.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use collision_avoidance::{ObjectType, SpaceObject};
+use orbital_mechanics::drag::{self, DragEstimate};
+use orbital_mechanics::tle::{OmmRecord, TleRecord};
+
+use crate::config::GatewayConfig;
+use crate::{AppState, ConstellationState};
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Element sets kept per object for drag estimation
+const HISTORY_LEN: usize = 60;
+
+/// CelesTrak refresh settings
+#[derive(Debug, Clone, Serialize)]
+pub struct CelestrakConfig {
+    pub groups: Vec<String>,
+    pub base_url: String,
+    /// Zero disables the scheduled task (manual refresh still works)
+    pub interval: Duration,
+}
+
+impl CelestrakConfig {
+    pub fn from_config(config: &GatewayConfig) -> Self {
+        Self {
+            groups: config.celestrak.groups.clone(),
+            base_url: config.celestrak.url.clone(),
+            interval: config.celestrak_interval(),
+        }
+    }
+
+    fn group_url(&self, group: &str) -> String {
+        format!("{}?GROUP={}&FORMAT=json", self.base_url, group)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogObject {
+    #[serde(flatten)]
+    pub object: SpaceObject,
+    pub tle: TleRecord,
+    /// CelesTrak group the latest elements came from
+    pub group: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchStatus {
+    Ok,
+    Failed,
+}
+
+/// Where and when a group's elements came from
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchProvenance {
+    pub group: String,
+    pub url: String,
+    pub fetched_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub status: FetchStatus,
+    pub records: usize,
+    /// Records that failed TLE conversion/validation
+    pub rejected: usize,
+    pub error: Option<String>,
+}
+
+/// Objects screened against the constellation, keyed by NORAD ID
+#[derive(Default)]
+pub struct ScreeningCatalog {
+    objects: HashMap<u32, CatalogObject>,
+    /// Latest fetch per group
+    provenance: HashMap<String, FetchProvenance>,
+    /// Distinct element sets per object, oldest first
+    history: HashMap<u32, Vec<TleRecord>>,
+}
+
+impl ScreeningCatalog {
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item = &CatalogObject> {
+        self.objects.values()
+    }
+
+    pub fn get(&self, norad_id: u32) -> Option<&CatalogObject> {
+        self.objects.get(&norad_id)
+    }
+
+    /// Time of the most recent successful fetch of any group
+    pub fn last_success(&self) -> Option<DateTime<Utc>> {
+        self.provenance
+            .values()
+            .filter(|p| p.status == FetchStatus::Ok)
+            .map(|p| p.fetched_at)
+            .max()
+    }
+
+    fn ingest(&mut self, group: &str, records: Vec<TleRecord>) {
+        for tle in records {
+            let history = self.history.entry(tle.norad_id).or_default();
+            if history.last().is_none_or(|last| last.line1 != tle.line1) {
+                history.push(tle.clone());
+                if history.len() > HISTORY_LEN {
+                    history.remove(0);
+                }
+            }
+
+            let name = tle
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("NORAD-{}", tle.norad_id));
+            self.objects.insert(
+                tle.norad_id,
+                CatalogObject {
+                    object: SpaceObject {
+                        id: tle.norad_id.to_string(),
+                        norad_id: Some(tle.norad_id),
+                        object_type: object_type_from_name(&name),
+                        name,
+                        rcs_m2: None,
+                    },
+                    tle,
+                    group: group.to_string(),
+                },
+            );
+        }
+    }
+}
+
+/// GP data has no object type; CelesTrak names follow SATCAT conventions
+fn object_type_from_name(name: &str) -> ObjectType {
+    let upper = name.to_uppercase();
+    if upper.contains(" DEB") {
+        ObjectType::Debris
+    } else if upper.contains(" R/B") {
+        ObjectType::RocketBody
+    } else if upper.starts_with("TBA") || upper.starts_with("OBJECT ") {
+        ObjectType::Unknown
+    } else {
+        ObjectType::Payload
+    }
+}
+
+async fn fetch_group(
+    client: &reqwest::Client,
+    config: &CelestrakConfig,
+    group: &str,
+) -> anyhow::Result<Vec<OmmRecord>> {
+    let records = client
+        .get(config.group_url(group))
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Vec<OmmRecord>>()
+        .await?;
+    Ok(records)
+}
+
+/// Fetch every configured group, update the catalog and tracked
+/// satellites. Returns the provenance of this pass.
+pub async fn refresh(
+    state: &AppState,
+    client: &reqwest::Client,
+    config: &CelestrakConfig,
+) -> Vec<FetchProvenance> {
+    let mut results = Vec::with_capacity(config.groups.len());
+
+    for group in &config.groups {
+        let started = Instant::now();
+        let fetched_at = Utc::now();
+        let outcome = fetch_group(client, config, group).await;
+
+        let provenance = match outcome {
+            Ok(omm) => {
+                let total = omm.len();
+                let tles: Vec<TleRecord> = omm.iter().filter_map(|r| r.to_tle().ok()).collect();
+                let rejected = total - tles.len();
+                state.catalog.write().unwrap().ingest(group, tles);
+
+                FetchProvenance {
+                    group: group.clone(),
+                    url: config.group_url(group),
+                    fetched_at,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    status: FetchStatus::Ok,
+                    records: total,
+                    rejected,
+                    error: None,
+                }
+            }
+            Err(e) => {
+                tracing::warn!("CelesTrak group {} fetch failed: {}", group, e);
+                FetchProvenance {
+                    group: group.clone(),
+                    url: config.group_url(group),
+                    fetched_at,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    status: FetchStatus::Failed,
+                    records: 0,
+                    rejected: 0,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+
+        state
+            .catalog
+            .write()
+            .unwrap()
+            .provenance
+            .insert(group.clone(), provenance.clone());
+        results.push(provenance);
+    }
+
+    let refreshed = refresh_tracked_satellites(state);
+    tracing::info!(
+        "CelesTrak refresh: {} objects in catalog, {} tracked satellites updated",
+        state.catalog.read().unwrap().len(),
+        refreshed
+    );
+
+    results
+}
+
+/// Copy newer catalog elements onto uploaded real satellites (plane 0).
+/// Walker-generated satellites are never touched.
+fn refresh_tracked_satellites(state: &AppState) -> usize {
+    let catalog = state.catalog.read().unwrap();
+
+    state.constellation.update(|current| {
+        let mut refreshed = 0;
+        let satellites = current
+            .satellites
+            .iter()
+            .cloned()
+            .map(|mut sat| {
+                if sat.plane == 0 {
+                    if let Some(entry) = catalog.objects.get(&sat.norad_id) {
+                        if entry.tle.line1 != sat.tle_line1 || entry.tle.line2 != sat.tle_line2 {
+                            sat.tle_line1 = entry.tle.line1.clone();
+                            sat.tle_line2 = entry.tle.line2.clone();
+                            refreshed += 1;
+                        }
+                    }
+                }
+                sat
+            })
+            .collect();
+
+        let next = ConstellationState {
+            satellites,
+            ground_stations: current.ground_stations.clone(),
+        };
+        (next, refreshed)
+    })
+}
+
+/// Run scheduled refreshes forever (first pass immediately)
+pub async fn run(state: AppState, config: CelestrakConfig) {
+    if config.interval.is_zero() || config.groups.is_empty() {
+        tracing::info!("CelesTrak scheduled refresh disabled");
+        return;
+    }
+
+    let client = reqwest::Client::new();
+    let mut ticker = tokio::time::interval(config.interval);
+    loop {
+        ticker.tick().await;
+        refresh(&state, &client, &config).await;
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Serialize)]
+pub struct CatalogSummary {
+    pub objects: usize,
+    pub by_type: HashMap<String, usize>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub config: CelestrakConfig,
+    pub provenance: Vec<FetchProvenance>,
+}
+
+/// GET /catalog
+pub async fn get_catalog(State(state): State<AppState>) -> Json<CatalogSummary> {
+    let catalog = state.catalog.read().unwrap();
+
+    let mut by_type = HashMap::new();
+    for entry in catalog.objects() {
+        *by_type
+            .entry(format!("{:?}", entry.object.object_type).to_lowercase())
+            .or_insert(0) += 1;
+    }
+    let mut provenance: Vec<_> = catalog.provenance.values().cloned().collect();
+    provenance.sort_by(|a, b| a.group.cmp(&b.group));
+
+    Json(CatalogSummary {
+        objects: catalog.len(),
+        by_type,
+        last_success: catalog.last_success(),
+        config: state.celestrak.as_ref().clone(),
+        provenance,
+    })
+}
+
+/// POST /catalog/refresh - run a refresh pass now
+pub async fn refresh_catalog(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<FetchProvenance>>, (StatusCode, String)> {
+    if state.celestrak.groups.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "No CelesTrak groups configured".to_string()));
+    }
+    let client = reqwest::Client::new();
+    Ok(Json(refresh(&state, &client, &state.celestrak).await))
+}
+
+/// GET /catalog/:norad_id/drag
+pub async fn get_drag(
+    State(state): State<AppState>,
+    Path(norad_id): Path<u32>,
+) -> Result<Json<DragEstimate>, (StatusCode, String)> {
+    let catalog = state.catalog.read().unwrap();
+    let history = catalog
+        .history
+        .get(&norad_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("NORAD {} not in catalog", norad_id)))?;
+    drag::estimate(history)
+        .map(Json)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_type_from_name() {
+        assert_eq!(object_type_from_name("COSMOS 2251 DEB"), ObjectType::Debris);
+        assert_eq!(object_type_from_name("SL-14 R/B"), ObjectType::RocketBody);
+        assert_eq!(object_type_from_name("GPS BIIF-1 (PRN 25)"), ObjectType::Payload);
+    }
+
+    #[test]
+    fn test_ingest_keeps_distinct_history() {
+        let tle = TleRecord {
+            name: Some("ISS (ZARYA)".to_string()),
+            norad_id: 25544,
+            line1: "1 25544U 98067A   24001.50000000  .00016717  00000-0  10270-3 0  9005".to_string(),
+            line2: "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.50377579999993".to_string(),
+        };
+        let mut catalog = ScreeningCatalog::default();
+        catalog.ingest("stations", vec![tle.clone()]);
+        catalog.ingest("stations", vec![tle.clone()]);
+        assert_eq!(catalog.history[&25544].len(), 1);
+
+        for day in 2..=(HISTORY_LEN + 5) {
+            let mut next = tle.clone();
+            next.line1 = tle.line1.replace("24001", &format!("24{:03}", day));
+            catalog.ingest("stations", vec![next]);
+        }
+        assert_eq!(catalog.history[&25544].len(), HISTORY_LEN);
+        assert!(catalog.history[&25544][0].line1.contains("24006"));
+        assert_eq!(catalog.len(), 1);
+    }
+}
@@ -0,0 +1,465 @@
+//! Fault injection
+//!
+//! Fail or degrade a satellite, link or station for a while to exercise
+//! routing and scheduling resilience:
+//! `POST /chaos/link/{id}/fail?minutes=10`,
+//! `POST /chaos/station/{id}/degrade?minutes=30&margin_loss_db=6`.
+//!
+//! Faults are timed in sim time, so they follow the clock through pauses,
+//! acceleration and jumps. While active they are applied wherever state is
+//! derived: topology snapshots (and so the routing graph) mark affected
+//! links inactive or cut their margin, and the propagation loop records
+//! affected ground links as down and stations as offline/degraded in
+//! history and JetStream. Injections and clears are also published on
+//! `orbital.chaos.{fault}` and the network event stream (`events`).
+//!
+//! Link IDs are the topology IDs (`ISL-…`, `SG-…`) or `{satellite}|{station}`
+//! as used by link history. A link fault applies in both directions; a
+//! satellite or station fault applies to every link touching it.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use crate::auth::Principal;
+use crate::memory::MemoryKind;
+use crate::topology;
+use crate::AppState;
+
+pub const DEFAULT_DURATION_MINUTES: i64 = 10;
+/// One week of sim time
+pub const MAX_DURATION_MINUTES: i64 = 7 * 24 * 60;
+/// Extra loss on links touching a degraded element unless specified
+pub const DEFAULT_MARGIN_LOSS_DB: f64 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetKind {
+    Satellite,
+    Link,
+    Station,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "effect", rename_all = "snake_case")]
+pub enum FaultEffect {
+    /// Element is down; every affected link is inactive
+    Fail,
+    /// Affected links lose `margin_loss_db` of link margin
+    Degrade { margin_loss_db: f64 },
+    /// Affected links carry at least `utilization` (0-1) of offered load.
+    /// Injected by scenario traffic surges; tracked apart from the
+    /// fail/degrade effects.
+    Congest { utilization: f64 },
+}
+
+impl FaultEffect {
+    /// Failure dominates; degradations add up (`Congest` is never combined)
+    fn combine(self, other: FaultEffect) -> FaultEffect {
+        match (self, other) {
+            (FaultEffect::Degrade { margin_loss_db: a }, FaultEffect::Degrade { margin_loss_db: b }) => {
+                FaultEffect::Degrade {
+                    margin_loss_db: a + b,
+                }
+            }
+            _ => FaultEffect::Fail,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fault {
+    pub id: String,
+    pub kind: TargetKind,
+    pub target: String,
+    /// Link endpoints (link faults only)
+    pub endpoints: Option<(String, String)>,
+    #[serde(flatten)]
+    pub effect: FaultEffect,
+    pub injected_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub injected_by: Option<String>,
+}
+
+impl Fault {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.injected_at <= now && now < self.expires_at
+    }
+}
+
+/// Unordered endpoint pair
+fn link_key(a: &str, b: &str) -> (String, String) {
+    if a <= b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+fn merge<K: Eq + Hash>(map: &mut HashMap<K, FaultEffect>, key: K, effect: FaultEffect) {
+    map.entry(key)
+        .and_modify(|e| *e = e.combine(effect))
+        .or_insert(effect);
+}
+
+fn raise<K: Eq + Hash>(map: &mut HashMap<K, f64>, key: K, utilization: f64) {
+    let entry = map.entry(key).or_insert(0.0);
+    *entry = entry.max(utilization.clamp(0.0, 1.0));
+}
+
+/// Active faults at one instant, indexed for lookups while deriving state
+#[derive(Debug, Default)]
+pub struct ActiveFaults {
+    elements: HashMap<String, FaultEffect>,
+    links: HashMap<(String, String), FaultEffect>,
+    element_load: HashMap<String, f64>,
+    link_load: HashMap<(String, String), f64>,
+}
+
+impl ActiveFaults {
+    pub fn from_faults<'a>(faults: impl IntoIterator<Item = &'a Fault>) -> Self {
+        let mut active = Self::default();
+        for fault in faults {
+            match (&fault.endpoints, fault.effect) {
+                (Some((a, b)), FaultEffect::Congest { utilization }) => {
+                    raise(&mut active.link_load, link_key(a, b), utilization)
+                }
+                (None, FaultEffect::Congest { utilization }) => {
+                    raise(&mut active.element_load, fault.target.clone(), utilization)
+                }
+                (Some((a, b)), effect) => merge(&mut active.links, link_key(a, b), effect),
+                (None, effect) => merge(&mut active.elements, fault.target.clone(), effect),
+            }
+        }
+        active
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+            && self.links.is_empty()
+            && self.element_load.is_empty()
+            && self.link_load.is_empty()
+    }
+
+    /// Fault on a satellite or station
+    pub fn element(&self, id: &str) -> Option<FaultEffect> {
+        self.elements.get(id).copied()
+    }
+
+    /// Combined fault on the link between `a` and `b`, including faults
+    /// on either endpoint
+    pub fn link(&self, a: &str, b: &str) -> Option<FaultEffect> {
+        [
+            self.links.get(&link_key(a, b)).copied(),
+            self.element(a),
+            self.element(b),
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(FaultEffect::combine)
+    }
+
+    /// Highest injected utilization on the link between `a` and `b` or
+    /// either endpoint (0 when none)
+    pub fn utilization(&self, a: &str, b: &str) -> f64 {
+        [
+            self.link_load.get(&link_key(a, b)),
+            self.element_load.get(a),
+            self.element_load.get(b),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(0.0, |max, u| max.max(*u))
+    }
+}
+
+pub struct ChaosState {
+    next_id: AtomicU64,
+    faults: RwLock<Vec<Fault>>,
+}
+
+impl Default for ChaosState {
+    fn default() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            faults: RwLock::new(Vec::new()),
+        }
+    }
+}
+
+impl ChaosState {
+    /// Register `fault` under a fresh ID
+    pub fn inject(&self, mut fault: Fault) -> Fault {
+        fault.id = format!("fault-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.faults.write().unwrap().push(fault.clone());
+        fault
+    }
+
+    /// Faults in effect at `now`. Expired faults are dropped; faults
+    /// injected after `now` (the clock was moved back) are kept but inactive.
+    pub fn active(&self, now: DateTime<Utc>) -> Vec<Fault> {
+        let mut faults = self.faults.write().unwrap();
+        faults.retain(|f| now < f.expires_at);
+        faults.iter().filter(|f| f.is_active(now)).cloned().collect()
+    }
+
+    pub fn active_at(&self, now: DateTime<Utc>) -> ActiveFaults {
+        let faults = self.faults.read().unwrap();
+        ActiveFaults::from_faults(faults.iter().filter(|f| f.is_active(now)))
+    }
+
+    pub fn clear(&self, id: &str) -> Option<Fault> {
+        let mut faults = self.faults.write().unwrap();
+        let index = faults.iter().position(|f| f.id == id)?;
+        Some(faults.remove(index))
+    }
+
+    pub fn clear_all(&self) -> Vec<Fault> {
+        std::mem::take(&mut *self.faults.write().unwrap())
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Deserialize)]
+pub struct FailQuery {
+    pub minutes: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct DegradeQuery {
+    pub minutes: Option<i64>,
+    pub margin_loss_db: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct FaultEvent<'a> {
+    event: &'static str,
+    fault: &'a Fault,
+}
+
+fn duration(minutes: Option<i64>) -> Result<Duration, (StatusCode, String)> {
+    let minutes = minutes.unwrap_or(DEFAULT_DURATION_MINUTES);
+    if !(1..=MAX_DURATION_MINUTES).contains(&minutes) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("minutes must be between 1 and {}", MAX_DURATION_MINUTES),
+        ));
+    }
+    Ok(Duration::minutes(minutes))
+}
+
+/// Check the target exists; links resolve to their endpoints
+pub fn resolve(
+    state: &AppState,
+    kind: TargetKind,
+    id: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<(String, String)>, (StatusCode, String)> {
+    let constellation = state.constellation.load();
+    let not_found = |what: &str| (StatusCode::NOT_FOUND, format!("{} not found: {}", what, id));
+
+    match kind {
+        TargetKind::Satellite => constellation
+            .satellites
+            .iter()
+            .any(|s| s.id == id)
+            .then_some(None)
+            .ok_or_else(|| not_found("Satellite")),
+        TargetKind::Station => constellation
+            .station(id)
+            .map(|_| None)
+            .ok_or_else(|| not_found("Station")),
+        TargetKind::Link => {
+            if let Some((sat, station)) = id.split_once('|') {
+                let known = constellation.satellites.iter().any(|s| s.id == sat)
+                    && constellation.station(station).is_some();
+                return known
+                    .then(|| Some((sat.to_string(), station.to_string())))
+                    .ok_or_else(|| not_found("Link"));
+            }
+            let snapshot = topology::snapshot(state, now)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            snapshot
+                .links
+                .iter()
+                .find(|l| l.id == id)
+                .map(|l| Some((l.source.clone(), l.target.clone())))
+                .ok_or_else(|| not_found("Link"))
+        }
+    }
+}
+
+async fn inject(
+    state: AppState,
+    kind: TargetKind,
+    id: String,
+    effect: FaultEffect,
+    duration: Duration,
+    principal: Option<Extension<Principal>>,
+) -> Result<Json<Fault>, (StatusCode, String)> {
+    let now = state.clock.now();
+    let endpoints = resolve(&state, kind, &id, now)?;
+    let fault = state.chaos.inject(Fault {
+        id: String::new(),
+        kind,
+        target: id,
+        endpoints,
+        effect,
+        injected_at: now,
+        expires_at: now + duration,
+        injected_by: principal.map(|Extension(p)| p.subject),
+    });
+    tracing::warn!(
+        "Chaos: {} {:?} {} {:?} until {}",
+        fault.id,
+        fault.kind,
+        fault.target,
+        fault.effect,
+        fault.expires_at
+    );
+    publish(&state, "injected", &fault).await;
+    Ok(Json(fault))
+}
+
+/// Journal a fault event as an operational memory
+pub fn remember(state: &AppState, event: &'static str, fault: &Fault, at: DateTime<Utc>) {
+    let mut subjects = vec![fault.id.clone(), fault.target.clone()];
+    if let Some((a, b)) = &fault.endpoints {
+        subjects.extend([a.clone(), b.clone()]);
+    }
+    let summary = format!(
+        "Fault {} {}: {:?} {} {:?} until {}",
+        fault.id, event, fault.kind, fault.target, fault.effect, fault.expires_at
+    );
+    state
+        .memory
+        .remember(at, MemoryKind::Chaos, subjects, summary, &FaultEvent { event, fault });
+}
+
+async fn publish(state: &AppState, event: &'static str, fault: &Fault) {
+    let now = state.clock.now();
+    state.events.fault(event, fault, now);
+    remember(state, event, fault, now);
+    if let Some(telemetry) = &state.telemetry {
+        if let Err(e) = telemetry.publish_fault(&fault.id, &FaultEvent { event, fault }).await {
+            tracing::warn!("Fault event publish failed: {}", e);
+        }
+    }
+}
+
+/// POST /chaos/:kind/:id/fail
+pub async fn fail(
+    State(state): State<AppState>,
+    Path((kind, id)): Path<(TargetKind, String)>,
+    Query(q): Query<FailQuery>,
+    principal: Option<Extension<Principal>>,
+) -> Result<Json<Fault>, (StatusCode, String)> {
+    let duration = duration(q.minutes)?;
+    inject(state, kind, id, FaultEffect::Fail, duration, principal).await
+}
+
+/// POST /chaos/:kind/:id/degrade
+pub async fn degrade(
+    State(state): State<AppState>,
+    Path((kind, id)): Path<(TargetKind, String)>,
+    Query(q): Query<DegradeQuery>,
+    principal: Option<Extension<Principal>>,
+) -> Result<Json<Fault>, (StatusCode, String)> {
+    let duration = duration(q.minutes)?;
+    let margin_loss_db = q.margin_loss_db.unwrap_or(DEFAULT_MARGIN_LOSS_DB);
+    if !(margin_loss_db > 0.0 && margin_loss_db.is_finite()) {
+        return Err((StatusCode::BAD_REQUEST, "margin_loss_db must be positive".to_string()));
+    }
+    inject(state, kind, id, FaultEffect::Degrade { margin_loss_db }, duration, principal).await
+}
+
+/// GET /chaos - faults in effect now
+pub async fn list_faults(State(state): State<AppState>) -> Json<Vec<Fault>> {
+    Json(state.chaos.active(state.clock.now()))
+}
+
+/// DELETE /chaos/faults/:id
+pub async fn clear_fault(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Fault>, (StatusCode, String)> {
+    let fault = state
+        .chaos
+        .clear(&id)
+        .ok_or((StatusCode::NOT_FOUND, format!("Fault not found: {}", id)))?;
+    tracing::info!("Chaos: cleared {}", fault.id);
+    publish(&state, "cleared", &fault).await;
+    Ok(Json(fault))
+}
+
+/// DELETE /chaos - clear every fault
+pub async fn clear_all(State(state): State<AppState>) -> Json<Vec<Fault>> {
+    let cleared = state.chaos.clear_all();
+    tracing::info!("Chaos: cleared {} faults", cleared.len());
+    for fault in &cleared {
+        publish(&state, "cleared", fault).await;
+    }
+    Json(cleared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fault(kind: TargetKind, target: &str, effect: FaultEffect, at: DateTime<Utc>) -> Fault {
+        Fault {
+            id: String::new(),
+            kind,
+            target: target.to_string(),
+            endpoints: target
+                .split_once('|')
+                .map(|(a, b)| (a.to_string(), b.to_string())),
+            effect,
+            injected_at: at,
+            expires_at: at + Duration::minutes(10),
+            injected_by: None,
+        }
+    }
+
+    #[test]
+    fn test_faults_expire_in_sim_time() {
+        let chaos = ChaosState::default();
+        let t0 = Utc::now();
+        let injected = chaos.inject(fault(TargetKind::Station, "GS-1", FaultEffect::Fail, t0));
+        assert_eq!(injected.id, "fault-1");
+
+        assert_eq!(chaos.active(t0 + Duration::minutes(5)).len(), 1);
+        assert!(chaos.active(t0 - Duration::minutes(1)).is_empty());
+        assert!(chaos.active(t0 + Duration::minutes(10)).is_empty());
+        assert!(chaos.active_at(t0).is_empty());
+    }
+
+    #[test]
+    fn test_link_effects_combine() {
+        let chaos = ChaosState::default();
+        let t0 = Utc::now();
+        let degrade = FaultEffect::Degrade { margin_loss_db: 3.0 };
+        chaos.inject(fault(TargetKind::Link, "SAT-1|GS-1", degrade, t0));
+        chaos.inject(fault(TargetKind::Station, "GS-1", degrade, t0));
+        chaos.inject(fault(TargetKind::Satellite, "SAT-2", FaultEffect::Fail, t0));
+
+        let active = chaos.active_at(t0);
+        // Reverse direction, link + station degradations add up
+        assert_eq!(
+            active.link("GS-1", "SAT-1"),
+            Some(FaultEffect::Degrade { margin_loss_db: 6.0 })
+        );
+        assert_eq!(active.link("SAT-2", "GS-1"), Some(FaultEffect::Fail));
+        assert_eq!(active.link("SAT-3", "GS-2"), None);
+        assert_eq!(active.element("SAT-2"), Some(FaultEffect::Fail));
+    }
+}
@@ -0,0 +1,420 @@
+//! Simulation checkpoints
+//!
+//! A checkpoint captures everything needed to resume a scenario run:
+//! satellite elements and status, ground-station statuses and weather,
+//! the sim clock (time and mode), the topology graph at that instant, and
+//! pending maneuvers. Checkpoints are JSON files in `checkpoints.dir`.
+//!
+//! Restoring swaps the constellation snapshot in one step, re-anchors the
+//! clock at the saved sim time and mode, and forces a propagation tick so
+//! history and telemetry continue from the restored state. The graph is
+//! always derived from elements, so it is rebuilt rather than loaded; the
+//! saved copy is kept for inspection and compared after the restore.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use collision_avoidance::ManeuverPlan;
+use ground_stations::GroundStation;
+use orbital_mechanics::Satellite;
+
+use crate::auth::Principal;
+use crate::clock::ClockMode;
+use crate::propagation::{self, TickSummary};
+use crate::topology::{self, TopologySnapshot};
+use crate::{AppState, ConstellationState};
+
+/// Bumped on incompatible changes to `Checkpoint`
+pub const FORMAT_VERSION: u32 = 1;
+
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockCheckpoint {
+    pub sim_time: DateTime<Utc>,
+    #[serde(flatten)]
+    pub mode: ClockMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub format_version: u32,
+    pub name: String,
+    pub label: Option<String>,
+    pub saved_at: DateTime<Utc>,
+    pub saved_by: Option<String>,
+    /// Registered constellation names; restoring into a different set is refused
+    pub constellations: Vec<String>,
+    pub clock: ClockCheckpoint,
+    pub satellites: Vec<Satellite>,
+    pub ground_stations: Vec<GroundStation>,
+    pub topology: TopologySnapshot,
+    /// No maneuver queue exists yet, so this is always empty for now
+    #[serde(default)]
+    pub pending_maneuvers: Vec<ManeuverPlan>,
+}
+
+impl Checkpoint {
+    pub fn summary(&self, size_bytes: u64) -> CheckpointSummary {
+        CheckpointSummary {
+            name: self.name.clone(),
+            label: self.label.clone(),
+            saved_at: self.saved_at,
+            saved_by: self.saved_by.clone(),
+            sim_time: self.clock.sim_time,
+            satellites: self.satellites.len(),
+            ground_stations: self.ground_stations.len(),
+            links: self.topology.links.len(),
+            pending_maneuvers: self.pending_maneuvers.len(),
+            size_bytes,
+        }
+    }
+}
+
+/// Checkpoint names become file names: ASCII letters, digits, `-`, `_`
+/// and `.`, not starting with `.`
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+pub struct CheckpointStore {
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn open(dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", name))
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.path(name).exists()
+    }
+
+    /// Write via a temp file and rename, so a crash never leaves a torn checkpoint
+    pub fn save(&self, checkpoint: &Checkpoint) -> anyhow::Result<u64> {
+        let bytes = serde_json::to_vec_pretty(checkpoint)?;
+        let tmp = self.dir.join(format!(".{}.json.tmp", checkpoint.name));
+        std::fs::write(&tmp, &bytes)?;
+        std::fs::rename(&tmp, self.path(&checkpoint.name))?;
+        Ok(bytes.len() as u64)
+    }
+
+    pub fn load(&self, name: &str) -> anyhow::Result<Option<Checkpoint>> {
+        let bytes = match std::fs::read(self.path(name)) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let checkpoint: Checkpoint = serde_json::from_slice(&bytes)?;
+        if checkpoint.format_version != FORMAT_VERSION {
+            anyhow::bail!(
+                "Checkpoint {} has format version {}, expected {}",
+                name,
+                checkpoint.format_version,
+                FORMAT_VERSION
+            );
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /// Every readable checkpoint, newest first
+    pub fn list(&self) -> anyhow::Result<Vec<CheckpointSummary>> {
+        let mut summaries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".json"))
+                .filter(|n| valid_name(n))
+            else {
+                continue;
+            };
+            match self.load(name) {
+                Ok(Some(checkpoint)) => {
+                    let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                    summaries.push(checkpoint.summary(size));
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Skipping unreadable checkpoint {}: {}", path.display(), e),
+            }
+        }
+        summaries.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+        Ok(summaries)
+    }
+}
+
+fn constellation_names(state: &AppState) -> Vec<String> {
+    state.config.constellations().map(|c| c.name.clone()).collect()
+}
+
+/// Capture the current simulation state at a single sim instant
+pub fn capture(
+    state: &AppState,
+    name: String,
+    label: Option<String>,
+    saved_by: Option<String>,
+) -> anyhow::Result<Checkpoint> {
+    let status = state.clock.status();
+    let constellation = state.constellation.load();
+    let topology = topology::snapshot(state, status.sim_time)?;
+
+    Ok(Checkpoint {
+        format_version: FORMAT_VERSION,
+        name,
+        label,
+        saved_at: Utc::now(),
+        saved_by,
+        constellations: constellation_names(state),
+        clock: ClockCheckpoint {
+            sim_time: status.sim_time,
+            mode: status.mode,
+        },
+        satellites: constellation.satellites.clone(),
+        ground_stations: constellation.ground_stations.clone(),
+        topology,
+        pending_maneuvers: Vec::new(),
+    })
+}
+
+// ========== Routes ==========
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointSummary {
+    pub name: String,
+    pub label: Option<String>,
+    pub saved_at: DateTime<Utc>,
+    pub saved_by: Option<String>,
+    pub sim_time: DateTime<Utc>,
+    pub satellites: usize,
+    pub ground_stations: usize,
+    pub links: usize,
+    pub pending_maneuvers: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SaveRequest {
+    /// File name; defaults to `sim-<sim time>`
+    pub name: Option<String>,
+    pub label: Option<String>,
+    /// Replace an existing checkpoint of the same name
+    pub overwrite: bool,
+}
+
+#[derive(Serialize)]
+pub struct RestoreResponse {
+    pub restored: CheckpointSummary,
+    pub tick: TickSummary,
+    /// Links in the rebuilt graph that differ from the saved one (should be 0)
+    pub topology_mismatches: usize,
+}
+
+fn internal(e: anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+fn check_name(name: &str) -> Result<(), (StatusCode, String)> {
+    if valid_name(name) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid checkpoint name: {:?} (use letters, digits, '-', '_', '.')", name),
+        ))
+    }
+}
+
+/// POST /state/checkpoints - save the current simulation state
+pub async fn save_checkpoint(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    body: Option<Json<SaveRequest>>,
+) -> Result<Json<CheckpointSummary>, (StatusCode, String)> {
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+    let name = req
+        .name
+        .unwrap_or_else(|| format!("sim-{}", state.clock.now().format("%Y%m%dT%H%M%SZ")));
+    check_name(&name)?;
+    if !req.overwrite && state.checkpoints.exists(&name) {
+        return Err((StatusCode::CONFLICT, format!("Checkpoint already exists: {}", name)));
+    }
+
+    let checkpoint = capture(&state, name, req.label, principal.map(|Extension(p)| p.subject))
+        .map_err(internal)?;
+    let size = state.checkpoints.save(&checkpoint).map_err(internal)?;
+    tracing::info!(
+        "Saved checkpoint {} at sim time {}",
+        checkpoint.name,
+        checkpoint.clock.sim_time
+    );
+    Ok(Json(checkpoint.summary(size)))
+}
+
+/// GET /state/checkpoints
+pub async fn list_checkpoints(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<CheckpointSummary>>, (StatusCode, String)> {
+    state.checkpoints.list().map(Json).map_err(internal)
+}
+
+/// GET /state/checkpoints/:name - full checkpoint document (for backups)
+pub async fn get_checkpoint(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<Checkpoint>, (StatusCode, String)> {
+    check_name(&name)?;
+    state
+        .checkpoints
+        .load(&name)
+        .map_err(internal)?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, format!("Checkpoint not found: {}", name)))
+}
+
+/// POST /state/checkpoints/:name/restore
+pub async fn restore_checkpoint(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<RestoreResponse>, (StatusCode, String)> {
+    check_name(&name)?;
+    let checkpoint = state
+        .checkpoints
+        .load(&name)
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, format!("Checkpoint not found: {}", name)))?;
+    let registered = constellation_names(&state);
+    if checkpoint.constellations != registered {
+        return Err((
+            StatusCode::CONFLICT,
+            format!(
+                "Checkpoint {} is for constellations {:?}, gateway runs {:?}",
+                name, checkpoint.constellations, registered
+            ),
+        ));
+    }
+
+    state.constellation.update(|_| {
+        (
+            ConstellationState {
+                satellites: checkpoint.satellites.clone(),
+                ground_stations: checkpoint.ground_stations.clone(),
+            },
+            (),
+        )
+    });
+    // Mode first: set_mode re-anchors at the current sim time, set_time then pins it
+    state.clock.set_mode(checkpoint.clock.mode);
+    state.clock.set_time(checkpoint.clock.sim_time);
+
+    let sim_time = checkpoint.clock.sim_time;
+    let rebuilt = topology::snapshot(&state, sim_time).map_err(internal)?;
+    let topology_mismatches = topology_mismatches(&checkpoint.topology, &rebuilt);
+    if topology_mismatches > 0 {
+        tracing::warn!(
+            "Checkpoint {}: {} links differ from the saved graph",
+            name,
+            topology_mismatches
+        );
+    }
+
+    let tick = propagation::tick(&state, sim_time).await.map_err(internal)?;
+    tracing::info!("Restored checkpoint {} at sim time {}", name, sim_time);
+
+    Ok(Json(RestoreResponse {
+        restored: checkpoint.summary(0),
+        tick,
+        topology_mismatches,
+    }))
+}
+
+/// Links present in only one graph, or whose active state differs
+fn topology_mismatches(saved: &TopologySnapshot, rebuilt: &TopologySnapshot) -> usize {
+    let saved_links: HashMap<&str, bool> =
+        saved.links.iter().map(|l| (l.id.as_str(), l.active)).collect();
+    let mut mismatches = rebuilt
+        .links
+        .iter()
+        .filter(|l| saved_links.get(l.id.as_str()) != Some(&l.active))
+        .count();
+    let rebuilt_ids: HashSet<&str> = rebuilt.links.iter().map(|l| l.id.as_str()).collect();
+    mismatches += saved_links.keys().filter(|id| !rebuilt_ids.contains(*id)).count();
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_name() {
+        assert!(valid_name("before-burn_2"));
+        assert!(valid_name("sim-20260101T000000Z"));
+        assert!(!valid_name(""));
+        assert!(!valid_name("../etc/passwd"));
+        assert!(!valid_name(".hidden"));
+        assert!(!valid_name("a/b"));
+        assert!(!valid_name(&"x".repeat(MAX_NAME_LEN + 1)));
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("orbital-checkpoints-{}", uuid::Uuid::new_v4()));
+        let store = CheckpointStore::open(&dir).unwrap();
+        let sim_time = Utc::now();
+        let checkpoint = Checkpoint {
+            format_version: FORMAT_VERSION,
+            name: "t1".to_string(),
+            label: Some("test".to_string()),
+            saved_at: Utc::now(),
+            saved_by: None,
+            constellations: vec!["HALO".to_string()],
+            clock: ClockCheckpoint {
+                sim_time,
+                mode: ClockMode::Accelerated { factor: 60.0 },
+            },
+            satellites: Vec::new(),
+            ground_stations: Vec::new(),
+            topology: TopologySnapshot {
+                epoch: sim_time,
+                nodes: Vec::new(),
+                links: Vec::new(),
+            },
+            pending_maneuvers: Vec::new(),
+        };
+
+        assert!(!store.exists("t1"));
+        store.save(&checkpoint).unwrap();
+        assert!(store.exists("t1"));
+
+        let loaded = store.load("t1").unwrap().unwrap();
+        assert_eq!(loaded.clock.sim_time, sim_time);
+        assert_eq!(loaded.clock.mode, ClockMode::Accelerated { factor: 60.0 });
+        assert!(store.load("missing").unwrap().is_none());
+
+        let listed = store.list().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "t1");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
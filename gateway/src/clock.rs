@@ -0,0 +1,204 @@
+//! Simulation clock
+//!
+//! Gateway-wide notion of "now". Runs in real time, paused, or accelerated
+//! N×, and can be jumped to any epoch. The propagation loop, `?at=`
+//! defaults and history windows all read from here instead of `Utc::now()`.
+//!
+//! Internally the clock stores an anchor pair (wall time, sim time) and a
+//! rate; `now()` = sim anchor + (wall now − wall anchor) × rate. Every mode
+//! change re-anchors at the current sim time so there are no jumps.
+
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+use crate::AppState;
+
+/// Maximum acceleration factor (1 day per wall second)
+pub const MAX_ACCELERATION: f64 = 86_400.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ClockMode {
+    RealTime,
+    Paused,
+    Accelerated { factor: f64 },
+}
+
+impl ClockMode {
+    pub fn rate(&self) -> f64 {
+        match self {
+            ClockMode::RealTime => 1.0,
+            ClockMode::Paused => 0.0,
+            ClockMode::Accelerated { factor } => *factor,
+        }
+    }
+}
+
+struct Anchor {
+    wall: DateTime<Utc>,
+    sim: DateTime<Utc>,
+    mode: ClockMode,
+}
+
+pub struct SimClock {
+    anchor: RwLock<Anchor>,
+}
+
+impl SimClock {
+    /// Real-time clock starting at the current wall time
+    pub fn real_time() -> Self {
+        let now = Utc::now();
+        Self {
+            anchor: RwLock::new(Anchor {
+                wall: now,
+                sim: now,
+                mode: ClockMode::RealTime,
+            }),
+        }
+    }
+
+    /// Current simulation time
+    pub fn now(&self) -> DateTime<Utc> {
+        let anchor = self.anchor.read().unwrap();
+        sim_time_at(&anchor, Utc::now())
+    }
+
+    pub fn mode(&self) -> ClockMode {
+        self.anchor.read().unwrap().mode
+    }
+
+    /// Change mode, continuing from the current sim time
+    pub fn set_mode(&self, mode: ClockMode) {
+        let mut anchor = self.anchor.write().unwrap();
+        let wall = Utc::now();
+        anchor.sim = sim_time_at(&anchor, wall);
+        anchor.wall = wall;
+        anchor.mode = mode;
+    }
+
+    /// Jump to `sim_time`, keeping the current mode
+    pub fn set_time(&self, sim_time: DateTime<Utc>) {
+        let mut anchor = self.anchor.write().unwrap();
+        anchor.wall = Utc::now();
+        anchor.sim = sim_time;
+    }
+
+    pub fn status(&self) -> ClockStatus {
+        let anchor = self.anchor.read().unwrap();
+        let wall = Utc::now();
+        ClockStatus {
+            sim_time: sim_time_at(&anchor, wall),
+            wall_time: wall,
+            mode: anchor.mode,
+            rate: anchor.mode.rate(),
+            offset_seconds: (sim_time_at(&anchor, wall) - wall).num_milliseconds() as f64 / 1000.0,
+        }
+    }
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self::real_time()
+    }
+}
+
+fn sim_time_at(anchor: &Anchor, wall: DateTime<Utc>) -> DateTime<Utc> {
+    let elapsed_ms = (wall - anchor.wall).num_milliseconds() as f64;
+    anchor.sim + Duration::milliseconds((elapsed_ms * anchor.mode.rate()) as i64)
+}
+
+// ========== Routes ==========
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClockStatus {
+    pub sim_time: DateTime<Utc>,
+    pub wall_time: DateTime<Utc>,
+    #[serde(flatten)]
+    pub mode: ClockMode,
+    pub rate: f64,
+    /// sim − wall, seconds
+    pub offset_seconds: f64,
+}
+
+#[derive(Deserialize)]
+pub struct ClockRequest {
+    /// real_time | paused | accelerated
+    pub mode: Option<String>,
+    /// Acceleration factor for `accelerated`
+    pub factor: Option<f64>,
+    /// Jump to this epoch
+    pub sim_time: Option<DateTime<Utc>>,
+    /// Jump back to wall time
+    pub reset: Option<bool>,
+}
+
+/// GET /state/clock
+pub async fn get_clock(State(state): State<AppState>) -> Json<ClockStatus> {
+    Json(state.clock.status())
+}
+
+/// POST /state/clock
+pub async fn set_clock(
+    State(state): State<AppState>,
+    Json(req): Json<ClockRequest>,
+) -> Result<Json<ClockStatus>, (StatusCode, String)> {
+    if let Some(mode) = &req.mode {
+        let mode = match mode.to_lowercase().as_str() {
+            "real_time" | "realtime" => ClockMode::RealTime,
+            "paused" | "pause" => ClockMode::Paused,
+            "accelerated" => {
+                let factor = req.factor.ok_or((
+                    StatusCode::BAD_REQUEST,
+                    "`factor` required for accelerated mode".to_string(),
+                ))?;
+                if !(factor > 0.0 && factor <= MAX_ACCELERATION) {
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        format!("`factor` must be in (0, {}]", MAX_ACCELERATION),
+                    ));
+                }
+                ClockMode::Accelerated { factor }
+            }
+            other => {
+                return Err((StatusCode::BAD_REQUEST, format!("Unknown clock mode: {}", other)))
+            }
+        };
+        state.clock.set_mode(mode);
+    }
+
+    if req.reset.unwrap_or(false) {
+        state.clock.set_time(Utc::now());
+    } else if let Some(sim_time) = req.sim_time {
+        state.clock.set_time(sim_time);
+    }
+
+    let status = state.clock.status();
+    tracing::info!("Sim clock: {:?} at {}", status.mode, status.sim_time);
+    Ok(Json(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paused_clock_holds_time() {
+        let clock = SimClock::real_time();
+        clock.set_mode(ClockMode::Paused);
+        let t0 = clock.now();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert_eq!(clock.now(), t0);
+    }
+
+    #[test]
+    fn test_accelerated_clock_runs_fast() {
+        let clock = SimClock::real_time();
+        let epoch = Utc::now() - Duration::days(1);
+        clock.set_time(epoch);
+        clock.set_mode(ClockMode::Accelerated { factor: 1000.0 });
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert!(clock.now() - epoch >= Duration::seconds(15));
+    }
+}
@@ -0,0 +1,167 @@
+//! Payload codecs
+//!
+//! Telemetry subjects and the WebSocket position stream can carry JSON,
+//! CBOR or MessagePack; subjects with a telemetry schema can also carry
+//! protobuf. Every encoded payload is tagged with its `Content-Type`, so
+//! readers (including telemetry replay) decode by header rather than by
+//! configuration. JSON stays the default wherever nothing is configured.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    Json,
+    Cbor,
+    #[serde(alias = "messagepack")]
+    Msgpack,
+    /// Schema subjects only (`schema`); others fall back to JSON
+    Protobuf,
+}
+
+impl Codec {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Codec::Json => "application/json",
+            Codec::Cbor => "application/cbor",
+            Codec::Msgpack => "application/msgpack",
+            Codec::Protobuf => crate::schema::CONTENT_TYPE,
+        }
+    }
+
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        [Codec::Json, Codec::Cbor, Codec::Msgpack, Codec::Protobuf]
+            .into_iter()
+            .find(|c| c.content_type().eq_ignore_ascii_case(essence))
+    }
+
+    /// Encode a self-describing payload; protobuf needs a schema, so it
+    /// encodes as JSON here
+    pub fn encode<T: Serialize>(self, value: &T) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            Codec::Json | Codec::Protobuf => serde_json::to_vec(value)?,
+            Codec::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf)?;
+                buf
+            }
+            // Named fields, so payloads decode without the Rust struct
+            Codec::Msgpack => rmp_serde::to_vec_named(value)?,
+        })
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, payload: &[u8]) -> anyhow::Result<T> {
+        Ok(match self {
+            Codec::Json | Codec::Protobuf => serde_json::from_slice(payload)?,
+            Codec::Cbor => ciborium::from_reader(payload)?,
+            Codec::Msgpack => rmp_serde::from_slice(payload)?,
+        })
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Codec::Json => "json",
+            Codec::Cbor => "cbor",
+            Codec::Msgpack => "msgpack",
+            Codec::Protobuf => "protobuf",
+        })
+    }
+}
+
+impl FromStr for Codec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Codec::Json),
+            "cbor" => Ok(Codec::Cbor),
+            "msgpack" | "messagepack" => Ok(Codec::Msgpack),
+            "protobuf" | "proto" => Ok(Codec::Protobuf),
+            other => Err(format!("unknown codec {} (json, cbor, msgpack, protobuf)", other)),
+        }
+    }
+}
+
+/// Codec per subject: the longest configured prefix (whole tokens) wins,
+/// else the default for the subject
+#[derive(Debug, Clone, Default)]
+pub struct SubjectCodecs {
+    by_prefix: BTreeMap<String, Codec>,
+}
+
+impl SubjectCodecs {
+    pub fn new(by_prefix: BTreeMap<String, Codec>) -> Self {
+        Self { by_prefix }
+    }
+
+    /// Configured codec for `subject`, if any prefix matches
+    pub fn lookup(&self, subject: &str) -> Option<Codec> {
+        self.by_prefix
+            .iter()
+            .filter(|(prefix, _)| {
+                subject == prefix.as_str()
+                    || subject
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, codec)| *codec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::StationTelemetryRecord;
+    use chrono::{DateTime, Utc};
+
+    fn record() -> StationTelemetryRecord {
+        StationTelemetryRecord {
+            station_id: "GS-001".to_string(),
+            timestamp: DateTime::<Utc>::from_timestamp_millis(1_700_000_000_000).unwrap(),
+            status: "online".to_string(),
+            weather_score: 0.82,
+            cloud_cover_pct: Some(35.0),
+        }
+    }
+
+    #[test]
+    fn test_codecs_round_trip_and_shrink() {
+        let json = Codec::Json.encode(&record()).unwrap();
+        for codec in [Codec::Json, Codec::Cbor, Codec::Msgpack] {
+            let bytes = codec.encode(&record()).unwrap();
+            let decoded: StationTelemetryRecord = codec.decode(&bytes).unwrap();
+            assert_eq!(serde_json::to_value(decoded).unwrap(), serde_json::to_value(record()).unwrap());
+            assert!(bytes.len() <= json.len(), "{} larger than JSON", codec);
+        }
+    }
+
+    #[test]
+    fn test_names_and_content_types() {
+        for codec in [Codec::Json, Codec::Cbor, Codec::Msgpack, Codec::Protobuf] {
+            assert_eq!(codec.to_string().parse::<Codec>(), Ok(codec));
+            assert_eq!(Codec::from_content_type(codec.content_type()), Some(codec));
+        }
+        assert_eq!("MessagePack".parse::<Codec>(), Ok(Codec::Msgpack));
+        assert_eq!(Codec::from_content_type("application/json; charset=utf-8"), Some(Codec::Json));
+        assert!("yaml".parse::<Codec>().is_err());
+    }
+
+    #[test]
+    fn test_longest_token_prefix_wins() {
+        let codecs = SubjectCodecs::new(BTreeMap::from([
+            ("orbital.gs".to_string(), Codec::Cbor),
+            ("orbital.gs.GS-001".to_string(), Codec::Msgpack),
+        ]));
+        assert_eq!(codecs.lookup("orbital.gs.GS-002.telemetry"), Some(Codec::Cbor));
+        assert_eq!(codecs.lookup("orbital.gs.GS-001.telemetry"), Some(Codec::Msgpack));
+        assert_eq!(codecs.lookup("orbital.gsx.telemetry"), None);
+        assert_eq!(codecs.lookup("orbital.sat.HALO-01.position"), None);
+    }
+}
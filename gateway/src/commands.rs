@@ -0,0 +1,72 @@
+//! Ground station command channel
+//!
+//! `POST /stations/:id/commands` wraps a typed `StationCommand` in an
+//! envelope and sends it as a NATS request on `orbital.gs.{id}.cmd`. The
+//! station (docker simulator / WASM twin) applies it and replies with a
+//! `CommandAck`, which is returned to the caller.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::time::Duration;
+
+use ground_station_wasm::command::command_subject;
+use ground_station_wasm::{CommandAck, CommandEnvelope, StationCommand};
+
+use crate::telemetry::subject_token;
+use crate::AppState;
+
+/// How long to wait for a station to acknowledge
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// POST /stations/:id/commands
+pub async fn send_command(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(command): Json<StationCommand>,
+) -> Result<Json<CommandAck>, (StatusCode, String)> {
+    if state.constellation.load().station(&id).is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("Station not found: {}", id)));
+    }
+
+    let nats = state.telemetry.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "NATS not configured".to_string(),
+    ))?;
+
+    let envelope = CommandEnvelope {
+        command_id: uuid::Uuid::new_v4().to_string(),
+        station_id: id.clone(),
+        issued_unix_ms: chrono::Utc::now().timestamp_millis(),
+        command,
+    };
+    let payload = serde_json::to_vec(&envelope)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let request = nats
+        .client()
+        .request(command_subject(&subject_token(&id)), payload.into());
+    let reply = tokio::time::timeout(COMMAND_TIMEOUT, request)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::GATEWAY_TIMEOUT,
+                format!("{} did not acknowledge within {:?}", id, COMMAND_TIMEOUT),
+            )
+        })?
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))?;
+
+    let ack: CommandAck = serde_json::from_slice(&reply.payload)
+        .map_err(|e| (StatusCode::BAD_GATEWAY, format!("Malformed ack: {}", e)))?;
+
+    tracing::info!(
+        "Command {} to {}: {:?}{}",
+        envelope.command_id,
+        id,
+        ack.status,
+        ack.message.as_deref().map(|m| format!(" ({})", m)).unwrap_or_default()
+    );
+    Ok(Json(ack))
+}
@@ -0,0 +1,730 @@
+//! Gateway configuration
+//!
+//! Loaded once at startup from a TOML file (`ORBITAL_CONFIG`, else
+//! `./orbital.toml` if present, else built-in defaults), then overlaid
+//! with the individual `ORBITAL_*` / `NATS_URL` environment variables so
+//! existing deployments keep working. Validated before anything starts.
+//! Secrets (API keys, JWT secret) stay environment-only; see `auth`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use ground_station_wasm::contact::AcquisitionModel;
+use orbital_mechanics::propagation::{StalePolicy, ValidityWindow};
+use orbital_mechanics::stationkeeping::StationKeepingBox;
+use orbital_mechanics::walker::WalkerDelta;
+
+use crate::codec::Codec;
+
+const DEFAULT_CONFIG_PATH: &str = "orbital.toml";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GatewayConfig {
+    pub server: ServerConfig,
+    /// Primary shell
+    pub constellation: ConstellationConfig,
+    /// Further shells (`[[additional_constellations]]`), each its own ID namespace
+    pub additional_constellations: Vec<ConstellationConfig>,
+    pub stations: StationsConfig,
+    pub history: HistoryConfig,
+    pub memory: MemoryConfig,
+    pub checkpoints: CheckpointConfig,
+    pub propagation: PropagationSection,
+    pub station_keeping: StationKeepingSection,
+    pub bus: BusSection,
+    pub keyring: KeyringSection,
+    pub celestrak: CelestrakSection,
+    pub nats: NatsSection,
+    pub weather: WeatherSection,
+    pub neo4j: Neo4jSection,
+    pub rate_limit: RateLimitSection,
+    pub features: FeatureToggles,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub rest_port: u16,
+    pub grpc_port: u16,
+    pub ui_dist: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            rest_port: 18700,
+            grpc_port: 18701,
+            ui_dist: "ui/cesium-orbital/dist".to_string(),
+        }
+    }
+}
+
+/// Walker Delta T/P/F shell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConstellationConfig {
+    /// ID/name prefix, e.g. `HALO` -> `HALO-01`
+    pub name: String,
+    pub total_satellites: u32,
+    pub planes: u32,
+    pub phasing: u32,
+    pub altitude_km: f64,
+    pub inclination_deg: f64,
+    pub norad_base: u32,
+    /// Trailing satellites held as on-orbit spares
+    pub spares: u32,
+}
+
+impl Default for ConstellationConfig {
+    fn default() -> Self {
+        let halo = WalkerDelta::halo_constellation();
+        Self {
+            name: "HALO".to_string(),
+            total_satellites: halo.total_satellites,
+            planes: halo.planes,
+            phasing: halo.phasing,
+            altitude_km: halo.altitude_km,
+            inclination_deg: halo.inclination_deg,
+            norad_base: 60000,
+            spares: 4,
+        }
+    }
+}
+
+impl ConstellationConfig {
+    /// NORAD IDs assigned to this shell
+    pub fn norad_range(&self) -> std::ops::Range<u32> {
+        self.walker().norad_ids(self.norad_base)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let name = &self.name;
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '/') {
+            anyhow::bail!("constellation name {:?} must be non-empty without spaces or '/'", name);
+        }
+        if self.planes == 0 || self.total_satellites == 0 || self.total_satellites % self.planes != 0 {
+            anyhow::bail!(
+                "constellation {}: total_satellites ({}) must be a positive multiple of planes ({})",
+                name,
+                self.total_satellites,
+                self.planes
+            );
+        }
+        if self.planes > u8::MAX as u32 || self.total_satellites / self.planes > u8::MAX as u32 {
+            anyhow::bail!("constellation {}: at most 255 planes and 255 satellites per plane", name);
+        }
+        if !(self.altitude_km > 100.0 && self.altitude_km < 100_000.0) {
+            anyhow::bail!("constellation {}: altitude_km {} out of range", name, self.altitude_km);
+        }
+        if !(0.0..=180.0).contains(&self.inclination_deg) {
+            anyhow::bail!("constellation {}: inclination_deg {} out of range", name, self.inclination_deg);
+        }
+        if self.spares > self.total_satellites {
+            anyhow::bail!("constellation {}: spares exceeds total_satellites", name);
+        }
+        if self.norad_base as u64 + self.total_satellites as u64 > 99_999 {
+            anyhow::bail!("constellation {}: NORAD IDs must fit in five digits", name);
+        }
+        Ok(())
+    }
+
+    pub fn walker(&self) -> WalkerDelta {
+        WalkerDelta {
+            total_satellites: self.total_satellites,
+            planes: self.planes,
+            phasing: self.phasing,
+            altitude_km: self.altitude_km,
+            inclination_deg: self.inclination_deg,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StationsConfig {
+    /// Candidate-selector `SelectionResult` JSON replacing the built-in
+    /// FSO ground-station network
+    pub selection_path: Option<String>,
+    /// JSON array of `NetworkStation`s replacing the built-in strategic set
+    pub strategic_path: Option<String>,
+    /// Submarine cable systems (`submarine_cables.json`) for terrestrial
+    /// backhaul between stations; needs `backhaul_landings_path`
+    pub backhaul_cables_path: Option<String>,
+    /// Cable landing coordinates (`cable_landing_complete.json`)
+    pub backhaul_landings_path: Option<String>,
+    /// Pointing and scan parameters for pass acquisition probability
+    pub acquisition: AcquisitionModel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    pub path: String,
+    pub retention_hours: i64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            path: ".orbital-history".to_string(),
+            retention_hours: 168,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MemoryConfig {
+    pub path: String,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            path: ".orbital-memory".to_string(),
+        }
+    }
+}
+
+/// Simulation checkpoint files (`POST /state/checkpoints`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheckpointConfig {
+    pub dir: String,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            dir: ".orbital-checkpoints".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PropagationSection {
+    /// Background tick cadence; fractional seconds allowed for demos
+    pub interval_secs: f64,
+    /// Spacing of the SGP4 nodes position queries interpolate between
+    pub ephemeris_node_secs: u32,
+    /// Nodes kept per satellite
+    pub ephemeris_max_nodes: usize,
+    /// Element age (days either side of epoch) past which a tick looks for
+    /// newer elements; 0 disables
+    pub max_element_age_days: f64,
+    /// Propagate stale elements with a warning, or skip the satellite
+    pub stale_elements: StalePolicy,
+    /// IERS `finals2000A` file; without it UT1 = UTC and the pole is fixed
+    pub eop_path: Option<String>,
+}
+
+impl Default for PropagationSection {
+    fn default() -> Self {
+        Self {
+            interval_secs: 30.0,
+            ephemeris_node_secs: 60,
+            ephemeris_max_nodes: 1440,
+            max_element_age_days: 14.0,
+            stale_elements: StalePolicy::Warn,
+            eop_path: None,
+        }
+    }
+}
+
+impl PropagationSection {
+    pub fn validity_window(&self) -> ValidityWindow {
+        ValidityWindow {
+            max_age_days: self.max_element_age_days,
+            policy: self.stale_elements,
+        }
+    }
+}
+
+/// Closed-loop station keeping of Walker slots (sim time)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StationKeepingSection {
+    pub enabled: bool,
+    /// Sim time between drift/box checks
+    pub step_minutes: i64,
+    pub inclination_deg: f64,
+    pub raan_deg: f64,
+    pub along_track_deg: f64,
+    /// Delta-v allowance per satellite per year
+    pub annual_budget_m_s: f64,
+}
+
+impl Default for StationKeepingSection {
+    fn default() -> Self {
+        let tolerance = StationKeepingBox::default();
+        Self {
+            enabled: true,
+            step_minutes: 60,
+            inclination_deg: tolerance.inclination_deg,
+            raan_deg: tolerance.raan_deg,
+            along_track_deg: tolerance.along_track_deg,
+            annual_budget_m_s: 25.0,
+        }
+    }
+}
+
+impl StationKeepingSection {
+    pub fn tolerance(&self) -> StationKeepingBox {
+        StationKeepingBox {
+            inclination_deg: self.inclination_deg,
+            raan_deg: self.raan_deg,
+            along_track_deg: self.along_track_deg,
+        }
+    }
+}
+
+/// Spacecraft bus power and thermal parameters, shared by every satellite
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BusSection {
+    pub enabled: bool,
+    /// Array output in full sun
+    pub solar_array_w: f64,
+    pub battery_wh: f64,
+    /// Housekeeping draw, always on
+    pub bus_load_w: f64,
+    /// Optical terminal draw while a ground link is active
+    pub payload_w: f64,
+    /// Crosslink draw for operational satellites
+    pub isl_w: f64,
+    /// Payload and crosslinks are shed below this state of charge
+    pub load_shed_pct: f64,
+    /// Lumped thermal mass
+    pub heat_capacity_j_k: f64,
+    pub radiator_area_m2: f64,
+    pub emissivity: f64,
+    /// Solar heat absorbed by the structure in full sun
+    pub absorbed_solar_w: f64,
+    pub min_temperature_c: f64,
+    pub max_temperature_c: f64,
+}
+
+impl Default for BusSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            solar_array_w: 4000.0,
+            battery_wh: 3000.0,
+            bus_load_w: 600.0,
+            payload_w: 900.0,
+            isl_w: 300.0,
+            load_shed_pct: 30.0,
+            heat_capacity_j_k: 360_000.0,
+            radiator_area_m2: 6.0,
+            emissivity: 0.85,
+            absorbed_solar_w: 600.0,
+            min_temperature_c: -10.0,
+            max_temperature_c: 45.0,
+        }
+    }
+}
+
+/// Sideband channel key lifecycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyringSection {
+    /// Age at which a channel key is replaced; it stays valid one more
+    /// interval for messages in flight
+    pub rotation_secs: u64,
+    /// Back keys with harvested entropy only, never OS randomness
+    pub require_harvested: bool,
+}
+
+impl Default for KeyringSection {
+    fn default() -> Self {
+        Self {
+            rotation_secs: 300,
+            require_harvested: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CelestrakSection {
+    pub groups: Vec<String>,
+    pub url: String,
+    /// Zero disables the scheduled refresh
+    pub interval_hours: u64,
+}
+
+impl Default for CelestrakSection {
+    fn default() -> Self {
+        Self {
+            groups: vec!["geo".to_string(), "gnss".to_string()],
+            url: "https://celestrak.org/NORAD/elements/gp.php".to_string(),
+            interval_hours: 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NatsSection {
+    /// Unset disables JetStream telemetry and station commands
+    pub url: Option<String>,
+    pub retention_hours: u64,
+    pub consumer_groups: Vec<String>,
+    /// Mirror protobuf telemetry as JSON on `orbital.json.<subject>`
+    pub json_fallback: bool,
+    /// Payload codec by subject prefix; unset subjects use protobuf where
+    /// a schema exists, else JSON
+    pub codecs: BTreeMap<String, Codec>,
+}
+
+impl Default for NatsSection {
+    fn default() -> Self {
+        Self {
+            url: None,
+            retention_hours: 24,
+            consumer_groups: vec!["dashboards".to_string()],
+            json_fallback: true,
+            codecs: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeatherSection {
+    /// How long a fetched observation is served from cache
+    pub cache_ttl_secs: u64,
+    /// Upper bound on in-flight provider requests during bulk fetches
+    pub max_concurrent: usize,
+    pub timeout_secs: u64,
+}
+
+impl Default for WeatherSection {
+    fn default() -> Self {
+        Self {
+            cache_ttl_secs: 600,
+            max_concurrent: 8,
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// Used only when built with the `neo4j` feature. The password comes from
+/// `NEO4J_PASSWORD`, never the file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Neo4jSection {
+    /// Unset disables the Neo4j connection
+    pub uri: Option<String>,
+    pub username: String,
+    pub database: Option<String>,
+}
+
+impl Default for Neo4jSection {
+    fn default() -> Self {
+        Self {
+            uri: None,
+            username: "neo4j".to_string(),
+            database: None,
+        }
+    }
+}
+
+#[cfg(feature = "neo4j")]
+impl Neo4jSection {
+    pub fn client_config(&self) -> Option<orbital_glaf::neo4j_client::Neo4jConfig> {
+        let defaults = orbital_glaf::neo4j_client::Neo4jConfig::default();
+        Some(orbital_glaf::neo4j_client::Neo4jConfig {
+            uri: self.uri.clone()?,
+            username: self.username.clone(),
+            password: std::env::var("NEO4J_PASSWORD").unwrap_or(defaults.password),
+            database: self.database.clone(),
+            ..defaults
+        })
+    }
+}
+
+/// Per-caller budgets; see `ratelimit`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitSection {
+    pub enabled: bool,
+    pub cheap_per_minute: u32,
+    pub cheap_burst: u32,
+    pub expensive_per_minute: u32,
+    pub expensive_burst: u32,
+}
+
+impl Default for RateLimitSection {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cheap_per_minute: 600,
+            cheap_burst: 120,
+            expensive_per_minute: 30,
+            expensive_burst: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeatureToggles {
+    pub grpc: bool,
+    pub celestrak: bool,
+    pub telemetry: bool,
+    pub serve_ui: bool,
+}
+
+impl Default for FeatureToggles {
+    fn default() -> Self {
+        Self {
+            grpc: true,
+            celestrak: true,
+            telemetry: true,
+            serve_ui: true,
+        }
+    }
+}
+
+impl GatewayConfig {
+    /// Resolve file + environment and validate
+    pub fn load() -> anyhow::Result<Self> {
+        let explicit = std::env::var("ORBITAL_CONFIG").ok();
+        let path = explicit.clone().unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_string());
+
+        let mut config = if Path::new(&path).exists() {
+            let text = std::fs::read_to_string(&path)?;
+            toml::from_str(&text).map_err(|e| anyhow::anyhow!("{}: {}", path, e))?
+        } else if explicit.is_some() {
+            anyhow::bail!("Config file {} not found", path);
+        } else {
+            Self::default()
+        };
+
+        config.apply_env();
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn apply_env(&mut self) {
+        env_override("PORT", &mut self.server.rest_port);
+        env_override("ORBITAL_GATEWAY_PORT", &mut self.server.rest_port);
+        env_override("ORBITAL_GRPC_PORT", &mut self.server.grpc_port);
+        env_override("ORBITAL_MEMORY_PATH", &mut self.memory.path);
+        env_override("ORBITAL_HISTORY_PATH", &mut self.history.path);
+        env_override("ORBITAL_CHECKPOINT_DIR", &mut self.checkpoints.dir);
+        env_override("ORBITAL_HISTORY_RETENTION_HOURS", &mut self.history.retention_hours);
+        env_override("ORBITAL_PROPAGATION_INTERVAL_SECS", &mut self.propagation.interval_secs);
+        env_override("ORBITAL_EPHEMERIS_NODE_SECS", &mut self.propagation.ephemeris_node_secs);
+        env_override("ORBITAL_KEY_ROTATION_SECS", &mut self.keyring.rotation_secs);
+        env_override("ORBITAL_CELESTRAK_URL", &mut self.celestrak.url);
+        env_override("ORBITAL_CELESTRAK_INTERVAL_HOURS", &mut self.celestrak.interval_hours);
+        env_list_override("ORBITAL_CELESTRAK_GROUPS", &mut self.celestrak.groups);
+        if let Ok(url) = std::env::var("NATS_URL") {
+            self.nats.url = Some(url);
+        }
+        if let Ok(uri) = std::env::var("NEO4J_URI") {
+            self.neo4j.uri = Some(uri);
+        }
+        if let Ok(path) = std::env::var("ORBITAL_EOP_PATH") {
+            self.propagation.eop_path = Some(path);
+        }
+        env_override("ORBITAL_TELEMETRY_RETENTION_HOURS", &mut self.nats.retention_hours);
+        env_list_override("ORBITAL_TELEMETRY_CONSUMER_GROUPS", &mut self.nats.consumer_groups);
+        env_override("ORBITAL_TELEMETRY_JSON_FALLBACK", &mut self.nats.json_fallback);
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let shells: Vec<&ConstellationConfig> = self.constellations().collect();
+        for (i, shell) in shells.iter().enumerate() {
+            shell.validate()?;
+            for other in &shells[..i] {
+                if other.name == shell.name {
+                    anyhow::bail!("constellation name {} is used twice", shell.name);
+                }
+                let (a, b) = (shell.norad_range(), other.norad_range());
+                if a.start < b.end && b.start < a.end {
+                    anyhow::bail!(
+                        "constellations {} and {} have overlapping NORAD ranges",
+                        other.name,
+                        shell.name
+                    );
+                }
+            }
+        }
+        if self.server.rest_port == self.server.grpc_port {
+            anyhow::bail!("server.rest_port and server.grpc_port must differ");
+        }
+        if self.history.retention_hours <= 0 {
+            anyhow::bail!("history.retention_hours must be positive");
+        }
+        let interval = self.propagation.interval_secs;
+        if interval.is_nan() || interval < 0.1 {
+            anyhow::bail!("propagation.interval_secs must be at least 0.1");
+        }
+        if !(1..=600).contains(&self.propagation.ephemeris_node_secs) || self.propagation.ephemeris_max_nodes < 2 {
+            anyhow::bail!("propagation.ephemeris_node_secs must be 1-600 and ephemeris_max_nodes at least 2");
+        }
+        if let Some(path) = &self.propagation.eop_path {
+            if !Path::new(path).exists() {
+                anyhow::bail!("propagation.eop_path {} not found", path);
+            }
+        }
+        let sk = &self.station_keeping;
+        if sk.step_minutes <= 0 {
+            anyhow::bail!("station_keeping.step_minutes must be positive");
+        }
+        let tolerances = [sk.inclination_deg, sk.raan_deg, sk.along_track_deg, sk.annual_budget_m_s];
+        if tolerances.iter().any(|t| t.is_nan() || *t <= 0.0) {
+            anyhow::bail!("station_keeping box tolerances and annual_budget_m_s must be positive");
+        }
+        let bus = &self.bus;
+        let positive = [
+            bus.battery_wh,
+            bus.heat_capacity_j_k,
+            bus.radiator_area_m2,
+            bus.emissivity,
+        ];
+        if positive.iter().any(|v| v.is_nan() || *v <= 0.0) {
+            anyhow::bail!("bus battery_wh, heat_capacity_j_k, radiator_area_m2 and emissivity must be positive");
+        }
+        let powers = [bus.solar_array_w, bus.bus_load_w, bus.payload_w, bus.isl_w, bus.absorbed_solar_w];
+        if powers.iter().any(|v| v.is_nan() || *v < 0.0) {
+            anyhow::bail!("bus power figures must not be negative");
+        }
+        if !(0.0..=100.0).contains(&bus.load_shed_pct) || bus.min_temperature_c >= bus.max_temperature_c {
+            anyhow::bail!("bus.load_shed_pct must be 0-100 and min_temperature_c below max_temperature_c");
+        }
+        if self.keyring.rotation_secs < 10 {
+            anyhow::bail!("keyring.rotation_secs must be at least 10");
+        }
+        if self.weather.max_concurrent == 0 {
+            anyhow::bail!("weather.max_concurrent must be at least 1");
+        }
+        if let Some(path) = &self.stations.strategic_path {
+            if !Path::new(path).exists() {
+                anyhow::bail!("stations.strategic_path {} not found", path);
+            }
+        }
+        match (&self.stations.backhaul_cables_path, &self.stations.backhaul_landings_path) {
+            (Some(cables), Some(landings)) => {
+                for path in [cables, landings] {
+                    if !Path::new(path).exists() {
+                        anyhow::bail!("stations backhaul dataset {} not found", path);
+                    }
+                }
+            }
+            (None, None) => {}
+            _ => anyhow::bail!("stations.backhaul_cables_path and backhaul_landings_path must be set together"),
+        }
+        Ok(())
+    }
+
+    /// Primary shell first, then the additional ones in file order
+    pub fn constellations(&self) -> impl Iterator<Item = &ConstellationConfig> {
+        std::iter::once(&self.constellation).chain(&self.additional_constellations)
+    }
+
+    pub fn propagation_interval(&self) -> Duration {
+        Duration::from_secs_f64(self.propagation.interval_secs)
+    }
+
+    pub fn celestrak_interval(&self) -> Duration {
+        if self.features.celestrak {
+            Duration::from_secs(self.celestrak.interval_hours * 3600)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+fn env_override<T: FromStr>(key: &str, target: &mut T) {
+    if let Some(value) = std::env::var(key).ok().and_then(|v| v.parse().ok()) {
+        *target = value;
+    }
+}
+
+fn env_list_override(key: &str, target: &mut Vec<String>) {
+    if let Ok(value) = std::env::var(key) {
+        *target = value
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_are_valid_halo() {
+        let config = GatewayConfig::default();
+        config.validate().unwrap();
+        assert_eq!(config.constellation.total_satellites, 12);
+        assert_eq!(config.server.rest_port, 18700);
+    }
+
+    #[test]
+    fn test_partial_file_keeps_defaults() {
+        let config: GatewayConfig = toml::from_str(
+            r#"
+            [constellation]
+            name = "LEO"
+            total_satellites = 24
+            planes = 4
+            altitude_km = 1200.0
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.constellation.inclination_deg, 55.0);
+        assert_eq!(config.history.retention_hours, 168);
+    }
+
+    #[test]
+    fn test_rejects_uneven_planes() {
+        let mut config = GatewayConfig::default();
+        config.constellation.total_satellites = 13;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_additional_constellations() {
+        let config: GatewayConfig = toml::from_str(
+            r#"
+            [[additional_constellations]]
+            name = "TLEO"
+            total_satellites = 8
+            planes = 2
+            altitude_km = 550.0
+            norad_base = 61000
+            spares = 0
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        let names: Vec<&str> = config.constellations().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["HALO", "TLEO"]);
+
+        let mut clash = config.clone();
+        clash.additional_constellations[0].norad_base = 60010;
+        assert!(clash.validate().is_err());
+
+        let mut duplicate = config;
+        duplicate.additional_constellations[0].name = "HALO".to_string();
+        assert!(duplicate.validate().is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys_rejected() {
+        assert!(toml::from_str::<GatewayConfig>("[server]\nrest_prot = 1").is_err());
+    }
+}
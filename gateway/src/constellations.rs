@@ -0,0 +1,249 @@
+//! Registered constellations
+//!
+//! The primary `[constellation]` shell plus any `[[additional_constellations]]`
+//! (e.g. a test LEO shell alongside HALO MEO). Each owns the ID namespace of
+//! its name prefix; `/constellations/:name/...` scopes the usual views to
+//! one shell, while the top-level `/satellites` and `/topology` span all.
+//! `/constellations/:name/conjunctions` screens a shell against itself;
+//! posting an OEM to `/constellations/:name/conjunctions/oem` screens it
+//! alongside the shell's TLEs.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use collision_avoidance::constellation::{EphemerisConjunction, SelfConjunction};
+use collision_avoidance::{oem, CollisionAssessment};
+use orbital_mechanics::SatelliteStatus;
+
+use crate::config::ConstellationConfig;
+use crate::routes::{satellite_info, AtQuery, SatelliteInfo};
+use crate::topology::{self, TopologySnapshot};
+use crate::{AppState, ConstellationState};
+
+#[derive(Serialize)]
+pub struct ConstellationSummary {
+    pub name: String,
+    pub primary: bool,
+    pub total_satellites: u32,
+    pub planes: u32,
+    pub phasing: u32,
+    pub altitude_km: f64,
+    pub inclination_deg: f64,
+    pub norad_base: u32,
+    /// Satellites currently in the constellation (TLE uploads may change it)
+    pub satellites: usize,
+    pub operational: usize,
+    pub spares: usize,
+}
+
+fn summary(shell: &ConstellationConfig, primary: bool, state: &ConstellationState) -> ConstellationSummary {
+    let satellites: Vec<_> = state.constellation(&shell.name).collect();
+    ConstellationSummary {
+        name: shell.name.clone(),
+        primary,
+        total_satellites: shell.total_satellites,
+        planes: shell.planes,
+        phasing: shell.phasing,
+        altitude_km: shell.altitude_km,
+        inclination_deg: shell.inclination_deg,
+        norad_base: shell.norad_base,
+        satellites: satellites.len(),
+        operational: satellites
+            .iter()
+            .filter(|s| s.status == SatelliteStatus::Operational)
+            .count(),
+        spares: satellites
+            .iter()
+            .filter(|s| s.status == SatelliteStatus::Spare)
+            .count(),
+    }
+}
+
+const DEFAULT_SCREENING_DAYS: i64 = 3;
+const MAX_SCREENING_DAYS: i64 = 7;
+const DEFAULT_SCREENING_RADIUS_KM: f64 = 10.0;
+const PROBABILITY_THRESHOLD: f64 = 1e-4;
+
+#[derive(Deserialize)]
+pub struct ScreeningQuery {
+    pub at: Option<DateTime<Utc>>,
+    /// Prediction horizon (default 3, at most 7)
+    pub days: Option<i64>,
+    pub radius_km: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct ScreeningResponse {
+    pub constellation: String,
+    pub from: DateTime<Utc>,
+    pub days: i64,
+    pub screening_radius_km: f64,
+    pub satellites: usize,
+    pub conjunctions: Vec<SelfConjunction>,
+}
+
+/// One object of a posted OEM
+#[derive(Serialize)]
+pub struct EphemerisSummary {
+    pub object_name: String,
+    pub object_id: String,
+    pub ref_frame: String,
+    pub start: Option<DateTime<Utc>>,
+    pub stop: Option<DateTime<Utc>>,
+    pub states: usize,
+    /// Satellite of the shell whose TLE it stands in for
+    pub satellite: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct EphemerisScreeningResponse {
+    pub constellation: String,
+    pub from: DateTime<Utc>,
+    pub days: i64,
+    pub screening_radius_km: f64,
+    pub satellites: usize,
+    pub ephemerides: Vec<EphemerisSummary>,
+    pub conjunctions: Vec<EphemerisConjunction>,
+}
+
+impl ScreeningQuery {
+    /// Start, horizon (days) and radius, defaulted and checked
+    fn resolve(&self, state: &AppState) -> Result<(DateTime<Utc>, i64, f64), (StatusCode, String)> {
+        let from = self.at.unwrap_or_else(|| state.clock.now());
+        let days = self.days.unwrap_or(DEFAULT_SCREENING_DAYS).clamp(1, MAX_SCREENING_DAYS);
+        let radius_km = self.radius_km.unwrap_or(DEFAULT_SCREENING_RADIUS_KM);
+        if radius_km.is_nan() || radius_km <= 0.0 {
+            return Err((StatusCode::BAD_REQUEST, "radius_km must be positive".to_string()));
+        }
+        Ok((from, days, radius_km))
+    }
+}
+
+fn find<'a>(state: &'a AppState, name: &str) -> Result<&'a ConstellationConfig, (StatusCode, String)> {
+    state
+        .config
+        .constellations()
+        .find(|c| c.name == name)
+        .ok_or((StatusCode::NOT_FOUND, format!("Constellation not found: {}", name)))
+}
+
+// ========== Routes ==========
+
+/// GET /constellations
+pub async fn list_constellations(State(state): State<AppState>) -> Json<Vec<ConstellationSummary>> {
+    let current = state.constellation.load();
+    Json(
+        state
+            .config
+            .constellations()
+            .enumerate()
+            .map(|(i, shell)| summary(shell, i == 0, &current))
+            .collect(),
+    )
+}
+
+/// GET /constellations/:name
+pub async fn get_constellation(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ConstellationSummary>, (StatusCode, String)> {
+    let shell = find(&state, &name)?;
+    let primary = shell.name == state.config.constellation.name;
+    Ok(Json(summary(shell, primary, &state.constellation.load())))
+}
+
+/// GET /constellations/:name/satellites
+pub async fn list_constellation_satellites(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<SatelliteInfo>>, (StatusCode, String)> {
+    let shell = find(&state, &name)?;
+    let current = state.constellation.load();
+    Ok(Json(current.constellation(&shell.name).map(satellite_info).collect()))
+}
+
+/// GET /constellations/:name/topology - one shell plus the ground segment
+pub async fn get_constellation_topology(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(q): Query<AtQuery>,
+) -> Result<Json<TopologySnapshot>, (StatusCode, String)> {
+    let shell = find(&state, &name)?;
+    topology::snapshot_of(&state, q.time(&state.clock), Some(&shell.name))
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// GET /constellations/:name/conjunctions?days=3&radius_km=10 - close
+/// approaches between the shell's own satellites
+pub async fn get_constellation_conjunctions(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(q): Query<ScreeningQuery>,
+) -> Result<Json<ScreeningResponse>, (StatusCode, String)> {
+    let shell = find(&state, &name)?;
+    let (from, days, radius_km) = q.resolve(&state)?;
+
+    let current = state.constellation.load();
+    let satellites: Vec<_> = current.constellation(&shell.name).cloned().collect();
+    let conjunctions = CollisionAssessment::new(radius_km, PROBABILITY_THRESHOLD, days)
+        .screen_constellation(&satellites, from);
+
+    Ok(Json(ScreeningResponse {
+        constellation: shell.name.clone(),
+        from,
+        days,
+        screening_radius_km: radius_km,
+        satellites: satellites.len(),
+        conjunctions,
+    }))
+}
+
+/// POST /constellations/:name/conjunctions/oem?days=3&radius_km=10 - close
+/// approaches with the posted OEM (KVN text): other operators' objects, or
+/// owner ephemerides replacing the shell's TLEs where they cover
+pub async fn screen_oem_conjunctions(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(q): Query<ScreeningQuery>,
+    body: String,
+) -> Result<Json<EphemerisScreeningResponse>, (StatusCode, String)> {
+    let shell = find(&state, &name)?;
+    let (from, days, radius_km) = q.resolve(&state)?;
+    let ephemerides = oem::parse(&body).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    if ephemerides.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "OEM contains no ephemeris".to_string()));
+    }
+
+    let current = state.constellation.load();
+    let satellites: Vec<_> = current.constellation(&shell.name).cloned().collect();
+    let summaries = ephemerides
+        .iter()
+        .map(|e| EphemerisSummary {
+            object_name: e.object_name.clone(),
+            object_id: e.object_id.clone(),
+            ref_frame: e.ref_frame.clone(),
+            start: e.start(),
+            stop: e.stop(),
+            states: e.states.len(),
+            satellite: satellites.iter().find(|s| e.describes(s)).map(|s| s.id.clone()),
+        })
+        .collect();
+    let conjunctions = CollisionAssessment::new(radius_km, PROBABILITY_THRESHOLD, days)
+        .screen_with_ephemerides(&satellites, &ephemerides, from);
+
+    Ok(Json(EphemerisScreeningResponse {
+        constellation: shell.name.clone(),
+        from,
+        days,
+        screening_radius_km: radius_km,
+        satellites: satellites.len(),
+        ephemerides: summaries,
+        conjunctions,
+    }))
+}
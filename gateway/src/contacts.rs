@@ -0,0 +1,373 @@
+//! Per-station contact schedules
+//!
+//! `GET /stations/:id/contacts` lays the station's predicted passes out as
+//! an operations schedule: each contact's satellite, AOS/LOS, purpose and
+//! the FSO terminal that takes it. Passes booked for key refresh
+//! (`keys`) are key transfers and get terminals first; every other pass is
+//! a data contact. Terminals are assigned first-free in AOS order, and a
+//! contact left over when every terminal is busy has no terminal.
+//!
+//! `?format=ical` returns the schedule as an RFC 5545 calendar with one
+//! event per contact, so operators and maintenance planners can subscribe
+//! to it from ordinary calendar tooling; the default is JSON.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::keys::KeyPass;
+use crate::passes::{predict_station_passes, PredictedPass};
+use crate::propagation::MIN_LINK_ELEVATION_DEG;
+use crate::AppState;
+
+const DEFAULT_HOURS: i64 = 24;
+const MAX_HOURS: i64 = 7 * 24;
+/// iCal content lines are folded at this many octets (RFC 5545 §3.1)
+const ICAL_LINE_OCTETS: usize = 75;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactPurpose {
+    Data,
+    KeyTransfer,
+}
+
+impl ContactPurpose {
+    fn label(self) -> &'static str {
+        match self {
+            ContactPurpose::Data => "Data",
+            ContactPurpose::KeyTransfer => "Key transfer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Contact {
+    pub satellite_id: String,
+    pub norad_id: u32,
+    pub aos: DateTime<Utc>,
+    pub los: DateTime<Utc>,
+    pub max_elevation_deg: f64,
+    pub aos_azimuth_deg: f64,
+    pub purpose: ContactPurpose,
+    /// 1-based FSO terminal; `None` when every terminal is busy
+    pub terminal: Option<u8>,
+    pub acquisition_probability: f64,
+    /// Key material expected from a key transfer (kbit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_kbit: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContactSchedule {
+    pub station_id: String,
+    pub station_name: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub terminals: u8,
+    /// AOS order
+    pub contacts: Vec<Contact>,
+    /// Contacts without a free terminal
+    pub unassigned: usize,
+}
+
+/// Schedule `passes` over `terminals`, marking those matching a booked
+/// key pass as key transfers
+pub fn schedule(passes: Vec<PredictedPass>, booked: &[KeyPass], terminals: u8) -> Vec<Contact> {
+    let mut contacts: Vec<Contact> = passes
+        .into_iter()
+        .map(|pass| {
+            let key = booked
+                .iter()
+                .find(|k| k.satellite_id == pass.satellite_id && k.aos < pass.los && pass.aos < k.los);
+            Contact {
+                satellite_id: pass.satellite_id,
+                norad_id: pass.norad_id,
+                aos: pass.aos,
+                los: pass.los,
+                max_elevation_deg: pass.max_elevation_deg,
+                aos_azimuth_deg: pass.aos_azimuth_deg,
+                purpose: match key {
+                    Some(_) => ContactPurpose::KeyTransfer,
+                    None => ContactPurpose::Data,
+                },
+                terminal: None,
+                acquisition_probability: pass.acquisition_probability,
+                expected_kbit: key.map(|k| k.expected_kbit),
+            }
+        })
+        .collect();
+    contacts.sort_by_key(|c| c.aos);
+
+    // Each terminal's busy intervals; key transfers claim terminals first
+    let mut busy: Vec<Vec<(DateTime<Utc>, DateTime<Utc>)>> = vec![Vec::new(); terminals as usize];
+    for purpose in [ContactPurpose::KeyTransfer, ContactPurpose::Data] {
+        for contact in contacts.iter_mut().filter(|c| c.purpose == purpose) {
+            let free = busy
+                .iter()
+                .position(|intervals| intervals.iter().all(|(aos, los)| contact.los <= *aos || *los <= contact.aos));
+            if let Some(index) = free {
+                busy[index].push((contact.aos, contact.los));
+                contact.terminal = Some(index as u8 + 1);
+            }
+        }
+    }
+    contacts
+}
+
+/// Escape TEXT values (RFC 5545 §3.3.11)
+fn ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line into CRLF-terminated lines of at most
+/// `ICAL_LINE_OCTETS`, continuation lines starting with a space
+fn fold_line(line: &str, out: &mut String) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > ICAL_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn ical_time(t: DateTime<Utc>) -> String {
+    t.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+impl ContactSchedule {
+    /// RFC 5545 calendar, one event per contact
+    pub fn to_ical(&self, stamp: DateTime<Utc>) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//SX9 Orbital//Contact Schedule//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            format!("X-WR-CALNAME:{}", ical_text(&format!("{} contacts", self.station_name))),
+        ];
+        for contact in &self.contacts {
+            let terminal = contact
+                .terminal
+                .map_or_else(|| "no terminal".to_string(), |t| format!("terminal {}", t));
+            let mut description = format!(
+                "Station {} ({})\nSatellite {} (NORAD {})\nMax elevation {:.1} deg\nAOS azimuth {:.1} deg\nAcquisition probability {:.2}",
+                self.station_name,
+                self.station_id,
+                contact.satellite_id,
+                contact.norad_id,
+                contact.max_elevation_deg,
+                contact.aos_azimuth_deg,
+                contact.acquisition_probability,
+            );
+            if let Some(kbit) = contact.expected_kbit {
+                description.push_str(&format!("\nExpected key material {:.0} kbit", kbit));
+            }
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!(
+                    "UID:{}-{}-{}@sx9-orbital",
+                    self.station_id,
+                    contact.satellite_id,
+                    ical_time(contact.aos)
+                ),
+                format!("DTSTAMP:{}", ical_time(stamp)),
+                format!("DTSTART:{}", ical_time(contact.aos)),
+                format!("DTEND:{}", ical_time(contact.los)),
+                format!(
+                    "SUMMARY:{}",
+                    ical_text(&format!("{} {} ({})", contact.purpose.label(), contact.satellite_id, terminal))
+                ),
+                format!("DESCRIPTION:{}", ical_text(&description)),
+                format!(
+                    "CATEGORIES:{}",
+                    match contact.purpose {
+                        ContactPurpose::Data => "DATA",
+                        ContactPurpose::KeyTransfer => "KEY-TRANSFER",
+                    }
+                ),
+                "TRANSP:TRANSPARENT".to_string(),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        let mut out = String::new();
+        for line in &lines {
+            fold_line(line, &mut out);
+        }
+        out
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleFormat {
+    #[default]
+    Json,
+    Ical,
+}
+
+#[derive(Deserialize)]
+pub struct ContactQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub hours: Option<i64>,
+    pub min_elevation_deg: Option<f64>,
+    #[serde(default)]
+    pub format: ScheduleFormat,
+}
+
+/// GET /stations/:id/contacts?hours=24&format=ical
+pub async fn get_contacts(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<ContactQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let constellation = state.constellation.load();
+    let station = constellation
+        .station(&id)
+        .ok_or((StatusCode::NOT_FOUND, format!("Station not found: {}", id)))?;
+
+    let now = state.clock.now();
+    let from = q.from.unwrap_or(now);
+    let to = from + Duration::hours(q.hours.unwrap_or(DEFAULT_HOURS).clamp(1, MAX_HOURS));
+    let min_el = q.min_elevation_deg.unwrap_or(MIN_LINK_ELEVATION_DEG);
+
+    let passes = predict_station_passes(&state, station, from, to, min_el);
+    let terminals = station.capabilities.fso_terminals;
+    let contacts = schedule(passes, &state.keys.booked(&id), terminals);
+    let schedule = ContactSchedule {
+        station_id: id,
+        station_name: station.name.clone(),
+        from,
+        to,
+        terminals,
+        unassigned: contacts.iter().filter(|c| c.terminal.is_none()).count(),
+        contacts,
+    };
+
+    Ok(match q.format {
+        ScheduleFormat::Json => Json(schedule).into_response(),
+        ScheduleFormat::Ical => (
+            [
+                (header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"{}-contacts.ics\"", schedule.station_id),
+                ),
+            ],
+            schedule.to_ical(now),
+        )
+            .into_response(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orbital_mechanics::eclipse::SunGeometry;
+
+    fn pass(satellite_id: &str, aos_min: i64, los_min: i64) -> PredictedPass {
+        let t0 = DateTime::<Utc>::from_timestamp(1_767_225_600, 0).unwrap();
+        PredictedPass {
+            satellite_id: satellite_id.to_string(),
+            norad_id: 90_001,
+            aos: t0 + Duration::minutes(aos_min),
+            los: t0 + Duration::minutes(los_min),
+            tca: t0 + Duration::minutes((aos_min + los_min) / 2),
+            duration_sec: ((los_min - aos_min) * 60) as f64,
+            max_elevation_deg: 42.0,
+            aos_azimuth_deg: 310.0,
+            los_azimuth_deg: 120.0,
+            predicted_margin_db: 8.0,
+            sun: SunGeometry {
+                sun_elevation_deg: -30.0,
+                phase_angle_deg: 90.0,
+                sun_separation_deg: 120.0,
+                daytime_background: false,
+            },
+            min_sun_separation_deg: 120.0,
+            predicted_fso_quality: 0.8,
+            acquisition_probability: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_key_transfers_claim_terminals_first() {
+        let passes = vec![pass("HALO-01", 0, 30), pass("HALO-02", 10, 40), pass("HALO-03", 35, 60)];
+        let key = pass("HALO-02", 10, 40);
+        let booked = [KeyPass {
+            satellite_id: key.satellite_id,
+            aos: key.aos,
+            los: key.los,
+            max_elevation_deg: 42.0,
+            predicted_margin_db: 8.0,
+            expected_kbit: 800.0,
+        }];
+
+        let contacts = schedule(passes, &booked, 1);
+        let summary: Vec<(&str, ContactPurpose, Option<u8>)> = contacts
+            .iter()
+            .map(|c| (c.satellite_id.as_str(), c.purpose, c.terminal))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("HALO-01", ContactPurpose::Data, None),
+                ("HALO-02", ContactPurpose::KeyTransfer, Some(1)),
+                ("HALO-03", ContactPurpose::Data, None),
+            ]
+        );
+        assert_eq!(contacts[1].expected_kbit, Some(800.0));
+
+        // A second terminal takes both data passes, which do not overlap
+        let contacts = schedule(
+            vec![pass("HALO-01", 0, 30), pass("HALO-02", 10, 40), pass("HALO-03", 35, 60)],
+            &booked,
+            2,
+        );
+        let terminals: Vec<Option<u8>> = contacts.iter().map(|c| c.terminal).collect();
+        assert_eq!(terminals, [Some(2), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_ical_escapes_and_folds() {
+        let contacts = schedule(vec![pass("HALO-01", 0, 30)], &[], 4);
+        let schedule = ContactSchedule {
+            station_id: "GS-001".to_string(),
+            station_name: "Vandenberg, CA; west coast site with a deliberately long name".to_string(),
+            from: contacts[0].aos,
+            to: contacts[0].los,
+            terminals: 4,
+            unassigned: 0,
+            contacts,
+        };
+        let ical = schedule.to_ical(schedule.from);
+
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        assert!(ical.contains("DTSTART:20260101T000000Z\r\n"));
+        assert!(ical.contains("DTEND:20260101T003000Z\r\n"));
+        assert!(ical.contains("SUMMARY:Data HALO-01 (terminal 1)\r\n"));
+        assert!(ical.contains("Vandenberg\\, CA\\; west"));
+        assert!(ical.split("\r\n").all(|line| line.len() <= ICAL_LINE_OCTETS));
+
+        // Unfolding restores the description's escaped newlines
+        let unfolded = ical.replace("\r\n ", "");
+        assert!(unfolded.contains("\\nMax elevation 42.0 deg\\n"));
+    }
+}
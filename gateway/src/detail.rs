@@ -0,0 +1,145 @@
+//! Per-satellite detail
+//!
+//! `GET /satellites/:id` gathers what otherwise takes several calls:
+//! osculating elements of the current SGP4 state alongside the TLE mean
+//! elements, station-keeping status, eclipse state, upcoming passes over
+//! the top-tier ground stations and the satellite's active links in the
+//! live topology.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use ground_stations::GroundStation;
+use orbital_mechanics::eclipse;
+use orbital_mechanics::stationkeeping::MeanElements;
+use orbital_mechanics::{transforms, OsculatingElements};
+
+use crate::passes::{predict_satellite_passes, PredictedPass};
+use crate::propagation::MIN_LINK_ELEVATION_DEG;
+use crate::routes::{find_satellite, propagate_geodetic, satellite_info, Position, SatelliteInfo};
+use crate::stationkeeping::KeepingStatus;
+use crate::topology::{self, LinkSnapshot};
+use crate::AppState;
+
+const DEFAULT_PASSES: usize = 5;
+const MAX_PASSES: usize = 50;
+const DEFAULT_TOP_STATIONS: usize = 5;
+const MAX_TOP_STATIONS: usize = 20;
+const PASS_HORIZON_HOURS: i64 = 24;
+
+#[derive(Deserialize)]
+pub struct DetailQuery {
+    pub at: Option<DateTime<Utc>>,
+    /// Upcoming passes to return (default 5)
+    pub passes: Option<usize>,
+    /// How many top-tier stations to predict passes over (default 5)
+    pub stations: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct EclipseState {
+    /// Visible fraction of the solar disc (1 sunlit, 0 umbra)
+    pub illumination: f64,
+    pub in_shadow: bool,
+    pub shadow: eclipse::Shadow,
+}
+
+#[derive(Serialize)]
+pub struct SatelliteDetail {
+    #[serde(flatten)]
+    pub info: SatelliteInfo,
+    pub epoch: DateTime<Utc>,
+    pub position: Position,
+    pub osculating_elements: OsculatingElements,
+    pub mean_elements: MeanElements,
+    /// `None` until the satellite's first station-keeping step
+    pub station_keeping: Option<KeepingStatus>,
+    pub eclipse: EclipseState,
+    /// Stations the passes were predicted over
+    pub pass_stations: Vec<String>,
+    /// Next passes over `pass_stations`, earliest first
+    pub passes: Vec<PredictedPass>,
+    pub active_links: Vec<LinkSnapshot>,
+}
+
+/// Operational stations with the most FSO throughput, weather breaking ties
+fn top_tier_stations(stations: impl Iterator<Item = GroundStation>, n: usize) -> Vec<GroundStation> {
+    let weighted = |s: &GroundStation| {
+        s.capabilities.max_throughput_gbps * s.weather.as_ref().map(|w| w.beam_quality_score).unwrap_or(1.0)
+    };
+    let mut stations: Vec<GroundStation> = stations.collect();
+    stations.sort_by(|a, b| {
+        b.capabilities
+            .max_throughput_gbps
+            .total_cmp(&a.capabilities.max_throughput_gbps)
+            .then(weighted(b).total_cmp(&weighted(a)))
+            .then(a.id.cmp(&b.id))
+    });
+    stations.truncate(n);
+    stations
+}
+
+/// GET /satellites/:id?passes=5&stations=5
+pub async fn get_satellite(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<DetailQuery>,
+) -> Result<Json<SatelliteDetail>, (StatusCode, String)> {
+    let now = q.at.unwrap_or_else(|| state.clock.now());
+    let sat = find_satellite(&state, &id)?;
+
+    let (sv, geo) = propagate_geodetic(&sat, now)?;
+    let osculating_elements =
+        transforms::elements_from_state(&sv).map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+    let mean_elements = MeanElements::from_satellite(&sat)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+    let illumination = eclipse::state_illumination(&sv);
+
+    let stations = top_tier_stations(
+        state.constellation.load().operational_stations().cloned(),
+        q.stations.unwrap_or(DEFAULT_TOP_STATIONS).min(MAX_TOP_STATIONS),
+    );
+    let horizon = now + Duration::hours(PASS_HORIZON_HOURS);
+    let acquisition = &state.config.stations.acquisition;
+    let mut passes: Vec<PredictedPass> = stations
+        .iter()
+        .flat_map(|station| predict_satellite_passes(acquisition, &sat, station, now, horizon, MIN_LINK_ELEVATION_DEG))
+        .collect();
+    passes.sort_by_key(|p| p.aos);
+    passes.truncate(q.passes.unwrap_or(DEFAULT_PASSES).min(MAX_PASSES));
+
+    let active_links = topology::snapshot(&state, now)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .links
+        .into_iter()
+        .filter(|l| l.active && (l.source == id || l.target == id))
+        .collect();
+
+    Ok(Json(SatelliteDetail {
+        info: satellite_info(&sat),
+        epoch: now,
+        position: Position {
+            latitude: geo.latitude,
+            longitude: geo.longitude,
+            altitude_km: geo.altitude_km,
+            velocity_km_s: (sv.velocity_x.powi(2) + sv.velocity_y.powi(2) + sv.velocity_z.powi(2)).sqrt(),
+            timestamp: now.to_rfc3339(),
+        },
+        osculating_elements,
+        mean_elements,
+        station_keeping: state.station_keeping.status_of(&id, now, state.config.station_keeping.annual_budget_m_s),
+        eclipse: EclipseState {
+            illumination,
+            in_shadow: illumination < 1.0,
+            shadow: eclipse::Shadow::from_illumination(illumination),
+        },
+        pass_stations: stations.into_iter().map(|s| s.id).collect(),
+        passes,
+        active_links,
+    }))
+}
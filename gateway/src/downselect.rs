@@ -0,0 +1,421 @@
+//! Persisted downselect runs
+//!
+//! Every `POST /strategic-stations/downselect` is stored with a monotonic
+//! version, the normalised weights, a hash of the candidate station set
+//! and the full ranked evaluations, so selection decisions can be audited
+//! and weight experiments compared with `GET .../runs/:a/diff/:b`.
+//! Runs share the history database but are never pruned.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use ground_station_wasm::{
+    downselect::{Downselect, DownselectSummary, ScoringWeights, StationEvaluation},
+    stations::NetworkStation,
+};
+use sx9_tcache::murmur3_128;
+
+use crate::auth::Principal;
+use crate::memory::MemoryKind;
+use crate::AppState;
+
+const RUNS_TREE: &str = "downselect_runs";
+const DEFAULT_TOP_N: usize = 10;
+const DEFAULT_LIST_LIMIT: usize = 50;
+
+/// A stored downselect run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownselectRun {
+    pub id: String,
+    pub version: u64,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<String>,
+    /// Normalised weights actually applied
+    pub weights: ScoringWeights,
+    /// Size of the selected set used for diffs
+    pub top_n: usize,
+    /// murmur3-128 of the candidate stations as JSON
+    pub inputs_hash: String,
+    pub evaluations: Vec<StationEvaluation>,
+}
+
+impl DownselectRun {
+    pub fn summary(&self) -> DownselectSummary {
+        Downselect {
+            weights: self.weights.clone(),
+            evaluations: self.evaluations.clone(),
+        }
+        .summary()
+    }
+
+    fn selected(&self) -> HashSet<&str> {
+        self.evaluations
+            .iter()
+            .take(self.top_n)
+            .map(|e| e.station_id.as_str())
+            .collect()
+    }
+}
+
+pub fn inputs_hash(stations: &[NetworkStation]) -> String {
+    let bytes = serde_json::to_vec(stations).unwrap_or_default();
+    hex::encode(murmur3_128(&bytes, 0))
+}
+
+/// sled-backed run log keyed by big-endian version
+pub struct DownselectStore {
+    db: sled::Db,
+    runs: sled::Tree,
+}
+
+impl DownselectStore {
+    pub fn open(db: sled::Db) -> anyhow::Result<Self> {
+        Ok(Self {
+            runs: db.open_tree(RUNS_TREE)?,
+            db,
+        })
+    }
+
+    /// Assign an ID/version and persist
+    pub fn record(
+        &self,
+        weights: ScoringWeights,
+        top_n: usize,
+        stations: &[NetworkStation],
+        evaluations: Vec<StationEvaluation>,
+        created_by: Option<String>,
+    ) -> anyhow::Result<DownselectRun> {
+        let version = self.db.generate_id()? + 1;
+        let run = DownselectRun {
+            id: format!("ds-{:06}", version),
+            version,
+            created_at: Utc::now(),
+            created_by,
+            weights,
+            top_n,
+            inputs_hash: inputs_hash(stations),
+            evaluations,
+        };
+        self.runs.insert(version.to_be_bytes(), serde_json::to_vec(&run)?)?;
+        self.runs.flush()?;
+        Ok(run)
+    }
+
+    pub fn get(&self, id: &str) -> anyhow::Result<Option<DownselectRun>> {
+        let Some(version) = parse_run_id(id) else {
+            return Ok(None);
+        };
+        self.runs
+            .get(version.to_be_bytes())?
+            .map(|v| Ok(serde_json::from_slice(&v)?))
+            .transpose()
+    }
+
+    /// Newest first
+    pub fn list(&self, limit: usize) -> anyhow::Result<Vec<DownselectRun>> {
+        self.runs
+            .iter()
+            .rev()
+            .values()
+            .take(limit)
+            .map(|v| Ok(serde_json::from_slice(&v?)?))
+            .collect()
+    }
+}
+
+/// Accepts `ds-000042` or `42`
+fn parse_run_id(id: &str) -> Option<u64> {
+    id.strip_prefix("ds-").unwrap_or(id).parse().ok()
+}
+
+// ========== Diff ==========
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeightDelta {
+    pub atmospheric: f64,
+    pub infrastructure: f64,
+    pub geographic: f64,
+    pub operational: f64,
+    pub strategic: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StationChange {
+    pub station_id: String,
+    pub station_name: String,
+    pub rank_from: Option<usize>,
+    pub rank_to: Option<usize>,
+    pub score_from: Option<f64>,
+    pub score_to: Option<f64>,
+    /// Positive = moved up
+    pub rank_change: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunDiff {
+    pub from: String,
+    pub to: String,
+    pub inputs_changed: bool,
+    pub weights: WeightDelta,
+    pub mean_score_delta: f64,
+    /// Entered the `to` run's top-N
+    pub selected_added: Vec<String>,
+    /// Dropped out of the `from` run's top-N
+    pub selected_removed: Vec<String>,
+    /// Stations whose rank moved, largest moves first
+    pub changes: Vec<StationChange>,
+}
+
+pub fn diff_runs(from: &DownselectRun, to: &DownselectRun) -> RunDiff {
+    let before: HashMap<&str, &StationEvaluation> =
+        from.evaluations.iter().map(|e| (e.station_id.as_str(), e)).collect();
+    let after: HashMap<&str, &StationEvaluation> =
+        to.evaluations.iter().map(|e| (e.station_id.as_str(), e)).collect();
+
+    let mut ids: Vec<&str> = before.keys().chain(after.keys()).copied().collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let mut changes: Vec<StationChange> = ids
+        .into_iter()
+        .filter_map(|id| {
+            let (a, b) = (before.get(id), after.get(id));
+            let rank_change = match (a, b) {
+                (Some(a), Some(b)) if a.rank == b.rank => return None,
+                (Some(a), Some(b)) => Some(a.rank as i64 - b.rank as i64),
+                _ => None,
+            };
+            let name = a.or(b).map(|e| e.station_name.clone()).unwrap_or_default();
+            Some(StationChange {
+                station_id: id.to_string(),
+                station_name: name,
+                rank_from: a.map(|e| e.rank),
+                rank_to: b.map(|e| e.rank),
+                score_from: a.map(|e| e.final_score),
+                score_to: b.map(|e| e.final_score),
+                rank_change,
+            })
+        })
+        .collect();
+    changes.sort_by_key(|c| std::cmp::Reverse(c.rank_change.map(i64::abs).unwrap_or(i64::MAX)));
+
+    let (selected_from, selected_to) = (from.selected(), to.selected());
+    let mut selected_added: Vec<String> =
+        selected_to.difference(&selected_from).map(|s| s.to_string()).collect();
+    let mut selected_removed: Vec<String> =
+        selected_from.difference(&selected_to).map(|s| s.to_string()).collect();
+    selected_added.sort();
+    selected_removed.sort();
+
+    let mean = |run: &DownselectRun| {
+        let n = run.evaluations.len().max(1) as f64;
+        run.evaluations.iter().map(|e| e.final_score).sum::<f64>() / n
+    };
+
+    RunDiff {
+        from: from.id.clone(),
+        to: to.id.clone(),
+        inputs_changed: from.inputs_hash != to.inputs_hash,
+        weights: WeightDelta {
+            atmospheric: to.weights.atmospheric - from.weights.atmospheric,
+            infrastructure: to.weights.infrastructure - from.weights.infrastructure,
+            geographic: to.weights.geographic - from.weights.geographic,
+            operational: to.weights.operational - from.weights.operational,
+            strategic: to.weights.strategic - from.weights.strategic,
+        },
+        mean_score_delta: mean(to) - mean(from),
+        selected_added,
+        selected_removed,
+        changes,
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Deserialize)]
+pub struct DownselectRequest {
+    pub weights: Option<ScoringWeights>,
+    pub top_n: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct RunSummary {
+    pub id: String,
+    pub version: u64,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<String>,
+    pub weights: ScoringWeights,
+    pub top_n: usize,
+    pub inputs_hash: String,
+    #[serde(flatten)]
+    pub summary: DownselectSummary,
+}
+
+impl From<&DownselectRun> for RunSummary {
+    fn from(run: &DownselectRun) -> Self {
+        Self {
+            id: run.id.clone(),
+            version: run.version,
+            created_at: run.created_at,
+            created_by: run.created_by.clone(),
+            weights: run.weights.clone(),
+            top_n: run.top_n,
+            inputs_hash: run.inputs_hash.clone(),
+            summary: run.summary(),
+        }
+    }
+}
+
+fn internal(e: anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// POST /strategic-stations/downselect - evaluate and record a run
+pub async fn run_downselect(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Json(req): Json<DownselectRequest>,
+) -> Result<Json<RunSummary>, (StatusCode, String)> {
+    let stations = state.strategic_stations.as_ref();
+
+    let weights = req.weights.unwrap_or_default();
+    let mut ds = Downselect::new().with_weights(weights);
+    ds.evaluate(stations);
+
+    let run = state
+        .downselect_runs
+        .record(
+            ds.weights,
+            req.top_n.unwrap_or(DEFAULT_TOP_N),
+            stations,
+            ds.evaluations,
+            principal.map(|Extension(p)| p.subject),
+        )
+        .map_err(internal)?;
+    tracing::info!("Downselect run {} recorded ({} candidates)", run.id, run.evaluations.len());
+
+    let summary = RunSummary::from(&run);
+    let selected: Vec<String> = run.evaluations.iter().take(run.top_n).map(|e| e.station_id.clone()).collect();
+    state.memory.remember(
+        state.clock.now(),
+        MemoryKind::Downselect,
+        std::iter::once(run.id.clone()).chain(selected.iter().cloned()).collect(),
+        format!(
+            "Downselect run {}: top {} of {} candidates: {}",
+            run.id,
+            run.top_n,
+            run.evaluations.len(),
+            selected.join(", ")
+        ),
+        &summary,
+    );
+
+    Ok(Json(summary))
+}
+
+#[derive(Deserialize)]
+pub struct ListRunsQuery {
+    pub limit: Option<usize>,
+}
+
+/// GET /strategic-stations/downselect/runs
+pub async fn list_runs(
+    State(state): State<AppState>,
+    Query(q): Query<ListRunsQuery>,
+) -> Result<Json<Vec<RunSummary>>, (StatusCode, String)> {
+    let runs = state
+        .downselect_runs
+        .list(q.limit.unwrap_or(DEFAULT_LIST_LIMIT))
+        .map_err(internal)?;
+    Ok(Json(runs.iter().map(RunSummary::from).collect()))
+}
+
+fn load_run(state: &AppState, id: &str) -> Result<DownselectRun, (StatusCode, String)> {
+    state
+        .downselect_runs
+        .get(id)
+        .map_err(internal)?
+        .ok_or((StatusCode::NOT_FOUND, format!("Downselect run not found: {}", id)))
+}
+
+/// GET /strategic-stations/downselect/runs/:id
+pub async fn get_run(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<DownselectRun>, (StatusCode, String)> {
+    load_run(&state, &id).map(Json)
+}
+
+/// GET /strategic-stations/downselect/runs/:from/diff/:to
+pub async fn diff(
+    State(state): State<AppState>,
+    Path((from, to)): Path<(String, String)>,
+) -> Result<Json<RunDiff>, (StatusCode, String)> {
+    let from = load_run(&state, &from)?;
+    let to = load_run(&state, &to)?;
+    Ok(Json(diff_runs(&from, &to)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ground_station_wasm::stations::load_strategic_stations;
+
+    fn run(version: u64, weights: ScoringWeights) -> DownselectRun {
+        let stations = load_strategic_stations();
+        let mut ds = Downselect::new().with_weights(weights);
+        ds.evaluate(&stations);
+        DownselectRun {
+            id: format!("ds-{:06}", version),
+            version,
+            created_at: Utc::now(),
+            created_by: None,
+            weights: ds.weights,
+            top_n: 10,
+            inputs_hash: inputs_hash(&stations),
+            evaluations: ds.evaluations,
+        }
+    }
+
+    #[test]
+    fn test_identical_runs_have_empty_diff() {
+        let a = run(1, ScoringWeights::default());
+        let b = run(2, ScoringWeights::default());
+        let d = diff_runs(&a, &b);
+        assert!(!d.inputs_changed);
+        assert!(d.changes.is_empty());
+        assert!(d.selected_added.is_empty() && d.selected_removed.is_empty());
+    }
+
+    #[test]
+    fn test_weight_change_reorders() {
+        let a = run(1, ScoringWeights::default());
+        let b = run(
+            2,
+            ScoringWeights {
+                atmospheric: 1.0,
+                infrastructure: 0.0,
+                geographic: 0.0,
+                operational: 0.0,
+                strategic: 0.0,
+            },
+        );
+        let d = diff_runs(&a, &b);
+        assert!(d.weights.atmospheric > 0.0);
+        assert!(!d.changes.is_empty());
+        assert_eq!(d.selected_added.len(), d.selected_removed.len());
+    }
+
+    #[test]
+    fn test_parse_run_id() {
+        assert_eq!(parse_run_id("ds-000042"), Some(42));
+        assert_eq!(parse_run_id("7"), Some(7));
+        assert_eq!(parse_run_id("nope"), None);
+    }
+}
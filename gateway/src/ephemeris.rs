@@ -0,0 +1,161 @@
+//! Interpolated ephemerides for position queries
+//!
+//! `GET /satellites/:id/position`, `/ground-track` and `/visibility` read
+//! satellite state from a per-satellite `EphemerisCache`: SGP4 runs once
+//! per node (`propagation.ephemeris_node_secs` apart) and queries between
+//! nodes are Hermite-interpolated, so a UI polling at frame rate costs no
+//! more propagation than one polling once a minute. A cache is rebuilt
+//! when its satellite's elements change (TLE upload, station-keeping
+//! burn). The background tick and pass prediction stay on exact SGP4.
+//!
+//! `GET /satellites/:id/ephemeris` returns the nodes themselves (ECI state
+//! with velocity) so clients such as the WASM twin can interpolate
+//! locally between fetches.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use orbital_mechanics::interpolation::EphemerisCache;
+use orbital_mechanics::{GeodeticPosition, Satellite, StateVector};
+
+use crate::routes::find_satellite;
+use crate::AppState;
+
+/// Longest span of nodes one request can ask for
+const MAX_EPHEMERIS_MINUTES: i64 = 24 * 60;
+
+pub struct EphemerisCaches {
+    step: Duration,
+    max_nodes: usize,
+    caches: Mutex<HashMap<String, EphemerisCache>>,
+}
+
+impl EphemerisCaches {
+    pub fn new(step_secs: u32, max_nodes: usize) -> Self {
+        Self {
+            step: Duration::seconds(step_secs as i64),
+            max_nodes,
+            caches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn with_cache<T>(&self, sat: &Satellite, f: impl FnOnce(&mut EphemerisCache) -> T) -> T {
+        let mut caches = self.caches.lock().unwrap();
+        let cache = caches
+            .entry(sat.id.clone())
+            .or_insert_with(|| EphemerisCache::new(sat, self.step, self.max_nodes));
+        if !cache.matches(sat) {
+            *cache = EphemerisCache::new(sat, self.step, self.max_nodes);
+        }
+        f(cache)
+    }
+
+    pub fn state_at(&self, sat: &Satellite, time: DateTime<Utc>) -> orbital_mechanics::Result<StateVector> {
+        self.with_cache(sat, |cache| cache.state_at(time))
+    }
+
+    pub fn nodes(
+        &self,
+        sat: &Satellite,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> orbital_mechanics::Result<Vec<StateVector>> {
+        self.with_cache(sat, |cache| cache.nodes_between(from, to))
+    }
+}
+
+/// Interpolated counterpart of `routes::propagate_geodetic`
+pub fn state_geodetic(
+    state: &AppState,
+    sat: &Satellite,
+    time: DateTime<Utc>,
+) -> Result<(StateVector, GeodeticPosition), (StatusCode, String)> {
+    let sv = state
+        .ephemeris
+        .state_at(sat, time)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+    let geo = sv.to_geodetic().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok((sv, geo))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EphemerisQuery {
+    pub at: Option<DateTime<Utc>>,
+    /// Span after `at` (default 60)
+    pub minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EphemerisNode {
+    pub timestamp: DateTime<Utc>,
+    pub position_eci_km: [f64; 3],
+    pub velocity_eci_km_s: [f64; 3],
+}
+
+#[derive(Debug, Serialize)]
+pub struct EphemerisResponse {
+    pub satellite_id: String,
+    pub node_step_s: i64,
+    pub nodes: Vec<EphemerisNode>,
+}
+
+/// GET /satellites/:id/ephemeris?at=&minutes=
+pub async fn get_ephemeris(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<EphemerisQuery>,
+) -> Result<Json<EphemerisResponse>, (StatusCode, String)> {
+    let from = q.at.unwrap_or_else(|| state.clock.now());
+    let minutes = q.minutes.unwrap_or(60).clamp(1, MAX_EPHEMERIS_MINUTES);
+    let sat = find_satellite(&state, &id)?;
+
+    let nodes = state
+        .ephemeris
+        .nodes(&sat, from, from + Duration::minutes(minutes))
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+    Ok(Json(EphemerisResponse {
+        satellite_id: sat.id,
+        node_step_s: state.ephemeris.step.num_seconds(),
+        nodes: nodes
+            .into_iter()
+            .map(|sv| EphemerisNode {
+                timestamp: sv.epoch,
+                position_eci_km: [sv.position_x, sv.position_y, sv.position_z],
+                velocity_eci_km_s: [sv.velocity_x, sv.velocity_y, sv.velocity_z],
+            })
+            .collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use orbital_mechanics::walker::WalkerDelta;
+
+    #[test]
+    fn test_cache_follows_element_changes() {
+        let epoch = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let mut sats = WalkerDelta::halo_constellation().generate_satellites("HALO", 90001, epoch);
+        let caches = EphemerisCaches::new(60, 16);
+        let t = epoch + Duration::seconds(90);
+
+        let cached = caches.state_at(&sats[0], t).unwrap();
+        let exact = sats[0].propagate(t).unwrap();
+        assert!((cached.position_x - exact.position_x).abs() < 1e-4);
+
+        // Same ID, new elements: the old nodes must not answer
+        sats[0].tle_line1 = sats[1].tle_line1.clone();
+        sats[0].tle_line2 = sats[1].tle_line2.clone();
+        let moved = caches.state_at(&sats[0], t).unwrap();
+        assert!((moved.position_x - sats[1].propagate(t).unwrap().position_x).abs() < 1e-4);
+        assert_eq!(caches.nodes(&sats[0], epoch, t).unwrap().len(), 3);
+    }
+}
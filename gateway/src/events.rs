@@ -0,0 +1,483 @@
+//! Network event stream
+//!
+//! `GET /ws/topology` upgrades to a WebSocket pushing one JSON text frame
+//! per event as the network changes:
+//! - `graph_diff`: nodes and links that appeared or disappeared
+//! - `link_state`: a link going active or inactive (passes setting,
+//!   ISLs grazing the atmosphere, chaos faults)
+//! - `route_reselected`: a live route recently requested from
+//!   `POST /routing/optimal` now selects a different path, or none
+//! - `fault`: chaos faults injected or cleared, by operators or scenarios
+//!
+//! The topology is re-derived after every propagation tick and straight
+//! after each fault change, but only while someone is listening; the
+//! baseline is dropped when the last listener leaves, so the first diff
+//! after a quiet spell is not everything that changed meanwhile.
+//! `?types=link_state,fault` limits a stream to some event types. As with
+//! the position stream, a client that falls behind skips ahead.
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Notify;
+
+use orbital_glaf::objective::{ObjectiveFunction, RouteMetrics, SlaTier};
+
+use crate::chaos::Fault;
+use crate::routes::routing_graph;
+use crate::topology::{self, LinkSnapshot, TopologySnapshot};
+use crate::AppState;
+
+const EVENT_BROADCAST_CAPACITY: usize = 1024;
+/// Routes stay watched for this long after they were last requested
+const ROUTE_WATCH_TTL: Duration = Duration::from_secs(3600);
+/// Oldest watches are dropped beyond this many
+const MAX_WATCHED_ROUTES: usize = 64;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NetworkEvent {
+    GraphDiff {
+        epoch: DateTime<Utc>,
+        nodes_added: Vec<String>,
+        nodes_removed: Vec<String>,
+        links_added: Vec<String>,
+        links_removed: Vec<String>,
+    },
+    LinkState {
+        epoch: DateTime<Utc>,
+        link: String,
+        source: String,
+        target: String,
+        active: bool,
+        margin_db: f64,
+    },
+    RouteReselected {
+        epoch: DateTime<Utc>,
+        source: String,
+        destination: String,
+        sla_tier: SlaTier,
+        /// `None` when the route had no feasible path
+        previous: Option<Vec<String>>,
+        /// `None` when no candidate meets the SLA any more
+        selected: Option<Vec<String>>,
+    },
+    Fault {
+        /// `injected` or `cleared`
+        event: &'static str,
+        fault: Fault,
+    },
+}
+
+impl NetworkEvent {
+    /// The `type` tag, as accepted by `?types=`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NetworkEvent::GraphDiff { .. } => "graph_diff",
+            NetworkEvent::LinkState { .. } => "link_state",
+            NetworkEvent::RouteReselected { .. } => "route_reselected",
+            NetworkEvent::Fault { .. } => "fault",
+        }
+    }
+}
+
+/// A live route re-evaluated on every topology change
+#[derive(Debug, Clone)]
+pub struct WatchedRoute {
+    pub source: String,
+    pub destination: String,
+    pub sla_tier: SlaTier,
+    pub k: usize,
+    pub terrestrial: bool,
+    /// Currently selected path
+    pub path: Option<Vec<String>>,
+}
+
+impl WatchedRoute {
+    fn same_route(&self, other: &WatchedRoute) -> bool {
+        self.source == other.source
+            && self.destination == other.destination
+            && self.sla_tier == other.sla_tier
+            && self.terrestrial == other.terrestrial
+    }
+}
+
+pub struct NetworkEvents {
+    events: broadcast::Sender<Arc<NetworkEvent>>,
+    /// Topology the next diff is taken against
+    baseline: Mutex<Option<TopologySnapshot>>,
+    routes: Mutex<Vec<(WatchedRoute, Instant)>>,
+    /// Sim time of a requested topology refresh
+    pending: Mutex<Option<DateTime<Utc>>>,
+    changed: Notify,
+}
+
+impl Default for NetworkEvents {
+    fn default() -> Self {
+        Self {
+            events: broadcast::channel(EVENT_BROADCAST_CAPACITY).0,
+            baseline: Mutex::new(None),
+            routes: Mutex::new(Vec::new()),
+            pending: Mutex::new(None),
+            changed: Notify::new(),
+        }
+    }
+}
+
+impl NetworkEvents {
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<NetworkEvent>> {
+        self.events.subscribe()
+    }
+
+    fn send(&self, event: NetworkEvent) {
+        if self.events.receiver_count() > 0 {
+            let _ = self.events.send(Arc::new(event));
+        }
+    }
+
+    /// Re-derive the topology at sim time `epoch` and push what changed
+    pub fn refresh(&self, epoch: DateTime<Utc>) {
+        *self.pending.lock().unwrap() = Some(epoch);
+        self.changed.notify_one();
+    }
+
+    /// Announce a chaos fault change and refresh the topology it affects
+    pub fn fault(&self, event: &'static str, fault: &Fault, now: DateTime<Utc>) {
+        self.send(NetworkEvent::Fault {
+            event,
+            fault: fault.clone(),
+        });
+        self.refresh(now);
+    }
+
+    /// Watch `route` for re-selection, replacing an earlier watch of the
+    /// same endpoints and tier
+    pub fn watch_route(&self, route: WatchedRoute) {
+        let mut routes = self.routes.lock().unwrap();
+        routes.retain(|(r, _)| !r.same_route(&route));
+        routes.push((route, Instant::now()));
+        let excess = routes.len().saturating_sub(MAX_WATCHED_ROUTES);
+        routes.drain(..excess);
+    }
+}
+
+/// Nodes and links that appeared or disappeared between two topologies,
+/// and the links whose active state flipped
+pub fn diff(before: &TopologySnapshot, after: &TopologySnapshot) -> Vec<NetworkEvent> {
+    let ids = |snapshot: &TopologySnapshot| -> HashSet<String> {
+        snapshot.nodes.iter().map(|n| n.id.clone()).collect()
+    };
+    let (nodes_before, nodes_after) = (ids(before), ids(after));
+    let links_before: HashMap<&str, &LinkSnapshot> = before.links.iter().map(|l| (l.id.as_str(), l)).collect();
+    let links_after: HashMap<&str, &LinkSnapshot> = after.links.iter().map(|l| (l.id.as_str(), l)).collect();
+    let sorted = |mut ids: Vec<String>| {
+        ids.sort();
+        ids
+    };
+
+    let nodes_added = sorted(nodes_after.difference(&nodes_before).cloned().collect());
+    let nodes_removed = sorted(nodes_before.difference(&nodes_after).cloned().collect());
+    let links_added = sorted(
+        links_after
+            .keys()
+            .filter(|id| !links_before.contains_key(*id))
+            .map(|id| id.to_string())
+            .collect(),
+    );
+    let links_removed = sorted(
+        links_before
+            .keys()
+            .filter(|id| !links_after.contains_key(*id))
+            .map(|id| id.to_string())
+            .collect(),
+    );
+
+    let mut events = Vec::new();
+    if !(nodes_added.is_empty() && nodes_removed.is_empty() && links_added.is_empty() && links_removed.is_empty()) {
+        events.push(NetworkEvent::GraphDiff {
+            epoch: after.epoch,
+            nodes_added,
+            nodes_removed,
+            links_added,
+            links_removed,
+        });
+    }
+    // A link's first appearance counts as a change when it comes up active
+    events.extend(
+        after
+            .links
+            .iter()
+            .filter(|l| links_before.get(l.id.as_str()).map_or(l.active, |b| b.active != l.active))
+            .map(|l| NetworkEvent::LinkState {
+                epoch: after.epoch,
+                link: l.id.clone(),
+                source: l.source.clone(),
+                target: l.target.clone(),
+                active: l.active,
+                margin_db: l.margin_db,
+            }),
+    );
+    events
+}
+
+/// Re-evaluate the watched routes on `snapshot`, returning re-selections
+fn reselect(state: &AppState, snapshot: &TopologySnapshot) -> Vec<NetworkEvent> {
+    let mut routes = state.events.routes.lock().unwrap();
+    routes.retain(|(_, requested)| requested.elapsed() < ROUTE_WATCH_TTL);
+    if routes.is_empty() {
+        return Vec::new();
+    }
+
+    let mut graphs = HashMap::new();
+    let mut events = Vec::new();
+    for (route, _) in routes.iter_mut() {
+        let terrestrial = route.terrestrial;
+        let graph = graphs
+            .entry(terrestrial)
+            .or_insert_with(|| routing_graph(state, snapshot, terrestrial));
+        let candidates: Vec<RouteMetrics> = graph
+            .k_shortest_paths(&route.source, &route.destination, route.k)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|path| RouteMetrics::from_path(graph, path))
+            .collect();
+        let selected = ObjectiveFunction::for_tier(route.sla_tier)
+            .select_optimal(&candidates)
+            .map(|r| r.metrics.path);
+        if selected != route.path {
+            events.push(NetworkEvent::RouteReselected {
+                epoch: snapshot.epoch,
+                source: route.source.clone(),
+                destination: route.destination.clone(),
+                sla_tier: route.sla_tier,
+                previous: std::mem::replace(&mut route.path, selected.clone()),
+                selected,
+            });
+        }
+    }
+    events
+}
+
+/// Derive network events for as long as the gateway runs
+pub async fn run(state: AppState) {
+    loop {
+        state.events.changed.notified().await;
+        let Some(epoch) = state.events.pending.lock().unwrap().take() else {
+            continue;
+        };
+        if state.events.events.receiver_count() == 0 {
+            *state.events.baseline.lock().unwrap() = None;
+            continue;
+        }
+
+        // Topology and route searches are CPU-bound; keep them off the
+        // async workers
+        let derived = {
+            let state = state.clone();
+            tokio::task::spawn_blocking(move || {
+                let snapshot = topology::snapshot(&state, epoch)?;
+                let previous = state.events.baseline.lock().unwrap().replace(snapshot.clone());
+                let mut events = previous.map(|p| diff(&p, &snapshot)).unwrap_or_default();
+                events.extend(reselect(&state, &snapshot));
+                Ok::<_, anyhow::Error>(events)
+            })
+            .await
+        };
+        let events = match derived {
+            Ok(Ok(events)) => events,
+            Ok(Err(e)) => {
+                tracing::warn!("Network event topology failed: {}", e);
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Network event derivation panicked: {}", e);
+                continue;
+            }
+        };
+        for event in events {
+            state.events.send(event);
+        }
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQuery {
+    /// Comma-separated event types; all when absent
+    pub types: Option<String>,
+}
+
+/// GET /ws/topology?types= (WebSocket)
+pub async fn topology_stream(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(q): Query<EventStreamQuery>,
+) -> Response {
+    let types: Vec<String> = q
+        .types
+        .iter()
+        .flat_map(|s| s.split(','))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    ws.on_upgrade(move |socket| stream_events(socket, state, types))
+}
+
+async fn stream_events(mut socket: WebSocket, state: AppState, types: Vec<String>) {
+    let mut events = state.events.subscribe();
+    state.events.refresh(state.clock.now());
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if !types.is_empty() && !types.iter().any(|t| t == event.kind()) {
+                        continue;
+                    }
+                    let frame = match serde_json::to_string(&*event) {
+                        Ok(text) => Message::Text(text),
+                        Err(e) => {
+                            tracing::warn!("Network event encode failed: {}", e);
+                            return;
+                        }
+                    };
+                    if socket.send(frame).await.is_err() {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => tracing::debug!("Network event stream skipped {} events", skipped),
+                Err(RecvError::Closed) => return,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                // Pings are answered by axum; anything else is ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::{LinkKind, NodeKind, NodeSnapshot};
+
+    fn node(id: &str) -> NodeSnapshot {
+        NodeSnapshot {
+            id: id.to_string(),
+            name: id.to_string(),
+            kind: NodeKind::Satellite,
+            constellation: "HALO".to_string(),
+            latitude: 0.0,
+            longitude: 0.0,
+            altitude_km: 10_500.0,
+            plane: 1,
+            weather_score: 1.0,
+        }
+    }
+
+    fn link(source: &str, target: &str, active: bool) -> LinkSnapshot {
+        LinkSnapshot {
+            id: format!("ISL-{}-{}", source, target),
+            source: source.to_string(),
+            target: target.to_string(),
+            kind: LinkKind::InterSatellite,
+            range_km: 8_000.0,
+            latency_ms: 26.7,
+            margin_db: 12.0,
+            weather_score: 1.0,
+            elevation_deg: None,
+            capacity_gbps: 10.0,
+            utilization: 0.0,
+            active,
+        }
+    }
+
+    fn snapshot(nodes: &[&str], links: Vec<LinkSnapshot>) -> TopologySnapshot {
+        TopologySnapshot {
+            epoch: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap(),
+            nodes: nodes.iter().map(|id| node(id)).collect(),
+            links,
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_graph_and_link_changes() {
+        let before = snapshot(&["A", "B", "C"], vec![link("A", "B", true), link("B", "C", true)]);
+        let after = snapshot(
+            &["A", "B", "D"],
+            vec![link("A", "B", false), link("B", "D", true), link("A", "D", false)],
+        );
+
+        let events = diff(&before, &after);
+        let NetworkEvent::GraphDiff {
+            nodes_added,
+            nodes_removed,
+            links_added,
+            links_removed,
+            ..
+        } = &events[0]
+        else {
+            panic!("expected a graph diff first");
+        };
+        assert_eq!(nodes_added, &["D"]);
+        assert_eq!(nodes_removed, &["C"]);
+        assert_eq!(links_added, &["ISL-A-D", "ISL-B-D"]);
+        assert_eq!(links_removed, &["ISL-B-C"]);
+
+        // A-B went down, B-D came up; A-D appeared inactive and is no change
+        let flips: Vec<(&str, bool)> = events[1..]
+            .iter()
+            .map(|e| match e {
+                NetworkEvent::LinkState { link, active, .. } => (link.as_str(), *active),
+                _ => panic!("expected link state events"),
+            })
+            .collect();
+        assert_eq!(flips, [("ISL-A-B", false), ("ISL-B-D", true)]);
+
+        assert!(diff(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_event_type_tag_matches_kind() {
+        let before = snapshot(&["A", "B"], vec![link("A", "B", true)]);
+        let after = snapshot(&["A"], vec![]);
+        for event in diff(&before, &after) {
+            let json = serde_json::to_value(&event).unwrap();
+            assert_eq!(json["type"], event.kind());
+        }
+    }
+
+    #[test]
+    fn test_watch_replaces_and_caps() {
+        let events = NetworkEvents::default();
+        let route = |source: &str| WatchedRoute {
+            source: source.to_string(),
+            destination: "GS-002".to_string(),
+            sla_tier: SlaTier::Gold,
+            k: 5,
+            terrestrial: true,
+            path: None,
+        };
+        events.watch_route(route("GS-001"));
+        events.watch_route(route("GS-001"));
+        assert_eq!(events.routes.lock().unwrap().len(), 1);
+
+        for i in 0..MAX_WATCHED_ROUTES + 3 {
+            events.watch_route(route(&format!("GS-{:03}", i + 10)));
+        }
+        let routes = events.routes.lock().unwrap();
+        assert_eq!(routes.len(), MAX_WATCHED_ROUTES);
+        assert_eq!(routes.last().unwrap().0.source, format!("GS-{:03}", MAX_WATCHED_ROUTES + 12));
+    }
+}
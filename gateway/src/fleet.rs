@@ -0,0 +1,317 @@
+//! Ground-station telemetry aggregation
+//!
+//! Subscribes to `orbital.gs.*.telemetry` and keeps the latest report and
+//! a short ring of recent reports per station from the docker simulator
+//! fleet, decoded by `Content-Type` like replay. The gateway's own
+//! per-tick station records share those subjects; they carry
+//! `Sx9-Origin: gateway` and are skipped, so a station that goes quiet
+//! turns stale however often the gateway publishes for it.
+//! `GET /stations/telemetry` joins the latest reports onto the station
+//! registry and flags stations that have gone quiet; the health report
+//! carries the same staleness as an optional check.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::codec::Codec;
+use crate::history::StationTelemetryRecord;
+use crate::routes::station_status_str;
+use crate::schema::{self, pb};
+use crate::telemetry::payload_codec;
+use crate::AppState;
+
+const SUBJECT: &str = "orbital.gs.*.telemetry";
+/// Reports kept per station
+const HISTORY_LEN: usize = 256;
+/// A station with no report for this long is stale
+const STALE_AFTER_S: i64 = 120;
+/// Wait before resubscribing after the subscription ends
+const RESUBSCRIBE_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceivedTelemetry {
+    #[serde(flatten)]
+    pub record: StationTelemetryRecord,
+    pub subject: String,
+    /// Gateway wall-clock time the report arrived
+    pub received_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct FleetTelemetry {
+    stations: Mutex<HashMap<String, VecDeque<ReceivedTelemetry>>>,
+}
+
+impl FleetTelemetry {
+    pub fn record(&self, report: ReceivedTelemetry) {
+        let mut stations = self.stations.lock().unwrap();
+        let history = stations.entry(report.record.station_id.clone()).or_default();
+        if history.len() == HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(report);
+    }
+
+    pub fn latest(&self, station_id: &str) -> Option<ReceivedTelemetry> {
+        self.stations
+            .lock()
+            .unwrap()
+            .get(station_id)
+            .and_then(|h| h.back().cloned())
+    }
+
+    /// Recent reports for one station, newest first
+    pub fn history(&self, station_id: &str, limit: usize) -> Vec<ReceivedTelemetry> {
+        self.stations
+            .lock()
+            .unwrap()
+            .get(station_id)
+            .map(|h| h.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Record one message from `subject`, unless the gateway published
+    /// it. Returns whether it was recorded.
+    pub fn ingest(
+        &self,
+        subject: &str,
+        headers: Option<&async_nats::HeaderMap>,
+        payload: &[u8],
+        received_at: DateTime<Utc>,
+    ) -> bool {
+        let origin = headers.and_then(|h| h.get(schema::ORIGIN_HEADER));
+        if origin.is_some_and(|o| o.as_str() == schema::GATEWAY_ORIGIN) {
+            return false;
+        }
+        match decode(subject, headers, payload) {
+            Some(record) => {
+                self.record(ReceivedTelemetry {
+                    record,
+                    subject: subject.to_string(),
+                    received_at,
+                });
+                true
+            }
+            None => {
+                tracing::debug!("Undecodable station telemetry on {}", subject);
+                false
+            }
+        }
+    }
+
+    /// IDs of every station that has reported
+    pub fn reporting(&self) -> Vec<String> {
+        self.stations.lock().unwrap().keys().cloned().collect()
+    }
+}
+
+/// Whether a report received at `received_at` is stale at `now`
+pub fn is_stale(received_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    now - received_at > Duration::seconds(STALE_AFTER_S)
+}
+
+/// Station record from a telemetry payload; a record without a station ID
+/// takes the one in its subject
+fn decode(subject: &str, headers: Option<&async_nats::HeaderMap>, payload: &[u8]) -> Option<StationTelemetryRecord> {
+    let mut record: StationTelemetryRecord = match payload_codec(headers) {
+        Codec::Protobuf => pb::StationTelemetry::decode(payload).ok()?.into(),
+        codec => codec.decode(payload).ok()?,
+    };
+    if record.station_id.is_empty() {
+        record.station_id = subject.split('.').nth(2)?.to_string();
+    }
+    Some(record)
+}
+
+/// Consume station telemetry for the lifetime of the gateway
+pub async fn run(state: AppState) {
+    let Some(nats) = state.telemetry.clone() else {
+        return;
+    };
+    loop {
+        match nats.client().subscribe(SUBJECT.to_string()).await {
+            Ok(mut subscriber) => {
+                while let Some(message) = subscriber.next().await {
+                    let subject = message.subject.to_string();
+                    state
+                        .fleet
+                        .ingest(&subject, message.headers.as_ref(), &message.payload, Utc::now());
+                }
+                tracing::warn!("Station telemetry subscription ended, resubscribing");
+            }
+            Err(e) => tracing::warn!("Station telemetry subscribe failed: {}", e),
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Serialize)]
+pub struct StationTelemetryStatus {
+    pub station_id: String,
+    /// `None` for stations reporting without a registry entry
+    pub name: Option<String>,
+    pub registry_status: Option<String>,
+    pub latest: Option<ReceivedTelemetry>,
+    /// No report yet, or none within the staleness window
+    pub stale: bool,
+}
+
+#[derive(Deserialize)]
+pub struct FleetHistoryQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct FleetHistoryResponse {
+    pub station_id: String,
+    pub reports: Vec<ReceivedTelemetry>,
+}
+
+/// Registry stations joined with their latest reports, plus unregistered
+/// stations that have reported
+pub fn fleet_status(state: &AppState, now: DateTime<Utc>) -> Vec<StationTelemetryStatus> {
+    let constellation = state.constellation.load();
+    let status = |station_id: String, name, registry_status| {
+        let latest = state.fleet.latest(&station_id);
+        StationTelemetryStatus {
+            stale: latest.as_ref().is_none_or(|r| is_stale(r.received_at, now)),
+            station_id,
+            name,
+            registry_status,
+            latest,
+        }
+    };
+
+    let mut statuses: Vec<StationTelemetryStatus> = constellation
+        .ground_stations
+        .iter()
+        .map(|s| {
+            status(
+                s.id.clone(),
+                Some(s.name.clone()),
+                Some(station_status_str(s.status).to_string()),
+            )
+        })
+        .collect();
+    for id in state.fleet.reporting() {
+        if constellation.station(&id).is_none() {
+            statuses.push(status(id, None, None));
+        }
+    }
+    statuses.sort_by(|a, b| a.station_id.cmp(&b.station_id));
+    statuses
+}
+
+/// GET /stations/telemetry
+pub async fn list_telemetry(State(state): State<AppState>) -> Json<Vec<StationTelemetryStatus>> {
+    Json(fleet_status(&state, Utc::now()))
+}
+
+/// GET /stations/:id/telemetry?limit=
+pub async fn station_telemetry(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<FleetHistoryQuery>,
+) -> Result<Json<FleetHistoryResponse>, (StatusCode, String)> {
+    let reports = state.fleet.history(&id, q.limit.unwrap_or(HISTORY_LEN).min(HISTORY_LEN));
+    if reports.is_empty() && state.constellation.load().station(&id).is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("Station not found: {}", id)));
+    }
+    Ok(Json(FleetHistoryResponse { station_id: id, reports }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(station_id: &str, received_at: DateTime<Utc>) -> ReceivedTelemetry {
+        ReceivedTelemetry {
+            record: StationTelemetryRecord {
+                station_id: station_id.to_string(),
+                timestamp: received_at,
+                status: "operational".to_string(),
+                weather_score: 0.9,
+                cloud_cover_pct: Some(10.0),
+            },
+            subject: format!("orbital.gs.{}.telemetry", station_id),
+            received_at,
+        }
+    }
+
+    #[test]
+    fn test_history_is_bounded_newest_first() {
+        let fleet = FleetTelemetry::default();
+        let t0 = Utc::now();
+        for i in 0..(HISTORY_LEN as i64 + 10) {
+            fleet.record(report("GS-001", t0 + Duration::seconds(i)));
+        }
+        let history = fleet.history("GS-001", usize::MAX);
+        assert_eq!(history.len(), HISTORY_LEN);
+        assert_eq!(history[0].received_at, t0 + Duration::seconds(HISTORY_LEN as i64 + 9));
+        assert_eq!(fleet.latest("GS-001").unwrap().received_at, history[0].received_at);
+        assert!(fleet.history("GS-002", 10).is_empty());
+
+        assert!(!is_stale(t0, t0 + Duration::seconds(STALE_AFTER_S)));
+        assert!(is_stale(t0, t0 + Duration::seconds(STALE_AFTER_S + 1)));
+    }
+
+    #[test]
+    fn test_decode_by_content_type() {
+        let record = report("GS-001", Utc::now()).record;
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Content-Type", crate::schema::CONTENT_TYPE);
+        let payload = pb::StationTelemetry::from(&record).encode_to_vec();
+        let decoded = decode("orbital.gs.GS-001.telemetry", Some(&headers), &payload).unwrap();
+        assert_eq!(decoded.station_id, "GS-001");
+        assert_eq!(decoded.cloud_cover_pct, Some(10.0));
+
+        // Untagged JSON without an ID takes it from the subject
+        let json = serde_json::json!({
+            "station_id": "",
+            "timestamp": record.timestamp,
+            "status": "degraded",
+            "weather_score": 0.4,
+            "cloud_cover_pct": null,
+        });
+        let payload = serde_json::to_vec(&json).unwrap();
+        let decoded = decode("orbital.gs.GS-007.telemetry", None, &payload).unwrap();
+        assert_eq!((decoded.station_id.as_str(), decoded.status.as_str()), ("GS-007", "degraded"));
+        assert!(decode("orbital.gs.GS-007.telemetry", None, b"not json").is_none());
+    }
+
+    #[test]
+    fn test_gateway_records_do_not_refresh_stations() {
+        let fleet = FleetTelemetry::default();
+        let t0 = Utc::now();
+        let subject = "orbital.gs.GS-001.telemetry";
+        let payload = pb::StationTelemetry::from(&report("GS-001", t0).record).encode_to_vec();
+        let mut simulator = async_nats::HeaderMap::new();
+        simulator.insert("Content-Type", crate::schema::CONTENT_TYPE);
+        let mut gateway = simulator.clone();
+        gateway.insert(schema::ORIGIN_HEADER, schema::GATEWAY_ORIGIN);
+
+        // Only the gateway publishes: nothing recorded, so the station is stale
+        assert!(!fleet.ingest(subject, Some(&gateway), &payload, t0));
+        assert!(fleet.latest("GS-001").is_none());
+
+        // The simulator reports once, then goes quiet while the gateway ticks on
+        assert!(fleet.ingest(subject, Some(&simulator), &payload, t0));
+        for i in 1..=10 {
+            fleet.ingest(subject, Some(&gateway), &payload, t0 + Duration::seconds(30 * i));
+        }
+        let latest = fleet.latest("GS-001").unwrap();
+        assert_eq!(latest.received_at, t0);
+        assert!(is_stale(latest.received_at, t0 + Duration::seconds(300)));
+    }
+}
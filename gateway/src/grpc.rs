@@ -0,0 +1,341 @@
+//! gRPC service - typed contract for internal consumers
+//!
+//! Runs alongside the REST API (default port 18701) and shares `AppState`.
+//! Schema: `proto/orbital.proto` (package `sx9.orbital.v1`).
+//!
+//! Access mirrors the REST read routes: every call needs the viewer role,
+//! waived by `public_reads`, and spends a token from the caller's budget
+//! in the shared `RateLimiter` (expensive tier for `Propagate` and
+//! `CalculateRoute`, cheap for the rest), so gRPC clients get no more
+//! than REST ones.
+
+use chrono::{DateTime, Utc};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use orbital_glaf::objective::{ObjectiveFunction, RouteMetrics, SlaTier};
+use orbital_glaf::{GlafError, NodeType};
+use orbital_mechanics::StateVector;
+
+use crate::auth::{AuthConfig, Principal};
+use crate::clock::SimClock;
+use crate::ratelimit::{CostTier, RateLimiter};
+use crate::routes::{routing_graph, station_status_str, DEFAULT_ROUTE_CANDIDATES};
+use crate::topology;
+use crate::AppState;
+
+pub mod pb {
+    tonic::include_proto!("sx9.orbital.v1");
+}
+
+use pb::orbital_service_server::{OrbitalService, OrbitalServiceServer};
+
+pub struct OrbitalGrpc {
+    state: AppState,
+    auth: Arc<AuthConfig>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl OrbitalGrpc {
+    pub fn new(state: AppState, auth: Arc<AuthConfig>, limiter: Arc<RateLimiter>) -> Self {
+        Self { state, auth, limiter }
+    }
+
+    /// `require_role(Viewer)` and `ratelimit::limit` for one call
+    fn admit<T>(&self, request: &Request<T>, tier: CostTier) -> Result<(), Status> {
+        let principal = request.extensions().get::<Principal>();
+        if self.auth.enabled() && !self.auth.public_reads && principal.is_none() {
+            return Err(Status::unauthenticated("Authentication required"));
+        }
+        if !self.limiter.enabled() {
+            return Ok(());
+        }
+        let caller = self.limiter.caller_key(principal, request.remote_addr());
+        self.limiter.check(&caller, tier).map(|_| ()).map_err(|wait| {
+            let retry_after_seconds = wait.as_secs_f64().ceil().max(1.0) as u64;
+            tracing::debug!("Rate limited {} ({:?}) over gRPC, retry in {}s", caller, tier, retry_after_seconds);
+            let mut status = Status::resource_exhausted("Rate limit exceeded");
+            status.metadata_mut().insert("retry-after", retry_after_seconds.into());
+            status
+        })
+    }
+}
+
+/// Run the gRPC server: API-key/JWT metadata resolves to a `Principal`
+/// (invalid credentials are refused outright), then each call is admitted
+/// like a REST read
+pub async fn serve(
+    state: AppState,
+    auth: Arc<AuthConfig>,
+    limiter: Arc<RateLimiter>,
+    addr: SocketAddr,
+) -> Result<(), tonic::transport::Error> {
+    let service = OrbitalServiceServer::with_interceptor(
+        OrbitalGrpc::new(state, auth.clone(), limiter),
+        move |mut req: Request<()>| -> Result<Request<()>, Status> {
+            let principal = if auth.enabled() {
+                let headers = req.metadata().clone().into_headers();
+                auth.resolve(&headers).map_err(|(_, msg)| Status::unauthenticated(msg))?
+            } else {
+                Some(Principal::dev_admin())
+            };
+            if let Some(principal) = principal {
+                req.extensions_mut().insert(principal);
+            }
+            Ok(req)
+        },
+    );
+
+    tonic::transport::Server::builder()
+        .add_service(service)
+        .serve(addr)
+        .await
+}
+
+fn unix_ms_to_time(ms: i64, clock: &SimClock) -> DateTime<Utc> {
+    if ms == 0 {
+        clock.now()
+    } else {
+        DateTime::<Utc>::from_timestamp_millis(ms).unwrap_or_else(|| clock.now())
+    }
+}
+
+fn state_to_pb(state: &StateVector) -> pb::StateVector {
+    pb::StateVector {
+        x_km: state.position_x,
+        y_km: state.position_y,
+        z_km: state.position_z,
+        vx_km_s: state.velocity_x,
+        vy_km_s: state.velocity_y,
+        vz_km_s: state.velocity_z,
+        epoch_unix_ms: state.epoch.timestamp_millis(),
+    }
+}
+
+fn geodetic_of(state: &StateVector) -> Result<pb::Geodetic, Status> {
+    let geo = state.to_geodetic().map_err(|e| Status::internal(e.to_string()))?;
+    Ok(pb::Geodetic {
+        latitude_deg: geo.latitude,
+        longitude_deg: geo.longitude,
+        altitude_km: geo.altitude_km,
+    })
+}
+
+fn speed_km_s(state: &StateVector) -> f64 {
+    (state.velocity_x.powi(2) + state.velocity_y.powi(2) + state.velocity_z.powi(2)).sqrt()
+}
+
+#[tonic::async_trait]
+impl OrbitalService for OrbitalGrpc {
+    async fn propagate(
+        &self,
+        request: Request<pb::PropagateRequest>,
+    ) -> Result<Response<pb::PropagateResponse>, Status> {
+        self.admit(&request, CostTier::Expensive)?;
+        let req = request.into_inner();
+        let time = unix_ms_to_time(req.time_unix_ms, &self.state.clock);
+
+        let state = if req.satellite_id.is_empty() {
+            orbital_mechanics::propagation::sgp4_propagate(&req.tle_line1, &req.tle_line2, time)
+        } else {
+            let constellation = self.state.constellation.load();
+            let sat = constellation
+                .satellites
+                .iter()
+                .find(|s| s.id == req.satellite_id)
+                .ok_or_else(|| Status::not_found(format!("Satellite {}", req.satellite_id)))?;
+            sat.propagate(time)
+        }
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        Ok(Response::new(pb::PropagateResponse {
+            satellite_id: req.satellite_id,
+            geodetic: Some(geodetic_of(&state)?),
+            state: Some(state_to_pb(&state)),
+        }))
+    }
+
+    type StreamPositionsStream = ReceiverStream<Result<pb::PositionUpdate, Status>>;
+
+    async fn stream_positions(
+        &self,
+        request: Request<pb::StreamPositionsRequest>,
+    ) -> Result<Response<Self::StreamPositionsStream>, Status> {
+        self.admit(&request, CostTier::Cheap)?;
+        let req = request.into_inner();
+        let interval_ms = if req.interval_ms == 0 { 1000 } else { req.interval_ms.max(50) };
+
+        let satellites: Vec<_> = self
+            .state
+            .constellation
+            .load()
+            .satellites
+            .iter()
+            .filter(|s| req.satellite_ids.is_empty() || req.satellite_ids.contains(&s.id))
+            .cloned()
+            .collect();
+
+        if satellites.is_empty() {
+            return Err(Status::not_found("No matching satellites"));
+        }
+
+        let (tx, rx) = mpsc::channel(satellites.len() * 4);
+        let clock = self.state.clock.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms as u64));
+            loop {
+                ticker.tick().await;
+                let now = clock.now();
+                for sat in &satellites {
+                    let update = sat
+                        .propagate(now)
+                        .map_err(|e| Status::internal(e.to_string()))
+                        .and_then(|state| {
+                            Ok(pb::PositionUpdate {
+                                satellite_id: sat.id.clone(),
+                                position: Some(geodetic_of(&state)?),
+                                velocity_km_s: speed_km_s(&state),
+                                timestamp_unix_ms: now.timestamp_millis(),
+                            })
+                        });
+                    if tx.send(update).await.is_err() {
+                        // Client disconnected
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn calculate_route(
+        &self,
+        request: Request<pb::RouteRequest>,
+    ) -> Result<Response<pb::RouteResponse>, Status> {
+        self.admit(&request, CostTier::Expensive)?;
+        let req = request.into_inner();
+
+        // Same pipeline as `POST /routing/optimal`: live topology, k-shortest
+        // candidates, then the SLA objective picks the route.
+        let tier = match req.priority.to_lowercase().as_str() {
+            "latency" | "platinum" => SlaTier::Platinum,
+            "reliability" | "gold" => SlaTier::Gold,
+            "throughput" | "best_effort" => SlaTier::BestEffort,
+            "" | "silver" => SlaTier::Silver,
+            other => return Err(Status::invalid_argument(format!("unknown priority '{other}'"))),
+        };
+
+        let state = self.state.clone();
+        let response = tokio::task::spawn_blocking(move || {
+            route_response(&state, &req.source_station, &req.destination_station, tier)
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))??;
+        Ok(Response::new(response))
+    }
+
+    async fn list_stations(
+        &self,
+        request: Request<pb::ListStationsRequest>,
+    ) -> Result<Response<pb::ListStationsResponse>, Status> {
+        self.admit(&request, CostTier::Cheap)?;
+        let req = request.into_inner();
+        let constellation = self.state.constellation.load();
+
+        let stations = if req.operational_only {
+            constellation.operational_stations().map(station_to_pb).collect()
+        } else {
+            constellation.ground_stations.iter().map(station_to_pb).collect()
+        };
+
+        Ok(Response::new(pb::ListStationsResponse { stations }))
+    }
+
+    async fn get_station(
+        &self,
+        request: Request<pb::GetStationRequest>,
+    ) -> Result<Response<pb::GroundStation>, Status> {
+        self.admit(&request, CostTier::Cheap)?;
+        let id = request.into_inner().id;
+        let constellation = self.state.constellation.load();
+        let station = constellation
+            .station(&id)
+            .ok_or_else(|| Status::not_found(format!("Station not found: {}", id)))?;
+
+        Ok(Response::new(station_to_pb(station)))
+    }
+}
+
+/// Best route under `tier` on the live topology; CPU-bound, run it on the
+/// blocking pool
+fn route_response(
+    state: &AppState,
+    source: &str,
+    destination: &str,
+    tier: SlaTier,
+) -> Result<pb::RouteResponse, Status> {
+    let snapshot = topology::snapshot(state, state.clock.now()).map_err(|e| Status::internal(e.to_string()))?;
+    let graph = routing_graph(state, &snapshot, true);
+    let paths = graph.k_shortest_paths(source, destination, DEFAULT_ROUTE_CANDIDATES).map_err(|e| match e {
+        GlafError::NodeNotFound(_) => Status::not_found(e.to_string()),
+        _ => Status::failed_precondition(e.to_string()),
+    })?;
+    let candidates: Vec<RouteMetrics> = paths
+        .iter()
+        .filter_map(|path| RouteMetrics::from_path(&graph, path))
+        .collect();
+    let route = ObjectiveFunction::for_tier(tier)
+        .select_optimal(&candidates)
+        .ok_or_else(|| Status::failed_precondition("no feasible route"))?;
+
+    let path = route
+        .metrics
+        .path
+        .iter()
+        .enumerate()
+        .map(|(i, node_id)| {
+            let node_type = match graph.get_node(node_id).map(|n| &n.node_type) {
+                Some(NodeType::Satellite { .. }) => "Satellite",
+                _ => "GroundStation",
+            };
+            let link = i
+                .checked_sub(1)
+                .and_then(|prev| graph.get_link(&route.metrics.path[prev], node_id));
+            pb::RouteHop {
+                node_id: node_id.clone(),
+                node_type: node_type.to_string(),
+                link_quality: link.map_or(1.0, |l| l.weather_score),
+                hop_latency_ms: link.map_or(0.0, |l| l.latency_ms),
+            }
+        })
+        .collect();
+
+    Ok(pb::RouteResponse {
+        path,
+        total_latency_ms: route.metrics.latency_ms,
+        quality_score: route.breakdown.utility,
+        weather_impact: 1.0 - route.metrics.weather_factor,
+    })
+}
+
+fn station_to_pb(station: &ground_stations::GroundStation) -> pb::GroundStation {
+    pb::GroundStation {
+        id: station.id.clone(),
+        name: station.name.clone(),
+        latitude_deg: station.location.latitude,
+        longitude_deg: station.location.longitude,
+        altitude_m: station.location.altitude_m,
+        status: station_status_str(station.status).to_string(),
+        weather_score: station
+            .weather
+            .as_ref()
+            .map(|w| w.beam_quality_score)
+            .unwrap_or(1.0),
+    }
+}
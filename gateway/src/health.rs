@@ -0,0 +1,289 @@
+//! Health checks
+//!
+//! - `GET /health` runs every dependency check concurrently (each bounded
+//!   by `CHECK_TIMEOUT`) and rolls them up: any failing critical check is
+//!   `unhealthy` (503), any failing optional check is `degraded` (200).
+//! - `GET /health/live` only proves the process is serving requests.
+//! - `GET /health/ready` runs the local critical checks (history and
+//!   memory stores) without touching external providers.
+
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::AppState;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// Result of a single dependency check
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyCheck {
+    pub name: &'static str,
+    pub status: HealthStatus,
+    /// Critical dependencies make the gateway unhealthy when they fail
+    pub critical: bool,
+    pub latency_ms: u64,
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub service: &'static str,
+    pub version: &'static str,
+    pub constellations: Vec<String>,
+    pub checked_at: DateTime<Utc>,
+    pub checks: Vec<DependencyCheck>,
+}
+
+enum Outcome {
+    Ok(Option<String>),
+    /// Working but not as configured (e.g. stale data)
+    Degraded(String),
+    Failed(String),
+}
+
+async fn check<F>(name: &'static str, critical: bool, probe: F) -> DependencyCheck
+where
+    F: Future<Output = Outcome>,
+{
+    let started = Instant::now();
+    let outcome = tokio::time::timeout(CHECK_TIMEOUT, probe)
+        .await
+        .unwrap_or_else(|_| Outcome::Failed(format!("Timed out after {:?}", CHECK_TIMEOUT)));
+
+    let (status, detail) = match outcome {
+        Outcome::Ok(detail) => (HealthStatus::Healthy, detail),
+        Outcome::Degraded(detail) => (HealthStatus::Degraded, Some(detail)),
+        Outcome::Failed(detail) if critical => (HealthStatus::Unhealthy, Some(detail)),
+        Outcome::Failed(detail) => (HealthStatus::Degraded, Some(detail)),
+    };
+
+    DependencyCheck {
+        name,
+        status,
+        critical,
+        latency_ms: started.elapsed().as_millis() as u64,
+        detail,
+    }
+}
+
+/// Worst status across checks
+pub fn rollup(checks: &[DependencyCheck]) -> HealthStatus {
+    checks
+        .iter()
+        .map(|c| c.status)
+        .max()
+        .unwrap_or(HealthStatus::Healthy)
+}
+
+async fn history_check(state: &AppState) -> DependencyCheck {
+    check("history", true, async {
+        match state.history.size_on_disk() {
+            Ok(bytes) => Outcome::Ok(Some(format!("{} bytes on disk", bytes))),
+            Err(e) => Outcome::Failed(e.to_string()),
+        }
+    })
+    .await
+}
+
+async fn memory_check(state: &AppState) -> DependencyCheck {
+    check("memory", true, async {
+        let cache = state.memory.tcache.read().await;
+        match cache.iter().next() {
+            Some(Err(e)) => Outcome::Failed(e.to_string()),
+            _ => Outcome::Ok(None),
+        }
+    })
+    .await
+}
+
+async fn nats_check(state: &AppState) -> DependencyCheck {
+    check("nats", false, async {
+        match (&state.telemetry, &state.config.nats.url) {
+            (Some(telemetry), _) if telemetry.is_connected() => Outcome::Ok(None),
+            (Some(_), _) => Outcome::Failed("Disconnected, reconnecting".to_string()),
+            (None, Some(url)) if state.config.features.telemetry => {
+                Outcome::Failed(format!("Configured for {} but not connected", url))
+            }
+            (None, _) => Outcome::Ok(Some("Not configured".to_string())),
+        }
+    })
+    .await
+}
+
+async fn weather_check(state: &AppState) -> DependencyCheck {
+    check("weather", false, async {
+        let constellation = state.constellation.load();
+        let Some(station) = constellation.ground_stations.first() else {
+            return Outcome::Ok(Some("No stations".to_string()));
+        };
+        let (latitude, longitude) = (station.location.latitude, station.location.longitude);
+        match state.weather.probe(latitude, longitude).await {
+            Ok(()) => Outcome::Ok(None),
+            Err(e) => Outcome::Failed(e),
+        }
+    })
+    .await
+}
+
+async fn catalog_check(state: &AppState) -> DependencyCheck {
+    check("catalog", false, async {
+        let interval = state.celestrak.interval;
+        if interval.is_zero() || state.celestrak.groups.is_empty() {
+            return Outcome::Ok(Some("Scheduled refresh disabled".to_string()));
+        }
+        let catalog = state.catalog.read().unwrap();
+        let objects = catalog.len();
+        match catalog.last_success() {
+            Some(at) => {
+                let age = (Utc::now() - at).to_std().unwrap_or_default();
+                if age > interval * 2 {
+                    Outcome::Degraded(format!("Last successful refresh {} ({} objects)", at, objects))
+                } else {
+                    Outcome::Ok(Some(format!("{} objects, refreshed {}", objects, at)))
+                }
+            }
+            None => Outcome::Degraded("No successful refresh yet".to_string()),
+        }
+    })
+    .await
+}
+
+async fn station_telemetry_check(state: &AppState) -> DependencyCheck {
+    check("station_telemetry", false, async {
+        if state.telemetry.is_none() {
+            return Outcome::Ok(Some("Not configured".to_string()));
+        }
+        let statuses = crate::fleet::fleet_status(state, Utc::now());
+        let stale = statuses.iter().filter(|s| s.stale && s.registry_status.is_some()).count();
+        match statuses.iter().filter(|s| s.latest.is_some()).count() {
+            0 => Outcome::Ok(Some("No station telemetry received".to_string())),
+            _ if stale > 0 => Outcome::Degraded(format!("{} registered stations stale", stale)),
+            reporting => Outcome::Ok(Some(format!("{} stations reporting", reporting))),
+        }
+    })
+    .await
+}
+
+#[cfg(feature = "neo4j")]
+async fn neo4j_check(state: &AppState) -> Option<DependencyCheck> {
+    let client = state.neo4j.as_ref()?;
+    Some(
+        check("neo4j", false, async {
+            match client.get_stats().await {
+                Ok(stats) => Outcome::Ok(Some(format!(
+                    "{} satellites, {} stations",
+                    stats.satellites, stats.ground_stations
+                ))),
+                Err(e) => Outcome::Failed(e.to_string()),
+            }
+        })
+        .await,
+    )
+}
+
+#[cfg(not(feature = "neo4j"))]
+async fn neo4j_check(_state: &AppState) -> Option<DependencyCheck> {
+    None
+}
+
+// ========== Routes ==========
+
+/// GET /health - deep dependency report
+pub async fn health(State(state): State<AppState>) -> (StatusCode, Json<HealthReport>) {
+    let (history, memory, nats, weather, catalog, stations, neo4j) = tokio::join!(
+        history_check(&state),
+        memory_check(&state),
+        nats_check(&state),
+        weather_check(&state),
+        catalog_check(&state),
+        station_telemetry_check(&state),
+        neo4j_check(&state),
+    );
+    let mut checks = vec![history, memory, nats, weather, catalog, stations];
+    checks.extend(neo4j);
+
+    let status = rollup(&checks);
+    let code = if status == HealthStatus::Unhealthy {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    (
+        code,
+        Json(HealthReport {
+            status,
+            service: "orbital-gateway",
+            version: env!("CARGO_PKG_VERSION"),
+            constellations: state.config.constellations().map(|c| c.name.clone()).collect(),
+            checked_at: Utc::now(),
+            checks,
+        }),
+    )
+}
+
+/// GET /health/live - process is up and serving
+pub async fn live() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": HealthStatus::Healthy }))
+}
+
+/// GET /health/ready - critical local dependencies only
+pub async fn ready(State(state): State<AppState>) -> (StatusCode, Json<serde_json::Value>) {
+    let (history, memory) = tokio::join!(history_check(&state), memory_check(&state));
+    let checks = [history, memory];
+    let status = rollup(&checks);
+    let code = if status == HealthStatus::Unhealthy {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (code, Json(serde_json::json!({ "status": status, "checks": checks })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(status: HealthStatus) -> DependencyCheck {
+        DependencyCheck {
+            name: "test",
+            status,
+            critical: false,
+            latency_ms: 0,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn test_rollup_takes_worst() {
+        assert_eq!(rollup(&[]), HealthStatus::Healthy);
+        assert_eq!(
+            rollup(&[dep(HealthStatus::Healthy), dep(HealthStatus::Degraded)]),
+            HealthStatus::Degraded
+        );
+        assert_eq!(
+            rollup(&[dep(HealthStatus::Unhealthy), dep(HealthStatus::Degraded)]),
+            HealthStatus::Unhealthy
+        );
+    }
+
+    #[tokio::test]
+    async fn test_optional_failure_degrades() {
+        let result = check("x", false, async { Outcome::Failed("down".to_string()) }).await;
+        assert_eq!(result.status, HealthStatus::Degraded);
+        let result = check("x", true, async { Outcome::Failed("down".to_string()) }).await;
+        assert_eq!(result.status, HealthStatus::Unhealthy);
+    }
+}
@@ -0,0 +1,289 @@
+//! Historical time-series store
+//!
+//! Persists propagated positions, link states and station telemetry in a
+//! local sled database so the UI can scrub backwards and analysts can pull
+//! training data.
+//!
+//! Each series lives in its own tree, keyed by
+//! `series_id 0x00 timestamp_ms (big-endian u64)` so a range scan over one
+//! satellite/link/station returns records in time order.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path as FsPath;
+
+use crate::AppState;
+
+const DEFAULT_QUERY_LIMIT: usize = 10_000;
+
+/// Propagated satellite position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionRecord {
+    pub satellite_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: f64,
+    pub velocity_km_s: f64,
+    /// ECI (TEME) position, km
+    pub position_eci_km: [f64; 3],
+    /// ECI (TEME) velocity, km/s
+    pub velocity_eci_km_s: [f64; 3],
+}
+
+/// Satellite-to-ground link state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkRecord {
+    pub link_id: String,
+    pub satellite_id: String,
+    pub station_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub elevation_deg: f64,
+    pub range_km: f64,
+    pub active: bool,
+}
+
+/// Ground station telemetry snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationTelemetryRecord {
+    pub station_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub status: String,
+    pub weather_score: f64,
+    pub cloud_cover_pct: Option<f64>,
+}
+
+/// Time-series store backed by sled
+pub struct HistoryStore {
+    db: sled::Db,
+    positions: sled::Tree,
+    links: sled::Tree,
+    telemetry: sled::Tree,
+    retention: Duration,
+}
+
+impl HistoryStore {
+    pub fn open(path: &str, retention: Duration) -> anyhow::Result<Self> {
+        let db = sled::open(FsPath::new(path))?;
+        Ok(Self {
+            positions: db.open_tree("positions")?,
+            links: db.open_tree("links")?,
+            telemetry: db.open_tree("telemetry")?,
+            db,
+            retention,
+        })
+    }
+
+    pub fn record_position(&self, record: &PositionRecord) -> anyhow::Result<()> {
+        insert(&self.positions, &record.satellite_id, record.timestamp, record)
+    }
+
+    pub fn record_link(&self, record: &LinkRecord) -> anyhow::Result<()> {
+        insert(&self.links, &record.link_id, record.timestamp, record)
+    }
+
+    pub fn record_telemetry(&self, record: &StationTelemetryRecord) -> anyhow::Result<()> {
+        insert(&self.telemetry, &record.station_id, record.timestamp, record)
+    }
+
+    pub fn positions(
+        &self,
+        satellite_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<PositionRecord>> {
+        query(&self.positions, satellite_id, from, to, limit)
+    }
+
+    pub fn links(
+        &self,
+        link_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<LinkRecord>> {
+        query(&self.links, link_id, from, to, limit)
+    }
+
+    pub fn telemetry(
+        &self,
+        station_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<StationTelemetryRecord>> {
+        query(&self.telemetry, station_id, from, to, limit)
+    }
+
+    /// Underlying database, for stores that share the file but not the
+    /// retention policy (e.g. downselect runs)
+    pub fn database(&self) -> sled::Db {
+        self.db.clone()
+    }
+
+    /// Cheap liveness probe: on-disk size in bytes
+    pub fn size_on_disk(&self) -> anyhow::Result<u64> {
+        Ok(self.db.size_on_disk()?)
+    }
+
+    /// Drop records older than the retention window before `now` (sim time).
+    /// Returns count removed.
+    pub fn prune(&self, now: DateTime<Utc>) -> anyhow::Result<usize> {
+        let cutoff = (now - self.retention).timestamp_millis().max(0) as u64;
+        let mut removed = 0;
+
+        for tree in [&self.positions, &self.links, &self.telemetry] {
+            for key in tree.iter().keys() {
+                let key = key?;
+                if key.len() < 8 {
+                    continue;
+                }
+                let ts_bytes: [u8; 8] = key[key.len() - 8..].try_into()?;
+                if u64::from_be_bytes(ts_bytes) < cutoff {
+                    tree.remove(&key)?;
+                    removed += 1;
+                }
+            }
+        }
+
+        self.db.flush()?;
+        Ok(removed)
+    }
+}
+
+fn series_key(id: &str, timestamp: DateTime<Utc>) -> Vec<u8> {
+    let mut key = Vec::with_capacity(id.len() + 9);
+    key.extend_from_slice(id.as_bytes());
+    key.push(0);
+    key.extend_from_slice(&(timestamp.timestamp_millis().max(0) as u64).to_be_bytes());
+    key
+}
+
+fn insert<T: Serialize>(
+    tree: &sled::Tree,
+    id: &str,
+    timestamp: DateTime<Utc>,
+    value: &T,
+) -> anyhow::Result<()> {
+    tree.insert(series_key(id, timestamp), serde_json::to_vec(value)?)?;
+    Ok(())
+}
+
+fn query<T: DeserializeOwned>(
+    tree: &sled::Tree,
+    id: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: usize,
+) -> anyhow::Result<Vec<T>> {
+    let start = series_key(id, from);
+    let end = series_key(id, to);
+
+    tree.range(start..=end)
+        .values()
+        .take(limit)
+        .map(|v| Ok(serde_json::from_slice(&v?)?))
+        .collect()
+}
+
+// ========== Routes ==========
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    /// Resolve to `(from, to, limit)`, defaulting to the hour before `now`
+    fn window(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<(DateTime<Utc>, DateTime<Utc>, usize), (StatusCode, String)> {
+        let to = self.to.unwrap_or(now);
+        let from = self.from.unwrap_or(to - Duration::hours(1));
+        if from > to {
+            return Err((StatusCode::BAD_REQUEST, "`from` must not be after `to`".to_string()));
+        }
+        Ok((from, to, self.limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(DEFAULT_QUERY_LIMIT)))
+    }
+}
+
+#[derive(Serialize)]
+pub struct HistoryResponse<T> {
+    pub id: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub count: usize,
+    pub records: Vec<T>,
+}
+
+fn respond<T>(
+    id: String,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    result: anyhow::Result<Vec<T>>,
+) -> Result<Json<HistoryResponse<T>>, (StatusCode, String)> {
+    let records = result.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(HistoryResponse {
+        id,
+        from,
+        to,
+        count: records.len(),
+        records,
+    }))
+}
+
+/// GET /satellites/:id/history?from=&to=&limit=
+pub async fn satellite_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<HistoryQuery>,
+) -> Result<Json<HistoryResponse<PositionRecord>>, (StatusCode, String)> {
+    let (from, to, limit) = q.window(state.clock.now())?;
+    let result = state.history.positions(&id, from, to, limit);
+    respond(id, from, to, result)
+}
+
+/// GET /ground-stations/:id/history?from=&to=&limit=
+pub async fn station_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<HistoryQuery>,
+) -> Result<Json<HistoryResponse<StationTelemetryRecord>>, (StatusCode, String)> {
+    let (from, to, limit) = q.window(state.clock.now())?;
+    let result = state.history.telemetry(&id, from, to, limit);
+    respond(id, from, to, result)
+}
+
+/// GET /links/:id/history?from=&to=&limit= (link ID is `SAT|STATION`)
+pub async fn link_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<HistoryQuery>,
+) -> Result<Json<HistoryResponse<LinkRecord>>, (StatusCode, String)> {
+    let (from, to, limit) = q.window(state.clock.now())?;
+    let result = state.history.links(&id, from, to, limit);
+    respond(id, from, to, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_keys_sort_by_time() {
+        let t0 = Utc::now();
+        let t1 = t0 + Duration::seconds(30);
+        assert!(series_key("HALO-01", t0) < series_key("HALO-01", t1));
+        // Prefix terminator keeps "HALO-01" records out of a "HALO-010" scan
+        assert!(series_key("HALO-01", t1) < series_key("HALO-010", t0));
+    }
+}
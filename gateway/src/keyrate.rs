@@ -0,0 +1,416 @@
+//! QKD key-rate and daily key-budget model
+//!
+//! The secret key rate of a downlink at one instant is the peak rate
+//! scaled by:
+//! - the pass's predicted FSO quality (weather × margin headroom)
+//! - channel loss relative to zenith: squared slant range over altitude,
+//!   and atmospheric transmittance over the airmass
+//! - background light: full rate with the Sun below nautical twilight
+//!   (-12°) at the station, falling linearly to the tier's daylight
+//!   fraction at sunrise
+//! - the QoS tier's secure fraction
+//!
+//! and is zero below the tier's elevation floor. A pass yields the rate
+//! integrated over its propagated track, weighted by its acquisition
+//! probability; passes unlikely to be acquired at all are not scheduled.
+//! Daily budgets add up passes per
+//! station and across the constellation, with each satellite terminal and
+//! station telescope holding one key session at a time.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use ground_station_wasm::calculate_look_angles;
+use ground_stations::GroundStation;
+use orbital_mechanics::{eclipse::sun_elevation_deg, Satellite};
+
+use crate::passes::{predict_station_passes, PredictedPass};
+use crate::propagation::MIN_LINK_ELEVATION_DEG;
+use crate::AppState;
+
+/// Secret key rate at zenith, at night, at full quality, best-effort
+/// tier (kbit/s)
+pub const PEAK_KEY_RATE_KBPS: f64 = 2.0;
+
+/// Zenith optical depth of the clear atmosphere at 1550 nm
+const ZENITH_OPTICAL_DEPTH: f64 = 0.15;
+
+/// Sun elevation below which background light no longer matters
+const NIGHT_SUN_ELEVATION_DEG: f64 = -12.0;
+
+/// Track sampling step when integrating a pass
+const RATE_SAMPLE_STEP_S: i64 = 60;
+
+const BUDGET_HOURS: i64 = 24;
+
+/// Passes less likely than this to be acquired are skipped
+pub const MIN_ACQUISITION_PROBABILITY: f64 = 0.5;
+
+/// Key-distribution service tier. Stricter tiers spend more of the raw
+/// key on privacy amplification and need a cleaner channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QosTier {
+    /// Tight finite-key security parameter; night passes only
+    Premium,
+    #[default]
+    Standard,
+    /// Loosest parameters, tolerates daylight background
+    BestEffort,
+}
+
+impl QosTier {
+    /// Fraction of the sifted key kept after privacy amplification
+    pub fn secure_fraction(self) -> f64 {
+        match self {
+            QosTier::Premium => 0.6,
+            QosTier::Standard => 0.8,
+            QosTier::BestEffort => 1.0,
+        }
+    }
+
+    /// No key below this elevation (degrees)
+    pub fn min_elevation_deg(self) -> f64 {
+        match self {
+            QosTier::Premium => 30.0,
+            QosTier::Standard => 20.0,
+            QosTier::BestEffort => MIN_LINK_ELEVATION_DEG,
+        }
+    }
+
+    /// Rate left in full daylight, as a fraction of the night rate
+    pub fn daylight_fraction(self) -> f64 {
+        match self {
+            QosTier::Premium => 0.0,
+            QosTier::Standard => 0.1,
+            QosTier::BestEffort => 0.25,
+        }
+    }
+}
+
+/// Geometry and lighting at one instant of a pass
+#[derive(Debug, Clone, Copy)]
+pub struct RateSample {
+    pub elevation_deg: f64,
+    pub range_km: f64,
+    pub altitude_km: f64,
+    pub sun_elevation_deg: f64,
+}
+
+/// Channel transmission relative to the satellite at zenith (0-1)
+pub fn elevation_factor(elevation_deg: f64, range_km: f64, altitude_km: f64) -> f64 {
+    if elevation_deg <= 0.0 || range_km <= 0.0 {
+        return 0.0;
+    }
+    let geometric = (altitude_km / range_km).powi(2).min(1.0);
+    let airmass = 1.0 / elevation_deg.to_radians().sin();
+    geometric * (-ZENITH_OPTICAL_DEPTH * (airmass - 1.0)).exp()
+}
+
+/// Rate factor from sky background at the station (0-1)
+pub fn background_factor(sun_elevation_deg: f64, tier: QosTier) -> f64 {
+    let day = tier.daylight_fraction();
+    if sun_elevation_deg <= NIGHT_SUN_ELEVATION_DEG {
+        1.0
+    } else if sun_elevation_deg >= 0.0 {
+        day
+    } else {
+        let dusk = sun_elevation_deg / NIGHT_SUN_ELEVATION_DEG;
+        day + (1.0 - day) * dusk
+    }
+}
+
+/// Secret key rate at one instant (kbit/s)
+pub fn key_rate_kbps(sample: &RateSample, fso_quality: f64, tier: QosTier) -> f64 {
+    if sample.elevation_deg < tier.min_elevation_deg() {
+        return 0.0;
+    }
+    PEAK_KEY_RATE_KBPS
+        * fso_quality.clamp(0.0, 1.0)
+        * elevation_factor(sample.elevation_deg, sample.range_km, sample.altitude_km)
+        * background_factor(sample.sun_elevation_deg, tier)
+        * tier.secure_fraction()
+}
+
+/// Key yield of samples taken every `step_s` seconds (kbit)
+pub fn integrate_kbit(samples: &[RateSample], step_s: f64, fso_quality: f64, tier: QosTier) -> f64 {
+    samples
+        .iter()
+        .map(|s| key_rate_kbps(s, fso_quality, tier) * step_s)
+        .sum()
+}
+
+/// Sample a pass's track from AOS to LOS
+fn sample_pass(sat: &Satellite, station: &GroundStation, pass: &PredictedPass) -> Vec<RateSample> {
+    let steps = ((pass.los - pass.aos).num_seconds() / RATE_SAMPLE_STEP_S).max(1);
+    (0..steps)
+        .filter_map(|i| {
+            // Midpoint of each step
+            let t = pass.aos + Duration::seconds(i * RATE_SAMPLE_STEP_S + RATE_SAMPLE_STEP_S / 2);
+            let sv = sat.propagate(t).ok()?;
+            let geo = sv.to_geodetic().ok()?;
+            let look = calculate_look_angles(
+                station.location.latitude,
+                station.location.longitude,
+                station.location.altitude_m / 1000.0,
+                geo.latitude,
+                geo.longitude,
+                geo.altitude_km,
+            );
+            Some(RateSample {
+                elevation_deg: look.elevation_deg,
+                range_km: look.range_km,
+                altitude_km: geo.altitude_km,
+                sun_elevation_deg: sun_elevation_deg(station.location.latitude, station.location.longitude, t),
+            })
+        })
+        .collect()
+}
+
+/// A predicted pass with its modelled key yield
+#[derive(Debug, Clone)]
+pub struct KeyCandidate {
+    pub pass: PredictedPass,
+    pub expected_kbit: f64,
+}
+
+/// Predicted passes over `station` likely to be acquired, with their
+/// expected key yield at `tier`
+pub fn key_candidates(
+    state: &AppState,
+    station: &GroundStation,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    min_elevation_deg: f64,
+    tier: QosTier,
+) -> Vec<KeyCandidate> {
+    let constellation = state.constellation.load();
+    predict_station_passes(state, station, from, to, min_elevation_deg.max(tier.min_elevation_deg()))
+        .into_iter()
+        .filter(|pass| pass.acquisition_probability >= MIN_ACQUISITION_PROBABILITY)
+        .filter_map(|pass| {
+            let sat = constellation.satellites.iter().find(|s| s.id == pass.satellite_id)?;
+            let samples = sample_pass(sat, station, &pass);
+            let expected_kbit = pass.acquisition_probability
+                * integrate_kbit(&samples, RATE_SAMPLE_STEP_S as f64, pass.predicted_fso_quality, tier);
+            Some(KeyCandidate { pass, expected_kbit })
+        })
+        .collect()
+}
+
+fn overlaps(a: &PredictedPass, b: &PredictedPass) -> bool {
+    a.aos < b.los && b.aos < a.los
+}
+
+/// Daily key budget of one station
+#[derive(Debug, Clone, Serialize)]
+pub struct StationBudget {
+    pub station_id: String,
+    /// Best the station could do with every satellite to itself (kbit)
+    pub potential_kbit: f64,
+    /// Its share with satellite terminals contended across stations (kbit)
+    pub budget_kbit: f64,
+    /// Passes making up `budget_kbit`
+    pub passes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyBudget {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub tier: QosTier,
+    /// Sum of station budgets (kbit)
+    pub constellation_kbit: f64,
+    pub stations: Vec<StationBudget>,
+}
+
+/// Highest-yield passes first, skipping any that collide with one already
+/// taken by the same station or (if `shared`) the same satellite
+fn allocate<'a>(
+    candidates: impl Iterator<Item = (&'a str, &'a KeyCandidate)>,
+    shared: bool,
+) -> Vec<(&'a str, &'a KeyCandidate)> {
+    let mut ranked: Vec<_> = candidates.filter(|(_, c)| c.expected_kbit > 0.0).collect();
+    ranked.sort_by(|a, b| b.1.expected_kbit.total_cmp(&a.1.expected_kbit));
+    let mut taken: Vec<(&str, &KeyCandidate)> = Vec::new();
+    for (station, candidate) in ranked {
+        let clash = taken.iter().any(|(s, c)| {
+            (*s == station || (shared && c.pass.satellite_id == candidate.pass.satellite_id))
+                && overlaps(&c.pass, &candidate.pass)
+        });
+        if !clash {
+            taken.push((station, candidate));
+        }
+    }
+    taken
+}
+
+/// Per-station and constellation budgets from each station's candidates
+pub fn budget(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    tier: QosTier,
+    candidates: &HashMap<String, Vec<KeyCandidate>>,
+) -> KeyBudget {
+    let all = candidates
+        .iter()
+        .flat_map(|(station, list)| list.iter().map(move |c| (station.as_str(), c)));
+    let shared = allocate(all, true);
+
+    let mut stations: Vec<StationBudget> = candidates
+        .iter()
+        .map(|(station, list)| {
+            let alone = allocate(list.iter().map(|c| (station.as_str(), c)), false);
+            let mine: Vec<_> = shared.iter().filter(|(s, _)| s == station).collect();
+            StationBudget {
+                station_id: station.clone(),
+                potential_kbit: alone.iter().map(|(_, c)| c.expected_kbit).sum(),
+                budget_kbit: mine.iter().map(|(_, c)| c.expected_kbit).sum(),
+                passes: mine.len(),
+            }
+        })
+        .collect();
+    stations.sort_by(|a, b| a.station_id.cmp(&b.station_id));
+
+    KeyBudget {
+        from,
+        to,
+        tier,
+        constellation_kbit: stations.iter().map(|s| s.budget_kbit).sum(),
+        stations,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BudgetQuery {
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tier: QosTier,
+    pub min_elevation_deg: Option<f64>,
+}
+
+/// GET /keys/budget?tier=standard - next 24 h of key supply across every
+/// operational station
+pub async fn key_budget(State(state): State<AppState>, Query(q): Query<BudgetQuery>) -> Json<KeyBudget> {
+    let from = q.from.unwrap_or_else(|| state.clock.now());
+    let to = from + Duration::hours(BUDGET_HOURS);
+    let min_el = q.min_elevation_deg.unwrap_or(MIN_LINK_ELEVATION_DEG);
+
+    let constellation = state.constellation.load();
+    let candidates = constellation
+        .operational_stations()
+        .map(|station| {
+            (
+                station.id.clone(),
+                key_candidates(&state, station, from, to, min_el, q.tier),
+            )
+        })
+        .collect();
+    Json(budget(from, to, q.tier, &candidates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orbital_mechanics::eclipse::SunGeometry;
+
+    fn sample(elevation_deg: f64, sun_elevation_deg: f64) -> RateSample {
+        // MEO at 10,500 km
+        let r = 6378.0;
+        let h = 10_500.0;
+        let s = elevation_deg.to_radians().sin();
+        let range_km = ((r * s).powi(2) + h * h + 2.0 * r * h).sqrt() - r * s;
+        RateSample {
+            elevation_deg,
+            range_km,
+            altitude_km: h,
+            sun_elevation_deg,
+        }
+    }
+
+    #[test]
+    fn test_rate_falls_with_elevation_and_daylight() {
+        let tier = QosTier::BestEffort;
+        let zenith = key_rate_kbps(&sample(90.0, -30.0), 1.0, tier);
+        assert!((zenith - PEAK_KEY_RATE_KBPS).abs() < 1e-9);
+
+        let low = key_rate_kbps(&sample(20.0, -30.0), 1.0, tier);
+        assert!(low > 0.0 && low < 0.5 * zenith);
+
+        let day = key_rate_kbps(&sample(90.0, 30.0), 1.0, tier);
+        assert!((day - 0.25 * zenith).abs() < 1e-9);
+        let twilight = key_rate_kbps(&sample(90.0, -6.0), 1.0, tier);
+        assert!(day < twilight && twilight < zenith);
+    }
+
+    #[test]
+    fn test_tiers() {
+        let night = sample(45.0, -20.0);
+        let rates: Vec<f64> = [QosTier::Premium, QosTier::Standard, QosTier::BestEffort]
+            .into_iter()
+            .map(|t| key_rate_kbps(&night, 1.0, t))
+            .collect();
+        assert!(rates[0] < rates[1] && rates[1] < rates[2]);
+
+        // Premium needs dark sky and 30° elevation
+        assert_eq!(key_rate_kbps(&sample(45.0, 10.0), 1.0, QosTier::Premium), 0.0);
+        assert_eq!(key_rate_kbps(&sample(25.0, -20.0), 1.0, QosTier::Premium), 0.0);
+        assert!(key_rate_kbps(&sample(25.0, -20.0), 1.0, QosTier::Standard) > 0.0);
+    }
+
+    fn candidate(sat: &str, start_min: i64, expected_kbit: f64) -> KeyCandidate {
+        let aos = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + Duration::minutes(start_min);
+        KeyCandidate {
+            pass: PredictedPass {
+                satellite_id: sat.into(),
+                norad_id: 0,
+                aos,
+                los: aos + Duration::minutes(10),
+                tca: aos + Duration::minutes(5),
+                duration_sec: 600.0,
+                max_elevation_deg: 60.0,
+                aos_azimuth_deg: 0.0,
+                los_azimuth_deg: 180.0,
+                predicted_margin_db: 10.0,
+                sun: SunGeometry {
+                    sun_elevation_deg: -30.0,
+                    phase_angle_deg: 90.0,
+                    sun_separation_deg: 120.0,
+                    daytime_background: false,
+                },
+                min_sun_separation_deg: 120.0,
+                predicted_fso_quality: 1.0,
+                acquisition_probability: 1.0,
+            },
+            expected_kbit,
+        }
+    }
+
+    #[test]
+    fn test_budget_shares_satellite_terminals() {
+        let candidates = HashMap::from([
+            (
+                "GS-A".to_string(),
+                vec![candidate("SAT-1", 0, 1000.0), candidate("SAT-2", 5, 400.0)],
+            ),
+            ("GS-B".to_string(), vec![candidate("SAT-1", 5, 800.0)]),
+        ]);
+        let from = candidates["GS-A"][0].pass.aos;
+        let budget = budget(from, from + Duration::hours(24), QosTier::Standard, &candidates);
+
+        let a = &budget.stations[0];
+        let b = &budget.stations[1];
+        // GS-A's telescope takes one of its two overlapping passes
+        assert_eq!(a.potential_kbit, 1000.0);
+        assert_eq!(a.budget_kbit, 1000.0);
+        // SAT-1 is busy with GS-A when GS-B could use it
+        assert_eq!(b.potential_kbit, 800.0);
+        assert_eq!(b.budget_kbit, 0.0);
+        assert_eq!(budget.constellation_kbit, 1000.0);
+    }
+}
@@ -0,0 +1,541 @@
+//! Sideband channel keys
+//!
+//! An entropy pool absorbs harvested entropy (`POST /keyring/entropy`,
+//! from the station entropy harvesters) and, unless
+//! `keyring.require_harvested` is set, operating-system randomness. Each
+//! channel key is derived with HKDF-SHA256 from a fresh pool draw and the
+//! previous key, so keys ratchet forward: a leaked key exposes neither
+//! its predecessors nor, once fresh entropy is mixed in, its successors.
+//!
+//! Keys are replaced every `keyring.rotation_secs` (5 minutes by default)
+//! and stay valid one further interval for messages in flight. Key
+//! material never leaves the gateway; only versions and fingerprints are
+//! served. Every rotation is an audit event, appended to the history
+//! database (never pruned) and published on `orbital.keyring.{channel}`.
+//! A channel counts as encrypted only while it holds an unexpired key, so
+//! with `require_harvested` and no harvester feed, channels drop out.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::auth::Principal;
+use crate::AppState;
+
+const EVENTS_TREE: &str = "keyring_events";
+
+/// Entropy a key draw must be backed by (bits)
+const KEY_BITS: u64 = 256;
+
+/// How often the rotation loop looks for due keys
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+const DEFAULT_EVENT_LIMIT: usize = 100;
+
+/// Hash-chained entropy accumulator
+pub struct EntropyPool {
+    state: [u8; 32],
+    /// Unspent credited entropy (bits), capped at the pool size
+    available_bits: u64,
+    /// Credited entropy per source, all time (bits)
+    harvested: BTreeMap<String, u64>,
+    allow_os: bool,
+}
+
+impl EntropyPool {
+    pub fn new(allow_os: bool) -> Self {
+        Self {
+            state: [0; 32],
+            available_bits: 0,
+            harvested: BTreeMap::new(),
+            allow_os,
+        }
+    }
+
+    /// Mix in `bytes`, crediting at most `estimated_bits` (default: 8 per
+    /// byte; harvesters should send their min-entropy estimate)
+    pub fn ingest(&mut self, source: &str, bytes: &[u8], estimated_bits: Option<u64>) -> u64 {
+        let mut hash = Sha256::new();
+        hash.update(b"ingest");
+        hash.update(self.state);
+        hash.update((source.len() as u64).to_be_bytes());
+        hash.update(source.as_bytes());
+        hash.update(bytes);
+        self.state = hash.finalize().into();
+
+        let credited = estimated_bits.unwrap_or(u64::MAX).min(bytes.len() as u64 * 8);
+        self.available_bits = (self.available_bits + credited).min(KEY_BITS);
+        *self.harvested.entry(source.to_string()).or_default() += credited;
+        credited
+    }
+
+    /// 32 bytes for one key, or `None` if the pool cannot back it
+    pub fn draw(&mut self) -> Option<[u8; 32]> {
+        let mut os = [0u8; 32];
+        let os_ok = getrandom::getrandom(&mut os).is_ok();
+        if os_ok && self.allow_os && self.available_bits < KEY_BITS {
+            self.ingest("os", &os, None);
+        }
+        if self.available_bits < KEY_BITS {
+            return None;
+        }
+        self.available_bits -= KEY_BITS;
+
+        // OS randomness is mixed in even when it is not credited
+        let mix = |label: &[u8], state: &[u8; 32]| -> [u8; 32] {
+            let mut hash = Sha256::new();
+            hash.update(label);
+            hash.update(state);
+            if os_ok {
+                hash.update(os);
+            }
+            hash.finalize().into()
+        };
+        let out = mix(b"draw", &self.state);
+        self.state = mix(b"next", &self.state);
+        Some(out)
+    }
+
+    pub fn status(&self) -> PoolStatus {
+        PoolStatus {
+            available_bits: self.available_bits,
+            harvested_bits: self.harvested.clone(),
+            os_fallback: self.allow_os,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStatus {
+    pub available_bits: u64,
+    pub harvested_bits: BTreeMap<String, u64>,
+    pub os_fallback: bool,
+}
+
+/// Short public identifier of a key
+fn fingerprint(key: &[u8; 32]) -> String {
+    let mut hash = Sha256::new();
+    hash.update(b"fingerprint");
+    hash.update(key);
+    hex::encode(&hash.finalize()[..8])
+}
+
+/// HKDF-SHA256 over the previous key, salted with a fresh draw and bound
+/// to the channel and version
+fn derive(channel: &str, version: u64, previous: Option<&[u8; 32]>, fresh: &[u8; 32]) -> [u8; 32] {
+    let hkdf = Hkdf::<Sha256>::new(Some(fresh), previous.map_or(&[][..], |k| &k[..]));
+    let info = format!("sx9-orbital/sideband/{}/v{}", channel, version);
+    let mut key = [0u8; 32];
+    hkdf.expand(info.as_bytes(), &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+struct ChannelKey {
+    version: u64,
+    key: [u8; 32],
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationReason {
+    /// First key for the channel since startup
+    Initial,
+    Scheduled,
+    Manual,
+}
+
+/// Audit record of one rotation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotationEvent {
+    pub sequence: u64,
+    pub channel: String,
+    pub version: u64,
+    pub previous_version: Option<u64>,
+    pub at: DateTime<Utc>,
+    pub reason: RotationReason,
+    /// Principal for manual rotations
+    pub actor: Option<String>,
+    pub fingerprint: String,
+}
+
+/// Key state of one channel as served; never the key itself
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelKeyStatus {
+    pub channel: String,
+    pub version: u64,
+    pub fingerprint: String,
+    pub created_at: DateTime<Utc>,
+    pub rotates_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub encryption_active: bool,
+}
+
+#[derive(Serialize)]
+pub struct KeyringStatus {
+    pub rotation_secs: i64,
+    pub pool: PoolStatus,
+    pub channels: Vec<ChannelKeyStatus>,
+}
+
+struct Inner {
+    pool: EntropyPool,
+    channels: HashMap<String, ChannelKey>,
+    /// Last version per channel, including ones from earlier runs, so
+    /// versions never repeat
+    versions: HashMap<String, u64>,
+}
+
+pub struct Keyring {
+    inner: Mutex<Inner>,
+    rotation: Duration,
+    db: sled::Db,
+    events: sled::Tree,
+}
+
+impl Keyring {
+    pub fn open(db: sled::Db, rotation: Duration, allow_os: bool) -> anyhow::Result<Self> {
+        let events = db.open_tree(EVENTS_TREE)?;
+        let mut versions = HashMap::new();
+        for value in events.iter().values() {
+            let event: RotationEvent = serde_json::from_slice(&value?)?;
+            versions.insert(event.channel, event.version);
+        }
+        Ok(Self {
+            inner: Mutex::new(Inner {
+                pool: EntropyPool::new(allow_os),
+                channels: HashMap::new(),
+                versions,
+            }),
+            rotation,
+            db,
+            events,
+        })
+    }
+
+    pub fn ingest(&self, source: &str, bytes: &[u8], estimated_bits: Option<u64>) -> u64 {
+        self.inner.lock().unwrap().pool.ingest(source, bytes, estimated_bits)
+    }
+
+    /// Replace a channel's key and record the event; `None` if the pool
+    /// cannot back a new key
+    pub fn rotate(
+        &self,
+        channel: &str,
+        reason: RotationReason,
+        actor: Option<String>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Option<RotationEvent>> {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(fresh) = inner.pool.draw() else {
+            return Ok(None);
+        };
+        let previous = inner.channels.get(channel);
+        let previous_version = previous.map(|k| k.version);
+        let version = inner.versions.get(channel).copied().unwrap_or(0) + 1;
+        let key = derive(channel, version, previous.map(|k| &k.key), &fresh);
+
+        let event = RotationEvent {
+            sequence: self.db.generate_id()? + 1,
+            channel: channel.to_string(),
+            version,
+            previous_version,
+            at: now,
+            reason,
+            actor,
+            fingerprint: fingerprint(&key),
+        };
+        self.events.insert(event.sequence.to_be_bytes(), serde_json::to_vec(&event)?)?;
+        self.events.flush()?;
+
+        inner.versions.insert(channel.to_string(), version);
+        inner.channels.insert(
+            channel.to_string(),
+            ChannelKey {
+                version,
+                key,
+                created_at: now,
+            },
+        );
+        Ok(Some(event))
+    }
+
+    /// Key `channels` that have none and rotate keys that are due
+    pub fn rotate_due(&self, channels: &[String], now: DateTime<Utc>) -> anyhow::Result<Vec<RotationEvent>> {
+        let mut events = Vec::new();
+        for channel in channels {
+            let reason = match self.inner.lock().unwrap().channels.get(channel) {
+                None => RotationReason::Initial,
+                Some(key) if now - key.created_at >= self.rotation => RotationReason::Scheduled,
+                Some(_) => continue,
+            };
+            match self.rotate(channel, reason, None, now)? {
+                Some(event) => events.push(event),
+                None => break,
+            }
+        }
+        Ok(events)
+    }
+
+    /// Version of the key currently used on `channel`, if one is live
+    pub fn current_version(&self, channel: &str, now: DateTime<Utc>) -> Option<u64> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .channels
+            .get(channel)
+            .filter(|k| now < k.created_at + self.rotation * 2)
+            .map(|k| k.version)
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> KeyringStatus {
+        let inner = self.inner.lock().unwrap();
+        let mut channels: Vec<ChannelKeyStatus> = inner
+            .channels
+            .iter()
+            .map(|(channel, k)| {
+                let expires_at = k.created_at + self.rotation * 2;
+                ChannelKeyStatus {
+                    channel: channel.clone(),
+                    version: k.version,
+                    fingerprint: fingerprint(&k.key),
+                    created_at: k.created_at,
+                    rotates_at: k.created_at + self.rotation,
+                    expires_at,
+                    encryption_active: now < expires_at,
+                }
+            })
+            .collect();
+        channels.sort_by(|a, b| a.channel.cmp(&b.channel));
+        KeyringStatus {
+            rotation_secs: self.rotation.num_seconds(),
+            pool: inner.pool.status(),
+            channels,
+        }
+    }
+
+    /// Newest first, optionally for one channel
+    pub fn events(&self, channel: Option<&str>, limit: usize) -> anyhow::Result<Vec<RotationEvent>> {
+        let mut events = Vec::new();
+        for value in self.events.iter().rev().values() {
+            let event: RotationEvent = serde_json::from_slice(&value?)?;
+            if channel.is_none_or(|c| c == event.channel) {
+                events.push(event);
+                if events.len() == limit {
+                    break;
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
+async fn publish(state: &AppState, event: &RotationEvent) {
+    tracing::info!(
+        "Channel {} key v{} ({:?}, {})",
+        event.channel,
+        event.version,
+        event.reason,
+        event.fingerprint
+    );
+    if let Some(telemetry) = &state.telemetry {
+        if let Err(e) = telemetry.publish_key_rotation(&event.channel, event).await {
+            tracing::warn!("Key rotation publish failed: {}", e);
+        }
+    }
+}
+
+/// Keep every sideband channel keyed for the lifetime of the gateway
+pub async fn run(state: AppState) {
+    let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+    let mut starved = false;
+    loop {
+        ticker.tick().await;
+        let mut channels = state.sideband.channels();
+        channels.extend(state.constellation.load().operational_stations().map(|s| s.id.clone()));
+        channels.sort();
+        channels.dedup();
+
+        let now = Utc::now();
+        match state.keyring.rotate_due(&channels, now) {
+            Ok(events) => {
+                for event in &events {
+                    publish(&state, event).await;
+                }
+                let due = channels
+                    .iter()
+                    .filter(|c| state.keyring.current_version(c, now).is_none())
+                    .count();
+                if due > 0 && !starved {
+                    tracing::warn!("Entropy pool exhausted: {} channels without a live key", due);
+                }
+                starved = due > 0;
+            }
+            Err(e) => tracing::error!("Key rotation failed: {}", e),
+        }
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Deserialize)]
+pub struct EntropyRequest {
+    /// Harvester identifier, e.g. the station ID
+    pub source: String,
+    /// Raw harvested bytes, hex
+    pub hex: String,
+    /// Min-entropy estimate; defaults to 8 bits per byte
+    pub estimated_bits: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct EntropyReceipt {
+    pub credited_bits: u64,
+    pub pool: PoolStatus,
+}
+
+#[derive(Deserialize)]
+pub struct EventsQuery {
+    pub channel: Option<String>,
+    pub limit: Option<usize>,
+}
+
+fn internal(e: anyhow::Error) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// GET /keyring - pool level and per-channel key versions
+pub async fn status(State(state): State<AppState>) -> Json<KeyringStatus> {
+    Json(state.keyring.status(Utc::now()))
+}
+
+/// GET /keyring/events?channel=GS-001&limit=100 - rotation audit log
+pub async fn events(
+    State(state): State<AppState>,
+    Query(q): Query<EventsQuery>,
+) -> Result<Json<Vec<RotationEvent>>, (StatusCode, String)> {
+    state
+        .keyring
+        .events(q.channel.as_deref(), q.limit.unwrap_or(DEFAULT_EVENT_LIMIT))
+        .map(Json)
+        .map_err(internal)
+}
+
+/// POST /keyring/entropy - feed harvested entropy into the pool
+pub async fn ingest_entropy(
+    State(state): State<AppState>,
+    Json(req): Json<EntropyRequest>,
+) -> Result<Json<EntropyReceipt>, (StatusCode, String)> {
+    let bytes = hex::decode(&req.hex).map_err(|e| (StatusCode::BAD_REQUEST, format!("hex: {}", e)))?;
+    if req.source.is_empty() || bytes.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "source and entropy bytes are required".to_string(),
+        ));
+    }
+    let credited_bits = state.keyring.ingest(&req.source, &bytes, req.estimated_bits);
+    Ok(Json(EntropyReceipt {
+        credited_bits,
+        pool: state.keyring.status(Utc::now()).pool,
+    }))
+}
+
+/// POST /keyring/:channel/rotate - rotate now, e.g. on suspected compromise
+pub async fn rotate_channel(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(channel): Path<String>,
+) -> Result<Json<RotationEvent>, (StatusCode, String)> {
+    let event = state
+        .keyring
+        .rotate(&channel, RotationReason::Manual, principal.map(|Extension(p)| p.subject), Utc::now())
+        .map_err(internal)?
+        .ok_or((StatusCode::SERVICE_UNAVAILABLE, "Entropy pool exhausted".to_string()))?;
+    publish(&state, &event).await;
+    Ok(Json(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyring(allow_os: bool) -> Keyring {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        Keyring::open(db, Duration::minutes(5), allow_os).unwrap()
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_harvested_only_pool_needs_credit() {
+        let mut pool = EntropyPool::new(false);
+        assert!(pool.draw().is_none());
+        // A low estimate credits less than the byte count
+        assert_eq!(pool.ingest("GS-001", &[7u8; 64], Some(128)), 128);
+        assert!(pool.draw().is_none());
+        pool.ingest("GS-002", &[9u8; 16], None);
+        let a = pool.draw().unwrap();
+        assert!(pool.draw().is_none());
+        pool.ingest("GS-001", &[7u8; 32], None);
+        assert_ne!(pool.draw().unwrap(), a);
+        assert_eq!(pool.status().harvested_bits["GS-001"], 384);
+    }
+
+    #[test]
+    fn test_scheduled_rotation_ratchets_versions() {
+        let keyring = keyring(true);
+        let channels = vec!["GS-001".to_string(), "GS-002".to_string()];
+
+        let first = keyring.rotate_due(&channels, at(0)).unwrap();
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(|e| e.reason == RotationReason::Initial && e.version == 1));
+        assert!(keyring.rotate_due(&channels, at(299)).unwrap().is_empty());
+
+        let second = keyring.rotate_due(&channels, at(300)).unwrap();
+        assert_eq!(second[0].reason, RotationReason::Scheduled);
+        assert_eq!((second[0].previous_version, second[0].version), (Some(1), 2));
+        assert_ne!(second[0].fingerprint, first[0].fingerprint);
+        assert_eq!(keyring.current_version("GS-001", at(301)), Some(2));
+
+        // Audit log, newest first, filterable by channel
+        let log = keyring.events(Some("GS-001"), 10).unwrap();
+        assert_eq!(log.iter().map(|e| e.version).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(keyring.events(None, 10).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_keys_expire_without_entropy() {
+        let keyring = keyring(false);
+        keyring.ingest("GS-001", &[1u8; 32], None);
+        let channels = vec!["GS-001".to_string()];
+        assert_eq!(keyring.rotate_due(&channels, at(0)).unwrap().len(), 1);
+
+        // Due at 5 min but the pool is dry; the key lapses after 10 min
+        assert!(keyring.rotate_due(&channels, at(300)).unwrap().is_empty());
+        assert!(keyring.status(at(599)).channels[0].encryption_active);
+        assert!(!keyring.status(at(600)).channels[0].encryption_active);
+        assert_eq!(keyring.current_version("GS-001", at(600)), None);
+    }
+
+    #[test]
+    fn test_versions_survive_restart() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let keyring = Keyring::open(db.clone(), Duration::minutes(5), true).unwrap();
+        keyring.rotate("GS-001", RotationReason::Manual, Some("ops".into()), at(0)).unwrap();
+        drop(keyring);
+
+        let reopened = Keyring::open(db, Duration::minutes(5), true).unwrap();
+        let event = reopened.rotate("GS-001", RotationReason::Initial, None, at(10)).unwrap().unwrap();
+        assert_eq!((event.previous_version, event.version), (None, 2));
+    }
+}
@@ -0,0 +1,756 @@
+//! Key-refresh pass scheduling
+//!
+//! Plans which predicted passes each station uses to refresh its key
+//! inventory over the next window (24 h by default), with each pass's
+//! yield from the key-rate model (`keyrate`) at the requested QoS tier.
+//! Stations are served
+//! lowest fill ratio first, one pass at a time, so a nearly empty station
+//! gets the best pass before a nearly full one gets a second. A satellite
+//! terminal can only hold one key session at a time, and passes that
+//! overlap a protected data pass (on the same station, or on the same
+//! satellite when one is named) are never used.
+//!
+//! Stations registered with `PUT /keys/:station` get a tracked key store:
+//! inventory, minimum reserve and target. Operators report encrypted
+//! traffic through `POST /keys/:station/consume` (both ends of a route
+//! spend the same key material); route queries never debit it. The
+//! trailing hour of draw-down gives the consumption rate. Applying a
+//! schedule books its passes; each propagation tick credits passes whose
+//! LOS has gone by (unless a chaos fault failed the link) and raises an
+//! alert when inventory is projected to fall below reserve before the
+//! next booked pass. Unregistered stations can still be planned by
+//! supplying inventories in the request.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+use crate::chaos::FaultEffect;
+use crate::keyrate::{key_candidates, KeyCandidate, QosTier};
+use crate::memory::MemoryKind;
+use crate::propagation::MIN_LINK_ELEVATION_DEG;
+use crate::AppState;
+
+/// Passes yielding less than this are not worth slewing for (kbit)
+const MIN_PASS_YIELD_KBIT: f64 = 50.0;
+
+const DEFAULT_HOURS: i64 = 24;
+const MAX_HOURS: i64 = 72;
+
+/// Trailing window the consumption rate is averaged over
+const CONSUMPTION_WINDOW_S: i64 = 3600;
+
+/// How far ahead to look for a reserve breach when no pass is booked
+const PROJECTION_HORIZON_H: i64 = 24;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StationInventory {
+    pub station_id: String,
+    /// Key material currently held (kbit)
+    pub inventory_kbit: f64,
+    /// Inventory to reach by the end of the window (kbit)
+    pub target_kbit: f64,
+}
+
+/// A high-value data pass key refresh must not collide with
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProtectedPass {
+    pub station_id: String,
+    /// Also blocks this satellite's terminal toward other stations
+    pub satellite_id: Option<String>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct ScheduleRequest {
+    /// Defaults to every tracked station's current inventory
+    #[serde(default)]
+    pub stations: Vec<StationInventory>,
+    #[serde(default)]
+    pub protected: Vec<ProtectedPass>,
+    pub from: Option<DateTime<Utc>>,
+    pub hours: Option<i64>,
+    pub min_elevation_deg: Option<f64>,
+    #[serde(default)]
+    pub tier: QosTier,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyPass {
+    pub satellite_id: String,
+    pub aos: DateTime<Utc>,
+    pub los: DateTime<Utc>,
+    pub max_elevation_deg: f64,
+    pub predicted_margin_db: f64,
+    pub expected_kbit: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StationPlan {
+    pub station_id: String,
+    pub inventory_kbit: f64,
+    pub target_kbit: f64,
+    pub projected_kbit: f64,
+    /// Shortfall left at the end of the window
+    pub deficit_kbit: f64,
+    /// Usable passes skipped because they overlapped a protected pass
+    pub protected_conflicts: usize,
+    pub passes: Vec<KeyPass>,
+}
+
+#[derive(Serialize)]
+pub struct KeySchedule {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub tier: QosTier,
+    /// Ordered lowest starting fill ratio first
+    pub stations: Vec<StationPlan>,
+}
+
+fn overlaps(a: (DateTime<Utc>, DateTime<Utc>), b: (DateTime<Utc>, DateTime<Utc>)) -> bool {
+    a.0 < b.1 && b.0 < a.1
+}
+
+fn fill_ratio(inventory: f64, target: f64) -> f64 {
+    if target > 0.0 {
+        inventory / target
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Assign passes to stations; `candidates` maps station ID to its
+/// predicted passes over the window
+pub fn plan(
+    stations: &[StationInventory],
+    mut candidates: HashMap<String, Vec<KeyCandidate>>,
+    protected: &[ProtectedPass],
+) -> Vec<StationPlan> {
+    let mut plans: Vec<StationPlan> = stations
+        .iter()
+        .map(|s| {
+            let passes = candidates.entry(s.station_id.clone()).or_default();
+            passes.retain(|c| c.expected_kbit >= MIN_PASS_YIELD_KBIT);
+            let before = passes.len();
+            passes.retain(|KeyCandidate { pass: p, .. }| {
+                !protected.iter().any(|d| {
+                    let shared = d.station_id == s.station_id
+                        || d.satellite_id.as_deref() == Some(p.satellite_id.as_str());
+                    shared && overlaps((p.aos, p.los), (d.from, d.to))
+                })
+            });
+            StationPlan {
+                station_id: s.station_id.clone(),
+                inventory_kbit: s.inventory_kbit,
+                target_kbit: s.target_kbit,
+                projected_kbit: s.inventory_kbit,
+                deficit_kbit: 0.0,
+                protected_conflicts: before - passes.len(),
+                passes: Vec::new(),
+            }
+        })
+        .collect();
+    plans.sort_by(|a, b| {
+        fill_ratio(a.inventory_kbit, a.target_kbit).total_cmp(&fill_ratio(b.inventory_kbit, b.target_kbit))
+    });
+
+    // Key sessions already booked per satellite terminal
+    let mut booked: HashMap<String, Vec<(DateTime<Utc>, DateTime<Utc>)>> = HashMap::new();
+    let mut exhausted: HashSet<usize> = HashSet::new();
+
+    loop {
+        let next = plans
+            .iter()
+            .enumerate()
+            .filter(|(i, p)| !exhausted.contains(i) && p.projected_kbit < p.target_kbit)
+            .min_by(|(_, a), (_, b)| {
+                fill_ratio(a.projected_kbit, a.target_kbit).total_cmp(&fill_ratio(b.projected_kbit, b.target_kbit))
+            })
+            .map(|(i, _)| i);
+        let Some(i) = next else { break };
+
+        let station = &mut plans[i];
+        let remaining = candidates.entry(station.station_id.clone()).or_default();
+        let best = remaining
+            .iter()
+            .enumerate()
+            .filter(|(_, KeyCandidate { pass: p, .. })| {
+                !station.passes.iter().any(|k| overlaps((p.aos, p.los), (k.aos, k.los)))
+                    && !booked
+                        .get(&p.satellite_id)
+                        .is_some_and(|b| b.iter().any(|w| overlaps((p.aos, p.los), *w)))
+            })
+            .max_by(|(_, a), (_, b)| a.expected_kbit.total_cmp(&b.expected_kbit))
+            .map(|(j, _)| j);
+        let Some(j) = best else {
+            exhausted.insert(i);
+            continue;
+        };
+
+        let KeyCandidate { pass, expected_kbit } = remaining.swap_remove(j);
+        booked
+            .entry(pass.satellite_id.clone())
+            .or_default()
+            .push((pass.aos, pass.los));
+        station.projected_kbit += expected_kbit;
+        station.passes.push(KeyPass {
+            satellite_id: pass.satellite_id,
+            aos: pass.aos,
+            los: pass.los,
+            max_elevation_deg: pass.max_elevation_deg,
+            predicted_margin_db: pass.predicted_margin_db,
+            expected_kbit,
+        });
+    }
+
+    for station in &mut plans {
+        station.passes.sort_by_key(|p| p.aos);
+        station.deficit_kbit = (station.target_kbit - station.projected_kbit).max(0.0);
+    }
+    plans
+}
+
+/// Tracked key store for one station
+#[derive(Debug, Clone)]
+struct StationKeys {
+    inventory_kbit: f64,
+    reserve_kbit: f64,
+    target_kbit: f64,
+    /// Recent draw-down (sim time, kbit)
+    consumed: VecDeque<(DateTime<Utc>, f64)>,
+    /// Booked passes not yet completed, by AOS
+    passes: Vec<KeyPass>,
+    /// Whether the reserve alert is currently raised
+    alerting: bool,
+}
+
+impl StationKeys {
+    fn consumption_kbps(&self, now: DateTime<Utc>) -> f64 {
+        let since = now - Duration::seconds(CONSUMPTION_WINDOW_S);
+        let total: f64 = self
+            .consumed
+            .iter()
+            .filter(|(t, _)| *t > since)
+            .map(|(_, kbit)| kbit)
+            .sum();
+        total / CONSUMPTION_WINDOW_S as f64
+    }
+
+    fn projection(&self, station_id: &str, now: DateTime<Utc>) -> KeyProjection {
+        let rate = self.consumption_kbps(now);
+        let next_pass = self.passes.iter().find(|p| p.los > now);
+        let until = next_pass
+            .map(|p| p.aos.max(now))
+            .unwrap_or(now + Duration::hours(PROJECTION_HORIZON_H));
+        let projected = self.inventory_kbit - rate * (until - now).num_seconds() as f64;
+
+        let reserve_breach_at = if self.inventory_kbit <= self.reserve_kbit {
+            Some(now)
+        } else if projected < self.reserve_kbit && rate > 0.0 {
+            let secs = (self.inventory_kbit - self.reserve_kbit) / rate;
+            Some(now + Duration::seconds(secs as i64))
+        } else {
+            None
+        };
+
+        KeyProjection {
+            station_id: station_id.to_string(),
+            inventory_kbit: self.inventory_kbit,
+            reserve_kbit: self.reserve_kbit,
+            target_kbit: self.target_kbit,
+            consumption_kbps: rate,
+            next_pass_aos: next_pass.map(|p| p.aos),
+            projected_kbit: projected.max(0.0),
+            reserve_breach_at,
+        }
+    }
+}
+
+/// Station key inventory with its outlook up to the next booked pass
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyProjection {
+    pub station_id: String,
+    pub inventory_kbit: f64,
+    pub reserve_kbit: f64,
+    pub target_kbit: f64,
+    /// Average over the trailing hour
+    pub consumption_kbps: f64,
+    pub next_pass_aos: Option<DateTime<Utc>>,
+    /// Inventory at the next pass (or the projection horizon)
+    pub projected_kbit: f64,
+    /// When inventory drops below reserve, if before the next pass
+    pub reserve_breach_at: Option<DateTime<Utc>>,
+}
+
+/// Published on `orbital.keys.{station}` when an alert is raised or cleared
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyAlert {
+    pub raised: bool,
+    #[serde(flatten)]
+    pub projection: KeyProjection,
+}
+
+/// A booked pass credited (or lost to a fault) at LOS
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletedPass {
+    pub station_id: String,
+    pub credited_kbit: f64,
+    #[serde(flatten)]
+    pub pass: KeyPass,
+}
+
+#[derive(Default)]
+pub struct KeyStore {
+    stations: Mutex<HashMap<String, StationKeys>>,
+}
+
+impl KeyStore {
+    /// Register or update a station; inventory is kept if not given
+    pub fn configure(&self, station_id: &str, update: KeyStoreUpdate) {
+        let mut stations = self.stations.lock().unwrap();
+        let keys = stations.entry(station_id.to_string()).or_insert_with(|| StationKeys {
+            inventory_kbit: 0.0,
+            reserve_kbit: 0.0,
+            target_kbit: 0.0,
+            consumed: VecDeque::new(),
+            passes: Vec::new(),
+            alerting: false,
+        });
+        if let Some(inventory) = update.inventory_kbit {
+            keys.inventory_kbit = inventory;
+        }
+        keys.reserve_kbit = update.reserve_kbit;
+        keys.target_kbit = update.target_kbit;
+    }
+
+    /// Draw down a tracked station; returns false if it is not tracked
+    pub fn consume(&self, station_id: &str, kbit: f64, now: DateTime<Utc>) -> bool {
+        let mut stations = self.stations.lock().unwrap();
+        let Some(keys) = stations.get_mut(station_id) else {
+            return false;
+        };
+        keys.inventory_kbit = (keys.inventory_kbit - kbit).max(0.0);
+        keys.consumed.push_back((now, kbit));
+        let since = now - Duration::seconds(CONSUMPTION_WINDOW_S);
+        while keys.consumed.front().is_some_and(|(t, _)| *t <= since) {
+            keys.consumed.pop_front();
+        }
+        true
+    }
+
+    /// Replace the booked passes of each planned, tracked station
+    pub fn book(&self, plans: &[StationPlan]) {
+        let mut stations = self.stations.lock().unwrap();
+        for plan in plans {
+            if let Some(keys) = stations.get_mut(&plan.station_id) {
+                keys.passes = plan.passes.clone();
+            }
+        }
+    }
+
+    /// Passes booked for a tracked station, not yet credited
+    pub fn booked(&self, station_id: &str) -> Vec<KeyPass> {
+        let stations = self.stations.lock().unwrap();
+        stations.get(station_id).map(|keys| keys.passes.clone()).unwrap_or_default()
+    }
+
+    /// Current inventories as scheduler input
+    pub fn inventories(&self) -> Vec<StationInventory> {
+        let stations = self.stations.lock().unwrap();
+        let mut inventories: Vec<StationInventory> = stations
+            .iter()
+            .map(|(id, keys)| StationInventory {
+                station_id: id.clone(),
+                inventory_kbit: keys.inventory_kbit,
+                target_kbit: keys.target_kbit,
+            })
+            .collect();
+        inventories.sort_by(|a, b| a.station_id.cmp(&b.station_id));
+        inventories
+    }
+
+    pub fn projection(&self, station_id: &str, now: DateTime<Utc>) -> Option<KeyProjection> {
+        let stations = self.stations.lock().unwrap();
+        stations.get(station_id).map(|keys| keys.projection(station_id, now))
+    }
+
+    pub fn projections(&self, now: DateTime<Utc>) -> Vec<KeyProjection> {
+        let stations = self.stations.lock().unwrap();
+        let mut projections: Vec<KeyProjection> = stations
+            .iter()
+            .map(|(id, keys)| keys.projection(id, now))
+            .collect();
+        projections.sort_by(|a, b| a.station_id.cmp(&b.station_id));
+        projections
+    }
+
+    /// Credit passes whose LOS is at or before `now` (`failed` says whether
+    /// a pass was lost) and return them plus any alert changes
+    pub fn advance(
+        &self,
+        now: DateTime<Utc>,
+        failed: impl Fn(&str, &KeyPass) -> bool,
+    ) -> (Vec<CompletedPass>, Vec<KeyAlert>) {
+        let mut stations = self.stations.lock().unwrap();
+        let mut completed = Vec::new();
+        let mut alerts = Vec::new();
+
+        for (id, keys) in stations.iter_mut() {
+            let (done, pending): (Vec<KeyPass>, Vec<KeyPass>) =
+                keys.passes.drain(..).partition(|p| p.los <= now);
+            keys.passes = pending;
+            for pass in done {
+                let credited_kbit = if failed(id, &pass) { 0.0 } else { pass.expected_kbit };
+                keys.inventory_kbit += credited_kbit;
+                completed.push(CompletedPass {
+                    station_id: id.clone(),
+                    credited_kbit,
+                    pass,
+                });
+            }
+
+            let projection = keys.projection(id, now);
+            let breach = projection.reserve_breach_at.is_some();
+            if breach != keys.alerting {
+                keys.alerting = breach;
+                alerts.push(KeyAlert {
+                    raised: breach,
+                    projection,
+                });
+            }
+        }
+        (completed, alerts)
+    }
+}
+
+/// Credit completed passes and publish alert changes at sim time `now`
+pub async fn tick(state: &AppState, now: DateTime<Utc>) {
+    let faults = state.chaos.active_at(now);
+    let (completed, alerts) = state.keys.advance(now, |station, pass| {
+        faults.link(&pass.satellite_id, station) == Some(FaultEffect::Fail)
+    });
+
+    for pass in &completed {
+        tracing::info!(
+            "Key pass {} -> {} complete: +{:.0} kbit",
+            pass.pass.satellite_id,
+            pass.station_id,
+            pass.credited_kbit
+        );
+    }
+    for alert in &alerts {
+        let summary = format!(
+            "{} key inventory {:.0} kbit projected {} reserve {:.0} kbit",
+            alert.projection.station_id,
+            alert.projection.inventory_kbit,
+            if alert.raised { "below" } else { "back above" },
+            alert.projection.reserve_kbit
+        );
+        if alert.raised {
+            tracing::warn!("{}", summary);
+        }
+        let subjects = vec![alert.projection.station_id.clone()];
+        state.memory.remember(now, MemoryKind::Alert, subjects, summary, alert);
+        if let Some(telemetry) = &state.telemetry {
+            if let Err(e) = telemetry.publish_key_alert(&alert.projection.station_id, alert).await {
+                tracing::warn!("Key alert publish failed: {}", e);
+            }
+        }
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Deserialize)]
+pub struct KeyStoreUpdate {
+    pub inventory_kbit: Option<f64>,
+    pub reserve_kbit: f64,
+    pub target_kbit: f64,
+}
+
+#[derive(Deserialize)]
+pub struct ConsumeRequest {
+    pub kbit: f64,
+}
+
+fn build_schedule(state: &AppState, mut req: ScheduleRequest) -> Result<KeySchedule, (StatusCode, String)> {
+    if req.stations.is_empty() {
+        req.stations = state.keys.inventories();
+    }
+    let mut seen = HashSet::new();
+    for s in &req.stations {
+        if !seen.insert(s.station_id.as_str()) {
+            return Err((StatusCode::BAD_REQUEST, format!("Duplicate station: {}", s.station_id)));
+        }
+        if s.inventory_kbit.is_nan() || s.inventory_kbit < 0.0 || s.target_kbit.is_nan() || s.target_kbit <= 0.0 {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("{}: inventory must be >= 0 and target > 0", s.station_id),
+            ));
+        }
+    }
+
+    let from = req.from.unwrap_or_else(|| state.clock.now());
+    let to = from + Duration::hours(req.hours.unwrap_or(DEFAULT_HOURS).clamp(1, MAX_HOURS));
+    let min_el = req.min_elevation_deg.unwrap_or(MIN_LINK_ELEVATION_DEG);
+
+    let constellation = state.constellation.load();
+    let mut candidates = HashMap::new();
+    for s in &req.stations {
+        let station = constellation
+            .station(&s.station_id)
+            .ok_or((StatusCode::NOT_FOUND, format!("Station not found: {}", s.station_id)))?;
+        candidates.insert(
+            s.station_id.clone(),
+            key_candidates(state, station, from, to, min_el, req.tier),
+        );
+    }
+
+    Ok(KeySchedule {
+        from,
+        to,
+        tier: req.tier,
+        stations: plan(&req.stations, candidates, &req.protected),
+    })
+}
+
+/// POST /keys/schedule - plan without booking
+pub async fn schedule_key_refresh(
+    State(state): State<AppState>,
+    Json(req): Json<ScheduleRequest>,
+) -> Result<Json<KeySchedule>, (StatusCode, String)> {
+    build_schedule(&state, req).map(Json)
+}
+
+/// POST /keys/schedule/apply - plan and book passes for tracked stations
+pub async fn apply_key_schedule(
+    State(state): State<AppState>,
+    Json(req): Json<ScheduleRequest>,
+) -> Result<Json<KeySchedule>, (StatusCode, String)> {
+    let schedule = build_schedule(&state, req)?;
+    state.keys.book(&schedule.stations);
+    Ok(Json(schedule))
+}
+
+/// GET /keys
+pub async fn list_inventories(State(state): State<AppState>) -> Json<Vec<KeyProjection>> {
+    Json(state.keys.projections(state.clock.now()))
+}
+
+/// PUT /keys/:station
+pub async fn configure_station(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(update): Json<KeyStoreUpdate>,
+) -> Result<Json<KeyProjection>, (StatusCode, String)> {
+    if state.constellation.load().station(&id).is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("Station not found: {}", id)));
+    }
+    let valid = update.inventory_kbit.unwrap_or(0.0) >= 0.0
+        && update.reserve_kbit >= 0.0
+        && update.target_kbit > update.reserve_kbit;
+    if !valid {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "inventory and reserve must be >= 0 and target above reserve".to_string(),
+        ));
+    }
+    state.keys.configure(&id, update);
+    state
+        .keys
+        .projection(&id, state.clock.now())
+        .map(Json)
+        .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Key store update lost".to_string()))
+}
+
+/// POST /keys/:station/consume - report key material spent
+pub async fn consume(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<ConsumeRequest>,
+) -> Result<Json<KeyProjection>, (StatusCode, String)> {
+    if req.kbit.is_nan() || req.kbit < 0.0 {
+        return Err((StatusCode::BAD_REQUEST, "kbit must be >= 0".to_string()));
+    }
+    let now = state.clock.now();
+    if !state.keys.consume(&id, req.kbit, now) {
+        return Err((StatusCode::NOT_FOUND, format!("No key store for station: {}", id)));
+    }
+    state
+        .keys
+        .projection(&id, now)
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, format!("No key store for station: {}", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::passes::PredictedPass;
+    use orbital_mechanics::eclipse::SunGeometry;
+
+    fn at(min: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + Duration::minutes(min)
+    }
+
+    /// A 10-minute pass yielding 1200 kbit
+    fn pass(sat: &str, start_min: i64) -> KeyCandidate {
+        let pass = PredictedPass {
+            satellite_id: sat.into(),
+            norad_id: 0,
+            aos: at(start_min),
+            los: at(start_min + 10),
+            tca: at(start_min + 5),
+            duration_sec: 600.0,
+            max_elevation_deg: 60.0,
+            aos_azimuth_deg: 0.0,
+            los_azimuth_deg: 180.0,
+            predicted_margin_db: 10.0,
+            sun: SunGeometry {
+                sun_elevation_deg: -30.0,
+                phase_angle_deg: 90.0,
+                sun_separation_deg: 120.0,
+                daytime_background: false,
+            },
+            min_sun_separation_deg: 120.0,
+            predicted_fso_quality: 1.0,
+            acquisition_probability: 1.0,
+        };
+        KeyCandidate {
+            pass,
+            expected_kbit: 1200.0,
+        }
+    }
+
+    fn inventory(id: &str, inventory_kbit: f64, target_kbit: f64) -> StationInventory {
+        StationInventory {
+            station_id: id.into(),
+            inventory_kbit,
+            target_kbit,
+        }
+    }
+
+    #[test]
+    fn test_low_inventory_wins_shared_satellite() {
+        let stations = [inventory("GS-FULL", 900.0, 1000.0), inventory("GS-EMPTY", 0.0, 1000.0)];
+        let candidates = HashMap::from([
+            ("GS-FULL".to_string(), vec![pass("SAT-1", 0)]),
+            ("GS-EMPTY".to_string(), vec![pass("SAT-1", 5)]),
+        ]);
+
+        let plans = plan(&stations, candidates, &[]);
+        assert_eq!(plans[0].station_id, "GS-EMPTY");
+        assert_eq!(plans[0].passes.len(), 1);
+        assert_eq!(plans[0].deficit_kbit, 0.0);
+        // SAT-1 is busy with GS-EMPTY for the overlapping pass
+        assert!(plans[1].passes.is_empty());
+        assert!((plans[1].deficit_kbit - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stops_at_target() {
+        let stations = [inventory("GS-A", 0.0, 2000.0)];
+        let candidates = HashMap::from([(
+            "GS-A".to_string(),
+            vec![pass("SAT-1", 0), pass("SAT-2", 60), pass("SAT-3", 120)],
+        )]);
+
+        let plans = plan(&stations, candidates, &[]);
+        assert_eq!(plans[0].passes.len(), 2);
+        assert!(plans[0].passes[0].aos < plans[0].passes[1].aos);
+        assert_eq!(plans[0].deficit_kbit, 0.0);
+    }
+
+    fn key_pass(start_min: i64, expected_kbit: f64) -> KeyPass {
+        let p = pass("SAT-1", start_min).pass;
+        KeyPass {
+            satellite_id: p.satellite_id,
+            aos: p.aos,
+            los: p.los,
+            max_elevation_deg: p.max_elevation_deg,
+            predicted_margin_db: p.predicted_margin_db,
+            expected_kbit,
+        }
+    }
+
+    fn tracked(inventory_kbit: f64, reserve_kbit: f64) -> KeyStore {
+        let store = KeyStore::default();
+        store.configure(
+            "GS-A",
+            KeyStoreUpdate {
+                inventory_kbit: Some(inventory_kbit),
+                reserve_kbit,
+                target_kbit: 10_000.0,
+            },
+        );
+        store
+    }
+
+    #[test]
+    fn test_consumption_rate_and_breach() {
+        let store = tracked(5000.0, 1000.0);
+        // 3600 kbit over the trailing hour: 1 kbit/s
+        store.consume("GS-A", 1800.0, at(-30));
+        store.consume("GS-A", 1800.0, at(0));
+        assert!(!store.consume("GS-B", 1.0, at(0)));
+
+        let projection = store.projection("GS-A", at(0)).unwrap();
+        assert_eq!(projection.inventory_kbit, 1400.0);
+        assert!((projection.consumption_kbps - 1.0).abs() < 1e-9);
+        // 400 kbit above reserve at 1 kbit/s, no pass booked
+        assert_eq!(projection.reserve_breach_at, Some(at(0) + Duration::seconds(400)));
+    }
+
+    #[test]
+    fn test_advance_credits_passes_and_toggles_alert() {
+        let store = tracked(500.0, 1000.0);
+        let mut plan = plan(&[inventory("GS-A", 500.0, 10_000.0)], HashMap::new(), &[]);
+        plan[0].passes = vec![key_pass(0, 800.0), key_pass(60, 800.0)];
+        store.book(&plan);
+
+        let (completed, alerts) = store.advance(at(5), |_, _| false);
+        assert!(completed.is_empty());
+        assert!(alerts[0].raised);
+
+        let (completed, alerts) = store.advance(at(15), |_, _| false);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].credited_kbit, 800.0);
+        assert!(!alerts[0].raised);
+
+        // A failed link credits nothing
+        let (completed, _) = store.advance(at(75), |_, _| true);
+        assert_eq!(completed[0].credited_kbit, 0.0);
+        assert_eq!(store.projection("GS-A", at(75)).unwrap().inventory_kbit, 1300.0);
+    }
+
+    #[test]
+    fn test_protected_passes_are_avoided() {
+        let stations = [inventory("GS-A", 0.0, 5000.0), inventory("GS-B", 0.0, 5000.0)];
+        let candidates = HashMap::from([
+            ("GS-A".to_string(), vec![pass("SAT-1", 0), pass("SAT-2", 60)]),
+            ("GS-B".to_string(), vec![pass("SAT-3", 200)]),
+        ]);
+        let protected = [ProtectedPass {
+            station_id: "GS-A".into(),
+            satellite_id: Some("SAT-3".into()),
+            from: at(55),
+            to: at(210),
+        }];
+
+        let plans = plan(&stations, candidates, &protected);
+        let a = plans.iter().find(|p| p.station_id == "GS-A").unwrap();
+        let b = plans.iter().find(|p| p.station_id == "GS-B").unwrap();
+        assert_eq!(a.protected_conflicts, 1);
+        assert_eq!(a.passes.len(), 1);
+        assert_eq!(a.passes[0].satellite_id, "SAT-1");
+        assert_eq!(b.protected_conflicts, 1);
+        assert!(b.passes.is_empty());
+    }
+}
@@ -1,11 +1,12 @@
 use anyhow::Result;
 use axum::{
     extract::State,
-    routing::{get, post},
+    middleware,
+    routing::{delete, get, post, put},
     Json, Router,
 };
-use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
 use tower_http::{
     cors::CorsLayer,
     services::ServeDir,
@@ -13,28 +14,164 @@ use tower_http::{
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 // Import ground station WASM types for API
-use ground_station_wasm::{
-    stations::{load_strategic_stations, NetworkStation, StationStats},
-    downselect::{Downselect, ScoringWeights, DownselectSummary},
-};
+use ground_station_wasm::stations::{load_strategic_stations, NetworkStation, StationStats};
 use ground_stations::StationRegistry;
+use orbital_mechanics::SatelliteStatus;
 
 mod routes;
 mod memory;
+mod auth;
+mod bus;
+mod catalog;
+mod chaos;
+mod checkpoint;
+mod clock;
+mod codec;
+mod commands;
+mod config;
+mod constellations;
+mod contacts;
+mod detail;
+mod downselect;
+mod ephemeris;
+mod events;
+mod fleet;
+mod grpc;
+mod health;
+mod history;
+mod keyrate;
+mod keyring;
+mod keys;
+mod passes;
+mod propagation;
+mod ratelimit;
+mod scenario;
+mod schema;
+mod sideband;
+mod stationkeeping;
+mod stream;
+mod telemetry;
+mod tle;
+mod topology;
+mod weather;
+
+use auth::{AuthConfig, Role, RoleGuard};
+use ratelimit::{CostTier, RateLimitGuard, RateLimiter};
+use config::{ConstellationConfig, GatewayConfig};
 
 #[derive(Clone)]
 pub struct AppState {
-    pub constellation: Arc<ConstellationState>,
+    pub config: Arc<GatewayConfig>,
+    pub constellation: Arc<SharedConstellation>,
     pub strategic_stations: Arc<Vec<NetworkStation>>,
-    pub station_registry: Arc<StationRegistry>,
+    /// Cable systems for terrestrial backhaul in routing; empty when unset
+    pub backhaul: Arc<Vec<orbital_glaf::backhaul::FiberRoute>>,
+    pub history: Arc<history::HistoryStore>,
+    pub downselect_runs: Arc<downselect::DownselectStore>,
+    pub checkpoints: Arc<checkpoint::CheckpointStore>,
+    pub memory: memory::MemoryState,
+    pub clock: Arc<clock::SimClock>,
+    pub propagation: Arc<propagation::PropagationControl>,
+    pub ephemeris: Arc<ephemeris::EphemerisCaches>,
+    pub chaos: Arc<chaos::ChaosState>,
+    pub scenarios: Arc<scenario::ScenarioEngine>,
+    pub sideband: Arc<sideband::SidebandQueue>,
+    pub keys: Arc<keys::KeyStore>,
+    pub keyring: Arc<keyring::Keyring>,
+    pub station_keeping: Arc<stationkeeping::StationKeeping>,
+    pub bus: Arc<bus::BusModel>,
+    pub catalog: Arc<RwLock<catalog::ScreeningCatalog>>,
+    pub celestrak: Arc<catalog::CelestrakConfig>,
+    /// JetStream publisher; `None` when NATS is not configured/reachable
+    pub telemetry: Option<Arc<telemetry::NatsTelemetry>>,
+    pub weather: Arc<weather::WeatherState>,
+    /// Latest ground-station telemetry received over NATS
+    pub fleet: Arc<fleet::FleetTelemetry>,
+    /// Topology, routing and chaos events for the network event stream
+    pub events: Arc<events::NetworkEvents>,
+    /// Live graph database; `None` when not configured/reachable
+    #[cfg(feature = "neo4j")]
+    pub neo4j: Option<Arc<orbital_glaf::neo4j_client::Neo4jClient>>,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct ConstellationState {
     pub satellites: Vec<orbital_mechanics::Satellite>,
     pub ground_stations: Vec<ground_stations::GroundStation>,
 }
 
+impl ConstellationState {
+    /// Every configured Walker Delta shell with elements at `epoch`, each
+    /// in its own ID namespace. The last `spares` satellites of each shell
+    /// are held as on-orbit spares.
+    pub fn from_config<'a>(
+        shells: impl IntoIterator<Item = &'a ConstellationConfig>,
+        stations: &StationRegistry,
+        epoch: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        let mut satellites = Vec::new();
+        for shell in shells {
+            let mut generated = shell
+                .walker()
+                .generate_satellites(&shell.name, shell.norad_base, epoch);
+            let active = generated.len().saturating_sub(shell.spares as usize);
+            for sat in generated.iter_mut().skip(active) {
+                sat.status = SatelliteStatus::Spare;
+            }
+            satellites.extend(generated);
+        }
+
+        Self {
+            satellites,
+            ground_stations: stations.all().cloned().collect(),
+        }
+    }
+
+    /// Satellites belonging to constellation `name`
+    pub fn constellation<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a orbital_mechanics::Satellite> + 'a {
+        self.satellites.iter().filter(move |s| s.constellation == name)
+    }
+
+    pub fn station(&self, id: &str) -> Option<&ground_stations::GroundStation> {
+        self.ground_stations.iter().find(|s| s.id == id)
+    }
+
+    pub fn operational_stations(&self) -> impl Iterator<Item = &ground_stations::GroundStation> {
+        self.ground_stations
+            .iter()
+            .filter(|s| s.status == ground_stations::StationStatus::Operational)
+    }
+}
+
+/// Swappable constellation. Readers take a cheap `Arc` snapshot; uploads
+/// replace the whole state at once so no reader sees a half-applied set.
+pub struct SharedConstellation {
+    current: RwLock<Arc<ConstellationState>>,
+}
+
+impl SharedConstellation {
+    pub fn new(state: ConstellationState) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(state)),
+        }
+    }
+
+    pub fn load(&self) -> Arc<ConstellationState> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Build the next state from the current one under the write lock
+    pub fn update<T>(&self, f: impl FnOnce(&ConstellationState) -> (ConstellationState, T)) -> T {
+        let mut current = self.current.write().unwrap();
+        let (next, out) = f(&current);
+        *current = Arc::new(next);
+        out
+    }
+}
+
 // Strategic stations response
 #[derive(Serialize)]
 pub struct StrategicStationsResponse {
@@ -42,13 +179,6 @@ pub struct StrategicStationsResponse {
     pub stats: StationStats,
 }
 
-// Downselect request
-#[derive(Deserialize)]
-pub struct DownselectRequest {
-    pub weights: Option<ScoringWeights>,
-    pub top_n: Option<usize>,
-}
-
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::registry()
@@ -58,47 +188,307 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Load strategic stations (Equinix, HALO Centres, etc.)
-    let strategic_stations = load_strategic_stations();
+    // Config file + environment overrides, validated before anything starts
+    let config = GatewayConfig::load()?;
+
+    // Earth orientation for every ECI -> Earth-fixed conversion
+    if let Some(path) = &config.propagation.eop_path {
+        let table = orbital_mechanics::eop::EopTable::parse_finals2000a(&std::fs::read_to_string(path)?)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+        let (first, last) = table.span().unwrap_or_default();
+        tracing::info!("   Loaded {} EOP days (MJD {} to {})", table.len(), first, last);
+        orbital_mechanics::eop::install(Arc::new(table));
+    }
+
+    // Load strategic stations (Equinix, HALO Centres, etc.) or a configured dataset
+    let strategic_stations: Vec<NetworkStation> = match &config.stations.strategic_path {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path, e))?,
+        None => load_strategic_stations(),
+    };
     tracing::info!("   Loaded {} strategic stations", strategic_stations.len());
 
-    // Initialize memory system (sx9-tcache)
-    let memory_db_path = std::env::var("ORBITAL_MEMORY_PATH")
-        .unwrap_or_else(|_| ".orbital-memory".to_string());
-    let memory_state = memory::MemoryState::new(&memory_db_path)
+    // FSO ground stations from the selection pipeline or the built-in network
+    let ground_stations = match &config.stations.selection_path {
+        Some(path) => StationRegistry::from_selection_result(path)?,
+        None => StationRegistry::with_fso_network(),
+    };
+    tracing::info!("   Loaded {} ground stations", ground_stations.all().count());
+
+    // Cable systems for terrestrial backhaul between stations
+    let backhaul = match (&config.stations.backhaul_cables_path, &config.stations.backhaul_landings_path) {
+        (Some(cables), Some(landings)) => {
+            let routes = orbital_glaf::backhaul::routes_from_json(
+                &std::fs::read_to_string(cables)?,
+                &std::fs::read_to_string(landings)?,
+            )?;
+            tracing::info!("   Loaded {} cable systems for backhaul", routes.len());
+            routes
+        }
+        _ => Vec::new(),
+    };
+
+    // Historical time-series store (positions, links, station telemetry)
+    let history = history::HistoryStore::open(
+        &config.history.path,
+        chrono::Duration::hours(config.history.retention_hours),
+    )
+    .expect("Failed to open history store");
+    let downselect_runs = downselect::DownselectStore::open(history.database())
+        .expect("Failed to open downselect run store");
+    tracing::info!(
+        "   History store at {} ({}h retention)",
+        config.history.path,
+        config.history.retention_hours
+    );
+    let keyring = keyring::Keyring::open(
+        history.database(),
+        chrono::Duration::seconds(config.keyring.rotation_secs as i64),
+        !config.keyring.require_harvested,
+    )
+    .expect("Failed to open keyring audit log");
+
+    // Initialize memory system (sx9-tcache); the journal shares the history database
+    let memory_state = memory::MemoryState::new(&config.memory.path, history.database())
         .expect("Failed to initialize memory system");
-    tracing::info!("   Memory system initialized at {}", memory_db_path);
+    tracing::info!("   Memory system initialized at {}", config.memory.path);
+
+    let checkpoints = checkpoint::CheckpointStore::open(&config.checkpoints.dir)
+        .expect("Failed to open checkpoint directory");
 
+    // JetStream telemetry (optional)
+    let nats_telemetry = match telemetry::TelemetryConfig::from_config(&config) {
+        Some(config) => match telemetry::NatsTelemetry::connect(&config).await {
+            Ok(t) => {
+                tracing::info!("   JetStream telemetry on {} ({:?} retention)", config.url, config.retention);
+                Some(Arc::new(t))
+            }
+            Err(e) => {
+                tracing::warn!("   NATS unavailable ({}), telemetry publishing disabled", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    // Neo4j graph store (optional, `neo4j` feature)
+    #[cfg(feature = "neo4j")]
+    let neo4j = match config.neo4j.client_config() {
+        Some(neo4j_config) => {
+            let uri = neo4j_config.uri.clone();
+            match orbital_glaf::neo4j_client::Neo4jClient::connect(neo4j_config).await {
+                Ok(client) => {
+                    tracing::info!("   Neo4j connected at {}", uri);
+                    Some(Arc::new(client))
+                }
+                Err(e) => {
+                    tracing::warn!("   Neo4j unavailable ({})", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    let constellation = ConstellationState::from_config(
+        config.constellations(),
+        &ground_stations,
+        chrono::Utc::now(),
+    );
     let state = AppState {
-        constellation: Arc::new(ConstellationState::default()),
+        config: Arc::new(config.clone()),
+        constellation: Arc::new(SharedConstellation::new(constellation)),
         strategic_stations: Arc::new(strategic_stations),
-        station_registry: Arc::new(StationRegistry::with_fso_network()),
+        backhaul: Arc::new(backhaul),
+        history: Arc::new(history),
+        downselect_runs: Arc::new(downselect_runs),
+        checkpoints: Arc::new(checkpoints),
+        memory: memory_state.clone(),
+        clock: Arc::new(clock::SimClock::real_time()),
+        propagation: Arc::new(propagation::PropagationControl::new(config.propagation_interval())),
+        ephemeris: Arc::new(ephemeris::EphemerisCaches::new(
+            config.propagation.ephemeris_node_secs,
+            config.propagation.ephemeris_max_nodes,
+        )),
+        chaos: Arc::new(chaos::ChaosState::default()),
+        scenarios: Arc::new(scenario::ScenarioEngine::default()),
+        sideband: Arc::new(sideband::SidebandQueue::default()),
+        keys: Arc::new(keys::KeyStore::default()),
+        keyring: Arc::new(keyring),
+        station_keeping: Arc::new(stationkeeping::StationKeeping::default()),
+        bus: Arc::new(bus::BusModel::default()),
+        catalog: Arc::new(RwLock::new(catalog::ScreeningCatalog::default())),
+        celestrak: Arc::new(catalog::CelestrakConfig::from_config(&config)),
+        telemetry: nats_telemetry,
+        weather: Arc::new(weather::WeatherState::new(&config.weather)),
+        fleet: Arc::new(fleet::FleetTelemetry::default()),
+        events: Arc::new(events::NetworkEvents::default()),
+        #[cfg(feature = "neo4j")]
+        neo4j,
     };
 
+    // Background propagation + history recording
+    tracing::info!("   Propagating every {:?}", state.propagation.interval);
+    tokio::spawn(propagation::run(state.clone()));
+
+    // Scheduled CelesTrak refresh into the screening catalog
+    tokio::spawn(catalog::run(state.clone(), state.celestrak.as_ref().clone()));
+
+    // Priority-ordered CTAS sideband delivery
+    tokio::spawn(sideband::run(state.clone()));
+    tokio::spawn(sideband::probe(state.clone()));
+    tokio::spawn(keyring::run(state.clone()));
+
+    // Ground-station telemetry from the simulator fleet
+    tokio::spawn(fleet::run(state.clone()));
+
+    // Graph diffs, link flips and route re-selections for /ws/topology
+    tokio::spawn(events::run(state.clone()));
+
+    // API key / JWT authentication
+    let auth_config = Arc::new(AuthConfig::from_env());
+    if auth_config.enabled() {
+        tracing::info!(
+            "   Auth enabled ({} API keys, public reads: {})",
+            auth_config.key_count(),
+            auth_config.public_reads
+        );
+    } else {
+        tracing::warn!("   Auth disabled - set ORBITAL_API_KEYS or ORBITAL_JWT_SECRET");
+    }
+    let viewer = RoleGuard::new(auth_config.clone(), Role::Viewer);
+    let operator = RoleGuard::new(auth_config.clone(), Role::Operator);
+    let admin = RoleGuard::new(auth_config.clone(), Role::Admin);
+
+    // Per-caller budgets: cheap reads vs expensive computations
+    let limiter = Arc::new(RateLimiter::new(&config.rate_limit, auth_config.clone()));
+    let cheap = RateLimitGuard::new(limiter.clone(), CostTier::Cheap);
+    let expensive = RateLimitGuard::new(limiter.clone(), CostTier::Expensive);
+
     // Memory routes (sx9-tcache) - separate router with its own state
-    let memory_router = memory::memory_routes(memory_state);
+    let memory_router = memory::memory_routes(memory_state, &auth_config);
 
     // API routes for constellation operations
-    let constellation_routes = Router::new()
+    let read_routes = Router::new()
         .route("/satellites", get(routes::list_satellites))
+        .route("/constellations", get(constellations::list_constellations))
+        .route("/constellations/:name", get(constellations::get_constellation))
+        .route("/constellations/:name/satellites", get(constellations::list_constellation_satellites))
+        .route("/constellations/:name/topology", get(constellations::get_constellation_topology))
         .route("/satellites/:id/position", get(routes::get_position))
+        .route("/satellites/:id/ground-track", get(routes::get_ground_track))
+        .route("/satellites/:id/ground-track/geojson", get(routes::get_ground_track_geojson))
+        .route("/satellites/:id/footprint", get(routes::get_footprint))
+        .route("/satellites/:id/visibility", get(routes::get_visibility))
+        .route("/satellites/:id/ephemeris", get(ephemeris::get_ephemeris))
+        .route("/satellites/:id/history", get(history::satellite_history))
+        .route("/positions/stream", get(stream::position_stream))
+        .route("/ws/topology", get(events::topology_stream))
         .route("/ground-stations", get(routes::list_ground_stations))
+        .route("/ground-stations/:id/history", get(history::station_history))
+        .route("/stations/telemetry", get(fleet::list_telemetry))
+        .route("/stations/:id/telemetry", get(fleet::station_telemetry))
+        .route("/links/:id/history", get(history::link_history))
+        .route("/topology", get(routes::get_topology))
+        .route("/topology/geojson", get(routes::get_topology_geojson))
+        .route("/weather/stations/:id", get(weather::get_station_weather))
+        .route("/state/clock", get(clock::get_clock))
+        .route("/state/checkpoints", get(checkpoint::list_checkpoints))
+        .route("/chaos", get(chaos::list_faults))
+        .route("/scenarios/current", get(scenario::current_scenario))
+        .route("/sideband", get(sideband::status))
+        .route("/sideband/qos", get(sideband::qos))
+        .route("/keyring", get(keyring::status))
+        .route("/keyring/events", get(keyring::events))
+        .route("/keys", get(keys::list_inventories))
+        .route("/station-keeping", get(stationkeeping::list_status))
+        .route("/station-keeping/:id", get(stationkeeping::get_status))
+        .route("/bus", get(bus::list_bus))
+        .route("/bus/:id", get(bus::get_bus))
+        .route("/state/checkpoints/:name", get(checkpoint::get_checkpoint))
+        .route("/catalog", get(catalog::get_catalog))
+        .route("/catalog/:norad_id/drag", get(catalog::get_drag))
+        .route("/tle.txt", get(tle::download_tle))
         .route("/strategic-stations", get(list_strategic_stations))
-        .route("/strategic-stations/downselect", post(run_downselect))
+        .route("/strategic-stations/downselect/runs", get(downselect::list_runs))
+        .route("/strategic-stations/downselect/runs/:id", get(downselect::get_run))
+        .route("/strategic-stations/downselect/runs/:from/diff/:to", get(downselect::diff))
+        .route_layer(middleware::from_fn_with_state(cheap.clone(), ratelimit::limit))
+        .route_layer(middleware::from_fn_with_state(viewer.clone(), auth::require_role));
+
+    let compute_routes = Router::new()
+        .route("/stations/:id/passes", get(passes::get_station_passes))
+        .route("/satellites/:id/next-pass", get(passes::get_next_pass))
+        .route("/stations/:id/contacts", get(contacts::get_contacts))
+        .route("/satellites/:id", get(detail::get_satellite))
+        .route("/weather/stations", get(weather::get_all_station_weather))
+        .route("/weather/links", get(weather::get_weather_links))
+        .route("/telemetry/replay", get(telemetry::replay_telemetry))
         .route("/routing/optimal", post(routes::calculate_route))
+        .route("/routing/latency-budget", get(routes::latency_budget))
         .route("/collision/check", post(routes::check_collision))
-        .with_state(state);
+        .route("/constellations/:name/conjunctions", get(constellations::get_constellation_conjunctions))
+        .route("/constellations/:name/conjunctions/oem", post(constellations::screen_oem_conjunctions))
+        .route("/keys/schedule", post(keys::schedule_key_refresh))
+        .route("/keys/budget", get(keyrate::key_budget))
+        .route_layer(middleware::from_fn_with_state(expensive.clone(), ratelimit::limit))
+        .route_layer(middleware::from_fn_with_state(viewer, auth::require_role));
+
+    let operator_routes = Router::new()
+        .route("/state/clock", post(clock::set_clock))
+        .route("/state/checkpoints", post(checkpoint::save_checkpoint))
+        .route("/stations/:id/commands", post(commands::send_command))
+        .route("/chaos", delete(chaos::clear_all))
+        .route("/chaos/faults/:id", delete(chaos::clear_fault))
+        .route("/chaos/:kind/:id/fail", post(chaos::fail))
+        .route("/chaos/:kind/:id/degrade", post(chaos::degrade))
+        .route("/scenarios", post(scenario::start_scenario))
+        .route("/scenarios/current/cancel", post(scenario::cancel_scenario))
+        .route("/sideband/:channel", post(sideband::send))
+        .route("/keyring/entropy", post(keyring::ingest_entropy))
+        .route("/keyring/:channel/rotate", post(keyring::rotate_channel))
+        .route("/keys/:station", put(keys::configure_station))
+        .route("/keys/:station/consume", post(keys::consume))
+        .route_layer(middleware::from_fn_with_state(cheap, ratelimit::limit))
+        .route_layer(middleware::from_fn_with_state(operator.clone(), auth::require_role));
+
+    let operator_compute_routes = Router::new()
+        .route("/strategic-stations/downselect", post(downselect::run_downselect))
+        .route("/state/repropagate", post(propagation::repropagate))
+        .route("/catalog/refresh", post(catalog::refresh_catalog))
+        .route("/keys/schedule/apply", post(keys::apply_key_schedule))
+        .route_layer(middleware::from_fn_with_state(expensive.clone(), ratelimit::limit))
+        .route_layer(middleware::from_fn_with_state(operator, auth::require_role));
+
+    let admin_routes = Router::new()
+        .route("/tle", post(tle::upload_tle))
+        .route("/state/checkpoints/:name/restore", post(checkpoint::restore_checkpoint))
+        .route_layer(middleware::from_fn_with_state(expensive, ratelimit::limit))
+        .route_layer(middleware::from_fn_with_state(admin, auth::require_role));
+
+    let constellation_routes = read_routes
+        .merge(compute_routes)
+        .merge(operator_routes)
+        .merge(operator_compute_routes)
+        .merge(admin_routes)
+        .with_state(state.clone());
 
     // Combine all routes
     let api_routes = Router::new()
-        .route("/health", get(health))
+        .route("/health", get(health::health))
+        .route("/health/live", get(health::live))
+        .route("/health/ready", get(health::ready))
+        .with_state(state.clone())
         .nest("/api/v1", constellation_routes)
         .nest("/api/v1/memory", memory_router)
+        .layer(middleware::from_fn_with_state(auth_config.clone(), auth::authenticate))
         .layer(CorsLayer::permissive());
 
     // Static file serving for UI (if dist exists)
-    let ui_path = std::path::Path::new("ui/cesium-orbital/dist");
-    let app = if ui_path.exists() {
+    let ui_path = std::path::Path::new(&config.server.ui_dist);
+    let app = if !config.features.serve_ui {
+        api_routes
+    } else if ui_path.exists() {
         tracing::info!("   Serving UI from {}", ui_path.display());
         api_routes.nest_service("/", ServeDir::new(ui_path))
     } else {
@@ -107,30 +497,45 @@ async fn main() -> Result<()> {
     };
 
     // Port 18700 per sx9/config/ports.toml (orbital services range)
-    let port = std::env::var("ORBITAL_GATEWAY_PORT")
-        .or_else(|_| std::env::var("PORT"))
-        .unwrap_or_else(|_| "18700".to_string());
-    let addr = format!("0.0.0.0:{}", port);
+    let addr = format!("0.0.0.0:{}", config.server.rest_port);
 
     tracing::info!("🛰️  Orbital Gateway starting on {}", addr);
-    tracing::info!("   Constellation: HALO (12 MEO satellites)");
-    tracing::info!("   Ground stations: 257 FSO");
+    for shell in config.constellations() {
+        tracing::info!(
+            "   Constellation: {} Walker {}/{}/{} at {} km, {}°",
+            shell.name,
+            shell.total_satellites,
+            shell.planes,
+            shell.phasing,
+            shell.altitude_km,
+            shell.inclination_deg
+        );
+    }
+    tracing::info!("   Ground stations: {} FSO", state.constellation.load().ground_stations.len());
+
+    // gRPC alongside REST for internal services (beam scheduler, CTAS mesh)
+    if config.features.grpc {
+        let grpc_addr: std::net::SocketAddr = format!("0.0.0.0:{}", config.server.grpc_port).parse()?;
+        let grpc_auth = auth_config.clone();
+        tokio::spawn(async move {
+            tracing::info!("   gRPC service on {}", grpc_addr);
+            if let Err(e) = grpc::serve(state, grpc_auth, limiter, grpc_addr).await {
+                tracing::error!("gRPC server failed: {}", e);
+            }
+        });
+    }
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    // Connect info lets the rate limiter key anonymous callers by address
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
 
-async fn health() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "status": "healthy",
-        "service": "orbital-gateway",
-        "constellation": "HALO",
-        "version": env!("CARGO_PKG_VERSION")
-    }))
-}
-
 /// List all strategic stations (Equinix, HALO, Africa, etc.)
 async fn list_strategic_stations(
     State(state): State<AppState>,
@@ -140,17 +545,3 @@ async fn list_strategic_stations(
 
     Json(StrategicStationsResponse { stations, stats })
 }
-
-/// Run downselect analysis on strategic stations
-async fn run_downselect(
-    State(state): State<AppState>,
-    Json(req): Json<DownselectRequest>,
-) -> Json<DownselectSummary> {
-    let stations = state.strategic_stations.as_ref();
-
-    let weights = req.weights.unwrap_or_default();
-    let mut ds = Downselect::new().with_weights(weights);
-    ds.evaluate(stations);
-
-    Json(ds.summary())
-}
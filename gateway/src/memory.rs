@@ -4,15 +4,22 @@
 //! - Simple key-value store/recall
 //! - Work context ENGRAM operations (cross-LLM handoff)
 //! - Similarity probe for trivariate matching
+//! - Operational journal: maneuvers, downselect runs, chaos injections and
+//!   alerts recorded as structured memories, queryable by time, type,
+//!   subject and text (`GET /events`)
 //!
-//! All data persists in local sled database with tcache indexing.
+//! All data persists in local sled database with tcache indexing. The
+//! journal lives in the history database, keyed by sim time, and is never
+//! pruned.
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
+    middleware,
     routing::{get, post},
     Json, Router,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::Arc;
@@ -21,20 +28,177 @@ use tokio::sync::RwLock;
 use sx9_tcache::{TrivariateCache, TrivariateRecord, Hd4Phase, murmur3_128, ProbeVector};
 use sx9_tcache::traits::MemoryBackend;
 
+use crate::auth::{self, AuthConfig, Role, RoleGuard};
+
+const JOURNAL_TREE: &str = "memory_journal";
+const DEFAULT_JOURNAL_LIMIT: usize = 100;
+const MAX_JOURNAL_LIMIT: usize = 1000;
+
 /// Memory state shared across routes
 #[derive(Clone)]
 pub struct MemoryState {
     pub tcache: Arc<RwLock<TrivariateCache>>,
+    pub journal: Arc<Journal>,
 }
 
 impl MemoryState {
-    pub fn new(db_path: &str) -> anyhow::Result<Self> {
+    /// `journal_db` holds the operational journal (the history database)
+    pub fn new(db_path: &str, journal_db: sled::Db) -> anyhow::Result<Self> {
         let path = Path::new(db_path);
         let tcache = TrivariateCache::open(path)?;
         Ok(Self {
             tcache: Arc::new(RwLock::new(tcache)),
+            journal: Arc::new(Journal::open(journal_db)?),
         })
     }
+
+    /// Journal an operational event at sim time `at`; failures are logged,
+    /// never returned, so recording can't break the operation itself
+    pub fn remember(
+        &self,
+        at: DateTime<Utc>,
+        kind: MemoryKind,
+        subjects: Vec<String>,
+        summary: String,
+        detail: &impl Serialize,
+    ) {
+        let detail = serde_json::to_value(detail).unwrap_or_default();
+        if let Err(e) = self.journal.record(at, kind, subjects, summary, detail) {
+            tracing::warn!("Memory journal write failed: {}", e);
+        }
+    }
+}
+
+// ========== Operational Journal ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryKind {
+    Maneuver,
+    Downselect,
+    Chaos,
+    Alert,
+}
+
+impl std::str::FromStr for MemoryKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.trim().to_lowercase()))
+            .map_err(|_| format!("Unknown memory type: {}", s))
+    }
+}
+
+/// A journaled operational event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpsMemory {
+    pub id: u64,
+    /// Sim time of the event
+    pub at: DateTime<Utc>,
+    pub kind: MemoryKind,
+    /// Satellites, stations, faults or runs the event concerns
+    pub subjects: Vec<String>,
+    pub summary: String,
+    /// The event as published (burn, run summary, fault, alert)
+    pub detail: serde_json::Value,
+}
+
+/// Filters of a journal query; all given ones must match
+#[derive(Debug, Default)]
+pub struct JournalFilter {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Empty = any kind
+    pub kinds: Vec<MemoryKind>,
+    /// Case-insensitive substring of any subject
+    pub subject: Option<String>,
+    /// Lowercase terms, each found in the summary, subjects or detail
+    pub terms: Vec<String>,
+}
+
+impl JournalFilter {
+    fn matches(&self, memory: &OpsMemory) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&memory.kind) {
+            return false;
+        }
+        if let Some(subject) = &self.subject {
+            let subject = subject.to_lowercase();
+            if !memory.subjects.iter().any(|s| s.to_lowercase().contains(&subject)) {
+                return false;
+            }
+        }
+        if self.terms.is_empty() {
+            return true;
+        }
+        let text = format!("{} {} {}", memory.summary, memory.subjects.join(" "), memory.detail).to_lowercase();
+        self.terms.iter().all(|t| text.contains(t.as_str()))
+    }
+}
+
+/// sled-backed journal keyed by `timestamp_ms (big-endian) id (big-endian)`
+/// so a range scan returns memories in time order
+pub struct Journal {
+    db: sled::Db,
+    memories: sled::Tree,
+}
+
+fn journal_key(at: DateTime<Utc>, id: u64) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    key[..8].copy_from_slice(&(at.timestamp_millis().max(0) as u64).to_be_bytes());
+    key[8..].copy_from_slice(&id.to_be_bytes());
+    key
+}
+
+impl Journal {
+    pub fn open(db: sled::Db) -> anyhow::Result<Self> {
+        Ok(Self {
+            memories: db.open_tree(JOURNAL_TREE)?,
+            db,
+        })
+    }
+
+    pub fn record(
+        &self,
+        at: DateTime<Utc>,
+        kind: MemoryKind,
+        subjects: Vec<String>,
+        summary: String,
+        detail: serde_json::Value,
+    ) -> anyhow::Result<OpsMemory> {
+        let memory = OpsMemory {
+            id: self.db.generate_id()? + 1,
+            at,
+            kind,
+            subjects,
+            summary,
+            detail,
+        };
+        self.memories
+            .insert(journal_key(at, memory.id), serde_json::to_vec(&memory)?)?;
+        Ok(memory)
+    }
+
+    /// Matching memories, newest first
+    pub fn query(&self, filter: &JournalFilter, limit: usize) -> anyhow::Result<Vec<OpsMemory>> {
+        let from = filter.from.map_or([0u8; 16], |t| journal_key(t, 0));
+        let to = filter.to.map(|t| journal_key(t, u64::MAX));
+        let range = match &to {
+            Some(to) => self.memories.range(from..=*to),
+            None => self.memories.range(from..),
+        };
+
+        let mut memories = Vec::new();
+        for value in range.values().rev() {
+            let memory: OpsMemory = serde_json::from_slice(&value?)?;
+            if filter.matches(&memory) {
+                memories.push(memory);
+                if memories.len() >= limit {
+                    break;
+                }
+            }
+        }
+        Ok(memories)
+    }
 }
 
 // ========== Request/Response Types ==========
@@ -146,6 +310,44 @@ pub struct ContextSummary {
     pub phase: String,
 }
 
+#[derive(Deserialize)]
+pub struct JournalQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Comma-separated kinds, e.g. `maneuver,chaos`
+    pub types: Option<String>,
+    pub subject: Option<String>,
+    /// Free text; every whitespace-separated term must match
+    pub q: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl JournalQuery {
+    fn filter(&self) -> Result<JournalFilter, String> {
+        let kinds = match &self.types {
+            Some(types) => types
+                .split(',')
+                .filter(|t| !t.trim().is_empty())
+                .map(str::parse)
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        Ok(JournalFilter {
+            from: self.from,
+            to: self.to,
+            kinds,
+            subject: self.subject.clone(),
+            terms: self
+                .q
+                .as_deref()
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(str::to_lowercase)
+                .collect(),
+        })
+    }
+}
+
 // ========== Route Handlers ==========
 
 /// Store a value in memory
@@ -345,6 +547,20 @@ pub async fn context_list(
     Ok(Json(ContextListResponse { contexts }))
 }
 
+/// GET /events?from=&to=&types=maneuver,chaos&subject=&q=&limit=
+pub async fn journal_query(
+    State(state): State<MemoryState>,
+    Query(q): Query<JournalQuery>,
+) -> Result<Json<Vec<OpsMemory>>, (StatusCode, String)> {
+    let filter = q.filter().map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let limit = q.limit.unwrap_or(DEFAULT_JOURNAL_LIMIT).min(MAX_JOURNAL_LIMIT);
+    let memories = state
+        .journal
+        .query(&filter, limit)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(memories))
+}
+
 /// Health check for memory subsystem
 pub async fn health(
     State(state): State<MemoryState>,
@@ -433,14 +649,103 @@ fn base64_encode(bytes: &[u8]) -> String {
 
 // ========== Router ==========
 
-pub fn memory_routes(state: MemoryState) -> Router {
-    Router::new()
+pub fn memory_routes(state: MemoryState, auth_config: &Arc<AuthConfig>) -> Router {
+    let viewer = RoleGuard::new(auth_config.clone(), Role::Viewer);
+    let operator = RoleGuard::new(auth_config.clone(), Role::Operator);
+
+    let read_routes = Router::new()
         .route("/health", get(health))
-        .route("/store", post(store))
         .route("/recall", post(recall))
         .route("/probe", post(probe))
         .route("/hash", post(hash_data))
-        .route("/context", post(context_store))
         .route("/context/list", get(context_list))
-        .with_state(state)
+        .route("/events", get(journal_query))
+        .route_layer(middleware::from_fn_with_state(viewer, auth::require_role));
+
+    let write_routes = Router::new()
+        .route("/store", post(store))
+        .route("/context", post(context_store))
+        .route_layer(middleware::from_fn_with_state(operator, auth::require_role));
+
+    read_routes.merge(write_routes).with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn journal() -> Journal {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let journal = Journal::open(db).unwrap();
+        let day = |d: u32| Utc.with_ymd_and_hms(2026, 10, d, 12, 0, 0).unwrap();
+        let record = |at, kind, subjects: &[&str], summary: &str| {
+            let subjects = subjects.iter().map(|s| s.to_string()).collect();
+            journal
+                .record(at, kind, subjects, summary.to_string(), serde_json::json!({ "note": summary }))
+                .unwrap();
+        };
+        record(day(5), MemoryKind::Maneuver, &["BETA-03"], "BETA-03 station-keeping burn 0.12 m/s");
+        record(day(6), MemoryKind::Chaos, &["F-1", "BETA-03"], "F-1 injected: Satellite BETA-03 fail");
+        record(day(6), MemoryKind::Alert, &["GS-TOKYO"], "GS-TOKYO key inventory below reserve");
+        record(day(8), MemoryKind::Maneuver, &["HALO-01"], "HALO-01 station-keeping burn 0.08 m/s");
+        journal
+    }
+
+    fn summaries(memories: &[OpsMemory]) -> Vec<&str> {
+        memories.iter().map(|m| m.summary.as_str()).collect()
+    }
+
+    #[test]
+    fn test_journal_filters_by_time_type_and_text() {
+        let journal = journal();
+
+        // Newest first, limited
+        let all = journal.query(&JournalFilter::default(), 2).unwrap();
+        assert_eq!(all[0].subjects, ["HALO-01"]);
+        assert_eq!(all.len(), 2);
+
+        // "What happened to beta on the 6th"
+        let q = JournalQuery {
+            from: Some(Utc.with_ymd_and_hms(2026, 10, 6, 0, 0, 0).unwrap()),
+            to: Some(Utc.with_ymd_and_hms(2026, 10, 6, 23, 59, 59).unwrap()),
+            types: None,
+            subject: None,
+            q: Some("beta".to_string()),
+            limit: None,
+        };
+        let found = journal.query(&q.filter().unwrap(), 10).unwrap();
+        assert_eq!(summaries(&found), ["F-1 injected: Satellite BETA-03 fail"]);
+
+        let filter = JournalFilter {
+            kinds: vec![MemoryKind::Maneuver],
+            subject: Some("beta".to_string()),
+            ..Default::default()
+        };
+        let found = journal.query(&filter, 10).unwrap();
+        assert_eq!(summaries(&found), ["BETA-03 station-keeping burn 0.12 m/s"]);
+
+        let filter = JournalFilter {
+            terms: vec!["key".to_string(), "reserve".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(journal.query(&filter, 10).unwrap()[0].kind, MemoryKind::Alert);
+    }
+
+    #[test]
+    fn test_journal_query_types() {
+        let q = |types: &str| JournalQuery {
+            from: None,
+            to: None,
+            types: Some(types.to_string()),
+            subject: None,
+            q: None,
+            limit: None,
+        };
+        assert_eq!(
+            q("maneuver, Chaos").filter().unwrap().kinds,
+            [MemoryKind::Maneuver, MemoryKind::Chaos]
+        );
+        assert!(q("maneuver,launch").filter().is_err());
+    }
 }
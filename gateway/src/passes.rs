@@ -0,0 +1,341 @@
+//! Per-station pass prediction
+//!
+//! Samples every satellite's ground track over the requested window and
+//! runs `ContactCalculator` to find AOS/LOS/TCA, then attaches a predicted
+//! FSO margin from the link budget at max elevation and current station
+//! weather, and the chance of acquiring the satellite in the pass
+//! (`contact::AcquisitionModel`).
+//!
+//! Daytime passes are downgraded by the Sun's separation from the line of
+//! sight: the closer the terminal points to the Sun, the more sky
+//! background reaches the receiver, and inside the exclusion cone it
+//! cannot track at all.
+//!
+//! `GET /satellites/:id/next-pass` answers for one satellite and station
+//! with `orbital_mechanics::passes`, which refines AOS, LOS and TCA
+//! rather than sampling once a minute.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use ground_station_wasm::{
+    contact::{AcquisitionModel, ContactCalculator},
+    link_budget, GroundStationConfig,
+};
+use ground_stations::GroundStation;
+use orbital_mechanics::eclipse::{self, SunGeometry};
+use orbital_mechanics::passes::{self, Pass};
+use orbital_mechanics::{GeodeticPosition, Satellite};
+
+use crate::propagation::MIN_LINK_ELEVATION_DEG;
+use crate::routes::{find_satellite, propagate_geodetic};
+use crate::AppState;
+
+/// Ground track sampling step for pass search
+const PASS_SAMPLE_STEP_S: i64 = 60;
+
+/// Margin at which predicted FSO quality saturates to 1.0
+const FULL_QUALITY_MARGIN_DB: f64 = 10.0;
+
+/// No daytime FSO quality with the Sun closer than this to the line of
+/// sight (degrees)
+const SUN_EXCLUSION_DEG: f64 = 10.0;
+
+/// Beyond this separation daylight background no longer grows (degrees)
+const SUN_CLEAR_SEPARATION_DEG: f64 = 45.0;
+
+/// Quality kept in daylight with the Sun well clear of the line of sight
+const DAYLIGHT_QUALITY_FACTOR: f64 = 0.8;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PredictedPass {
+    pub satellite_id: String,
+    pub norad_id: u32,
+    pub aos: DateTime<Utc>,
+    pub los: DateTime<Utc>,
+    pub tca: DateTime<Utc>,
+    pub duration_sec: f64,
+    pub max_elevation_deg: f64,
+    pub aos_azimuth_deg: f64,
+    pub los_azimuth_deg: f64,
+    /// Link margin at max elevation with current weather
+    pub predicted_margin_db: f64,
+    /// Sun relative to the line of sight at TCA
+    pub sun: SunGeometry,
+    /// Closest approach of the line of sight to the Sun during the pass
+    /// (degrees)
+    pub min_sun_separation_deg: f64,
+    /// 0-1: weather score × margin headroom × daylight background
+    pub predicted_fso_quality: f64,
+    /// 0-1: chance of acquiring the satellite given pointing error, scan
+    /// time against pass length and weather
+    pub acquisition_probability: f64,
+}
+
+#[derive(Deserialize)]
+pub struct PassQuery {
+    pub hours: Option<i64>,
+    pub from: Option<DateTime<Utc>>,
+    pub min_elevation_deg: Option<f64>,
+    /// Drop passes less likely than this to be acquired
+    pub min_acquisition_probability: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct NextPassQuery {
+    pub station: String,
+    pub from: Option<DateTime<Utc>>,
+    /// Search horizon (default 24, at most a week)
+    pub hours: Option<i64>,
+    pub min_elevation_deg: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct NextPassResponse {
+    pub satellite_id: String,
+    pub station_id: String,
+    pub from: DateTime<Utc>,
+    /// `None` when there is no pass within the horizon; AOS is `from`
+    /// for a pass in progress
+    pub pass: Option<Pass>,
+}
+
+#[derive(Serialize)]
+pub struct PassesResponse {
+    pub station_id: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub passes: Vec<PredictedPass>,
+}
+
+fn station_weather_score(station: &GroundStation) -> f64 {
+    station
+        .weather
+        .as_ref()
+        .map(|w| w.beam_quality_score)
+        .unwrap_or(1.0)
+}
+
+/// Quality factor from daylight background (0-1); 1 at night
+fn daylight_factor(daytime_background: bool, min_sun_separation_deg: f64) -> f64 {
+    if !daytime_background {
+        return 1.0;
+    }
+    let clear = (min_sun_separation_deg - SUN_EXCLUSION_DEG) / (SUN_CLEAR_SEPARATION_DEG - SUN_EXCLUSION_DEG);
+    DAYLIGHT_QUALITY_FACTOR * clear.clamp(0.0, 1.0)
+}
+
+fn calculator_for(station: &GroundStation, min_elevation_deg: f64) -> ContactCalculator {
+    ContactCalculator::new(GroundStationConfig {
+        id: station.id.clone(),
+        name: station.name.clone(),
+        latitude_deg: station.location.latitude,
+        longitude_deg: station.location.longitude,
+        altitude_m: station.location.altitude_m,
+        min_elevation_deg,
+        ..Default::default()
+    })
+}
+
+/// Passes of one satellite found by `calculator`, unsorted
+fn passes_of(
+    calculator: &ContactCalculator,
+    acquisition: &AcquisitionModel,
+    sat: &Satellite,
+    station: &GroundStation,
+    weather_score: f64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<PredictedPass> {
+    let site = GeodeticPosition {
+        latitude: station.location.latitude,
+        longitude: station.location.longitude,
+        altitude_km: station.location.altitude_m / 1000.0,
+    };
+    let steps = (to - from).num_seconds() / PASS_SAMPLE_STEP_S;
+    let samples: Vec<(i64, f64, f64, f64)> = (0..=steps)
+        .filter_map(|i| {
+            let t = from + Duration::seconds(i * PASS_SAMPLE_STEP_S);
+            propagate_geodetic(sat, t)
+                .ok()
+                .map(|(_, geo)| (t.timestamp(), geo.latitude, geo.longitude, geo.altitude_km))
+        })
+        .collect();
+
+    calculator
+        .find_windows(sat.norad_id, &samples)
+        .into_iter()
+        .map(|window| {
+            let margin = link_budget::calculate_margin(window.max_elevation_deg, weather_score);
+            let headroom = (margin / FULL_QUALITY_MARGIN_DB).clamp(0.0, 1.0);
+            let ts = |unix: i64| DateTime::<Utc>::from_timestamp(unix, 0).unwrap_or(from);
+
+            // Sun geometry at each in-pass sample; the one nearest TCA
+            // stands for the pass
+            let sun: Vec<(i64, SunGeometry)> = samples
+                .iter()
+                .filter(|(t, ..)| (window.aos_unix..=window.los_unix).contains(t))
+                .filter_map(|&(t, latitude, longitude, altitude_km)| {
+                    let position = GeodeticPosition {
+                        latitude,
+                        longitude,
+                        altitude_km,
+                    };
+                    eclipse::sun_geometry(&site, &position, ts(t)).ok().map(|g| (t, g))
+                })
+                .collect();
+            let sun_at_tca = sun
+                .iter()
+                .min_by_key(|(t, _)| (t - window.tca_unix).abs())
+                .map(|(_, g)| *g)
+                .unwrap_or_else(|| SunGeometry {
+                    sun_elevation_deg: eclipse::sun_elevation_deg(site.latitude, site.longitude, ts(window.tca_unix)),
+                    phase_angle_deg: 0.0,
+                    sun_separation_deg: 180.0,
+                    daytime_background: false,
+                });
+            let min_sun_separation_deg = sun
+                .iter()
+                .map(|(_, g)| g.sun_separation_deg)
+                .fold(sun_at_tca.sun_separation_deg, f64::min);
+            let daytime_background = sun.iter().any(|(_, g)| g.daytime_background);
+
+            PredictedPass {
+                satellite_id: sat.id.clone(),
+                norad_id: sat.norad_id,
+                aos: ts(window.aos_unix),
+                los: ts(window.los_unix),
+                tca: ts(window.tca_unix),
+                duration_sec: window.duration_sec,
+                max_elevation_deg: window.max_elevation_deg,
+                aos_azimuth_deg: window.aos_azimuth_deg,
+                los_azimuth_deg: window.los_azimuth_deg,
+                predicted_margin_db: margin,
+                sun: sun_at_tca,
+                min_sun_separation_deg,
+                predicted_fso_quality: weather_score
+                    * headroom
+                    * daylight_factor(daytime_background, min_sun_separation_deg),
+                acquisition_probability: acquisition.estimate_window(&window, weather_score).probability,
+            }
+        })
+        .collect()
+}
+
+/// Predict passes of every constellation satellite over `station`
+pub fn predict_station_passes(
+    state: &AppState,
+    station: &GroundStation,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    min_elevation_deg: f64,
+) -> Vec<PredictedPass> {
+    let calculator = calculator_for(station, min_elevation_deg);
+    let acquisition = &state.config.stations.acquisition;
+    let weather_score = station_weather_score(station);
+
+    let constellation = state.constellation.load();
+    let mut passes: Vec<PredictedPass> = constellation
+        .satellites
+        .iter()
+        .flat_map(|sat| passes_of(&calculator, acquisition, sat, station, weather_score, from, to))
+        .collect();
+
+    passes.sort_by_key(|p| p.aos);
+    passes
+}
+
+/// Predict passes of one satellite over `station`
+pub fn predict_satellite_passes(
+    acquisition: &AcquisitionModel,
+    sat: &Satellite,
+    station: &GroundStation,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    min_elevation_deg: f64,
+) -> Vec<PredictedPass> {
+    let calculator = calculator_for(station, min_elevation_deg);
+    let weather_score = station_weather_score(station);
+    let mut passes = passes_of(&calculator, acquisition, sat, station, weather_score, from, to);
+    passes.sort_by_key(|p| p.aos);
+    passes
+}
+
+/// GET /stations/:id/passes?hours=24
+pub async fn get_station_passes(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<PassQuery>,
+) -> Result<Json<PassesResponse>, (StatusCode, String)> {
+    let constellation = state.constellation.load();
+    let station = constellation
+        .station(&id)
+        .ok_or((StatusCode::NOT_FOUND, format!("Station not found: {}", id)))?;
+
+    let from = q.from.unwrap_or_else(|| state.clock.now());
+    let to = from + Duration::hours(q.hours.unwrap_or(24).clamp(1, 7 * 24));
+    let min_el = q.min_elevation_deg.unwrap_or(MIN_LINK_ELEVATION_DEG);
+
+    let min_acquisition = q.min_acquisition_probability.unwrap_or(0.0);
+    let passes = predict_station_passes(&state, station, from, to, min_el)
+        .into_iter()
+        .filter(|p| p.acquisition_probability >= min_acquisition)
+        .collect();
+
+    Ok(Json(PassesResponse {
+        station_id: id,
+        from,
+        to,
+        passes,
+    }))
+}
+
+/// GET /satellites/:id/next-pass?station=GS-1&hours=24
+pub async fn get_next_pass(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<NextPassQuery>,
+) -> Result<Json<NextPassResponse>, (StatusCode, String)> {
+    let sat = find_satellite(&state, &id)?;
+    let constellation = state.constellation.load();
+    let station = constellation
+        .station(&q.station)
+        .ok_or((StatusCode::NOT_FOUND, format!("Station not found: {}", q.station)))?;
+    let site = GeodeticPosition {
+        latitude: station.location.latitude,
+        longitude: station.location.longitude,
+        altitude_km: station.location.altitude_m / 1000.0,
+    };
+
+    let from = q.from.unwrap_or_else(|| state.clock.now());
+    let horizon = Duration::hours(q.hours.unwrap_or(24).clamp(1, 7 * 24));
+    let min_el = q.min_elevation_deg.unwrap_or(MIN_LINK_ELEVATION_DEG);
+    let pass = passes::next_pass(&sat, &site, from, horizon, min_el)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    Ok(Json(NextPassResponse {
+        satellite_id: id,
+        station_id: q.station,
+        from,
+        pass,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daylight_factor_by_sun_separation() {
+        assert_eq!(daylight_factor(false, 0.0), 1.0);
+        assert_eq!(daylight_factor(true, SUN_EXCLUSION_DEG), 0.0);
+        assert_eq!(daylight_factor(true, 90.0), DAYLIGHT_QUALITY_FACTOR);
+        let mid = daylight_factor(true, (SUN_EXCLUSION_DEG + SUN_CLEAR_SEPARATION_DEG) / 2.0);
+        assert!((mid - DAYLIGHT_QUALITY_FACTOR / 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,349 @@
+//! Background propagation loop
+//!
+//! Every tick: propagate all satellites (in parallel, on the rayon pool),
+//! derive satellite-to-ground link states from look angles, snapshot
+//! station telemetry, and persist all of it to the history store (and
+//! JetStream, when NATS is configured). Ticks run on a configurable
+//! wall-clock cadence but are stamped with sim-clock time; a tick whose
+//! sim time equals the previous one (paused clock) is skipped.
+//! `POST /state/repropagate` forces a tick immediately. Active
+//! chaos faults take ground links down and override station status.
+//! Station key stores are credited for completed passes on each tick, and
+//! station keeping runs first so positions reflect any burn. The bus
+//! model steps on the tick's positions and link states. Each tick's
+//! positions are also broadcast to live streams (`stream`) and the tick
+//! refreshes the network event stream (`events`). With NATS configured
+//! the tick's routing graph is published for `glaf-server`.
+//!
+//! Elements outside `propagation.max_element_age_days` are replaced before
+//! propagating: uploaded real satellites from the screening catalog,
+//! Walker satellites by regenerating their nominal slot at tick time.
+//! Satellites with nothing newer are propagated stale with a warning or
+//! skipped, per `propagation.stale_elements`.
+
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use ground_station_wasm::batch::{self, StationBatch};
+use orbital_mechanics::propagation::{propagate_many_within, RefreshHook};
+use orbital_mechanics::Satellite;
+
+use crate::bus;
+use crate::chaos::FaultEffect;
+use crate::history::{LinkRecord, PositionRecord, StationTelemetryRecord};
+use crate::keys;
+use crate::routes::{self, station_status_str};
+use crate::stationkeeping;
+use crate::telemetry::NatsTelemetry;
+use crate::topology;
+use crate::AppState;
+
+/// Ticks a slow stream subscriber can fall behind before skipping ahead
+const POSITION_BROADCAST_CAPACITY: usize = 16;
+
+/// Minimum elevation for a ground link to count as active
+pub const MIN_LINK_ELEVATION_DEG: f64 = 10.0;
+
+/// How often history is pruned, independent of the tick cadence
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Tick cadence plus the sim time of the last completed tick
+pub struct PropagationControl {
+    pub interval: Duration,
+    last_sim_time: Mutex<Option<DateTime<Utc>>>,
+    positions: broadcast::Sender<Arc<Vec<PositionRecord>>>,
+    /// Satellites already warned about as stale
+    stale: Mutex<HashSet<String>>,
+}
+
+impl PropagationControl {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_sim_time: Mutex::new(None),
+            positions: broadcast::channel(POSITION_BROADCAST_CAPACITY).0,
+            stale: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Positions from every subsequent tick
+    pub fn subscribe_positions(&self) -> broadcast::Receiver<Arc<Vec<PositionRecord>>> {
+        self.positions.subscribe()
+    }
+
+    pub fn last_sim_time(&self) -> Option<DateTime<Utc>> {
+        *self.last_sim_time.lock().unwrap()
+    }
+}
+
+/// Outcome of one tick
+#[derive(Debug, Clone, Serialize)]
+pub struct TickSummary {
+    pub sim_time: DateTime<Utc>,
+    pub satellites: usize,
+    pub links: usize,
+    pub active_links: usize,
+    pub stations: usize,
+    pub published: bool,
+}
+
+/// Run the propagation loop forever
+pub async fn run(state: AppState) {
+    let mut ticker = tokio::time::interval(state.propagation.interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut last_prune = Instant::now();
+
+    loop {
+        ticker.tick().await;
+
+        let now = state.clock.now();
+        if state.propagation.last_sim_time() == Some(now) {
+            tracing::trace!("Sim clock paused, propagation skipped");
+        } else {
+            match tick(&state, now).await {
+                Ok(summary) => tracing::debug!("Propagated {} satellites at {}", summary.satellites, now),
+                Err(e) => tracing::warn!("Propagation tick failed: {}", e),
+            }
+        }
+
+        if last_prune.elapsed() >= PRUNE_INTERVAL {
+            last_prune = Instant::now();
+            let history = state.history.clone();
+            let now = state.clock.now();
+            match tokio::task::spawn_blocking(move || history.prune(now)).await {
+                Ok(Ok(removed)) if removed > 0 => tracing::info!("Pruned {} history records", removed),
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => tracing::warn!("History prune failed: {}", e),
+                Err(e) => tracing::warn!("History prune panicked: {}", e),
+            }
+        }
+    }
+}
+
+/// Propagate, record and publish at sim time `now`
+pub async fn tick(state: &AppState, now: DateTime<Utc>) -> anyhow::Result<TickSummary> {
+    stationkeeping::tick(state, now).await;
+    // Rayon propagation and sled writes, off the async workers
+    let records = {
+        let state = state.clone();
+        tokio::task::spawn_blocking(move || propagate_and_record(&state, now)).await??
+    };
+    *state.propagation.last_sim_time.lock().unwrap() = Some(now);
+    if state.propagation.positions.receiver_count() > 0 {
+        let _ = state.propagation.positions.send(Arc::new(records.positions.clone()));
+    }
+    state.events.refresh(now);
+    keys::tick(state, now).await;
+    bus::tick(state, now, &records.positions, &records.links).await;
+
+    let mut published = false;
+    if let Some(telemetry) = &state.telemetry {
+        match publish(telemetry, &records).await {
+            Ok(()) => published = true,
+            Err(e) => tracing::warn!("Telemetry publish failed: {}", e),
+        }
+        if let Err(e) = publish_graph(state, telemetry, now).await {
+            tracing::warn!("Routing graph publish failed: {}", e);
+        }
+    }
+
+    Ok(TickSummary {
+        sim_time: now,
+        satellites: records.positions.len(),
+        links: records.links.len(),
+        active_links: records.links.iter().filter(|l| l.active).count(),
+        stations: records.telemetry.len(),
+        published,
+    })
+}
+
+/// POST /state/repropagate - run a tick now, even if the clock is paused
+pub async fn repropagate(
+    State(state): State<AppState>,
+) -> Result<Json<TickSummary>, (StatusCode, String)> {
+    let summary = tick(&state, state.clock.now())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    tracing::info!("Manual re-propagation at {}", summary.sim_time);
+    Ok(Json(summary))
+}
+
+/// Newer elements for satellites past the validity window
+struct ElementRefresh<'a> {
+    state: &'a AppState,
+}
+
+impl RefreshHook for ElementRefresh<'_> {
+    fn refresh(&self, sat: &Satellite, time: DateTime<Utc>) -> Option<(String, String)> {
+        // Uploaded and catalog objects belong to no configured shell
+        if sat.constellation.is_empty() {
+            let catalog = self.state.catalog.read().unwrap();
+            let entry = catalog.get(sat.norad_id)?;
+            return Some((entry.tle.line1.clone(), entry.tle.line2.clone()));
+        }
+        let shell = self.state.config.constellations().find(|c| c.name == sat.constellation)?;
+        shell
+            .walker()
+            .generate_satellites(&shell.name, shell.norad_base, time)
+            .into_iter()
+            .find(|s| s.id == sat.id)
+            .map(|s| (s.tle_line1, s.tle_line2))
+    }
+}
+
+/// Everything produced by one propagation tick
+#[derive(Default)]
+pub struct TickRecords {
+    pub positions: Vec<PositionRecord>,
+    pub links: Vec<LinkRecord>,
+    pub telemetry: Vec<StationTelemetryRecord>,
+}
+
+async fn publish(telemetry: &NatsTelemetry, records: &TickRecords) -> anyhow::Result<()> {
+    for record in &records.positions {
+        telemetry.publish_position(record).await?;
+    }
+    for record in &records.links {
+        telemetry.publish_link(record).await?;
+    }
+    for record in &records.telemetry {
+        telemetry.publish_station(record).await?;
+    }
+    Ok(())
+}
+
+/// Routing graph at `time` (weather and backhaul applied), for `glaf-server`
+async fn publish_graph(state: &AppState, telemetry: &NatsTelemetry, time: DateTime<Utc>) -> anyhow::Result<()> {
+    let graph = {
+        let state = state.clone();
+        tokio::task::spawn_blocking(move || {
+            let snapshot = topology::snapshot(&state, time)?;
+            Ok::<_, anyhow::Error>(routes::routing_graph(&state, &snapshot, true).to_snapshot(time.timestamp()))
+        })
+        .await??
+    };
+    telemetry.publish_graph(&graph).await
+}
+
+/// Propagate every satellite at `time` and persist positions, links and
+/// station telemetry. Returns what was recorded.
+pub fn propagate_and_record(state: &AppState, time: DateTime<Utc>) -> anyhow::Result<TickRecords> {
+    let history = &state.history;
+    let mut records = TickRecords::default();
+
+    let constellation = state.constellation.load();
+    let faults = state.chaos.active_at(time);
+    let sites = StationBatch::from_geodetic(
+        constellation
+            .ground_stations
+            .iter()
+            .map(|s| (s.location.latitude, s.location.longitude, s.location.altitude_m / 1000.0)),
+    );
+    let window = state.config.propagation.validity_window();
+    let hook = ElementRefresh { state };
+    let mut refreshed = Vec::new();
+    let states = propagate_many_within(&constellation.satellites, time, &window, Some(&hook));
+    for (sat, checked) in constellation.satellites.iter().zip(states) {
+        let checked = match checked {
+            Ok(checked) => checked,
+            Err(e) => {
+                tracing::warn!("{}: {}", sat.id, e);
+                continue;
+            }
+        };
+        let newly_stale = if checked.stale {
+            state.propagation.stale.lock().unwrap().insert(sat.id.clone())
+        } else {
+            state.propagation.stale.lock().unwrap().remove(&sat.id);
+            false
+        };
+        if newly_stale {
+            tracing::warn!("{}: propagating elements {:.1} days from epoch", sat.id, checked.age_days);
+        }
+        if let Some(lines) = checked.refreshed {
+            refreshed.push((sat.id.clone(), lines));
+        }
+        let sv = checked.state;
+        let geo = match sv.to_geodetic() {
+            Ok(geo) => geo,
+            Err(e) => {
+                tracing::warn!("{}: {}", sat.id, e);
+                continue;
+            }
+        };
+        let speed = (sv.velocity_x.powi(2) + sv.velocity_y.powi(2) + sv.velocity_z.powi(2)).sqrt();
+
+        let position = PositionRecord {
+            satellite_id: sat.id.clone(),
+            timestamp: time,
+            latitude: geo.latitude,
+            longitude: geo.longitude,
+            altitude_km: geo.altitude_km,
+            velocity_km_s: speed,
+            position_eci_km: [sv.position_x, sv.position_y, sv.position_z],
+            velocity_eci_km_s: [sv.velocity_x, sv.velocity_y, sv.velocity_z],
+        };
+        history.record_position(&position)?;
+        records.positions.push(position);
+
+        let angles = sites.stations_to_satellite(batch::to_cartesian(geo.latitude, geo.longitude, geo.altitude_km));
+        for i in batch::above(&angles, 0.0) {
+            let station = &constellation.ground_stations[i];
+            let elevation_deg = angles.elevation_deg[i];
+            let link = LinkRecord {
+                link_id: format!("{}|{}", sat.id, station.id),
+                satellite_id: sat.id.clone(),
+                station_id: station.id.clone(),
+                timestamp: time,
+                elevation_deg,
+                range_km: angles.range_km[i],
+                active: elevation_deg >= MIN_LINK_ELEVATION_DEG
+                    && faults.link(&sat.id, &station.id) != Some(FaultEffect::Fail),
+            };
+            history.record_link(&link)?;
+            records.links.push(link);
+        }
+    }
+
+    for station in &constellation.ground_stations {
+        let telemetry = StationTelemetryRecord {
+            station_id: station.id.clone(),
+            timestamp: time,
+            status: match faults.element(&station.id) {
+                Some(FaultEffect::Fail) => "offline",
+                Some(FaultEffect::Degrade { .. }) => "degraded",
+                Some(FaultEffect::Congest { .. }) | None => station_status_str(station.status),
+            }
+            .to_string(),
+            weather_score: station
+                .weather
+                .as_ref()
+                .map(|w| w.beam_quality_score)
+                .unwrap_or(1.0),
+            cloud_cover_pct: station.weather.as_ref().map(|w| w.cloud_cover_pct),
+        };
+        history.record_telemetry(&telemetry)?;
+        records.telemetry.push(telemetry);
+    }
+
+    if !refreshed.is_empty() {
+        tracing::info!("Refreshed aged-out elements of {} satellites", refreshed.len());
+        state.constellation.update(|current| {
+            let mut next = current.clone();
+            for sat in next.satellites.iter_mut() {
+                if let Some((_, (line1, line2))) = refreshed.iter().find(|(id, _)| *id == sat.id) {
+                    sat.tle_line1 = line1.clone();
+                    sat.tle_line2 = line2.clone();
+                }
+            }
+            (next, ())
+        });
+    }
+
+    Ok(records)
+}
@@ -0,0 +1,265 @@
+//! Per-caller rate limiting
+//!
+//! Token buckets keyed by caller and cost tier. Authenticated callers are
+//! keyed by principal subject (one bucket set per API key / JWT subject);
+//! anonymous and dev-mode callers by remote address. Cheap reads and
+//! expensive computations draw from separate budgets, so a dashboard
+//! polling positions can't exhaust routing capacity and a client hammering
+//! downselect can't starve everything else. Rejections are 429 with
+//! `Retry-After`. gRPC calls draw from the same buckets (see `grpc`).
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::auth::{AuthConfig, Principal};
+use crate::config::RateLimitSection;
+
+/// Buckets idle this long are dropped during cleanup
+const IDLE_EVICTION: Duration = Duration::from_secs(600);
+const CLEANUP_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostTier {
+    /// Lookups and snapshots (positions, lists, history)
+    Cheap,
+    /// Graph search, screening and optimisation (routing, downselect, passes)
+    Expensive,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    /// Sustained refill rate
+    pub per_second: f64,
+    /// Bucket capacity
+    pub burst: f64,
+}
+
+impl Budget {
+    pub fn per_minute(per_minute: u32, burst: u32) -> Self {
+        Self {
+            per_second: per_minute as f64 / 60.0,
+            burst: burst.max(1) as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(budget: &Budget, now: Instant) -> Self {
+        Self {
+            tokens: budget.burst,
+            updated: now,
+        }
+    }
+
+    /// Take one token. `Ok(remaining)` or `Err(wait until one is available)`.
+    fn try_take(&mut self, budget: &Budget, now: Instant) -> Result<u32, Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * budget.per_second).min(budget.burst);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(self.tokens as u32)
+        } else if budget.per_second > 0.0 {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / budget.per_second))
+        } else {
+            Err(Duration::from_secs(60))
+        }
+    }
+}
+
+pub struct RateLimiter {
+    enabled: bool,
+    cheap: Budget,
+    expensive: Budget,
+    auth: Arc<AuthConfig>,
+    buckets: Mutex<HashMap<(String, CostTier), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitSection, auth: Arc<AuthConfig>) -> Self {
+        Self {
+            enabled: config.enabled,
+            cheap: Budget::per_minute(config.cheap_per_minute, config.cheap_burst),
+            expensive: Budget::per_minute(config.expensive_per_minute, config.expensive_burst),
+            auth,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn budget(&self, tier: CostTier) -> &Budget {
+        match tier {
+            CostTier::Cheap => &self.cheap,
+            CostTier::Expensive => &self.expensive,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn check(&self, caller: &str, tier: CostTier) -> Result<u32, Duration> {
+        self.check_at(caller, tier, Instant::now())
+    }
+
+    fn check_at(&self, caller: &str, tier: CostTier, now: Instant) -> Result<u32, Duration> {
+        let budget = *self.budget(tier);
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= CLEANUP_THRESHOLD {
+            buckets.retain(|_, b| now.saturating_duration_since(b.updated) < IDLE_EVICTION);
+        }
+
+        buckets
+            .entry((caller.to_string(), tier))
+            .or_insert_with(|| Bucket::full(&budget, now))
+            .try_take(&budget, now)
+    }
+
+    /// Bucket key for a request
+    fn caller(&self, req: &Request) -> String {
+        self.caller_key(
+            req.extensions().get::<Principal>(),
+            req.extensions().get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| *addr),
+        )
+    }
+
+    /// Bucket key: the principal's subject when auth is on, else the
+    /// remote address
+    pub fn caller_key(&self, principal: Option<&Principal>, addr: Option<SocketAddr>) -> String {
+        if self.auth.enabled() {
+            if let Some(principal) = principal {
+                return format!("sub:{}", principal.subject);
+            }
+        }
+        addr.map(|addr| format!("ip:{}", addr.ip()))
+            .unwrap_or_else(|| "anonymous".to_string())
+    }
+}
+
+/// Tier applied to a group of routes
+#[derive(Clone)]
+pub struct RateLimitGuard {
+    pub limiter: Arc<RateLimiter>,
+    pub tier: CostTier,
+}
+
+impl RateLimitGuard {
+    pub fn new(limiter: Arc<RateLimiter>, tier: CostTier) -> Self {
+        Self { limiter, tier }
+    }
+}
+
+#[derive(Serialize)]
+struct RateLimited {
+    error: &'static str,
+    tier: CostTier,
+    retry_after_seconds: u64,
+}
+
+/// Route-level middleware: spend one token from the caller's tier budget
+pub async fn limit(State(guard): State<RateLimitGuard>, req: Request, next: Next) -> Response {
+    if !guard.limiter.enabled {
+        return next.run(req).await;
+    }
+
+    let caller = guard.limiter.caller(&req);
+    let budget = *guard.limiter.budget(guard.tier);
+
+    match guard.limiter.check(&caller, guard.tier) {
+        Ok(remaining) => {
+            let mut response = next.run(req).await;
+            let headers = response.headers_mut();
+            headers.insert("x-ratelimit-limit", HeaderValue::from(budget.burst as u64));
+            headers.insert("x-ratelimit-remaining", HeaderValue::from(remaining));
+            response
+        }
+        Err(wait) => {
+            let retry_after_seconds = wait.as_secs_f64().ceil().max(1.0) as u64;
+            tracing::debug!("Rate limited {} ({:?}), retry in {}s", caller, guard.tier, retry_after_seconds);
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(RateLimited {
+                    error: "Rate limit exceeded",
+                    tier: guard.tier,
+                    retry_after_seconds,
+                }),
+            )
+                .into_response();
+            let headers = response.headers_mut();
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after_seconds));
+            headers.insert("x-ratelimit-limit", HeaderValue::from(budget.burst as u64));
+            headers.insert("x-ratelimit-remaining", HeaderValue::from(0u32));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_burst_then_refill() {
+        let budget = Budget::per_minute(60, 2);
+        let t0 = Instant::now();
+        let mut bucket = Bucket::full(&budget, t0);
+
+        assert_eq!(bucket.try_take(&budget, t0), Ok(1));
+        assert_eq!(bucket.try_take(&budget, t0), Ok(0));
+        let wait = bucket.try_take(&budget, t0).unwrap_err();
+        assert!(wait <= Duration::from_secs(1));
+
+        assert!(bucket.try_take(&budget, t0 + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn test_tiers_are_independent() {
+        let config = RateLimitSection {
+            enabled: true,
+            cheap_per_minute: 60,
+            cheap_burst: 5,
+            expensive_per_minute: 1,
+            expensive_burst: 1,
+        };
+        let limiter = RateLimiter::new(&config, Arc::new(AuthConfig::from_env()));
+        let now = Instant::now();
+
+        assert!(limiter.check_at("a", CostTier::Expensive, now).is_ok());
+        assert!(limiter.check_at("a", CostTier::Expensive, now).is_err());
+        assert!(limiter.check_at("a", CostTier::Cheap, now).is_ok());
+        assert!(limiter.check_at("b", CostTier::Expensive, now).is_ok());
+    }
+
+    #[test]
+    fn test_caller_key_falls_back_to_address() {
+        let config = RateLimitSection {
+            enabled: true,
+            cheap_per_minute: 60,
+            cheap_burst: 5,
+            expensive_per_minute: 1,
+            expensive_burst: 1,
+        };
+        let limiter = RateLimiter::new(&config, Arc::new(AuthConfig::from_env()));
+        let addr: SocketAddr = "192.0.2.7:50123".parse().unwrap();
+        assert_eq!(limiter.caller_key(None, Some(addr)), "ip:192.0.2.7");
+        assert_eq!(limiter.caller_key(None, None), "anonymous");
+    }
+}
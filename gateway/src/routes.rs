@@ -1,16 +1,34 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::clock::SimClock;
+use crate::ephemeris;
+use crate::events;
+use orbital_glaf::backhaul::BackhaulConfig;
+use orbital_glaf::latency::LatencyAssumptions;
+use orbital_glaf::objective::{EvaluatedRoute, ObjectiveFunction, RouteMetrics, SlaTier};
+use orbital_glaf::{ConstellationGraph, GlafError};
+use crate::topology::{self, TopologySnapshot};
 use crate::AppState;
+use ground_station_wasm::calculate_look_angles;
+use orbital_mechanics::{GeodeticPosition, Satellite, StateVector};
 use ground_stations::StationStatus;
+use orbital_mechanics::SatelliteStatus;
+
+pub const DEFAULT_ROUTE_CANDIDATES: usize = 5;
+const MAX_ROUTE_CANDIDATES: usize = 20;
 
 #[derive(Serialize)]
 pub struct SatelliteInfo {
     pub id: String,
     pub name: String,
+    pub constellation: String,
     pub norad_id: u32,
     pub plane: u8,
     pub slot: u8,
@@ -40,15 +58,45 @@ pub struct GroundStationInfo {
 pub struct RouteRequest {
     pub source_station: String,
     pub destination_station: String,
-    pub priority: Option<String>,
+    #[serde(default)]
+    pub sla_tier: SlaTier,
+    /// Number of candidate routes to enumerate (default 5)
+    pub k: Option<usize>,
+    pub at: Option<DateTime<Utc>>,
+    /// Consider terrestrial fibre between stations (default true when a
+    /// cable dataset is configured)
+    pub terrestrial: Option<bool>,
 }
 
 #[derive(Serialize)]
 pub struct RouteResponse {
-    pub path: Vec<String>,
-    pub latency_ms: f64,
-    pub quality_score: f64,
-    pub weather_impact: f64,
+    pub epoch: DateTime<Utc>,
+    pub sla_tier: SlaTier,
+    pub objective: ObjectiveFunction,
+    /// Best feasible route, if any candidate meets the SLA
+    pub selected: Option<EvaluatedRoute>,
+    /// Remaining candidates, best first
+    pub alternatives: Vec<EvaluatedRoute>,
+    pub candidates_evaluated: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Deserialize)]
+pub struct LatencyBudgetQuery {
+    /// Comma-separated node IDs, source first (e.g. a selected route's path)
+    pub path: String,
+    pub at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub format: BudgetFormat,
+    /// Include terrestrial fibre hops (default true)
+    pub terrestrial: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -66,46 +114,304 @@ pub struct CollisionCheckResponse {
     pub recommended_action: Option<String>,
 }
 
-pub async fn list_satellites(State(_state): State<AppState>) -> Json<Vec<SatelliteInfo>> {
-    // HALO constellation: 12 satellites in Walker Delta 3/4
-    let satellites: Vec<SatelliteInfo> = (0..12)
+pub async fn list_satellites(State(state): State<AppState>) -> Json<Vec<SatelliteInfo>> {
+    let constellation = state.constellation.load();
+    Json(constellation.satellites.iter().map(satellite_info).collect())
+}
+
+pub fn satellite_info(sat: &Satellite) -> SatelliteInfo {
+    SatelliteInfo {
+        id: sat.id.clone(),
+        name: sat.name.clone(),
+        constellation: sat.constellation.clone(),
+        norad_id: sat.norad_id,
+        plane: sat.plane,
+        slot: sat.slot,
+        status: satellite_status_str(sat.status).to_string(),
+    }
+}
+
+/// API string for a satellite status
+pub fn satellite_status_str(status: SatelliteStatus) -> &'static str {
+    match status {
+        SatelliteStatus::Operational => "operational",
+        SatelliteStatus::Spare => "spare",
+        SatelliteStatus::Maneuvering => "maneuvering",
+        SatelliteStatus::Degraded => "degraded",
+        SatelliteStatus::Offline => "offline",
+    }
+}
+
+/// `?at=<RFC3339>` epoch selector; absent means current sim time
+#[derive(Deserialize)]
+pub struct AtQuery {
+    pub at: Option<DateTime<Utc>>,
+}
+
+impl AtQuery {
+    /// Requested epoch, defaulting to the simulation clock
+    pub fn time(&self, clock: &SimClock) -> DateTime<Utc> {
+        self.at.unwrap_or_else(|| clock.now())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GroundTrackQuery {
+    pub at: Option<DateTime<Utc>>,
+    /// Track length (default one 6-hour MEO orbit)
+    pub minutes: Option<i64>,
+    pub step_s: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct FootprintQuery {
+    pub at: Option<DateTime<Utc>>,
+    /// Elevation mask (default the link mask)
+    pub min_elevation_deg: Option<f64>,
+    /// Edge points (default 72)
+    pub vertices: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct GroundTrackPoint {
+    pub timestamp: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: f64,
+}
+
+#[derive(Serialize)]
+pub struct StationVisibility {
+    pub station_id: String,
+    pub azimuth_deg: f64,
+    pub elevation_deg: f64,
+    pub range_km: f64,
+}
+
+#[derive(Serialize)]
+pub struct VisibilityResponse {
+    pub satellite_id: String,
+    pub timestamp: String,
+    pub stations: Vec<StationVisibility>,
+}
+
+pub fn find_satellite(state: &AppState, id: &str) -> Result<Satellite, (StatusCode, String)> {
+    state
+        .constellation
+        .load()
+        .satellites
+        .iter()
+        .find(|s| s.id == id)
+        .cloned()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Satellite not found: {}", id)))
+}
+
+/// Propagate a satellite and convert to geodetic
+pub fn propagate_geodetic(
+    sat: &Satellite,
+    time: DateTime<Utc>,
+) -> Result<(StateVector, GeodeticPosition), (StatusCode, String)> {
+    let sv = sat
+        .propagate(time)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+    let geo = sv.to_geodetic().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok((sv, geo))
+}
+
+pub async fn get_position(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<AtQuery>,
+) -> Result<Json<Position>, (StatusCode, String)> {
+    let time = q.time(&state.clock);
+    let sat = find_satellite(&state, &id)?;
+    let (sv, geo) = ephemeris::state_geodetic(&state, &sat, time)?;
+
+    Ok(Json(Position {
+        latitude: geo.latitude,
+        longitude: geo.longitude,
+        altitude_km: geo.altitude_km,
+        velocity_km_s: (sv.velocity_x.powi(2) + sv.velocity_y.powi(2) + sv.velocity_z.powi(2)).sqrt(),
+        timestamp: time.to_rfc3339(),
+    }))
+}
+
+/// Sub-satellite points from `at` forward
+pub async fn get_ground_track(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<GroundTrackQuery>,
+) -> Result<Json<Vec<GroundTrackPoint>>, (StatusCode, String)> {
+    let start = q.at.unwrap_or_else(|| state.clock.now());
+    let minutes = q.minutes.unwrap_or(360).clamp(1, 7 * 24 * 60);
+    let step_s = q.step_s.unwrap_or(60).clamp(1, minutes * 60);
+    let steps = track_steps(start, minutes, step_s)?;
+    let sat = find_satellite(&state, &id)?;
+
+    let points = (0..=steps)
         .map(|i| {
-            let plane = (i / 4) + 1;
-            let slot = (i % 4) + 1;
-            SatelliteInfo {
-                id: format!("HALO-{:02}", i + 1),
-                name: format!("HALO-{}{}", plane, slot),
-                norad_id: 60000 + i as u32,
-                plane: plane as u8,
-                slot: slot as u8,
-                status: if i < 8 { "operational" } else { "spare" }.to_string(),
-            }
+            let t = start + Duration::seconds(i * step_s);
+            let (_, geo) = ephemeris::state_geodetic(&state, &sat, t)?;
+            Ok(GroundTrackPoint {
+                timestamp: t.to_rfc3339(),
+                latitude: geo.latitude,
+                longitude: geo.longitude,
+                altitude_km: geo.altitude_km,
+            })
         })
-        .collect();
+        .collect::<Result<Vec<_>, (StatusCode, String)>>()?;
 
-    Json(satellites)
+    Ok(Json(points))
 }
 
-pub async fn get_position(
-    State(_state): State<AppState>,
+/// Steps in a ground track of `minutes` from `start` at `step_s`,
+/// refused past `ground_track::MAX_SAMPLES` points or the calendar
+fn track_steps(start: DateTime<Utc>, minutes: i64, step_s: i64) -> Result<i64, (StatusCode, String)> {
+    if start.checked_add_signed(Duration::minutes(minutes)).is_none() {
+        return Err((StatusCode::BAD_REQUEST, format!("ground track from {} runs out of range", start)));
+    }
+    let steps = minutes * 60 / step_s;
+    if steps >= orbital_mechanics::ground_track::MAX_SAMPLES {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "ground track of {} points exceeds {}; raise step_s or shorten minutes",
+                steps + 1,
+                orbital_mechanics::ground_track::MAX_SAMPLES
+            ),
+        ));
+    }
+    Ok(steps)
+}
+
+/// Ground track from `at` forward as a GeoJSON Feature, split at the
+/// antimeridian
+pub async fn get_ground_track_geojson(
+    State(state): State<AppState>,
     Path(id): Path<String>,
-) -> Json<Position> {
-    // Placeholder - would use SGP4 propagation
-    Json(Position {
-        latitude: 35.0,
-        longitude: -120.0,
-        altitude_km: 10500.0,
-        velocity_km_s: 4.5,
-        timestamp: chrono::Utc::now().to_rfc3339(),
-    })
+    Query(q): Query<GroundTrackQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let start = q.at.unwrap_or_else(|| state.clock.now());
+    let minutes = q.minutes.unwrap_or(360).clamp(1, 7 * 24 * 60);
+    let step_s = q.step_s.unwrap_or(60).clamp(1, minutes * 60);
+    track_steps(start, minutes, step_s)?;
+    let sat = find_satellite(&state, &id)?;
+
+    let track = orbital_mechanics::ground_track::sample(
+        &sat,
+        start,
+        start + Duration::minutes(minutes),
+        Duration::seconds(step_s),
+    )
+    .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "type": "Feature",
+        "id": sat.id,
+        "geometry": { "type": "MultiLineString", "coordinates": track.coordinates() },
+        "properties": {
+            "name": sat.name,
+            "start": start,
+            "minutes": minutes,
+            "step_s": step_s,
+        },
+    })))
+}
+
+/// Coverage circle of the satellite at `at` above the elevation mask
+pub async fn get_footprint(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<FootprintQuery>,
+) -> Result<Json<orbital_mechanics::footprint::Footprint>, (StatusCode, String)> {
+    let time = q.at.unwrap_or_else(|| state.clock.now());
+    let sat = find_satellite(&state, &id)?;
+    let (_, geo) = ephemeris::state_geodetic(&state, &sat, time)?;
+    let min_elevation_deg = q.min_elevation_deg.unwrap_or(crate::propagation::MIN_LINK_ELEVATION_DEG);
+    let vertices = q.vertices.unwrap_or(72).clamp(3, 720);
+
+    orbital_mechanics::footprint::footprint(&geo, min_elevation_deg, vertices)
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+/// Ground stations with the satellite above the link elevation mask at `at`
+pub async fn get_visibility(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<AtQuery>,
+) -> Result<Json<VisibilityResponse>, (StatusCode, String)> {
+    let time = q.time(&state.clock);
+    let sat = find_satellite(&state, &id)?;
+    let (_, geo) = ephemeris::state_geodetic(&state, &sat, time)?;
+
+    let constellation = state.constellation.load();
+    let stations = constellation
+        .operational_stations()
+        .filter_map(|station| {
+            let angles = calculate_look_angles(
+                station.location.latitude,
+                station.location.longitude,
+                station.location.altitude_m / 1000.0,
+                geo.latitude,
+                geo.longitude,
+                geo.altitude_km,
+            );
+            (angles.elevation_deg >= crate::propagation::MIN_LINK_ELEVATION_DEG).then(|| {
+                StationVisibility {
+                    station_id: station.id.clone(),
+                    azimuth_deg: angles.azimuth_deg,
+                    elevation_deg: angles.elevation_deg,
+                    range_km: angles.range_km,
+                }
+            })
+        })
+        .collect();
+
+    Ok(Json(VisibilityResponse {
+        satellite_id: id,
+        timestamp: time.to_rfc3339(),
+        stations,
+    }))
+}
+
+/// Full node/link topology at `at`
+pub async fn get_topology(
+    State(state): State<AppState>,
+    Query(q): Query<AtQuery>,
+) -> Result<Json<TopologySnapshot>, (StatusCode, String)> {
+    topology::snapshot(&state, q.time(&state.clock))
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Topology at `at` as a GeoJSON FeatureCollection
+pub async fn get_topology_geojson(
+    State(state): State<AppState>,
+    Query(q): Query<AtQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    topology::snapshot(&state, q.time(&state.clock))
+        .map(|snapshot| Json(snapshot.to_geojson()))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// API string for a station status (shared by REST and gRPC)
+pub fn station_status_str(status: StationStatus) -> &'static str {
+    match status {
+        StationStatus::Operational => "operational",
+        StationStatus::Degraded => "degraded",
+        StationStatus::WeatherHold => "weather_hold",
+        StationStatus::Maintenance => "maintenance",
+        StationStatus::Offline => "offline",
+    }
 }
 
 pub async fn list_ground_stations(
     State(state): State<AppState>,
 ) -> Json<Vec<GroundStationInfo>> {
-    let stations = state
-        .station_registry
-        .operational()
+    let constellation = state.constellation.load();
+    let stations = constellation
+        .operational_stations()
         .map(|station| {
             let weather_score = station
                 .weather
@@ -113,20 +419,12 @@ pub async fn list_ground_stations(
                 .map(|w| w.beam_quality_score)
                 .unwrap_or(1.0);
 
-            let status = match station.status {
-                StationStatus::Operational => "operational",
-                StationStatus::Degraded => "degraded",
-                StationStatus::WeatherHold => "weather_hold",
-                StationStatus::Maintenance => "maintenance",
-                StationStatus::Offline => "offline",
-            };
-
             GroundStationInfo {
                 id: station.id.clone(),
                 name: station.name.clone(),
                 latitude: station.location.latitude,
                 longitude: station.location.longitude,
-                status: status.to_string(),
+                status: station_status_str(station.status).to_string(),
                 weather_score,
             }
         })
@@ -135,21 +433,123 @@ pub async fn list_ground_stations(
     Json(stations)
 }
 
+/// Routing graph of a topology snapshot: weather applied and, when
+/// `terrestrial`, fibre backhaul between stations
+pub fn routing_graph(state: &AppState, snapshot: &TopologySnapshot, terrestrial: bool) -> ConstellationGraph {
+    let mut graph = snapshot.to_graph();
+    state.weather.apply_to(&mut graph);
+    if terrestrial && !state.backhaul.is_empty() {
+        graph.add_fiber_backhaul(&state.backhaul, &BackhaulConfig::default());
+    }
+    graph
+}
+
+/// Enumerate k candidate routes on the live topology and pick the best
+/// under the requested SLA tier's objective function
 pub async fn calculate_route(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Json(request): Json<RouteRequest>,
-) -> Json<RouteResponse> {
-    // Placeholder - would use beam-routing crate with weather-aware ANN/CNN
-    Json(RouteResponse {
-        path: vec![
-            request.source_station.clone(),
-            "HALO-11".to_string(),
-            "HALO-21".to_string(),
-            request.destination_station.clone(),
-        ],
-        latency_ms: 85.0,
-        quality_score: 0.94,
-        weather_impact: 0.03,
+) -> Result<Json<RouteResponse>, (StatusCode, String)> {
+    let epoch = request.at.unwrap_or_else(|| state.clock.now());
+    let k = request.k.unwrap_or(DEFAULT_ROUTE_CANDIDATES).clamp(1, MAX_ROUTE_CANDIDATES);
+
+    let terrestrial = request.terrestrial.unwrap_or(true);
+    // Topology, graph and Yen's search are CPU-bound; keep them off the
+    // async workers
+    let candidates: Vec<RouteMetrics> = {
+        let state = state.clone();
+        let (source, destination) = (request.source_station.clone(), request.destination_station.clone());
+        tokio::task::spawn_blocking(move || {
+            let snapshot = topology::snapshot(&state, epoch)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            let graph = routing_graph(&state, &snapshot, terrestrial);
+            let paths = graph.k_shortest_paths(&source, &destination, k).map_err(|e| match e {
+                GlafError::NodeNotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+                _ => (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()),
+            })?;
+            Ok::<_, (StatusCode, String)>(
+                paths
+                    .iter()
+                    .filter_map(|path| RouteMetrics::from_path(&graph, path))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??
+    };
+
+    let objective = ObjectiveFunction::for_tier(request.sla_tier);
+    let mut ranked = objective.rank(&candidates).into_iter();
+    let selected = objective.select_optimal(&candidates);
+    if selected.is_some() {
+        ranked.next();
+    }
+    if request.at.is_none() {
+        state.events.watch_route(events::WatchedRoute {
+            source: request.source_station.clone(),
+            destination: request.destination_station.clone(),
+            sla_tier: request.sla_tier,
+            k,
+            terrestrial,
+            path: selected.as_ref().map(|r| r.metrics.path.clone()),
+        });
+    }
+
+    Ok(Json(RouteResponse {
+        epoch,
+        sla_tier: request.sla_tier,
+        selected,
+        alternatives: ranked.collect(),
+        candidates_evaluated: candidates.len(),
+        objective,
+    }))
+}
+
+/// GET /routing/latency-budget?path=GS-001,HALO-0101,GS-002&format=csv -
+/// per-hop propagation, switching and queueing delay of a route on the
+/// live topology
+pub async fn latency_budget(
+    State(state): State<AppState>,
+    Query(q): Query<LatencyBudgetQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let path: Vec<String> = q
+        .path
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let epoch = q.at.unwrap_or_else(|| state.clock.now());
+    let terrestrial = q.terrestrial.unwrap_or(true);
+    let budget = {
+        let state = state.clone();
+        tokio::task::spawn_blocking(move || {
+            let snapshot = topology::snapshot(&state, epoch)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            routing_graph(&state, &snapshot, terrestrial)
+                .latency_budget(&path, &LatencyAssumptions::default())
+                .map_err(|e| match e {
+                    GlafError::NodeNotFound(_) | GlafError::LinkNotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+                    _ => (StatusCode::BAD_REQUEST, e.to_string()),
+                })
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??
+    };
+    Ok(match q.format {
+        BudgetFormat::Json => Json(budget).into_response(),
+        BudgetFormat::Csv => {
+            let csv = budget
+                .to_csv()
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            (
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                    (header::CONTENT_DISPOSITION, "inline; filename=\"latency-budget.csv\""),
+                ],
+                csv,
+            )
+                .into_response()
+        }
     })
 }
 
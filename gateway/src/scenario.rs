@@ -0,0 +1,884 @@
+//! Scenario timelines
+//!
+//! A scenario is a YAML timeline of events injected at scripted offsets
+//! from the moment it starts, measured on the sim clock (so pausing or
+//! accelerating the clock pauses or accelerates the script):
+//!
+//! ```yaml
+//! name: european-storm
+//! start: 2026-03-01T00:00:00Z        # optional: jump the clock here first
+//! clock: { mode: accelerated, factor: 60 }
+//! events:
+//!   - at: 10m
+//!     weather_front: { stations: [GS-FRA], cloud_cover_pct: 90, beam_quality_score: 0.2, duration: 45m }
+//!   - at: 20m
+//!     link_failure: { target: "HALO-01|GS-FRA", duration: 10m }
+//!   - at: 30m
+//!     conjunction: { satellite: HALO-03, object: COSMOS 2251 DEB, miss_distance_km: 0.8 }
+//!   - at: 40m
+//!     traffic_surge: { targets: [GS-FRA], utilization: 0.9, duration: 15m }
+//!   - at: 1h
+//!     maneuver: { satellite: HALO-03, duration: 20m }
+//! ```
+//!
+//! Weather fronts overwrite station conditions and restore them afterwards;
+//! failures and surges become sim-timed chaos faults; maneuvers hold the
+//! satellite in `Maneuvering` (optionally applying new elements at the
+//! end); conjunctions are raised as alerts on `orbital.conjunction.{sat}`.
+//! Every step is stamped with its scripted sim time, so reruns from the
+//! same start are identical. One scenario runs at a time; progress is at
+//! `GET /scenarios/current` and on `orbital.scenario.{run}`.
+
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use ground_stations::WeatherConditions;
+use orbital_mechanics::tle::parse_tle_text;
+use orbital_mechanics::SatelliteStatus;
+
+use crate::chaos::{self, Fault, FaultEffect, TargetKind};
+use crate::clock::ClockMode;
+use crate::schema::ConjunctionAlert;
+use crate::{AppState, ConstellationState};
+
+/// How often a waiting run re-reads the sim clock
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+/// Log entries kept in the progress report
+const MAX_LOG_ENTRIES: usize = 500;
+/// Longest offset or duration a scenario may script
+const MAX_OFFSET_DAYS: i64 = 365;
+
+/// Sim-time offset: seconds as a number, or `90s`, `10m`, `1h30m`, `2d`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "OffsetRepr")]
+pub struct Offset(pub Duration);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OffsetRepr {
+    Seconds(f64),
+    Text(String),
+}
+
+impl TryFrom<OffsetRepr> for Offset {
+    type Error = String;
+
+    fn try_from(repr: OffsetRepr) -> Result<Self, String> {
+        match repr {
+            OffsetRepr::Seconds(s) if s.is_finite() && (0.0..=max_offset_secs()).contains(&s) => {
+                Ok(Offset(Duration::milliseconds((s * 1000.0) as i64)))
+            }
+            OffsetRepr::Seconds(s) => Err(format!("Invalid offset: {}", s)),
+            OffsetRepr::Text(text) => parse_offset(&text).map(Offset),
+        }
+    }
+}
+
+fn max_offset_secs() -> f64 {
+    (MAX_OFFSET_DAYS * 86_400) as f64
+}
+
+pub fn parse_offset(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid offset {:?} (use e.g. 90s, 10m, 1h30m)", text);
+    let mut total_secs = 0.0;
+    let mut number = String::new();
+    for c in text.trim().chars() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let value: f64 = number.parse().map_err(|_| invalid())?;
+        let unit_secs = match c {
+            's' => 1.0,
+            'm' => 60.0,
+            'h' => 3600.0,
+            'd' => 86_400.0,
+            _ => return Err(invalid()),
+        };
+        total_secs += value * unit_secs;
+        number.clear();
+    }
+    if !number.is_empty() {
+        // Bare number: seconds
+        let value: f64 = number.parse().map_err(|_| invalid())?;
+        total_secs += value;
+    } else if text.trim().is_empty() {
+        return Err(invalid());
+    }
+    if total_secs > max_offset_secs() {
+        return Err(format!("Offset {:?} is beyond {} days", text, MAX_OFFSET_DAYS));
+    }
+    Ok(Duration::milliseconds((total_secs * 1000.0).round() as i64))
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Jump the sim clock here before the first event
+    #[serde(default)]
+    pub start: Option<DateTime<Utc>>,
+    /// Clock mode for the run
+    #[serde(default)]
+    pub clock: Option<ClockMode>,
+    pub events: Vec<ScenarioEvent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioEvent {
+    pub at: Offset,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    WeatherFront {
+        stations: Vec<String>,
+        beam_quality_score: f64,
+        #[serde(default = "default_cloud_cover")]
+        cloud_cover_pct: f64,
+        #[serde(default = "default_visibility")]
+        visibility_km: f64,
+        #[serde(default)]
+        precipitation_mm_hr: f64,
+        duration: Offset,
+    },
+    /// Fail (or, with `margin_loss_db`, degrade) a link, satellite or station
+    #[serde(alias = "failure")]
+    LinkFailure {
+        #[serde(default = "default_target_kind")]
+        kind: TargetKind,
+        target: String,
+        #[serde(default)]
+        margin_loss_db: Option<f64>,
+        duration: Offset,
+    },
+    Conjunction {
+        satellite: String,
+        object: String,
+        miss_distance_km: f64,
+        #[serde(default)]
+        probability: Option<f64>,
+    },
+    TrafficSurge {
+        /// Station or satellite IDs, or link IDs (`{satellite}|{station}`)
+        targets: Vec<String>,
+        utilization: f64,
+        duration: Offset,
+    },
+    Maneuver {
+        satellite: String,
+        duration: Offset,
+        /// Post-maneuver elements applied when the maneuver completes
+        #[serde(default)]
+        tle: Option<[String; 2]>,
+    },
+}
+
+fn default_cloud_cover() -> f64 {
+    90.0
+}
+
+fn default_visibility() -> f64 {
+    2.0
+}
+
+fn default_target_kind() -> TargetKind {
+    TargetKind::Link
+}
+
+impl Scenario {
+    pub fn from_yaml(text: &str) -> Result<Self, String> {
+        let scenario: Scenario = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+        if scenario.name.trim().is_empty() {
+            return Err("Scenario name must not be empty".to_string());
+        }
+        if scenario.events.is_empty() {
+            return Err("Scenario has no events".to_string());
+        }
+        for (i, event) in scenario.events.iter().enumerate() {
+            if event.at.0 > Duration::days(MAX_OFFSET_DAYS) {
+                return Err(format!("events[{}]: at is beyond {} days", i, MAX_OFFSET_DAYS));
+            }
+            event
+                .kind
+                .check_values()
+                .map_err(|e| format!("events[{}] ({}): {}", i, event.kind.name(), e))?;
+        }
+        Ok(scenario)
+    }
+
+    /// Check referenced satellites and stations exist
+    pub fn check_targets(&self, constellation: &ConstellationState) -> Result<(), String> {
+        let satellite = |id: &str| {
+            constellation
+                .satellites
+                .iter()
+                .any(|s| s.id == id)
+                .then_some(())
+                .ok_or_else(|| format!("Satellite not found: {}", id))
+        };
+        let station = |id: &str| {
+            constellation
+                .station(id)
+                .map(|_| ())
+                .ok_or_else(|| format!("Station not found: {}", id))
+        };
+        for (i, event) in self.events.iter().enumerate() {
+            let result = match &event.kind {
+                EventKind::WeatherFront { stations, .. } => stations.iter().try_for_each(|id| station(id)),
+                EventKind::LinkFailure { kind, target, .. } => match kind {
+                    TargetKind::Satellite => satellite(target),
+                    TargetKind::Station => station(target),
+                    // Topology link IDs only exist while the link is in view
+                    TargetKind::Link => Ok(()),
+                },
+                EventKind::Conjunction { satellite: id, .. } | EventKind::Maneuver { satellite: id, .. } => {
+                    satellite(id)
+                }
+                EventKind::TrafficSurge { targets, .. } => targets.iter().try_for_each(|id| {
+                    match id.split_once('|') {
+                        Some((sat, gs)) => satellite(sat).and_then(|_| station(gs)),
+                        None => satellite(id).or_else(|_| station(id)),
+                    }
+                }),
+            };
+            result.map_err(|e| format!("events[{}] ({}): {}", i, event.kind.name(), e))?;
+        }
+        Ok(())
+    }
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::WeatherFront { .. } => "weather_front",
+            EventKind::LinkFailure { .. } => "link_failure",
+            EventKind::Conjunction { .. } => "conjunction",
+            EventKind::TrafficSurge { .. } => "traffic_surge",
+            EventKind::Maneuver { .. } => "maneuver",
+        }
+    }
+
+    fn check_values(&self) -> Result<(), String> {
+        let positive = |d: &Offset| {
+            if d.0 <= Duration::zero() {
+                Err("duration must be positive".to_string())
+            } else if d.0 > Duration::days(MAX_OFFSET_DAYS) {
+                Err(format!("duration is beyond {} days", MAX_OFFSET_DAYS))
+            } else {
+                Ok(())
+            }
+        };
+        match self {
+            EventKind::WeatherFront {
+                stations,
+                beam_quality_score,
+                duration,
+                ..
+            } => {
+                if stations.is_empty() {
+                    return Err("no stations".to_string());
+                }
+                if !(0.0..=1.0).contains(beam_quality_score) {
+                    return Err("beam_quality_score must be within 0-1".to_string());
+                }
+                positive(duration)
+            }
+            EventKind::LinkFailure {
+                margin_loss_db,
+                duration,
+                ..
+            } => {
+                if margin_loss_db.is_some_and(|db| !(db > 0.0 && db.is_finite())) {
+                    return Err("margin_loss_db must be positive".to_string());
+                }
+                positive(duration)
+            }
+            EventKind::Conjunction { miss_distance_km, .. } => {
+                if *miss_distance_km >= 0.0 {
+                    Ok(())
+                } else {
+                    Err("miss_distance_km must not be negative".to_string())
+                }
+            }
+            EventKind::TrafficSurge {
+                targets,
+                utilization,
+                duration,
+            } => {
+                if targets.is_empty() {
+                    return Err("no targets".to_string());
+                }
+                if !(0.0..=1.0).contains(utilization) {
+                    return Err("utilization must be within 0-1".to_string());
+                }
+                positive(duration)
+            }
+            EventKind::Maneuver { duration, tle, .. } => {
+                if let Some([line1, line2]) = tle {
+                    parse_tle_text(&format!("{}\n{}", line1, line2)).map_err(|e| e.to_string())?;
+                }
+                positive(duration)
+            }
+        }
+    }
+}
+
+/// One timeline step. Events with a duration expand to a begin and an end.
+#[derive(Debug, Clone)]
+struct Step {
+    at: Duration,
+    event: usize,
+    end: bool,
+}
+
+fn timeline(scenario: &Scenario) -> Vec<Step> {
+    let mut steps = Vec::new();
+    for (i, event) in scenario.events.iter().enumerate() {
+        let begin = event.at.0;
+        steps.push(Step {
+            at: begin,
+            event: i,
+            end: false,
+        });
+        // Faults expire on their own; only state overrides need an end step
+        match &event.kind {
+            EventKind::WeatherFront { duration, .. } | EventKind::Maneuver { duration, .. } => {
+                steps.push(Step {
+                    at: begin.checked_add(&duration.0).unwrap_or(Duration::MAX),
+                    event: i,
+                    end: true,
+                })
+            }
+            _ => {}
+        }
+    }
+    // Stable: scripted order breaks ties
+    steps.sort_by_key(|s| s.at);
+    steps
+}
+
+// ========== Runs ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunState {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub event: usize,
+    pub kind: &'static str,
+    pub phase: &'static str,
+    pub sim_time: DateTime<Utc>,
+    pub detail: String,
+    pub ok: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunStatus {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub state: RunState,
+    /// Sim time the offsets are measured from
+    pub epoch: DateTime<Utc>,
+    pub total_steps: usize,
+    pub applied_steps: usize,
+    pub failed_steps: usize,
+    pub next_step_at: Option<DateTime<Utc>>,
+    pub log: Vec<LogEntry>,
+}
+
+pub struct ScenarioRun {
+    scenario: Scenario,
+    cancelled: AtomicBool,
+    status: Mutex<RunStatus>,
+    /// Conditions/statuses overridden at a begin step, restored at its end
+    saved_weather: Mutex<HashMap<usize, Vec<(String, Option<WeatherConditions>)>>>,
+    saved_status: Mutex<HashMap<usize, SatelliteStatus>>,
+}
+
+impl ScenarioRun {
+    pub fn status(&self) -> RunStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    fn record(&self, entry: LogEntry) {
+        let mut status = self.status.lock().unwrap();
+        status.applied_steps += 1;
+        if !entry.ok {
+            status.failed_steps += 1;
+        }
+        if status.log.len() >= MAX_LOG_ENTRIES {
+            status.log.remove(0);
+        }
+        status.log.push(entry);
+    }
+}
+
+#[derive(Default)]
+pub struct ScenarioEngine {
+    next_id: AtomicU64,
+    current: Mutex<Option<Arc<ScenarioRun>>>,
+}
+
+impl ScenarioEngine {
+    pub fn current(&self) -> Option<Arc<ScenarioRun>> {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Register a run unless one is still running
+    fn begin(&self, scenario: Scenario, epoch: DateTime<Utc>) -> Result<Arc<ScenarioRun>, String> {
+        let mut current = self.current.lock().unwrap();
+        if let Some(run) = current.as_ref() {
+            if run.status().state == RunState::Running {
+                return Err(format!("Scenario {} is still running", run.status().id));
+            }
+        }
+        let total_steps = timeline(&scenario).len();
+        let run = Arc::new(ScenarioRun {
+            status: Mutex::new(RunStatus {
+                id: format!("scn-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1),
+                name: scenario.name.clone(),
+                description: scenario.description.clone(),
+                state: RunState::Running,
+                epoch,
+                total_steps,
+                applied_steps: 0,
+                failed_steps: 0,
+                next_step_at: None,
+                log: Vec::new(),
+            }),
+            scenario,
+            cancelled: AtomicBool::new(false),
+            saved_weather: Mutex::new(HashMap::new()),
+            saved_status: Mutex::new(HashMap::new()),
+        });
+        *current = Some(run.clone());
+        Ok(run)
+    }
+}
+
+/// Drive `run` to completion against the sim clock
+async fn execute(state: AppState, run: Arc<ScenarioRun>) {
+    let epoch = run.status().epoch;
+    for step in timeline(&run.scenario) {
+        let event = &run.scenario.events[step.event];
+        let Some(due) = epoch.checked_add_signed(step.at) else {
+            let detail = format!("offset {} out of range from {}", step.at, epoch);
+            tracing::warn!("Scenario {}: {} failed: {}", run.scenario.name, event.kind.name(), detail);
+            run.record(LogEntry {
+                event: step.event,
+                kind: event.kind.name(),
+                phase: if step.end { "end" } else { "begin" },
+                sim_time: epoch,
+                ok: false,
+                detail,
+            });
+            continue;
+        };
+        run.status.lock().unwrap().next_step_at = Some(due);
+        while state.clock.now() < due {
+            if run.cancelled.load(Ordering::Relaxed) {
+                finish(&state, &run, RunState::Cancelled).await;
+                return;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        let result = apply(&state, &run, &step, due);
+        let entry = LogEntry {
+            event: step.event,
+            kind: event.kind.name(),
+            phase: if step.end { "end" } else { "begin" },
+            sim_time: due,
+            ok: result.is_ok(),
+            detail: result.unwrap_or_else(|e| e),
+        };
+        if entry.ok {
+            tracing::info!("Scenario {} @{}: {} {}", run.scenario.name, due, entry.kind, entry.detail);
+        } else {
+            tracing::warn!("Scenario {} @{}: {} failed: {}", run.scenario.name, due, entry.kind, entry.detail);
+        }
+        publish(&state, &run, &entry).await;
+        if entry.ok && !step.end {
+            publish_conjunction(&state, &event.kind, due).await;
+        }
+        run.record(entry);
+    }
+    finish(&state, &run, RunState::Completed).await;
+}
+
+async fn finish(state: &AppState, run: &ScenarioRun, final_state: RunState) {
+    let status = {
+        let mut status = run.status.lock().unwrap();
+        status.state = final_state;
+        status.next_step_at = None;
+        status.clone()
+    };
+    tracing::info!("Scenario {} {:?}", status.name, final_state);
+    if let Some(telemetry) = &state.telemetry {
+        if let Err(e) = telemetry.publish_scenario(&status.id, &status).await {
+            tracing::warn!("Scenario status publish failed: {}", e);
+        }
+    }
+}
+
+async fn publish(state: &AppState, run: &ScenarioRun, entry: &LogEntry) {
+    if let Some(telemetry) = &state.telemetry {
+        let id = run.status().id;
+        if let Err(e) = telemetry.publish_scenario(&id, entry).await {
+            tracing::warn!("Scenario event publish failed: {}", e);
+        }
+    }
+}
+
+/// Raise a scripted conjunction as an alert at its step time
+async fn publish_conjunction(state: &AppState, kind: &EventKind, tca: DateTime<Utc>) {
+    let (
+        Some(telemetry),
+        EventKind::Conjunction {
+            satellite,
+            object,
+            miss_distance_km,
+            probability,
+        },
+    ) = (&state.telemetry, kind)
+    else {
+        return;
+    };
+    let alert = ConjunctionAlert {
+        satellite_id: satellite.clone(),
+        object: object.clone(),
+        tca,
+        miss_distance_km: *miss_distance_km,
+        probability: *probability,
+        source: "scenario".to_string(),
+    };
+    if let Err(e) = telemetry.publish_conjunction(&alert).await {
+        tracing::warn!("Conjunction alert publish failed: {}", e);
+    }
+}
+
+/// Apply one step at scripted sim time `at`; `Ok(detail)` or `Err(reason)`
+fn apply(state: &AppState, run: &ScenarioRun, step: &Step, at: DateTime<Utc>) -> Result<String, String> {
+    match (&run.scenario.events[step.event].kind, step.end) {
+        (
+            EventKind::WeatherFront {
+                stations,
+                beam_quality_score,
+                cloud_cover_pct,
+                visibility_km,
+                precipitation_mm_hr,
+                ..
+            },
+            false,
+        ) => {
+            let conditions = WeatherConditions {
+                cloud_cover_pct: *cloud_cover_pct,
+                visibility_km: *visibility_km,
+                precipitation_mm_hr: *precipitation_mm_hr,
+                wind_speed_ms: 0.0,
+                temperature_c: 15.0,
+                humidity_pct: 90.0,
+                beam_quality_score: *beam_quality_score,
+                timestamp: at,
+            };
+            let saved = state.constellation.update(|current| {
+                let mut next = current.clone();
+                let mut saved = Vec::new();
+                for station in next.ground_stations.iter_mut().filter(|s| stations.contains(&s.id)) {
+                    saved.push((station.id.clone(), station.weather.replace(conditions.clone())));
+                }
+                (next, saved)
+            });
+            let detail = format!("{} stations at beam quality {}", saved.len(), beam_quality_score);
+            run.saved_weather.lock().unwrap().insert(step.event, saved);
+            Ok(detail)
+        }
+        (EventKind::WeatherFront { .. }, true) => {
+            let saved = run.saved_weather.lock().unwrap().remove(&step.event).unwrap_or_default();
+            let restored = saved.len();
+            state.constellation.update(|current| {
+                let mut next = current.clone();
+                for (id, weather) in saved {
+                    if let Some(station) = next.ground_stations.iter_mut().find(|s| s.id == id) {
+                        station.weather = weather;
+                    }
+                }
+                (next, ())
+            });
+            Ok(format!("{} stations cleared", restored))
+        }
+        (
+            EventKind::LinkFailure {
+                kind,
+                target,
+                margin_loss_db,
+                duration,
+            },
+            _,
+        ) => {
+            let effect = match margin_loss_db {
+                Some(db) => FaultEffect::Degrade { margin_loss_db: *db },
+                None => FaultEffect::Fail,
+            };
+            inject_fault(state, *kind, target, effect, at, duration.0)
+        }
+        (
+            EventKind::TrafficSurge {
+                targets,
+                utilization,
+                duration,
+            },
+            _,
+        ) => {
+            let effect = FaultEffect::Congest {
+                utilization: *utilization,
+            };
+            let faults = targets
+                .iter()
+                .map(|target| {
+                    let kind = if target.contains('|') {
+                        TargetKind::Link
+                    } else if state.constellation.load().station(target).is_some() {
+                        TargetKind::Station
+                    } else {
+                        TargetKind::Satellite
+                    };
+                    inject_fault(state, kind, target, effect, at, duration.0)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(faults.join(", "))
+        }
+        (
+            EventKind::Conjunction {
+                satellite,
+                object,
+                miss_distance_km,
+                probability,
+            },
+            _,
+        ) => Ok(format!(
+            "{} vs {}: miss {:.3} km{}",
+            satellite,
+            object,
+            miss_distance_km,
+            probability.map(|p| format!(", Pc {:.1e}", p)).unwrap_or_default()
+        )),
+        (EventKind::Maneuver { satellite, .. }, false) => {
+            let previous = set_satellite(state, satellite, |sat| {
+                std::mem::replace(&mut sat.status, SatelliteStatus::Maneuvering)
+            })?;
+            run.saved_status.lock().unwrap().insert(step.event, previous);
+            Ok(format!("{} maneuvering", satellite))
+        }
+        (EventKind::Maneuver { satellite, tle, .. }, true) => {
+            let previous = run
+                .saved_status
+                .lock()
+                .unwrap()
+                .remove(&step.event)
+                .unwrap_or(SatelliteStatus::Operational);
+            set_satellite(state, satellite, |sat| {
+                sat.status = previous;
+                if let Some([line1, line2]) = tle {
+                    sat.tle_line1 = line1.clone();
+                    sat.tle_line2 = line2.clone();
+                }
+            })?;
+            Ok(format!(
+                "{} back to {:?}{}",
+                satellite,
+                previous,
+                if tle.is_some() { " with new elements" } else { "" }
+            ))
+        }
+    }
+}
+
+fn set_satellite<T>(
+    state: &AppState,
+    id: &str,
+    f: impl FnOnce(&mut orbital_mechanics::Satellite) -> T,
+) -> Result<T, String> {
+    state.constellation.update(|current| {
+        let mut next = current.clone();
+        let out = next
+            .satellites
+            .iter_mut()
+            .find(|s| s.id == id)
+            .map(f)
+            .ok_or_else(|| format!("Satellite not found: {}", id));
+        (next, out)
+    })
+}
+
+fn inject_fault(
+    state: &AppState,
+    kind: TargetKind,
+    target: &str,
+    effect: FaultEffect,
+    at: DateTime<Utc>,
+    duration: Duration,
+) -> Result<String, String> {
+    let endpoints = chaos::resolve(state, kind, target, at).map_err(|(_, e)| e)?;
+    let fault = state.chaos.inject(Fault {
+        id: String::new(),
+        kind,
+        target: target.to_string(),
+        endpoints,
+        effect,
+        injected_at: at,
+        expires_at: at + duration,
+        injected_by: Some("scenario".to_string()),
+    });
+    state.events.fault("injected", &fault, at);
+    chaos::remember(state, "injected", &fault, at);
+    Ok(format!("{} on {}", fault.id, target))
+}
+
+// ========== Routes ==========
+
+/// POST /scenarios - body is the YAML timeline; starts it immediately
+pub async fn start_scenario(
+    State(state): State<AppState>,
+    body: String,
+) -> Result<Json<RunStatus>, (StatusCode, String)> {
+    let scenario = Scenario::from_yaml(&body).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    scenario
+        .check_targets(&state.constellation.load())
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    if let Some(mode) = scenario.clock {
+        state.clock.set_mode(mode);
+    }
+    if let Some(start) = scenario.start {
+        state.clock.set_time(start);
+    }
+    let epoch = state.clock.now();
+
+    let run = state
+        .scenarios
+        .begin(scenario, epoch)
+        .map_err(|e| (StatusCode::CONFLICT, e))?;
+    let status = run.status();
+    tracing::info!(
+        "Scenario {} ({}) started at sim time {}, {} steps",
+        status.name,
+        status.id,
+        epoch,
+        status.total_steps
+    );
+    tokio::spawn(execute(state.clone(), run));
+    Ok(Json(status))
+}
+
+/// GET /scenarios/current - progress of the latest run
+pub async fn current_scenario(
+    State(state): State<AppState>,
+) -> Result<Json<RunStatus>, (StatusCode, String)> {
+    state
+        .scenarios
+        .current()
+        .map(|run| Json(run.status()))
+        .ok_or((StatusCode::NOT_FOUND, "No scenario has been run".to_string()))
+}
+
+/// POST /scenarios/current/cancel - stop before the next step. Faults
+/// already injected stay until they expire.
+pub async fn cancel_scenario(
+    State(state): State<AppState>,
+) -> Result<Json<RunStatus>, (StatusCode, String)> {
+    let run = state
+        .scenarios
+        .current()
+        .filter(|run| run.status().state == RunState::Running)
+        .ok_or((StatusCode::NOT_FOUND, "No scenario is running".to_string()))?;
+    run.cancelled.store(true, Ordering::Relaxed);
+    Ok(Json(run.status()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+name: storm
+clock: { mode: accelerated, factor: 60 }
+events:
+  - at: 10m
+    weather_front: { stations: [GS-1], beam_quality_score: 0.2, duration: 45m }
+  - at: 20m
+    link_failure: { target: "SAT-1|GS-1", duration: 10m }
+  - at: 5m
+    maneuver: { satellite: SAT-1, duration: 1h }
+  - at: 30
+    traffic_surge: { targets: [GS-1], utilization: 0.9, duration: 15m }
+"#;
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("90s").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_offset("1h30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse_offset("2d").unwrap(), Duration::days(2));
+        assert_eq!(parse_offset("45").unwrap(), Duration::seconds(45));
+        assert_eq!(parse_offset("0.5m").unwrap(), Duration::seconds(30));
+        assert!(parse_offset("10x").is_err());
+        assert!(parse_offset("").is_err());
+        assert!(parse_offset("m").is_err());
+    }
+
+    #[test]
+    fn test_scenario_parses_and_orders() {
+        let scenario = Scenario::from_yaml(SCENARIO).unwrap();
+        assert_eq!(scenario.events.len(), 4);
+        assert_eq!(scenario.clock, Some(ClockMode::Accelerated { factor: 60.0 }));
+        assert!(matches!(
+            scenario.events[1].kind,
+            EventKind::LinkFailure { kind: TargetKind::Link, .. }
+        ));
+
+        let steps = timeline(&scenario);
+        let order: Vec<(usize, bool)> = steps.iter().map(|s| (s.event, s.end)).collect();
+        // 30s surge, 5m maneuver, 10m front, 20m failure, 55m front end, 65m maneuver end
+        assert_eq!(
+            order,
+            [(3, false), (2, false), (0, false), (1, false), (0, true), (2, true)]
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_values() {
+        let bad = SCENARIO.replace("utilization: 0.9", "utilization: 1.5");
+        let err = Scenario::from_yaml(&bad).unwrap_err();
+        assert!(err.contains("events[3]"), "{}", err);
+        assert!(Scenario::from_yaml("name: x\nevents: []").is_err());
+    }
+
+    #[test]
+    fn test_rejects_offsets_past_horizon() {
+        assert_eq!(parse_offset("365d").unwrap(), Duration::days(365));
+        assert!(parse_offset("366d").is_err());
+        assert!(parse_offset("99999999999999999999d").is_err());
+
+        let far = SCENARIO.replace("at: 30\n", "at: 1e15\n");
+        assert!(Scenario::from_yaml(&far).is_err());
+        let long = SCENARIO.replace("duration: 1h", "duration: 400d");
+        let err = Scenario::from_yaml(&long).unwrap_err();
+        assert!(err.contains("events[2]"), "{}", err);
+    }
+}
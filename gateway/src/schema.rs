@@ -0,0 +1,292 @@
+//! Telemetry wire schemas
+//!
+//! Protobuf messages for the JetStream telemetry subjects
+//! (`proto/telemetry.proto`, package `sx9.orbital.telemetry.v1`) and
+//! conversions to and from the gateway's record types. Timestamps travel
+//! as Unix milliseconds.
+
+use chrono::{DateTime, Utc};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::bus::{BusHealth, BusState};
+use crate::history::{LinkRecord, PositionRecord, StationTelemetryRecord};
+
+pub mod pb {
+    tonic::include_proto!("sx9.orbital.telemetry.v1");
+}
+
+pub const CONTENT_TYPE: &str = "application/x-protobuf";
+/// Header naming the protobuf message in a payload
+pub const SCHEMA_HEADER: &str = "Sx9-Schema";
+/// Header naming the publisher; the gateway stamps its own telemetry so
+/// consumers sharing its subjects can tell it from external reports
+pub const ORIGIN_HEADER: &str = "Sx9-Origin";
+pub const GATEWAY_ORIGIN: &str = "gateway";
+
+/// Close approach between a satellite and another object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConjunctionAlert {
+    pub satellite_id: String,
+    pub object: String,
+    pub tca: DateTime<Utc>,
+    pub miss_distance_km: f64,
+    pub probability: Option<f64>,
+    pub source: String,
+}
+
+fn from_unix_ms(ms: i64) -> DateTime<Utc> {
+    DateTime::<Utc>::from_timestamp_millis(ms).unwrap_or_default()
+}
+
+impl From<&PositionRecord> for pb::SatellitePosition {
+    fn from(r: &PositionRecord) -> Self {
+        let [x_km, y_km, z_km] = r.position_eci_km;
+        let [vx_km_s, vy_km_s, vz_km_s] = r.velocity_eci_km_s;
+        Self {
+            satellite_id: r.satellite_id.clone(),
+            timestamp_unix_ms: r.timestamp.timestamp_millis(),
+            latitude_deg: r.latitude,
+            longitude_deg: r.longitude,
+            altitude_km: r.altitude_km,
+            velocity_km_s: r.velocity_km_s,
+            x_km,
+            y_km,
+            z_km,
+            vx_km_s,
+            vy_km_s,
+            vz_km_s,
+        }
+    }
+}
+
+impl From<pb::SatellitePosition> for PositionRecord {
+    fn from(m: pb::SatellitePosition) -> Self {
+        Self {
+            satellite_id: m.satellite_id,
+            timestamp: from_unix_ms(m.timestamp_unix_ms),
+            latitude: m.latitude_deg,
+            longitude: m.longitude_deg,
+            altitude_km: m.altitude_km,
+            velocity_km_s: m.velocity_km_s,
+            position_eci_km: [m.x_km, m.y_km, m.z_km],
+            velocity_eci_km_s: [m.vx_km_s, m.vy_km_s, m.vz_km_s],
+        }
+    }
+}
+
+impl From<&LinkRecord> for pb::LinkState {
+    fn from(r: &LinkRecord) -> Self {
+        Self {
+            link_id: r.link_id.clone(),
+            satellite_id: r.satellite_id.clone(),
+            station_id: r.station_id.clone(),
+            timestamp_unix_ms: r.timestamp.timestamp_millis(),
+            elevation_deg: r.elevation_deg,
+            range_km: r.range_km,
+            active: r.active,
+        }
+    }
+}
+
+impl From<pb::LinkState> for LinkRecord {
+    fn from(m: pb::LinkState) -> Self {
+        Self {
+            link_id: m.link_id,
+            satellite_id: m.satellite_id,
+            station_id: m.station_id,
+            timestamp: from_unix_ms(m.timestamp_unix_ms),
+            elevation_deg: m.elevation_deg,
+            range_km: m.range_km,
+            active: m.active,
+        }
+    }
+}
+
+impl From<&StationTelemetryRecord> for pb::StationTelemetry {
+    fn from(r: &StationTelemetryRecord) -> Self {
+        Self {
+            station_id: r.station_id.clone(),
+            timestamp_unix_ms: r.timestamp.timestamp_millis(),
+            status: r.status.clone(),
+            weather_score: r.weather_score,
+            cloud_cover_pct: r.cloud_cover_pct,
+        }
+    }
+}
+
+impl From<pb::StationTelemetry> for StationTelemetryRecord {
+    fn from(m: pb::StationTelemetry) -> Self {
+        Self {
+            station_id: m.station_id,
+            timestamp: from_unix_ms(m.timestamp_unix_ms),
+            status: m.status,
+            weather_score: m.weather_score,
+            cloud_cover_pct: m.cloud_cover_pct,
+        }
+    }
+}
+
+impl From<&ConjunctionAlert> for pb::ConjunctionAlert {
+    fn from(a: &ConjunctionAlert) -> Self {
+        Self {
+            satellite_id: a.satellite_id.clone(),
+            object: a.object.clone(),
+            tca_unix_ms: a.tca.timestamp_millis(),
+            miss_distance_km: a.miss_distance_km,
+            probability: a.probability,
+            source: a.source.clone(),
+        }
+    }
+}
+
+impl From<pb::ConjunctionAlert> for ConjunctionAlert {
+    fn from(m: pb::ConjunctionAlert) -> Self {
+        Self {
+            satellite_id: m.satellite_id,
+            object: m.object,
+            tca: from_unix_ms(m.tca_unix_ms),
+            miss_distance_km: m.miss_distance_km,
+            probability: m.probability,
+            source: m.source,
+        }
+    }
+}
+
+impl From<&BusState> for pb::BusTelemetry {
+    fn from(b: &BusState) -> Self {
+        Self {
+            satellite_id: b.satellite_id.clone(),
+            timestamp_unix_ms: b.timestamp.timestamp_millis(),
+            illumination: b.illumination,
+            solar_w: b.solar_w,
+            load_w: b.load_w,
+            battery_wh: b.battery_wh,
+            state_of_charge_pct: b.state_of_charge_pct,
+            temperature_c: b.temperature_c,
+            payload_on: b.payload_on,
+            isl_on: b.isl_on,
+            health: b.health.as_str().to_string(),
+        }
+    }
+}
+
+impl From<pb::BusTelemetry> for BusState {
+    fn from(m: pb::BusTelemetry) -> Self {
+        Self {
+            satellite_id: m.satellite_id,
+            timestamp: from_unix_ms(m.timestamp_unix_ms),
+            illumination: m.illumination,
+            solar_w: m.solar_w,
+            load_w: m.load_w,
+            battery_wh: m.battery_wh,
+            state_of_charge_pct: m.state_of_charge_pct,
+            temperature_c: m.temperature_c,
+            payload_on: m.payload_on,
+            isl_on: m.isl_on,
+            health: BusHealth::parse(&m.health).unwrap_or(BusHealth::Nominal),
+        }
+    }
+}
+
+/// A telemetry message and its fully qualified name for the `Sx9-Schema`
+/// header
+pub trait Schema: Message {
+    const NAME: &'static str;
+}
+
+impl Schema for pb::SatellitePosition {
+    const NAME: &'static str = "sx9.orbital.telemetry.v1.SatellitePosition";
+}
+
+impl Schema for pb::LinkState {
+    const NAME: &'static str = "sx9.orbital.telemetry.v1.LinkState";
+}
+
+impl Schema for pb::StationTelemetry {
+    const NAME: &'static str = "sx9.orbital.telemetry.v1.StationTelemetry";
+}
+
+impl Schema for pb::ConjunctionAlert {
+    const NAME: &'static str = "sx9.orbital.telemetry.v1.ConjunctionAlert";
+}
+
+impl Schema for pb::BusTelemetry {
+    const NAME: &'static str = "sx9.orbital.telemetry.v1.BusTelemetry";
+}
+
+/// Decode a protobuf payload by its schema name into the JSON form of the
+/// matching record; `None` for unknown schemas or undecodable bytes
+pub fn decode_json(schema: &str, payload: &[u8]) -> Option<serde_json::Value> {
+    fn json<M: Message + Default, R: From<M> + Serialize>(payload: &[u8]) -> Option<serde_json::Value> {
+        let record = R::from(M::decode(payload).ok()?);
+        serde_json::to_value(record).ok()
+    }
+    match schema {
+        s if s == pb::SatellitePosition::NAME => json::<pb::SatellitePosition, PositionRecord>(payload),
+        s if s == pb::LinkState::NAME => json::<pb::LinkState, LinkRecord>(payload),
+        s if s == pb::StationTelemetry::NAME => json::<pb::StationTelemetry, StationTelemetryRecord>(payload),
+        s if s == pb::ConjunctionAlert::NAME => json::<pb::ConjunctionAlert, ConjunctionAlert>(payload),
+        s if s == pb::BusTelemetry::NAME => json::<pb::BusTelemetry, BusState>(payload),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        from_unix_ms(ms)
+    }
+
+    #[test]
+    fn test_position_round_trip() {
+        let record = PositionRecord {
+            satellite_id: "HALO-01".to_string(),
+            timestamp: at(1_700_000_000_123),
+            latitude: 12.5,
+            longitude: -45.25,
+            altitude_km: 10_500.0,
+            velocity_km_s: 4.9,
+            position_eci_km: [1.0, 2.0, 3.0],
+            velocity_eci_km_s: [4.0, 5.0, 6.0],
+        };
+        let bytes = pb::SatellitePosition::from(&record).encode_to_vec();
+        let decoded = PositionRecord::from(pb::SatellitePosition::decode(bytes.as_slice()).unwrap());
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&record).unwrap());
+    }
+
+    #[test]
+    fn test_optional_fields_survive() {
+        let mut record = StationTelemetryRecord {
+            station_id: "GS-001".to_string(),
+            timestamp: at(1_700_000_000_000),
+            status: "online".to_string(),
+            weather_score: 0.8,
+            cloud_cover_pct: None,
+        };
+        let decoded = StationTelemetryRecord::from(pb::StationTelemetry::from(&record));
+        assert_eq!(decoded.cloud_cover_pct, None);
+        record.cloud_cover_pct = Some(0.0);
+        let decoded = StationTelemetryRecord::from(pb::StationTelemetry::from(&record));
+        assert_eq!(decoded.cloud_cover_pct, Some(0.0));
+    }
+
+    #[test]
+    fn test_decode_json_by_schema() {
+        let alert = ConjunctionAlert {
+            satellite_id: "HALO-03".to_string(),
+            object: "COSMOS 2251 DEB".to_string(),
+            tca: at(1_700_000_600_000),
+            miss_distance_km: 0.42,
+            probability: Some(1.5e-4),
+            source: "scenario".to_string(),
+        };
+        let bytes = pb::ConjunctionAlert::from(&alert).encode_to_vec();
+        let name = pb::ConjunctionAlert::NAME;
+        assert_eq!(decode_json(name, &bytes), Some(serde_json::to_value(&alert).unwrap()));
+        assert_eq!(decode_json("sx9.orbital.telemetry.v1.Unknown", &bytes), None);
+        assert_eq!(decode_json(name, &[0xff, 0xff]), None);
+    }
+}
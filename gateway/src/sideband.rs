@@ -0,0 +1,412 @@
+//! CTAS sideband transport
+//!
+//! `POST /sideband/:channel` queues a `SidebandMessage` with the channel's
+//! next sequence number. A single dispatcher drains the queue highest
+//! priority first (FIFO within a priority) and sends each message as a
+//! NATS request on `orbital.sideband.{channel}`; the receiver's
+//! `SidebandAck` confirms delivery. Unacknowledged messages are retried
+//! with the same sequence a few times before being counted as failed, so
+//! receivers see retries as `Late`/`Duplicate` rather than new traffic.
+//! Each send is tagged with the version of the channel's live key (see
+//! `keyring`).
+//!
+//! A prober sends a Heartbeat probe down every channel (each operational
+//! station plus any channel that has carried traffic) on a fixed interval.
+//! Probes are never retried: each one is a `QosSample` in the channel's
+//! `SpeedOfService` window, lost if unacked. Summaries are served at
+//! `GET /sideband/qos` and published to `orbital.qos.{channel}`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+use ground_station_wasm::sideband::{
+    sideband_subject, MessagePriority, QosSample, QosSummary, SidebandAck, SidebandMessage, SpeedOfService,
+};
+
+use crate::telemetry::subject_token;
+use crate::AppState;
+
+/// How long to wait for a receiver to acknowledge
+const ACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sends per message before it is counted as failed
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Time between probe rounds
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Probes kept per channel for QoS figures
+const QOS_WINDOW: usize = 100;
+
+struct Pending {
+    message: SidebandMessage,
+    /// Enqueue order, for FIFO within a priority
+    order: u64,
+    attempts: u32,
+    probe: bool,
+}
+
+impl Pending {
+    fn key(&self) -> (MessagePriority, std::cmp::Reverse<u64>) {
+        (self.message.priority, std::cmp::Reverse(self.order))
+    }
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Delivery accounting for one channel
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChannelStats {
+    /// Last sequence assigned
+    pub sequence: u64,
+    pub acked: u64,
+    pub retries: u64,
+    pub failed: u64,
+    pub last_ack_latency_ms: Option<u64>,
+    /// Version of the channel's live key
+    pub key_version: Option<u64>,
+    /// Whether the channel holds an unexpired key (see `keyring`)
+    pub encryption_active: bool,
+}
+
+#[derive(Default)]
+struct Inner {
+    queue: BinaryHeap<Pending>,
+    next_order: u64,
+    channels: HashMap<String, ChannelStats>,
+    qos: HashMap<String, SpeedOfService>,
+}
+
+#[derive(Default)]
+pub struct SidebandQueue {
+    inner: Mutex<Inner>,
+    notify: Notify,
+}
+
+impl SidebandQueue {
+    /// Assign the channel's next sequence and queue the message
+    pub fn enqueue(
+        &self,
+        channel: &str,
+        priority: MessagePriority,
+        payload: serde_json::Value,
+    ) -> SidebandMessage {
+        self.push(channel, priority, payload, false)
+    }
+
+    /// Queue a QoS probe behind all other traffic
+    pub fn enqueue_probe(&self, channel: &str) -> SidebandMessage {
+        self.push(channel, MessagePriority::Heartbeat, serde_json::json!({ "probe": true }), true)
+    }
+
+    fn push(
+        &self,
+        channel: &str,
+        priority: MessagePriority,
+        payload: serde_json::Value,
+        probe: bool,
+    ) -> SidebandMessage {
+        let mut inner = self.inner.lock().unwrap();
+        let stats = inner.channels.entry(channel.to_string()).or_default();
+        stats.sequence += 1;
+        let message = SidebandMessage {
+            message_id: uuid::Uuid::new_v4().to_string(),
+            channel: channel.to_string(),
+            priority,
+            sequence: stats.sequence,
+            sent_unix_ms: chrono::Utc::now().timestamp_millis(),
+            key_version: None,
+            payload,
+        };
+        inner.next_order += 1;
+        let order = inner.next_order;
+        inner.queue.push(Pending {
+            message: message.clone(),
+            order,
+            attempts: 0,
+            probe,
+        });
+        drop(inner);
+        self.notify.notify_one();
+        message
+    }
+
+    fn pop(&self) -> Option<Pending> {
+        self.inner.lock().unwrap().queue.pop()
+    }
+
+    /// Put a message back in its original place in line
+    fn retry(&self, pending: Pending) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(stats) = inner.channels.get_mut(&pending.message.channel) {
+            stats.retries += 1;
+        }
+        inner.queue.push(pending);
+    }
+
+    fn record(&self, channel: &str, update: impl FnOnce(&mut ChannelStats)) {
+        if let Some(stats) = self.inner.lock().unwrap().channels.get_mut(channel) {
+            update(stats);
+        }
+    }
+
+    fn record_probe(&self, message: &SidebandMessage, rtt_ms: Option<f64>) {
+        self.inner
+            .lock()
+            .unwrap()
+            .qos
+            .entry(message.channel.clone())
+            .or_insert_with(|| SpeedOfService::new(QOS_WINDOW))
+            .record(QosSample {
+                sequence: message.sequence,
+                sent_unix_ms: message.sent_unix_ms,
+                rtt_ms,
+            });
+    }
+
+    /// Channels that have carried any message
+    pub fn channels(&self) -> Vec<String> {
+        self.inner.lock().unwrap().channels.keys().cloned().collect()
+    }
+
+    pub fn qos(&self) -> BTreeMap<String, QosSummary> {
+        self.inner
+            .lock()
+            .unwrap()
+            .qos
+            .iter()
+            .map(|(channel, sos)| (channel.clone(), sos.summary()))
+            .collect()
+    }
+
+    pub fn status(&self) -> SidebandStatus {
+        let inner = self.inner.lock().unwrap();
+        let mut queued: BTreeMap<MessagePriority, usize> = BTreeMap::new();
+        for pending in inner.queue.iter() {
+            *queued.entry(pending.message.priority).or_default() += 1;
+        }
+        SidebandStatus {
+            queued,
+            channels: inner.channels.clone().into_iter().collect(),
+        }
+    }
+}
+
+/// Send one message and wait for its ack
+async fn deliver(state: &AppState, message: &SidebandMessage) -> anyhow::Result<SidebandAck> {
+    let nats = state
+        .telemetry
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("NATS not configured"))?;
+    let payload = serde_json::to_vec(message)?;
+    let request = nats
+        .client()
+        .request(sideband_subject(&subject_token(&message.channel)), payload.into());
+    let reply = tokio::time::timeout(ACK_TIMEOUT, request)
+        .await
+        .map_err(|_| anyhow::anyhow!("no ack within {:?}", ACK_TIMEOUT))??;
+    let ack: SidebandAck = serde_json::from_slice(&reply.payload)?;
+    if ack.message_id != message.message_id || ack.sequence != message.sequence {
+        anyhow::bail!("ack for {} #{} does not match", ack.message_id, ack.sequence);
+    }
+    Ok(ack)
+}
+
+/// Drain the queue for the lifetime of the gateway
+pub async fn run(state: AppState) {
+    let queue = state.sideband.clone();
+    loop {
+        let Some(mut pending) = queue.pop() else {
+            queue.notify.notified().await;
+            continue;
+        };
+        pending.attempts += 1;
+        // Retries go out under whichever key is live at the time
+        let channel = pending.message.channel.clone();
+        pending.message.key_version = state.keyring.current_version(&channel, chrono::Utc::now());
+
+        let started = Instant::now();
+        let result = deliver(&state, &pending.message).await;
+        if pending.probe {
+            let rtt_ms = result.as_ref().ok().map(|_| started.elapsed().as_secs_f64() * 1000.0);
+            queue.record_probe(&pending.message, rtt_ms);
+        }
+        match result {
+            Ok(_) => queue.record(&channel, |s| {
+                s.acked += 1;
+                s.last_ack_latency_ms = Some(started.elapsed().as_millis() as u64);
+            }),
+            Err(_) if pending.probe => queue.record(&channel, |s| s.failed += 1),
+            Err(e) if pending.attempts < MAX_ATTEMPTS => {
+                tracing::debug!("Sideband {} #{} attempt {}: {}", channel, pending.message.sequence, pending.attempts, e);
+                queue.retry(pending);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Sideband {} #{} ({:?}) undelivered after {} attempts: {}",
+                    channel,
+                    pending.message.sequence,
+                    pending.message.priority,
+                    MAX_ATTEMPTS,
+                    e
+                );
+                queue.record(&channel, |s| s.failed += 1);
+            }
+        }
+    }
+}
+
+/// Probe every channel each `PROBE_INTERVAL` and publish the QoS summaries
+/// gathered since the previous round
+pub async fn probe(state: AppState) {
+    let mut ticker = tokio::time::interval(PROBE_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let Some(telemetry) = state.telemetry.as_ref() else {
+            continue;
+        };
+
+        for (channel, summary) in state.sideband.qos() {
+            if let Err(e) = telemetry.publish_qos(&channel, &summary).await {
+                tracing::debug!("QoS publish for {} failed: {}", channel, e);
+            }
+        }
+
+        let mut channels = state.sideband.channels();
+        channels.extend(state.constellation.load().operational_stations().map(|s| s.id.clone()));
+        channels.sort();
+        channels.dedup();
+        for channel in &channels {
+            state.sideband.enqueue_probe(channel);
+        }
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Deserialize)]
+pub struct SendRequest {
+    pub priority: MessagePriority,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+#[derive(Serialize)]
+pub struct SidebandStatus {
+    /// Messages waiting, by priority
+    pub queued: BTreeMap<MessagePriority, usize>,
+    pub channels: BTreeMap<String, ChannelStats>,
+}
+
+/// POST /sideband/:channel - queue a message; delivery is asynchronous
+pub async fn send(
+    State(state): State<AppState>,
+    Path(channel): Path<String>,
+    Json(req): Json<SendRequest>,
+) -> Result<(StatusCode, Json<SidebandMessage>), (StatusCode, String)> {
+    if state.telemetry.is_none() {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, "NATS not configured".to_string()));
+    }
+    let message = state.sideband.enqueue(&channel, req.priority, req.payload);
+    Ok((StatusCode::ACCEPTED, Json(message)))
+}
+
+/// GET /sideband
+pub async fn status(State(state): State<AppState>) -> Json<SidebandStatus> {
+    let mut status = state.sideband.status();
+    let now = chrono::Utc::now();
+    for (channel, stats) in status.channels.iter_mut() {
+        stats.key_version = state.keyring.current_version(channel, now);
+        stats.encryption_active = stats.key_version.is_some();
+    }
+    Json(status)
+}
+
+/// GET /sideband/qos - per-channel RTT, jitter and loss from recent probes
+pub async fn qos(State(state): State<AppState>) -> Json<BTreeMap<String, QosSummary>> {
+    Json(state.sideband.qos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drains_by_priority_then_fifo() {
+        let queue = SidebandQueue::default();
+        queue.enqueue("GS-001", MessagePriority::Heartbeat, serde_json::Value::Null);
+        queue.enqueue("GS-002", MessagePriority::ThreatAlert, serde_json::Value::Null);
+        queue.enqueue("GS-001", MessagePriority::Emergency, serde_json::Value::Null);
+        queue.enqueue("GS-002", MessagePriority::ThreatAlert, serde_json::Value::Null);
+
+        let order: Vec<(MessagePriority, String, u64)> = std::iter::from_fn(|| queue.pop())
+            .map(|p| (p.message.priority, p.message.channel, p.message.sequence))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (MessagePriority::Emergency, "GS-001".to_string(), 2),
+                (MessagePriority::ThreatAlert, "GS-002".to_string(), 1),
+                (MessagePriority::ThreatAlert, "GS-002".to_string(), 2),
+                (MessagePriority::Heartbeat, "GS-001".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retry_keeps_place_and_sequence() {
+        let queue = SidebandQueue::default();
+        queue.enqueue("GS-001", MessagePriority::Command, serde_json::Value::Null);
+        queue.enqueue("GS-001", MessagePriority::Command, serde_json::Value::Null);
+
+        let first = queue.pop().unwrap();
+        queue.retry(first);
+        assert_eq!(queue.pop().unwrap().message.sequence, 1);
+
+        let status = queue.status();
+        assert_eq!(status.channels["GS-001"].retries, 1);
+        assert_eq!(status.queued[&MessagePriority::Command], 1);
+    }
+
+    #[test]
+    fn test_probes_share_sequence_and_feed_qos() {
+        let queue = SidebandQueue::default();
+        queue.enqueue("GS-001", MessagePriority::Command, serde_json::Value::Null);
+        let probe = queue.enqueue_probe("GS-001");
+        assert_eq!(probe.sequence, 2);
+        assert_eq!(probe.priority, MessagePriority::Heartbeat);
+
+        queue.record_probe(&probe, Some(42.0));
+        queue.record_probe(&queue.enqueue_probe("GS-001"), None);
+        let qos = queue.qos();
+        assert_eq!((qos["GS-001"].samples, qos["GS-001"].lost), (2, 1));
+        assert_eq!(qos["GS-001"].rtt_mean_ms, Some(42.0));
+    }
+}
@@ -0,0 +1,347 @@
+//! Closed-loop station keeping
+//!
+//! A Walker satellite's slot is fixed from its TLE the first time it is
+//! seen (or whenever its TLE is replaced from outside, e.g. an upload or
+//! checkpoint restore). Every `step_minutes` of sim time its elements
+//! drift under J2 and `Perturbation::meo` and are re-epoched into the TLE,
+//! so nodes regress and perigees rotate together across the shell. Once an
+//! operational satellite leaves the `StationKeepingBox` around its (equally
+//! precessing) slot, a correction burn is sized, the elements are put back
+//! in the slot and the delta-v is charged against the satellite's annual
+//! budget. Spares, degraded and offline satellites drift but never burn;
+//! maneuvering satellites are left alone. Burns are published on
+//! `orbital.maneuver.{sat}`.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use orbital_mechanics::stationkeeping::{
+    apply_drift, plan_correction, BoxAxis, CorrectionBurn, MeanElements, Perturbation, SlotOffsets,
+    StationKeepingBox,
+};
+use orbital_mechanics::{Satellite, SatelliteStatus};
+
+use crate::memory::MemoryKind;
+use crate::AppState;
+
+const BUDGET_WINDOW_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BurnRecord {
+    pub satellite_id: String,
+    pub time: DateTime<Utc>,
+    /// Box axes that were exceeded
+    pub axes: Vec<BoxAxis>,
+    pub offsets: SlotOffsets,
+    #[serde(flatten)]
+    pub burn: CorrectionBurn,
+    /// Delta-v over the trailing year including this burn
+    pub delta_v_year_m_s: f64,
+    pub over_budget: bool,
+}
+
+struct Track {
+    slot: MeanElements,
+    actual: MeanElements,
+    perturbation: Perturbation,
+    offsets: SlotOffsets,
+    /// Line 1 as last written, to spot outside TLE changes
+    line1: String,
+    burns: Vec<BurnRecord>,
+}
+
+impl Track {
+    fn new(sat: &Satellite) -> Option<Self> {
+        let elements = MeanElements::from_satellite(sat).ok()?;
+        Some(Self {
+            slot: elements,
+            actual: elements,
+            perturbation: Perturbation::meo(elements.raan_deg, sat.norad_id),
+            offsets: SlotOffsets::default(),
+            line1: sat.tle_line1.clone(),
+            burns: Vec::new(),
+        })
+    }
+
+    fn delta_v_since(&self, since: DateTime<Utc>) -> f64 {
+        self.burns
+            .iter()
+            .filter(|b| b.time > since)
+            .map(|b| b.burn.delta_v_m_s)
+            .sum()
+    }
+
+    fn status(&self, id: &str, now: DateTime<Utc>, budget: f64) -> KeepingStatus {
+        let delta_v_year_m_s = self.delta_v_since(now - Duration::days(BUDGET_WINDOW_DAYS));
+        KeepingStatus {
+            satellite_id: id.to_string(),
+            slot_epoch: self.slot.epoch,
+            elements_epoch: self.actual.epoch,
+            offsets: self.offsets,
+            burns: self.burns.len(),
+            last_burn: self.burns.last().map(|b| b.time),
+            delta_v_total_m_s: self.burns.iter().map(|b| b.burn.delta_v_m_s).sum(),
+            delta_v_year_m_s,
+            annual_budget_m_s: budget,
+            budget_used_pct: 100.0 * delta_v_year_m_s / budget,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct StationKeeping {
+    tracks: Mutex<HashMap<String, Track>>,
+    last_step: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl StationKeeping {
+    /// Current status of one satellite's track, if it has one
+    pub fn status_of(&self, id: &str, now: DateTime<Utc>, annual_budget_m_s: f64) -> Option<KeepingStatus> {
+        self.tracks
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|t| t.status(id, now, annual_budget_m_s))
+    }
+
+    /// Whether a step is due at `now`; a clock moved backwards restarts the cadence
+    fn due(&self, now: DateTime<Utc>, step: Duration) -> bool {
+        let mut last = self.last_step.lock().unwrap();
+        match *last {
+            Some(t) if now >= t && now - t < step => false,
+            _ => {
+                *last = Some(now);
+                true
+            }
+        }
+    }
+
+    /// Drift every Walker satellite not mid-maneuver in place, correcting
+    /// operational ones that left the box, and rewrite their TLEs at `now`.
+    /// Returns the burns made.
+    pub fn step(
+        &self,
+        satellites: &mut [Satellite],
+        now: DateTime<Utc>,
+        tolerance: &StationKeepingBox,
+        annual_budget_m_s: f64,
+    ) -> Vec<BurnRecord> {
+        let mut tracks = self.tracks.lock().unwrap();
+        let mut burns = Vec::new();
+
+        for sat in satellites
+            .iter_mut()
+            .filter(|s| !s.constellation.is_empty() && s.status != SatelliteStatus::Maneuvering)
+        {
+            let stale = match tracks.get(&sat.id) {
+                Some(t) => t.line1 != sat.tle_line1 || t.actual.epoch > now,
+                None => true,
+            };
+            if stale {
+                let Some(track) = Track::new(sat) else {
+                    continue;
+                };
+                tracks.insert(sat.id.clone(), track);
+            }
+            let Some(track) = tracks.get_mut(&sat.id) else {
+                continue;
+            };
+
+            track.actual = apply_drift(&track.actual, &track.perturbation, now - track.actual.epoch);
+            let nominal = apply_drift(&track.slot, &Perturbation::default(), now - track.slot.epoch);
+            track.offsets = SlotOffsets::between(&track.actual, &nominal);
+
+            let axes = tolerance.violations(&track.offsets);
+            if sat.status == SatelliteStatus::Operational && !axes.is_empty() {
+                let burn = plan_correction(&track.actual, &nominal);
+                let since = now - Duration::days(BUDGET_WINDOW_DAYS);
+                let delta_v_year_m_s = track.delta_v_since(since) + burn.delta_v_m_s;
+                let record = BurnRecord {
+                    satellite_id: sat.id.clone(),
+                    time: now,
+                    axes,
+                    offsets: track.offsets,
+                    burn,
+                    delta_v_year_m_s,
+                    over_budget: delta_v_year_m_s > annual_budget_m_s,
+                };
+                track.actual = nominal;
+                track.offsets = SlotOffsets::default();
+                track.burns.push(record.clone());
+                burns.push(record);
+            }
+
+            let (line1, line2) = track.actual.to_tle_lines(sat.norad_id);
+            sat.tle_line1 = line1;
+            sat.tle_line2 = line2;
+            track.line1 = sat.tle_line1.clone();
+        }
+        burns
+    }
+}
+
+/// Run a station-keeping step at sim time `now` if one is due
+pub async fn tick(state: &AppState, now: DateTime<Utc>) {
+    let config = &state.config.station_keeping;
+    if !config.enabled || !state.station_keeping.due(now, Duration::minutes(config.step_minutes)) {
+        return;
+    }
+
+    let tolerance = config.tolerance();
+    let burns = state.constellation.update(|current| {
+        let mut next = current.clone();
+        let burns = state
+            .station_keeping
+            .step(&mut next.satellites, now, &tolerance, config.annual_budget_m_s);
+        (next, burns)
+    });
+
+    for burn in &burns {
+        let log = format!(
+            "{} station-keeping burn {:.2} m/s ({:?}), {:.1}/{:.1} m/s this year",
+            burn.satellite_id, burn.burn.delta_v_m_s, burn.axes, burn.delta_v_year_m_s, config.annual_budget_m_s
+        );
+        if burn.over_budget {
+            tracing::warn!("{} - over budget", log);
+        } else {
+            tracing::info!("{}", log);
+        }
+        state
+            .memory
+            .remember(now, MemoryKind::Maneuver, vec![burn.satellite_id.clone()], log, burn);
+        if let Some(telemetry) = &state.telemetry {
+            if let Err(e) = telemetry.publish_maneuver(&burn.satellite_id, burn).await {
+                tracing::warn!("Maneuver publish failed: {}", e);
+            }
+        }
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Serialize)]
+pub struct KeepingStatus {
+    pub satellite_id: String,
+    pub slot_epoch: DateTime<Utc>,
+    /// Epoch of the satellite's current elements (last step)
+    pub elements_epoch: DateTime<Utc>,
+    pub offsets: SlotOffsets,
+    pub burns: usize,
+    pub last_burn: Option<DateTime<Utc>>,
+    pub delta_v_total_m_s: f64,
+    pub delta_v_year_m_s: f64,
+    pub annual_budget_m_s: f64,
+    pub budget_used_pct: f64,
+}
+
+#[derive(Serialize)]
+pub struct KeepingDetail {
+    #[serde(flatten)]
+    pub status: KeepingStatus,
+    pub history: Vec<BurnRecord>,
+}
+
+/// GET /station-keeping
+pub async fn list_status(State(state): State<AppState>) -> Json<Vec<KeepingStatus>> {
+    let now = state.clock.now();
+    let budget = state.config.station_keeping.annual_budget_m_s;
+    let tracks = state.station_keeping.tracks.lock().unwrap();
+    let mut statuses: Vec<KeepingStatus> = tracks
+        .iter()
+        .map(|(id, track)| track.status(id, now, budget))
+        .collect();
+    statuses.sort_by(|a, b| a.satellite_id.cmp(&b.satellite_id));
+    Json(statuses)
+}
+
+/// GET /station-keeping/:id
+pub async fn get_status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<KeepingDetail>, (StatusCode, String)> {
+    let budget = state.config.station_keeping.annual_budget_m_s;
+    let tracks = state.station_keeping.tracks.lock().unwrap();
+    let track = tracks.get(&id).ok_or((
+        StatusCode::NOT_FOUND,
+        format!("No station-keeping track for satellite: {}", id),
+    ))?;
+    Ok(Json(KeepingDetail {
+        status: track.status(&id, state.clock.now(), budget),
+        history: track.burns.clone(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use orbital_mechanics::walker::WalkerDelta;
+
+    #[test]
+    fn test_drift_triggers_burn_and_resets_slot() {
+        let epoch = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+        let mut sats = WalkerDelta::halo_constellation().generate_satellites("HALO", 60000, epoch);
+        let keeping = StationKeeping::default();
+        let tolerance = StationKeepingBox::default();
+
+        assert!(keeping.step(&mut sats, epoch, &tolerance, 25.0).is_empty());
+        let original = sats[4].tle_line2.clone();
+
+        // Plane 2 (RAAN 120°) drifts out of the inclination box within ~100 days
+        let mut burns = Vec::new();
+        for day in 1..=120 {
+            burns.extend(keeping.step(&mut sats, epoch + Duration::days(day), &tolerance, 25.0));
+        }
+        let burn = burns.iter().find(|b| b.satellite_id == sats[4].id).unwrap();
+        assert_eq!(burn.axes, vec![BoxAxis::Inclination]);
+        assert!(burn.burn.delta_v_m_s > 15.0 && !burn.over_budget);
+        assert_ne!(sats[4].tle_line2, original);
+        assert!(sats[4].propagate(epoch + Duration::days(120)).is_ok());
+
+        // Plane 1 (RAAN 0°) sees no lunisolar inclination drift
+        assert!(burns.iter().all(|b| b.satellite_id != sats[0].id));
+    }
+
+    #[test]
+    fn test_shell_precesses_together() {
+        let epoch = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+        let walker = WalkerDelta::halo_constellation();
+        let mut sats = walker.generate_satellites("HALO", 60000, epoch);
+        sats[1].status = SatelliteStatus::Spare;
+        sats[2].status = SatelliteStatus::Maneuvering;
+        let keeping = StationKeeping::default();
+        let tolerance = StationKeepingBox::default();
+        keeping.step(&mut sats, epoch, &tolerance, 25.0);
+
+        let later = epoch + Duration::days(10);
+        keeping.step(&mut sats, later, &tolerance, 25.0);
+        // Plane 1 starts at RAAN 0°; operational and spare regress alike
+        let expected = (10.0 * walker.raan_drift_per_day()).rem_euclid(360.0);
+        for sat in &sats[..2] {
+            let raan = MeanElements::from_satellite(sat).unwrap().raan_deg;
+            assert!((raan - expected).abs() < 0.01, "{}", sat.id);
+        }
+        assert_eq!(MeanElements::from_satellite(&sats[2]).unwrap().epoch, epoch);
+    }
+
+    #[test]
+    fn test_outside_tle_change_resets_slot() {
+        let epoch = Utc.with_ymd_and_hms(2026, 1, 4, 0, 0, 0).unwrap();
+        let mut sats = WalkerDelta::halo_constellation().generate_satellites("HALO", 60000, epoch);
+        let keeping = StationKeeping::default();
+        let tolerance = StationKeepingBox::default();
+        keeping.step(&mut sats, epoch, &tolerance, 25.0);
+
+        // A fresh upload 200 days later is the new slot, not a violation
+        let later = epoch + Duration::days(200);
+        let mut uploaded = WalkerDelta::halo_constellation().generate_satellites("HALO", 60000, later);
+        assert!(keeping.step(&mut uploaded, later, &tolerance, 25.0).is_empty());
+    }
+}
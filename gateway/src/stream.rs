@@ -0,0 +1,141 @@
+//! Live position stream
+//!
+//! `GET /positions/stream` upgrades to a WebSocket that receives one frame
+//! per propagation tick: the tick's positions, optionally limited to
+//! `?satellites=a,b`. `?format=` picks the codec (default JSON as text
+//! frames; `cbor`, `msgpack` and `protobuf` as binary frames, the last a
+//! `PositionBatch`). A client that falls behind skips ahead to the latest
+//! ticks rather than stalling propagation.
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::Response,
+};
+use prost::Message as _;
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::codec::Codec;
+use crate::history::PositionRecord;
+use crate::schema::pb;
+use crate::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+    pub format: Option<Codec>,
+    /// Comma-separated satellite IDs; all when absent
+    pub satellites: Option<String>,
+}
+
+/// GET /positions/stream?format=&satellites= (WebSocket)
+pub async fn position_stream(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(q): Query<StreamQuery>,
+) -> Response {
+    let codec = q.format.unwrap_or(Codec::Json);
+    let satellites: Vec<String> = q
+        .satellites
+        .iter()
+        .flat_map(|s| s.split(','))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    ws.on_upgrade(move |socket| stream_positions(socket, state, codec, satellites))
+}
+
+async fn stream_positions(mut socket: WebSocket, state: AppState, codec: Codec, satellites: Vec<String>) {
+    let mut ticks = state.propagation.subscribe_positions();
+    loop {
+        tokio::select! {
+            tick = ticks.recv() => match tick {
+                Ok(positions) => {
+                    let batch: Vec<&PositionRecord> = positions
+                        .iter()
+                        .filter(|p| satellites.is_empty() || satellites.contains(&p.satellite_id))
+                        .collect();
+                    if batch.is_empty() {
+                        continue;
+                    }
+                    let frame = match encode_frame(codec, &batch) {
+                        Ok(frame) => frame,
+                        Err(e) => {
+                            tracing::warn!("Position stream encode failed: {}", e);
+                            return;
+                        }
+                    };
+                    if socket.send(frame).await.is_err() {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => tracing::debug!("Position stream skipped {} ticks", skipped),
+                Err(RecvError::Closed) => return,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                // Pings are answered by axum; anything else is ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+fn encode_frame(codec: Codec, batch: &[&PositionRecord]) -> anyhow::Result<Message> {
+    Ok(match codec {
+        Codec::Protobuf => {
+            let message = pb::PositionBatch {
+                positions: batch.iter().map(|&p| p.into()).collect(),
+            };
+            Message::Binary(message.encode_to_vec())
+        }
+        Codec::Json => Message::Text(String::from_utf8(codec.encode(&batch)?)?),
+        codec => Message::Binary(codec.encode(&batch)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    fn position(id: &str) -> PositionRecord {
+        PositionRecord {
+            satellite_id: id.to_string(),
+            timestamp: DateTime::<Utc>::from_timestamp_millis(1_700_000_000_000).unwrap(),
+            latitude: 1.0,
+            longitude: 2.0,
+            altitude_km: 10_500.0,
+            velocity_km_s: 4.9,
+            position_eci_km: [1.0, 2.0, 3.0],
+            velocity_eci_km_s: [4.0, 5.0, 6.0],
+        }
+    }
+
+    #[test]
+    fn test_frames_per_codec() {
+        let (a, b) = (position("HALO-01"), position("HALO-02"));
+        let batch = [&a, &b];
+
+        let Message::Text(json) = encode_frame(Codec::Json, &batch).unwrap() else {
+            panic!("JSON should be a text frame");
+        };
+        assert!(json.starts_with('['));
+
+        let Message::Binary(bytes) = encode_frame(Codec::Protobuf, &batch).unwrap() else {
+            panic!("protobuf should be a binary frame");
+        };
+        let decoded = pb::PositionBatch::decode(bytes.as_slice()).unwrap();
+        assert_eq!(decoded.positions.len(), 2);
+        assert_eq!(decoded.positions[1].satellite_id, "HALO-02");
+
+        let Message::Binary(bytes) = encode_frame(Codec::Cbor, &batch).unwrap() else {
+            panic!("CBOR should be a binary frame");
+        };
+        let decoded: Vec<PositionRecord> = Codec::Cbor.decode(&bytes).unwrap();
+        assert_eq!(decoded[0].satellite_id, "HALO-01");
+        assert!(bytes.len() < json.len());
+    }
+}
@@ -0,0 +1,426 @@
+//! NATS JetStream telemetry
+//!
+//! Publishes propagated positions, link states and station telemetry into
+//! a file-backed JetStream stream so nothing is lost across gateway or
+//! dashboard restarts:
+//! - `orbital.sat.{id}.position`
+//! - `orbital.link.{sat}.{station}.state`
+//! - `orbital.gs.{id}.telemetry`
+//! - `orbital.chaos.{fault}` (fault injected / cleared)
+//! - `orbital.scenario.{run}` (scenario steps and completion)
+//! - `orbital.qos.{channel}` (sideband speed-of-service summaries)
+//! - `orbital.keys.{station}` (key reserve alerts raised / cleared)
+//! - `orbital.maneuver.{sat}` (station-keeping burns)
+//! - `orbital.conjunction.{sat}` (close-approach alerts)
+//! - `orbital.bus.{sat}` (spacecraft power and thermal state)
+//!
+//! The routing graph of each tick goes out as JSON on core NATS only
+//! (`orbital.topology.graph`, for `glaf-server`): it is latest-state, not
+//! worth retaining.
+//!
+//! Positions, link states, station telemetry, conjunction alerts and bus
+//! state are
+//! protobuf (`proto/telemetry.proto`, see `schema`) and the other subjects
+//! JSON, unless `nats.codecs` picks another codec for a subject prefix.
+//! Payloads carry their `Content-Type` and `Sx9-Origin: gateway`. With `json_fallback` on, records
+//! not already JSON are also published as JSON on `orbital.json.<subject>`.
+//!
+//! Dashboards attach as durable consumer groups (load-balanced pull
+//! consumers); `replay` re-reads any retained window from a start time.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use async_nats::jetstream::{
+    self,
+    consumer::{pull, AckPolicy, DeliverPolicy},
+    stream::{self, StorageType},
+};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use orbital_glaf::snapshot::{GraphSnapshot, TOPOLOGY_SUBJECT};
+
+use crate::bus::BusState;
+use crate::codec::{Codec, SubjectCodecs};
+use crate::config::GatewayConfig;
+use crate::history::{LinkRecord, PositionRecord, StationTelemetryRecord};
+use crate::schema::{self, pb, ConjunctionAlert, Schema};
+use crate::AppState;
+
+pub const STREAM_NAME: &str = "ORBITAL_TELEMETRY";
+const STREAM_SUBJECTS: [&str; 12] = [
+    "orbital.sat.*.position",
+    "orbital.link.*.*.state",
+    "orbital.gs.*.telemetry",
+    "orbital.chaos.*",
+    "orbital.scenario.*",
+    "orbital.qos.*",
+    "orbital.keys.*",
+    "orbital.keyring.*",
+    "orbital.maneuver.*",
+    "orbital.conjunction.*",
+    "orbital.bus.*",
+    "orbital.json.>",
+];
+/// Prefix for JSON copies of protobuf subjects
+pub const JSON_PREFIX: &str = "orbital.json";
+const MAX_REPLAY_MESSAGES: usize = 10_000;
+
+/// JetStream settings
+#[derive(Debug, Clone)]
+pub struct TelemetryConfig {
+    pub url: String,
+    pub retention: Duration,
+    /// Durable consumer groups created at startup (all subjects)
+    pub consumer_groups: Vec<String>,
+    /// Mirror non-JSON subjects as JSON under `orbital.json.`
+    pub json_fallback: bool,
+    pub codecs: SubjectCodecs,
+}
+
+impl TelemetryConfig {
+    /// `None` when no NATS URL is set or telemetry is toggled off
+    pub fn from_config(config: &GatewayConfig) -> Option<Self> {
+        if !config.features.telemetry {
+            return None;
+        }
+        Some(Self {
+            url: config.nats.url.clone()?,
+            retention: Duration::from_secs(config.nats.retention_hours * 3600),
+            consumer_groups: config.nats.consumer_groups.clone(),
+            json_fallback: config.nats.json_fallback,
+            codecs: SubjectCodecs::new(config.nats.codecs.clone()),
+        })
+    }
+}
+
+/// Make an ID safe to use as a single NATS subject token
+pub fn subject_token(id: &str) -> String {
+    id.chars()
+        .map(|c| match c {
+            '.' | '*' | '>' => '_',
+            c if c.is_whitespace() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+pub struct NatsTelemetry {
+    client: async_nats::Client,
+    jetstream: jetstream::Context,
+    stream: stream::Stream,
+    json_fallback: bool,
+    codecs: SubjectCodecs,
+}
+
+impl NatsTelemetry {
+    /// Connect and create (or update) the telemetry stream and consumer groups
+    pub async fn connect(config: &TelemetryConfig) -> anyhow::Result<Self> {
+        let client = async_nats::connect(&config.url).await?;
+        let jetstream = jetstream::new(client.clone());
+
+        let stream_config = stream::Config {
+            name: STREAM_NAME.to_string(),
+            subjects: STREAM_SUBJECTS.iter().map(|s| s.to_string()).collect(),
+            max_age: config.retention,
+            storage: StorageType::File,
+            ..Default::default()
+        };
+        let stream = match jetstream.get_stream(STREAM_NAME).await {
+            Ok(_) => {
+                // Pick up retention changes on restart
+                jetstream.update_stream(&stream_config).await?;
+                jetstream.get_stream(STREAM_NAME).await?
+            }
+            Err(_) => jetstream.create_stream(stream_config).await?,
+        };
+
+        let telemetry = Self {
+            client,
+            jetstream,
+            stream,
+            json_fallback: config.json_fallback,
+            codecs: config.codecs.clone(),
+        };
+        for group in &config.consumer_groups {
+            telemetry.ensure_consumer_group(group, None).await?;
+        }
+        Ok(telemetry)
+    }
+
+    /// Core NATS client (request-reply, plain subjects)
+    pub fn client(&self) -> &async_nats::Client {
+        &self.client
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.client.connection_state() == async_nats::connection::State::Connected
+    }
+
+    /// Publish in the subject's codec (JSON unless configured) and wait
+    /// for the JetStream ack
+    pub async fn publish<T: Serialize>(&self, subject: String, value: &T) -> anyhow::Result<()> {
+        let codec = match self.codecs.lookup(&subject) {
+            Some(Codec::Protobuf) | None => Codec::Json,
+            Some(codec) => codec,
+        };
+        self.publish_with(subject, codec, value).await
+    }
+
+    /// Publish `record` as protobuf message `M` tagged with its schema
+    /// name (or in the subject's configured codec), plus the JSON copy
+    /// when the fallback is on
+    async fn publish_schema<T, M>(&self, subject: String, record: &T) -> anyhow::Result<()>
+    where
+        T: Serialize,
+        M: Schema + for<'a> From<&'a T>,
+    {
+        let codec = self.codecs.lookup(&subject).unwrap_or(Codec::Protobuf);
+        if self.json_fallback && codec != Codec::Json {
+            self.publish_with(format!("{}.{}", JSON_PREFIX, subject), Codec::Json, record)
+                .await?;
+        }
+        if codec != Codec::Protobuf {
+            return self.publish_with(subject, codec, record).await;
+        }
+
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Content-Type", schema::CONTENT_TYPE);
+        headers.insert(schema::SCHEMA_HEADER, M::NAME);
+        headers.insert(schema::ORIGIN_HEADER, schema::GATEWAY_ORIGIN);
+        let payload = M::from(record).encode_to_vec();
+        self.jetstream
+            .publish_with_headers(subject, headers, payload.into())
+            .await?
+            .await?;
+        Ok(())
+    }
+
+    async fn publish_with<T: Serialize>(&self, subject: String, codec: Codec, value: &T) -> anyhow::Result<()> {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Content-Type", codec.content_type());
+        headers.insert(schema::ORIGIN_HEADER, schema::GATEWAY_ORIGIN);
+        let payload = codec.encode(value)?;
+        self.jetstream
+            .publish_with_headers(subject, headers, payload.into())
+            .await?
+            .await?;
+        Ok(())
+    }
+
+    pub async fn publish_position(&self, record: &PositionRecord) -> anyhow::Result<()> {
+        let subject = format!("orbital.sat.{}.position", subject_token(&record.satellite_id));
+        self.publish_schema::<_, pb::SatellitePosition>(subject, record).await
+    }
+
+    pub async fn publish_link(&self, record: &LinkRecord) -> anyhow::Result<()> {
+        let subject = format!(
+            "orbital.link.{}.{}.state",
+            subject_token(&record.satellite_id),
+            subject_token(&record.station_id)
+        );
+        self.publish_schema::<_, pb::LinkState>(subject, record).await
+    }
+
+    pub async fn publish_station(&self, record: &StationTelemetryRecord) -> anyhow::Result<()> {
+        let subject = format!("orbital.gs.{}.telemetry", subject_token(&record.station_id));
+        self.publish_schema::<_, pb::StationTelemetry>(subject, record).await
+    }
+
+    pub async fn publish_conjunction(&self, alert: &ConjunctionAlert) -> anyhow::Result<()> {
+        let subject = format!("orbital.conjunction.{}", subject_token(&alert.satellite_id));
+        self.publish_schema::<_, pb::ConjunctionAlert>(subject, alert).await
+    }
+
+    pub async fn publish_bus(&self, state: &BusState) -> anyhow::Result<()> {
+        let subject = format!("orbital.bus.{}", subject_token(&state.satellite_id));
+        self.publish_schema::<_, pb::BusTelemetry>(subject, state).await
+    }
+
+    pub async fn publish_fault<T: Serialize>(&self, fault_id: &str, event: &T) -> anyhow::Result<()> {
+        let subject = format!("orbital.chaos.{}", subject_token(fault_id));
+        self.publish(subject, event).await
+    }
+
+    pub async fn publish_scenario<T: Serialize>(&self, run_id: &str, event: &T) -> anyhow::Result<()> {
+        let subject = format!("orbital.scenario.{}", subject_token(run_id));
+        self.publish(subject, event).await
+    }
+
+    pub async fn publish_qos<T: Serialize>(&self, channel: &str, summary: &T) -> anyhow::Result<()> {
+        let subject = format!("orbital.qos.{}", subject_token(channel));
+        self.publish(subject, summary).await
+    }
+
+    pub async fn publish_key_alert<T: Serialize>(&self, station_id: &str, alert: &T) -> anyhow::Result<()> {
+        let subject = format!("orbital.keys.{}", subject_token(station_id));
+        self.publish(subject, alert).await
+    }
+
+    pub async fn publish_key_rotation<T: Serialize>(&self, channel: &str, event: &T) -> anyhow::Result<()> {
+        let subject = format!("orbital.keyring.{}", subject_token(channel));
+        self.publish(subject, event).await
+    }
+
+    pub async fn publish_maneuver<T: Serialize>(&self, satellite_id: &str, burn: &T) -> anyhow::Result<()> {
+        let subject = format!("orbital.maneuver.{}", subject_token(satellite_id));
+        self.publish(subject, burn).await
+    }
+
+    /// Publish a routing graph for `glaf-server`; always JSON, not retained
+    pub async fn publish_graph(&self, snapshot: &GraphSnapshot) -> anyhow::Result<()> {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Content-Type", Codec::Json.content_type());
+        let payload = Codec::Json.encode(snapshot)?;
+        self.client
+            .publish_with_headers(TOPOLOGY_SUBJECT, headers, payload.into())
+            .await?;
+        Ok(())
+    }
+
+    /// Durable pull consumer shared by every member of `group`; each
+    /// message is delivered to one member and must be acked.
+    pub async fn ensure_consumer_group(
+        &self,
+        group: &str,
+        filter_subject: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.stream
+            .get_or_create_consumer(
+                group,
+                pull::Config {
+                    durable_name: Some(group.to_string()),
+                    filter_subject: filter_subject.unwrap_or_default().to_string(),
+                    ack_policy: AckPolicy::Explicit,
+                    deliver_policy: DeliverPolicy::New,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Re-read retained messages matching `filter_subject` from `from`
+    pub async fn replay(
+        &self,
+        filter_subject: &str,
+        from: DateTime<Utc>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<ReplayedMessage>> {
+        let start_time = time::OffsetDateTime::from_unix_timestamp_nanos(
+            from.timestamp_nanos_opt().unwrap_or_default() as i128,
+        )?;
+
+        // Ephemeral consumer; the server reaps it once idle
+        let consumer = self
+            .stream
+            .create_consumer(pull::Config {
+                filter_subject: filter_subject.to_string(),
+                deliver_policy: DeliverPolicy::ByStartTime { start_time },
+                ack_policy: AckPolicy::None,
+                inactive_threshold: Duration::from_secs(30),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut batch = consumer
+            .fetch()
+            .max_messages(limit)
+            .expires(Duration::from_secs(2))
+            .messages()
+            .await?;
+
+        let mut replayed = Vec::new();
+        while let Some(message) = batch.next().await {
+            let message = message.map_err(|e| anyhow::anyhow!(e))?;
+            let info = message.info().map_err(|e| anyhow::anyhow!(e))?;
+            replayed.push(ReplayedMessage {
+                subject: message.subject.to_string(),
+                sequence: info.stream_sequence,
+                published: DateTime::from_timestamp(
+                    info.published.unix_timestamp(),
+                    info.published.nanosecond(),
+                )
+                .unwrap_or(from),
+                payload: decode_payload(&message),
+            });
+        }
+        Ok(replayed)
+    }
+}
+
+fn header(headers: Option<&async_nats::HeaderMap>, name: &str) -> Option<String> {
+    headers.and_then(|h| h.get(name)).map(|v| v.as_str().to_string())
+}
+
+/// Codec of a payload by its `Content-Type`; untagged payloads are JSON
+pub fn payload_codec(headers: Option<&async_nats::HeaderMap>) -> Codec {
+    header(headers, "Content-Type")
+        .and_then(|c| Codec::from_content_type(&c))
+        .unwrap_or(Codec::Json)
+}
+
+/// JSON view of a stored payload, decoded by its `Content-Type` (and
+/// `Sx9-Schema` for protobuf)
+fn decode_payload(message: &jetstream::Message) -> serde_json::Value {
+    match payload_codec(message.headers.as_ref()) {
+        Codec::Protobuf => header(message.headers.as_ref(), schema::SCHEMA_HEADER)
+            .and_then(|name| schema::decode_json(&name, &message.payload)),
+        codec => codec.decode(&message.payload).ok(),
+    }
+    .unwrap_or(serde_json::Value::Null)
+}
+
+// ========== Routes ==========
+
+#[derive(Serialize)]
+pub struct ReplayedMessage {
+    pub subject: String,
+    pub sequence: u64,
+    pub published: DateTime<Utc>,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+pub struct ReplayQuery {
+    /// NATS subject filter, e.g. `orbital.gs.GS-001.telemetry` or `orbital.sat.*.position`
+    pub subject: String,
+    pub from: DateTime<Utc>,
+    pub limit: Option<usize>,
+}
+
+/// GET /telemetry/replay?subject=&from=&limit=
+pub async fn replay_telemetry(
+    State(state): State<AppState>,
+    Query(q): Query<ReplayQuery>,
+) -> Result<Json<Vec<ReplayedMessage>>, (StatusCode, String)> {
+    let telemetry = state.telemetry.as_ref().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "NATS telemetry not configured".to_string(),
+    ))?;
+    let limit = q.limit.unwrap_or(1000).min(MAX_REPLAY_MESSAGES);
+
+    telemetry
+        .replay(&q.subject, q.from, limit)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subject_token() {
+        assert_eq!(subject_token("HALO-01"), "HALO-01");
+        assert_eq!(subject_token("ISS (ZARYA)"), "ISS_(ZARYA)");
+        assert_eq!(subject_token("a.b*c>d"), "a_b_c_d");
+    }
+}
@@ -0,0 +1,296 @@
+//! TLE upload and download
+//!
+//! `POST /tle` accepts either 2LE/3LE text or OMM JSON (a single record or
+//! an array). The whole batch is validated before anything changes; on
+//! success the constellation is swapped in one step, either replacing the
+//! current set or merging into it by NORAD ID. Topology, routing graphs
+//! and passes are derived from the live constellation per request, so they
+//! pick up the new set immediately.
+//!
+//! `GET /tle.txt` returns the live elements as a 3LE file (24-column name
+//! line, 69-column element lines with checksums) for Gpredict, STK and
+//! SatNOGS.
+
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use orbital_mechanics::tle::{parse_tle_text, OmmRecord, TleRecord};
+use orbital_mechanics::walker::tle_checksum;
+use orbital_mechanics::Satellite;
+
+use crate::{AppState, ConstellationState};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadMode {
+    /// Update matching NORAD IDs in place and append the rest
+    #[default]
+    Merge,
+    /// Discard the current constellation
+    Replace,
+}
+
+#[derive(Deserialize)]
+pub struct UploadQuery {
+    #[serde(default)]
+    pub mode: UploadMode,
+}
+
+#[derive(Serialize)]
+pub struct UploadResponse {
+    pub mode: UploadMode,
+    pub parsed: usize,
+    pub added: usize,
+    pub updated: usize,
+    pub total_satellites: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OmmBody {
+    One(OmmRecord),
+    Many(Vec<OmmRecord>),
+}
+
+/// Parse the request body as OMM JSON or TLE text
+fn parse_body(headers: &HeaderMap, body: &str) -> Result<Vec<TleRecord>, String> {
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|ct| ct.contains("json"))
+        .unwrap_or_else(|| body.trim_start().starts_with(['{', '[']));
+
+    if is_json {
+        let omm = match serde_json::from_str::<OmmBody>(body).map_err(|e| e.to_string())? {
+            OmmBody::One(record) => vec![record],
+            OmmBody::Many(records) => records,
+        };
+        omm.iter().map(|r| r.to_tle().map_err(|e| e.to_string())).collect()
+    } else {
+        parse_tle_text(body).map_err(|e| e.to_string())
+    }
+}
+
+/// Apply `records` to `current`, returning the new set and (added, updated)
+fn apply(
+    current: &[Satellite],
+    records: Vec<TleRecord>,
+    mode: UploadMode,
+) -> (Vec<Satellite>, usize, usize) {
+    let mut satellites = match mode {
+        UploadMode::Merge => current.to_vec(),
+        UploadMode::Replace => Vec::new(),
+    };
+    let (mut added, mut updated) = (0, 0);
+
+    for record in records {
+        match satellites.iter_mut().find(|s| s.norad_id == record.norad_id) {
+            // Keep identity, plane/slot and status; refresh elements only
+            Some(existing) => {
+                existing.tle_line1 = record.line1;
+                existing.tle_line2 = record.line2;
+                updated += 1;
+            }
+            None => {
+                satellites.push(record.into_satellite());
+                added += 1;
+            }
+        }
+    }
+
+    (satellites, added, updated)
+}
+
+/// POST /tle?mode=merge|replace
+pub async fn upload_tle(
+    State(state): State<AppState>,
+    Query(q): Query<UploadQuery>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<UploadResponse>, (StatusCode, String)> {
+    let records = parse_body(&headers, &body).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    if records.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "No element sets in upload".to_string()));
+    }
+    let parsed = records.len();
+
+    let (added, updated, total_satellites) = state.constellation.update(|current| {
+        let (satellites, added, updated) = apply(&current.satellites, records, q.mode);
+        let total = satellites.len();
+        let next = ConstellationState {
+            satellites,
+            ground_stations: current.ground_stations.clone(),
+        };
+        (next, (added, updated, total))
+    });
+
+    tracing::info!(
+        "TLE upload ({:?}): {} parsed, {} added, {} updated, {} total",
+        q.mode,
+        parsed,
+        added,
+        updated,
+        total_satellites
+    );
+
+    Ok(Json(UploadResponse {
+        mode: q.mode,
+        parsed,
+        added,
+        updated,
+        total_satellites,
+    }))
+}
+
+/// 3LE name lines are at most 24 columns
+const TLE_NAME_WIDTH: usize = 24;
+/// Element line width before the checksum digit
+const TLE_LINE_WIDTH: usize = 68;
+
+/// Element line at exactly 68 columns plus a freshly computed checksum
+fn element_line(line: &str) -> String {
+    let body: String = line.chars().take(TLE_LINE_WIDTH).collect();
+    let body = format!("{:<width$}", body, width = TLE_LINE_WIDTH);
+    let checksum = tle_checksum(&body);
+    format!("{}{}", body, checksum)
+}
+
+/// Render satellites as 3LE text
+pub fn format_3le<'a>(satellites: impl IntoIterator<Item = &'a Satellite>) -> String {
+    let mut out = String::new();
+    for sat in satellites {
+        let name: String = sat.name.chars().take(TLE_NAME_WIDTH).collect();
+        out.push_str(name.trim_end());
+        out.push('\n');
+        out.push_str(&element_line(&sat.tle_line1));
+        out.push('\n');
+        out.push_str(&element_line(&sat.tle_line2));
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Deserialize)]
+pub struct DownloadQuery {
+    /// Limit to one registered constellation
+    pub constellation: Option<String>,
+}
+
+/// GET /tle.txt - live elements as a 3LE file
+pub async fn download_tle(
+    State(state): State<AppState>,
+    Query(q): Query<DownloadQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let current = state.constellation.load();
+    let (filename, body) = match &q.constellation {
+        Some(name) => {
+            if !state.config.constellations().any(|c| &c.name == name) {
+                return Err((StatusCode::NOT_FOUND, format!("Constellation not found: {}", name)));
+            }
+            (name.to_lowercase(), format_3le(current.constellation(name)))
+        }
+        None => ("constellation".to_string(), format_3le(&current.satellites)),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}.tle\"", filename),
+            ),
+        ],
+        body,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537
+";
+
+    #[test]
+    fn test_merge_updates_by_norad_id() {
+        let records = parse_tle_text(ISS).unwrap();
+        let (sats, added, _) = apply(&[], records.clone(), UploadMode::Merge);
+        assert_eq!((sats.len(), added), (1, 1));
+
+        let (sats, added, updated) = apply(&sats, records, UploadMode::Merge);
+        assert_eq!((sats.len(), added, updated), (1, 0, 1));
+    }
+
+    #[test]
+    fn test_same_named_objects_get_distinct_ids() {
+        let mut text = String::new();
+        for norad_id in ["34427", "34454"] {
+            text.push_str("COSMOS 2251 DEB\n");
+            for line in ISS.lines().skip(1) {
+                text.push_str(&element_line(&format!("{}{}{}", &line[..2], norad_id, &line[7..])));
+                text.push('\n');
+            }
+        }
+        let (sats, added, _) = apply(&[], parse_tle_text(&text).unwrap(), UploadMode::Merge);
+        assert_eq!(added, 2);
+        let ids: Vec<&str> = sats.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["NORAD-34427", "NORAD-34454"]);
+        assert!(sats.iter().all(|s| s.name == "COSMOS 2251 DEB"));
+    }
+
+    #[test]
+    fn test_replace_drops_existing() {
+        let records = parse_tle_text(ISS).unwrap();
+        let existing = vec![Satellite {
+            id: "HALO-01".to_string(),
+            constellation: "HALO".to_string(),
+            norad_id: 60000,
+            name: "HALO-11".to_string(),
+            tle_line1: String::new(),
+            tle_line2: String::new(),
+            plane: 1,
+            slot: 1,
+            status: orbital_mechanics::SatelliteStatus::Operational,
+            propagator: orbital_mechanics::PropagatorKind::Sgp4,
+        }];
+        let (sats, _, _) = apply(&existing, records, UploadMode::Replace);
+        assert_eq!(sats.len(), 1);
+        assert_eq!(sats[0].norad_id, 25544);
+    }
+
+    #[test]
+    fn test_3le_round_trips() {
+        let epoch = chrono::Utc::now();
+        let sats = orbital_mechanics::walker::WalkerDelta::halo_constellation()
+            .generate_satellites("HALO", 60000, epoch);
+        let text = format_3le(&sats);
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), sats.len() * 3);
+        assert_eq!(lines[0], "HALO-11");
+        for line in lines.iter().skip(1).step_by(3).chain(lines.iter().skip(2).step_by(3)) {
+            assert_eq!(line.len(), 69);
+            let checksum = line[68..].parse::<u8>().unwrap();
+            assert_eq!(checksum, tle_checksum(line));
+        }
+
+        let parsed = parse_tle_text(&text).unwrap();
+        assert_eq!(parsed.len(), sats.len());
+        assert_eq!(parsed[4].norad_id, 60004);
+        assert_eq!(parsed[4].name.as_deref(), Some("HALO-21"));
+    }
+
+    #[test]
+    fn test_element_line_restamps_checksum() {
+        let line1 = ISS.lines().nth(1).unwrap();
+        let broken = format!("{}0", &line1[..68]);
+        assert_eq!(element_line(&broken), line1);
+    }
+}
@@ -0,0 +1,490 @@
+//! Constellation topology snapshots
+//!
+//! Builds the node/link picture of the mesh at an arbitrary epoch by
+//! propagating every satellite on demand:
+//! - ISLs: in-plane ring neighbours plus same-slot cross-plane neighbours
+//!   within each constellation, dropped when the line of sight grazes the
+//!   atmosphere
+//! - Ground links: every operational station with the satellite above
+//!   `MIN_LINK_ELEVATION_DEG`
+//!
+//! The default snapshot spans every registered constellation, joined
+//! through the shared ground segment; it can also be limited to one.
+//! Active chaos faults are applied last (see `chaos`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use ground_station_wasm::batch::{self, PointBatch, StationBatch};
+use ground_station_wasm::link_budget;
+use orbital_glaf::{ConstellationGraph, ConstellationLink, ConstellationNode};
+use orbital_mechanics::Satellite;
+
+use crate::chaos::FaultEffect;
+use crate::propagation::MIN_LINK_ELEVATION_DEG;
+use crate::AppState;
+
+/// Speed of light (km/s)
+pub const SPEED_OF_LIGHT_KM_S: f64 = 299_792.458;
+
+/// ISL line of sight must clear Earth radius plus this much atmosphere (km)
+const ISL_GRAZING_ALTITUDE_KM: f64 = 100.0;
+const EARTH_RADIUS_KM: f64 = 6378.137;
+
+/// ISL margin at the reference range; falls off with 20·log10(range)
+const ISL_REFERENCE_MARGIN_DB: f64 = 10.0;
+const ISL_REFERENCE_RANGE_KM: f64 = 10_000.0;
+
+/// Nominal FSO link capacity (matches GLAF link defaults)
+pub const LINK_CAPACITY_GBPS: f64 = 10.0;
+
+/// Great-circle samples per GeoJSON link arc
+const ARC_SEGMENTS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Satellite,
+    GroundStation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    InterSatellite,
+    SatelliteToGround,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeSnapshot {
+    pub id: String,
+    pub name: String,
+    pub kind: NodeKind,
+    /// Owning constellation; empty for ground stations
+    pub constellation: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: f64,
+    /// Satellite plane (1-based); ground stations carry 0
+    pub plane: u8,
+    /// Ground station weather score (1.0 for satellites)
+    pub weather_score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkSnapshot {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub kind: LinkKind,
+    pub range_km: f64,
+    pub latency_ms: f64,
+    pub margin_db: f64,
+    pub weather_score: f64,
+    /// Elevation at the ground end (ground links only)
+    pub elevation_deg: Option<f64>,
+    pub capacity_gbps: f64,
+    /// Offered load / capacity (0-1). No traffic accounting yet, so 0.
+    pub utilization: f64,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologySnapshot {
+    pub epoch: DateTime<Utc>,
+    pub nodes: Vec<NodeSnapshot>,
+    pub links: Vec<LinkSnapshot>,
+}
+
+struct SatSample<'a> {
+    sat: &'a Satellite,
+    eci: [f64; 3],
+}
+
+/// Build the combined topology of every constellation at `time`
+pub fn snapshot(state: &AppState, time: DateTime<Utc>) -> anyhow::Result<TopologySnapshot> {
+    snapshot_of(state, time, None)
+}
+
+/// Build the topology at `time`, limited to one constellation's satellites
+/// (plus the ground segment) when `only` is set
+pub fn snapshot_of(
+    state: &AppState,
+    time: DateTime<Utc>,
+    only: Option<&str>,
+) -> anyhow::Result<TopologySnapshot> {
+    let mut nodes = Vec::new();
+    let mut links = Vec::new();
+    let mut samples = Vec::new();
+
+    let constellation = state.constellation.load();
+    let satellites = constellation
+        .satellites
+        .iter()
+        .filter(|sat| only.is_none_or(|name| sat.constellation == name));
+    for sat in satellites {
+        let sv = match sat.propagate(time) {
+            Ok(sv) => sv,
+            Err(e) => {
+                tracing::warn!("{}: {}", sat.id, e);
+                continue;
+            }
+        };
+        let geo = sv.to_geodetic()?;
+
+        nodes.push(NodeSnapshot {
+            id: sat.id.clone(),
+            name: sat.name.clone(),
+            kind: NodeKind::Satellite,
+            constellation: sat.constellation.clone(),
+            latitude: geo.latitude,
+            longitude: geo.longitude,
+            altitude_km: geo.altitude_km,
+            plane: sat.plane,
+            weather_score: 1.0,
+        });
+        samples.push((
+            SatSample {
+                sat,
+                eci: [sv.position_x, sv.position_y, sv.position_z],
+            },
+            geo,
+        ));
+    }
+
+    // Inter-satellite links, never across constellations
+    for (a, _) in &samples {
+        let shell: Vec<&Satellite> = samples
+            .iter()
+            .map(|(s, _)| s.sat)
+            .filter(|s| s.constellation == a.sat.constellation)
+            .collect();
+        let planes = shell.iter().map(|s| s.plane).max().unwrap_or(0);
+        let per_plane = shell.iter().filter(|s| s.plane == a.sat.plane).count() as u8;
+        let next_slot = a.sat.slot % per_plane.max(1) + 1;
+        let next_plane = a.sat.plane % planes.max(1) + 1;
+
+        let neighbours = samples.iter().filter(|(b, _)| {
+            b.sat.constellation == a.sat.constellation
+                && b.sat.id != a.sat.id
+                && ((b.sat.plane == a.sat.plane && b.sat.slot == next_slot)
+                    || (planes > 1 && b.sat.plane == next_plane && b.sat.slot == a.sat.slot))
+        });
+
+        for (b, _) in neighbours {
+            let range_km = distance(&a.eci, &b.eci);
+            let clear = segment_clearance_km(&a.eci, &b.eci)
+                > EARTH_RADIUS_KM + ISL_GRAZING_ALTITUDE_KM;
+            links.push(LinkSnapshot {
+                id: format!("ISL-{}-{}", a.sat.id, b.sat.id),
+                source: a.sat.id.clone(),
+                target: b.sat.id.clone(),
+                kind: LinkKind::InterSatellite,
+                range_km,
+                latency_ms: range_km / SPEED_OF_LIGHT_KM_S * 1000.0,
+                margin_db: ISL_REFERENCE_MARGIN_DB
+                    - 20.0 * (range_km / ISL_REFERENCE_RANGE_KM).log10(),
+                weather_score: 1.0,
+                elevation_deg: None,
+                capacity_gbps: LINK_CAPACITY_GBPS,
+                utilization: 0.0,
+                active: clear,
+            });
+        }
+    }
+
+    // Ground stations and their links, one batch of look angles per station
+    let stations: Vec<_> = constellation.operational_stations().collect();
+    let sites = StationBatch::from_geodetic(
+        stations
+            .iter()
+            .map(|s| (s.location.latitude, s.location.longitude, s.location.altitude_m / 1000.0)),
+    );
+    let sat_points = PointBatch::from_geodetic(
+        samples
+            .iter()
+            .map(|(_, geo)| (geo.latitude, geo.longitude, geo.altitude_km)),
+    );
+    for (i, station) in stations.into_iter().enumerate() {
+        let weather_score = station
+            .weather
+            .as_ref()
+            .map(|w| w.beam_quality_score)
+            .unwrap_or(1.0);
+
+        nodes.push(NodeSnapshot {
+            id: station.id.clone(),
+            name: station.name.clone(),
+            kind: NodeKind::GroundStation,
+            constellation: String::new(),
+            latitude: station.location.latitude,
+            longitude: station.location.longitude,
+            altitude_km: station.location.altitude_m / 1000.0,
+            plane: 0,
+            weather_score,
+        });
+
+        let angles = sites.station_to_satellites(i, &sat_points);
+        for j in batch::above(&angles, MIN_LINK_ELEVATION_DEG) {
+            let sample = &samples[j].0;
+            let (elevation_deg, range_km) = (angles.elevation_deg[j], angles.range_km[j]);
+
+            let margin_db = link_budget::calculate_margin(elevation_deg, weather_score);
+            links.push(LinkSnapshot {
+                id: format!("SG-{}-{}", sample.sat.id, station.id),
+                source: sample.sat.id.clone(),
+                target: station.id.clone(),
+                kind: LinkKind::SatelliteToGround,
+                range_km,
+                latency_ms: range_km / SPEED_OF_LIGHT_KM_S * 1000.0,
+                margin_db,
+                weather_score,
+                elevation_deg: Some(elevation_deg),
+                capacity_gbps: LINK_CAPACITY_GBPS,
+                utilization: 0.0,
+                active: margin_db > 0.0,
+            });
+        }
+    }
+
+    // Injected faults
+    let faults = state.chaos.active_at(time);
+    if !faults.is_empty() {
+        for link in &mut links {
+            match faults.link(&link.source, &link.target) {
+                Some(FaultEffect::Fail) => link.active = false,
+                Some(FaultEffect::Degrade { margin_loss_db }) => {
+                    link.margin_db -= margin_loss_db;
+                    if link.kind == LinkKind::SatelliteToGround {
+                        link.active = link.margin_db > 0.0;
+                    }
+                }
+                Some(FaultEffect::Congest { .. }) | None => {}
+            }
+            link.utilization = link.utilization.max(faults.utilization(&link.source, &link.target));
+        }
+    }
+
+    Ok(TopologySnapshot {
+        epoch: time,
+        nodes,
+        links,
+    })
+}
+
+impl TopologySnapshot {
+    /// Load the snapshot into a GLAF graph for routing
+    pub fn to_graph(&self) -> ConstellationGraph {
+        let mut graph = ConstellationGraph::new();
+
+        for node in &self.nodes {
+            let mut glaf_node = match node.kind {
+                NodeKind::Satellite => ConstellationNode::satellite(
+                    &node.id,
+                    &node.name,
+                    node.latitude,
+                    node.longitude,
+                    node.altitude_km,
+                    node.plane,
+                    0.0,
+                ),
+                NodeKind::GroundStation => ConstellationNode::ground_station(
+                    &node.id,
+                    &node.name,
+                    node.latitude,
+                    node.longitude,
+                    1,
+                ),
+            };
+            glaf_node.epoch = self.epoch.timestamp();
+            graph.add_node(glaf_node);
+        }
+
+        for link in &self.links {
+            let mut glaf_link = match link.kind {
+                LinkKind::InterSatellite => ConstellationLink::inter_satellite(&link.id, link.margin_db),
+                LinkKind::SatelliteToGround => {
+                    ConstellationLink::satellite_to_ground(&link.id, link.margin_db, link.weather_score)
+                }
+            };
+            glaf_link.latency_ms = link.latency_ms;
+            glaf_link.throughput_gbps = link.capacity_gbps * (1.0 - link.utilization);
+            glaf_link.utilization = link.utilization;
+            glaf_link.active = link.active;
+
+            if let Err(e) = graph.add_link(&link.source, &link.target, glaf_link) {
+                tracing::warn!("Skipping link {}: {}", link.id, e);
+            }
+        }
+
+        graph
+    }
+
+    /// GeoJSON FeatureCollection: a Point per node and a great-circle arc
+    /// per active link. Coordinates are `[lon, lat, alt_m]`; arcs crossing
+    /// the antimeridian are split into a MultiLineString (RFC 7946 §3.1.9).
+    pub fn to_geojson(&self) -> serde_json::Value {
+        let mut features = Vec::with_capacity(self.nodes.len() + self.links.len());
+
+        for node in &self.nodes {
+            features.push(json!({
+                "type": "Feature",
+                "id": node.id,
+                "geometry": {
+                    "type": "Point",
+                    "coordinates": [node.longitude, node.latitude, node.altitude_km * 1000.0],
+                },
+                "properties": {
+                    "name": node.name,
+                    "kind": node.kind,
+                    "constellation": node.constellation,
+                    "plane": node.plane,
+                    "weather_score": node.weather_score,
+                },
+            }));
+        }
+
+        for link in self.links.iter().filter(|l| l.active) {
+            let (Some(a), Some(b)) = (
+                self.nodes.iter().find(|n| n.id == link.source),
+                self.nodes.iter().find(|n| n.id == link.target),
+            ) else {
+                continue;
+            };
+
+            let parts = split_antimeridian(&great_circle_arc(a, b, ARC_SEGMENTS));
+            let geometry = if parts.len() == 1 {
+                json!({ "type": "LineString", "coordinates": parts[0] })
+            } else {
+                json!({ "type": "MultiLineString", "coordinates": parts })
+            };
+
+            features.push(json!({
+                "type": "Feature",
+                "id": link.id,
+                "geometry": geometry,
+                "properties": {
+                    "source": link.source,
+                    "target": link.target,
+                    "kind": link.kind,
+                    "range_km": link.range_km,
+                    "latency_ms": link.latency_ms,
+                    "margin_db": link.margin_db,
+                    "weather_score": link.weather_score,
+                    "elevation_deg": link.elevation_deg,
+                    "capacity_gbps": link.capacity_gbps,
+                    "utilization": link.utilization,
+                },
+            }));
+        }
+
+        json!({
+            "type": "FeatureCollection",
+            "epoch": self.epoch,
+            "features": features,
+        })
+    }
+}
+
+/// Slerp between two nodes' sub-points, linearly interpolating altitude
+fn great_circle_arc(a: &NodeSnapshot, b: &NodeSnapshot, segments: usize) -> Vec<[f64; 3]> {
+    let to_unit = |lat: f64, lon: f64| {
+        let (lat, lon) = (lat.to_radians(), lon.to_radians());
+        [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+    };
+    let pa = to_unit(a.latitude, a.longitude);
+    let pb = to_unit(b.latitude, b.longitude);
+    let dot = (pa[0] * pb[0] + pa[1] * pb[1] + pa[2] * pb[2]).clamp(-1.0, 1.0);
+    let omega = dot.acos();
+
+    (0..=segments)
+        .map(|i| {
+            let t = i as f64 / segments as f64;
+            let (wa, wb) = if omega.abs() < 1e-9 {
+                (1.0 - t, t)
+            } else {
+                (
+                    ((1.0 - t) * omega).sin() / omega.sin(),
+                    (t * omega).sin() / omega.sin(),
+                )
+            };
+            let p = [
+                wa * pa[0] + wb * pb[0],
+                wa * pa[1] + wb * pb[1],
+                wa * pa[2] + wb * pb[2],
+            ];
+            let lat = p[2].atan2((p[0] * p[0] + p[1] * p[1]).sqrt()).to_degrees();
+            let lon = p[1].atan2(p[0]).to_degrees();
+            let alt_m = (a.altitude_km + t * (b.altitude_km - a.altitude_km)) * 1000.0;
+            [lon, lat, alt_m]
+        })
+        .collect()
+}
+
+/// Break a polyline wherever consecutive longitudes jump across ±180°
+fn split_antimeridian(points: &[[f64; 3]]) -> Vec<Vec<[f64; 3]>> {
+    let mut parts = vec![Vec::new()];
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 {
+            let prev = points[i - 1];
+            if (p[0] - prev[0]).abs() > 180.0 {
+                // Interpolate the crossing latitude/altitude at the seam
+                let edge = if prev[0] > 0.0 { 180.0 } else { -180.0 };
+                let unwrapped = p[0] + 2.0 * edge;
+                let t = (edge - prev[0]) / (unwrapped - prev[0]);
+                let lat = prev[1] + t * (p[1] - prev[1]);
+                let alt = prev[2] + t * (p[2] - prev[2]);
+                parts.last_mut().unwrap().push([edge, lat, alt]);
+                parts.push(vec![[-edge, lat, alt]]);
+            }
+        }
+        parts.last_mut().unwrap().push(*p);
+    }
+    parts
+}
+
+fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// Closest approach of the segment a→b to Earth's centre (km)
+fn segment_clearance_km(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    let d = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let dd = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+    if dd == 0.0 {
+        return distance(a, &[0.0; 3]);
+    }
+    let t = (-(a[0] * d[0] + a[1] * d[1] + a[2] * d[2]) / dd).clamp(0.0, 1.0);
+    let p = [a[0] + t * d[0], a[1] + t * d[1], a[2] + t * d[2]];
+    distance(&p, &[0.0; 3])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_clearance() {
+        // Segment passing through Earth's centre
+        let through = segment_clearance_km(&[-10000.0, 0.0, 0.0], &[10000.0, 0.0, 0.0]);
+        assert!(through < 1.0);
+
+        // Segment well above the surface
+        let above = segment_clearance_km(&[-10000.0, 15000.0, 0.0], &[10000.0, 15000.0, 0.0]);
+        assert!((above - 15000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_antimeridian_split() {
+        let points = [[170.0, 0.0, 0.0], [179.0, 1.0, 0.0], [-179.0, 3.0, 0.0], [-170.0, 4.0, 0.0]];
+        let parts = split_antimeridian(&points);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].last().unwrap()[0], 180.0);
+        assert_eq!(parts[1][0][0], -180.0);
+        assert!((parts[0].last().unwrap()[1] - 2.0).abs() < 1e-9);
+
+        let straight = [[10.0, 0.0, 0.0], [20.0, 0.0, 0.0]];
+        assert_eq!(split_antimeridian(&straight).len(), 1);
+    }
+}
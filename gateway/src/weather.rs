@@ -0,0 +1,192 @@
+//! Station weather
+//!
+//! Live FSO weather for the ground-station network. Observations come from
+//! Open-Meteo through `WeatherApi`, which caches each location for the
+//! configured TTL; bulk requests fan out with bounded parallelism so a full
+//! network refresh neither runs serially nor floods the provider.
+//!
+//! The latest score fetched for each station is kept and applied to the
+//! GLAF routing graph (`orbital_glaf::weather`), so routes follow weather
+//! as soon as it is fetched. `GET /weather/links` shows the ground links
+//! that weather changes on the current topology.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use ground_station_wasm::{FsoWeatherScore, WeatherApi, WeatherApiConfig, WeatherApiProvider};
+use ground_stations::GroundStation;
+use orbital_glaf::weather::{StationWeather as GlafWeather, TopologyDiff};
+use orbital_glaf::ConstellationGraph;
+
+use crate::config::WeatherSection;
+use crate::{topology, AppState};
+
+pub struct WeatherState {
+    api: WeatherApi,
+    /// Most recent successful score per station
+    latest: RwLock<HashMap<String, FsoWeatherScore>>,
+}
+
+impl WeatherState {
+    pub fn new(config: &WeatherSection) -> Self {
+        Self {
+            api: WeatherApi::new(WeatherApiConfig {
+                provider: WeatherApiProvider::OpenMeteo,
+                cache_ttl_sec: config.cache_ttl_secs,
+                max_concurrent: config.max_concurrent,
+                timeout_sec: config.timeout_secs,
+            }),
+            latest: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Apply the latest known scores to a routing graph
+    pub fn apply_to(&self, graph: &mut ConstellationGraph) -> TopologyDiff {
+        let scores: Vec<GlafWeather> = self
+            .latest
+            .read()
+            .unwrap()
+            .values()
+            .map(|s| GlafWeather {
+                station_id: s.station_id.clone(),
+                quality: s.quality,
+                link_viable: s.link_viable,
+                degradation_reason: s.degradation_reason.clone(),
+            })
+            .collect();
+        graph.apply_weather(&scores)
+    }
+
+    /// Most recent score of every station fetched so far
+    pub fn latest_scores(&self) -> Vec<FsoWeatherScore> {
+        self.latest.read().unwrap().values().cloned().collect()
+    }
+
+    /// Fetch one location through the cache; `Err` if the provider is unreachable
+    pub async fn probe(&self, latitude: f64, longitude: f64) -> Result<(), String> {
+        self.api
+            .fetch_current(latitude, longitude)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Scores for `stations`, fetched concurrently (cache hits are free)
+    pub async fn scores<'a>(
+        &self,
+        stations: impl Iterator<Item = &'a GroundStation>,
+    ) -> Vec<StationWeather> {
+        let stations: Vec<&GroundStation> = stations.collect();
+        let locations: Vec<(String, f64, f64)> = stations
+            .iter()
+            .map(|s| (s.id.clone(), s.location.latitude, s.location.longitude))
+            .collect();
+        let mut results = self.api.fetch_batch(&locations).await;
+        {
+            let mut latest = self.latest.write().unwrap();
+            for (id, result) in &results {
+                if let Ok(score) = result {
+                    latest.insert(id.clone(), score.clone());
+                }
+            }
+        }
+
+        stations
+            .into_iter()
+            .map(|station| {
+                let (score, error) = match results.remove(&station.id) {
+                    Some(Ok(score)) => (Some(score), None),
+                    Some(Err(e)) => (None, Some(e.to_string())),
+                    None => (None, Some("Not fetched".to_string())),
+                };
+                StationWeather {
+                    station_id: station.id.clone(),
+                    name: station.name.clone(),
+                    latitude: station.location.latitude,
+                    longitude: station.location.longitude,
+                    score,
+                    error,
+                }
+            })
+            .collect()
+    }
+}
+
+// ========== Routes ==========
+
+#[derive(Serialize)]
+pub struct StationWeather {
+    pub station_id: String,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub score: Option<FsoWeatherScore>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BulkWeatherResponse {
+    pub generated_at: DateTime<Utc>,
+    pub total: usize,
+    pub viable: usize,
+    pub failed: usize,
+    pub cache_entries: usize,
+    pub cache_valid: usize,
+    pub stations: Vec<StationWeather>,
+}
+
+/// GET /weather/stations - FSO scores for every station in one response
+pub async fn get_all_station_weather(State(state): State<AppState>) -> Json<BulkWeatherResponse> {
+    let constellation = state.constellation.load();
+    let stations = state.weather.scores(constellation.ground_stations.iter()).await;
+    let (cache_entries, cache_valid) = state.weather.api.cache_stats().await;
+
+    Json(BulkWeatherResponse {
+        generated_at: Utc::now(),
+        total: stations.len(),
+        viable: stations
+            .iter()
+            .filter(|s| s.score.as_ref().is_some_and(|score| score.link_viable))
+            .count(),
+        failed: stations.iter().filter(|s| s.score.is_none()).count(),
+        cache_entries,
+        cache_valid,
+        stations,
+    })
+}
+
+/// GET /weather/stations/:id
+pub async fn get_station_weather(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<StationWeather>, (StatusCode, String)> {
+    let constellation = state.constellation.load();
+    let station = constellation
+        .station(&id)
+        .ok_or((StatusCode::NOT_FOUND, format!("Station not found: {}", id)))?;
+
+    let mut results = state.weather.scores(std::iter::once(station)).await;
+    let result = results.remove(0);
+    match &result.error {
+        Some(e) => Err((StatusCode::BAD_GATEWAY, e.clone())),
+        None => Ok(Json(result)),
+    }
+}
+
+/// GET /weather/links - ground links changed by the latest station
+/// weather on the current topology
+pub async fn get_weather_links(
+    State(state): State<AppState>,
+) -> Result<Json<TopologyDiff>, (StatusCode, String)> {
+    let mut graph = topology::snapshot(&state, state.clock.now())
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .to_graph();
+    Ok(Json(state.weather.apply_to(&mut graph)))
+}
@@ -0,0 +1,405 @@
+//! AOS Transfer Frames (CCSDS 732.0) and virtual channel multiplexing
+//! AOS frames share `FrameFormat` and the sync sublayer with TM; the data
+//! field is an M_PDU (first header pointer, then the packet zone). `VcMux`
+//! keeps a packet queue per virtual channel and sends the highest-priority
+//! channel with data, round-robin within a priority level, falling back to
+//! idle frames on VC 63. `VcDemux` feeds each channel's own extractor.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+
+use crate::spp::{ExtractorStats, PacketExtractor, PacketMux, SpacePacket, FHP_IDLE};
+use crate::tm::{crc16, FrameFormat, FECF_LEN, PRIMARY_HEADER_LEN};
+
+pub const AOS_VERSION: u8 = 1;
+pub const M_PDU_HEADER_LEN: usize = 2;
+/// Virtual channel reserved for idle frames
+pub const IDLE_VCID: u8 = 63;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AosFrame {
+    /// Spacecraft ID, 8 bits
+    pub scid: u8,
+    /// Virtual channel ID, 6 bits
+    pub vcid: u8,
+    /// Virtual channel frame count, 24 bits
+    pub vc_count: u32,
+    pub replay: bool,
+    /// M_PDU first header pointer, 11 bits
+    pub first_header_pointer: u16,
+    /// Padded to the format's packet zone length on encode
+    pub packet_zone: Vec<u8>,
+    pub ocf: Option<[u8; 4]>,
+}
+
+impl AosFrame {
+    pub fn idle(scid: u8) -> Self {
+        Self {
+            scid,
+            vcid: IDLE_VCID,
+            vc_count: 0,
+            replay: false,
+            first_header_pointer: FHP_IDLE,
+            packet_zone: Vec::new(),
+            ocf: None,
+        }
+    }
+
+    pub fn packet_zone_len(format: &FrameFormat) -> usize {
+        format.data_field_len() - M_PDU_HEADER_LEN
+    }
+
+    pub fn to_bytes(&self, format: &FrameFormat) -> Result<Vec<u8>> {
+        let zone_len = Self::packet_zone_len(format);
+        if self.packet_zone.len() > zone_len {
+            bail!(
+                "packet zone {} bytes exceeds {}",
+                self.packet_zone.len(),
+                zone_len
+            );
+        }
+        if format.ocf != self.ocf.is_some() {
+            bail!("OCF presence does not match frame format");
+        }
+
+        let mut v = Vec::with_capacity(format.frame_len);
+        let id =
+            ((AOS_VERSION as u16) << 14) | ((self.scid as u16) << 6) | (self.vcid as u16 & 0x3F);
+        v.extend(id.to_be_bytes());
+        v.extend(&(self.vc_count & 0xFF_FFFF).to_be_bytes()[1..]);
+        // Frame count usage flag clear, no count cycle
+        v.push((self.replay as u8) << 7);
+        v.extend((self.first_header_pointer & 0x7FF).to_be_bytes());
+        v.extend(&self.packet_zone);
+        v.resize(PRIMARY_HEADER_LEN + format.data_field_len(), 0);
+        if let Some(ocf) = self.ocf {
+            v.extend(ocf);
+        }
+        if format.fecf {
+            v.extend(crc16(&v).to_be_bytes());
+        }
+        Ok(v)
+    }
+
+    pub fn parse(raw: &[u8], format: &FrameFormat) -> Result<Self> {
+        if raw.len() != format.frame_len {
+            bail!(
+                "frame is {} bytes, expected {}",
+                raw.len(),
+                format.frame_len
+            );
+        }
+        if format.fecf {
+            let (body, fecf) = raw.split_at(raw.len() - FECF_LEN);
+            if crc16(body) != u16::from_be_bytes([fecf[0], fecf[1]]) {
+                bail!("FECF mismatch");
+            }
+        }
+
+        let id = u16::from_be_bytes([raw[0], raw[1]]);
+        if (id >> 14) as u8 != AOS_VERSION {
+            bail!("not an AOS frame (version {})", id >> 14);
+        }
+        let zone_start = PRIMARY_HEADER_LEN + M_PDU_HEADER_LEN;
+        let data_end = PRIMARY_HEADER_LEN + format.data_field_len();
+        let ocf = format.ocf.then(|| {
+            [
+                raw[data_end],
+                raw[data_end + 1],
+                raw[data_end + 2],
+                raw[data_end + 3],
+            ]
+        });
+
+        Ok(Self {
+            scid: (id >> 6) as u8,
+            vcid: (id & 0x3F) as u8,
+            vc_count: u32::from_be_bytes([0, raw[2], raw[3], raw[4]]),
+            replay: raw[5] & 0x80 != 0,
+            first_header_pointer: u16::from_be_bytes([raw[6], raw[7]]) & 0x7FF,
+            packet_zone: raw[zone_start..data_end].to_vec(),
+            ocf,
+        })
+    }
+}
+
+/// How `VcMux` picks among channels with data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// Every channel in turn, priorities ignored
+    RoundRobin,
+    /// Highest priority first, round-robin among equals
+    Priority,
+}
+
+#[derive(Debug)]
+struct Channel {
+    vcid: u8,
+    priority: u8,
+    queue: PacketMux,
+    vc_count: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MuxStats {
+    pub frames: u64,
+    pub idle_frames: u64,
+    /// Frames sent per virtual channel
+    pub vc_frames: BTreeMap<u8, u64>,
+}
+
+/// Per-virtual-channel packet queues multiplexed onto one master channel
+#[derive(Debug)]
+pub struct VcMux {
+    scid: u8,
+    format: FrameFormat,
+    schedule: Schedule,
+    channels: Vec<Channel>,
+    /// Round-robin position, the channel after the last one served
+    cursor: usize,
+    pub stats: MuxStats,
+}
+
+impl VcMux {
+    pub fn new(scid: u8, format: FrameFormat, schedule: Schedule) -> Result<Self> {
+        format.validate()?;
+        if format.data_field_len() <= M_PDU_HEADER_LEN {
+            bail!("data field too short for an M_PDU");
+        }
+        Ok(Self {
+            scid,
+            format,
+            schedule,
+            channels: Vec::new(),
+            cursor: 0,
+            stats: MuxStats::default(),
+        })
+    }
+
+    /// Open `vcid` with `priority`, higher sent first under `Schedule::Priority`
+    pub fn add_channel(&mut self, vcid: u8, priority: u8) -> Result<()> {
+        if vcid >= IDLE_VCID {
+            bail!("VCID {} is reserved or out of range", vcid);
+        }
+        if self.channels.iter().any(|c| c.vcid == vcid) {
+            bail!("VCID {} already open", vcid);
+        }
+        self.channels.push(Channel {
+            vcid,
+            priority,
+            queue: PacketMux::default(),
+            vc_count: 0,
+        });
+        Ok(())
+    }
+
+    pub fn push(&mut self, vcid: u8, packet: &SpacePacket) -> Result<()> {
+        let Some(channel) = self.channels.iter_mut().find(|c| c.vcid == vcid) else {
+            bail!("VCID {} not open", vcid);
+        };
+        channel.queue.push(packet)
+    }
+
+    /// Bytes queued on `vcid`, or on every channel
+    pub fn pending(&self, vcid: Option<u8>) -> usize {
+        self.channels
+            .iter()
+            .filter(|c| vcid.is_none() || vcid == Some(c.vcid))
+            .map(|c| c.queue.pending())
+            .sum()
+    }
+
+    fn select(&self) -> Option<usize> {
+        let n = self.channels.len();
+        let ready: Vec<usize> = (0..n)
+            .map(|k| (self.cursor + k) % n)
+            .filter(|&i| self.channels[i].queue.pending() > 0)
+            .collect();
+        match self.schedule {
+            Schedule::RoundRobin => ready.first().copied(),
+            Schedule::Priority => {
+                let top = ready.iter().map(|&i| self.channels[i].priority).max()?;
+                ready
+                    .into_iter()
+                    .find(|&i| self.channels[i].priority == top)
+            }
+        }
+    }
+
+    /// Next frame for the master channel, idle when every queue is empty
+    pub fn next_frame(&mut self) -> Result<AosFrame> {
+        self.stats.frames += 1;
+        let Some(i) = self.select() else {
+            self.stats.idle_frames += 1;
+            let mut frame = AosFrame::idle(self.scid);
+            frame.ocf = self.format.ocf.then_some([0; 4]);
+            return Ok(frame);
+        };
+        self.cursor = (i + 1) % self.channels.len();
+
+        let zone_len = AosFrame::packet_zone_len(&self.format);
+        let channel = &mut self.channels[i];
+        let (packet_zone, fhp) = channel.queue.data_field(zone_len)?;
+        let frame = AosFrame {
+            scid: self.scid,
+            vcid: channel.vcid,
+            vc_count: channel.vc_count,
+            replay: false,
+            first_header_pointer: fhp,
+            packet_zone,
+            ocf: self.format.ocf.then_some([0; 4]),
+        };
+        channel.vc_count = (channel.vc_count + 1) & 0xFF_FFFF;
+        *self.stats.vc_frames.entry(channel.vcid).or_default() += 1;
+        Ok(frame)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DemuxStats {
+    pub frames: u64,
+    pub idle_frames: u64,
+    /// Frames on channels that were never opened
+    pub unknown_vc: u64,
+}
+
+/// Splits a master channel back into per-virtual-channel packet streams
+#[derive(Debug, Default)]
+pub struct VcDemux {
+    /// Extractor and last frame count per open channel
+    channels: BTreeMap<u8, (PacketExtractor, Option<u32>)>,
+    pub stats: DemuxStats,
+}
+
+impl VcDemux {
+    pub fn add_channel(&mut self, vcid: u8) {
+        self.channels.entry(vcid).or_default();
+    }
+
+    pub fn channel_stats(&self, vcid: u8) -> Option<&ExtractorStats> {
+        self.channels
+            .get(&vcid)
+            .map(|(extractor, _)| &extractor.stats)
+    }
+
+    /// Feed the next frame; returns the non-idle packets it completed
+    pub fn push_frame(&mut self, frame: &AosFrame) -> Vec<SpacePacket> {
+        self.stats.frames += 1;
+        if frame.vcid == IDLE_VCID {
+            self.stats.idle_frames += 1;
+            return Vec::new();
+        }
+        let Some((extractor, last)) = self.channels.get_mut(&frame.vcid) else {
+            self.stats.unknown_vc += 1;
+            return Vec::new();
+        };
+        let gap = last.is_some_and(|c| frame.vc_count != (c + 1) & 0xFF_FFFF);
+        *last = Some(frame.vc_count);
+        extractor.push_field(gap, frame.first_header_pointer, &frame.packet_zone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format() -> FrameFormat {
+        FrameFormat {
+            frame_len: 64,
+            ocf: false,
+            fecf: true,
+        }
+    }
+
+    fn packet(apid: u16, count: u16, len: usize) -> SpacePacket {
+        SpacePacket::telemetry(apid, count, &vec![apid as u8; len])
+    }
+
+    #[test]
+    fn header_round_trip() {
+        let format = FrameFormat {
+            ocf: true,
+            ..format()
+        };
+        let frame = AosFrame {
+            scid: 0x42,
+            vcid: 5,
+            vc_count: 0x12_3456,
+            replay: true,
+            first_header_pointer: 3,
+            packet_zone: vec![7; 50],
+            ocf: Some([1, 2, 3, 4]),
+        };
+        let raw = frame.to_bytes(&format).unwrap();
+        assert_eq!(raw[..8], [0x50, 0x85, 0x12, 0x34, 0x56, 0x80, 0x00, 0x03]);
+        assert_eq!(AosFrame::parse(&raw, &format).unwrap(), frame);
+
+        let mut tm_like = raw.clone();
+        tm_like[0] &= 0x3F;
+        assert!(AosFrame::parse(&tm_like, &format).is_err());
+    }
+
+    #[test]
+    fn priority_and_round_robin_order() {
+        let order = |schedule| {
+            let mut mux = VcMux::new(0x42, format(), schedule).unwrap();
+            mux.add_channel(1, 0).unwrap();
+            mux.add_channel(2, 0).unwrap();
+            mux.add_channel(3, 9).unwrap();
+            for vcid in 1..=3u8 {
+                for i in 0..2 {
+                    mux.push(vcid, &packet(vcid as u16, i, 49)).unwrap();
+                }
+            }
+            (0..7)
+                .map(|_| mux.next_frame().unwrap().vcid)
+                .collect::<Vec<_>>()
+        };
+        // Two 55-byte packets take three 54-byte zones, the last idle-filled
+        assert_eq!(order(Schedule::Priority), [3, 3, 3, 1, 2, 1, 2]);
+        assert_eq!(order(Schedule::RoundRobin), [1, 2, 3, 1, 2, 3, 1]);
+        assert!(VcMux::new(0x42, format(), Schedule::Priority)
+            .unwrap()
+            .add_channel(IDLE_VCID, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn demux_recovers_each_channel() {
+        let format = format();
+        let mut mux = VcMux::new(0x42, format, Schedule::RoundRobin).unwrap();
+        let mut demux = VcDemux::default();
+        for vcid in [1, 2] {
+            mux.add_channel(vcid, 0).unwrap();
+            demux.add_channel(vcid);
+        }
+        for i in 0..10 {
+            mux.push(1, &packet(0x10, i, 20)).unwrap();
+            mux.push(2, &packet(0x20, i, 90)).unwrap();
+        }
+
+        let mut packets = Vec::new();
+        let mut sent = 0;
+        while mux.pending(None) > 0 {
+            let frame = mux.next_frame().unwrap();
+            sent += 1;
+            // Lose one VC 2 frame in the middle
+            if frame.vcid == 2 && frame.vc_count == 8 {
+                continue;
+            }
+            let raw = frame.to_bytes(&format).unwrap();
+            packets.extend(demux.push_frame(&AosFrame::parse(&raw, &format).unwrap()));
+        }
+        demux.push_frame(&AosFrame::idle(0x42));
+
+        let count = |apid| packets.iter().filter(|p| p.apid == apid).count();
+        assert_eq!(count(0x10), 10);
+        assert!(count(0x20) < 10 && count(0x20) >= 8);
+        assert_eq!(demux.channel_stats(1).unwrap().frame_gaps, 0);
+        assert_eq!(demux.channel_stats(2).unwrap().frame_gaps, 1);
+        assert_eq!(demux.stats.idle_frames, 1);
+        assert_eq!(mux.stats.frames, sent);
+        assert_eq!(mux.stats.vc_frames.values().sum::<u64>(), sent);
+    }
+}
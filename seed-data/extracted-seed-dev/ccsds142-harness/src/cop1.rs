@@ -0,0 +1,461 @@
+//! COP-1 (CCSDS 232.1), skeleton
+//! FOP-1 on the ground sends AD frames through a sliding window and
+//! retransmits on CLCW reports or timer expiry; FARM-1 on board accepts them
+//! in sequence and reports V(R) and its flags in the CLCW, which travels back
+//! in the TM frame OCF. Suspend/resume and the FOP initialisation handshake
+//! are left out: `Fop::initialise` goes straight to Active.
+
+use std::collections::VecDeque;
+
+use anyhow::{bail, Result};
+
+use crate::tc::{TcFrame, TcFrameType};
+
+/// BC "Unlock" control command
+pub const UNLOCK: [u8; 1] = [0x00];
+
+/// BC "Set V(R)" control command
+pub fn set_vr(vr: u8) -> [u8; 3] {
+    [0x82, 0x00, vr]
+}
+
+/// Communications Link Control Word, type 0 with COP-1 in effect
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Clcw {
+    pub vcid: u8,
+    pub no_rf_available: bool,
+    pub no_bit_lock: bool,
+    pub lockout: bool,
+    pub wait: bool,
+    pub retransmit: bool,
+    /// Count of accepted BD/BC frames, 2 bits
+    pub farm_b_counter: u8,
+    /// V(R), the next expected N(S)
+    pub report_value: u8,
+}
+
+impl Clcw {
+    pub fn to_bytes(self) -> [u8; 4] {
+        [
+            0x01,
+            (self.vcid & 0x3F) << 2,
+            ((self.no_rf_available as u8) << 7)
+                | ((self.no_bit_lock as u8) << 6)
+                | ((self.lockout as u8) << 5)
+                | ((self.wait as u8) << 4)
+                | ((self.retransmit as u8) << 3)
+                | ((self.farm_b_counter & 0x03) << 1),
+            self.report_value,
+        ]
+    }
+
+    pub fn parse(raw: [u8; 4]) -> Result<Self> {
+        if raw[0] >> 7 != 0 {
+            bail!("not a CLCW (control word type 1)");
+        }
+        if raw[0] & 0x03 != 0x01 {
+            bail!(
+                "CLCW does not report COP-1 (COP in effect {})",
+                raw[0] & 0x03
+            );
+        }
+        Ok(Self {
+            vcid: raw[1] >> 2,
+            no_rf_available: raw[2] & 0x80 != 0,
+            no_bit_lock: raw[2] & 0x40 != 0,
+            lockout: raw[2] & 0x20 != 0,
+            wait: raw[2] & 0x10 != 0,
+            retransmit: raw[2] & 0x08 != 0,
+            farm_b_counter: (raw[2] >> 1) & 0x03,
+            report_value: raw[3],
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FarmResult {
+    /// AD or BD frame data passed up
+    Accepted(Vec<u8>),
+    /// BC frame executed
+    Control,
+    Discarded,
+}
+
+/// FARM-1 for one virtual channel
+#[derive(Debug, Clone)]
+pub struct Farm {
+    pub vcid: u8,
+    /// V(R)
+    pub vr: u8,
+    /// Sliding window width W, split evenly into positive and negative
+    pub window: u8,
+    lockout: bool,
+    wait: bool,
+    retransmit: bool,
+    buffer_full: bool,
+    farm_b_counter: u8,
+}
+
+impl Farm {
+    pub fn new(vcid: u8, window: u8) -> Self {
+        Self {
+            vcid,
+            vr: 0,
+            window,
+            lockout: false,
+            wait: false,
+            retransmit: false,
+            buffer_full: false,
+            farm_b_counter: 0,
+        }
+    }
+
+    /// Back-pressure from the frame consumer; releasing the buffer clears Wait
+    pub fn set_buffer_full(&mut self, full: bool) {
+        self.buffer_full = full;
+        if !full {
+            self.wait = false;
+        }
+    }
+
+    pub fn receive(&mut self, frame: &TcFrame) -> FarmResult {
+        if frame.vcid != self.vcid {
+            return FarmResult::Discarded;
+        }
+        match frame.frame_type {
+            TcFrameType::Ad => self.receive_ad(frame),
+            TcFrameType::Bd => {
+                self.farm_b_counter = self.farm_b_counter.wrapping_add(1);
+                FarmResult::Accepted(frame.data.clone())
+            }
+            TcFrameType::Bc => self.receive_bc(&frame.data),
+        }
+    }
+
+    fn receive_ad(&mut self, frame: &TcFrame) -> FarmResult {
+        if self.lockout {
+            return FarmResult::Discarded;
+        }
+        let half = self.window / 2;
+        let ahead = frame.sequence.wrapping_sub(self.vr);
+        if ahead == 0 {
+            if self.buffer_full {
+                self.wait = true;
+                self.retransmit = true;
+                return FarmResult::Discarded;
+            }
+            self.vr = self.vr.wrapping_add(1);
+            self.retransmit = false;
+            FarmResult::Accepted(frame.data.clone())
+        } else if ahead < half {
+            // A frame went missing in front of this one
+            self.retransmit = true;
+            FarmResult::Discarded
+        } else if self.vr.wrapping_sub(frame.sequence) <= half {
+            // Already accepted, a retransmission
+            FarmResult::Discarded
+        } else {
+            self.lockout = true;
+            FarmResult::Discarded
+        }
+    }
+
+    fn receive_bc(&mut self, data: &[u8]) -> FarmResult {
+        match data {
+            [0x00] => {
+                self.lockout = false;
+                self.wait = false;
+                self.retransmit = false;
+            }
+            [0x82, 0x00, vr] => {
+                if !self.lockout {
+                    self.vr = *vr;
+                    self.wait = false;
+                    self.retransmit = false;
+                }
+            }
+            _ => return FarmResult::Discarded,
+        }
+        self.farm_b_counter = self.farm_b_counter.wrapping_add(1);
+        FarmResult::Control
+    }
+
+    pub fn clcw(&self) -> Clcw {
+        Clcw {
+            vcid: self.vcid,
+            lockout: self.lockout,
+            wait: self.wait,
+            retransmit: self.retransmit,
+            farm_b_counter: self.farm_b_counter & 0x03,
+            report_value: self.vr,
+            ..Clcw::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FopState {
+    /// Not initialised, AD service unavailable
+    Initial,
+    Active,
+    RetransmitWithoutWait,
+    RetransmitWithWait,
+    /// Lockout, bad N(R) or transmission limit; needs `initialise` again
+    Alert,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FopStats {
+    pub frames_sent: u64,
+    pub retransmissions: u64,
+    pub acknowledged: u64,
+    pub alerts: u64,
+}
+
+/// FOP-1 for one virtual channel
+#[derive(Debug)]
+pub struct Fop {
+    pub scid: u16,
+    pub vcid: u8,
+    pub state: FopState,
+    /// Sliding window width K, frames in flight
+    pub window: usize,
+    /// Transmissions of the same window before giving up
+    pub transmission_limit: u32,
+    pub stats: FopStats,
+    /// V(S)
+    vs: u8,
+    /// NN(R), the oldest unacknowledged N(S)
+    nnr: u8,
+    transmissions: u32,
+    sent: VecDeque<TcFrame>,
+    waiting: VecDeque<Vec<u8>>,
+}
+
+impl Fop {
+    pub fn new(scid: u16, vcid: u8, window: usize, transmission_limit: u32) -> Self {
+        Self {
+            scid,
+            vcid,
+            state: FopState::Initial,
+            window,
+            transmission_limit,
+            stats: FopStats::default(),
+            vs: 0,
+            nnr: 0,
+            transmissions: 0,
+            sent: VecDeque::new(),
+            waiting: VecDeque::new(),
+        }
+    }
+
+    /// Unacknowledged frames plus data still queued
+    pub fn outstanding(&self) -> usize {
+        self.sent.len() + self.waiting.len()
+    }
+
+    /// Unlock the FARM if needed and align it with V(S) = `vr`; frames still
+    /// in flight are queued again ahead of new data
+    pub fn initialise(&mut self, vr: u8, unlock: bool) -> Vec<TcFrame> {
+        self.vs = vr;
+        self.nnr = vr;
+        self.transmissions = 0;
+        while let Some(frame) = self.sent.pop_back() {
+            self.waiting.push_front(frame.data);
+        }
+        self.state = FopState::Active;
+
+        let mut out = Vec::new();
+        if unlock {
+            out.push(TcFrame::new(
+                TcFrameType::Bc,
+                self.scid,
+                self.vcid,
+                0,
+                &UNLOCK,
+            ));
+        }
+        out.push(TcFrame::new(
+            TcFrameType::Bc,
+            self.scid,
+            self.vcid,
+            0,
+            &set_vr(vr),
+        ));
+        out.extend(self.release());
+        out
+    }
+
+    /// Queue `data` for sequence-controlled delivery
+    pub fn send(&mut self, data: &[u8]) -> Vec<TcFrame> {
+        self.waiting.push_back(data.to_vec());
+        self.release()
+    }
+
+    pub fn on_clcw(&mut self, clcw: &Clcw) -> Vec<TcFrame> {
+        if clcw.vcid != self.vcid || matches!(self.state, FopState::Initial | FopState::Alert) {
+            return Vec::new();
+        }
+        if clcw.lockout {
+            self.alert();
+            return Vec::new();
+        }
+
+        let acked = clcw.report_value.wrapping_sub(self.nnr) as usize;
+        if acked > self.sent.len() {
+            // N(R) outside the frames actually in flight
+            self.alert();
+            return Vec::new();
+        }
+        self.sent.drain(..acked);
+        self.nnr = clcw.report_value;
+        self.stats.acknowledged += acked as u64;
+        if acked > 0 {
+            self.transmissions = 0;
+        }
+
+        if !clcw.retransmit {
+            self.state = FopState::Active;
+            return self.release();
+        }
+        if clcw.wait {
+            self.state = FopState::RetransmitWithWait;
+            return Vec::new();
+        }
+        // Retransmit once per report that shows progress; otherwise the
+        // timer drives it
+        if self.state != FopState::RetransmitWithoutWait || acked > 0 {
+            self.state = FopState::RetransmitWithoutWait;
+            return self.retransmit();
+        }
+        Vec::new()
+    }
+
+    pub fn timer_expired(&mut self) -> Vec<TcFrame> {
+        match self.state {
+            FopState::Active | FopState::RetransmitWithoutWait if !self.sent.is_empty() => {
+                self.retransmit()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn release(&mut self) -> Vec<TcFrame> {
+        let mut out = Vec::new();
+        if self.state != FopState::Active {
+            return out;
+        }
+        while self.sent.len() < self.window {
+            let Some(data) = self.waiting.pop_front() else {
+                break;
+            };
+            let frame = TcFrame::new(TcFrameType::Ad, self.scid, self.vcid, self.vs, &data);
+            self.vs = self.vs.wrapping_add(1);
+            self.sent.push_back(frame.clone());
+            self.stats.frames_sent += 1;
+            out.push(frame);
+        }
+        out
+    }
+
+    fn retransmit(&mut self) -> Vec<TcFrame> {
+        self.transmissions += 1;
+        if self.transmissions > self.transmission_limit {
+            self.alert();
+            return Vec::new();
+        }
+        self.stats.retransmissions += self.sent.len() as u64;
+        self.sent.iter().cloned().collect()
+    }
+
+    fn alert(&mut self) {
+        self.state = FopState::Alert;
+        self.stats.alerts += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deliver(farm: &mut Farm, frames: &[TcFrame], drop: Option<u8>) -> Vec<Vec<u8>> {
+        frames
+            .iter()
+            .filter(|f| f.frame_type != TcFrameType::Ad || Some(f.sequence) != drop)
+            .filter_map(|f| match farm.receive(f) {
+                FarmResult::Accepted(data) => Some(data),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn clcw_round_trip() {
+        let clcw = Clcw {
+            vcid: 5,
+            lockout: true,
+            retransmit: true,
+            farm_b_counter: 2,
+            report_value: 200,
+            ..Clcw::default()
+        };
+        assert_eq!(clcw.to_bytes(), [0x01, 0x14, 0x2C, 200]);
+        assert_eq!(Clcw::parse(clcw.to_bytes()).unwrap(), clcw);
+        assert!(Clcw::parse([0x80, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn lost_frame_is_retransmitted_in_order() {
+        let mut fop = Fop::new(0x042, 1, 4, 3);
+        let mut farm = Farm::new(1, 10);
+        let mut frames = fop.initialise(250, false);
+        for i in 0..6u8 {
+            frames.extend(fop.send(&[i]));
+        }
+        // Window of 4 in flight; the second AD frame is lost
+        assert_eq!(fop.outstanding(), 6);
+        let mut received = deliver(&mut farm, &frames, Some(251));
+        assert_eq!(received, vec![vec![0]]);
+        assert!(farm.clcw().retransmit);
+
+        let mut rounds = 0;
+        while fop.outstanding() > 0 {
+            let frames = fop.on_clcw(&farm.clcw());
+            received.extend(deliver(&mut farm, &frames, None));
+            rounds += 1;
+            assert!(rounds < 10);
+        }
+        assert_eq!(received, (0..6u8).map(|i| vec![i]).collect::<Vec<_>>());
+        assert_eq!(farm.vr, 0);
+        assert_eq!(fop.state, FopState::Active);
+        assert_eq!(fop.stats.acknowledged, 6);
+    }
+
+    #[test]
+    fn lockout_and_transmission_limit_alert() {
+        let mut farm = Farm::new(1, 10);
+        let far = TcFrame::new(TcFrameType::Ad, 0x042, 1, 100, b"x");
+        assert_eq!(farm.receive(&far), FarmResult::Discarded);
+        assert!(farm.clcw().lockout);
+
+        let mut fop = Fop::new(0x042, 1, 4, 2);
+        fop.initialise(0, false);
+        fop.send(b"cmd");
+        assert!(fop.on_clcw(&farm.clcw()).is_empty());
+        assert_eq!(fop.state, FopState::Alert);
+
+        // Unlock, resynchronise and deliver
+        let frames = fop.initialise(0, true);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(deliver(&mut farm, &frames, None), vec![b"cmd".to_vec()]);
+        assert_eq!(farm.clcw().farm_b_counter, 2);
+
+        // No CLCW ever comes back: two retransmissions, then alert
+        fop.send(b"lost");
+        assert_eq!(fop.timer_expired().len(), 2);
+        assert_eq!(fop.timer_expired().len(), 2);
+        assert!(fop.timer_expired().is_empty());
+        assert_eq!(fop.state, FopState::Alert);
+        assert_eq!(fop.stats.alerts, 2);
+    }
+}
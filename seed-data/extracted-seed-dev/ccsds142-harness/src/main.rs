@@ -1,50 +1,400 @@
-//! CCSDS 142.0 Framing/Deframing Harness (Scaffold)
-//! NOTE: Coding (LDPC/RS) intentionally not implemented; wire here after FPGA/HDL selection.
+//! CCSDS 142.0 Framing/Deframing Harness
+//! TM transfer frames (`tm`) carried over the CCSDS 131.0 synchronization
+//! sublayer (`sync`): ASM, pseudo-randomizer and a slip-tolerant deframer,
+//! with optional interleaved Reed-Solomon (255,223) coding (`rs`) and,
+//! behind the `ldpc` feature, the AR4JA LDPC family (`ldpc`). Space
+//! packets (`spp`) ride in the frame data fields, or in AOS frames (`aos`)
+//! multiplexed across virtual channels. The command path uses TC
+//! frames in BCH-coded CLTUs (`tc`) under COP-1 (`cop1`), with the CLCW
+//! returned in the TM OCF.
+
+mod aos;
+mod cop1;
+#[cfg(feature = "ldpc")]
+mod ldpc;
+mod rs;
+mod spp;
+mod sync;
+mod tc;
+mod tm;
 
 use anyhow::*;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use aos::{AosFrame, Schedule, VcDemux, VcMux};
+use cop1::{Clcw, Farm, FarmResult, Fop, FopState};
+use spp::{ApidRouter, PacketExtractor, PacketMux, SequenceCounters, SpacePacket};
+use sync::{Deframer, Framer, SyncConfig};
+use tc::{CltuStats, TcFrame};
+use tm::{Frame, FrameFormat};
+
+const FRAMES: usize = 200;
+const BIT_ERROR_RATE: f64 = 2e-4;
+
+fn run(label: &str, format: FrameFormat, config: SyncConfig) -> Result<()> {
+    let mut rng = rand::thread_rng();
+    let mut framer = Framer::new(format, config)?;
+    let mut stream: Vec<u8> = (0..13).map(|_| rng.gen()).collect();
+    for _ in 0..FRAMES {
+        let payload: Vec<u8> = (0..format.data_field_len()).map(|_| rng.gen()).collect();
+        stream.extend(framer.cadu(0x042, 7, &payload)?);
+    }
+
+    let mut bit_errors = 0;
+    for byte in stream.iter_mut() {
+        for bit in 0..8 {
+            if rng.gen_bool(BIT_ERROR_RATE) {
+                *byte ^= 1 << bit;
+                bit_errors += 1;
+            }
+        }
+    }
+    // Drop one bit mid-stream: everything after it shifts left
+    let slip = stream.len() / 2;
+    for i in slip..stream.len() - 1 {
+        stream[i] = (stream[i] << 1) | (stream[i + 1] >> 7);
+    }
 
-#[derive(Debug, Clone)]
-pub struct Frame {
-    pub version: u8,
-    pub scid: u16,
-    pub vcid: u8,
-    pub seq: u32,
-    pub payload: Vec<u8>,
+    let mut deframer = Deframer::new(format, config)?;
+    let frames: Vec<_> = stream.chunks(4096).flat_map(|c| deframer.push(c)).collect();
+    let first = frames.first().context("no frames recovered")?;
+    println!("[{}] frame: version={} scid={:#x} vcid={} payload_len={}",
+             label, first.version, first.scid, first.vcid, first.payload.len());
+    let stats = &deframer.stats;
+    println!("[{}] sent={} recovered={} bad={} injected_bit_errors={} asm_bit_errors={}",
+             label, FRAMES, stats.frames, stats.bad_frames, bit_errors, stats.asm_bit_errors);
+    println!("[{}] bit_slips={} sync_losses={} mc_gaps={} locked={}",
+             label, stats.bit_slips, stats.sync_losses, stats.mc_gaps, deframer.locked());
+    if let Some(depth) = config.rs_interleave {
+        let rs = &stats.rs;
+        println!("[{}] rs I={} codewords={} corrected={} symbols={} uncorrectable={} failed_blocks={}",
+                 label, depth, rs.codewords, rs.corrected_codewords, rs.corrected_symbols,
+                 rs.uncorrectable_codewords, rs.failed_codeblocks);
+    }
+    Ok(())
 }
 
-impl Frame {
-    pub fn build(scid: u16, vcid: u8, seq: u32, payload: &[u8]) -> Self {
-        Self { version: 1, scid, vcid, seq, payload: payload.to_vec() }
+const APID_STATION_TELEMETRY: u16 = 0x101;
+const APID_BULK: u16 = 0x1F0;
+
+/// Ground station telemetry snapshot, as the gateway records it
+#[derive(Debug, Serialize, Deserialize)]
+struct StationTelemetry {
+    station_id: String,
+    timestamp_unix_ms: i64,
+    status: String,
+    weather_score: f64,
+    cloud_cover_pct: Option<f64>,
+}
+
+/// Station telemetry and a segmented bulk dump as space packets over VC 1
+fn packet_demo() -> Result<()> {
+    let format = FrameFormat::default();
+    let config = SyncConfig { rs_interleave: Some(5), ..SyncConfig::default() };
+    let mut counters = SequenceCounters::default();
+    let mut mux = PacketMux::default();
+
+    for i in 0..20 {
+        let telemetry = StationTelemetry {
+            station_id: format!("GS-{:03}", i % 4 + 1),
+            timestamp_unix_ms: 1_767_225_600_000 + i * 1000,
+            status: "operational".into(),
+            weather_score: 0.9 - 0.01 * i as f64,
+            cloud_cover_pct: (i % 3 == 0).then_some(12.5),
+        };
+        let count = counters.next(APID_STATION_TELEMETRY);
+        mux.push(&SpacePacket::from_json(APID_STATION_TELEMETRY, count, &telemetry)?)?;
     }
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut v = Vec::new();
-        v.push(self.version);
-        v.extend(self.scid.to_be_bytes());
-        v.push(self.vcid);
-        v.extend(self.seq.to_be_bytes());
-        v.extend(&self.payload);
-        // TODO: add sync marker, scrambler, FEC (per CCSDS 142.0)
-        v
+    let bulk: Vec<u8> = (0..5000u32).map(|i| i as u8).collect();
+    for packet in spp::segment(APID_BULK, &mut counters, &bulk, 1024) {
+        mux.push(&packet)?;
     }
-    pub fn parse(raw: &[u8]) -> Result<Self> {
-        if raw.len() < 1+2+1+4 { bail!("frame too short"); }
-        let version = raw[0];
-        let scid = u16::from_be_bytes([raw[1], raw[2]]);
-        let vcid = raw[3];
-        let seq = u32::from_be_bytes([raw[4],raw[5],raw[6],raw[7]]);
-        let payload = raw[8..].to_vec();
-        Ok(Self{version, scid, vcid, seq, payload})
+    let queued = mux.pending();
+
+    let mut framer = Framer::new(format, config)?;
+    let mut stream = Vec::new();
+    while mux.pending() > 0 {
+        stream.extend(framer.frame_cadu(mux.frame(0x042, 1, format.data_field_len())?)?);
+    }
+    stream.extend([0; 2]);
+
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let bulk_len = Rc::new(RefCell::new(0));
+    let mut router = ApidRouter::default();
+    let sink = received.clone();
+    router.on(APID_STATION_TELEMETRY, move |_, data| {
+        sink.borrow_mut().extend(serde_json::from_slice::<StationTelemetry>(data).ok());
+    });
+    let bulk_sink = bulk_len.clone();
+    router.on(APID_BULK, move |_, data| *bulk_sink.borrow_mut() += data.len());
+
+    let mut deframer = Deframer::new(format, config)?;
+    let mut extractor = PacketExtractor::default();
+    for frame in deframer.push(&stream) {
+        for packet in extractor.push_frame(&frame) {
+            router.route(packet);
+        }
     }
+
+    let telemetry = received.borrow();
+    let last = telemetry.last().context("no telemetry packets recovered")?;
+    println!("[spp] queued_bytes={} frames={} packets={} idle={} telemetry={} bulk_bytes={} dropped_segments={}",
+             queued, deframer.stats.frames, extractor.stats.packets, extractor.stats.idle_packets,
+             telemetry.len(), bulk_len.borrow(), router.stats.dropped_segments);
+    println!("[spp] last: station={} status={} weather={:.2} cloud={:?} t={}",
+             last.station_id, last.status, last.weather_score, last.cloud_cover_pct, last.timestamp_unix_ms);
+    println!("[spp] frame_gaps={} dropped_partials={} unrouted={} delivered={}",
+             extractor.stats.frame_gaps, extractor.stats.dropped_partials,
+             router.stats.unrouted, router.stats.delivered);
+    Ok(())
+}
+
+const VC_SIDEBAND: u8 = 0;
+const VC_TELEMETRY: u8 = 1;
+const VC_BULK: u8 = 2;
+const APID_SIDEBAND: u16 = 0x120;
+
+/// CTAS sideband message, as the gateway queues it
+#[derive(Debug, Serialize, Deserialize)]
+struct SidebandMessage {
+    message_id: String,
+    channel: String,
+    priority: String,
+    sequence: u64,
+    sent_unix_ms: i64,
+    payload: serde_json::Value,
+}
+
+/// Sideband, telemetry and bulk traffic on their own AOS virtual channels:
+/// sideband and telemetry messages arrive while a bulk dump is streaming,
+/// and the sideband latency is counted in frames from queueing to sent
+fn vc_demo(label: &str, schedule: Schedule) -> Result<()> {
+    const SIDEBAND_MESSAGES: u64 = 5;
+    const TELEMETRY_PACKETS: i64 = 10;
+    let format = FrameFormat::default();
+    let config = SyncConfig { rs_interleave: Some(5), ..SyncConfig::default() };
+    let mut mux = VcMux::new(0x42, format, schedule)?;
+    let mut demux = VcDemux::default();
+    for (vcid, priority) in [(VC_SIDEBAND, 2), (VC_TELEMETRY, 1), (VC_BULK, 0)] {
+        mux.add_channel(vcid, priority)?;
+        demux.add_channel(vcid);
+    }
+
+    let mut counters = SequenceCounters::default();
+    let bulk: Vec<u8> = (0..20_000u32).map(|i| i as u8).collect();
+    for packet in spp::segment(APID_BULK, &mut counters, &bulk, 1024) {
+        mux.push(VC_BULK, &packet)?;
+    }
+
+    let framer = Framer::new(format, config)?;
+    let mut stream = Vec::new();
+    let (mut sequence, mut sent_telemetry) = (0, 0);
+    let mut queued_at = None;
+    let mut latencies = Vec::new();
+    while mux.pending(None) > 0 {
+        let frame_no = mux.stats.frames;
+        if frame_no % 4 == 0 && sequence < SIDEBAND_MESSAGES {
+            sequence += 1;
+            let message = SidebandMessage {
+                message_id: format!("sb-handover-{}", sequence),
+                channel: "handover".into(),
+                priority: "critical".into(),
+                sequence,
+                sent_unix_ms: 1_767_225_600_000 + frame_no as i64,
+                payload: serde_json::json!({ "from": "GS-001", "to": "GS-002" }),
+            };
+            let count = counters.next(APID_SIDEBAND);
+            mux.push(VC_SIDEBAND, &SpacePacket::from_json(APID_SIDEBAND, count, &message)?)?;
+            queued_at.get_or_insert(frame_no);
+        }
+        if frame_no % 2 == 1 && sent_telemetry < TELEMETRY_PACKETS {
+            let telemetry = StationTelemetry {
+                station_id: format!("GS-{:03}", sent_telemetry % 4 + 1),
+                timestamp_unix_ms: 1_767_225_600_000 + sent_telemetry * 1000,
+                status: "operational".into(),
+                weather_score: 0.8,
+                cloud_cover_pct: None,
+            };
+            let count = counters.next(APID_STATION_TELEMETRY);
+            mux.push(VC_TELEMETRY, &SpacePacket::from_json(APID_STATION_TELEMETRY, count, &telemetry)?)?;
+            sent_telemetry += 1;
+        }
+
+        stream.extend(framer.wrap(mux.next_frame()?.to_bytes(&format)?)?);
+        if mux.pending(Some(VC_SIDEBAND)) == 0 {
+            latencies.extend(queued_at.take().map(|t| mux.stats.frames - t));
+        }
+    }
+    // Idle frames keep the link busy and flush the deframer
+    for _ in 0..2 {
+        stream.extend(framer.wrap(mux.next_frame()?.to_bytes(&format)?)?);
+    }
+
+    let mut deframer = Deframer::new(format, config)?;
+    let mut sideband = Vec::new();
+    let mut telemetry = 0;
+    let mut bulk_bytes = 0;
+    for raw in deframer.push_raw(&stream) {
+        let Some(frame) = AosFrame::parse(&raw, &format).ok() else { continue };
+        for packet in demux.push_frame(&frame) {
+            match packet.apid {
+                APID_SIDEBAND => sideband.extend(serde_json::from_slice::<SidebandMessage>(&packet.data).ok()),
+                APID_STATION_TELEMETRY => telemetry += 1,
+                _ => bulk_bytes += packet.data.len(),
+            }
+        }
+    }
+
+    let last = sideband.last().context("no sideband messages recovered")?;
+    println!("[aos:{}] frames={} idle={} vc_frames={:?} sideband_latency_frames={:?}",
+             label, mux.stats.frames, mux.stats.idle_frames, mux.stats.vc_frames, latencies);
+    println!("[aos:{}] received frames={} idle={} unknown_vc={} sideband={} telemetry={} bulk_bytes={} bulk_gaps={:?}",
+             label, demux.stats.frames, demux.stats.idle_frames, demux.stats.unknown_vc, sideband.len(),
+             telemetry, bulk_bytes, demux.channel_stats(VC_BULK).map(|s| s.frame_gaps));
+    println!("[aos:{}] last sideband: {} channel={} priority={} seq={} t={} payload={}",
+             label, last.message_id, last.channel, last.priority, last.sequence, last.sent_unix_ms, last.payload);
+    Ok(())
+}
+
+const COMMANDS: usize = 24;
+const TC_BIT_ERROR_RATE: f64 = 5e-4;
+const CLTU_FADE_RATE: f64 = 0.1;
+
+/// Gateway commands through a station uplink to the spacecraft: FOP-1 sends
+/// AD frames in CLTUs over a noisy, fading uplink to FARM-1, and each round
+/// the CLCW comes back in the OCF of two downlink TM frames
+fn command_demo() -> Result<()> {
+    let mut rng = rand::thread_rng();
+    let format = FrameFormat { ocf: true, ..FrameFormat::default() };
+    let config = SyncConfig::default();
+    let mut framer = Framer::new(format, config)?;
+    let mut deframer = Deframer::new(format, config)?;
+    let mut fop = Fop::new(0x042, 0, 8, 5);
+    let mut farm = Farm::new(0, 16);
+    let mut cltu_stats = CltuStats::default();
+
+    let commands: Vec<String> = (0..COMMANDS)
+        .map(|i| format!("SLEW GS-{:03} AZ={} EL=45", i % 4 + 1, i * 15))
+        .collect();
+    let mut uplink = fop.initialise(0, false);
+    for command in &commands {
+        uplink.extend(fop.send(command.as_bytes()));
+    }
+
+    let mut executed = Vec::new();
+    let mut faded = 0;
+    let mut rounds = 0;
+    while fop.outstanding() > 0 && fop.state != FopState::Alert && rounds < 50 {
+        rounds += 1;
+        let mut stream = Vec::new();
+        for frame in &uplink {
+            let mut cltu = tc::cltu_encode(&frame.to_bytes(true)?);
+            for byte in cltu.iter_mut() {
+                for bit in 0..8 {
+                    if rng.gen_bool(TC_BIT_ERROR_RATE) {
+                        *byte ^= 1 << bit;
+                    }
+                }
+            }
+            if rng.gen_bool(CLTU_FADE_RATE) {
+                faded += 1;
+                continue;
+            }
+            stream.extend(cltu);
+            stream.extend([tc::FILL; 2]);
+        }
+
+        // The on-board command buffer backs up for one round
+        farm.set_buffer_full(rounds == 3);
+        for data in tc::cltu_decode(&stream, &mut cltu_stats) {
+            let Some(frame) = TcFrame::parse(&data, true).ok() else { continue };
+            if let FarmResult::Accepted(command) = farm.receive(&frame) {
+                executed.push(String::from_utf8_lossy(&command).into_owned());
+            }
+        }
+
+        let mut downlink = Vec::new();
+        for _ in 0..2 {
+            let mut frame = Frame::build(0x042, 0, 0, &[]);
+            frame.ocf = Some(farm.clcw().to_bytes());
+            downlink.extend(framer.frame_cadu(frame)?);
+        }
+        uplink = match deframer.push(&downlink).iter().rev().find_map(|f| f.ocf) {
+            Some(raw) => fop.on_clcw(&Clcw::parse(raw)?),
+            None => Vec::new(),
+        };
+        if uplink.is_empty() {
+            uplink = fop.timer_expired();
+        }
+    }
+
+    let stats = &fop.stats;
+    println!("[tc] commands={} executed={} in_order={} rounds={} fop_state={:?}",
+             COMMANDS, executed.len(), executed == commands, rounds, fop.state);
+    println!("[tc] cltus={} aborted={} faded={} codeblocks={} corrected_codeblocks={}",
+             cltu_stats.cltus, cltu_stats.aborted, faded, cltu_stats.codeblocks, cltu_stats.corrected_codeblocks);
+    println!("[tc] frames_sent={} retransmissions={} acknowledged={} alerts={} clcw={:?}",
+             stats.frames_sent, stats.retransmissions, stats.acknowledged, stats.alerts, farm.clcw());
+    Ok(())
+}
+
+/// Frame error rate over BPSK/AWGN, per rate and Eb/N0, for link budgets
+#[cfg(feature = "ldpc")]
+fn ldpc_sweep() -> Result<()> {
+    use ldpc::{Ar4ja, CodeRate, LdpcStats};
+
+    const CODEWORDS: usize = 40;
+    const MAX_ITERATIONS: usize = 50;
+    let mut rng = rand::thread_rng();
+
+    for rate in [CodeRate::R1_2, CodeRate::R2_3, CodeRate::R4_5] {
+        let code = Ar4ja::ccsds(rate, 1024)?;
+        let r = code.rate().value();
+        println!("[ldpc] rate={:.3} k={} n={} systematic={}",
+                 r, code.k(), code.n_transmitted(), code.is_systematic());
+        for ebn0_db in [1.0, 2.0, 3.0, 4.0] {
+            let sigma = (1.0 / (2.0 * r * 10f64.powf(ebn0_db / 10.0))).sqrt();
+            let mut stats = LdpcStats::default();
+            let mut errors = 0;
+            for _ in 0..CODEWORDS {
+                let info: Vec<u8> = (0..code.k() / 8).map(|_| rng.gen()).collect();
+                let llr: Vec<f32> = code
+                    .encode(&info)?
+                    .iter()
+                    .flat_map(|b| (0..8).rev().map(move |i| (b >> i) & 1))
+                    .map(|bit| {
+                        // Box-Muller noise on ±1 BPSK
+                        let (u1, u2): (f64, f64) = (rng.gen::<f64>().max(1e-12), rng.gen());
+                        let noise = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                        let y = 1.0 - 2.0 * bit as f64 + sigma * noise;
+                        (2.0 * y / (sigma * sigma)) as f32
+                    })
+                    .collect();
+                if code.decode(&llr, MAX_ITERATIONS, &mut stats).as_deref() != Some(&info[..]) {
+                    errors += 1;
+                }
+            }
+            let fer = errors as f64 / CODEWORDS as f64;
+            println!("[ldpc]   Eb/N0={:.1} dB FER={:.3} throughput={:.3} avg_iter={:.1}",
+                     ebn0_db, fer, r * (1.0 - fer), stats.iterations as f64 / stats.codewords as f64);
+        }
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
-    // Demo
-    let payload: Vec<u8> = (0..32).map(|_| rand::thread_rng().gen()).collect();
-    let f = Frame::build(0x0420, 7, 1, &payload);
-    let raw = f.to_bytes();
-    let back = Frame::parse(&raw)?;
-    println!("frame: version={} scid={:#x} vcid={} seq={} payload_len={}",
-             back.version, back.scid, back.vcid, back.seq, back.payload.len());
+    // Demo: frame, corrupt, slip a bit, deframe - uncoded vs RS I=5
+    let format = FrameFormat::default();
+    run("uncoded", format, SyncConfig::default())?;
+    run("rs", format, SyncConfig { rs_interleave: Some(5), ..SyncConfig::default() })?;
+    packet_demo()?;
+    vc_demo("priority", Schedule::Priority)?;
+    vc_demo("round-robin", Schedule::RoundRobin)?;
+    command_demo()?;
+    #[cfg(feature = "ldpc")]
+    ldpc_sweep()?;
     Ok(())
 }
@@ -0,0 +1,418 @@
+//! Reed-Solomon (255,223) per CCSDS 131.0 §4
+//! GF(2^8) over x^8 + x^7 + x^2 + x + 1, generator roots β^(112..=143)
+//! with β = α^11, symbols on the wire in Berlekamp's dual basis. Codewords
+//! are symbol-interleaved to depth I (symbol i of codeword j at i·I + j),
+//! and frames shorter than I·223 bytes use virtual fill.
+
+use anyhow::{bail, Result};
+
+pub const N: usize = 255;
+pub const K: usize = 223;
+pub const PARITY: usize = N - K;
+/// Symbol errors correctable per codeword
+pub const T: usize = PARITY / 2;
+
+/// Interleaving depths allowed by CCSDS 131.0
+pub const INTERLEAVE_DEPTHS: [usize; 6] = [1, 2, 3, 4, 5, 8];
+
+const FIELD_POLY: u16 = 0x187;
+const FIRST_ROOT: usize = 112;
+const ROOT_STEP: usize = 11;
+/// Conventional-to-dual basis transform rows (CCSDS 131.0 Annex F)
+const TAL: [u8; 8] = [0x8d, 0xef, 0xec, 0x86, 0xfa, 0x99, 0xaf, 0x7b];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RsStats {
+    pub codewords: u64,
+    /// Codewords that needed (and got) correction
+    pub corrected_codewords: u64,
+    pub corrected_symbols: u64,
+    pub uncorrectable_codewords: u64,
+    /// Codeblocks dropped because at least one codeword was uncorrectable
+    pub failed_codeblocks: u64,
+}
+
+pub struct ReedSolomon {
+    interleave: usize,
+    exp: [u8; 2 * N],
+    log: [usize; 256],
+    /// Generator coefficients, lowest degree first, monic x^32 term omitted
+    generator: [u8; PARITY],
+    to_dual: [u8; 256],
+    from_dual: [u8; 256],
+}
+
+impl ReedSolomon {
+    pub fn new(interleave: usize) -> Result<Self> {
+        if !INTERLEAVE_DEPTHS.contains(&interleave) {
+            bail!(
+                "interleave depth {} not one of {:?}",
+                interleave,
+                INTERLEAVE_DEPTHS
+            );
+        }
+
+        let mut exp = [0u8; 2 * N];
+        let mut log = [0usize; 256];
+        let mut x = 1u16;
+        for (i, e) in exp.iter_mut().enumerate().take(N) {
+            *e = x as u8;
+            log[x as usize] = i;
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= FIELD_POLY;
+            }
+        }
+        for i in N..2 * N {
+            exp[i] = exp[i - N];
+        }
+
+        let mut to_dual = [0u8; 256];
+        let mut from_dual = [0u8; 256];
+        for (i, dual) in to_dual.iter_mut().enumerate() {
+            let mut t = 0u8;
+            for (k, row) in TAL.iter().rev().enumerate() {
+                if i & (1 << k) != 0 {
+                    t ^= row;
+                }
+            }
+            *dual = t;
+            from_dual[t as usize] = i as u8;
+        }
+
+        let mut rs = Self {
+            interleave,
+            exp,
+            log,
+            generator: [0; PARITY],
+            to_dual,
+            from_dual,
+        };
+
+        // g(x) = Π (x + β^(112+i)), built lowest degree first
+        let mut g = vec![1u8];
+        for i in 0..PARITY {
+            let root = rs.beta_pow(FIRST_ROOT + i);
+            let mut next = vec![0u8; g.len() + 1];
+            for (j, &c) in g.iter().enumerate() {
+                next[j + 1] ^= c;
+                next[j] ^= rs.mul(c, root);
+            }
+            g = next;
+        }
+        rs.generator.copy_from_slice(&g[..PARITY]);
+        Ok(rs)
+    }
+
+    pub fn parity_len(&self) -> usize {
+        PARITY * self.interleave
+    }
+
+    /// Codeblock length (data + parity) for `data_len` bytes of frame
+    pub fn codeblock_len(&self, data_len: usize) -> usize {
+        data_len + self.parity_len()
+    }
+
+    /// Frames must split evenly across the codewords and fit with fill
+    pub fn check_len(&self, data_len: usize) -> Result<()> {
+        if data_len == 0 || !data_len.is_multiple_of(self.interleave) || data_len > K * self.interleave {
+            bail!(
+                "{} bytes does not fit RS(255,223) at depth {} (multiple of {} up to {})",
+                data_len,
+                self.interleave,
+                self.interleave,
+                K * self.interleave
+            );
+        }
+        Ok(())
+    }
+
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] + self.log[b as usize]]
+        }
+    }
+
+    fn div(&self, a: u8, b: u8) -> u8 {
+        if a == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] + N - self.log[b as usize]]
+        }
+    }
+
+    fn beta_pow(&self, e: usize) -> u8 {
+        self.exp[(ROOT_STEP * e) % N]
+    }
+
+    /// Parity for one codeword (conventional basis), highest degree first
+    fn parity(&self, data: &[u8]) -> [u8; PARITY] {
+        let mut reg = [0u8; PARITY];
+        for &d in data {
+            let feedback = d ^ reg[0];
+            reg.copy_within(1.., 0);
+            reg[PARITY - 1] = 0;
+            if feedback != 0 {
+                for (i, r) in reg.iter_mut().enumerate() {
+                    *r ^= self.mul(feedback, self.generator[PARITY - 1 - i]);
+                }
+            }
+        }
+        reg
+    }
+
+    fn syndromes(&self, codeword: &[u8; N]) -> [u8; PARITY] {
+        let mut s = [0u8; PARITY];
+        for (i, si) in s.iter_mut().enumerate() {
+            let x = self.beta_pow(FIRST_ROOT + i);
+            *si = codeword.iter().fold(0, |acc, &c| self.mul(acc, x) ^ c);
+        }
+        s
+    }
+
+    /// Correct one full-length codeword in place, returning the symbols
+    /// fixed, or `None` if it is beyond correction
+    fn correct(&self, codeword: &mut [u8; N], fill: usize) -> Option<usize> {
+        let s = self.syndromes(codeword);
+        if s.iter().all(|&x| x == 0) {
+            return Some(0);
+        }
+
+        // Berlekamp-Massey: error locator Λ(x), lowest degree first
+        let mut lambda = vec![1u8];
+        let mut prev = vec![1u8];
+        let (mut l, mut m, mut prev_d) = (0usize, 1usize, 1u8);
+        for n in 0..PARITY {
+            let d = lambda
+                .iter()
+                .enumerate()
+                .skip(1)
+                .take(l)
+                .fold(s[n], |acc, (i, &c)| acc ^ self.mul(c, s[n - i]));
+            if d == 0 {
+                m += 1;
+                continue;
+            }
+            let scale = self.div(d, prev_d);
+            let mut next = lambda.clone();
+            next.resize(next.len().max(prev.len() + m), 0);
+            for (i, &p) in prev.iter().enumerate() {
+                next[i + m] ^= self.mul(scale, p);
+            }
+            if 2 * l <= n {
+                prev = std::mem::replace(&mut lambda, next);
+                l = n + 1 - l;
+                prev_d = d;
+                m = 1;
+            } else {
+                lambda = next;
+                m += 1;
+            }
+        }
+        while lambda.len() > 1 && lambda[lambda.len() - 1] == 0 {
+            lambda.pop();
+        }
+        if l > T || lambda.len() - 1 != l {
+            return None;
+        }
+
+        // Ω(x) = S(x)Λ(x) mod x^32
+        let mut omega = [0u8; PARITY];
+        for (i, &li) in lambda.iter().enumerate() {
+            for j in 0..PARITY - i {
+                omega[i + j] ^= self.mul(li, s[j]);
+            }
+        }
+        let eval = |poly: &[u8], x: u8| poly.iter().rev().fold(0, |acc, &c| self.mul(acc, x) ^ c);
+
+        // Chien search over the codeword, then Forney for each root
+        let mut fixes = Vec::with_capacity(l);
+        for pos in 0..N {
+            let degree = N - 1 - pos;
+            let x_inv = self.beta_pow(N - degree % N);
+            if eval(&lambda, x_inv) != 0 {
+                continue;
+            }
+            if pos < fill {
+                return None;
+            }
+            let derivative: u8 = lambda
+                .iter()
+                .enumerate()
+                .skip(1)
+                .step_by(2)
+                .fold(0, |acc, (i, &c)| acc ^ self.mul(c, self.pow(x_inv, i - 1)));
+            if derivative == 0 {
+                return None;
+            }
+            // e = X^(1-b) Ω(X⁻¹) / Λ'(X⁻¹), X^(1-b) = (X⁻¹)^(b-1)
+            let magnitude = self.mul(
+                self.pow(x_inv, FIRST_ROOT - 1),
+                self.div(eval(&omega, x_inv), derivative),
+            );
+            fixes.push((pos, magnitude));
+        }
+        if fixes.len() != l {
+            return None;
+        }
+
+        for &(pos, e) in &fixes {
+            codeword[pos] ^= e;
+        }
+        if self.syndromes(codeword).iter().any(|&x| x != 0) {
+            return None;
+        }
+        Some(fixes.len())
+    }
+
+    fn pow(&self, x: u8, e: usize) -> u8 {
+        if x == 0 {
+            return if e == 0 { 1 } else { 0 };
+        }
+        self.exp[(self.log[x as usize] * e) % N]
+    }
+
+    /// Append interleaved parity to `data` (dual basis in and out)
+    pub fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.check_len(data.len())?;
+        let depth = self.interleave;
+        let mut block = data.to_vec();
+        block.resize(self.codeblock_len(data.len()), 0);
+
+        for j in 0..depth {
+            let symbols: Vec<u8> = data
+                .iter()
+                .skip(j)
+                .step_by(depth)
+                .map(|&b| self.from_dual[b as usize])
+                .collect();
+            for (i, p) in self.parity(&symbols).iter().enumerate() {
+                block[data.len() + i * depth + j] = self.to_dual[*p as usize];
+            }
+        }
+        Ok(block)
+    }
+
+    /// Correct a codeblock and return its data, or `None` if any codeword
+    /// was uncorrectable
+    pub fn decode(&self, codeblock: &[u8], stats: &mut RsStats) -> Option<Vec<u8>> {
+        let depth = self.interleave;
+        let data_len = codeblock.len().checked_sub(self.parity_len())?;
+        if self.check_len(data_len).is_err() {
+            stats.failed_codeblocks += 1;
+            return None;
+        }
+        let fill = K - data_len / depth;
+
+        let mut data = codeblock[..data_len].to_vec();
+        let mut failed = false;
+        for j in 0..depth {
+            let mut codeword = [0u8; N];
+            for (i, &b) in codeblock.iter().skip(j).step_by(depth).enumerate() {
+                codeword[fill + i] = self.from_dual[b as usize];
+            }
+
+            stats.codewords += 1;
+            match self.correct(&mut codeword, fill) {
+                Some(0) => {}
+                Some(fixed) => {
+                    stats.corrected_codewords += 1;
+                    stats.corrected_symbols += fixed as u64;
+                    for (i, b) in data.iter_mut().skip(j).step_by(depth).enumerate() {
+                        *b = self.to_dual[codeword[fill + i] as usize];
+                    }
+                }
+                None => {
+                    stats.uncorrectable_codewords += 1;
+                    failed = true;
+                }
+            }
+        }
+
+        if failed {
+            stats.failed_codeblocks += 1;
+            return None;
+        }
+        Some(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 37 + 11) as u8).collect()
+    }
+
+    #[test]
+    fn dual_basis_round_trip() {
+        let rs = ReedSolomon::new(1).unwrap();
+        assert!((0..=255u8).all(|b| rs.from_dual[rs.to_dual[b as usize] as usize] == b));
+        assert_eq!(rs.to_dual[0], 0);
+    }
+
+    #[test]
+    fn clean_codeblock_has_zero_syndromes() {
+        let rs = ReedSolomon::new(1).unwrap();
+        let block = rs.encode(&data(K)).unwrap();
+        let mut codeword = [0u8; N];
+        for (c, b) in codeword.iter_mut().zip(&block) {
+            *c = rs.from_dual[*b as usize];
+        }
+        assert!(rs.syndromes(&codeword).iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn corrects_up_to_t_errors_per_codeword() {
+        let rs = ReedSolomon::new(5).unwrap();
+        let original = data(5 * K);
+        let mut block = rs.encode(&original).unwrap();
+        assert_eq!(block.len(), 5 * N);
+
+        // 16 errors in every codeword, parity included
+        let len = block.len();
+        for e in 0..T * 5 {
+            block[(e * 71) % len] ^= (e as u8) | 1;
+        }
+        let mut stats = RsStats::default();
+        assert_eq!(rs.decode(&block, &mut stats), Some(original));
+        assert_eq!(stats.codewords, 5);
+        assert_eq!(stats.corrected_codewords, 5);
+        assert_eq!(stats.corrected_symbols, 80);
+    }
+
+    #[test]
+    fn flags_uncorrectable_codeword() {
+        let rs = ReedSolomon::new(2).unwrap();
+        let mut block = rs.encode(&data(2 * K)).unwrap();
+        // 17 errors in codeword 0, one in codeword 1
+        for e in 0..=T {
+            block[e * 2 * 3] ^= 0x5A;
+        }
+        block[1] ^= 0x01;
+
+        let mut stats = RsStats::default();
+        assert_eq!(rs.decode(&block, &mut stats), None);
+        assert_eq!(stats.uncorrectable_codewords, 1);
+        assert_eq!(stats.corrected_codewords, 1);
+        assert_eq!(stats.failed_codeblocks, 1);
+    }
+
+    #[test]
+    fn shortened_codewords() {
+        let rs = ReedSolomon::new(4).unwrap();
+        assert!(rs.check_len(4 * K + 4).is_err());
+        assert!(rs.check_len(61).is_err());
+
+        let original = data(4 * 60);
+        let mut block = rs.encode(&original).unwrap();
+        block[3] ^= 0xFF;
+        block[200] ^= 0x01;
+        let mut stats = RsStats::default();
+        assert_eq!(rs.decode(&block, &mut stats), Some(original));
+        assert_eq!(stats.corrected_symbols, 2);
+    }
+}
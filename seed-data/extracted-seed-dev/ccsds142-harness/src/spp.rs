@@ -0,0 +1,520 @@
+//! Space Packet Protocol (CCSDS 133.0)
+//! Packets are laid end to end across TM frame data fields; each frame's
+//! first header pointer marks where the first packet starts in it, so the
+//! receiver can resynchronize after a lost frame. Gaps are filled with idle
+//! packets (APID 0x7FF). Large user data is split into First/Continuation/
+//! Last packets and put back together per APID by `ApidRouter`.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+use crate::tm::Frame;
+
+pub const PRIMARY_HEADER_LEN: usize = 6;
+pub const IDLE_APID: u16 = 0x7FF;
+/// Smallest packet: header plus one data byte
+pub const MIN_PACKET_LEN: usize = PRIMARY_HEADER_LEN + 1;
+pub const MAX_DATA_LEN: usize = 65536;
+const SEQUENCE_MODULO: u16 = 1 << 14;
+
+/// First header pointer values with special meaning
+pub const FHP_NO_PACKET_START: u16 = 0x7FF;
+pub const FHP_IDLE: u16 = 0x7FE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketType {
+    Telemetry,
+    Telecommand,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceFlags {
+    Continuation = 0,
+    First = 1,
+    Last = 2,
+    Unsegmented = 3,
+}
+
+impl SequenceFlags {
+    fn from_bits(bits: u16) -> Self {
+        match bits & 0x3 {
+            0 => SequenceFlags::Continuation,
+            1 => SequenceFlags::First,
+            2 => SequenceFlags::Last,
+            _ => SequenceFlags::Unsegmented,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpacePacket {
+    pub packet_type: PacketType,
+    pub secondary_header: bool,
+    /// Application process ID, 11 bits
+    pub apid: u16,
+    pub sequence_flags: SequenceFlags,
+    /// Per-APID count, 14 bits
+    pub sequence_count: u16,
+    pub data: Vec<u8>,
+}
+
+impl SpacePacket {
+    pub fn telemetry(apid: u16, sequence_count: u16, data: &[u8]) -> Self {
+        Self {
+            packet_type: PacketType::Telemetry,
+            secondary_header: false,
+            apid: apid & 0x7FF,
+            sequence_flags: SequenceFlags::Unsegmented,
+            sequence_count: sequence_count % SEQUENCE_MODULO,
+            data: data.to_vec(),
+        }
+    }
+
+    /// Telemetry packet carrying `value` as JSON
+    pub fn from_json<T: Serialize>(apid: u16, sequence_count: u16, value: &T) -> Result<Self> {
+        Ok(Self::telemetry(
+            apid,
+            sequence_count,
+            &serde_json::to_vec(value)?,
+        ))
+    }
+
+    /// Idle packet of `len` bytes in total (at least `MIN_PACKET_LEN`)
+    pub fn idle(len: usize) -> Self {
+        let data_len = len.max(MIN_PACKET_LEN) - PRIMARY_HEADER_LEN;
+        Self::telemetry(IDLE_APID, 0, &vec![0x55; data_len])
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.apid == IDLE_APID
+    }
+
+    pub fn packet_len(&self) -> usize {
+        PRIMARY_HEADER_LEN + self.data.len()
+    }
+
+    /// Total packet length announced by a primary header
+    pub fn len_from_header(header: &[u8]) -> usize {
+        PRIMARY_HEADER_LEN + u16::from_be_bytes([header[4], header[5]]) as usize + 1
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        if self.data.is_empty() || self.data.len() > MAX_DATA_LEN {
+            bail!(
+                "packet data length {} outside 1..={}",
+                self.data.len(),
+                MAX_DATA_LEN
+            );
+        }
+        let id = (((self.packet_type == PacketType::Telecommand) as u16) << 12)
+            | ((self.secondary_header as u16) << 11)
+            | (self.apid & 0x7FF);
+        let sequence =
+            ((self.sequence_flags as u16) << 14) | (self.sequence_count % SEQUENCE_MODULO);
+
+        let mut v = Vec::with_capacity(self.packet_len());
+        v.extend(id.to_be_bytes());
+        v.extend(sequence.to_be_bytes());
+        v.extend(((self.data.len() - 1) as u16).to_be_bytes());
+        v.extend(&self.data);
+        Ok(v)
+    }
+
+    /// Parse one packet from the front of `raw`, returning it and its length
+    pub fn parse(raw: &[u8]) -> Result<(Self, usize)> {
+        if raw.len() < PRIMARY_HEADER_LEN {
+            bail!("packet header truncated");
+        }
+        let id = u16::from_be_bytes([raw[0], raw[1]]);
+        if id >> 13 != 0 {
+            bail!("unsupported packet version {}", id >> 13);
+        }
+        let len = Self::len_from_header(raw);
+        if raw.len() < len {
+            bail!("packet is {} bytes, only {} available", len, raw.len());
+        }
+        let sequence = u16::from_be_bytes([raw[2], raw[3]]);
+
+        let packet = Self {
+            packet_type: if id & 0x1000 != 0 {
+                PacketType::Telecommand
+            } else {
+                PacketType::Telemetry
+            },
+            secondary_header: id & 0x0800 != 0,
+            apid: id & 0x7FF,
+            sequence_flags: SequenceFlags::from_bits(sequence >> 14),
+            sequence_count: sequence & 0x3FFF,
+            data: raw[PRIMARY_HEADER_LEN..len].to_vec(),
+        };
+        Ok((packet, len))
+    }
+}
+
+/// Per-APID source sequence counters
+#[derive(Debug, Default)]
+pub struct SequenceCounters(HashMap<u16, u16>);
+
+impl SequenceCounters {
+    pub fn next(&mut self, apid: u16) -> u16 {
+        let count = self.0.entry(apid).or_insert(0);
+        let current = *count;
+        *count = (*count + 1) % SEQUENCE_MODULO;
+        current
+    }
+}
+
+/// Split user data into packets of at most `max_data_len` data bytes,
+/// flagged First/Continuation/Last (or Unsegmented if it fits in one)
+pub fn segment(
+    apid: u16,
+    counters: &mut SequenceCounters,
+    data: &[u8],
+    max_data_len: usize,
+) -> Vec<SpacePacket> {
+    let chunks: Vec<&[u8]> = data.chunks(max_data_len.clamp(1, MAX_DATA_LEN)).collect();
+    let last = chunks.len().saturating_sub(1);
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut packet = SpacePacket::telemetry(apid, counters.next(apid), chunk);
+            packet.sequence_flags = match (i, last) {
+                (0, 0) => SequenceFlags::Unsegmented,
+                (0, _) => SequenceFlags::First,
+                (i, last) if i == last => SequenceFlags::Last,
+                _ => SequenceFlags::Continuation,
+            };
+            packet
+        })
+        .collect()
+}
+
+/// Lays packets end to end across fixed-size frame data fields
+#[derive(Debug, Default)]
+pub struct PacketMux {
+    pending: VecDeque<u8>,
+    /// Offsets into `pending` where packets start
+    starts: VecDeque<usize>,
+}
+
+impl PacketMux {
+    pub fn push(&mut self, packet: &SpacePacket) -> Result<()> {
+        let bytes = packet.to_bytes()?;
+        self.starts.push_back(self.pending.len());
+        self.pending.extend(bytes);
+        Ok(())
+    }
+
+    /// Bytes queued for the next frames, idle spill-over included
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Next data field of `len` bytes and its first header pointer, topped
+    /// up with idle packets when the queue runs short
+    pub fn data_field(&mut self, len: usize) -> Result<(Vec<u8>, u16)> {
+        if self.pending.len() < len {
+            let idle = SpacePacket::idle(len - self.pending.len());
+            self.push(&idle)?;
+        }
+
+        let fhp = match self.starts.front() {
+            Some(&start) if start < len => start as u16,
+            _ => FHP_NO_PACKET_START,
+        };
+        let field: Vec<u8> = self.pending.drain(..len).collect();
+        self.starts.retain(|&s| s >= len);
+        for start in self.starts.iter_mut() {
+            *start -= len;
+        }
+        Ok((field, fhp))
+    }
+
+    /// Frame for virtual channel `vcid` carrying the next data field
+    pub fn frame(&mut self, scid: u16, vcid: u8, data_field_len: usize) -> Result<Frame> {
+        let (field, fhp) = self.data_field(data_field_len)?;
+        let mut frame = Frame::build(scid, vcid, 0, &field);
+        frame.first_header_pointer = fhp;
+        Ok(frame)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractorStats {
+    pub packets: u64,
+    pub idle_packets: u64,
+    /// Partial packets dropped on a VC count gap or header mismatch
+    pub dropped_partials: u64,
+    pub frame_gaps: u64,
+}
+
+/// Recovers packets from the frames of one virtual channel
+#[derive(Debug, Default)]
+pub struct PacketExtractor {
+    buf: Vec<u8>,
+    synced: bool,
+    last_vc_count: Option<u8>,
+    pub stats: ExtractorStats,
+}
+
+impl PacketExtractor {
+    fn resync(&mut self) {
+        if !self.buf.is_empty() {
+            self.stats.dropped_partials += 1;
+        }
+        self.buf.clear();
+        self.synced = false;
+    }
+
+    /// Bytes needed to finish the buffered partial packet, if its header is in
+    fn remaining(&self) -> Option<usize> {
+        (self.buf.len() >= PRIMARY_HEADER_LEN)
+            .then(|| SpacePacket::len_from_header(&self.buf).saturating_sub(self.buf.len()))
+    }
+
+    /// Feed the next frame of the channel; returns completed non-idle packets
+    pub fn push_frame(&mut self, frame: &Frame) -> Vec<SpacePacket> {
+        let gap = self
+            .last_vc_count
+            .is_some_and(|c| frame.vc_count != c.wrapping_add(1));
+        self.last_vc_count = Some(frame.vc_count);
+        self.push_field(gap, frame.first_header_pointer, &frame.payload)
+    }
+
+    /// Feed the next packet zone of the channel with its first header
+    /// pointer, `gap` if frames were lost since the previous one
+    pub fn push_field(&mut self, gap: bool, fhp: u16, field: &[u8]) -> Vec<SpacePacket> {
+        if gap {
+            self.stats.frame_gaps += 1;
+            self.resync();
+        }
+
+        let pointer = (fhp < FHP_IDLE)
+            .then_some(fhp as usize)
+            .filter(|&p| p < field.len());
+
+        if self.synced {
+            // The buffered packet must end exactly where the header pointer says
+            let consistent = match (self.remaining(), pointer) {
+                (Some(rem), Some(p)) => rem == p,
+                (Some(rem), None) => rem >= field.len() && fhp == FHP_NO_PACKET_START,
+                (None, _) => true,
+            };
+            if consistent {
+                self.buf.extend_from_slice(field);
+            } else {
+                self.resync();
+            }
+        }
+        if !self.synced {
+            let Some(p) = pointer else {
+                return Vec::new();
+            };
+            self.buf.extend_from_slice(&field[p..]);
+            self.synced = true;
+        }
+
+        let mut packets = Vec::new();
+        let mut offset = 0;
+        while let Ok((packet, len)) = SpacePacket::parse(&self.buf[offset..]) {
+            offset += len;
+            if packet.is_idle() {
+                self.stats.idle_packets += 1;
+            } else {
+                self.stats.packets += 1;
+                packets.push(packet);
+            }
+        }
+        self.buf.drain(..offset);
+        packets
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouterStats {
+    pub delivered: u64,
+    /// Packets for APIDs without a handler
+    pub unrouted: u64,
+    /// Segments discarded for arriving out of order or without a First,
+    /// and incomplete units abandoned when a new First arrives
+    pub dropped_segments: u64,
+}
+
+type Handler = Box<dyn FnMut(u16, &[u8])>;
+
+/// Delivers packet data to per-APID handlers, reassembling segmented data
+#[derive(Default)]
+pub struct ApidRouter {
+    handlers: HashMap<u16, Handler>,
+    /// APID -> (next expected sequence count, data so far)
+    partial: HashMap<u16, (u16, Vec<u8>)>,
+    pub stats: RouterStats,
+}
+
+impl ApidRouter {
+    pub fn on(&mut self, apid: u16, handler: impl FnMut(u16, &[u8]) + 'static) {
+        self.handlers.insert(apid & 0x7FF, Box::new(handler));
+    }
+
+    pub fn route(&mut self, packet: SpacePacket) {
+        let Some(handler) = self.handlers.get_mut(&packet.apid) else {
+            self.stats.unrouted += 1;
+            return;
+        };
+        let next = (packet.sequence_count + 1) % SEQUENCE_MODULO;
+
+        match packet.sequence_flags {
+            SequenceFlags::Unsegmented => {
+                handler(packet.apid, &packet.data);
+                self.stats.delivered += 1;
+            }
+            SequenceFlags::First => {
+                if self
+                    .partial
+                    .insert(packet.apid, (next, packet.data))
+                    .is_some()
+                {
+                    self.stats.dropped_segments += 1;
+                }
+            }
+            SequenceFlags::Continuation | SequenceFlags::Last => {
+                let in_order = self
+                    .partial
+                    .get(&packet.apid)
+                    .is_some_and(|(expected, _)| *expected == packet.sequence_count);
+                if !in_order {
+                    self.partial.remove(&packet.apid);
+                    self.stats.dropped_segments += 1;
+                    return;
+                }
+                let Some((expected, data)) = self.partial.get_mut(&packet.apid) else {
+                    return;
+                };
+                data.extend_from_slice(&packet.data);
+                *expected = next;
+                if packet.sequence_flags == SequenceFlags::Last {
+                    if let Some((_, data)) = self.partial.remove(&packet.apid) {
+                        handler(packet.apid, &data);
+                        self.stats.delivered += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn header_round_trip() {
+        let mut packet = SpacePacket::telemetry(0x123, 16383, b"abc");
+        packet.sequence_flags = SequenceFlags::Last;
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!(bytes[..6], [0x01, 0x23, 0xBF, 0xFF, 0x00, 0x02]);
+
+        let (back, len) = SpacePacket::parse(&bytes).unwrap();
+        assert_eq!((back, len), (packet, 9));
+        assert!(SpacePacket::parse(&bytes[..8]).is_err());
+    }
+
+    #[test]
+    fn packets_span_frames_and_survive_a_lost_frame() {
+        let mut mux = PacketMux::default();
+        let mut counters = SequenceCounters::default();
+        for i in 0..6u8 {
+            let apid = 0x10 + (i % 2) as u16;
+            mux.push(&SpacePacket::telemetry(apid, counters.next(apid), &[i; 25]))
+                .unwrap();
+        }
+
+        let mut frames = Vec::new();
+        for vc_count in 0..8u8 {
+            let mut frame = mux.frame(0x042, 2, 40).unwrap();
+            frame.vc_count = vc_count;
+            frames.push(frame);
+        }
+        // 31-byte packets over 40-byte fields: frame 1 starts mid-packet
+        assert_eq!(frames[0].first_header_pointer, 0);
+        assert_eq!(frames[1].first_header_pointer, 22);
+        assert_eq!(mux.pending(), 0);
+
+        let mut extractor = PacketExtractor::default();
+        let all: Vec<SpacePacket> = frames
+            .iter()
+            .flat_map(|f| extractor.push_frame(f))
+            .collect();
+        assert_eq!(all.len(), 6);
+        assert_eq!(all[5].data, [5; 25]);
+        assert_eq!((all[2].apid, all[2].sequence_count), (0x10, 1));
+
+        // Drop frame 1: packet 1 (spanning 0-1) and packet 2 (spanning 1-2) are lost
+        let mut extractor = PacketExtractor::default();
+        let survivors: Vec<u8> = frames
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != 1)
+            .flat_map(|(_, f)| extractor.push_frame(f))
+            .map(|p| p.data[0])
+            .collect();
+        assert_eq!(survivors, vec![0, 3, 4, 5]);
+        assert_eq!(extractor.stats.frame_gaps, 1);
+        assert_eq!(extractor.stats.dropped_partials, 1);
+    }
+
+    #[test]
+    fn router_reassembles_segments() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let sink = received.clone();
+        let mut router = ApidRouter::default();
+        router.on(0x20, move |apid, data| {
+            sink.borrow_mut().push((apid, data.to_vec()))
+        });
+
+        let mut counters = SequenceCounters::default();
+        let data: Vec<u8> = (0..100).collect();
+        let packets = segment(0x20, &mut counters, &data, 30);
+        assert_eq!(packets.len(), 4);
+        assert_eq!(packets[0].sequence_flags, SequenceFlags::First);
+        assert_eq!(packets[3].sequence_flags, SequenceFlags::Last);
+
+        for packet in packets.iter().cloned() {
+            router.route(packet);
+        }
+        router.route(SpacePacket::telemetry(0x21, 0, b"x"));
+        // A missing continuation discards the rest of the unit
+        let again = segment(0x20, &mut counters, &data, 30);
+        for (i, packet) in again.into_iter().enumerate() {
+            if i != 1 {
+                router.route(packet);
+            }
+        }
+
+        assert_eq!(*received.borrow(), vec![(0x20, data)]);
+        assert_eq!(router.stats.unrouted, 1);
+        assert_eq!(router.stats.dropped_segments, 2);
+    }
+
+    #[test]
+    fn json_payload() {
+        #[derive(Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Sample {
+            station_id: String,
+            weather_score: f64,
+        }
+        let sample = Sample {
+            station_id: "GS-001".into(),
+            weather_score: 0.9,
+        };
+        let packet = SpacePacket::from_json(0x30, 0, &sample).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Sample>(&packet.data).unwrap(),
+            sample
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! TM Synchronization (CCSDS 131.0 §9-10)
+//! CADU = 32-bit attached sync marker + (pseudo-randomized) transfer frame,
+//! optionally extended to an RS(255,223) codeblock before randomization.
+//! The deframer searches the bit stream for the ASM at any bit offset,
+//! tolerating a few marker bit errors, then flywheels on the fixed CADU
+//! length, re-acquiring within a small window when bits slip.
+
+use anyhow::{bail, Result};
+
+use crate::rs::{ReedSolomon, RsStats};
+use crate::tm::{Frame, FrameFormat};
+
+pub const ASM: u32 = 0x1ACF_FC1D;
+pub const ASM_LEN: usize = 4;
+
+/// Pseudo-randomizer sequence, h(x) = x^8 + x^7 + x^5 + x^3 + 1, all-ones
+/// seed, repeating every 255 bytes
+pub fn pseudo_random_sequence() -> [u8; 255] {
+    let mut seq = [0u8; 255];
+    let mut state = 0xFFu8;
+    for byte in seq.iter_mut() {
+        for _ in 0..8 {
+            *byte = (*byte << 1) | (state >> 7);
+            let feedback = (state >> 7) ^ (state >> 4) ^ (state >> 2) ^ state;
+            state = (state << 1) | (feedback & 1);
+        }
+    }
+    seq
+}
+
+/// XOR the sequence over `data` in place (its own inverse)
+pub fn randomize(data: &mut [u8]) {
+    let seq = pseudo_random_sequence();
+    for (byte, r) in data.iter_mut().zip(seq.iter().cycle()) {
+        *byte ^= r;
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SyncConfig {
+    pub randomize: bool,
+    /// ASM bit errors accepted as a match
+    pub max_asm_errors: u32,
+    /// Bits either side of the expected ASM position searched while locked
+    pub slip_window_bits: usize,
+    /// RS(255,223) interleaving depth; `None` sends frames uncoded
+    pub rs_interleave: Option<usize>,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            randomize: true,
+            max_asm_errors: 2,
+            slip_window_bits: 4,
+            rs_interleave: None,
+        }
+    }
+}
+
+/// RS codec for `config`, checked against the frame length
+fn codec(format: &FrameFormat, config: &SyncConfig) -> Result<Option<ReedSolomon>> {
+    format.validate()?;
+    let Some(depth) = config.rs_interleave else {
+        return Ok(None);
+    };
+    let rs = ReedSolomon::new(depth)?;
+    rs.check_len(format.frame_len)?;
+    Ok(Some(rs))
+}
+
+/// Turns frames into CADUs, stamping master/virtual channel counts
+pub struct Framer {
+    format: FrameFormat,
+    config: SyncConfig,
+    rs: Option<ReedSolomon>,
+    mc_count: u8,
+    vc_counts: [u8; 8],
+}
+
+impl Framer {
+    pub fn new(format: FrameFormat, config: SyncConfig) -> Result<Self> {
+        Ok(Self {
+            rs: codec(&format, &config)?,
+            format,
+            config,
+            mc_count: 0,
+            vc_counts: [0; 8],
+        })
+    }
+
+    pub fn cadu(&mut self, scid: u16, vcid: u8, payload: &[u8]) -> Result<Vec<u8>> {
+        self.frame_cadu(Frame::build(scid, vcid, 0, payload))
+    }
+
+    /// Stamp the channel counts on a prepared frame and wrap it in a CADU
+    pub fn frame_cadu(&mut self, mut frame: Frame) -> Result<Vec<u8>> {
+        let vc = (frame.vcid & 0x07) as usize;
+        frame.mc_count = self.mc_count;
+        frame.vc_count = self.vc_counts[vc];
+        if self.format.ocf && frame.ocf.is_none() {
+            frame.ocf = Some([0; 4]);
+        }
+        let bytes = frame.to_bytes(&self.format)?;
+        self.mc_count = self.mc_count.wrapping_add(1);
+        self.vc_counts[vc] = self.vc_counts[vc].wrapping_add(1);
+        self.wrap(bytes)
+    }
+
+    /// CADU for an already encoded transfer frame of the format's length,
+    /// for frame types other than TM
+    pub fn wrap(&self, mut bytes: Vec<u8>) -> Result<Vec<u8>> {
+        if bytes.len() != self.format.frame_len {
+            bail!(
+                "frame is {} bytes, expected {}",
+                bytes.len(),
+                self.format.frame_len
+            );
+        }
+        if let Some(rs) = &self.rs {
+            bytes = rs.encode(&bytes)?;
+        }
+        if self.config.randomize {
+            randomize(&mut bytes);
+        }
+        let mut cadu = Vec::with_capacity(ASM_LEN + bytes.len());
+        cadu.extend(ASM.to_be_bytes());
+        cadu.extend(bytes);
+        Ok(cadu)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeframerStats {
+    pub frames: u64,
+    /// Frames dropped on RS, FECF or header errors
+    pub bad_frames: u64,
+    /// Marker bits that differed from the ASM in accepted markers
+    pub asm_bit_errors: u64,
+    /// Re-locks within the slip window at a shifted bit offset
+    pub bit_slips: u64,
+    pub sync_losses: u64,
+    /// Master channel count discontinuities between good frames
+    pub mc_gaps: u64,
+    pub rs: RsStats,
+}
+
+/// Streaming frame recovery from an unaligned, noisy byte stream
+pub struct Deframer {
+    format: FrameFormat,
+    config: SyncConfig,
+    rs: Option<ReedSolomon>,
+    buf: Vec<u8>,
+    /// Next unexamined bit in `buf`
+    pos: usize,
+    locked: bool,
+    last_mc: Option<u8>,
+    pub stats: DeframerStats,
+}
+
+impl Deframer {
+    pub fn new(format: FrameFormat, config: SyncConfig) -> Result<Self> {
+        Ok(Self {
+            rs: codec(&format, &config)?,
+            format,
+            config,
+            buf: Vec::new(),
+            pos: 0,
+            locked: false,
+            last_mc: None,
+            stats: DeframerStats::default(),
+        })
+    }
+
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+
+    fn codeblock_len(&self) -> usize {
+        match &self.rs {
+            Some(rs) => rs.codeblock_len(self.format.frame_len),
+            None => self.format.frame_len,
+        }
+    }
+
+    fn cadu_bits(&self) -> usize {
+        (ASM_LEN + self.codeblock_len()) * 8
+    }
+
+    fn total_bits(&self) -> usize {
+        self.buf.len() * 8
+    }
+
+    fn bit(&self, i: usize) -> u32 {
+        ((self.buf[i / 8] >> (7 - i % 8)) & 1) as u32
+    }
+
+    fn asm_errors_at(&self, i: usize) -> u32 {
+        let word = (i..i + 32).fold(0u32, |w, b| (w << 1) | self.bit(b));
+        (word ^ ASM).count_ones()
+    }
+
+    /// `len` bytes starting at an arbitrary bit offset
+    fn bytes_at(&self, bit: usize, len: usize) -> Vec<u8> {
+        let (start, shift) = (bit / 8, bit % 8);
+        (start..start + len)
+            .map(|k| {
+                if shift == 0 {
+                    self.buf[k]
+                } else {
+                    (self.buf[k] << shift) | (self.buf[k + 1] >> (8 - shift))
+                }
+            })
+            .collect()
+    }
+
+    /// Best ASM within the slip window around `pos`, nearest offset on ties
+    fn find_near(&self) -> Option<(usize, u32)> {
+        let w = self.config.slip_window_bits;
+        let mut candidates: Vec<usize> = vec![self.pos];
+        for d in 1..=w {
+            candidates.push(self.pos + d);
+            if let Some(p) = self.pos.checked_sub(d) {
+                candidates.push(p);
+            }
+        }
+        candidates
+            .into_iter()
+            .map(|p| (p, self.asm_errors_at(p)))
+            .filter(|&(_, e)| e <= self.config.max_asm_errors)
+            .min_by_key(|&(p, e)| (e, p.abs_diff(self.pos)))
+    }
+
+    fn search(&self) -> Option<(usize, u32)> {
+        (self.pos..=self.total_bits().checked_sub(32)?)
+            .map(|p| (p, self.asm_errors_at(p)))
+            .find(|&(_, e)| e <= self.config.max_asm_errors)
+    }
+
+    fn extract(&mut self, asm_at: usize, errors: u32) -> Option<Vec<u8>> {
+        self.stats.asm_bit_errors += errors as u64;
+        let mut raw = self.bytes_at(asm_at + 32, self.codeblock_len());
+        if self.config.randomize {
+            randomize(&mut raw);
+        }
+        if let Some(rs) = &self.rs {
+            let Some(data) = rs.decode(&raw, &mut self.stats.rs) else {
+                self.stats.bad_frames += 1;
+                return None;
+            };
+            raw = data;
+        }
+        Some(raw)
+    }
+
+    /// Feed received bytes; returns the TM frames completed by them
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        for raw in self.push_raw(bytes) {
+            let Some(frame) = Frame::parse(&raw, &self.format).ok() else {
+                self.stats.bad_frames += 1;
+                continue;
+            };
+            self.stats.frames += 1;
+            if self
+                .last_mc
+                .is_some_and(|mc| frame.mc_count != mc.wrapping_add(1))
+            {
+                self.stats.mc_gaps += 1;
+            }
+            self.last_mc = Some(frame.mc_count);
+            frames.push(frame);
+        }
+        frames
+    }
+
+    /// Feed received bytes; returns the decoded, derandomized transfer
+    /// frames completed by them, unparsed. `stats.frames` and `mc_gaps` are
+    /// left to the caller's frame type
+    pub fn push_raw(&mut self, bytes: &[u8]) -> Vec<Vec<u8>> {
+        self.buf.extend_from_slice(bytes);
+        let cadu_bits = self.cadu_bits();
+        // One spare byte so an unaligned frame can read its last partial byte
+        let slack = self.config.slip_window_bits + 8;
+        let mut frames = Vec::new();
+
+        loop {
+            if self.locked {
+                if self.pos + cadu_bits + slack > self.total_bits() {
+                    break;
+                }
+                match self.find_near() {
+                    Some((at, errors)) => {
+                        if at != self.pos {
+                            self.stats.bit_slips += 1;
+                        }
+                        frames.extend(self.extract(at, errors));
+                        self.pos = at + cadu_bits;
+                    }
+                    None => {
+                        self.locked = false;
+                        self.stats.sync_losses += 1;
+                        self.pos = self.pos.saturating_sub(self.config.slip_window_bits);
+                    }
+                }
+            } else {
+                match self.search() {
+                    Some((at, _)) if at + cadu_bits + 8 > self.total_bits() => {
+                        self.pos = at;
+                        break;
+                    }
+                    Some((at, errors)) => {
+                        self.locked = true;
+                        frames.extend(self.extract(at, errors));
+                        self.pos = at + cadu_bits;
+                    }
+                    None => {
+                        self.pos = self.total_bits().saturating_sub(31).max(self.pos);
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Keep the slip window behind `pos`, drop whole bytes before it
+        let drop = self.pos.saturating_sub(self.config.slip_window_bits) / 8;
+        self.buf.drain(..drop);
+        self.pos -= drop * 8;
+        frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_bits(bytes: &[u8]) -> Vec<u8> {
+        bytes
+            .iter()
+            .flat_map(|b| (0..8).rev().map(move |i| (b >> i) & 1))
+            .collect()
+    }
+
+    fn from_bits(bits: &[u8]) -> Vec<u8> {
+        bits.chunks(8)
+            .map(|c| {
+                c.iter()
+                    .chain(std::iter::repeat(&0))
+                    .take(8)
+                    .fold(0, |b, bit| (b << 1) | bit)
+            })
+            .collect()
+    }
+
+    fn stream(format: FrameFormat, count: usize) -> Vec<u8> {
+        coded_stream(format, SyncConfig::default(), count)
+    }
+
+    fn coded_stream(format: FrameFormat, config: SyncConfig, count: usize) -> Vec<u8> {
+        let mut framer = Framer::new(format, config).unwrap();
+        (0..count)
+            .flat_map(|i| framer.cadu(0x042, 1, &[i as u8; 16]).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn randomizer_sequence() {
+        let seq = pseudo_random_sequence();
+        assert_eq!(seq[..8], [0xFF, 0x48, 0x0E, 0xC0, 0x9A, 0x0D, 0x70, 0xBC]);
+
+        let mut data = b"transfer frame".to_vec();
+        randomize(&mut data);
+        randomize(&mut data);
+        assert_eq!(data, b"transfer frame");
+    }
+
+    #[test]
+    fn recovers_frames_from_noisy_stream() {
+        let format = FrameFormat {
+            frame_len: 64,
+            ocf: false,
+            fecf: true,
+        };
+        let cadu_bits = (ASM_LEN + 64) * 8;
+        let mut bits = vec![1, 0, 1];
+        bits.extend(to_bits(&[0x5A; 7]));
+        let start = bits.len();
+        bits.extend(to_bits(&stream(format, 6)));
+
+        // Two ASM bit errors in frame 1, a slipped (extra) bit inside frame 2
+        bits[start + cadu_bits + 3] ^= 1;
+        bits[start + cadu_bits + 17] ^= 1;
+        bits.insert(start + 2 * cadu_bits + 100, 0);
+        let bytes = from_bits(&bits);
+
+        let mut deframer = Deframer::new(format, SyncConfig::default()).unwrap();
+        let frames: Vec<Frame> = bytes.chunks(37).flat_map(|c| deframer.push(c)).collect();
+
+        let counts: Vec<u8> = frames.iter().map(|f| f.vc_count).collect();
+        assert_eq!(counts, vec![0, 1, 3, 4]);
+        assert_eq!(frames[2].payload[..16], [3; 16]);
+        assert_eq!(deframer.stats.bad_frames, 1);
+        assert_eq!(deframer.stats.asm_bit_errors, 2);
+        assert_eq!(deframer.stats.bit_slips, 1);
+        assert_eq!(deframer.stats.mc_gaps, 1);
+        assert_eq!(deframer.stats.sync_losses, 0);
+        // The last CADU waits for slack bytes
+        assert!(deframer.locked());
+        assert_eq!(deframer.push(&[0; 2]).len(), 1);
+    }
+
+    #[test]
+    fn loses_and_regains_sync() {
+        let format = FrameFormat {
+            frame_len: 64,
+            ocf: false,
+            fecf: true,
+        };
+        let mut bytes = stream(format, 2);
+        bytes.extend([0x00; 40]);
+        bytes.extend(stream(format, 3));
+        bytes.extend([0; 2]);
+
+        let mut deframer = Deframer::new(format, SyncConfig::default()).unwrap();
+        let frames = deframer.push(&bytes);
+        assert_eq!(frames.len(), 5);
+        assert_eq!(deframer.stats.sync_losses, 1);
+        assert_eq!(deframer.stats.bad_frames, 0);
+    }
+
+    #[test]
+    fn reed_solomon_repairs_corrupted_frames() {
+        let format = FrameFormat {
+            frame_len: 64,
+            ocf: false,
+            fecf: true,
+        };
+        let config = SyncConfig {
+            rs_interleave: Some(2),
+            ..SyncConfig::default()
+        };
+        assert!(Framer::new(
+            format,
+            SyncConfig {
+                rs_interleave: Some(3),
+                ..config
+            }
+        )
+        .is_err());
+
+        // 10 byte errors per codeblock, all within one codeword's correction power
+        let cadu_len = ASM_LEN + 64 + 64;
+        let mut bytes = coded_stream(format, config, 4);
+        for cadu in bytes.chunks_mut(cadu_len) {
+            for e in 0..10 {
+                cadu[ASM_LEN + e * 12] ^= 0xA5;
+            }
+        }
+        bytes.extend([0; 2]);
+
+        let mut deframer = Deframer::new(format, config).unwrap();
+        let frames = deframer.push(&bytes);
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[3].payload[..16], [3; 16]);
+        assert_eq!(deframer.stats.rs.codewords, 8);
+        assert_eq!(deframer.stats.rs.corrected_symbols, 40);
+        assert_eq!(deframer.stats.bad_frames, 0);
+    }
+}
@@ -0,0 +1,303 @@
+//! Telecommand (CCSDS 231.0 / 232.0)
+//! TC transfer frames are carried in CLTUs: start sequence 0xEB90, BCH(63,56)
+//! codeblocks of 7 data bytes plus a complemented parity byte, and the
+//! tail sequence. The BCH decoder corrects one bit error per codeblock and
+//! ends the CLTU on anything worse, as a flight decoder in correction mode.
+
+use anyhow::{bail, Result};
+
+use crate::tm::crc16;
+
+pub const START_SEQUENCE: [u8; 2] = [0xEB, 0x90];
+pub const TAIL_SEQUENCE: [u8; 8] = [0xC5, 0xC5, 0xC5, 0xC5, 0xC5, 0xC5, 0xC5, 0x79];
+pub const CODEBLOCK_LEN: usize = 8;
+pub const CODEBLOCK_DATA_LEN: usize = 7;
+/// Fill for the last codeblock
+pub const FILL: u8 = 0x55;
+
+pub const TC_HEADER_LEN: usize = 5;
+pub const TC_MAX_FRAME_LEN: usize = 1024;
+
+/// g(x) = x^7 + x^6 + x^2 + 1 without the x^7 term
+const BCH_POLY: u8 = 0x45;
+
+fn bch_remainder(data: &[u8]) -> u8 {
+    let mut reg = 0u8;
+    for &byte in data {
+        for i in (0..8).rev() {
+            let feedback = ((byte >> i) & 1) ^ ((reg >> 6) & 1);
+            reg = (reg << 1) & 0x7F;
+            if feedback == 1 {
+                reg ^= BCH_POLY;
+            }
+        }
+    }
+    reg
+}
+
+/// Codeblock for 7 data bytes: data, then complemented parity and a 0 filler bit
+pub fn bch_encode(data: &[u8; CODEBLOCK_DATA_LEN]) -> [u8; CODEBLOCK_LEN] {
+    let mut block = [0u8; CODEBLOCK_LEN];
+    block[..CODEBLOCK_DATA_LEN].copy_from_slice(data);
+    block[CODEBLOCK_DATA_LEN] = (!bch_remainder(data) & 0x7F) << 1;
+    block
+}
+
+/// Syndrome of a single error at each of the 63 code bits, data bits first
+fn single_error_syndromes() -> [u8; 63] {
+    let mut table = [0u8; 63];
+    for (bit, syndrome) in table.iter_mut().enumerate() {
+        if bit < 56 {
+            let mut data = [0u8; CODEBLOCK_DATA_LEN];
+            data[bit / 8] = 0x80 >> (bit % 8);
+            *syndrome = bch_remainder(&data);
+        } else {
+            *syndrome = 0x40 >> (bit - 56);
+        }
+    }
+    table
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codeblock {
+    Clean([u8; CODEBLOCK_DATA_LEN]),
+    Corrected([u8; CODEBLOCK_DATA_LEN]),
+    Uncorrectable,
+}
+
+pub fn bch_decode(block: &[u8; CODEBLOCK_LEN]) -> Codeblock {
+    let mut data = [0u8; CODEBLOCK_DATA_LEN];
+    data.copy_from_slice(&block[..CODEBLOCK_DATA_LEN]);
+    let parity = !(block[CODEBLOCK_DATA_LEN] >> 1) & 0x7F;
+    let syndrome = bch_remainder(&data) ^ parity;
+    if syndrome == 0 {
+        return Codeblock::Clean(data);
+    }
+    match single_error_syndromes().iter().position(|&s| s == syndrome) {
+        Some(bit) if bit < 56 => {
+            data[bit / 8] ^= 0x80 >> (bit % 8);
+            Codeblock::Corrected(data)
+        }
+        // The error was in the parity itself
+        Some(_) => Codeblock::Corrected(data),
+        None => Codeblock::Uncorrectable,
+    }
+}
+
+/// Wrap `data` (one or more TC frames) in a CLTU
+pub fn cltu_encode(data: &[u8]) -> Vec<u8> {
+    let mut cltu = START_SEQUENCE.to_vec();
+    for chunk in data.chunks(CODEBLOCK_DATA_LEN) {
+        let mut block = [FILL; CODEBLOCK_DATA_LEN];
+        block[..chunk.len()].copy_from_slice(chunk);
+        cltu.extend(bch_encode(&block));
+    }
+    cltu.extend(TAIL_SEQUENCE);
+    cltu
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CltuStats {
+    pub cltus: u64,
+    /// CLTUs ended by an uncorrectable codeblock
+    pub aborted: u64,
+    pub codeblocks: u64,
+    pub corrected_codeblocks: u64,
+}
+
+/// Decode every CLTU in `stream`; CLTUs hit by an uncorrectable codeblock
+/// are dropped whole
+pub fn cltu_decode(stream: &[u8], stats: &mut CltuStats) -> Vec<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i + START_SEQUENCE.len() <= stream.len() {
+        if stream[i..i + START_SEQUENCE.len()] != START_SEQUENCE {
+            i += 1;
+            continue;
+        }
+        i += START_SEQUENCE.len();
+        stats.cltus += 1;
+
+        let mut data = Vec::new();
+        let mut complete = false;
+        while i + CODEBLOCK_LEN <= stream.len() {
+            let mut block = [0u8; CODEBLOCK_LEN];
+            block.copy_from_slice(&stream[i..i + CODEBLOCK_LEN]);
+            i += CODEBLOCK_LEN;
+            if block == TAIL_SEQUENCE {
+                complete = true;
+                break;
+            }
+            stats.codeblocks += 1;
+            match bch_decode(&block) {
+                Codeblock::Clean(d) => data.extend(d),
+                Codeblock::Corrected(d) => {
+                    stats.corrected_codeblocks += 1;
+                    data.extend(d);
+                }
+                // A corrupted tail ends the CLTU the same way
+                Codeblock::Uncorrectable => break,
+            }
+        }
+        if complete {
+            out.push(data);
+        } else {
+            stats.aborted += 1;
+        }
+    }
+    out
+}
+
+/// AD (sequence-controlled), BD (expedited) or BC (COP control)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TcFrameType {
+    Ad,
+    Bd,
+    Bc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcFrame {
+    pub frame_type: TcFrameType,
+    /// Spacecraft ID, 10 bits
+    pub scid: u16,
+    /// Virtual channel ID, 6 bits
+    pub vcid: u8,
+    /// N(S) for AD frames
+    pub sequence: u8,
+    pub data: Vec<u8>,
+}
+
+impl TcFrame {
+    pub fn new(frame_type: TcFrameType, scid: u16, vcid: u8, sequence: u8, data: &[u8]) -> Self {
+        Self {
+            frame_type,
+            scid: scid & 0x3FF,
+            vcid: vcid & 0x3F,
+            sequence,
+            data: data.to_vec(),
+        }
+    }
+
+    pub fn to_bytes(&self, fecf: bool) -> Result<Vec<u8>> {
+        let len = TC_HEADER_LEN + self.data.len() + if fecf { 2 } else { 0 };
+        if self.data.is_empty() || len > TC_MAX_FRAME_LEN {
+            bail!(
+                "TC frame length {} outside {}..={}",
+                len,
+                TC_HEADER_LEN + 1,
+                TC_MAX_FRAME_LEN
+            );
+        }
+        let (bypass, control) = match self.frame_type {
+            TcFrameType::Ad => (0, 0),
+            TcFrameType::Bd => (1, 0),
+            TcFrameType::Bc => (1, 1),
+        };
+        let first = (bypass << 13) | (control << 12) | (self.scid & 0x3FF);
+        let second = ((self.vcid as u16 & 0x3F) << 10) | ((len - 1) as u16 & 0x3FF);
+
+        let mut v = Vec::with_capacity(len);
+        v.extend(first.to_be_bytes());
+        v.extend(second.to_be_bytes());
+        v.push(self.sequence);
+        v.extend(&self.data);
+        if fecf {
+            v.extend(crc16(&v).to_be_bytes());
+        }
+        Ok(v)
+    }
+
+    /// Parse the frame at the front of `raw` (CLTU fill may follow)
+    pub fn parse(raw: &[u8], fecf: bool) -> Result<Self> {
+        if raw.len() < TC_HEADER_LEN {
+            bail!("TC frame header truncated");
+        }
+        let first = u16::from_be_bytes([raw[0], raw[1]]);
+        let second = u16::from_be_bytes([raw[2], raw[3]]);
+        if first >> 14 != 0 {
+            bail!("not a TC frame (version {})", first >> 14);
+        }
+        let len = (second & 0x3FF) as usize + 1;
+        let trailer = if fecf { 2 } else { 0 };
+        if len > raw.len() || len <= TC_HEADER_LEN + trailer {
+            bail!("TC frame length {} does not fit {} bytes", len, raw.len());
+        }
+        if fecf && crc16(&raw[..len - 2]) != u16::from_be_bytes([raw[len - 2], raw[len - 1]]) {
+            bail!("FECF mismatch");
+        }
+
+        let frame_type = match ((first >> 13) & 1, (first >> 12) & 1) {
+            (0, 0) => TcFrameType::Ad,
+            (1, 0) => TcFrameType::Bd,
+            (1, 1) => TcFrameType::Bc,
+            _ => bail!("reserved bypass/control flag combination"),
+        };
+        Ok(Self {
+            frame_type,
+            scid: first & 0x3FF,
+            vcid: (second >> 10) as u8,
+            sequence: raw[4],
+            data: raw[TC_HEADER_LEN..len - trailer].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bch_corrects_single_and_rejects_double_errors() {
+        let data = [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE];
+        let block = bch_encode(&data);
+        assert_eq!(bch_decode(&block), Codeblock::Clean(data));
+        // All-zero data has all-ones (complemented) parity
+        assert_eq!(bch_encode(&[0; 7])[7], 0xFE);
+
+        for bit in 0..63 {
+            let mut hit = block;
+            hit[bit / 8] ^= 0x80 >> (bit % 8);
+            assert_eq!(bch_decode(&hit), Codeblock::Corrected(data), "bit {}", bit);
+        }
+        let mut double = block;
+        double[0] ^= 0x81;
+        assert_eq!(bch_decode(&double), Codeblock::Uncorrectable);
+    }
+
+    #[test]
+    fn cltu_round_trip_with_errors() {
+        let frame = TcFrame::new(TcFrameType::Ad, 0x042, 3, 17, b"SET_MODE FINE_TRACK");
+        let bytes = frame.to_bytes(true).unwrap();
+        let mut cltu = cltu_encode(&bytes);
+        assert_eq!(cltu[..2], START_SEQUENCE);
+        assert_eq!(cltu.len(), 2 + 4 * CODEBLOCK_LEN + 8);
+        cltu[5] ^= 0x04;
+
+        let mut stream = vec![0xAA; 5];
+        stream.extend(&cltu);
+        let mut damaged = cltu_encode(&bytes);
+        damaged[10] ^= 0x03;
+        stream.extend(damaged);
+
+        let mut stats = CltuStats::default();
+        let decoded = cltu_decode(&stream, &mut stats);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(TcFrame::parse(&decoded[0], true).unwrap(), frame);
+        assert_eq!(
+            (stats.cltus, stats.aborted, stats.corrected_codeblocks),
+            (2, 1, 1)
+        );
+    }
+
+    #[test]
+    fn frame_types_and_length() {
+        let bc = TcFrame::new(TcFrameType::Bc, 0x3FF, 63, 0, &[0x82, 0x00, 9]);
+        let raw = bc.to_bytes(false).unwrap();
+        assert_eq!(raw[..5], [0x33, 0xFF, 0xFC, 0x07, 0x00]);
+        assert_eq!(TcFrame::parse(&raw, false).unwrap(), bc);
+        assert!(TcFrame::new(TcFrameType::Ad, 1, 1, 0, &[0; 1020])
+            .to_bytes(true)
+            .is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! TM Transfer Frame (CCSDS 132.0)
+//! Fixed-length frames: 6-byte primary header, data field, optional OCF and
+//! a CRC-16 frame error control field.
+
+use anyhow::{bail, Result};
+
+pub const PRIMARY_HEADER_LEN: usize = 6;
+pub const OCF_LEN: usize = 4;
+pub const FECF_LEN: usize = 2;
+
+/// Fixed frame layout for a physical channel (all frames share it)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameFormat {
+    /// Total transfer frame length in bytes, header and trailer included
+    pub frame_len: usize,
+    pub ocf: bool,
+    pub fecf: bool,
+}
+
+impl Default for FrameFormat {
+    fn default() -> Self {
+        // 1115 = 5 × 223, the RS(255,223) I=5 codeblock
+        Self {
+            frame_len: 1115,
+            ocf: false,
+            fecf: true,
+        }
+    }
+}
+
+impl FrameFormat {
+    pub fn data_field_len(&self) -> usize {
+        self.frame_len
+            - PRIMARY_HEADER_LEN
+            - if self.ocf { OCF_LEN } else { 0 }
+            - if self.fecf { FECF_LEN } else { 0 }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let trailer = if self.ocf { OCF_LEN } else { 0 } + if self.fecf { FECF_LEN } else { 0 };
+        if self.frame_len <= PRIMARY_HEADER_LEN + trailer || self.frame_len > 2048 {
+            bail!(
+                "frame length {} outside {}..=2048",
+                self.frame_len,
+                PRIMARY_HEADER_LEN + trailer + 1
+            );
+        }
+        Ok(())
+    }
+}
+
+/// CRC-16-CCITT as used by the FECF: poly 0x1021, preset 0xFFFF, no final XOR
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Transfer frame version, 0 for TM
+    pub version: u8,
+    /// Spacecraft ID, 10 bits
+    pub scid: u16,
+    /// Virtual channel ID, 3 bits
+    pub vcid: u8,
+    /// Master channel frame count
+    pub mc_count: u8,
+    /// Virtual channel frame count
+    pub vc_count: u8,
+    /// Offset of the first packet header in the data field, 11 bits
+    pub first_header_pointer: u16,
+    /// Data field, padded to the format's data field length on encode
+    pub payload: Vec<u8>,
+    pub ocf: Option<[u8; 4]>,
+}
+
+impl Frame {
+    pub fn build(scid: u16, vcid: u8, vc_count: u8, payload: &[u8]) -> Self {
+        Self {
+            version: 0,
+            scid: scid & 0x3FF,
+            vcid: vcid & 0x07,
+            mc_count: 0,
+            vc_count,
+            first_header_pointer: 0,
+            payload: payload.to_vec(),
+            ocf: None,
+        }
+    }
+
+    pub fn to_bytes(&self, format: &FrameFormat) -> Result<Vec<u8>> {
+        let data_len = format.data_field_len();
+        if self.payload.len() > data_len {
+            bail!(
+                "payload {} bytes exceeds data field of {}",
+                self.payload.len(),
+                data_len
+            );
+        }
+        if format.ocf != self.ocf.is_some() {
+            bail!("OCF presence does not match frame format");
+        }
+
+        let mut v = Vec::with_capacity(format.frame_len);
+        let id = ((self.version as u16 & 0x03) << 14)
+            | ((self.scid & 0x3FF) << 4)
+            | ((self.vcid as u16 & 0x07) << 1)
+            | format.ocf as u16;
+        v.extend(id.to_be_bytes());
+        v.push(self.mc_count);
+        v.push(self.vc_count);
+        // No secondary header, packets in order, segment length ID 0b11
+        let status = (0b11 << 11) | (self.first_header_pointer & 0x7FF);
+        v.extend(status.to_be_bytes());
+        v.extend(&self.payload);
+        v.resize(PRIMARY_HEADER_LEN + data_len, 0);
+        if let Some(ocf) = self.ocf {
+            v.extend(ocf);
+        }
+        if format.fecf {
+            v.extend(crc16(&v).to_be_bytes());
+        }
+        Ok(v)
+    }
+
+    pub fn parse(raw: &[u8], format: &FrameFormat) -> Result<Self> {
+        if raw.len() != format.frame_len {
+            bail!(
+                "frame is {} bytes, expected {}",
+                raw.len(),
+                format.frame_len
+            );
+        }
+        if format.fecf {
+            let (body, fecf) = raw.split_at(raw.len() - FECF_LEN);
+            if crc16(body) != u16::from_be_bytes([fecf[0], fecf[1]]) {
+                bail!("FECF mismatch");
+            }
+        }
+
+        let id = u16::from_be_bytes([raw[0], raw[1]]);
+        let version = (id >> 14) as u8;
+        if version != 0 {
+            bail!("not a TM frame (version {})", version);
+        }
+        if (id & 1 == 1) != format.ocf {
+            bail!("OCF flag does not match frame format");
+        }
+        let status = u16::from_be_bytes([raw[4], raw[5]]);
+        let data_end = PRIMARY_HEADER_LEN + format.data_field_len();
+        let ocf = format.ocf.then(|| {
+            [
+                raw[data_end],
+                raw[data_end + 1],
+                raw[data_end + 2],
+                raw[data_end + 3],
+            ]
+        });
+
+        Ok(Self {
+            version,
+            scid: (id >> 4) & 0x3FF,
+            vcid: ((id >> 1) & 0x07) as u8,
+            mc_count: raw[2],
+            vc_count: raw[3],
+            first_header_pointer: status & 0x7FF,
+            payload: raw[PRIMARY_HEADER_LEN..data_end].to_vec(),
+            ocf,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn round_trip_and_fecf() {
+        let format = FrameFormat {
+            frame_len: 64,
+            ocf: true,
+            fecf: true,
+        };
+        let mut frame = Frame::build(0x042, 7, 9, b"hello");
+        frame.mc_count = 200;
+        frame.ocf = Some([1, 2, 3, 4]);
+        let mut raw = frame.to_bytes(&format).unwrap();
+        assert_eq!(raw.len(), 64);
+
+        let back = Frame::parse(&raw, &format).unwrap();
+        assert_eq!(
+            (back.scid, back.vcid, back.mc_count, back.vc_count),
+            (0x042, 7, 200, 9)
+        );
+        assert_eq!(&back.payload[..5], b"hello");
+        assert_eq!(back.payload.len(), format.data_field_len());
+        assert_eq!(back.ocf, Some([1, 2, 3, 4]));
+
+        raw[20] ^= 0x10;
+        assert!(Frame::parse(&raw, &format).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+//! Guide star centroiding
+//! Background level and noise come from the frame median and MAD, so the
+//! dark frame only has to take out hot pixels and amp glow. The brightest
+//! pixel above `threshold_sigma` with at least two lit neighbours seeds a
+//! window; inside it the background-subtracted pixels above threshold give an
+//! intensity-weighted centroid, optionally refined by a least-squares
+//! Gaussian fit to their logarithm. Pixel centres sit on integer coordinates.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::frame::Frame;
+
+/// FWHM of a Gaussian over its sigma
+const FWHM_PER_SIGMA: f64 = 2.354_820_045;
+/// Background statistics use at most this many pixels
+const BACKGROUND_SAMPLES: usize = 65_536;
+
+#[derive(Debug, Clone)]
+pub struct CentroidConfig {
+    /// Detection threshold above background, in noise sigmas
+    pub threshold_sigma: f64,
+    /// Half-size of the square window around the peak, pixels
+    pub radius: usize,
+    /// Fewer lit pixels than this is noise, not a star
+    pub min_pixels: usize,
+    /// Refine with a Gaussian fit instead of plain moments
+    pub gaussian: bool,
+}
+
+impl Default for CentroidConfig {
+    fn default() -> Self {
+        Self {
+            threshold_sigma: 5.0,
+            radius: 12,
+            min_pixels: 3,
+            gaussian: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Centroid {
+    pub x: f64,
+    pub y: f64,
+    /// Full width at half maximum, pixels
+    pub fwhm: f64,
+    /// Background-subtracted peak, ADU
+    pub peak: f64,
+    /// Background-subtracted sum over the lit pixels, ADU
+    pub flux: f64,
+    pub snr: f64,
+    pub pixels: usize,
+}
+
+/// Centroid telemetry in integer units: centipixels (cpx) and
+/// centi-arcseconds (cas)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CentroidTelemetry {
+    pub centroid_x_cpx: i32,
+    pub centroid_y_cpx: i32,
+    pub error_x_cpx: i32,
+    pub error_y_cpx: i32,
+    pub fwhm_cas: u32,
+    pub snr: f32,
+}
+
+impl Centroid {
+    /// Offset from the lock position, pixels
+    pub fn error(&self, reference: (f64, f64)) -> (f64, f64) {
+        (self.x - reference.0, self.y - reference.1)
+    }
+
+    /// `plate_scale` in arcseconds per pixel
+    pub fn telemetry(&self, reference: (f64, f64), plate_scale: f64) -> CentroidTelemetry {
+        let (ex, ey) = self.error(reference);
+        let cpx = |v: f64| (v * 100.0).round() as i32;
+        CentroidTelemetry {
+            centroid_x_cpx: cpx(self.x),
+            centroid_y_cpx: cpx(self.y),
+            error_x_cpx: cpx(ex),
+            error_y_cpx: cpx(ey),
+            fwhm_cas: (self.fwhm * plate_scale * 100.0).round() as u32,
+            snr: self.snr as f32,
+        }
+    }
+}
+
+/// Median level and MAD-based noise sigma
+pub fn background(frame: &Frame) -> (f64, f64) {
+    let step = frame.pixels.len().div_ceil(BACKGROUND_SAMPLES).max(1);
+    let mut samples: Vec<f32> = frame.pixels.iter().step_by(step).copied().collect();
+    if samples.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mid = samples.len() / 2;
+    let median = *samples.select_nth_unstable_by(mid, f32::total_cmp).1;
+    let mut deviations: Vec<f32> = samples.iter().map(|s| (s - median).abs()).collect();
+    let mad = *deviations.select_nth_unstable_by(mid, f32::total_cmp).1;
+    (median as f64, 1.4826 * mad as f64)
+}
+
+pub fn centroid(frame: &Frame, config: &CentroidConfig) -> Result<Centroid> {
+    let (level, noise) = background(frame);
+    let threshold = level + config.threshold_sigma * noise;
+    let lit = |x: usize, y: usize| frame.get(x, y) as f64 > threshold;
+
+    // Brightest pixel that isn't a lone hot pixel
+    let mut seed: Option<(usize, usize, f64)> = None;
+    for y in 1..frame.height.saturating_sub(1) {
+        for x in 1..frame.width.saturating_sub(1) {
+            let v = frame.get(x, y) as f64;
+            if v <= threshold || seed.is_some_and(|(_, _, best)| v <= best) {
+                continue;
+            }
+            let neighbours = [(0, 1), (2, 1), (1, 0), (1, 2)]
+                .iter()
+                .filter(|&&(dx, dy)| lit(x + dx - 1, y + dy - 1))
+                .count();
+            if neighbours >= 2 {
+                seed = Some((x, y, v));
+            }
+        }
+    }
+    let Some((px, py, _)) = seed else {
+        bail!("no star above {} sigma", config.threshold_sigma);
+    };
+
+    let x0 = px.saturating_sub(config.radius);
+    let y0 = py.saturating_sub(config.radius);
+    let x1 = (px + config.radius).min(frame.width - 1);
+    let y1 = (py + config.radius).min(frame.height - 1);
+    let mut lit_pixels = Vec::new();
+    for y in y0..=y1 {
+        for x in x0..=x1 {
+            if lit(x, y) {
+                let dx = x as f64 - px as f64;
+                let dy = y as f64 - py as f64;
+                lit_pixels.push((dx, dy, frame.get(x, y) as f64 - level));
+            }
+        }
+    }
+    if lit_pixels.len() < config.min_pixels {
+        bail!(
+            "{} pixels above threshold, need {}",
+            lit_pixels.len(),
+            config.min_pixels
+        );
+    }
+
+    let flux: f64 = lit_pixels.iter().map(|p| p.2).sum();
+    let peak = lit_pixels.iter().map(|p| p.2).fold(0.0, f64::max);
+    let mut cx = lit_pixels.iter().map(|p| p.0 * p.2).sum::<f64>() / flux;
+    let mut cy = lit_pixels.iter().map(|p| p.1 * p.2).sum::<f64>() / flux;
+    // Area above half maximum, as a circle
+    let half = lit_pixels.iter().filter(|p| p.2 >= peak / 2.0).count();
+    let mut fwhm = 2.0 * (half as f64 / std::f64::consts::PI).sqrt();
+
+    if config.gaussian {
+        if let Some((gx, gy, sigma)) = fit_gaussian(&lit_pixels) {
+            // A fit that wanders off the window is worse than the moments
+            if gx.abs() <= config.radius as f64 && gy.abs() <= config.radius as f64 {
+                (cx, cy, fwhm) = (gx, gy, FWHM_PER_SIGMA * sigma);
+            }
+        }
+    }
+
+    Ok(Centroid {
+        x: px as f64 + cx,
+        y: py as f64 + cy,
+        fwhm,
+        peak,
+        flux,
+        snr: flux / (noise.max(f64::EPSILON) * (lit_pixels.len() as f64).sqrt()),
+        pixels: lit_pixels.len(),
+    })
+}
+
+/// Circular Gaussian through `(dx, dy, value)`: ln v = a + b dx + c dy +
+/// d r^2, weighted by v^2 to undo the log's noise stretching. Returns the
+/// centre and sigma
+fn fit_gaussian(pixels: &[(f64, f64, f64)]) -> Option<(f64, f64, f64)> {
+    let mut normal = [[0.0f64; 5]; 4];
+    for &(dx, dy, v) in pixels {
+        let basis = [1.0, dx, dy, dx * dx + dy * dy];
+        let w = v * v;
+        let z = v.ln();
+        for (row, bi) in normal.iter_mut().zip(basis) {
+            for (cell, bj) in row.iter_mut().zip(basis) {
+                *cell += w * bi * bj;
+            }
+            row[4] += w * bi * z;
+        }
+    }
+    let [_, b, c, d] = solve(normal)?;
+    if d >= 0.0 {
+        return None;
+    }
+    Some((-b / (2.0 * d), -c / (2.0 * d), (-1.0 / (2.0 * d)).sqrt()))
+}
+
+/// Gaussian elimination with partial pivoting on an augmented 4x5 system
+fn solve(mut m: [[f64; 5]; 4]) -> Option<[f64; 4]> {
+    for col in 0..4 {
+        let pivot = (col..4).max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))?;
+        if m[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot);
+        let pivot_row = m[col];
+        for (r, row) in m.iter_mut().enumerate() {
+            if r != col {
+                let f = row[col] / pivot_row[col];
+                for (cell, p) in row.iter_mut().zip(pivot_row).skip(col) {
+                    *cell -= f * p;
+                }
+            }
+        }
+    }
+    Some([0, 1, 2, 3].map(|i| m[i][4] / m[i][i]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::to_fits;
+
+    /// Gaussian star on a noisy background, through a FITS round trip
+    fn star(x: f64, y: f64, sigma: f64) -> Frame {
+        let (width, height) = (64, 48);
+        let mut state = 0x2545_f491_u32;
+        let mut pixels = Vec::with_capacity(width * height);
+        for j in 0..height {
+            for i in 0..width {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let noise = (state >> 8) as f64 / (1u32 << 24) as f64 * 20.0 - 10.0;
+                let r2 = (i as f64 - x).powi(2) + (j as f64 - y).powi(2);
+                let v = 1000.0 + noise + 20_000.0 * (-r2 / (2.0 * sigma * sigma)).exp();
+                pixels.push(v as f32);
+            }
+        }
+        Frame::from_fits(&to_fits(&Frame::new(width, height, pixels).unwrap())).unwrap()
+    }
+
+    #[test]
+    fn weighted_and_gaussian_centroids() {
+        let mut frame = star(30.3, 20.7, 1.5);
+        // Hot pixel brighter than the star is not taken as the seed
+        frame.pixels[5 * 64 + 5] = 60_000.0;
+        let expected_fwhm = FWHM_PER_SIGMA * 1.5;
+
+        let moments = centroid(&frame, &CentroidConfig::default()).unwrap();
+        assert!((moments.x - 30.3).abs() < 0.05, "{:?}", moments);
+        assert!((moments.y - 20.7).abs() < 0.05, "{:?}", moments);
+        assert!((moments.fwhm - expected_fwhm).abs() < 0.5, "{:?}", moments);
+        assert!(moments.snr > 100.0);
+
+        let config = CentroidConfig {
+            gaussian: true,
+            ..CentroidConfig::default()
+        };
+        let fit = centroid(&frame, &config).unwrap();
+        assert!((fit.x - 30.3).abs() < 0.02, "{:?}", fit);
+        assert!((fit.y - 20.7).abs() < 0.02, "{:?}", fit);
+        assert!((fit.fwhm - expected_fwhm).abs() < 0.05, "{:?}", fit);
+
+        let telemetry = fit.telemetry((30.0, 21.0), 1.2);
+        assert_eq!(telemetry.error_x_cpx, 30);
+        assert_eq!(telemetry.error_y_cpx, -30);
+        assert!((telemetry.fwhm_cas as f64 - expected_fwhm * 120.0).abs() < 10.0);
+    }
+
+    #[test]
+    fn empty_frame_has_no_star() {
+        let frame = Frame::new(32, 32, vec![500.0; 32 * 32]).unwrap();
+        assert!(centroid(&frame, &CentroidConfig::default()).is_err());
+    }
+}
@@ -0,0 +1,391 @@
+//! Async INDI client
+//! A background task owns the TCP connection: it writes queued client
+//! messages, folds server XML into the shared `Devices` registry and
+//! broadcasts an `Event` per change. When the server goes away it reconnects
+//! with exponential backoff, asks for all properties again and replays the
+//! `enableBLOB` settings. Messages queued while disconnected are dropped
+//! rather than delivered late; for mount corrections late is worse than lost.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+
+use crate::device::{ConnectionState, Devices, Event};
+use crate::property::Property;
+use crate::protocol::{self, BlobMode};
+use crate::xml::{Element, XmlStream};
+
+const EVENT_CAPACITY: usize = 1024;
+const READ_BUFFER: usize = 64 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// `host:port` of the INDI server
+    pub addr: String,
+    pub reconnect_delay: Duration,
+    pub max_reconnect_delay: Duration,
+}
+
+impl ClientConfig {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            reconnect_delay: Duration::from_millis(500),
+            max_reconnect_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+pub struct IndiClient {
+    outgoing: mpsc::UnboundedSender<Element>,
+    events: broadcast::Sender<Event>,
+    devices: Arc<RwLock<Devices>>,
+    state: watch::Receiver<ConnectionState>,
+    task: JoinHandle<()>,
+}
+
+impl IndiClient {
+    /// Start connecting in the background; needs a running tokio runtime
+    pub fn connect(config: ClientConfig) -> Self {
+        let (outgoing, queue) = mpsc::unbounded_channel();
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let devices = Arc::new(RwLock::new(Devices::default()));
+        let (state_tx, state) = watch::channel(ConnectionState::Connecting);
+        let connection = Connection {
+            config,
+            queue,
+            events: events.clone(),
+            devices: devices.clone(),
+            state: state_tx,
+            blob_modes: BTreeMap::new(),
+        };
+        Self {
+            outgoing,
+            events,
+            devices,
+            state,
+            task: tokio::spawn(connection.run()),
+        }
+    }
+
+    /// Events from now on; a receiver that falls more than
+    /// `EVENT_CAPACITY` behind sees `Lagged` and should re-read `devices`
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.events.subscribe()
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        *self.state.borrow()
+    }
+
+    pub async fn wait_connected(&self, timeout: Duration) -> Result<()> {
+        let mut state = self.state.clone();
+        tokio::time::timeout(
+            timeout,
+            state.wait_for(|s| *s == ConnectionState::Connected),
+        )
+        .await
+        .map_err(|_| anyhow!("not connected after {:?}", timeout))??;
+        Ok(())
+    }
+
+    /// Snapshot of everything discovered so far
+    pub fn devices(&self) -> Devices {
+        self.devices.read().expect("devices lock").clone()
+    }
+
+    pub fn property(&self, device: &str, name: &str) -> Option<Arc<Property>> {
+        self.devices
+            .read()
+            .expect("devices lock")
+            .property(device, name)
+    }
+
+    /// The property once the server has defined it
+    pub async fn wait_property(
+        &self,
+        device: &str,
+        name: &str,
+        timeout: Duration,
+    ) -> Result<Arc<Property>> {
+        let mut events = self.subscribe();
+        if let Some(property) = self.property(device, name) {
+            return Ok(property);
+        }
+        let wait = async {
+            loop {
+                match events.recv().await {
+                    Ok(Event::Defined(p)) if p.device == device && p.name == name => return Ok(p),
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        if let Some(property) = self.property(device, name) {
+                            return Ok(property);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => bail!("client closed"),
+                }
+            }
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| anyhow!("{}.{} not defined after {:?}", device, name, timeout))?
+    }
+
+    /// Queue a raw client message
+    pub fn send(&self, element: Element) -> Result<()> {
+        self.outgoing
+            .send(element)
+            .map_err(|_| anyhow!("client closed"))
+    }
+
+    pub fn set_number(&self, device: &str, name: &str, values: &[(&str, f64)]) -> Result<()> {
+        self.send(protocol::new_number_vector(device, name, values))
+    }
+
+    pub fn set_switch(&self, device: &str, name: &str, values: &[(&str, bool)]) -> Result<()> {
+        self.send(protocol::new_switch_vector(device, name, values))
+    }
+
+    pub fn set_text(&self, device: &str, name: &str, values: &[(&str, &str)]) -> Result<()> {
+        self.send(protocol::new_text_vector(device, name, values))
+    }
+
+    /// Kept across reconnects
+    pub fn enable_blob(&self, device: &str, name: Option<&str>, mode: BlobMode) -> Result<()> {
+        self.send(protocol::enable_blob(device, name, mode))
+    }
+
+    /// Have the driver connect to (or release) its hardware
+    pub fn connect_device(&self, device: &str, connect: bool) -> Result<()> {
+        self.set_switch(
+            device,
+            "CONNECTION",
+            &[("CONNECT", connect), ("DISCONNECT", !connect)],
+        )
+    }
+}
+
+impl Drop for IndiClient {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct Connection {
+    config: ClientConfig,
+    queue: mpsc::UnboundedReceiver<Element>,
+    events: broadcast::Sender<Event>,
+    devices: Arc<RwLock<Devices>>,
+    state: watch::Sender<ConnectionState>,
+    /// Last `enableBLOB` per (device, property), replayed on reconnect
+    blob_modes: BTreeMap<(String, Option<String>), Element>,
+}
+
+impl Connection {
+    fn publish(&self, event: Event) {
+        // No subscribers is fine
+        let _ = self.events.send(event);
+    }
+
+    fn notify(&self, message: String) {
+        self.publish(Event::Message {
+            device: None,
+            message,
+        });
+    }
+
+    fn set_state(&self, state: ConnectionState) {
+        self.state.send_replace(state);
+        self.publish(Event::Connection(state));
+    }
+
+    fn remember(&mut self, element: &Element) {
+        if element.name == "enableBLOB" {
+            let key = (
+                element.get("device").unwrap_or("").to_string(),
+                element.get("name").map(String::from),
+            );
+            self.blob_modes.insert(key, element.clone());
+        }
+    }
+
+    async fn run(mut self) {
+        let mut delay = self.config.reconnect_delay;
+        loop {
+            self.set_state(ConnectionState::Connecting);
+            match TcpStream::connect(&self.config.addr).await {
+                Ok(stream) => {
+                    delay = self.config.reconnect_delay;
+                    self.set_state(ConnectionState::Connected);
+                    match self.session(stream).await {
+                        // The client was dropped
+                        Ok(()) => return,
+                        Err(e) => {
+                            self.notify(format!("connection to {} lost: {e:#}", self.config.addr))
+                        }
+                    }
+                }
+                Err(e) => self.notify(format!("connect to {} failed: {e}", self.config.addr)),
+            }
+            self.set_state(ConnectionState::Disconnected);
+
+            // The server defines everything again after a reconnect
+            let dropped: Vec<String> = {
+                let mut devices = self.devices.write().expect("devices lock");
+                let names = devices.iter().map(|d| d.name.clone()).collect();
+                devices.clear();
+                names
+            };
+            for device in dropped {
+                self.publish(Event::Deleted { device, name: None });
+            }
+
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(self.config.max_reconnect_delay);
+            loop {
+                match self.queue.try_recv() {
+                    Ok(element) => self.remember(&element),
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => return,
+                }
+            }
+        }
+    }
+
+    /// Serve one connection until it fails (`Err`) or the client is dropped
+    async fn session(&mut self, stream: TcpStream) -> Result<()> {
+        stream.set_nodelay(true)?;
+        let (mut reader, mut writer) = stream.into_split();
+        let mut hello = protocol::get_properties(None, None).to_xml();
+        for element in self.blob_modes.values() {
+            hello.push_str(&element.to_xml());
+        }
+        writer.write_all(hello.as_bytes()).await?;
+
+        let mut parser = XmlStream::default();
+        let mut buf = vec![0u8; READ_BUFFER];
+        loop {
+            tokio::select! {
+                read = reader.read(&mut buf) => {
+                    let n = read?;
+                    if n == 0 {
+                        bail!("server closed the connection");
+                    }
+                    let errors = parser.errors;
+                    for element in parser.push(&buf[..n]) {
+                        self.dispatch(&element);
+                    }
+                    if parser.errors > errors {
+                        let error = parser.last_error.clone().unwrap_or_default();
+                        self.notify(format!("skipped malformed XML: {}", error));
+                    }
+                }
+                element = self.queue.recv() => {
+                    let Some(element) = element else {
+                        return Ok(());
+                    };
+                    self.remember(&element);
+                    writer.write_all(element.to_xml().as_bytes()).await?;
+                }
+            }
+        }
+    }
+
+    fn dispatch(&self, element: &Element) {
+        let event = protocol::parse(element).and_then(|message| match message {
+            Some(message) => self
+                .devices
+                .write()
+                .expect("devices lock")
+                .apply(message)
+                .map(Some),
+            None => Ok(None),
+        });
+        match event {
+            Ok(Some(event)) => self.publish(event),
+            Ok(None) => {}
+            Err(e) => self.notify(format!("<{}>: {e:#}", element.name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    async fn read_until(stream: &mut TcpStream, needle: &str) -> String {
+        let mut seen = String::new();
+        let mut buf = [0u8; 4096];
+        while !seen.contains(needle) {
+            let n = stream.read(&mut buf).await.unwrap();
+            assert!(n > 0, "client closed before sending {}", needle);
+            seen.push_str(&String::from_utf8_lossy(&buf[..n]));
+        }
+        seen
+    }
+
+    #[tokio::test]
+    async fn discovers_updates_and_reconnects() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut config = ClientConfig::new(listener.local_addr().unwrap().to_string());
+        config.reconnect_delay = Duration::from_millis(10);
+        let client = IndiClient::connect(config);
+        let mut events = client.subscribe();
+
+        let (mut server, _) = listener.accept().await.unwrap();
+        read_until(&mut server, "<getProperties").await;
+        server
+            .write_all(
+                br#"<defNumberVector device="Mount" name="GUIDE_RATE" state="Idle" perm="rw">
+                    <defNumber name="GUIDE_RATE_WE" min="0" max="1" step="0.1">0.5</defNumber>
+                </defNumberVector>"#,
+            )
+            .await
+            .unwrap();
+        let property = client
+            .wait_property("Mount", "GUIDE_RATE", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(property.number("GUIDE_RATE_WE"), Some(0.5));
+
+        client
+            .set_number("Mount", "GUIDE_RATE", &[("GUIDE_RATE_WE", 0.3)])
+            .unwrap();
+        client
+            .enable_blob("Guide CCD", None, BlobMode::Also)
+            .unwrap();
+        let sent = read_until(&mut server, "</enableBLOB>").await;
+        assert!(sent.contains(r#"<oneNumber name="GUIDE_RATE_WE">0.3</oneNumber>"#));
+
+        server
+            .write_all(
+                br#"<setNumberVector device="Mount" name="GUIDE_RATE" state="Ok">
+                    <oneNumber name="GUIDE_RATE_WE">0.3</oneNumber>
+                </setNumberVector>"#,
+            )
+            .await
+            .unwrap();
+        loop {
+            if let Event::Updated(p) = events.recv().await.unwrap() {
+                assert_eq!(p.number("GUIDE_RATE_WE"), Some(0.3));
+                break;
+            }
+        }
+
+        // Server restart: registry cleared, properties requested again and
+        // the BLOB mode replayed
+        drop(server);
+        let (mut server, _) = listener.accept().await.unwrap();
+        let hello = read_until(&mut server, "</enableBLOB>").await;
+        assert!(hello.starts_with("<getProperties"));
+        assert!(client.property("Mount", "GUIDE_RATE").is_none());
+        client.wait_connected(Duration::from_secs(5)).await.unwrap();
+    }
+}
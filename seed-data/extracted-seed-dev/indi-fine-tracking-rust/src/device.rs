@@ -0,0 +1,193 @@
+//! Devices and their properties, as discovered from the server
+//! `Devices::apply` folds each `Message` into the registry and reports what
+//! changed as an `Event`, which the client fans out to subscribers.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+
+use crate::property::Property;
+use crate::protocol::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    Disconnected,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Defined(Arc<Property>),
+    Updated(Arc<Property>),
+    /// Whole device when `name` is `None`
+    Deleted {
+        device: String,
+        name: Option<String>,
+    },
+    Message {
+        device: Option<String>,
+        message: String,
+    },
+    /// Client connection to the server; definitions are resent after a
+    /// reconnect
+    Connection(ConnectionState),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Device {
+    pub name: String,
+    pub properties: BTreeMap<String, Arc<Property>>,
+}
+
+impl Device {
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.get(name).map(|p| p.as_ref())
+    }
+
+    /// Driver connected to its hardware (standard CONNECTION switch)
+    pub fn is_connected(&self) -> bool {
+        self.property("CONNECTION")
+            .and_then(|p| p.switch("CONNECT"))
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Devices {
+    devices: BTreeMap<String, Device>,
+}
+
+impl Devices {
+    pub fn get(&self, device: &str) -> Option<&Device> {
+        self.devices.get(device)
+    }
+
+    pub fn property(&self, device: &str, name: &str) -> Option<Arc<Property>> {
+        self.devices.get(device)?.properties.get(name).cloned()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Device> {
+        self.devices.values()
+    }
+
+    pub fn clear(&mut self) {
+        self.devices.clear();
+    }
+
+    /// Fold `message` in; `Err` for updates to properties never defined
+    pub fn apply(&mut self, message: Message) -> Result<Event> {
+        Ok(match message {
+            Message::Define(property) => {
+                let device = self
+                    .devices
+                    .entry(property.device.clone())
+                    .or_insert_with(|| Device {
+                        name: property.device.clone(),
+                        ..Device::default()
+                    });
+                let property = Arc::new(property);
+                device
+                    .properties
+                    .insert(property.name.clone(), property.clone());
+                Event::Defined(property)
+            }
+            Message::Set(update) => {
+                let Some(slot) = self
+                    .devices
+                    .get_mut(&update.device)
+                    .and_then(|d| d.properties.get_mut(&update.name))
+                else {
+                    bail!("update for undefined {}.{}", update.device, update.name);
+                };
+                // Copy-on-write: subscribers may still hold the previous value
+                Arc::make_mut(slot).apply(&update)?;
+                Event::Updated(slot.clone())
+            }
+            Message::Delete { device, name, .. } => {
+                match &name {
+                    Some(name) => {
+                        if let Some(d) = self.devices.get_mut(&device) {
+                            d.properties.remove(name);
+                        }
+                    }
+                    None => {
+                        self.devices.remove(&device);
+                    }
+                }
+                Event::Deleted { device, name }
+            }
+            Message::Message {
+                device, message, ..
+            } => Event::Message { device, message },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::parse;
+    use crate::xml::XmlStream;
+
+    fn apply(devices: &mut Devices, xml: &str) -> Result<Event> {
+        let element = XmlStream::default().push(xml.as_bytes()).remove(0);
+        devices.apply(parse(&element)?.expect("server message"))
+    }
+
+    #[test]
+    fn discovery_update_and_delete() {
+        let mut devices = Devices::default();
+        apply(
+            &mut devices,
+            r#"<defSwitchVector device="Mount" name="CONNECTION" state="Idle" perm="rw" rule="OneOfMany">
+                <defSwitch name="CONNECT">Off</defSwitch><defSwitch name="DISCONNECT">On</defSwitch>
+            </defSwitchVector>"#,
+        )
+        .unwrap();
+        let defined = apply(
+            &mut devices,
+            r#"<defNumberVector device="Mount" name="GUIDE_RATE" state="Ok" perm="rw">
+                <defNumber name="GUIDE_RATE_WE" min="0" max="1" step="0.1">0.5</defNumber>
+            </defNumberVector>"#,
+        )
+        .unwrap();
+        assert!(!devices.get("Mount").unwrap().is_connected());
+
+        let Event::Defined(before) = defined else {
+            panic!("expected a definition");
+        };
+        let Event::Updated(after) = apply(
+            &mut devices,
+            r#"<setNumberVector device="Mount" name="GUIDE_RATE"><oneNumber name="GUIDE_RATE_WE">0.3</oneNumber></setNumberVector>"#,
+        )
+        .unwrap() else {
+            panic!("expected an update");
+        };
+        assert_eq!(before.number("GUIDE_RATE_WE"), Some(0.5));
+        assert_eq!(after.number("GUIDE_RATE_WE"), Some(0.3));
+
+        apply(
+            &mut devices,
+            r#"<setSwitchVector device="Mount" name="CONNECTION"><oneSwitch name="CONNECT">On</oneSwitch></setSwitchVector>"#,
+        )
+        .unwrap();
+        assert!(devices.get("Mount").unwrap().is_connected());
+
+        assert!(apply(
+            &mut devices,
+            r#"<setNumberVector device="Guide CCD" name="CCD_EXPOSURE"><oneNumber name="CCD_EXPOSURE_VALUE">1</oneNumber></setNumberVector>"#,
+        )
+        .is_err());
+
+        apply(
+            &mut devices,
+            r#"<delProperty device="Mount" name="GUIDE_RATE"/>"#,
+        )
+        .unwrap();
+        assert!(devices.property("Mount", "GUIDE_RATE").is_none());
+        apply(&mut devices, r#"<delProperty device="Mount"/>"#).unwrap();
+        assert_eq!(devices.iter().count(), 0);
+    }
+}
@@ -0,0 +1,209 @@
+//! Guide frames
+//! INDI cameras deliver exposures as FITS BLOBs. Only what guiding needs is
+//! read: the primary image (first plane of a cube), any integer or float
+//! BITPIX, with BZERO/BSCALE applied. Compressed `.fits.z` BLOBs are rejected;
+//! turn compression off on the camera driver.
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::property::Blob;
+
+const BLOCK: usize = 2880;
+const CARD: usize = 80;
+
+/// Monochrome image in ADU, row-major, pixel (x, y) at `y * width + x`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<f32>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, pixels: Vec<f32>) -> Result<Self> {
+        if pixels.len() != width * height {
+            bail!("{} pixels for a {}x{} frame", pixels.len(), width, height);
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> f32 {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn from_blob(blob: &Blob) -> Result<Self> {
+        match blob.format.as_str() {
+            ".fits" | ".fit" | ".fts" => Self::from_fits(&blob.data),
+            other => bail!("unsupported guide frame format '{}'", other),
+        }
+    }
+
+    pub fn from_fits(data: &[u8]) -> Result<Self> {
+        let mut header = Header::default();
+        let mut offset = 0;
+        'blocks: loop {
+            let block = data
+                .get(offset..offset + BLOCK)
+                .ok_or_else(|| anyhow!("FITS header without END"))?;
+            offset += BLOCK;
+            for card in block.chunks(CARD) {
+                let card = std::str::from_utf8(card)?;
+                if card.trim_end() == "END" {
+                    break 'blocks;
+                }
+                header.card(card)?;
+            }
+        }
+
+        let (width, height) = match header.naxis.as_slice() {
+            [w, h] | [w, h, _] => (*w, *h),
+            other => bail!("FITS image with NAXIS {:?}", other),
+        };
+        if header.bitpix == 0 {
+            bail!("FITS header without BITPIX");
+        }
+        let size = header.bitpix.unsigned_abs() as usize / 8;
+        let len = width * height;
+        let raw = data
+            .get(offset..offset + len * size)
+            .ok_or_else(|| anyhow!("FITS data truncated"))?;
+        let (bzero, bscale) = (header.bzero, header.bscale);
+        let pixels = raw
+            .chunks_exact(size)
+            .map(|b| {
+                let v = match header.bitpix {
+                    8 => b[0] as f64,
+                    16 => i16::from_be_bytes([b[0], b[1]]) as f64,
+                    32 => i32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    -32 => f32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    _ => f64::from_be_bytes(b.try_into().expect("8-byte chunk")),
+                };
+                (bzero + bscale * v) as f32
+            })
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    /// Remove a dark (or bias) frame of the same size and exposure
+    pub fn subtract(&mut self, dark: &Frame) -> Result<()> {
+        if (dark.width, dark.height) != (self.width, self.height) {
+            bail!(
+                "{}x{} dark for a {}x{} frame",
+                dark.width,
+                dark.height,
+                self.width,
+                self.height
+            );
+        }
+        for (p, d) in self.pixels.iter_mut().zip(&dark.pixels) {
+            *p -= d;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Header {
+    bitpix: i32,
+    naxis: Vec<usize>,
+    bzero: f64,
+    bscale: f64,
+}
+
+impl Default for Header {
+    fn default() -> Self {
+        Self {
+            bitpix: 0,
+            naxis: Vec::new(),
+            bzero: 0.0,
+            bscale: 1.0,
+        }
+    }
+}
+
+impl Header {
+    fn card(&mut self, card: &str) -> Result<()> {
+        let Some((key, value)) = card.split_once('=') else {
+            return Ok(());
+        };
+        // Strip the comment; the numeric values used here are never quoted
+        let value = value.split('/').next().unwrap_or("").trim();
+        let number = || -> Result<f64> {
+            value
+                .parse()
+                .map_err(|_| anyhow!("FITS {} = '{}'", key.trim(), value))
+        };
+        match key.trim() {
+            "BITPIX" => {
+                self.bitpix = number()? as i32;
+                if ![8, 16, 32, -32, -64].contains(&self.bitpix) {
+                    bail!("unsupported BITPIX {}", self.bitpix);
+                }
+            }
+            "NAXIS" => self.naxis = vec![0; number()? as usize],
+            "BZERO" => self.bzero = number()?,
+            "BSCALE" => self.bscale = number()?,
+            key => {
+                if let Some(axis) = key.strip_prefix("NAXIS") {
+                    let axis: usize = axis.parse().unwrap_or(0);
+                    let slot = axis
+                        .checked_sub(1)
+                        .and_then(|i| self.naxis.get_mut(i))
+                        .ok_or_else(|| anyhow!("FITS {} before NAXIS", key))?;
+                    *slot = number()? as usize;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Minimal 16-bit FITS writer for test frames
+#[cfg(test)]
+pub fn to_fits(frame: &Frame) -> Vec<u8> {
+    let cards = [
+        "SIMPLE  =                    T".to_string(),
+        "BITPIX  =                   16".to_string(),
+        "NAXIS   =                    2".to_string(),
+        format!("NAXIS1  = {:>20}", frame.width),
+        format!("NAXIS2  = {:>20}", frame.height),
+        "BZERO   =                32768 / unsigned 16-bit".to_string(),
+        "END".to_string(),
+    ];
+    let mut out: Vec<u8> = cards
+        .iter()
+        .flat_map(|c| format!("{:<80}", c).into_bytes())
+        .collect();
+    out.resize(out.len().div_ceil(BLOCK) * BLOCK, b' ');
+    for &p in &frame.pixels {
+        let v = (p.round().clamp(0.0, 65535.0) as i32 - 32768) as i16;
+        out.extend_from_slice(&v.to_be_bytes());
+    }
+    out.resize(out.len().div_ceil(BLOCK) * BLOCK, 0);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_round_trip_and_dark() {
+        let frame = Frame::new(3, 2, vec![0.0, 1.0, 2.0, 1000.0, 40000.0, 65535.0]).unwrap();
+        let mut read = Frame::from_fits(&to_fits(&frame)).unwrap();
+        assert_eq!(read, frame);
+        assert_eq!(read.get(2, 1), 65535.0);
+
+        let dark = Frame::new(3, 2, vec![1.0; 6]).unwrap();
+        read.subtract(&dark).unwrap();
+        assert_eq!(read.get(1, 0), 0.0);
+        assert!(read
+            .subtract(&Frame::new(2, 3, vec![0.0; 6]).unwrap())
+            .is_err());
+        assert!(Frame::from_fits(&to_fits(&frame)[..BLOCK]).is_err());
+    }
+}
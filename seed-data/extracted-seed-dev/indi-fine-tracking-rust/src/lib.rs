@@ -0,0 +1,23 @@
+//! INDI client for the fine-tracking loop
+//! XML over TCP (port 7624): `xml` splits the element stream, `protocol`
+//! maps elements to messages, `property` and `device` hold what the server
+//! has defined, and `client` owns the connection and fans out events.
+//! `tracking` turns guide errors into mount rate commands.
+//! With the `vision` feature, `frame` decodes FITS guide frames and
+//! `centroid` locates the guide star in them.
+
+#[cfg(feature = "vision")]
+pub mod centroid;
+pub mod client;
+pub mod device;
+#[cfg(feature = "vision")]
+pub mod frame;
+pub mod property;
+pub mod protocol;
+pub mod tracking;
+pub mod xml;
+
+pub use client::{ClientConfig, IndiClient};
+pub use device::{ConnectionState, Device, Devices, Event};
+pub use property::{Members, Property, PropertyState};
+pub use protocol::BlobMode;
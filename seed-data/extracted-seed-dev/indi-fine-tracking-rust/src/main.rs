@@ -1,36 +1,147 @@
 //! INDI Fine-Tracking Loop (Scaffold)
-//! - Connects to INDI server (TCP 7624)
-//! - Reads guide camera stream (stub)
-//! - Computes centroid (stub unless built with `--features vision`)
-//! - Sends mount corrections (RA/DEC rate) at 50-200 Hz (stub)
+//! - Connects to INDI server (TCP 7624) and lists devices and properties
+//! - Requests guide camera exposures as BLOBs
+//! - Computes centroid and FWHM per frame (with `--features vision`)
+//! - Sends PID mount corrections (RA/DEC track rate) at 50-200 Hz (with `--features vision`)
 
 use anyhow::*;
-use tokio::net::TcpStream;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::time::Duration;
+
+use indi_fine_tracking::{BlobMode, ClientConfig, Event, IndiClient, Members};
+#[cfg(feature = "vision")]
+use indi_fine_tracking::{centroid::{centroid, CentroidConfig, CentroidTelemetry}, frame::Frame, property::Blob, tracking::{Correction, Tracker, TrackingConfig}, xml::Element};
+#[cfg(feature = "vision")]
+use std::time::Instant;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let indi_host = std::env::var("INDI_HOST").unwrap_or("127.0.0.1".into());
     let indi_port = std::env::var("INDI_PORT").unwrap_or("7624".into());
+    let watch_secs: u64 = env_or("INDI_WATCH_SECS", 10);
     let addr = format!("{indi_host}:{indi_port}");
     println!("Connecting to INDI at {addr}...");
-    let mut stream = TcpStream::connect(addr).await?;
+    let client = IndiClient::connect(ClientConfig::new(addr));
+    client.wait_connected(Duration::from_secs(10)).await?;
+    let mut events = client.subscribe();
 
-    // Minimal handshake (INDI XML protocol). Real implementation will parse/emit XML elements.
-    // Send getProperties to enumerate devices
-    let get_props = r#"<getProperties version='1.7'/>"#;
-    stream.write_all(get_props.as_bytes()).await?;
+    // Give the server a moment to send its definitions
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    for device in client.devices().iter() {
+        println!("{} (connected={})", device.name, device.is_connected());
+        for property in device.properties.values() {
+            println!("  [{}] {} {} x{} {:?}", property.group, property.name, property.members.kind(), property.members.len(), property.state);
+        }
+    }
 
-    let mut buf = vec![0u8; 4096];
-    let n = stream.read(&mut buf).await?;
-    println!("INDI replied: {} bytes", n);
+    // Guide frames arrive as BLOBs on the camera device
+    let camera = std::env::var("INDI_GUIDE_CAMERA").ok();
+    // 10 ms exposures keep the loop near 100 Hz
+    let exposure: f64 = env_or("INDI_EXPOSURE", 0.01);
+    if let Some(camera) = &camera {
+        client.enable_blob(camera, None, BlobMode::Also)?;
+        client.set_number(camera, "CCD_EXPOSURE", &[("CCD_EXPOSURE_VALUE", exposure)])?;
+    }
+    #[cfg(feature = "vision")]
+    let mut guider = Guider::from_env()?;
+    #[cfg(feature = "vision")]
+    if camera.is_some() {
+        // Rate commands only take effect in custom track mode
+        client.set_switch(&guider.tracker.config.mount, "TELESCOPE_TRACK_MODE", &[("TRACK_CUSTOM", true)])?;
+    }
 
-    // -------- Tracking loop (stub) --------
+    // -------- Tracking loop --------
     // 1) Acquire guide image -> compute centroid (cx, cy)
     // 2) PID -> convert error to RA/DEC rates
-    // 3) Send <newNumberVector> with TELESCOPEx_RATE
-    // For now, just print placeholder.
-    println!("[stub] centroid=(0,0) err=(0,0) rates=(0,0)");
+    // 3) Send <newNumberVector> with TELESCOPE_TRACK_RATE
+    #[cfg(not(feature = "vision"))]
+    println!("[stub] centroid=(0,0) err=(0,0) rates=(0,0) (build with --features vision)");
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(watch_secs);
+    while let Some(event) = tokio::time::timeout_at(deadline, events.recv()).await.ok().and_then(|r| r.ok()) {
+        match event {
+            Event::Updated(p) if Some(&p.device) == camera.as_ref() && matches!(p.members, Members::Blob(_)) => {
+                // Next exposure first so it overlaps the processing
+                client.set_number(&p.device, "CCD_EXPOSURE", &[("CCD_EXPOSURE_VALUE", exposure)])?;
+                #[cfg(feature = "vision")]
+                if let Some(blob) = p.blob("CCD1") {
+                    let received = Instant::now();
+                    match guider.process(blob, received) {
+                        std::result::Result::Ok((centroid, correction, command)) => {
+                            if let Some(command) = command {
+                                client.send(command)?;
+                            }
+                            let latency_us = received.elapsed().as_micros() as u64;
+                            println!("{}", serde_json::json!({ "centroid": centroid, "loop": correction, "latency_us": latency_us }));
+                        }
+                        Err(e) => {
+                            println!("[guide] {e:#}");
+                            if let Some(command) = guider.tracker.lost(received) {
+                                client.send(command)?;
+                            }
+                        }
+                    }
+                }
+            }
+            #[cfg(feature = "vision")]
+            Event::Defined(p) | Event::Updated(p) if p.device == guider.tracker.config.mount && p.name == "EQUATORIAL_EOD_COORD" => {
+                if let Some(dec) = p.number("DEC") {
+                    guider.tracker.set_declination(dec);
+                }
+            }
+            Event::Defined(p) => println!("defined {}.{}", p.device, p.name),
+            Event::Updated(p) => println!("updated {}.{} {:?}", p.device, p.name, p.state),
+            Event::Deleted { device, name } => println!("deleted {device}.{}", name.unwrap_or("*".into())),
+            Event::Message { device, message } => println!("message {}: {message}", device.unwrap_or("server".into())),
+            Event::Connection(state) => println!("connection {state:?}"),
+        }
+    }
 
     Ok(())
 }
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key).ok().and_then(|s| s.parse().ok()).unwrap_or(default)
+}
+
+/// Dark subtraction, centroiding and mount correction for each guide frame;
+/// the first star found becomes the lock position
+#[cfg(feature = "vision")]
+struct Guider {
+    config: CentroidConfig,
+    dark: Option<Frame>,
+    tracker: Tracker,
+    reference: Option<(f64, f64)>,
+}
+
+#[cfg(feature = "vision")]
+impl Guider {
+    fn from_env() -> Result<Self> {
+        let dark = match std::env::var("INDI_DARK_FITS").ok() {
+            Some(path) => Some(Frame::from_fits(&std::fs::read(&path).with_context(|| format!("reading dark {path}"))?)?),
+            None => None,
+        };
+        let config = CentroidConfig { gaussian: std::env::var("INDI_CENTROID_GAUSSIAN").is_ok(), ..CentroidConfig::default() };
+        let defaults = TrackingConfig::default();
+        let tracker = Tracker::new(TrackingConfig {
+            mount: env_or("INDI_MOUNT", defaults.mount.clone()),
+            loop_hz: env_or("INDI_LOOP_HZ", defaults.loop_hz),
+            plate_scale: env_or("INDI_PLATE_SCALE", defaults.plate_scale),
+            camera_angle: env_or("INDI_CAMERA_ANGLE", defaults.camera_angle),
+            flipped: std::env::var("INDI_CAMERA_FLIPPED").is_ok(),
+            base_rate: (env_or("INDI_BASE_RATE_RA", defaults.base_rate.0), env_or("INDI_BASE_RATE_DEC", defaults.base_rate.1)),
+            ..defaults
+        })?;
+        Ok(Self { config, dark, tracker, reference: None })
+    }
+
+    fn process(&mut self, blob: &Blob, now: Instant) -> Result<(CentroidTelemetry, Correction, Option<Element>)> {
+        let mut frame = Frame::from_blob(blob)?;
+        if let Some(dark) = &self.dark {
+            frame.subtract(dark)?;
+        }
+        let star = centroid(&frame, &self.config)?;
+        let reference = *self.reference.get_or_insert((star.x, star.y));
+        let (correction, command) = self.tracker.step(star.error(reference), now);
+        Ok((star.telemetry(reference, self.tracker.config.plate_scale), correction, command))
+    }
+}
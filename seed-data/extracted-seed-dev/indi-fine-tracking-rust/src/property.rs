@@ -0,0 +1,376 @@
+//! INDI property model
+//! A property is a named vector of members on a device, all of one kind:
+//! numbers, switches, texts, lights or BLOBs. `def*Vector` messages create
+//! it, `set*Vector` messages update members by name.
+
+use anyhow::{anyhow, bail, Result};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PropertyState {
+    #[default]
+    Idle,
+    Ok,
+    Busy,
+    Alert,
+}
+
+impl PropertyState {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s.trim() {
+            "Idle" => Self::Idle,
+            "Ok" => Self::Ok,
+            "Busy" => Self::Busy,
+            "Alert" => Self::Alert,
+            other => bail!("unknown property state '{}'", other),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Permission {
+    #[default]
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+impl Permission {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s {
+            "ro" => Self::ReadOnly,
+            "wo" => Self::WriteOnly,
+            "rw" => Self::ReadWrite,
+            other => bail!("unknown permission '{}'", other),
+        })
+    }
+
+    pub fn writable(self) -> bool {
+        self != Self::ReadOnly
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwitchRule {
+    OneOfMany,
+    AtMostOne,
+    AnyOfMany,
+}
+
+impl SwitchRule {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s {
+            "OneOfMany" => Self::OneOfMany,
+            "AtMostOne" => Self::AtMostOne,
+            "AnyOfMany" => Self::AnyOfMany,
+            other => bail!("unknown switch rule '{}'", other),
+        })
+    }
+}
+
+/// Number value, plain or sexagesimal ("-45:30:00", "12 30 00.5")
+pub fn parse_number(s: &str) -> Result<f64> {
+    let s = s.trim();
+    if !s.contains([':', ' ']) {
+        return s.parse().map_err(|_| anyhow!("invalid number '{}'", s));
+    }
+    let negative = s.starts_with('-');
+    let mut value = 0.0;
+    let mut scale = 1.0;
+    for part in s.split([':', ' ']).filter(|p| !p.is_empty()).take(3) {
+        let part: f64 = part
+            .trim_start_matches(['-', '+'])
+            .parse()
+            .map_err(|_| anyhow!("invalid sexagesimal '{}'", s))?;
+        value += part / scale;
+        scale *= 60.0;
+    }
+    Ok(if negative { -value } else { value })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Number {
+    pub name: String,
+    pub label: String,
+    /// printf-style, `%m` for sexagesimal
+    pub format: String,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Switch {
+    pub name: String,
+    pub label: String,
+    pub on: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Text {
+    pub name: String,
+    pub label: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Light {
+    pub name: String,
+    pub label: String,
+    pub state: PropertyState,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blob {
+    pub name: String,
+    pub label: String,
+    /// File suffix such as ".fits", ".fits.z" when compressed
+    pub format: String,
+    /// Size before compression, as announced
+    pub size: usize,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Members {
+    Number(Vec<Number>),
+    Switch(SwitchRule, Vec<Switch>),
+    Text(Vec<Text>),
+    Light(Vec<Light>),
+    Blob(Vec<Blob>),
+}
+
+impl Members {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Number(_) => "Number",
+            Self::Switch(..) => "Switch",
+            Self::Text(_) => "Text",
+            Self::Light(_) => "Light",
+            Self::Blob(_) => "BLOB",
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Number(v) => v.len(),
+            Self::Switch(_, v) => v.len(),
+            Self::Text(v) => v.len(),
+            Self::Light(v) => v.len(),
+            Self::Blob(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub device: String,
+    pub name: String,
+    pub label: String,
+    pub group: String,
+    pub state: PropertyState,
+    /// Lights are always read-only
+    pub perm: Permission,
+    /// Seconds the device expects a change to take
+    pub timeout: f64,
+    pub timestamp: Option<String>,
+    /// Last message attached to a def or set
+    pub message: Option<String>,
+    pub members: Members,
+}
+
+impl Property {
+    pub fn number(&self, name: &str) -> Option<f64> {
+        match &self.members {
+            Members::Number(v) => v.iter().find(|n| n.name == name).map(|n| n.value),
+            _ => None,
+        }
+    }
+
+    pub fn switch(&self, name: &str) -> Option<bool> {
+        match &self.members {
+            Members::Switch(_, v) => v.iter().find(|s| s.name == name).map(|s| s.on),
+            _ => None,
+        }
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match &self.members {
+            Members::Text(v) => v.iter().find(|t| t.name == name).map(|t| t.value.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn light(&self, name: &str) -> Option<PropertyState> {
+        match &self.members {
+            Members::Light(v) => v.iter().find(|l| l.name == name).map(|l| l.state),
+            _ => None,
+        }
+    }
+
+    pub fn blob(&self, name: &str) -> Option<&Blob> {
+        match &self.members {
+            Members::Blob(v) => v.iter().find(|b| b.name == name),
+            _ => None,
+        }
+    }
+
+    /// Merge a `set*Vector`; members not named in it keep their values
+    pub fn apply(&mut self, update: &Update) -> Result<()> {
+        if let Some(state) = update.state {
+            self.state = state;
+        }
+        if let Some(timeout) = update.timeout {
+            self.timeout = timeout;
+        }
+        if update.timestamp.is_some() {
+            self.timestamp = update.timestamp.clone();
+        }
+        if update.message.is_some() {
+            self.message = update.message.clone();
+        }
+
+        fn merge<T, V: Clone>(
+            members: &mut [T],
+            values: &[(String, V)],
+            name: impl Fn(&T) -> &str,
+            set: impl Fn(&mut T, V),
+        ) -> Result<()> {
+            for (member, value) in values {
+                let Some(target) = members.iter_mut().find(|m| name(m) == member) else {
+                    bail!("no member '{}'", member);
+                };
+                set(target, value.clone());
+            }
+            Ok(())
+        }
+
+        match (&mut self.members, &update.values) {
+            (Members::Number(m), Values::Number(v)) => {
+                merge(m, v, |n| &n.name, |n, value| n.value = value)
+            }
+            (Members::Switch(_, m), Values::Switch(v)) => {
+                merge(m, v, |s| &s.name, |s, on| s.on = on)
+            }
+            (Members::Text(m), Values::Text(v)) => {
+                merge(m, v, |t| &t.name, |t, value| t.value = value)
+            }
+            (Members::Light(m), Values::Light(v)) => {
+                merge(m, v, |l| &l.name, |l, state| l.state = state)
+            }
+            (Members::Blob(m), Values::Blob(v)) => merge(
+                m,
+                v,
+                |b| &b.name,
+                |b, (format, size, data)| {
+                    b.format = format;
+                    b.size = size;
+                    b.data = data;
+                },
+            ),
+            (members, _) => bail!(
+                "{} update for {} property {}.{}",
+                update.values.kind(),
+                members.kind(),
+                self.device,
+                self.name
+            ),
+        }
+    }
+}
+
+/// Format, announced size and decoded data of a BLOB update
+pub type BlobValue = (String, usize, Vec<u8>);
+
+/// Member values carried by a `set*Vector`, by member name
+#[derive(Debug, Clone, PartialEq)]
+pub enum Values {
+    Number(Vec<(String, f64)>),
+    Switch(Vec<(String, bool)>),
+    Text(Vec<(String, String)>),
+    Light(Vec<(String, PropertyState)>),
+    Blob(Vec<(String, BlobValue)>),
+}
+
+impl Values {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Number(_) => "Number",
+            Self::Switch(_) => "Switch",
+            Self::Text(_) => "Text",
+            Self::Light(_) => "Light",
+            Self::Blob(_) => "BLOB",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Update {
+    pub device: String,
+    pub name: String,
+    pub state: Option<PropertyState>,
+    pub timeout: Option<f64>,
+    pub timestamp: Option<String>,
+    pub message: Option<String>,
+    pub values: Values,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sexagesimal_numbers() {
+        assert_eq!(parse_number(" 12.5 ").unwrap(), 12.5);
+        assert_eq!(parse_number("-45:30:00").unwrap(), -45.5);
+        assert_eq!(parse_number("-0:30").unwrap(), -0.5);
+        assert!((parse_number("12 30 36").unwrap() - 12.51).abs() < 1e-12);
+        assert!(parse_number("abc").is_err());
+    }
+
+    #[test]
+    fn apply_merges_by_member_name() {
+        let mut property = Property {
+            device: "Guide CCD".into(),
+            name: "CCD_EXPOSURE".into(),
+            label: "Expose".into(),
+            group: "Main Control".into(),
+            state: PropertyState::Idle,
+            perm: Permission::ReadWrite,
+            timeout: 60.0,
+            timestamp: None,
+            message: None,
+            members: Members::Number(vec![Number {
+                name: "CCD_EXPOSURE_VALUE".into(),
+                label: "Duration (s)".into(),
+                format: "%5.2f".into(),
+                min: 0.0,
+                max: 3600.0,
+                step: 1.0,
+                value: 0.0,
+            }]),
+        };
+        let mut update = Update {
+            device: "Guide CCD".into(),
+            name: "CCD_EXPOSURE".into(),
+            state: Some(PropertyState::Busy),
+            timeout: None,
+            timestamp: Some("2026-01-01T00:00:01".into()),
+            message: None,
+            values: Values::Number(vec![("CCD_EXPOSURE_VALUE".into(), 0.02)]),
+        };
+        property.apply(&update).unwrap();
+        assert_eq!(property.number("CCD_EXPOSURE_VALUE"), Some(0.02));
+        assert_eq!(property.state, PropertyState::Busy);
+        assert_eq!(property.timeout, 60.0);
+
+        update.values = Values::Switch(vec![("CCD_EXPOSURE_VALUE".into(), true)]);
+        assert!(property.apply(&update).is_err());
+        update.values = Values::Number(vec![("GAIN".into(), 1.0)]);
+        assert!(property.apply(&update).is_err());
+    }
+}
@@ -0,0 +1,433 @@
+//! INDI 1.7 messages
+//! Server-to-client elements become `Message`s; client-to-server elements
+//! are built by the functions at the bottom.
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::property::{
+    parse_number, Blob, Light, Members, Number, Permission, Property, PropertyState, Switch,
+    SwitchRule, Text, Update, Values,
+};
+use crate::xml::Element;
+
+pub const PROTOCOL_VERSION: &str = "1.7";
+pub const DEFAULT_PORT: u16 = 7624;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Define(Property),
+    Set(Update),
+    /// Whole device when `name` is `None`
+    Delete {
+        device: String,
+        name: Option<String>,
+        message: Option<String>,
+    },
+    Message {
+        device: Option<String>,
+        timestamp: Option<String>,
+        message: String,
+    },
+}
+
+/// When the server sends BLOBs on a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobMode {
+    Never,
+    Also,
+    Only,
+}
+
+impl BlobMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Never => "Never",
+            Self::Also => "Also",
+            Self::Only => "Only",
+        }
+    }
+}
+
+fn switch_value(s: &str) -> Result<bool> {
+    match s.trim() {
+        "On" => Ok(true),
+        "Off" => Ok(false),
+        other => bail!("invalid switch value '{}'", other),
+    }
+}
+
+fn number_attr(element: &Element, key: &str) -> Result<f64> {
+    element.get(key).map_or(Ok(0.0), parse_number)
+}
+
+pub fn decode_base64(s: &str) -> Result<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let (mut acc, mut bits) = (0u32, 0);
+    for c in s.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        if c == b'=' {
+            break;
+        }
+        let v = value(c).ok_or_else(|| anyhow!("invalid base64 byte {:#04x}", c))?;
+        acc = (acc << 6) | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
+
+fn members(element: &Element, kind: &str) -> Result<Members> {
+    let children = element
+        .children
+        .iter()
+        .filter(|c| c.name == format!("def{}", kind));
+    let label = |c: &Element| -> Result<(String, String)> {
+        let name = c.require("name")?.to_string();
+        let label = c.get("label").unwrap_or(&name).to_string();
+        Ok((name, label))
+    };
+
+    Ok(match kind {
+        "Number" => Members::Number(
+            children
+                .map(|c| {
+                    let (name, label) = label(c)?;
+                    Ok(Number {
+                        name,
+                        label,
+                        format: c.get("format").unwrap_or("%g").into(),
+                        min: number_attr(c, "min")?,
+                        max: number_attr(c, "max")?,
+                        step: number_attr(c, "step")?,
+                        value: parse_number(&c.text)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        ),
+        "Switch" => Members::Switch(
+            SwitchRule::parse(element.require("rule")?)?,
+            children
+                .map(|c| {
+                    let (name, label) = label(c)?;
+                    Ok(Switch {
+                        name,
+                        label,
+                        on: switch_value(&c.text)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        ),
+        "Text" => Members::Text(
+            children
+                .map(|c| {
+                    let (name, label) = label(c)?;
+                    Ok(Text {
+                        name,
+                        label,
+                        value: c.text.trim().into(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+        ),
+        "Light" => Members::Light(
+            children
+                .map(|c| {
+                    let (name, label) = label(c)?;
+                    Ok(Light {
+                        name,
+                        label,
+                        state: PropertyState::parse(&c.text)?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        ),
+        _ => Members::Blob(
+            children
+                .map(|c| {
+                    let (name, label) = label(c)?;
+                    Ok(Blob {
+                        name,
+                        label,
+                        format: String::new(),
+                        size: 0,
+                        data: Vec::new(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+        ),
+    })
+}
+
+fn values(element: &Element, kind: &str) -> Result<Values> {
+    let children = element
+        .children
+        .iter()
+        .filter(|c| c.name == format!("one{}", kind));
+    let name = |c: &Element| c.require("name").map(String::from);
+
+    Ok(match kind {
+        "Number" => Values::Number(
+            children
+                .map(|c| Ok((name(c)?, parse_number(&c.text)?)))
+                .collect::<Result<_>>()?,
+        ),
+        "Switch" => Values::Switch(
+            children
+                .map(|c| Ok((name(c)?, switch_value(&c.text)?)))
+                .collect::<Result<_>>()?,
+        ),
+        "Text" => Values::Text(
+            children
+                .map(|c| Ok((name(c)?, c.text.trim().to_string())))
+                .collect::<Result<_>>()?,
+        ),
+        "Light" => Values::Light(
+            children
+                .map(|c| Ok((name(c)?, PropertyState::parse(&c.text)?)))
+                .collect::<Result<_>>()?,
+        ),
+        _ => Values::Blob(
+            children
+                .map(|c| {
+                    let size = c.get("size").unwrap_or("0").trim().parse()?;
+                    let format = c.get("format").unwrap_or("").to_string();
+                    Ok((name(c)?, (format, size, decode_base64(&c.text)?)))
+                })
+                .collect::<Result<_>>()?,
+        ),
+    })
+}
+
+/// Interpret a server element; `None` for elements a client does not act on
+pub fn parse(element: &Element) -> Result<Option<Message>> {
+    let name = element.name.as_str();
+    let kind = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix("Vector"))
+            .filter(|k| ["Number", "Switch", "Text", "Light", "BLOB"].contains(k))
+    };
+    let opt = |key: &str| element.get(key).map(String::from);
+
+    if let Some(kind) = kind("def") {
+        let device = element.require("device")?.to_string();
+        let property_name = element.require("name")?.to_string();
+        let perm = match kind {
+            "Light" => Permission::ReadOnly,
+            _ => Permission::parse(element.require("perm")?)?,
+        };
+        return Ok(Some(Message::Define(Property {
+            label: element.get("label").unwrap_or(&property_name).into(),
+            group: element.get("group").unwrap_or("").into(),
+            state: PropertyState::parse(element.require("state")?)?,
+            perm,
+            timeout: number_attr(element, "timeout")?,
+            timestamp: opt("timestamp"),
+            message: opt("message"),
+            members: members(element, kind)?,
+            device,
+            name: property_name,
+        })));
+    }
+    if let Some(kind) = kind("set") {
+        return Ok(Some(Message::Set(Update {
+            device: element.require("device")?.into(),
+            name: element.require("name")?.into(),
+            state: element.get("state").map(PropertyState::parse).transpose()?,
+            timeout: element.get("timeout").map(parse_number).transpose()?,
+            timestamp: opt("timestamp"),
+            message: opt("message"),
+            values: values(element, kind)?,
+        })));
+    }
+    Ok(match name {
+        "delProperty" => Some(Message::Delete {
+            device: element.require("device")?.into(),
+            name: opt("name"),
+            message: opt("message"),
+        }),
+        "message" => Some(Message::Message {
+            device: opt("device"),
+            timestamp: opt("timestamp"),
+            message: element.get("message").unwrap_or("").into(),
+        }),
+        _ => None,
+    })
+}
+
+/// Ask for property definitions, of everything or one device or property
+pub fn get_properties(device: Option<&str>, name: Option<&str>) -> Element {
+    let mut element = Element::new("getProperties").attr("version", PROTOCOL_VERSION);
+    if let Some(device) = device {
+        element = element.attr("device", device);
+    }
+    if let Some(name) = name {
+        element = element.attr("name", name);
+    }
+    element
+}
+
+pub fn enable_blob(device: &str, name: Option<&str>, mode: BlobMode) -> Element {
+    let mut element = Element::new("enableBLOB").attr("device", device);
+    if let Some(name) = name {
+        element = element.attr("name", name);
+    }
+    element.with_text(mode.as_str())
+}
+
+fn new_vector<'a>(
+    kind: &str,
+    device: &str,
+    name: &str,
+    members: impl IntoIterator<Item = (&'a str, String)>,
+) -> Element {
+    members.into_iter().fold(
+        Element::new(&format!("new{}Vector", kind))
+            .attr("device", device)
+            .attr("name", name),
+        |vector, (member, value)| {
+            vector.child(
+                Element::new(&format!("one{}", kind))
+                    .attr("name", member)
+                    .with_text(value),
+            )
+        },
+    )
+}
+
+pub fn new_number_vector(device: &str, name: &str, values: &[(&str, f64)]) -> Element {
+    new_vector(
+        "Number",
+        device,
+        name,
+        values.iter().map(|&(m, v)| (m, v.to_string())),
+    )
+}
+
+pub fn new_switch_vector(device: &str, name: &str, values: &[(&str, bool)]) -> Element {
+    new_vector(
+        "Switch",
+        device,
+        name,
+        values
+            .iter()
+            .map(|&(m, on)| (m, if on { "On" } else { "Off" }.to_string())),
+    )
+}
+
+pub fn new_text_vector(device: &str, name: &str, values: &[(&str, &str)]) -> Element {
+    new_vector(
+        "Text",
+        device,
+        name,
+        values.iter().map(|&(m, v)| (m, v.to_string())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xml::XmlStream;
+
+    fn parse_one(xml: &str) -> Message {
+        let elements = XmlStream::default().push(xml.as_bytes());
+        parse(&elements[0]).unwrap().unwrap()
+    }
+
+    #[test]
+    fn define_and_set_switch() {
+        let Message::Define(mut property) = parse_one(
+            r#"<defSwitchVector device="Guide CCD" name="CONNECTION" label="Connection" group="Main Control" state="Idle" perm="rw" rule="OneOfMany" timeout="60">
+                <defSwitch name="CONNECT" label="Connect">Off</defSwitch>
+                <defSwitch name="DISCONNECT" label="Disconnect">On</defSwitch>
+            </defSwitchVector>"#,
+        ) else {
+            panic!("expected a definition");
+        };
+        assert_eq!(property.members.kind(), "Switch");
+        assert_eq!(property.switch("DISCONNECT"), Some(true));
+        assert!(matches!(
+            property.members,
+            Members::Switch(SwitchRule::OneOfMany, _)
+        ));
+
+        let Message::Set(update) = parse_one(
+            r#"<setSwitchVector device="Guide CCD" name="CONNECTION" state="Ok" timestamp="2026-01-01T00:00:02">
+                <oneSwitch name="CONNECT">On</oneSwitch>
+                <oneSwitch name="DISCONNECT">Off</oneSwitch>
+            </setSwitchVector>"#,
+        ) else {
+            panic!("expected an update");
+        };
+        property.apply(&update).unwrap();
+        assert_eq!(property.switch("CONNECT"), Some(true));
+        assert_eq!(property.state, PropertyState::Ok);
+    }
+
+    #[test]
+    fn blob_and_lights() {
+        let Message::Set(update) = parse_one(
+            r#"<setBLOBVector device="Guide CCD" name="CCD1" state="Ok">
+                <oneBLOB name="CCD1" size="3" format=".fits">
+                TWFu
+                </oneBLOB>
+            </setBLOBVector>"#,
+        ) else {
+            panic!("expected an update");
+        };
+        let Values::Blob(blobs) = &update.values else {
+            panic!("expected BLOB values");
+        };
+        assert_eq!(blobs[0].1, (".fits".to_string(), 3, b"Man".to_vec()));
+
+        let Message::Define(lights) = parse_one(
+            r#"<defLightVector device="Mount" name="STATUS" state="Alert"><defLight name="SLEW">Busy</defLight></defLightVector>"#,
+        ) else {
+            panic!("expected a definition");
+        };
+        assert_eq!(lights.perm, Permission::ReadOnly);
+        assert_eq!(lights.light("SLEW"), Some(PropertyState::Busy));
+
+        assert_eq!(decode_base64("aGVsbG8gd29ybGQ=").unwrap(), b"hello world");
+        assert!(decode_base64("a$b").is_err());
+    }
+
+    #[test]
+    fn client_messages() {
+        let xml = new_number_vector(
+            "Mount",
+            "TELESCOPE_TIMED_GUIDE_NS",
+            &[("TIMED_GUIDE_N", 12.5)],
+        )
+        .to_xml();
+        assert_eq!(
+            xml,
+            "<newNumberVector device=\"Mount\" name=\"TELESCOPE_TIMED_GUIDE_NS\">\n<oneNumber name=\"TIMED_GUIDE_N\">12.5</oneNumber>\n</newNumberVector>\n"
+        );
+        assert_eq!(
+            get_properties(None, None).to_xml(),
+            "<getProperties version=\"1.7\"/>\n"
+        );
+        assert_eq!(
+            enable_blob("Guide CCD", None, BlobMode::Also).to_xml(),
+            "<enableBLOB device=\"Guide CCD\">Also</enableBLOB>\n"
+        );
+        let switch = new_switch_vector("Mount", "CONNECTION", &[("CONNECT", true)]);
+        assert_eq!(switch.children[0].text, "On");
+        assert!(parse(&switch).unwrap().is_none());
+        assert!(parse(&Element::new("delProperty")).is_err());
+    }
+}
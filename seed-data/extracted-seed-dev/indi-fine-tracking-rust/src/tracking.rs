@@ -0,0 +1,336 @@
+//! Closed-loop mount correction
+//! Each guide-star error (pixels from the lock position) is rotated and
+//! scaled onto the sky, run through one PID per axis, and added to the
+//! feed-forward track rate. The sum goes to the mount as a
+//! `TELESCOPE_TRACK_RATE` update. The loop runs once per guide frame, so the
+//! camera exposure sets its rate (5-20 ms exposures for 50-200 Hz);
+//! commands arriving faster than `loop_hz` are coalesced. Signs follow the
+//! sky: a star east of the lock position drives the RA rate up.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::protocol;
+use crate::xml::Element;
+
+/// Sidereal rate in arcseconds of RA per second
+pub const SIDEREAL_RATE: f64 = 15.041_067;
+
+pub const RATE_PROPERTY: &str = "TELESCOPE_TRACK_RATE";
+pub const RATE_RA: &str = "TRACK_RATE_RA";
+pub const RATE_DEC: &str = "TRACK_RATE_DE";
+
+/// PID with output clamping and conditional integration: the integral stops
+/// accumulating while the output is saturated in the error's direction, so it
+/// doesn't wind up during a large offset and overshoot once it clears
+#[derive(Debug, Clone)]
+pub struct Pid {
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+    /// Output magnitude limit
+    pub limit: f64,
+    integral: f64,
+    previous: Option<f64>,
+}
+
+impl Pid {
+    pub fn new(kp: f64, ki: f64, kd: f64, limit: f64) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            limit,
+            integral: 0.0,
+            previous: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.previous = None;
+    }
+
+    pub fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    /// `dt` in seconds since the previous update
+    pub fn update(&mut self, error: f64, dt: f64) -> f64 {
+        let derivative = match self.previous {
+            Some(previous) if dt > 0.0 => (error - previous) / dt,
+            _ => 0.0,
+        };
+        self.previous = Some(error);
+
+        let integral = self.integral + error * dt;
+        let unclamped = self.kp * error + self.ki * integral + self.kd * derivative;
+        if unclamped.abs() <= self.limit || unclamped.signum() != error.signum() {
+            self.integral = integral;
+        }
+        (self.kp * error + self.ki * self.integral + self.kd * derivative)
+            .clamp(-self.limit, self.limit)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackingConfig {
+    pub mount: String,
+    /// Command rate ceiling, 50-200 Hz
+    pub loop_hz: f64,
+    /// Arcseconds per pixel
+    pub plate_scale: f64,
+    /// Angle from the camera +x axis to east, counter-clockwise, degrees
+    pub camera_angle: f64,
+    /// Camera image is mirrored (+y is south when +x is east)
+    pub flipped: bool,
+    /// Feed-forward rates in arcsec/s: sidereal for stars, the ephemeris
+    /// rate when following a satellite
+    pub base_rate: (f64, f64),
+    /// Gains per axis; output in arcsec/s per arcsec of error
+    pub ra_gains: (f64, f64, f64),
+    pub dec_gains: (f64, f64, f64),
+    /// Largest correction added to the base rate, arcsec/s
+    pub max_correction: f64,
+    /// Frames without a star before the loop falls back to the base rate
+    pub max_lost: u32,
+}
+
+impl Default for TrackingConfig {
+    fn default() -> Self {
+        Self {
+            mount: "Telescope Simulator".into(),
+            loop_hz: 100.0,
+            plate_scale: 1.0,
+            camera_angle: 0.0,
+            flipped: false,
+            base_rate: (SIDEREAL_RATE, 0.0),
+            ra_gains: (0.6, 0.1, 0.0),
+            dec_gains: (0.6, 0.1, 0.0),
+            max_correction: 30.0,
+            max_lost: 10,
+        }
+    }
+}
+
+impl TrackingConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(50.0..=200.0).contains(&self.loop_hz) {
+            bail!("loop rate {} Hz outside 50-200 Hz", self.loop_hz);
+        }
+        if self.plate_scale <= 0.0 {
+            bail!("plate scale must be positive");
+        }
+        if self.max_correction <= 0.0 {
+            bail!("max correction must be positive");
+        }
+        Ok(())
+    }
+
+    /// Pixel offset to (east, north) arcseconds on the sky
+    pub fn to_sky(&self, error: (f64, f64)) -> (f64, f64) {
+        let (sin, cos) = self.camera_angle.to_radians().sin_cos();
+        let y = if self.flipped { -error.1 } else { error.1 };
+        let east = cos * error.0 - sin * y;
+        let north = sin * error.0 + cos * y;
+        (east * self.plate_scale, north * self.plate_scale)
+    }
+}
+
+/// One loop iteration, for telemetry
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Correction {
+    /// Guide error on the sky, arcsec
+    pub error_east: f64,
+    pub error_north: f64,
+    /// Commanded rates, arcsec/s
+    pub rate_ra: f64,
+    pub rate_dec: f64,
+    /// Since the previous frame, ms
+    pub interval_ms: f64,
+    /// A rate hit `max_correction`
+    pub saturated: bool,
+    /// Throttled by `loop_hz`; the next command carries the latest rates
+    pub coalesced: bool,
+}
+
+#[derive(Debug)]
+pub struct Tracker {
+    pub config: TrackingConfig,
+    ra: Pid,
+    dec: Pid,
+    declination: f64,
+    last_frame: Option<Instant>,
+    last_command: Option<Instant>,
+    lost: u32,
+}
+
+impl Tracker {
+    pub fn new(config: TrackingConfig) -> Result<Self> {
+        config.validate()?;
+        let pid = |(kp, ki, kd)| Pid::new(kp, ki, kd, config.max_correction);
+        Ok(Self {
+            ra: pid(config.ra_gains),
+            dec: pid(config.dec_gains),
+            config,
+            declination: 0.0,
+            last_frame: None,
+            last_command: None,
+            lost: 0,
+        })
+    }
+
+    /// Current mount declination, degrees; RA rate per arcsec on the sky
+    /// grows as 1/cos(dec)
+    pub fn set_declination(&mut self, declination: f64) {
+        self.declination = declination;
+    }
+
+    /// Rate command for a guide-star error in pixels measured at `now`;
+    /// `None` when throttled
+    pub fn step(&mut self, error: (f64, f64), now: Instant) -> (Correction, Option<Element>) {
+        let interval = self
+            .last_frame
+            .map(|t| now.duration_since(t))
+            .unwrap_or_default();
+        self.last_frame = Some(now);
+        self.lost = 0;
+
+        let (east, north) = self.config.to_sky(error);
+        let dt = interval.as_secs_f64();
+        let ra = self.ra.update(east, dt);
+        let dec = self.dec.update(north, dt);
+        // Keep the RA correction finite near the pole
+        let cos_dec = self.declination.to_radians().cos().abs().max(0.05);
+        let limit = self.config.max_correction;
+        let correction = Correction {
+            error_east: east,
+            error_north: north,
+            rate_ra: self.config.base_rate.0 + ra / cos_dec,
+            rate_dec: self.config.base_rate.1 + dec,
+            interval_ms: interval.as_secs_f64() * 1e3,
+            saturated: ra.abs() >= limit || dec.abs() >= limit,
+            coalesced: false,
+        };
+        let command = self.command(correction.rate_ra, correction.rate_dec, now);
+        (
+            Correction {
+                coalesced: command.is_none(),
+                ..correction
+            },
+            command,
+        )
+    }
+
+    /// A frame without a star; after `max_lost` in a row the controllers
+    /// reset and the base rate is restored
+    pub fn lost(&mut self, now: Instant) -> Option<Element> {
+        self.lost += 1;
+        if self.lost != self.config.max_lost {
+            return None;
+        }
+        self.ra.reset();
+        self.dec.reset();
+        self.last_frame = None;
+        self.last_command = None;
+        let (ra, dec) = self.config.base_rate;
+        self.command(ra, dec, now)
+    }
+
+    fn command(&mut self, ra: f64, dec: f64, now: Instant) -> Option<Element> {
+        let period = Duration::from_secs_f64(1.0 / self.config.loop_hz);
+        if self
+            .last_command
+            .is_some_and(|t| now.duration_since(t) < period)
+        {
+            return None;
+        }
+        self.last_command = Some(now);
+        Some(protocol::new_number_vector(
+            &self.config.mount,
+            RATE_PROPERTY,
+            &[(RATE_RA, ra), (RATE_DEC, dec)],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pid_anti_windup() {
+        let mut pid = Pid::new(1.0, 2.0, 0.0, 5.0);
+        // Large offset saturates the output; the integral must not grow
+        for _ in 0..100 {
+            assert_eq!(pid.update(20.0, 0.01), 5.0);
+        }
+        assert_eq!(pid.integral(), 0.0);
+        // Once the error reverses the output follows at once
+        assert!(pid.update(-1.0, 0.01) < 0.0);
+
+        let mut pid = Pid::new(0.0, 1.0, 0.0, 5.0);
+        assert_eq!(pid.update(1.0, 0.5), 0.5);
+        assert_eq!(pid.update(1.0, 0.5), 1.0);
+        pid.reset();
+        assert_eq!(pid.update(1.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn pixel_error_to_rates() {
+        let config = TrackingConfig {
+            plate_scale: 2.0,
+            camera_angle: 90.0,
+            ra_gains: (1.0, 0.0, 0.0),
+            dec_gains: (1.0, 0.0, 0.0),
+            ..TrackingConfig::default()
+        };
+        // +y is west when +x is north
+        let (east, north) = config.to_sky((1.0, 1.0));
+        assert!((east + 2.0).abs() < 1e-9 && (north - 2.0).abs() < 1e-9);
+
+        let mut tracker = Tracker::new(config).unwrap();
+        tracker.set_declination(60.0);
+        let t0 = Instant::now();
+        let (correction, command) = tracker.step((0.0, -1.5), t0);
+        // 3" east at dec 60 is 6" of RA
+        assert!((correction.rate_ra - (SIDEREAL_RATE + 6.0)).abs() < 1e-9);
+        assert!(correction.rate_dec.abs() < 1e-9);
+        let command = command.unwrap();
+        assert_eq!(command.get("name"), Some(RATE_PROPERTY));
+        assert_eq!(command.children.len(), 2);
+
+        // Faster than loop_hz: coalesced
+        let (correction, command) = tracker.step((0.0, 0.0), t0 + Duration::from_millis(4));
+        assert!(command.is_none() && correction.coalesced);
+        assert_eq!(correction.interval_ms, 4.0);
+        let (_, command) = tracker.step((0.0, 0.0), t0 + Duration::from_millis(12));
+        assert!(command.is_some());
+
+        assert!(Tracker::new(TrackingConfig {
+            loop_hz: 500.0,
+            ..TrackingConfig::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn lost_star_falls_back_to_base_rate() {
+        let mut tracker = Tracker::new(TrackingConfig {
+            max_lost: 3,
+            ..TrackingConfig::default()
+        })
+        .unwrap();
+        let t0 = Instant::now();
+        tracker.step((5.0, 5.0), t0);
+        let later = t0 + Duration::from_secs(1);
+        assert!(tracker.lost(later).is_none());
+        assert!(tracker.lost(later).is_none());
+        let command = tracker.lost(later).unwrap();
+        let ra = &command.children[0];
+        assert_eq!(ra.text, SIDEREAL_RATE.to_string());
+        assert!(tracker.lost(later).is_none());
+    }
+}
@@ -0,0 +1,365 @@
+//! Streaming XML for the INDI wire protocol
+//! INDI sends top-level elements back to back with no enclosing document,
+//! split across reads at arbitrary points. `XmlStream` buffers bytes and
+//! yields each top-level element once its closing tag has arrived. Only the
+//! subset INDI uses is handled: elements, attributes, character data, CDATA,
+//! the predefined entities and numeric references; declarations, comments
+//! and doctypes are skipped.
+
+use anyhow::{anyhow, bail, Result};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Element {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    /// Character data directly inside the element, untrimmed
+    pub text: String,
+}
+
+impl Element {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn attr(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.attrs.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn child(mut self, child: Element) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Attribute that the protocol requires
+    pub fn require(&self, key: &str) -> Result<&str> {
+        self.get(key)
+            .ok_or_else(|| anyhow!("<{}> missing attribute '{}'", self.name, key))
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut String) {
+        out.push('<');
+        out.push_str(&self.name);
+        for (k, v) in &self.attrs {
+            out.push_str(&format!(" {}=\"{}\"", k, escape(v)));
+        }
+        if self.children.is_empty() && self.text.is_empty() {
+            out.push_str("/>\n");
+            return;
+        }
+        out.push('>');
+        out.push_str(&escape(&self.text));
+        if !self.children.is_empty() {
+            out.push('\n');
+        }
+        for child in &self.children {
+            child.write(out);
+        }
+        out.push_str(&format!("</{}>\n", self.name));
+    }
+}
+
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(s: &str) -> Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let end = rest[amp..]
+            .find(';')
+            .ok_or_else(|| anyhow!("unterminated entity"))?;
+        let entity = &rest[amp + 1..amp + end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| anyhow!("unknown entity &{};", entity))?
+            }
+        };
+        out.push(c);
+        rest = &rest[amp + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Name and attributes of a start tag's contents (between `<` and `>`)
+fn parse_tag(tag: &str) -> Result<Element> {
+    let tag = tag.trim_end();
+    let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+    let mut element = Element::new(&tag[..name_end]);
+    if element.name.is_empty() {
+        bail!("empty tag name");
+    }
+
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| anyhow!("attribute without value in <{}>", element.name))?;
+        let key = rest[..eq].trim();
+        let after = rest[eq + 1..].trim_start();
+        let quote = after
+            .chars()
+            .next()
+            .filter(|&q| q == '"' || q == '\'')
+            .ok_or_else(|| anyhow!("unquoted attribute '{}' in <{}>", key, element.name))?;
+        let close = after[1..]
+            .find(quote)
+            .ok_or_else(|| anyhow!("unterminated attribute '{}'", key))?;
+        element
+            .attrs
+            .push((key.into(), unescape(&after[1..1 + close])?));
+        rest = after[close + 2..].trim_start();
+    }
+    Ok(element)
+}
+
+/// Position of the `>` closing the tag that starts at `buf[0]`, skipping
+/// any inside quoted attribute values
+fn tag_end(buf: &[u8]) -> Option<usize> {
+    let mut quote = None;
+    for (i, &b) in buf.iter().enumerate().skip(1) {
+        match (quote, b) {
+            (None, b'"' | b'\'') => quote = Some(b),
+            (Some(q), _) if b == q => quote = None,
+            (None, b'>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn find(buf: &[u8], from: usize, pattern: &[u8]) -> Option<usize> {
+    buf.get(from..)?
+        .windows(pattern.len())
+        .position(|w| w == pattern)
+        .map(|p| p + from)
+}
+
+/// Incremental parser for a stream of top-level elements
+#[derive(Debug, Default)]
+pub struct XmlStream {
+    buf: Vec<u8>,
+    /// Elements opened but not yet closed, outermost first
+    stack: Vec<Element>,
+    /// Bytes of pending character data already searched for `<`, so large
+    /// BLOB payloads arriving in many reads are scanned once
+    scanned: usize,
+    /// Malformed constructs skipped so far
+    pub errors: u64,
+    pub last_error: Option<String>,
+}
+
+impl XmlStream {
+    /// Feed received bytes; returns the top-level elements they completed.
+    /// Malformed input discards the elements still open and parsing picks
+    /// up again at the next tag; see `errors`
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Element> {
+        self.buf.extend_from_slice(bytes);
+        let mut done = Vec::new();
+        loop {
+            let mut pos = 0;
+            let result = self.parse(&mut pos, &mut done);
+            self.buf.drain(..pos);
+            let Err(e) = result else {
+                return done;
+            };
+            self.errors += 1;
+            self.last_error = Some(e.to_string());
+            self.stack.clear();
+            self.scanned = 0;
+            let next = self.buf.iter().skip(1).position(|&b| b == b'<');
+            self.buf.drain(..next.map_or(self.buf.len(), |p| p + 1));
+        }
+    }
+
+    fn close(&mut self, element: Element, done: &mut Vec<Element>) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(element),
+            None => done.push(element),
+        }
+    }
+
+    fn parse(&mut self, pos: &mut usize, done: &mut Vec<Element>) -> Result<()> {
+        while *pos < self.buf.len() {
+            let buf = &self.buf[*pos..];
+            if buf[0] != b'<' {
+                let from = self.scanned.max(1);
+                let Some(lt) = buf[from..].iter().position(|&b| b == b'<') else {
+                    self.scanned = buf.len();
+                    return Ok(());
+                };
+                let text = std::str::from_utf8(&buf[..from + lt])?;
+                if let Some(open) = self.stack.last_mut() {
+                    open.text.push_str(&unescape(text)?);
+                }
+                self.scanned = 0;
+                *pos += from + lt;
+                continue;
+            }
+
+            // Wait until a `<!` construct can be told apart
+            if buf.len() < 2 || (buf[1] == b'!' && buf.len() < 9) {
+                return Ok(());
+            }
+            // Markup that carries no elements
+            let skip = [
+                (&b"<!--"[..], &b"-->"[..]),
+                (b"<?", b"?>"),
+                (b"<![CDATA[", b"]]>"),
+                (b"<!", b">"),
+            ];
+            if let Some(&(open, close)) = skip.iter().find(|(open, _)| buf.starts_with(open)) {
+                let Some(end) = find(buf, open.len(), close) else {
+                    return Ok(());
+                };
+                if open == b"<![CDATA[" {
+                    if let Some(element) = self.stack.last_mut() {
+                        element
+                            .text
+                            .push_str(std::str::from_utf8(&buf[open.len()..end])?);
+                    }
+                }
+                *pos += end + close.len();
+                continue;
+            }
+
+            let Some(end) = tag_end(buf) else {
+                return Ok(());
+            };
+            // Errors leave `pos` on the offending tag for `push` to skip
+            let tag = std::str::from_utf8(&buf[1..end])?;
+            if let Some(name) = tag.strip_prefix('/') {
+                let element = self
+                    .stack
+                    .pop()
+                    .ok_or_else(|| anyhow!("unexpected </{}>", name.trim()))?;
+                if element.name != name.trim() {
+                    bail!("</{}> closes <{}>", name.trim(), element.name);
+                }
+                self.close(element, done);
+            } else if let Some(tag) = tag.strip_suffix('/') {
+                let element = parse_tag(tag)?;
+                self.close(element, done);
+            } else {
+                let element = parse_tag(tag)?;
+                self.stack.push(element);
+            }
+            *pos += end + 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEF: &str = r#"<?xml version="1.0"?>
+<defNumberVector device="Telescope Simulator" name="EQUATORIAL_EOD_COORD" label="Eq. Coordinates" group="Main Control" state="Idle" perm="rw" timeout="60" timestamp="2026-01-01T00:00:00">
+    <defNumber name="RA" label="RA (hh:mm:ss)" format="%010.6m" min="0" max="24" step="0">
+      12.5
+    </defNumber>
+    <!-- a comment -->
+    <defNumber name="DEC" label="DEC (dd:mm:ss)" format="%010.6m" min="-90" max="90" step="0">-45:30:00</defNumber>
+</defNumberVector>
+<message device="Telescope Simulator" message="Tom &amp; Jerry &lt;3 &#65;"/>"#;
+
+    #[test]
+    fn parses_elements_split_at_every_byte() {
+        let mut stream = XmlStream::default();
+        let mut elements = Vec::new();
+        for b in DEF.as_bytes() {
+            elements.extend(stream.push(std::slice::from_ref(b)));
+        }
+        assert_eq!(elements.len(), 2);
+
+        let def = &elements[0];
+        assert_eq!(def.name, "defNumberVector");
+        assert_eq!(def.get("label"), Some("Eq. Coordinates"));
+        assert_eq!(def.children.len(), 2);
+        assert_eq!(def.children[0].text.trim(), "12.5");
+        assert_eq!(def.children[1].get("min"), Some("-90"));
+        assert_eq!(def.children[1].text, "-45:30:00");
+        assert_eq!(elements[1].get("message"), Some("Tom & Jerry <3 A"));
+        assert!(def.require("rule").is_err());
+    }
+
+    #[test]
+    fn write_round_trip() {
+        let element = Element::new("newSwitchVector")
+            .attr("device", "CCD \"Guide\"")
+            .attr("name", "CONNECTION")
+            .child(
+                Element::new("oneSwitch")
+                    .attr("name", "CONNECT")
+                    .with_text("On"),
+            );
+        let xml = element.to_xml();
+        assert!(xml.starts_with("<newSwitchVector device=\"CCD &quot;Guide&quot;\""));
+
+        let parsed = XmlStream::default().push(xml.as_bytes());
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].get("device"), Some("CCD \"Guide\""));
+        assert_eq!(parsed[0].children[0].text, "On");
+    }
+
+    #[test]
+    fn recovers_after_malformed_input() {
+        let mut stream = XmlStream::default();
+        let elements = stream.push(b"<a><b></a><ok x='1>2'/><c y=3/><d>&bogus;</d><e/>");
+        let names: Vec<_> = elements.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["ok", "e"]);
+        assert_eq!(elements[0].get("x"), Some("1>2"));
+        assert_eq!(stream.errors, 4);
+        assert!(stream.last_error.unwrap().contains("unexpected </d>"));
+    }
+}
@@ -0,0 +1,287 @@
+// Batched intensity frames along the path or over time (frozen-flow turbulence)
+// Module: beam_patterns/animation.rs | Lines: ~255 | Tier: Module (<350)
+
+use crate::beam_patterns::field::{self, Complex, PIXEL_PITCH_M};
+use crate::beam_patterns::gaussian::viridis_colormap;
+use crate::beam_patterns::propagation;
+use crate::beam_patterns::turbulence::{self, phase_screen, Rng, TurbulenceConfig};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on frames per call
+pub const MAX_FRAMES: u32 = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimationMode {
+    /// Frames at evenly spaced distances from the waist to the end of the path
+    Path,
+    /// Received beam at the end of the path while the wind carries the
+    /// screens across it
+    Time,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnimationConfig {
+    pub mode: AnimationMode,
+    pub frames: u32,
+    /// Time step between frames in `Time` mode, s
+    pub frame_interval_s: f64,
+    /// Scale every frame to the brightest one instead of to itself, so the
+    /// beam visibly fades as it spreads
+    pub shared_scale: bool,
+}
+
+impl Default for AnimationConfig {
+    fn default() -> Self {
+        Self {
+            mode: AnimationMode::Path,
+            frames: 16,
+            frame_interval_s: 0.001,
+            shared_scale: false,
+        }
+    }
+}
+
+/// Per-frame metadata, in frame order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameInfo {
+    pub index: u32,
+    /// Distance from the waist, m
+    pub distance_m: f64,
+    pub time_s: f64,
+    pub peak_w_m2: f64,
+    /// Power inside the rendered window, W
+    pub power_w: f64,
+    /// Second-moment (D4σ / 2) radius about the centroid, m
+    pub radius_m: f64,
+    /// Beam wander, m off axis
+    pub centroid_x_m: f64,
+    pub centroid_y_m: f64,
+}
+
+/// `frames` RGBA images of width x height packed back to back in `data`
+#[derive(Debug, Clone)]
+pub struct Animation {
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<FrameInfo>,
+    pub data: Vec<u8>,
+}
+
+impl Animation {
+    pub fn frame_len(&self) -> usize {
+        (self.width * self.height * 4) as usize
+    }
+}
+
+/// Animate the width x height waist `field` (1 mm grid, sqrt(W/m²))
+pub fn animate(
+    field: &[Complex],
+    width: u32,
+    height: u32,
+    wavelength_m: f64,
+    turbulence: &TurbulenceConfig,
+    config: &AnimationConfig,
+) -> Result<Animation, String> {
+    let frames = match config.mode {
+        AnimationMode::Path if config.frames < 2 => return Err("Path animation needs at least 2 frames".into()),
+        _ if config.frames == 0 || config.frames > MAX_FRAMES => {
+            return Err(format!("Frame count must be within 1..={}", MAX_FRAMES))
+        }
+        AnimationMode::Path => path_frames(field, width, height, wavelength_m, turbulence, config.frames),
+        AnimationMode::Time => time_frames(field, width, height, wavelength_m, turbulence, config)?,
+    };
+
+    let shared_peak = frames.iter().map(|(info, _)| info.peak_w_m2).fold(0.0, f64::max);
+    let mut data = Vec::with_capacity(frames.len() * (width * height * 4) as usize);
+    for (info, intensity) in &frames {
+        let peak = if config.shared_scale { shared_peak } else { info.peak_w_m2 };
+        data.extend(intensity.iter().flat_map(|&i| {
+            let (r, g, b) = viridis_colormap(if peak > 0.0 { i / peak } else { 0.0 });
+            [r, g, b, 255]
+        }));
+    }
+
+    Ok(Animation {
+        width,
+        height,
+        frames: frames.into_iter().map(|(info, _)| info).collect(),
+        data,
+    })
+}
+
+type Frame = (FrameInfo, Vec<f64>);
+
+/// One split step per frame; the screen strength is set so the whole path
+/// still has the configured r0
+fn path_frames(
+    field: &[Complex],
+    width: u32,
+    height: u32,
+    wavelength_m: f64,
+    turbulence: &TurbulenceConfig,
+    frames: u32,
+) -> Vec<Frame> {
+    let n = propagation::grid_size(width, height);
+    let mut grid = propagation::embed(field, width, height, n);
+    let steps = frames - 1;
+    let step = turbulence.path_length_m / steps as f64;
+    let r0 = turbulence::screen_r0(turbulence, wavelength_m, steps);
+    let mut rng = Rng::new(turbulence.seed);
+
+    let mut out = vec![capture(&grid, width, height, n, 0, 0.0, 0.0)];
+    for index in 1..frames {
+        if r0.is_finite() {
+            let screen = phase_screen(n, PIXEL_PITCH_M, r0, turbulence.inner_scale_m, turbulence.outer_scale_m, &mut rng);
+            turbulence::apply_screen(&mut grid, n, &screen, n, 0);
+        }
+        propagation::propagate(&mut grid, n, PIXEL_PITCH_M, wavelength_m, step);
+        out.push(capture(&grid, width, height, n, index, index as f64 * step, 0.0));
+    }
+    out
+}
+
+/// Taylor frozen flow: the screens are drawn once, wide enough for the whole
+/// animation, and slide across the beam at the wind speed
+fn time_frames(
+    field: &[Complex],
+    width: u32,
+    height: u32,
+    wavelength_m: f64,
+    turbulence: &TurbulenceConfig,
+    config: &AnimationConfig,
+) -> Result<Vec<Frame>, String> {
+    let n = propagation::grid_size(width, height);
+    let shift_per_frame = turbulence.wind_speed_m_s.abs() * config.frame_interval_s / PIXEL_PITCH_M;
+    let max_shift = (shift_per_frame * (config.frames - 1) as f64).round() as usize;
+    let m = (n + max_shift).next_power_of_two();
+    if m > 2 * n {
+        return Err("Wind drift over the animation exceeds the grid; use fewer frames or a shorter interval".into());
+    }
+
+    let screens = turbulence.screens.max(1);
+    let step = turbulence.path_length_m / screens as f64;
+    let r0 = turbulence::screen_r0(turbulence, wavelength_m, screens);
+    let stack: Vec<Vec<f64>> = if r0.is_finite() {
+        let mut rng = Rng::new(turbulence.seed);
+        (0..screens)
+            .map(|_| phase_screen(m, PIXEL_PITCH_M, r0, turbulence.inner_scale_m, turbulence.outer_scale_m, &mut rng))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let waist = propagation::embed(field, width, height, n);
+    let mut out = Vec::with_capacity(config.frames as usize);
+    for index in 0..config.frames {
+        // phi_t(x) = phi_0(x - v t): the window slides back along the screen
+        let shift = (shift_per_frame * index as f64).round() as usize;
+        let offset = if turbulence.wind_speed_m_s >= 0.0 { max_shift - shift } else { shift };
+        let mut grid = waist.clone();
+        for s in 0..screens as usize {
+            if let Some(screen) = stack.get(s) {
+                turbulence::apply_screen(&mut grid, n, screen, m, offset);
+            }
+            propagation::propagate(&mut grid, n, PIXEL_PITCH_M, wavelength_m, step);
+        }
+        let time = index as f64 * config.frame_interval_s;
+        out.push(capture(&grid, width, height, n, index, turbulence.path_length_m, time));
+    }
+    Ok(out)
+}
+
+fn capture(grid: &[Complex], width: u32, height: u32, n: usize, index: u32, distance_m: f64, time_s: f64) -> Frame {
+    let intensity: Vec<f64> = propagation::crop(grid, width, height, n).iter().map(|c| c.norm_sqr()).collect();
+    let (mut sum, mut sx, mut sy) = (0.0, 0.0, 0.0);
+    for (i, &v) in intensity.iter().enumerate() {
+        let (x, y) = field::grid_point(i as u32 % width, i as u32 / width, width, height);
+        sum += v;
+        sx += v * x;
+        sy += v * y;
+    }
+    let (cx, cy) = if sum > 0.0 { (sx / sum, sy / sum) } else { (0.0, 0.0) };
+    let second_moment = intensity
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let (x, y) = field::grid_point(i as u32 % width, i as u32 / width, width, height);
+            v * ((x - cx).powi(2) + (y - cy).powi(2))
+        })
+        .sum::<f64>();
+
+    let info = FrameInfo {
+        index,
+        distance_m,
+        time_s,
+        peak_w_m2: intensity.iter().cloned().fold(0.0, f64::max),
+        power_w: sum * PIXEL_PITCH_M * PIXEL_PITCH_M,
+        radius_m: if sum > 0.0 { (2.0 * second_moment / sum).sqrt() } else { 0.0 },
+        centroid_x_m: cx,
+        centroid_y_m: cy,
+    };
+    (info, intensity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beam_patterns::field::BeamGeometry;
+    use crate::beam_patterns::gaussian::compute_gaussian_field;
+    use crate::ecs::components::BeamParameters;
+
+    fn waist(width: u32, height: u32) -> (BeamParameters, Vec<Complex>) {
+        let params = BeamParameters {
+            waist_radius_mm: 6.0,
+            ..BeamParameters::default()
+        };
+        let field = compute_gaussian_field(&params, width, height);
+        (params, field)
+    }
+
+    #[test]
+    fn test_path_frames_follow_beam_width() {
+        let (params, field) = waist(64, 64);
+        let calm = TurbulenceConfig::from_cn2(0.0);
+        let config = AnimationConfig { frames: 5, ..AnimationConfig::default() };
+        let anim = animate(&field, 64, 64, params.wavelength_nm * 1e-9, &calm, &config).unwrap();
+
+        assert_eq!(anim.frames.len(), 5);
+        assert_eq!(anim.data.len(), 5 * anim.frame_len());
+        for info in &anim.frames {
+            let w = BeamGeometry::at(&params, info.distance_m).w;
+            assert!((info.radius_m / w - 1.0).abs() < 0.05, "{}: {} vs {}", info.index, info.radius_m, w);
+            assert!((info.power_w - params.power_watts).abs() < 0.01 * params.power_watts);
+        }
+        assert_eq!(anim.frames[4].distance_m, calm.path_length_m);
+    }
+
+    #[test]
+    fn test_time_frames_evolve_with_wind() {
+        let (params, field) = waist(32, 32);
+        let wavelength = params.wavelength_nm * 1e-9;
+        let config = AnimationConfig { mode: AnimationMode::Time, frames: 4, ..AnimationConfig::default() };
+        let mut turbulence = TurbulenceConfig { cn2: 1e-12, ..TurbulenceConfig::default() };
+
+        let windy = animate(&field, 32, 32, wavelength, &turbulence, &config).unwrap();
+        let frame = |a: &Animation, i: usize| a.data[i * a.frame_len()..(i + 1) * a.frame_len()].to_vec();
+        assert_ne!(frame(&windy, 0), frame(&windy, 3));
+        assert!((windy.frames[3].time_s - 0.003).abs() < 1e-12);
+
+        turbulence.wind_speed_m_s = 0.0;
+        let still = animate(&field, 32, 32, wavelength, &turbulence, &config).unwrap();
+        assert_eq!(frame(&still, 0), frame(&still, 3));
+    }
+
+    #[test]
+    fn test_rejects_bad_frame_counts() {
+        let (_, field) = waist(16, 16);
+        let turbulence = TurbulenceConfig::default();
+        for (mode, frames) in [(AnimationMode::Path, 1), (AnimationMode::Time, 0), (AnimationMode::Time, MAX_FRAMES + 1)] {
+            let config = AnimationConfig { mode, frames, ..AnimationConfig::default() };
+            assert!(animate(&field, 16, 16, 1.55e-6, &turbulence, &config).is_err());
+        }
+        let long = AnimationConfig { mode: AnimationMode::Time, frames: 200, frame_interval_s: 0.01, ..AnimationConfig::default() };
+        assert!(animate(&field, 16, 16, 1.55e-6, &turbulence, &long).is_err());
+    }
+}
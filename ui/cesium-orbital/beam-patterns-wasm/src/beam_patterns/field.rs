@@ -0,0 +1,194 @@
+// Complex field helpers shared by the higher-order mode generators
+// Module: beam_patterns/field.rs | Lines: ~195 | Tier: Simple (<200)
+
+use crate::beam_patterns::gaussian::viridis_colormap;
+use crate::ecs::components::BeamParameters;
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+/// Grid spacing shared by every generator: 1 mm per pixel, beam axis at
+/// the grid centre
+pub const PIXEL_PITCH_M: f64 = 0.001;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    pub fn from_polar(r: f64, theta: f64) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        Self::new(r * cos, r * sin)
+    }
+
+    pub fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    pub fn scale(self, factor: f64) -> Self {
+        Self::new(self.re * factor, self.im * factor)
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+/// Gaussian-beam envelope at distance `z` (m) from the waist
+#[derive(Debug, Clone, Copy)]
+pub struct BeamGeometry {
+    /// Wavenumber, rad/m
+    pub k: f64,
+    /// 1/e² radius, m
+    pub w: f64,
+    /// Wavefront curvature 1/R, 1/m (zero at the waist)
+    pub curvature: f64,
+    /// Gouy phase of the fundamental mode, rad
+    pub gouy: f64,
+}
+
+impl BeamGeometry {
+    pub fn at(params: &BeamParameters, z: f64) -> Self {
+        let lambda = params.wavelength_nm * 1e-9;
+        let w0 = params.waist_radius_mm / 1000.0;
+        let z_r = PI * w0 * w0 / lambda;
+        Self {
+            k: 2.0 * PI / lambda,
+            w: w0 * (1.0 + (z / z_r).powi(2)).sqrt(),
+            curvature: z / (z * z + z_r * z_r),
+            gouy: (z / z_r).atan(),
+        }
+    }
+}
+
+pub fn factorial(n: u32) -> f64 {
+    (1..=n).map(f64::from).product()
+}
+
+/// Physical coordinates (m) of a pixel centre
+pub fn grid_point(x: u32, y: u32, width: u32, height: u32) -> (f64, f64) {
+    (
+        (x as f64 - width as f64 / 2.0) * PIXEL_PITCH_M,
+        (y as f64 - height as f64 / 2.0) * PIXEL_PITCH_M,
+    )
+}
+
+/// Sample `f(x, y)` over the grid, row-major
+pub fn sample(width: u32, height: u32, f: impl Fn(f64, f64) -> Complex) -> Vec<Complex> {
+    let mut field = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let (px, py) = grid_point(x, y, width, height);
+            field.push(f(px, py));
+        }
+    }
+    field
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Render {
+    /// Normalized intensity on viridis
+    Intensity,
+    /// Phase as hue, brightness following the amplitude; shows the OAM helix
+    Phase,
+}
+
+impl Render {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "intensity" => Some(Self::Intensity),
+            "phase" => Some(Self::Phase),
+            _ => None,
+        }
+    }
+}
+
+pub fn to_rgba(field: &[Complex], render: Render) -> Vec<u8> {
+    let max_intensity = field.iter().map(|c| c.norm_sqr()).fold(0.0, f64::max);
+    let mut rgba = Vec::with_capacity(field.len() * 4);
+    for c in field {
+        let normalized = if max_intensity > 0.0 {
+            c.norm_sqr() / max_intensity
+        } else {
+            0.0
+        };
+        let (r, g, b) = match render {
+            Render::Intensity => viridis_colormap(normalized),
+            Render::Phase => hue_colormap(c.arg(), normalized.sqrt()),
+        };
+        rgba.extend_from_slice(&[r, g, b, 255]);
+    }
+    rgba
+}
+
+/// Cyclic hue for a phase in (-pi, pi], scaled by `value` in [0, 1]
+fn hue_colormap(phase: f64, value: f64) -> (u8, u8, u8) {
+    let h = (phase + PI) / (2.0 * PI) * 6.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    let (r, g, b) = match h as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    let v = value.clamp(0.0, 1.0) * 255.0;
+    ((r * v) as u8, (g * v) as u8, (b * v) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geometry_at_rayleigh_range() {
+        let params = BeamParameters::default();
+        let w0 = params.waist_radius_mm / 1000.0;
+        let z_r = PI * w0 * w0 / (params.wavelength_nm * 1e-9);
+        let geo = BeamGeometry::at(&params, z_r);
+        assert!((geo.w - w0 * 2f64.sqrt()).abs() < 1e-12);
+        assert!((geo.gouy - PI / 4.0).abs() < 1e-12);
+        assert!((geo.curvature - 1.0 / (2.0 * z_r)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_complex_ops() {
+        let a = Complex::from_polar(2.0, PI / 2.0);
+        let b = a * Complex::new(0.0, 1.0) + Complex::new(1.0, 0.0);
+        assert!((b.re + 1.0).abs() < 1e-12 && b.im.abs() < 1e-12);
+        assert_eq!(factorial(5), 120.0);
+    }
+}
@@ -1,51 +1,33 @@
 // Gaussian beam pattern generator
-// Module: beam_patterns/gaussian.rs | Lines: ~180 | Tier: Simple (<200)
+// Module: beam_patterns/gaussian.rs | Lines: ~120 | Tier: Simple (<200)
 
+use crate::beam_patterns::field::{self, Complex, Render};
+use crate::beam_patterns::turbulence::{self, TurbulenceConfig};
 use crate::ecs::components::{BeamParameters, AtmosphericConditions};
 use std::f64::consts::PI;
 
+/// Gaussian beam after the turbulent path set by `conditions.cn2_turbulence`
+/// (see `turbulence::DEFAULT_PATH_M`)
 pub fn generate_gaussian_beam(
     params: &BeamParameters,
     conditions: &AtmosphericConditions,
     width: u32,
     height: u32,
 ) -> Vec<u8> {
-    let center_x = width as f64 / 2.0;
-    let center_y = height as f64 / 2.0;
-    let w0 = params.waist_radius_mm / 1000.0;
-
-    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
-    let mut max_intensity = 0.0_f64;
-    let mut intensities = Vec::with_capacity((width * height) as usize);
-
-    // Compute intensities
-    for y in 0..height {
-        for x in 0..width {
-            let dx = (x as f64 - center_x) * 0.001;
-            let dy = (y as f64 - center_y) * 0.001;
-            let r2 = dx * dx + dy * dy;
-
-            let intensity = (params.power_watts / (PI * w0 * w0))
-                * (-2.0 * r2 / (w0 * w0)).exp();
-
-            intensities.push(intensity);
-            if intensity > max_intensity {
-                max_intensity = intensity;
-            }
-        }
-    }
-
-    // Apply turbulence and normalize
-    let turbulence_strength = conditions.cn2_turbulence.log10().abs() / 15.0;
-
-    for intensity in intensities {
-        let turbulence_factor = 1.0 - turbulence_strength * 0.2;
-        let normalized = (intensity / max_intensity * turbulence_factor).clamp(0.0, 1.0);
-        let (r, g, b) = viridis_colormap(normalized);
-        rgba.extend_from_slice(&[r, g, b, 255]);
-    }
+    let waist = compute_gaussian_field(params, width, height);
+    let config = TurbulenceConfig::from_cn2(conditions.cn2_turbulence);
+    let received =
+        turbulence::propagate_turbulent(&waist, width, height, params.wavelength_nm * 1e-9, &config);
+    field::to_rgba(&received, Render::Intensity)
+}
 
-    rgba
+/// Complex field at the waist, sqrt(W/m²)
+pub fn compute_gaussian_field(params: &BeamParameters, width: u32, height: u32) -> Vec<Complex> {
+    let w0 = params.waist_radius_mm / 1000.0;
+    let peak = (2.0 * params.power_watts / (PI * w0 * w0)).sqrt();
+    field::sample(width, height, |x, y| {
+        Complex::new(peak * (-(x * x + y * y) / (w0 * w0)).exp(), 0.0)
+    })
 }
 
 pub fn compute_gaussian_pattern(
@@ -73,7 +55,7 @@ pub fn compute_gaussian_pattern(
     data
 }
 
-fn viridis_colormap(t: f64) -> (u8, u8, u8) {
+pub(crate) fn viridis_colormap(t: f64) -> (u8, u8, u8) {
     // Viridis colormap approximation for scientific visualization
     let t = t.clamp(0.0, 1.0);
 
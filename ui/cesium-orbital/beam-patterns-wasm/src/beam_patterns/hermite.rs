@@ -0,0 +1,98 @@
+// Hermite-Gaussian beam modes (rectangular symmetry)
+// Module: beam_patterns/hermite.rs | Lines: ~100 | Tier: Simple (<200)
+
+use crate::beam_patterns::field::{self, factorial, BeamGeometry, Complex, Render};
+use crate::ecs::components::BeamParameters;
+use std::f64::consts::PI;
+
+/// Highest index accepted on either axis
+pub const MAX_ORDER: u32 = 20;
+
+/// Physicists' Hermite polynomial H_n(x) by upward recurrence
+pub fn hermite(n: u32, x: f64) -> f64 {
+    let (mut prev, mut curr) = (1.0, 2.0 * x);
+    if n == 0 {
+        return prev;
+    }
+    for k in 1..n {
+        let next = 2.0 * x * curr - 2.0 * k as f64 * prev;
+        prev = curr;
+        curr = next;
+    }
+    curr
+}
+
+/// HG_mn field at (x, y) m off axis and `z` m from the waist, normalized to
+/// unit power; `m` nodes along x, `n` along y
+pub fn hg_field(params: &BeamParameters, m: u32, n: u32, x: f64, y: f64, z: f64) -> Complex {
+    let geo = BeamGeometry::at(params, z);
+    let r2 = x * x + y * y;
+    let scale = 2f64.sqrt() / geo.w;
+
+    let norm = (2.0 / (PI * 2f64.powi((m + n) as i32) * factorial(m) * factorial(n))).sqrt()
+        / geo.w;
+    let amplitude =
+        norm * hermite(m, scale * x) * hermite(n, scale * y) * (-r2 / (geo.w * geo.w)).exp();
+    let phase = -geo.k * r2 * geo.curvature / 2.0 + (m + n + 1) as f64 * geo.gouy;
+    Complex::from_polar(amplitude, phase)
+}
+
+pub fn compute_hg_field(
+    params: &BeamParameters,
+    m: u32,
+    n: u32,
+    z: f64,
+    width: u32,
+    height: u32,
+) -> Vec<Complex> {
+    let amplitude = params.power_watts.sqrt();
+    field::sample(width, height, |x, y| {
+        hg_field(params, m, n, x, y, z).scale(amplitude)
+    })
+}
+
+pub fn generate_hg_beam(
+    params: &BeamParameters,
+    m: u32,
+    n: u32,
+    z: f64,
+    width: u32,
+    height: u32,
+    render: Render,
+) -> Vec<u8> {
+    field::to_rgba(&compute_hg_field(params, m, n, z, width, height), render)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beam_patterns::field::PIXEL_PITCH_M;
+
+    #[test]
+    fn test_hermite_values() {
+        // H_3(x) = 8x^3 - 12x
+        assert!((hermite(3, 0.7) - (8.0 * 0.343 - 8.4)).abs() < 1e-12);
+        assert_eq!(hermite(0, 5.0), 1.0);
+    }
+
+    #[test]
+    fn test_hg_power_and_nodes() {
+        let params = BeamParameters::default();
+        for (m, n) in [(0, 0), (1, 0), (2, 3)] {
+            let field = compute_hg_field(&params, m, n, 0.0, 160, 160);
+            let power: f64 =
+                field.iter().map(|c| c.norm_sqr()).sum::<f64>() * PIXEL_PITCH_M * PIXEL_PITCH_M;
+            assert!((power - params.power_watts).abs() < 0.01, "HG{}{}: {}", m, n, power);
+        }
+        // HG10 has a nodal line along x = 0
+        assert_eq!(hg_field(&params, 1, 0, 0.0, 0.004, 0.0).norm_sqr(), 0.0);
+        assert!(hg_field(&params, 1, 0, 0.004, 0.0, 0.0).norm_sqr() > 0.0);
+    }
+
+    #[test]
+    fn test_hg_generation() {
+        let params = BeamParameters::default();
+        let pattern = generate_hg_beam(&params, 1, 1, 0.0, 50, 50, Render::Intensity);
+        assert_eq!(pattern.len(), 50 * 50 * 4);
+    }
+}
@@ -0,0 +1,116 @@
+// Laguerre-Gaussian beam modes (orbital angular momentum)
+// Module: beam_patterns/laguerre.rs | Lines: ~115 | Tier: Simple (<200)
+
+use crate::beam_patterns::field::{self, factorial, BeamGeometry, Complex, Render};
+use crate::ecs::components::BeamParameters;
+use std::f64::consts::PI;
+
+/// Highest radial index or |azimuthal| index accepted
+pub const MAX_ORDER: u32 = 20;
+
+/// Generalized Laguerre polynomial L_p^alpha(x) by upward recurrence
+pub fn laguerre(p: u32, alpha: f64, x: f64) -> f64 {
+    let (mut prev, mut curr) = (1.0, 1.0 + alpha - x);
+    if p == 0 {
+        return prev;
+    }
+    for k in 1..p {
+        let k = k as f64;
+        let next = ((2.0 * k + 1.0 + alpha - x) * curr - (k + alpha) * prev) / (k + 1.0);
+        prev = curr;
+        curr = next;
+    }
+    curr
+}
+
+/// LG_pl field at (x, y) m off axis and `z` m from the waist, normalized to
+/// unit power. `l` is the topological charge: l·ħ of OAM per photon
+pub fn lg_field(params: &BeamParameters, p: u32, l: i32, x: f64, y: f64, z: f64) -> Complex {
+    let geo = BeamGeometry::at(params, z);
+    let order = l.unsigned_abs();
+    let r2 = x * x + y * y;
+    let rho = 2.0 * r2 / (geo.w * geo.w);
+
+    let norm = (2.0 * factorial(p) / (PI * factorial(p + order))).sqrt() / geo.w;
+    let amplitude = norm
+        * rho.powf(order as f64 / 2.0)
+        * laguerre(p, order as f64, rho)
+        * (-rho / 2.0).exp();
+    let phase = l as f64 * y.atan2(x) - geo.k * r2 * geo.curvature / 2.0
+        + (2 * p + order + 1) as f64 * geo.gouy;
+    Complex::from_polar(amplitude, phase)
+}
+
+pub fn compute_lg_field(
+    params: &BeamParameters,
+    p: u32,
+    l: i32,
+    z: f64,
+    width: u32,
+    height: u32,
+) -> Vec<Complex> {
+    let amplitude = params.power_watts.sqrt();
+    field::sample(width, height, |x, y| {
+        lg_field(params, p, l, x, y, z).scale(amplitude)
+    })
+}
+
+pub fn generate_lg_beam(
+    params: &BeamParameters,
+    p: u32,
+    l: i32,
+    z: f64,
+    width: u32,
+    height: u32,
+    render: Render,
+) -> Vec<u8> {
+    field::to_rgba(&compute_lg_field(params, p, l, z, width, height), render)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beam_patterns::field::PIXEL_PITCH_M;
+
+    #[test]
+    fn test_laguerre_values() {
+        // L_2^1(x) = (x^2 - 6x + 6) / 2
+        assert!((laguerre(2, 1.0, 1.5) - (2.25 - 9.0 + 6.0) / 2.0).abs() < 1e-12);
+        assert_eq!(laguerre(0, 3.0, 7.0), 1.0);
+    }
+
+    #[test]
+    fn test_lg_power_and_vortex() {
+        let params = BeamParameters::default();
+        for (p, l) in [(0, 1), (1, -2), (2, 3)] {
+            let field = compute_lg_field(&params, p, l, 0.0, 160, 160);
+            let power: f64 =
+                field.iter().map(|c| c.norm_sqr()).sum::<f64>() * PIXEL_PITCH_M * PIXEL_PITCH_M;
+            assert!((power - params.power_watts).abs() < 0.01, "LG{}{}: {}", p, l, power);
+            // Dark core for any charge
+            assert!(field[80 * 160 + 80].norm_sqr() < 1e-12);
+        }
+
+        // Phase winds by 2*pi*l around the axis
+        let r = 0.01;
+        let samples = 64;
+        let winding: f64 = (0..samples)
+            .map(|i| {
+                let a = 2.0 * PI * i as f64 / samples as f64;
+                let b = 2.0 * PI * (i + 1) as f64 / samples as f64;
+                let pa = lg_field(&params, 0, 3, r * a.cos(), r * a.sin(), 0.0).arg();
+                let pb = lg_field(&params, 0, 3, r * b.cos(), r * b.sin(), 0.0).arg();
+                let d = pb - pa;
+                d - 2.0 * PI * (d / (2.0 * PI)).round()
+            })
+            .sum();
+        assert!((winding - 6.0 * PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lg_generation() {
+        let params = BeamParameters::default();
+        let pattern = generate_lg_beam(&params, 1, 2, 100.0, 64, 48, Render::Phase);
+        assert_eq!(pattern.len(), 64 * 48 * 4);
+    }
+}
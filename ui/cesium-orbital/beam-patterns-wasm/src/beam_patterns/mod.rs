@@ -1,3 +1,10 @@
 // Beam pattern generators module
 pub mod gaussian;
 pub mod bessel;
+pub mod field;
+pub mod laguerre;
+pub mod hermite;
+pub mod propagation;
+pub mod turbulence;
+pub mod animation;
+pub mod receiver;
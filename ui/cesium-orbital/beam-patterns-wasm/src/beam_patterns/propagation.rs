@@ -0,0 +1,157 @@
+// FFT and angular-spectrum propagation of complex beam fields
+// Module: beam_patterns/propagation.rs | Lines: ~160 | Tier: Simple (<200)
+
+use crate::beam_patterns::field::Complex;
+use std::f64::consts::PI;
+
+/// In-place radix-2 FFT; `data.len()` must be a power of two. The inverse
+/// is unnormalized
+pub fn fft(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    debug_assert!(n.is_power_of_two());
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let step = Complex::from_polar(1.0, sign * 2.0 * PI / len as f64);
+        for chunk in data.chunks_mut(len) {
+            let (lower, upper) = chunk.split_at_mut(len / 2);
+            let mut twiddle = Complex::new(1.0, 0.0);
+            for (a, b) in lower.iter_mut().zip(upper) {
+                let t = *b * twiddle;
+                *b = *a - t;
+                *a = *a + t;
+                twiddle = twiddle * step;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// 2D FFT of a row-major n x n grid
+pub fn fft2(data: &mut [Complex], n: usize, inverse: bool) {
+    for row in data.chunks_mut(n) {
+        fft(row, inverse);
+    }
+    let mut column = vec![Complex::default(); n];
+    for x in 0..n {
+        for (y, c) in column.iter_mut().enumerate() {
+            *c = data[y * n + x];
+        }
+        fft(&mut column, inverse);
+        for (y, c) in column.iter().enumerate() {
+            data[y * n + x] = *c;
+        }
+    }
+}
+
+/// Spatial frequency (cycles/m) of FFT bin `i` on an n-point grid
+pub fn frequency(i: usize, n: usize, pitch: f64) -> f64 {
+    let k = if i < n / 2 {
+        i as f64
+    } else {
+        i as f64 - n as f64
+    };
+    k / (n as f64 * pitch)
+}
+
+/// Free-space propagation over `distance` m (angular spectrum, paraxial)
+pub fn propagate(field: &mut [Complex], n: usize, pitch: f64, wavelength_m: f64, distance: f64) {
+    fft2(field, n, false);
+    let scale = 1.0 / (n * n) as f64;
+    for (i, c) in field.iter_mut().enumerate() {
+        let fx = frequency(i % n, n, pitch);
+        let fy = frequency(i / n, n, pitch);
+        let phase = -PI * wavelength_m * distance * (fx * fx + fy * fy);
+        *c = *c * Complex::from_polar(scale, phase);
+    }
+    fft2(field, n, true);
+}
+
+/// Side of the square simulation grid for a width x height pattern: a power
+/// of two with a guard band so the spreading beam doesn't wrap around
+pub fn grid_size(width: u32, height: u32) -> usize {
+    (2 * width.max(height) as usize).next_power_of_two()
+}
+
+fn offsets(n: usize, width: u32, height: u32) -> (usize, usize) {
+    (n / 2 - width as usize / 2, n / 2 - height as usize / 2)
+}
+
+/// Centre a width x height field in an n x n grid of zeros
+pub fn embed(field: &[Complex], width: u32, height: u32, n: usize) -> Vec<Complex> {
+    let (ox, oy) = offsets(n, width, height);
+    let mut grid = vec![Complex::default(); n * n];
+    for (y, row) in field.chunks(width as usize).enumerate() {
+        let start = (y + oy) * n + ox;
+        grid[start..start + row.len()].copy_from_slice(row);
+    }
+    grid
+}
+
+/// Inverse of `embed`
+pub fn crop(grid: &[Complex], width: u32, height: u32, n: usize) -> Vec<Complex> {
+    let (ox, oy) = offsets(n, width, height);
+    let mut field = Vec::with_capacity((width * height) as usize);
+    for y in 0..height as usize {
+        let start = (y + oy) * n + ox;
+        field.extend_from_slice(&grid[start..start + width as usize]);
+    }
+    field
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beam_patterns::field::{self, PIXEL_PITCH_M};
+
+    #[test]
+    fn test_fft_round_trip() {
+        let data: Vec<Complex> = (0..16)
+            .map(|i| Complex::new(i as f64, (i * i) as f64 * 0.1))
+            .collect();
+        let mut work = data.clone();
+        fft(&mut work, false);
+        // DC bin is the sum
+        let sum: f64 = data.iter().map(|c| c.re).sum();
+        assert!((work[0].re - sum).abs() < 1e-9);
+        fft(&mut work, true);
+        for (a, b) in data.iter().zip(&work) {
+            assert!((a.re - b.re / 16.0).abs() < 1e-9 && (a.im - b.im / 16.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_gaussian_spreads_to_rayleigh_width() {
+        let (wavelength, w0) = (1.55e-6, 0.005);
+        let z_r = PI * w0 * w0 / wavelength;
+        let (width, height) = (64, 64);
+        let field = field::sample(width, height, |x, y| {
+            Complex::new((-(x * x + y * y) / (w0 * w0)).exp(), 0.0)
+        });
+        let n = grid_size(width, height);
+        let mut grid = embed(&field, width, height, n);
+        let power_before: f64 = grid.iter().map(|c| c.norm_sqr()).sum();
+        propagate(&mut grid, n, PIXEL_PITCH_M, wavelength, z_r);
+        let power_after: f64 = grid.iter().map(|c| c.norm_sqr()).sum();
+        assert!((power_after / power_before - 1.0).abs() < 1e-9);
+
+        // w^2 doubles at one Rayleigh range, so the peak halves
+        let out = crop(&grid, width, height, n);
+        let peak = out[32 * 64 + 32].norm_sqr();
+        assert!((peak - 0.5).abs() < 0.02, "{}", peak);
+    }
+}
@@ -0,0 +1,227 @@
+// Received power: beam intensity integrated over a receiver aperture
+// Module: beam_patterns/receiver.rs | Lines: ~235 | Tier: Module (<350)
+
+use crate::beam_patterns::{hermite, laguerre};
+use crate::ecs::components::{atmospheric_transmission, BeamParameters};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Quadrature over the aperture disk: rings x spokes midpoint samples
+const RINGS: usize = 48;
+const SPOKES: usize = 96;
+
+/// Transverse mode of the transmitted beam
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Gaussian,
+    Lg(u32, i32),
+    Hg(u32, u32),
+}
+
+impl Mode {
+    /// Intensity of the unit-power mode, 1/m²
+    pub fn intensity(self, params: &BeamParameters, x: f64, y: f64, z: f64) -> f64 {
+        match self {
+            Mode::Gaussian => laguerre::lg_field(params, 0, 0, x, y, z).norm_sqr(),
+            Mode::Lg(p, l) => laguerre::lg_field(params, p, l, x, y, z).norm_sqr(),
+            Mode::Hg(m, n) => hermite::hg_field(params, m, n, x, y, z).norm_sqr(),
+        }
+    }
+}
+
+/// Receiver placement and the path between it and the transmitter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReceiverConfig {
+    /// Slant range from the beam waist, m
+    pub range_m: f64,
+    /// Aperture centre relative to the beam axis, m (pointing error times range)
+    pub offset_x_m: f64,
+    pub offset_y_m: f64,
+    pub aperture_diameter_m: f64,
+    pub elevation_deg: f64,
+    /// Refractive-index structure constant, m^-2/3, taken as uniform over
+    /// `turbulent_path_m`
+    pub cn2: f64,
+    /// Part of the range inside the turbulent atmosphere, m
+    pub turbulent_path_m: f64,
+    pub cloud_cover_percent: f64,
+    /// Power needed at the detector for the target BER, dBm
+    pub sensitivity_dbm: f64,
+}
+
+impl Default for ReceiverConfig {
+    fn default() -> Self {
+        Self {
+            range_m: 1000e3,
+            offset_x_m: 0.0,
+            offset_y_m: 0.0,
+            aperture_diameter_m: 0.5,
+            elevation_deg: 90.0,
+            cn2: 1e-15,
+            turbulent_path_m: 20e3,
+            cloud_cover_percent: 0.0,
+            sensitivity_dbm: -40.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceivedPower {
+    /// Power collected by the aperture before atmospheric losses, W
+    pub captured_w: f64,
+    /// Long-term 1/e² radius at the receiver, including turbulent spread, m
+    pub beam_radius_m: f64,
+    /// Transmitted power missing a boresighted aperture, dB
+    pub geometric_loss_db: f64,
+    /// Extra loss from the offset versus boresight, dB (negative when the
+    /// offset lands on a brighter part of the mode, e.g. an LG ring)
+    pub pointing_loss_db: f64,
+    pub atmospheric_loss_db: f64,
+    pub cloud_loss_db: f64,
+    pub received_dbm: f64,
+    /// Received power over the receiver sensitivity, dB
+    pub link_margin_db: f64,
+}
+
+/// Long-term beam radius after turbulent spreading (Yura):
+/// W_LT² = W² + 2 (λL / π ρ0)², with the spherical-wave coherence radius
+/// ρ0 = (0.545 k² Cn² L_t)^-3/5
+pub fn long_term_radius(params: &BeamParameters, config: &ReceiverConfig) -> (f64, f64) {
+    let wavelength = params.wavelength_nm * 1e-9;
+    let w0 = params.waist_radius_mm / 1000.0;
+    let z_r = PI * w0 * w0 / wavelength;
+    let w = w0 * (1.0 + (config.range_m / z_r).powi(2)).sqrt();
+
+    let k = 2.0 * PI / wavelength;
+    let turbulent = config.turbulent_path_m.min(config.range_m);
+    let rho0 = (0.545 * k * k * config.cn2.max(0.0) * turbulent).powf(-3.0 / 5.0);
+    let spread = wavelength * config.range_m / (PI * rho0);
+    (w, (w * w + 2.0 * spread * spread).sqrt())
+}
+
+/// Power of the unit-power `mode` inside the aperture centred at
+/// (cx, cy), with the long-term pattern stretched by `stretch`
+fn aperture_fraction(
+    mode: Mode,
+    params: &BeamParameters,
+    z: f64,
+    stretch: f64,
+    diameter: f64,
+    (cx, cy): (f64, f64),
+) -> f64 {
+    let radius = diameter / 2.0;
+    let dr = radius / RINGS as f64;
+    let dtheta = 2.0 * PI / SPOKES as f64;
+    let mut sum = 0.0;
+    for i in 0..RINGS {
+        let r = (i as f64 + 0.5) * dr;
+        for j in 0..SPOKES {
+            let theta = (j as f64 + 0.5) * dtheta;
+            let (x, y) = (cx + r * theta.cos(), cy + r * theta.sin());
+            // Same mode shape, spread over a stretch² larger area
+            sum += mode.intensity(params, x / stretch, y / stretch, z) / (stretch * stretch) * r;
+        }
+    }
+    sum * dr * dtheta
+}
+
+pub fn received_power(params: &BeamParameters, mode: Mode, config: &ReceiverConfig) -> ReceivedPower {
+    let (w, w_lt) = long_term_radius(params, config);
+    let stretch = w_lt / w;
+    let fraction = |offset| {
+        aperture_fraction(mode, params, config.range_m, stretch, config.aperture_diameter_m, offset)
+    };
+    let boresight = fraction((0.0, 0.0));
+    let offset = fraction((config.offset_x_m, config.offset_y_m));
+
+    let captured_w = params.power_watts * offset;
+    let atmospheric_loss_db = -10.0 * atmospheric_transmission(config.elevation_deg).log10();
+    // Same 0.05 dB per percent as the link-margin heuristic
+    let cloud_loss_db = config.cloud_cover_percent * 0.05;
+    let received_dbm = 10.0 * (captured_w * 1000.0).log10() - atmospheric_loss_db - cloud_loss_db;
+
+    ReceivedPower {
+        captured_w,
+        beam_radius_m: w_lt,
+        geometric_loss_db: -10.0 * boresight.log10(),
+        pointing_loss_db: 10.0 * (boresight / offset).log10(),
+        atmospheric_loss_db,
+        cloud_loss_db,
+        received_dbm,
+        link_margin_db: received_dbm - config.sensitivity_dbm,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calm(range_m: f64, aperture_diameter_m: f64) -> ReceiverConfig {
+        ReceiverConfig {
+            range_m,
+            aperture_diameter_m,
+            cn2: 0.0,
+            ..ReceiverConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_gaussian_capture_matches_closed_form() {
+        let params = BeamParameters::default();
+        let config = calm(2000.0, 0.1);
+        let (w, w_lt) = long_term_radius(&params, &config);
+        assert_eq!(w, w_lt);
+
+        let rx = received_power(&params, Mode::Gaussian, &config);
+        let a = config.aperture_diameter_m / 2.0;
+        let expected = params.power_watts * (1.0 - (-2.0 * a * a / (w * w)).exp());
+        assert!((rx.captured_w / expected - 1.0).abs() < 1e-3, "{} vs {}", rx.captured_w, expected);
+        assert!(rx.pointing_loss_db.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gaussian_pointing_loss() {
+        // Small aperture: loss = 10 log10(e) * 2 r² / w²
+        let params = BeamParameters::default();
+        let mut config = calm(100e3, 0.05);
+        let (w, _) = long_term_radius(&params, &config);
+        config.offset_x_m = 0.6 * w;
+        config.offset_y_m = 0.3 * w;
+        let rx = received_power(&params, Mode::Gaussian, &config);
+        let expected = 10.0 * std::f64::consts::LOG10_E * 2.0 * 0.45;
+        assert!((rx.pointing_loss_db - expected).abs() < 0.01, "{}", rx.pointing_loss_db);
+    }
+
+    #[test]
+    fn test_turbulence_and_weather_reduce_margin() {
+        let params = BeamParameters::default();
+        let clear = received_power(&params, Mode::Gaussian, &calm(500e3, 0.5));
+        let rough = received_power(
+            &params,
+            Mode::Gaussian,
+            &ReceiverConfig {
+                range_m: 500e3,
+                cn2: 1e-13,
+                elevation_deg: 30.0,
+                cloud_cover_percent: 20.0,
+                ..ReceiverConfig::default()
+            },
+        );
+        assert!(rough.beam_radius_m > clear.beam_radius_m);
+        assert!(rough.geometric_loss_db > clear.geometric_loss_db);
+        assert!((rough.cloud_loss_db - 1.0).abs() < 1e-12);
+        assert!(rough.link_margin_db < clear.link_margin_db);
+    }
+
+    #[test]
+    fn test_lg_ring_beats_boresight() {
+        let params = BeamParameters::default();
+        let mut config = calm(1000.0, 0.002);
+        let (w, _) = long_term_radius(&params, &config);
+        // LG01 peaks at r = w / sqrt(2)
+        config.offset_x_m = w / 2f64.sqrt();
+        let rx = received_power(&params, Mode::Lg(0, 1), &config);
+        assert!(rx.pointing_loss_db < 0.0);
+    }
+}
@@ -0,0 +1,272 @@
+// Kolmogorov / von Kármán phase screens and split-step turbulent propagation
+// Module: beam_patterns/turbulence.rs | Lines: ~275 | Tier: Module (<350)
+
+use crate::beam_patterns::field::{Complex, PIXEL_PITCH_M};
+use crate::beam_patterns::propagation::{self, frequency};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Path rendered by `generate_beam_pattern`: about one Rayleigh range of the
+/// default beam, so it still fits the 1 mm grid
+pub const DEFAULT_PATH_M: f64 = 200.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TurbulenceConfig {
+    /// Refractive-index structure constant, m^-2/3, uniform along the path
+    pub cn2: f64,
+    pub path_length_m: f64,
+    /// Fried parameter override, m; derived from `cn2` when absent
+    pub r0_m: Option<f64>,
+    /// Inner scale l0, m
+    pub inner_scale_m: f64,
+    /// Outer scale L0, m
+    pub outer_scale_m: f64,
+    /// Phase screens along the path (split-step)
+    pub screens: u32,
+    /// Same seed, same screens; step it to evolve the turbulence
+    pub seed: u64,
+    /// Transverse wind carrying the screens across the beam (frozen flow)
+    pub wind_speed_m_s: f64,
+}
+
+impl Default for TurbulenceConfig {
+    fn default() -> Self {
+        Self {
+            cn2: 1e-15,
+            path_length_m: DEFAULT_PATH_M,
+            r0_m: None,
+            inner_scale_m: 0.005,
+            outer_scale_m: 20.0,
+            screens: 4,
+            seed: 1,
+            wind_speed_m_s: 10.0,
+        }
+    }
+}
+
+impl TurbulenceConfig {
+    pub fn from_cn2(cn2: f64) -> Self {
+        Self {
+            cn2,
+            ..Self::default()
+        }
+    }
+
+    /// Plane-wave Fried parameter over the whole path, m (infinite for no
+    /// turbulence)
+    pub fn r0(&self, wavelength_m: f64) -> f64 {
+        self.r0_m.unwrap_or_else(|| {
+            let k = 2.0 * PI / wavelength_m;
+            (0.423 * k * k * self.cn2.max(0.0) * self.path_length_m).powf(-3.0 / 5.0)
+        })
+    }
+}
+
+/// SplitMix64 with Box-Muller normals; keeps screens reproducible per seed
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in (0, 1]
+    fn uniform(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn normal(&mut self) -> f64 {
+        (-2.0 * self.uniform().ln()).sqrt() * (2.0 * PI * self.uniform()).cos()
+    }
+}
+
+/// Phase screen (rad) on an n x n grid of spacing `pitch` m, drawn from the
+/// modified von Kármán spectrum by filtering white noise in the frequency
+/// domain, plus three levels of subharmonics for the tilt an FFT screen
+/// alone would miss
+pub fn phase_screen(
+    n: usize,
+    pitch: f64,
+    r0: f64,
+    inner_scale: f64,
+    outer_scale: f64,
+    rng: &mut Rng,
+) -> Vec<f64> {
+    let fm = 5.92 / inner_scale / (2.0 * PI);
+    let f0 = 1.0 / outer_scale;
+    let psd = |fx: f64, fy: f64| {
+        let f2 = fx * fx + fy * fy;
+        0.023 * r0.powf(-5.0 / 3.0) * (-f2 / (fm * fm)).exp() / (f2 + f0 * f0).powf(11.0 / 6.0)
+    };
+
+    let df = 1.0 / (n as f64 * pitch);
+    let mut spectrum = vec![Complex::default(); n * n];
+    for (i, c) in spectrum.iter_mut().enumerate().skip(1) {
+        let amplitude = psd(frequency(i % n, n, pitch), frequency(i / n, n, pitch)).sqrt() * df;
+        *c = Complex::new(rng.normal(), rng.normal()).scale(amplitude);
+    }
+    propagation::fft2(&mut spectrum, n, true);
+    let mut screen: Vec<f64> = spectrum.iter().map(|c| c.re).collect();
+
+    // Subharmonics: 3x3 frequency grids at 1/3, 1/9 and 1/27 of df
+    let mut low = vec![0.0; n * n];
+    for level in 1..=3 {
+        let dfs = df / 3f64.powi(level);
+        for (ix, iy) in (0..9).map(|i| (i % 3, i / 3)).filter(|&i| i != (1, 1)) {
+            let fx = (ix as f64 - 1.0) * dfs;
+            let fy = (iy as f64 - 1.0) * dfs;
+            let cn = Complex::new(rng.normal(), rng.normal()).scale(psd(fx, fy).sqrt() * dfs);
+            // exp(i 2pi (fx x + fy y)) separates into a row and a column term
+            let wave = |f: f64| -> Vec<Complex> {
+                (0..n)
+                    .map(|i| Complex::from_polar(1.0, 2.0 * PI * f * i as f64 * pitch))
+                    .collect()
+            };
+            let (ex, ey) = (wave(fx), wave(fy));
+            for (row, ey) in low.chunks_mut(n).zip(ey) {
+                let cy = cn * ey;
+                for (v, ex) in row.iter_mut().zip(&ex) {
+                    *v += (cy * *ex).re;
+                }
+            }
+        }
+    }
+    let mean = low.iter().sum::<f64>() / low.len() as f64;
+    for (s, v) in screen.iter_mut().zip(low) {
+        *s += v - mean;
+    }
+    screen
+}
+
+/// Multiply an n x n grid by the n x n window of an m-wide `screen` starting
+/// at column `offset`
+pub fn apply_screen(grid: &mut [Complex], n: usize, screen: &[f64], m: usize, offset: usize) {
+    for (row, phases) in grid.chunks_mut(n).zip(screen.chunks(m)) {
+        for (c, &phi) in row.iter_mut().zip(&phases[offset..offset + n]) {
+            *c = *c * Complex::from_polar(1.0, phi);
+        }
+    }
+}
+
+/// Fried parameter of each of `steps` equal screens, so that the sum of
+/// r0_i^-5/3 over the path equals r0^-5/3
+pub fn screen_r0(config: &TurbulenceConfig, wavelength_m: f64, steps: u32) -> f64 {
+    config.r0(wavelength_m) * (steps.max(1) as f64).powf(3.0 / 5.0)
+}
+
+/// Propagate a width x height field (1 mm grid) along the path, through
+/// `screens` equal-strength phase screens
+pub fn propagate_turbulent(
+    field: &[Complex],
+    width: u32,
+    height: u32,
+    wavelength_m: f64,
+    config: &TurbulenceConfig,
+) -> Vec<Complex> {
+    let n = propagation::grid_size(width, height);
+    let mut grid = propagation::embed(field, width, height, n);
+    let screens = config.screens.max(1);
+    let step = config.path_length_m / screens as f64;
+    let r0 = screen_r0(config, wavelength_m, screens);
+    let mut rng = Rng::new(config.seed);
+
+    for _ in 0..screens {
+        if r0.is_finite() {
+            let screen = phase_screen(
+                n,
+                PIXEL_PITCH_M,
+                r0,
+                config.inner_scale_m,
+                config.outer_scale_m,
+                &mut rng,
+            );
+            apply_screen(&mut grid, n, &screen, n, 0);
+        }
+        propagation::propagate(&mut grid, n, PIXEL_PITCH_M, wavelength_m, step);
+    }
+    propagation::crop(&grid, width, height, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beam_patterns::field;
+
+    #[test]
+    fn test_fried_parameter() {
+        let config = TurbulenceConfig {
+            cn2: 1e-14,
+            path_length_m: 1000.0,
+            ..TurbulenceConfig::default()
+        };
+        let k: f64 = 2.0 * PI / 1.55e-6;
+        let expected = (0.423 * k * k * 1e-14 * 1000.0).powf(-0.6);
+        assert!((config.r0(1.55e-6) - expected).abs() < 1e-12);
+        assert!(TurbulenceConfig::from_cn2(0.0).r0(1.55e-6).is_infinite());
+    }
+
+    #[test]
+    fn test_screen_structure_function() {
+        // Kolmogorov: D(r) = 6.88 (r / r0)^(5/3) well inside the scales
+        let (n, pitch, r0) = (128, 0.002, 0.05);
+        let mut rng = Rng::new(7);
+        let sep = 4;
+        let (mut sum, mut count) = (0.0, 0.0);
+        for _ in 0..20 {
+            let screen = phase_screen(n, pitch, r0, 1e-6, 1e6, &mut rng);
+            for y in 0..n {
+                for x in 0..n - sep {
+                    let d = screen[y * n + x + sep] - screen[y * n + x];
+                    sum += d * d;
+                    count += 1.0;
+                }
+            }
+        }
+        let expected = 6.88 * (sep as f64 * pitch / r0).powf(5.0 / 3.0);
+        let measured = sum / count;
+        assert!((measured / expected - 1.0).abs() < 0.2, "{} vs {}", measured, expected);
+    }
+
+    #[test]
+    fn test_turbulence_adds_scintillation() {
+        let (width, height, w0) = (48, 48, 0.008);
+        let field = field::sample(width, height, |x, y| {
+            Complex::new((-(x * x + y * y) / (w0 * w0)).exp(), 0.0)
+        });
+        let scintillation = |cn2: f64, seed: u64| {
+            let config = TurbulenceConfig {
+                cn2,
+                seed,
+                ..TurbulenceConfig::default()
+            };
+            let out = propagate_turbulent(&field, width, height, 1.55e-6, &config);
+            // Normalized variance inside the beam core
+            let core: Vec<f64> = out
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| {
+                    let (x, y) = field::grid_point((i % 48) as u32, (i / 48) as u32, 48, 48);
+                    x * x + y * y < w0 * w0
+                })
+                .map(|(_, c)| c.norm_sqr())
+                .collect();
+            let mean = core.iter().sum::<f64>() / core.len() as f64;
+            let var = core.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / core.len() as f64;
+            (var / (mean * mean), out)
+        };
+        let (calm, _) = scintillation(0.0, 1);
+        let (strong, a) = scintillation(1e-12, 3);
+        let (_, b) = scintillation(1e-12, 3);
+        assert!(strong > calm * 2.0, "{} vs {}", strong, calm);
+        assert_eq!(a, b);
+    }
+}
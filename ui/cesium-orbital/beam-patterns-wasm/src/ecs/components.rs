@@ -1,5 +1,5 @@
 // ECS Component definitions for ground stations and beam patterns
-// Module: ecs/components.rs | Lines: ~185 | Tier: Simple (<200)
+// Module: ecs/components.rs | Lines: ~190 | Tier: Simple (<200)
 
 use serde::{Deserialize, Serialize};
 
@@ -95,8 +95,7 @@ pub struct LinkBudget {
 
 impl LinkBudget {
     pub fn calculate(elevation_deg: f64, conditions: &AtmosphericConditions) -> Self {
-        let air_mass = 1.0 / (elevation_deg.to_radians().sin());
-        let transmission = (-0.15 * air_mass).exp();
+        let transmission = atmospheric_transmission(elevation_deg);
         let atm_loss = -10.0 * transmission.log10();
 
         let turbulence_factor = if elevation_deg < 30.0 {
@@ -116,6 +115,12 @@ impl LinkBudget {
     }
 }
 
+/// Clear-sky transmission along the slant path (plane-parallel air mass)
+pub fn atmospheric_transmission(elevation_deg: f64) -> f64 {
+    let air_mass = 1.0 / elevation_deg.to_radians().sin();
+    (-0.15 * air_mass).exp()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BeamPattern {
     pub width: u32,
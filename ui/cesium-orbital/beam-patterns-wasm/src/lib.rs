@@ -1,5 +1,5 @@
 // Main WASM library entry point
-// Module: lib.rs | Lines: ~80 | Tier: Simple (<200)
+// Module: lib.rs | Lines: ~295 | Tier: Module (<350)
 
 use wasm_bindgen::prelude::*;
 
@@ -11,7 +11,11 @@ mod beam_patterns;
 
 pub use ecs::world::ECSWorld;
 use ecs::components::{BeamParameters, AtmosphericConditions};
-use beam_patterns::{gaussian, bessel};
+use beam_patterns::{gaussian, bessel, laguerre, hermite, field, turbulence, animation};
+use beam_patterns::field::{Complex, Render};
+use beam_patterns::turbulence::TurbulenceConfig;
+use beam_patterns::animation::{Animation, AnimationConfig};
+use beam_patterns::receiver::{Mode, ReceiverConfig};
 
 #[wasm_bindgen]
 pub fn init_panic_hook() {
@@ -48,18 +52,234 @@ pub fn generate_beam_pattern(
     match beam_type.as_str() {
         "gaussian" => Ok(gaussian::generate_gaussian_beam(&params, &conditions, width, height)),
         "bessel" => Ok(bessel::generate_bessel_beam(&params, width, height)),
+        "lg" | "hg" => {
+            let waist = mode_field(&beam_type, &params, width, height)?;
+            let config = TurbulenceConfig::from_cn2(cn2_turbulence);
+            let received = turbulence::propagate_turbulent(&waist, width, height, wavelength_nm * 1e-9, &config);
+            Ok(field::to_rgba(&received, Render::Intensity))
+        }
         _ => Err(JsValue::from_str(&format!("Unknown beam type: {}", beam_type))),
     }
 }
 
+/// Waist field for the beam types that can be propagated
+fn mode_field(beam_type: &str, params: &BeamParameters, width: u32, height: u32) -> Result<Vec<Complex>, JsValue> {
+    match beam_type {
+        "gaussian" => Ok(gaussian::compute_gaussian_field(params, width, height)),
+        // Lowest-order OAM donut and first-order HG lobe pair
+        "lg" => Ok(laguerre::compute_lg_field(params, 0, 1, 0.0, width, height)),
+        "hg" => Ok(hermite::compute_hg_field(params, 1, 0, 0.0, width, height)),
+        _ => Err(JsValue::from_str(&format!("Beam type cannot be propagated: {}", beam_type))),
+    }
+}
+
+/// Beam after a turbulent path. `turbulence` is a partial `TurbulenceConfig`
+/// object ({ cn2, path_length_m, r0_m, inner_scale_m, outer_scale_m,
+/// screens, seed, wind_speed_m_s }); missing fields take their defaults.
+#[wasm_bindgen]
+pub fn generate_turbulent_pattern(
+    beam_type: String,
+    wavelength_nm: f64,
+    waist_radius_mm: f64,
+    power_watts: f64,
+    turbulence: JsValue,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, JsValue> {
+    let params = BeamParameters {
+        wavelength_nm,
+        waist_radius_mm,
+        power_watts,
+        m2_factor: 1.0,
+    };
+    let config = turbulence_config(turbulence)?;
+    let waist = mode_field(&beam_type, &params, width, height)?;
+    let received = turbulence::propagate_turbulent(&waist, width, height, wavelength_nm * 1e-9, &config);
+    Ok(field::to_rgba(&received, Render::Intensity))
+}
+
+/// One phase screen in radians on the 1 mm grid, row-major, drawn for the
+/// whole path's r0
+#[wasm_bindgen]
+pub fn generate_phase_screen(
+    wavelength_nm: f64,
+    turbulence: JsValue,
+    width: u32,
+    height: u32,
+) -> Result<Vec<f32>, JsValue> {
+    let config = turbulence_config(turbulence)?;
+    let r0 = config.r0(wavelength_nm * 1e-9);
+    if !r0.is_finite() {
+        return Ok(vec![0.0; (width * height) as usize]);
+    }
+    let n = beam_patterns::propagation::grid_size(width, height);
+    let mut rng = turbulence::Rng::new(config.seed);
+    let screen = turbulence::phase_screen(n, field::PIXEL_PITCH_M, r0, config.inner_scale_m, config.outer_scale_m, &mut rng);
+    Ok((0..height as usize)
+        .flat_map(|y| screen[y * n..y * n + width as usize].iter().map(|&v| v as f32))
+        .collect())
+}
+
+/// Frame sequence for animating a beam in the UI with a single call
+#[wasm_bindgen]
+pub struct BeamAnimation(Animation);
+
+#[wasm_bindgen]
+impl BeamAnimation {
+    #[wasm_bindgen]
+    pub fn frame_count(&self) -> usize {
+        self.0.frames.len()
+    }
+
+    /// Bytes per RGBA frame; frame i starts at i * frame_size()
+    #[wasm_bindgen]
+    pub fn frame_size(&self) -> usize {
+        self.0.frame_len()
+    }
+
+    /// All frames back to back
+    #[wasm_bindgen]
+    pub fn data(&self) -> Vec<u8> {
+        self.0.data.clone()
+    }
+
+    /// JSON array of per-frame distance, time, peak, power, radius and
+    /// centroid
+    #[wasm_bindgen]
+    pub fn metadata(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.0.frames).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+/// Intensity frames of a beam along the turbulent path (`mode: "path"`) or
+/// at the receiver as the wind carries the screens across it
+/// (`mode: "time"`). `animation` is a partial `AnimationConfig` object
+/// ({ mode, frames, frame_interval_s, shared_scale }).
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_beam_animation(
+    beam_type: String,
+    wavelength_nm: f64,
+    waist_radius_mm: f64,
+    power_watts: f64,
+    turbulence: JsValue,
+    animation: JsValue,
+    width: u32,
+    height: u32,
+) -> Result<BeamAnimation, JsValue> {
+    let params = BeamParameters {
+        wavelength_nm,
+        waist_radius_mm,
+        power_watts,
+        m2_factor: 1.0,
+    };
+    let turbulence = turbulence_config(turbulence)?;
+    let config: AnimationConfig = if animation.is_undefined() || animation.is_null() {
+        AnimationConfig::default()
+    } else {
+        serde_wasm_bindgen::from_value(animation).map_err(|e| JsValue::from_str(&e.to_string()))?
+    };
+    let waist = mode_field(&beam_type, &params, width, height)?;
+    animation::animate(&waist, width, height, wavelength_nm * 1e-9, &turbulence, &config)
+        .map(BeamAnimation)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+fn turbulence_config(value: JsValue) -> Result<TurbulenceConfig, JsValue> {
+    if value.is_undefined() || value.is_null() {
+        return Ok(TurbulenceConfig::default());
+    }
+    serde_wasm_bindgen::from_value(value).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Higher-order mode pattern. `family` is "lg" (order_a = radial index p,
+/// order_b = azimuthal index l, signed) or "hg" (order_a = m, order_b = n).
+/// `render` is "intensity" or "phase"; `distance_m` is from the waist.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_mode_pattern(
+    family: String,
+    order_a: u32,
+    order_b: i32,
+    wavelength_nm: f64,
+    waist_radius_mm: f64,
+    power_watts: f64,
+    distance_m: f64,
+    width: u32,
+    height: u32,
+    render: String,
+) -> Result<Vec<u8>, JsValue> {
+    let params = BeamParameters {
+        wavelength_nm,
+        waist_radius_mm,
+        power_watts,
+        m2_factor: 1.0,
+    };
+    let render = Render::parse(&render)
+        .ok_or_else(|| JsValue::from_str(&format!("Unknown render mode: {}", render)))?;
+
+    match family.as_str() {
+        "lg" => {
+            if order_a > laguerre::MAX_ORDER || order_b.unsigned_abs() > laguerre::MAX_ORDER {
+                return Err(JsValue::from_str(&format!("LG indices must be within {}", laguerre::MAX_ORDER)));
+            }
+            Ok(laguerre::generate_lg_beam(&params, order_a, order_b, distance_m, width, height, render))
+        }
+        "hg" => {
+            if order_b < 0 || order_a > hermite::MAX_ORDER || order_b as u32 > hermite::MAX_ORDER {
+                return Err(JsValue::from_str(&format!("HG indices must be within 0..={}", hermite::MAX_ORDER)));
+            }
+            Ok(hermite::generate_hg_beam(&params, order_a, order_b as u32, distance_m, width, height, render))
+        }
+        _ => Err(JsValue::from_str(&format!("Unknown mode family: {}", family))),
+    }
+}
+
+/// Power a receiver aperture collects from the beam and the resulting link
+/// margin. `receiver` is a partial `ReceiverConfig` object ({ range_m,
+/// offset_x_m, offset_y_m, aperture_diameter_m, elevation_deg, cn2,
+/// turbulent_path_m, cloud_cover_percent, sensitivity_dbm }); returns
+/// { captured_w, beam_radius_m, geometric_loss_db, pointing_loss_db,
+/// atmospheric_loss_db, cloud_loss_db, received_dbm, link_margin_db }.
+#[wasm_bindgen]
+pub fn calculate_received_power(
+    beam_type: String,
+    wavelength_nm: f64,
+    waist_radius_mm: f64,
+    power_watts: f64,
+    receiver: JsValue,
+) -> Result<JsValue, JsValue> {
+    let params = BeamParameters {
+        wavelength_nm,
+        waist_radius_mm,
+        power_watts,
+        m2_factor: 1.0,
+    };
+    let mode = match beam_type.as_str() {
+        "gaussian" => Mode::Gaussian,
+        // Same modes as mode_field
+        "lg" => Mode::Lg(0, 1),
+        "hg" => Mode::Hg(1, 0),
+        _ => return Err(JsValue::from_str(&format!("Unknown beam type: {}", beam_type))),
+    };
+    let config: ReceiverConfig = if receiver.is_undefined() || receiver.is_null() {
+        ReceiverConfig::default()
+    } else {
+        serde_wasm_bindgen::from_value(receiver).map_err(|e| JsValue::from_str(&e.to_string()))?
+    };
+    let received = beam_patterns::receiver::received_power(&params, mode, &config);
+    serde_wasm_bindgen::to_value(&received).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Elevation-only margin estimate, with no beam or aperture; prefer
+/// `calculate_received_power` when those are known
 #[wasm_bindgen]
 pub fn calculate_link_margin(
     elevation_deg: f64,
     cn2_turbulence: f64,
     cloud_cover: f64,
 ) -> f64 {
-    let air_mass = 1.0 / elevation_deg.to_radians().sin();
-    let transmission = (-0.15 * air_mass).exp();
+    let transmission = ecs::components::atmospheric_transmission(elevation_deg);
     let atm_loss = -10.0 * transmission.log10();
 
     let turbulence_factor = if elevation_deg < 30.0 {
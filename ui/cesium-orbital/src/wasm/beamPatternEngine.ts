@@ -1,7 +1,9 @@
 // TypeScript wrapper for WASM beam pattern engine
-// Module: wasm/beamPatternEngine.ts | Lines: ~170 | Tier: Simple (<200)
+// Module: wasm/beamPatternEngine.ts | Lines: ~335 | Tier: Module (<350)
 
-type BeamType = 'gaussian' | 'bessel' | 'airy' | 'lg';
+type BeamType = 'gaussian' | 'bessel' | 'airy' | 'lg' | 'hg';
+type ModeFamily = 'lg' | 'hg';
+type ModeRender = 'intensity' | 'phase';
 type PresetType = 'basic' | 'operational' | 'precision';
 
 interface StationConfig {
@@ -12,6 +14,70 @@ interface StationConfig {
   preset?: PresetType;
 }
 
+/** Partial turbulence settings; omitted fields use the WASM defaults */
+interface TurbulenceConfig {
+  cn2?: number;
+  path_length_m?: number;
+  r0_m?: number;
+  inner_scale_m?: number;
+  outer_scale_m?: number;
+  screens?: number;
+  seed?: number;
+  wind_speed_m_s?: number;
+}
+
+/** Partial animation settings; omitted fields use the WASM defaults */
+interface AnimationConfig {
+  mode?: 'path' | 'time';
+  frames?: number;
+  frame_interval_s?: number;
+  shared_scale?: boolean;
+}
+
+interface FrameInfo {
+  index: number;
+  distance_m: number;
+  time_s: number;
+  peak_w_m2: number;
+  power_w: number;
+  radius_m: number;
+  centroid_x_m: number;
+  centroid_y_m: number;
+}
+
+interface BeamAnimation {
+  width: number;
+  height: number;
+  frameSize: number;
+  /** RGBA frames back to back */
+  data: Uint8Array;
+  frames: FrameInfo[];
+}
+
+/** Partial receiver settings; omitted fields use the WASM defaults */
+interface ReceiverConfig {
+  range_m?: number;
+  offset_x_m?: number;
+  offset_y_m?: number;
+  aperture_diameter_m?: number;
+  elevation_deg?: number;
+  cn2?: number;
+  turbulent_path_m?: number;
+  cloud_cover_percent?: number;
+  sensitivity_dbm?: number;
+}
+
+interface ReceivedPower {
+  captured_w: number;
+  beam_radius_m: number;
+  geometric_loss_db: number;
+  pointing_loss_db: number;
+  atmospheric_loss_db: number;
+  cloud_loss_db: number;
+  received_dbm: number;
+  link_margin_db: number;
+}
+
 interface LinkBudget {
   elevation_deg: number;
   atmospheric_loss_db: number;
@@ -109,6 +175,130 @@ export class BeamPatternEngine {
     return new Uint8Array(pattern);
   }
 
+  /**
+   * Laguerre-Gaussian (orderA = p, orderB = l) or Hermite-Gaussian
+   * (orderA = m, orderB = n) mode at distanceM from the waist
+   */
+  async generateModePattern(
+    family: ModeFamily,
+    orderA: number,
+    orderB: number,
+    wavelengthNm: number,
+    waistRadiusMm: number,
+    powerWatts: number,
+    distanceM: number,
+    width: number,
+    height: number,
+    render: ModeRender = 'intensity'
+  ): Promise<Uint8Array> {
+    this.ensureInitialized();
+
+    const pattern = await this.wasmModule.generate_mode_pattern(
+      family,
+      orderA,
+      orderB,
+      wavelengthNm,
+      waistRadiusMm,
+      powerWatts,
+      distanceM,
+      width,
+      height,
+      render
+    );
+
+    return new Uint8Array(pattern);
+  }
+
+  async generateTurbulentPattern(
+    beamType: 'gaussian' | 'lg' | 'hg',
+    wavelengthNm: number,
+    waistRadiusMm: number,
+    powerWatts: number,
+    turbulence: TurbulenceConfig,
+    width: number,
+    height: number
+  ): Promise<Uint8Array> {
+    this.ensureInitialized();
+
+    const pattern = await this.wasmModule.generate_turbulent_pattern(
+      beamType,
+      wavelengthNm,
+      waistRadiusMm,
+      powerWatts,
+      turbulence,
+      width,
+      height
+    );
+
+    return new Uint8Array(pattern);
+  }
+
+  /** Phase screen in radians, row-major */
+  async generatePhaseScreen(
+    wavelengthNm: number,
+    turbulence: TurbulenceConfig,
+    width: number,
+    height: number
+  ): Promise<Float32Array> {
+    this.ensureInitialized();
+    const screen = await this.wasmModule.generate_phase_screen(wavelengthNm, turbulence, width, height);
+    return new Float32Array(screen);
+  }
+
+  /** Whole frame sequence in one call; slice data by frameSize to animate */
+  async generateBeamAnimation(
+    beamType: 'gaussian' | 'lg' | 'hg',
+    wavelengthNm: number,
+    waistRadiusMm: number,
+    powerWatts: number,
+    turbulence: TurbulenceConfig,
+    animation: AnimationConfig,
+    width: number,
+    height: number
+  ): Promise<BeamAnimation> {
+    this.ensureInitialized();
+
+    const result = await this.wasmModule.generate_beam_animation(
+      beamType,
+      wavelengthNm,
+      waistRadiusMm,
+      powerWatts,
+      turbulence,
+      animation,
+      width,
+      height
+    );
+    try {
+      return {
+        width,
+        height,
+        frameSize: result.frame_size(),
+        data: new Uint8Array(result.data()),
+        frames: JSON.parse(result.metadata()),
+      };
+    } finally {
+      result.free();
+    }
+  }
+
+  /** Beam power captured by a receiver aperture, and the link margin it gives */
+  calculateReceivedPower(
+    beamType: 'gaussian' | 'lg' | 'hg',
+    wavelengthNm: number,
+    waistRadiusMm: number,
+    powerWatts: number,
+    receiver: ReceiverConfig
+  ): ReceivedPower {
+    this.ensureInitialized();
+    return this.wasmModule.calculate_received_power(
+      beamType,
+      wavelengthNm,
+      waistRadiusMm,
+      powerWatts,
+      receiver
+    );
+  }
+
   calculateLinkMargin(
     elevationDeg: number,
     cn2Turbulence: number,